@@ -9,3 +9,4 @@
 #![doc = include_str!("rust-client/creating_notes_in_masm_tutorial.md")]
 #![doc = include_str!("rust-client/delegated_proving_tutorial.md")]
 #![doc = include_str!("rust-client/network_transactions_tutorial.md")]
+#![doc = include_str!("rust-client/debugging_masm_tutorial.md")]
@@ -0,0 +1,189 @@
+//! Measures how FPI cost scales with the number of foreign accounts.
+//!
+//! Mirrors the oracle read from `oracle_data_query`: a transaction reads the price of every
+//! publisher through foreign procedure invocation and aggregates the results. The benchmark
+//! runs against the mock chain with 1, 4, 8 and 16 publishers and reports execution and
+//! proving times.
+//!
+//! Run with `cargo bench --bench fpi_scaling`.
+
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use miden_client::transaction::LocalTransactionProver;
+use miden_lib::{
+    account::{auth::NoAuth, wallets::BasicWallet},
+    transaction::TransactionKernel,
+    utils::ScriptBuilder,
+};
+use miden_objects::{
+    account::{
+        Account, AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot,
+    },
+    Felt, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::fpi::foreign_accounts_warning;
+
+const PUBLISHER_COUNTS: [usize; 4] = [1, 4, 8, 16];
+
+/// Reads a MASM file relative to the `masm` directory at the root of the repository.
+fn masm(relative_path: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../masm")
+        .join(relative_path);
+    fs::read_to_string(path).unwrap()
+}
+
+/// Formats a word so that it can be pushed onto the stack with `push.{word}`.
+fn word_to_masm(word: Word) -> String {
+    word.iter()
+        .map(|felt| felt.as_int().to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Returns the MAST root of the `get_price` procedure of the publisher component.
+fn get_price_root(component: &AccountComponent) -> Word {
+    let export = component
+        .library()
+        .exports()
+        .find(|export| export.name.name.as_str() == "get_price")
+        .unwrap();
+    let node_id = component.library().get_export_node_id(&export.name);
+
+    component
+        .library()
+        .mast_forest()
+        .get_node_by_id(node_id)
+        .unwrap()
+        .digest()
+}
+
+/// Builds a transaction script summing the prices of all `publishers` via FPI and asserting
+/// the sum equals `expected_sum`.
+fn aggregate_script(publishers: &[Account], get_price_root: Word, expected_sum: u64) -> String {
+    let mut script = String::from("use.miden::tx\nuse.std::sys\n\nbegin\n    push.0\n");
+    for publisher in publishers {
+        script.push_str(&format!(
+            "
+    # => [sum]
+    padw padw padw padw
+    push.{root}
+    push.{suffix} push.{prefix}
+    exec.tx::execute_foreign_procedure
+    # => [price, pad(15), sum]
+
+    movdn.15 dropw dropw dropw drop drop drop
+    add
+",
+            root = word_to_masm(get_price_root),
+            suffix = publisher.id().suffix(),
+            prefix = publisher.id().prefix().as_felt(),
+        ));
+    }
+    script.push_str(&format!(
+        "
+    push.{expected_sum} assert_eq
+    exec.sys::truncate_stack
+end
+"
+    ));
+    script
+}
+
+/// Executes and proves the aggregation over `num_publishers` publishers, returning the
+/// execution and proving times.
+async fn measure(num_publishers: usize) -> Result<(Duration, Duration)> {
+    let publisher_code = masm("accounts/price_publisher.masm");
+    let publisher_component =
+        AccountComponent::compile(&publisher_code, TransactionKernel::assembler(), vec![])?
+            .with_supports_all_types();
+    let get_price_root = get_price_root(&publisher_component);
+
+    let mut builder = MockChain::builder();
+    let mut publishers = Vec::with_capacity(num_publishers);
+    for i in 0..num_publishers {
+        let price: Word = [ZERO, ZERO, ZERO, Felt::new(60_000 + i as u64)].into();
+        let component = AccountComponent::compile(
+            &publisher_code,
+            TransactionKernel::assembler(),
+            vec![StorageSlot::Value(price)],
+        )?
+        .with_supports_all_types();
+
+        let publisher = AccountBuilder::new(rand::random())
+            .account_type(AccountType::RegularAccountImmutableCode)
+            .storage_mode(AccountStorageMode::Public)
+            .with_auth_component(NoAuth)
+            .with_component(component)
+            .build_existing()?;
+        builder.add_account(publisher.clone())?;
+        publishers.push(publisher);
+    }
+
+    let reader = AccountBuilder::new(rand::random())
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(BasicWallet)
+        .build_existing()?;
+    builder.add_account(reader.clone())?;
+    let chain = builder.build()?;
+
+    let expected_sum = (0..num_publishers as u64).map(|i| 60_000 + i).sum();
+    let tx_script = ScriptBuilder::new(true).compile_tx_script(aggregate_script(
+        &publishers,
+        get_price_root,
+        expected_sum,
+    ))?;
+
+    let foreign_accounts = publishers
+        .iter()
+        .map(|publisher| chain.get_foreign_account_inputs(publisher.id()))
+        .collect::<Result<Vec<_>>>()?;
+
+    let tx_context = chain
+        .build_tx_context(reader.id(), &[], &[])?
+        .foreign_accounts(foreign_accounts)
+        .tx_script(tx_script)
+        .build()?;
+
+    let start = Instant::now();
+    let executed = tx_context.execute().await?;
+    let execution_time = start.elapsed();
+
+    let start = Instant::now();
+    LocalTransactionProver::default().prove(executed.into())?;
+    let proving_time = start.elapsed();
+
+    Ok((execution_time, proving_time))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    println!(
+        "{:>16} {:>16} {:>16}",
+        "foreign accounts", "execution (ms)", "proving (ms)"
+    );
+
+    for num_publishers in PUBLISHER_COUNTS {
+        if let Some(warning) = foreign_accounts_warning(num_publishers) {
+            eprintln!("warning: {warning}");
+        }
+
+        let (execution_time, proving_time) = measure(num_publishers).await?;
+        println!(
+            "{:>16} {:>16} {:>16}",
+            num_publishers,
+            execution_time.as_millis(),
+            proving_time.as_millis()
+        );
+    }
+
+    Ok(())
+}
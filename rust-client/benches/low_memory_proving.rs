@@ -0,0 +1,231 @@
+//! Measures the memory proving takes, and proves a large job within a memory limit.
+//!
+//! The prover has no memory setting: its memory grows with the execution trace. The benchmark
+//! first proves a few transactions of increasing length against the mock chain, reporting the
+//! trace length, proving time and peak resident memory of each, and derives from them what a
+//! trace row costs on this machine. It then proves a job too long for the limit set by
+//! `MIDEN_PROVER_MEMORY_MIB` (1024 MiB by default): split into transactions short enough to
+//! fit, or delegated whole to the remote prover given with `--prover <URL>`.
+//!
+//! Peak memory is read from `/proc`, so the benchmark only runs on Linux.
+//!
+//! Run with `MIDEN_PROVER_MEMORY_MIB=512 cargo bench --bench low_memory_proving`.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use miden_client::transaction::LocalTransactionProver;
+use miden_lib::{
+    account::{auth::NoAuth, wallets::BasicWallet},
+    utils::ScriptBuilder,
+};
+use miden_objects::account::{Account, AccountBuilder, AccountStorageMode, AccountType};
+use miden_testing::MockChain;
+use rust_client::{
+    cli::ProverOption,
+    helpers::total_cycles,
+    proving::{self, MemoryBudget},
+};
+
+/// Loop iterations of the transactions proven to calibrate the budget.
+const CALIBRATION_ITERATIONS: [u64; 4] = [0, 1 << 12, 1 << 14, 1 << 16];
+/// Loop iterations of the job proven within the limit.
+const JOB_ITERATIONS: u64 = 1 << 20;
+
+const MIB: u64 = 1024 * 1024;
+
+/// Builds a transaction script counting down from `iterations`, a stand-in for any
+/// computation whose length grows with its input.
+fn countdown_script(iterations: u64) -> String {
+    format!(
+        "
+begin
+    push.{iterations}
+    dup neq.0
+    while.true
+        sub.1
+        dup neq.0
+    end
+    drop
+end
+"
+    )
+}
+
+/// Proof of a countdown, with what it cost.
+struct Measurement {
+    cycles: u64,
+    proving_time: Duration,
+    peak_rss: u64,
+}
+
+/// Executes and proves a countdown of `iterations` against `account` on this machine.
+async fn prove_locally(
+    chain: &MockChain,
+    account: &Account,
+    iterations: u64,
+) -> Result<Measurement> {
+    let tx_script = ScriptBuilder::new(true).compile_tx_script(countdown_script(iterations))?;
+    let tx_context = chain
+        .build_tx_context(account.id(), &[], &[])?
+        .tx_script(tx_script)
+        .build()?;
+    let executed = tx_context.execute().await?;
+    let cycles = total_cycles(executed.measurements()) as u64;
+
+    // Only the proof counts towards the peak, not the chain or the execution
+    proving::reset_peak_rss()?;
+    let start = Instant::now();
+    LocalTransactionProver::default().prove(executed.into())?;
+    let proving_time = start.elapsed();
+
+    Ok(Measurement {
+        cycles,
+        proving_time,
+        peak_rss: proving::peak_rss()?,
+    })
+}
+
+/// Executes a countdown of `iterations` against `account` and has `--prover` prove it.
+async fn prove_delegated(
+    chain: &MockChain,
+    account: &Account,
+    iterations: u64,
+) -> Result<Duration> {
+    let prover = ProverOption::global()
+        .transaction_prover()
+        .ok_or_else(|| anyhow!("delegating needs a prover, run without `--prover none`"))?;
+    let tx_script = ScriptBuilder::new(true).compile_tx_script(countdown_script(iterations))?;
+    let tx_context = chain
+        .build_tx_context(account.id(), &[], &[])?
+        .tx_script(tx_script)
+        .build()?;
+    let executed = tx_context.execute().await?;
+
+    let start = Instant::now();
+    prover.prove(executed.into()).await?;
+    Ok(start.elapsed())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let limit = proving::memory_limit().map_err(|err| anyhow!(err))?;
+
+    let account = AccountBuilder::new(rand::random())
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(BasicWallet)
+        .build_existing()?;
+    let mut builder = MockChain::builder();
+    builder.add_account(account.clone())?;
+    let chain = builder.build()?;
+    let baseline = proving::current_rss()?;
+
+    // -------------------------------------------------------------------------
+    // Calibrate: what a trace row costs on this machine
+    // -------------------------------------------------------------------------
+    println!(
+        "{:>16} {:>16} {:>16} {:>16} {:>16}",
+        "iterations", "cycles", "trace rows", "proving (ms)", "peak RSS (MiB)"
+    );
+
+    let mut measurements = Vec::with_capacity(CALIBRATION_ITERATIONS.len());
+    for iterations in CALIBRATION_ITERATIONS {
+        let measurement = prove_locally(&chain, &account, iterations).await?;
+        println!(
+            "{:>16} {:>16} {:>16} {:>16} {:>16}",
+            iterations,
+            measurement.cycles,
+            proving::trace_rows(measurement.cycles),
+            measurement.proving_time.as_millis(),
+            measurement.peak_rss / MIB
+        );
+        measurements.push(measurement);
+    }
+
+    let samples: Vec<_> = measurements
+        .iter()
+        .map(|measurement| {
+            (
+                proving::trace_rows(measurement.cycles),
+                measurement.peak_rss,
+            )
+        })
+        .collect();
+    let budget = MemoryBudget::calibrate(limit, baseline, &samples);
+
+    // Cycles of a countdown grow linearly with its iterations, on top of the transaction itself
+    let (first, last) = (&measurements[0], &measurements[measurements.len() - 1]);
+    let fixed_cycles = first.cycles;
+    let cycles_per_iteration = (last.cycles - first.cycles)
+        .div_ceil(CALIBRATION_ITERATIONS[CALIBRATION_ITERATIONS.len() - 1]);
+    let job_cycles = fixed_cycles + JOB_ITERATIONS * cycles_per_iteration;
+
+    println!(
+        "\nBaseline {} MiB, {} bytes per trace row, limit {} MiB",
+        baseline / MIB,
+        budget.bytes_per_row,
+        limit / MIB
+    );
+
+    // -------------------------------------------------------------------------
+    // Prove the job within the limit
+    // -------------------------------------------------------------------------
+    println!(
+        "Job of {JOB_ITERATIONS} iterations: about {job_cycles} cycles, estimated peak {} MiB",
+        budget.estimate(job_cycles) / MIB
+    );
+
+    if budget.fits(job_cycles) {
+        let measurement = prove_locally(&chain, &account, JOB_ITERATIONS).await?;
+        println!(
+            "Fits: proven in {} ms with a peak of {} MiB",
+            measurement.proving_time.as_millis(),
+            measurement.peak_rss / MIB
+        );
+        return Ok(());
+    }
+
+    // A remote prover takes the job whole, this machine only executes it
+    if let ProverOption::Remote(url) = ProverOption::global() {
+        let proving_time = prove_delegated(&chain, &account, JOB_ITERATIONS).await?;
+        println!(
+            "Too large: delegated to {url}, proven in {} ms",
+            proving_time.as_millis()
+        );
+        return Ok(());
+    }
+
+    let chunk_iterations = budget
+        .max_cycles()
+        .map(|max_cycles| max_cycles.saturating_sub(fixed_cycles) / cycles_per_iteration)
+        .filter(|chunk_iterations| *chunk_iterations > 0)
+        .ok_or_else(|| {
+            anyhow!("no transaction fits in the limit, delegate with `--prover <URL>`")
+        })?;
+    let num_chunks = JOB_ITERATIONS.div_ceil(chunk_iterations);
+    println!("Too large: splitting into {num_chunks} transactions of at most {chunk_iterations} iterations");
+
+    let mut total_time = Duration::ZERO;
+    let mut highest_peak = 0;
+    let mut remaining = JOB_ITERATIONS;
+    while remaining > 0 {
+        let iterations = remaining.min(chunk_iterations);
+        let measurement = prove_locally(&chain, &account, iterations).await?;
+        total_time += measurement.proving_time;
+        highest_peak = highest_peak.max(measurement.peak_rss);
+        remaining -= iterations;
+    }
+
+    println!(
+        "Proven in {} ms with a peak of {} MiB",
+        total_time.as_millis(),
+        highest_peak / MIB
+    );
+    if highest_peak > limit {
+        eprintln!("warning: the peak exceeded the limit, calibrate with longer transactions");
+    }
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use miden_lib::account::auth::AuthRpoFalcon512;
-use rand::{rngs::StdRng, RngCore};
+use rand::RngCore;
 use std::sync::Arc;
 use tokio::time::Duration;
 
@@ -8,12 +8,10 @@ use miden_client::{
         component::{BasicFungibleFaucet, BasicWallet},
         AccountId,
     },
-    address::NetworkId,
     auth::AuthSecretKey,
     builder::ClientBuilder,
-    keystore::FilesystemKeyStore,
     note::{create_p2id_note, NoteType},
-    rpc::{Endpoint, GrpcClient},
+    rpc::GrpcClient,
     transaction::{OutputNote, TransactionRequestBuilder},
     ClientError,
 };
@@ -23,19 +21,21 @@ use miden_objects::{
     asset::{FungibleAsset, TokenSymbol},
     Felt,
 };
+use tutorial_utils::{cli::SubmitWithProver, context::TutorialContext};
 
 #[tokio::main]
 async fn main() -> Result<(), ClientError> {
     // Initialize client
-    let endpoint = Endpoint::testnet();
+    let network = tutorial_utils::cli::network().await?;
+    let endpoint = network.endpoint().clone();
     let timeout_ms = 10_000;
     let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
 
-    // Initialize keystore
-    let keystore_path = std::path::PathBuf::from("./keystore");
-    let keystore = Arc::new(FilesystemKeyStore::<StdRng>::new(keystore_path).unwrap());
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("create_mint_consume_send")?;
+    let keystore = context.keystore()?;
 
-    let store_path = std::path::PathBuf::from("./store.sqlite3");
+    let store_path = context.store_path();
 
     let mut client = ClientBuilder::new()
         .rpc(rpc_client)
@@ -74,7 +74,7 @@ async fn main() -> Result<(), ClientError> {
     // Add the key pair to the keystore
     keystore.add_key(&key_pair).unwrap();
 
-    let alice_account_id_bech32 = alice_account.id().to_bech32(NetworkId::Testnet);
+    let alice_account_id_bech32 = alice_account.id().to_bech32(network.network_id());
     println!("Alice's account ID: {:?}", alice_account_id_bech32);
 
     //------------------------------------------------------------
@@ -109,7 +109,7 @@ async fn main() -> Result<(), ClientError> {
     // Add the key pair to the keystore
     keystore.add_key(&key_pair).unwrap();
 
-    let faucet_account_id_bech32 = faucet_account.id().to_bech32(NetworkId::Testnet);
+    let faucet_account_id_bech32 = faucet_account.id().to_bech32(network.network_id());
     println!("Faucet account ID: {:?}", faucet_account_id_bech32);
 
     // Resync to show newly deployed faucet
@@ -137,7 +137,7 @@ async fn main() -> Result<(), ClientError> {
         println!("tx request built");
 
         let tx_id = client
-            .submit_new_transaction(faucet_account.id(), transaction_request)
+            .submit_with_prover(faucet_account.id(), transaction_request)
             .await?;
         println!(
             "Minted note #{} of {} tokens for Alice. TX: {:?}",
@@ -171,7 +171,7 @@ async fn main() -> Result<(), ClientError> {
                 .unwrap();
 
             let tx_id = client
-                .submit_new_transaction(alice_account.id(), transaction_request)
+                .submit_with_prover(alice_account.id(), transaction_request)
                 .await?;
             println!(
                 "All of Alice's notes consumed successfully. TX: {:?}",
@@ -232,7 +232,7 @@ async fn main() -> Result<(), ClientError> {
         .unwrap();
 
     let tx_id = client
-        .submit_new_transaction(alice_account.id(), transaction_request)
+        .submit_with_prover(alice_account.id(), transaction_request)
         .await?;
 
     println!("Submitted a transaction with 4 P2ID notes. TX: {:?}", tx_id);
@@ -268,7 +268,7 @@ async fn main() -> Result<(), ClientError> {
         .unwrap();
 
     let tx_id = client
-        .submit_new_transaction(alice_account.id(), transaction_request)
+        .submit_with_prover(alice_account.id(), transaction_request)
         .await?;
 
     println!("Submitted final P2ID transaction. TX: {:?}", tx_id);
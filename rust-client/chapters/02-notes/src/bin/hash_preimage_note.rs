@@ -1,128 +1,40 @@
-use miden_lib::account::auth::AuthRpoFalcon512;
-use rand::{rngs::StdRng, RngCore};
 use std::{fs, path::Path, sync::Arc};
-use tokio::time::{sleep, Duration};
 
 use miden_client::{
-    account::{
-        component::{BasicFungibleFaucet, BasicWallet},
-        Account,
-    },
-    address::NetworkId,
-    auth::AuthSecretKey,
     builder::ClientBuilder,
     crypto::FeltRng,
-    keystore::FilesystemKeyStore,
     note::{
         Note, NoteAssets, NoteExecutionHint, NoteExecutionMode, NoteInputs, NoteMetadata,
         NoteRecipient, NoteTag, NoteType,
     },
-    rpc::{Endpoint, GrpcClient},
-    store::TransactionFilter,
-    transaction::{OutputNote, TransactionId, TransactionRequestBuilder, TransactionStatus},
-    Client, ClientError, Felt, ScriptBuilder,
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Felt, ScriptBuilder,
 };
 use miden_client_sqlite_store::ClientBuilderSqliteExt;
 use miden_objects::{
-    account::{AccountBuilder, AccountStorageMode, AccountType},
     asset::{FungibleAsset, TokenSymbol},
     Hasher,
 };
-
-// Helper to create a basic account
-async fn create_basic_account(
-    client: &mut Client<FilesystemKeyStore<StdRng>>,
-    keystore: &Arc<FilesystemKeyStore<StdRng>>,
-) -> Result<Account, ClientError> {
-    let mut init_seed = [0_u8; 32];
-    client.rng().fill_bytes(&mut init_seed);
-
-    let key_pair = AuthSecretKey::new_rpo_falcon512();
-
-    let account = AccountBuilder::new(init_seed)
-        .account_type(AccountType::RegularAccountUpdatableCode)
-        .storage_mode(AccountStorageMode::Public)
-        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
-        .with_component(BasicWallet)
-        .build()
-        .unwrap();
-
-    client.add_account(&account, false).await?;
-    keystore.add_key(&key_pair).unwrap();
-
-    Ok(account)
-}
-
-async fn create_basic_faucet(
-    client: &mut Client<FilesystemKeyStore<StdRng>>,
-    keystore: &Arc<FilesystemKeyStore<StdRng>>,
-) -> Result<Account, ClientError> {
-    let mut init_seed = [0u8; 32];
-    client.rng().fill_bytes(&mut init_seed);
-
-    let key_pair = AuthSecretKey::new_rpo_falcon512();
-    let symbol = TokenSymbol::new("MID").unwrap();
-    let decimals = 8;
-    let max_supply = Felt::new(1_000_000);
-
-    let account = AccountBuilder::new(init_seed)
-        .account_type(AccountType::FungibleFaucet)
-        .storage_mode(AccountStorageMode::Public)
-        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
-        .with_component(BasicFungibleFaucet::new(symbol, decimals, max_supply).unwrap())
-        .build()
-        .unwrap();
-
-    client.add_account(&account, false).await?;
-    keystore.add_key(&key_pair).unwrap();
-
-    Ok(account)
-}
-
-/// Waits for a specific transaction to be committed.
-async fn wait_for_tx(
-    client: &mut Client<FilesystemKeyStore<StdRng>>,
-    tx_id: TransactionId,
-) -> Result<(), ClientError> {
-    loop {
-        client.sync_state().await?;
-
-        // Check transaction status
-        let txs = client
-            .get_transactions(TransactionFilter::Ids(vec![tx_id]))
-            .await?;
-        let tx_committed = if !txs.is_empty() {
-            matches!(txs[0].status, TransactionStatus::Committed { .. })
-        } else {
-            false
-        };
-
-        if tx_committed {
-            println!("✅ transaction {} committed", tx_id.to_hex());
-            break;
-        }
-
-        println!(
-            "Transaction {} not yet committed. Waiting...",
-            tx_id.to_hex()
-        );
-        sleep(Duration::from_secs(2)).await;
-    }
-    Ok(())
-}
+use tutorial_utils::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{create_basic_account, create_basic_faucet, wait_for_tx},
+};
 
 #[tokio::main]
-async fn main() -> Result<(), ClientError> {
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize client
-    let endpoint = Endpoint::testnet();
+    let network = tutorial_utils::cli::network().await?;
+    let endpoint = network.endpoint().clone();
     let timeout_ms = 10_000;
     let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
 
-    // Initialize keystore
-    let keystore_path = std::path::PathBuf::from("./keystore");
-    let keystore = Arc::new(FilesystemKeyStore::<StdRng>::new(keystore_path).unwrap());
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("hash_preimage_note")?;
+    let keystore = context.keystore()?;
 
-    let store_path = std::path::PathBuf::from("./store.sqlite3");
+    let store_path = context.store_path();
 
     let mut client = ClientBuilder::new()
         .rpc(rpc_client)
@@ -142,19 +54,20 @@ async fn main() -> Result<(), ClientError> {
     let alice_account = create_basic_account(&mut client, &keystore).await?;
     println!(
         "Alice's account ID: {:?}",
-        alice_account.id().to_bech32(NetworkId::Testnet)
+        alice_account.id().to_bech32(network.network_id())
     );
     let bob_account = create_basic_account(&mut client, &keystore).await?;
     println!(
         "Bob's account ID: {:?}",
-        bob_account.id().to_bech32(NetworkId::Testnet)
+        bob_account.id().to_bech32(network.network_id())
     );
 
     println!("\nDeploying a new fungible faucet.");
-    let faucet = create_basic_faucet(&mut client, &keystore).await?;
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
     println!(
         "Faucet account ID: {:?}",
-        faucet.id().to_bech32(NetworkId::Testnet)
+        faucet.id().to_bech32(network.network_id())
     );
     client.sync_state().await?;
 
@@ -174,9 +87,7 @@ async fn main() -> Result<(), ClientError> {
         )
         .unwrap();
 
-    let tx_id = client
-        .submit_new_transaction(faucet.id(), tx_request)
-        .await?;
+    let tx_id = client.submit_with_prover(faucet.id(), tx_request).await?;
     println!("Minted tokens. TX: {:?}", tx_id);
 
     // Wait for the note to be available
@@ -194,7 +105,7 @@ async fn main() -> Result<(), ClientError> {
             .unwrap();
 
         let tx_id = client
-            .submit_new_transaction(alice_account.id(), consume_request)
+            .submit_with_prover(alice_account.id(), consume_request)
             .await?;
         println!("Consumed minted note. TX: {:?}", tx_id);
     }
@@ -233,7 +144,7 @@ async fn main() -> Result<(), ClientError> {
         .unwrap();
 
     let tx_id = client
-        .submit_new_transaction(alice_account.id(), note_request)
+        .submit_with_prover(alice_account.id(), note_request)
         .await?;
     println!(
         "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
@@ -254,7 +165,7 @@ async fn main() -> Result<(), ClientError> {
         .unwrap();
 
     let tx_id = client
-        .submit_new_transaction(bob_account.id(), consume_custom_request)
+        .submit_with_prover(bob_account.id(), consume_custom_request)
         .await?;
     println!(
         "Consumed Note Tx on MidenScan: https://testnet.midenscan.com/tx/{:?} \n",
@@ -1,118 +1,39 @@
-use miden_lib::account::auth::AuthRpoFalcon512;
 use miden_lib::transaction::TransactionKernel;
-use rand::{rngs::StdRng, RngCore};
 use std::{fs, path::Path, sync::Arc};
-use tokio::time::{sleep, Duration};
 
 use miden_client::{
-    account::{
-        component::{BasicFungibleFaucet, BasicWallet},
-        Account,
-    },
-    address::NetworkId,
     asset::{FungibleAsset, TokenSymbol},
-    auth::AuthSecretKey,
     builder::ClientBuilder,
     crypto::FeltRng,
-    keystore::FilesystemKeyStore,
     note::{
         Note, NoteAssets, NoteExecutionHint, NoteExecutionMode, NoteInputs, NoteMetadata,
         NoteRecipient, NoteScript, NoteTag, NoteType,
     },
-    rpc::{Endpoint, GrpcClient},
+    rpc::GrpcClient,
     transaction::{OutputNote, TransactionRequestBuilder},
-    Client, ClientError, Felt,
+    Felt,
 };
 use miden_client_sqlite_store::ClientBuilderSqliteExt;
-use miden_objects::{
-    account::{AccountBuilder, AccountStorageMode, AccountType},
-    note::NoteDetails,
+use miden_objects::note::NoteDetails;
+use tutorial_utils::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{create_basic_account, create_basic_faucet, wait_for_notes},
 };
 
-// Helper to create a basic account
-async fn create_basic_account(
-    client: &mut Client<FilesystemKeyStore<StdRng>>,
-    keystore: &Arc<FilesystemKeyStore<StdRng>>,
-) -> Result<Account, ClientError> {
-    let mut init_seed = [0u8; 32];
-    client.rng().fill_bytes(&mut init_seed);
-
-    let key_pair = AuthSecretKey::new_rpo_falcon512();
-
-    let account = AccountBuilder::new(init_seed)
-        .account_type(AccountType::RegularAccountUpdatableCode)
-        .storage_mode(AccountStorageMode::Public)
-        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
-        .with_component(BasicWallet)
-        .build()
-        .unwrap();
-
-    client.add_account(&account, false).await?;
-    keystore.add_key(&key_pair).unwrap();
-
-    Ok(account)
-}
-
-async fn create_basic_faucet(
-    client: &mut Client<FilesystemKeyStore<StdRng>>,
-    keystore: &Arc<FilesystemKeyStore<StdRng>>,
-) -> Result<Account, ClientError> {
-    let mut init_seed = [0u8; 32];
-    client.rng().fill_bytes(&mut init_seed);
-
-    let key_pair = AuthSecretKey::new_rpo_falcon512();
-    let symbol = TokenSymbol::new("MID").unwrap();
-    let decimals = 8;
-    let max_supply = Felt::new(1_000_000);
-
-    let account = AccountBuilder::new(init_seed)
-        .account_type(AccountType::FungibleFaucet)
-        .storage_mode(AccountStorageMode::Public)
-        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
-        .with_component(BasicFungibleFaucet::new(symbol, decimals, max_supply).unwrap())
-        .build()
-        .unwrap();
-
-    client.add_account(&account, false).await?;
-    keystore.add_key(&key_pair).unwrap();
-
-    Ok(account)
-}
-
-// Helper to wait until an account has the expected number of consumable notes
-async fn wait_for_notes(
-    client: &mut Client<FilesystemKeyStore<StdRng>>,
-    account_id: &Account,
-    expected: usize,
-) -> Result<(), ClientError> {
-    loop {
-        client.sync_state().await?;
-        let notes = client.get_consumable_notes(Some(account_id.id())).await?;
-        if notes.len() >= expected {
-            break;
-        }
-        println!(
-            "{} consumable notes found for account {}. Waiting...",
-            notes.len(),
-            account_id.id().to_bech32(NetworkId::Testnet)
-        );
-        sleep(Duration::from_secs(3)).await;
-    }
-    Ok(())
-}
-
 #[tokio::main]
-async fn main() -> Result<(), ClientError> {
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize client
-    let endpoint = Endpoint::testnet();
+    let network = tutorial_utils::cli::network().await?;
+    let endpoint = network.endpoint().clone();
     let timeout_ms = 10_000;
     let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
 
-    // Initialize keystore
-    let keystore_path = std::path::PathBuf::from("./keystore");
-    let keystore = Arc::new(FilesystemKeyStore::<StdRng>::new(keystore_path).unwrap());
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("note_creation_in_masm")?;
+    let keystore = context.keystore()?;
 
-    let store_path = std::path::PathBuf::from("./store.sqlite3");
+    let store_path = context.store_path();
 
     let mut client = ClientBuilder::new()
         .rpc(rpc_client)
@@ -132,19 +53,20 @@ async fn main() -> Result<(), ClientError> {
     let alice_account = create_basic_account(&mut client, &keystore).await?;
     println!(
         "Alice's account ID: {:?}",
-        alice_account.id().to_bech32(NetworkId::Testnet)
+        alice_account.id().to_bech32(network.network_id())
     );
     let bob_account = create_basic_account(&mut client, &keystore).await?;
     println!(
         "Bob's account ID: {:?}",
-        bob_account.id().to_bech32(NetworkId::Testnet)
+        bob_account.id().to_bech32(network.network_id())
     );
 
     println!("\nDeploying a new fungible faucet.");
-    let faucet = create_basic_faucet(&mut client, &keystore).await?;
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
     println!(
         "Faucet account ID: {:?}",
-        faucet.id().to_bech32(NetworkId::Testnet)
+        faucet.id().to_bech32(network.network_id())
     );
     client.sync_state().await?;
 
@@ -165,10 +87,10 @@ async fn main() -> Result<(), ClientError> {
         )
         .unwrap();
 
-    let tx_id = client.submit_new_transaction(faucet.id(), tx_req).await?;
+    let tx_id = client.submit_with_prover(faucet.id(), tx_req).await?;
     println!("Minted tokens. TX: {:?}", tx_id);
 
-    wait_for_notes(&mut client, &alice_account, 1).await?;
+    wait_for_notes(&mut client, &network, alice_account.id(), 1).await?;
 
     // Consume the minted note
     let consumable_notes = client
@@ -181,7 +103,7 @@ async fn main() -> Result<(), ClientError> {
             .unwrap();
 
         let tx_id = client
-            .submit_new_transaction(alice_account.id(), consume_req)
+            .submit_with_prover(alice_account.id(), consume_req)
             .await?;
         println!("Consumed minted note. TX: {:?}", tx_id);
     }
@@ -226,7 +148,7 @@ async fn main() -> Result<(), ClientError> {
         .unwrap();
 
     let tx_id = client
-        .submit_new_transaction(alice_account.id(), note_req)
+        .submit_with_prover(alice_account.id(), note_req)
         .await?;
     println!(
         "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
@@ -277,7 +199,7 @@ async fn main() -> Result<(), ClientError> {
         .unwrap();
 
     let tx_id = client
-        .submit_new_transaction(bob_account.id(), consume_custom_req)
+        .submit_with_prover(bob_account.id(), consume_custom_req)
         .await?;
     println!(
         "Consumed Note Tx on MidenScan: https://testnet.midenscan.com/tx/{:?}",
@@ -1,69 +1,35 @@
-use miden_lib::account::auth::AuthRpoFalcon512;
-use rand::{rngs::StdRng, RngCore};
 use std::sync::Arc;
-use tokio::time::{sleep, Duration, Instant};
+use tokio::time::{Duration, Instant};
 
 use miden_client::{
-    account::component::{BasicFungibleFaucet, BasicWallet},
-    address::NetworkId,
     asset::{FungibleAsset, TokenSymbol},
-    auth::AuthSecretKey,
     builder::ClientBuilder,
-    keystore::FilesystemKeyStore,
     note::{create_p2id_note, Note, NoteType},
-    rpc::{Endpoint, GrpcClient},
-    store::TransactionFilter,
-    transaction::{OutputNote, TransactionId, TransactionRequestBuilder, TransactionStatus},
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
     utils::{Deserializable, Serializable},
-    Client, ClientError, Felt,
+    Felt,
 };
 use miden_client_sqlite_store::ClientBuilderSqliteExt;
-use miden_objects::account::{AccountBuilder, AccountStorageMode, AccountType};
-
-/// Waits for a specific transaction to be committed.
-async fn wait_for_tx(
-    client: &mut Client<FilesystemKeyStore<StdRng>>,
-    tx_id: TransactionId,
-) -> Result<(), ClientError> {
-    loop {
-        client.sync_state().await?;
-
-        // Check transaction status
-        let txs = client
-            .get_transactions(TransactionFilter::Ids(vec![tx_id]))
-            .await?;
-        let tx_committed = if !txs.is_empty() {
-            matches!(txs[0].status, TransactionStatus::Committed { .. })
-        } else {
-            false
-        };
-
-        if tx_committed {
-            println!("✅ transaction {} committed", tx_id.to_hex());
-            break;
-        }
-
-        println!(
-            "Transaction {} not yet committed. Waiting...",
-            tx_id.to_hex()
-        );
-        sleep(Duration::from_secs(2)).await;
-    }
-    Ok(())
-}
+use tutorial_utils::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{create_basic_account, create_basic_faucet, wait_for_tx},
+};
 
 #[tokio::main]
-async fn main() -> Result<(), ClientError> {
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize client
-    let endpoint = Endpoint::testnet();
+    let network = tutorial_utils::cli::network().await?;
+    let endpoint = network.endpoint().clone();
     let timeout_ms = 10_000;
     let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
 
-    // Initialize keystore
-    let keystore_path = std::path::PathBuf::from("./keystore");
-    let keystore = Arc::new(FilesystemKeyStore::<StdRng>::new(keystore_path).unwrap());
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("unauthenticated_note_transfer")?;
+    let keystore = context.keystore()?;
 
-    let store_path = std::path::PathBuf::from("./store.sqlite3");
+    let store_path = context.store_path();
 
     let mut client = ClientBuilder::new()
         .rpc(rpc_client)
@@ -81,38 +47,14 @@ async fn main() -> Result<(), ClientError> {
     //------------------------------------------------------------
     println!("\n[STEP 1] Deploying a new fungible faucet.");
 
-    // Faucet seed
-    let mut init_seed = [0_u8; 32];
-    client.rng().fill_bytes(&mut init_seed);
-
-    // Generate key pair
-    let key_pair = AuthSecretKey::new_rpo_falcon512();
-
-    // Faucet parameters
-    let symbol = TokenSymbol::new("MID").unwrap();
-    let decimals = 8;
-    let max_supply = Felt::new(1_000_000);
-
-    // Build the account
-    let faucet_account = AccountBuilder::new(init_seed)
-        .account_type(AccountType::FungibleFaucet)
-        .storage_mode(AccountStorageMode::Public)
-        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
-        .with_component(BasicFungibleFaucet::new(symbol, decimals, max_supply).unwrap())
-        .build()
-        .unwrap();
-
-    // Add the faucet to the client
-    client.add_account(&faucet_account, false).await?;
+    let faucet_account =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
 
     println!(
         "Faucet account ID: {}",
-        faucet_account.id().to_bech32(NetworkId::Testnet)
+        faucet_account.id().to_bech32(network.network_id())
     );
 
-    // Add the key pair to the keystore
-    keystore.add_key(&key_pair).unwrap();
-
     // Resync to show newly deployed faucet
     tokio::time::sleep(Duration::from_secs(2)).await;
     client.sync_state().await?;
@@ -126,29 +68,13 @@ async fn main() -> Result<(), ClientError> {
     let number_of_accounts = 10;
 
     for i in 0..number_of_accounts {
-        let mut init_seed = [0_u8; 32];
-        client.rng().fill_bytes(&mut init_seed);
-
-        let key_pair = AuthSecretKey::new_rpo_falcon512();
-
-        let account = AccountBuilder::new(init_seed)
-            .account_type(AccountType::RegularAccountUpdatableCode)
-            .storage_mode(AccountStorageMode::Public)
-            .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
-            .with_component(BasicWallet)
-            .build()
-            .unwrap();
-
+        let account = create_basic_account(&mut client, &keystore).await?;
         accounts.push(account.clone());
         println!(
             "account id {:?}: {}",
             i,
-            account.id().to_bech32(NetworkId::Testnet)
+            account.id().to_bech32(network.network_id())
         );
-        client.add_account(&account, true).await?;
-
-        // Add the key pair to the keystore
-        keystore.add_key(&key_pair).unwrap();
     }
 
     // For demo purposes, Alice is the first account.
@@ -171,7 +97,7 @@ async fn main() -> Result<(), ClientError> {
         .unwrap();
 
     let tx_id = client
-        .submit_new_transaction(faucet_account.id(), transaction_request)
+        .submit_with_prover(faucet_account.id(), transaction_request)
         .await?;
     println!("Minted tokens. TX: {:?}", tx_id);
 
@@ -187,7 +113,7 @@ async fn main() -> Result<(), ClientError> {
             .unwrap();
 
         let consume_tx_id = client
-            .submit_new_transaction(alice.id(), transaction_request)
+            .submit_with_prover(alice.id(), transaction_request)
             .await?;
         println!("Consumed minted note. TX: {:?}", consume_tx_id);
 
@@ -204,10 +130,13 @@ async fn main() -> Result<(), ClientError> {
     for i in 0..number_of_accounts - 1 {
         let loop_start = Instant::now();
         println!("\nunauthenticated tx {:?}", i + 1);
-        println!("sender: {}", accounts[i].id().to_bech32(NetworkId::Testnet));
+        println!(
+            "sender: {}",
+            accounts[i].id().to_bech32(network.network_id())
+        );
         println!(
             "target: {}",
-            accounts[i + 1].id().to_bech32(NetworkId::Testnet)
+            accounts[i + 1].id().to_bech32(network.network_id())
         );
 
         // Time the creation of the p2id note
@@ -241,7 +170,7 @@ async fn main() -> Result<(), ClientError> {
             .unwrap();
 
         let tx_id = client
-            .submit_new_transaction(accounts[i].id(), transaction_request)
+            .submit_with_prover(accounts[i].id(), transaction_request)
             .await?;
         println!("Created note. TX: {:?}", tx_id);
 
@@ -257,7 +186,7 @@ async fn main() -> Result<(), ClientError> {
             .unwrap();
 
         let tx_id = client
-            .submit_new_transaction(accounts[i + 1].id(), consume_note_request)
+            .submit_with_prover(accounts[i + 1].id(), consume_note_request)
             .await?;
 
         println!(
@@ -288,7 +217,7 @@ async fn main() -> Result<(), ClientError> {
             .unwrap();
         println!(
             "Account: {} balance: {}",
-            account.id().to_bech32(NetworkId::Testnet),
+            account.id().to_bech32(network.network_id()),
             balance
         );
     }
@@ -0,0 +1,215 @@
+use rand::{rngs::StdRng, RngCore};
+use std::{fs, path::Path, sync::Arc};
+
+use miden_client::{
+    account::AccountId,
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    rpc::GrpcClient,
+    transaction::{TransactionRequest, TransactionRequestBuilder},
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_lib::{
+    account::auth::{AuthRpoFalcon512Acl, AuthRpoFalcon512AclConfig},
+    transaction::TransactionKernel,
+};
+use miden_objects::{
+    account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
+    assembly::Library,
+    Word,
+};
+use tutorial_utils::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{create_library, procedure_root, wait_for_tx},
+    storage::read_u64_slot,
+};
+
+/// Storage slot of the count. The counter component comes after the ACL auth component, which
+/// keeps the public key, its configuration and the trigger procedures in the first three slots.
+const COUNT_SLOT: u8 = 3;
+
+const INCREMENT_SCRIPT_PATH: &str = "../masm/scripts/counter_script.masm";
+const DECREMENT_SCRIPT_PATH: &str = "../masm/scripts/counter_decrement_script.masm";
+const RESET_SCRIPT_PATH: &str = "../masm/scripts/counter_reset_script.masm";
+
+/// Compiles the transaction script at `script_path` into a request calling the counter.
+fn counter_request(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    library: &Library,
+    script_path: &str,
+) -> Result<TransactionRequest, Box<dyn std::error::Error>> {
+    let script_code = fs::read_to_string(Path::new(script_path))?;
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(library)?
+        .compile_tx_script(&script_code)?;
+    Ok(TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .build()?)
+}
+
+/// Returns the count of the counter as last synced by the client.
+async fn count(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    counter_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(counter_id)
+        .await?
+        .ok_or("the counter is not tracked by the client")?;
+    Ok(read_u64_slot(record.account(), COUNT_SLOT)?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = tutorial_utils::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("counter_acl_example")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create a counter whose reset is restricted to its owner
+    // -------------------------------------------------------------------------
+    // The ACL auth component asks for the signature of the owner only when the transaction
+    // calls one of its trigger procedures. Incrementing and decrementing stay open to anyone.
+    println!("\n[STEP 1] Creating a counter only its owner can reset");
+
+    let counter_code = fs::read_to_string(Path::new("../masm/accounts/counter.masm"))?;
+    let counter_component = AccountComponent::compile(
+        &counter_code,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Value(Word::default())],
+    )?
+    .with_supports_all_types();
+    let reset_root = procedure_root(counter_component.library(), "reset_count")?;
+
+    // The owner key is generated here but only added to the keystore in step 5: until then
+    // this client cannot sign for the owner, and every reset it submits is unauthorized
+    let owner_key = AuthSecretKey::new_rpo_falcon512();
+    let auth = AuthRpoFalcon512Acl::new(
+        owner_key.public_key().to_commitment(),
+        AuthRpoFalcon512AclConfig::new().with_auth_trigger_procedures(vec![reset_root]),
+    )?;
+
+    let mut seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut seed);
+    let counter_contract = AccountBuilder::new(seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(auth)
+        .with_component(counter_component)
+        .build()?;
+    client.add_account(&counter_contract, false).await?;
+    println!(
+        "Counter contract id: {}",
+        counter_contract.id().to_bech32(network.network_id())
+    );
+
+    let library = create_library(&counter_code, "external_contract::counter_contract")?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Increment the counter
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Incrementing the counter");
+
+    let request = counter_request(&client, &library, INCREMENT_SCRIPT_PATH)?;
+    let tx_id = client
+        .submit_with_prover(counter_contract.id(), request)
+        .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Count: {}", count(&client, counter_contract.id()).await?);
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Decrement the counter, then try to go below zero
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Decrementing the counter");
+
+    let request = counter_request(&client, &library, DECREMENT_SCRIPT_PATH)?;
+    let tx_id = client
+        .submit_with_prover(counter_contract.id(), request)
+        .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Count: {}", count(&client, counter_contract.id()).await?);
+
+    let request = counter_request(&client, &library, DECREMENT_SCRIPT_PATH)?;
+    let Err(err) = client
+        .submit_with_prover(counter_contract.id(), request)
+        .await
+    else {
+        return Err("the counter went below zero".into());
+    };
+    println!("Decrementing a zero count failed as expected: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Reset the counter without the key of the owner
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Resetting the counter without the key of the owner");
+
+    let request = counter_request(&client, &library, INCREMENT_SCRIPT_PATH)?;
+    let tx_id = client
+        .submit_with_prover(counter_contract.id(), request)
+        .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let request = counter_request(&client, &library, RESET_SCRIPT_PATH)?;
+    let Err(err) = client
+        .submit_with_prover(counter_contract.id(), request)
+        .await
+    else {
+        return Err("the counter was reset without the signature of its owner".into());
+    };
+    println!("Unauthorized reset failed as expected: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Reset the counter as its owner
+    // -------------------------------------------------------------------------
+    // Once the owner key is in the keystore, the client signs the transaction calling the
+    // trigger procedure and the ACL auth component accepts it.
+    println!("\n[STEP 5] Resetting the counter with the key of the owner");
+
+    client.sync_state().await?;
+    let count_before = count(&client, counter_contract.id()).await?;
+    println!("Count before the reset: {count_before}");
+    assert_eq!(count_before, 1);
+
+    keystore.add_key(&owner_key)?;
+
+    let request = counter_request(&client, &library, RESET_SCRIPT_PATH)?;
+    let tx_id = client
+        .submit_with_prover(counter_contract.id(), request)
+        .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Reading the count");
+
+    client.sync_state().await?;
+    let final_count = count(&client, counter_contract.id()).await?;
+    println!("Count: {final_count}");
+    assert_eq!(final_count, 0);
+
+    Ok(())
+}
@@ -1,50 +1,34 @@
 use miden_lib::account::auth::NoAuth;
 use miden_lib::transaction::TransactionKernel;
-use rand::{rngs::StdRng, RngCore};
+use rand::RngCore;
 use std::{fs, path::Path, sync::Arc};
 
 use miden_client::{
-    address::NetworkId,
-    assembly::{Assembler, DefaultSourceManager, LibraryPath, Module, ModuleKind},
-    builder::ClientBuilder,
-    keystore::FilesystemKeyStore,
-    rpc::{Endpoint, GrpcClient},
-    transaction::TransactionRequestBuilder,
-    ClientError,
+    builder::ClientBuilder, rpc::GrpcClient, transaction::TransactionRequestBuilder, ClientError,
 };
 use miden_client_sqlite_store::ClientBuilderSqliteExt;
 use miden_objects::{
     account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
     Word,
 };
-
-fn create_library(
-    assembler: Assembler,
-    library_path: &str,
-    source_code: &str,
-) -> Result<miden_objects::assembly::Library, Box<dyn std::error::Error>> {
-    let source_manager = Arc::new(DefaultSourceManager::default());
-    let module = Module::parser(ModuleKind::Library).parse_str(
-        LibraryPath::new(library_path)?,
-        source_code,
-        &source_manager,
-    )?;
-    let library = assembler.clone().assemble_library([module])?;
-    Ok(library)
-}
+use tutorial_utils::{
+    cli::SubmitWithProver, context::TutorialContext, helpers::create_library,
+    storage::read_u64_slot,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), ClientError> {
     // Initialize client
-    let endpoint = Endpoint::testnet();
+    let network = tutorial_utils::cli::network().await?;
+    let endpoint = network.endpoint().clone();
     let timeout_ms = 10_000;
     let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
 
-    // Initialize keystore
-    let keystore_path = std::path::PathBuf::from("./keystore");
-    let keystore = Arc::new(FilesystemKeyStore::<StdRng>::new(keystore_path).unwrap());
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("counter_contract_deploy")?;
+    let keystore = context.keystore()?;
 
-    let store_path = std::path::PathBuf::from("./store.sqlite3");
+    let store_path = context.store_path();
 
     let mut client = ClientBuilder::new()
         .rpc(rpc_client)
@@ -107,13 +91,8 @@ async fn main() -> Result<(), ClientError> {
     let script_code = fs::read_to_string(script_path).unwrap();
 
     // Create a library from the counter contract code
-    let assembler = TransactionKernel::assembler().with_debug_mode(true);
-    let account_component_lib = create_library(
-        assembler.clone(),
-        "external_contract::counter_contract",
-        &counter_code,
-    )
-    .unwrap();
+    let account_component_lib =
+        create_library(&counter_code, "external_contract::counter_contract").unwrap();
 
     let tx_script = client
         .script_builder()
@@ -130,7 +109,7 @@ async fn main() -> Result<(), ClientError> {
 
     // Execute and submit the transaction
     let tx_id = client
-        .submit_new_transaction(counter_contract.id(), tx_increment_request)
+        .submit_with_prover(counter_contract.id(), tx_increment_request)
         .await
         .unwrap();
 
@@ -141,7 +120,7 @@ async fn main() -> Result<(), ClientError> {
 
     println!(
         "Counter contract id: {:?}",
-        counter_contract.id().to_bech32(NetworkId::Testnet)
+        counter_contract.id().to_bech32(network.network_id())
     );
 
     client.sync_state().await.unwrap();
@@ -149,8 +128,8 @@ async fn main() -> Result<(), ClientError> {
     // Retrieve updated contract data to see the incremented counter
     let account = client.get_account(counter_contract.id()).await.unwrap();
     println!(
-        "counter contract storage: {:?}",
-        account.unwrap().account().storage().get_item(0)
+        "counter contract count: {}",
+        read_u64_slot(account.unwrap().account(), 0)?
     );
 
     Ok(())
@@ -1,45 +1,28 @@
-use miden_lib::transaction::TransactionKernel;
-use rand::rngs::StdRng;
 use std::{fs, path::Path, sync::Arc};
 
 use miden_client::{
-    account::AccountId,
-    assembly::{Assembler, DefaultSourceManager, LibraryPath, Module, ModuleKind},
-    builder::ClientBuilder,
-    keystore::FilesystemKeyStore,
-    rpc::{Endpoint, GrpcClient},
-    transaction::TransactionRequestBuilder,
-    ClientError,
+    account::AccountId, builder::ClientBuilder, rpc::GrpcClient,
+    transaction::TransactionRequestBuilder, ClientError,
 };
 use miden_client_sqlite_store::ClientBuilderSqliteExt;
-
-fn create_library(
-    assembler: Assembler,
-    library_path: &str,
-    source_code: &str,
-) -> Result<miden_objects::assembly::Library, Box<dyn std::error::Error>> {
-    let source_manager = Arc::new(DefaultSourceManager::default());
-    let module = Module::parser(ModuleKind::Library).parse_str(
-        LibraryPath::new(library_path)?,
-        source_code,
-        &source_manager,
-    )?;
-    let library = assembler.clone().assemble_library([module])?;
-    Ok(library)
-}
+use tutorial_utils::{
+    cli::SubmitWithProver, context::TutorialContext, helpers::create_library,
+    storage::read_u64_slot,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), ClientError> {
     // Initialize client
-    let endpoint = Endpoint::testnet();
+    let network = tutorial_utils::cli::network().await?;
+    let endpoint = network.endpoint().clone();
     let timeout_ms = 10_000;
     let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
 
-    // Initialize keystore
-    let keystore_path = std::path::PathBuf::from("./keystore");
-    let keystore = Arc::new(FilesystemKeyStore::<StdRng>::new(keystore_path).unwrap());
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("counter_contract_increment")?;
+    let keystore = context.keystore()?;
 
-    let store_path = std::path::PathBuf::from("./store.sqlite3");
+    let store_path = context.store_path();
 
     let mut client = ClientBuilder::new()
         .rpc(rpc_client)
@@ -92,13 +75,8 @@ async fn main() -> Result<(), ClientError> {
     let counter_path = Path::new("../masm/accounts/counter.masm");
     let counter_code = fs::read_to_string(counter_path).unwrap();
 
-    let assembler = TransactionKernel::assembler().with_debug_mode(true);
-    let account_component_lib = create_library(
-        assembler.clone(),
-        "external_contract::counter_contract",
-        &counter_code,
-    )
-    .unwrap();
+    let account_component_lib =
+        create_library(&counter_code, "external_contract::counter_contract").unwrap();
 
     let tx_script = client
         .script_builder()
@@ -115,7 +93,7 @@ async fn main() -> Result<(), ClientError> {
 
     // Execute and submit the transaction
     let tx_id = client
-        .submit_new_transaction(counter_contract.id(), tx_increment_request)
+        .submit_with_prover(counter_contract.id(), tx_increment_request)
         .await
         .unwrap();
 
@@ -129,8 +107,8 @@ async fn main() -> Result<(), ClientError> {
     // Retrieve updated contract data to see the incremented counter
     let account = client.get_account(counter_contract.id()).await.unwrap();
     println!(
-        "counter contract storage: {:?}",
-        account.unwrap().account().storage().get_item(0)
+        "counter contract count: {}",
+        read_u64_slot(account.unwrap().account(), 0)?
     );
     Ok(())
 }
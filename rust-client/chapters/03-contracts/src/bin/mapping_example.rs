@@ -1,51 +1,39 @@
 use miden_lib::account::auth::NoAuth;
 use miden_lib::transaction::TransactionKernel;
-use rand::{rngs::StdRng, RngCore};
+use rand::RngCore;
 use std::{fs, path::Path, sync::Arc};
 
 use miden_client::{
-    assembly::{Assembler, DefaultSourceManager, LibraryPath, Module, ModuleKind},
-    builder::ClientBuilder,
-    keystore::FilesystemKeyStore,
-    rpc::{Endpoint, GrpcClient},
-    transaction::TransactionRequestBuilder,
-    ClientError,
+    assembly::Assembler, builder::ClientBuilder, rpc::GrpcClient,
+    transaction::TransactionRequestBuilder, ClientError,
 };
 use miden_client_sqlite_store::ClientBuilderSqliteExt;
 use miden_objects::{
     account::{
         AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
     },
-    Felt, Word,
+    Word,
+};
+use tutorial_utils::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::create_library,
+    storage::{felt_key, read_map_u64},
 };
-
-fn create_library(
-    assembler: Assembler,
-    library_path: &str,
-    source_code: &str,
-) -> Result<miden_objects::assembly::Library, Box<dyn std::error::Error>> {
-    let source_manager = Arc::new(DefaultSourceManager::default());
-    let module = Module::parser(ModuleKind::Library).parse_str(
-        LibraryPath::new(library_path)?,
-        source_code,
-        &source_manager,
-    )?;
-    let library = assembler.clone().assemble_library([module])?;
-    Ok(library)
-}
 
 #[tokio::main]
 async fn main() -> Result<(), ClientError> {
     // Initialize client
-    let endpoint = Endpoint::testnet();
+    let network = tutorial_utils::cli::network().await?;
+    let endpoint = network.endpoint().clone();
     let timeout_ms = 10_000;
     let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
 
-    // Initialize keystore
-    let keystore_path = std::path::PathBuf::from("./keystore");
-    let keystore = Arc::new(FilesystemKeyStore::<StdRng>::new(keystore_path).unwrap());
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("mapping_example")?;
+    let keystore = context.keystore()?;
 
-    let store_path = std::path::PathBuf::from("./store.sqlite3");
+    let store_path = context.store_path();
 
     let mut client = ClientBuilder::new()
         .rpc(rpc_client)
@@ -114,12 +102,8 @@ async fn main() -> Result<(), ClientError> {
         fs::read_to_string(Path::new("../masm/scripts/mapping_example_script.masm")).unwrap();
 
     // Create the library from the account source code using the helper function.
-    let account_component_lib = create_library(
-        assembler.clone(),
-        "miden_by_example::mapping_example_contract",
-        &account_code,
-    )
-    .unwrap();
+    let account_component_lib =
+        create_library(&account_code, "miden_by_example::mapping_example_contract").unwrap();
 
     // Compile the transaction script with the library.
     let tx_script = client
@@ -137,7 +121,7 @@ async fn main() -> Result<(), ClientError> {
 
     // Execute and submit the transaction
     let tx_id = client
-        .submit_new_transaction(mapping_example_contract.id(), tx_increment_request)
+        .submit_with_prover(mapping_example_contract.id(), tx_increment_request)
         .await
         .unwrap();
 
@@ -153,16 +137,12 @@ async fn main() -> Result<(), ClientError> {
         .await
         .unwrap();
     let index = 1;
-    let key = [Felt::new(0), Felt::new(0), Felt::new(0), Felt::new(0)].into();
+    let key = felt_key(0);
     println!(
         "Mapping state\n Index: {:?}\n Key: {:?}\n Value: {:?}",
         index,
         key,
-        account
-            .unwrap()
-            .account()
-            .storage()
-            .get_map_item(index, key)
+        read_map_u64(account.unwrap().account(), index, key)?
     );
 
     Ok(())
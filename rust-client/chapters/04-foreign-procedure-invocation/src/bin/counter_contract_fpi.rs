@@ -1,15 +1,13 @@
 use miden_crypto::Felt;
 use miden_lib::account::auth::NoAuth;
 use miden_lib::transaction::TransactionKernel;
-use rand::{rngs::StdRng, RngCore};
+use rand::RngCore;
 use std::{fs, path::Path, sync::Arc, time::Duration};
 use tokio::time::sleep;
 
 use miden_client::{
-    assembly::{Assembler, DefaultSourceManager, LibraryPath, Module, ModuleKind},
     builder::ClientBuilder,
-    keystore::FilesystemKeyStore,
-    rpc::{domain::account::AccountStorageRequirements, Endpoint, GrpcClient},
+    rpc::{domain::account::AccountStorageRequirements, GrpcClient},
     transaction::{ForeignAccount, TransactionRequestBuilder},
     ClientError,
 };
@@ -21,34 +19,24 @@ use miden_objects::{
     assembly::mast::MastNodeExt,
     Word,
 };
-
-fn create_library(
-    assembler: Assembler,
-    library_path: &str,
-    source_code: &str,
-) -> Result<miden_objects::assembly::Library, Box<dyn std::error::Error>> {
-    let source_manager = Arc::new(DefaultSourceManager::default());
-    let module = Module::parser(ModuleKind::Library).parse_str(
-        LibraryPath::new(library_path)?,
-        source_code,
-        &source_manager,
-    )?;
-    let library = assembler.clone().assemble_library([module])?;
-    Ok(library)
-}
+use tutorial_utils::{
+    cli::SubmitWithProver, context::TutorialContext, helpers::create_library,
+    storage::read_u64_slot,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), ClientError> {
     // Initialize client
-    let endpoint = Endpoint::testnet();
+    let network = tutorial_utils::cli::network().await?;
+    let endpoint = network.endpoint().clone();
     let timeout_ms = 10_000;
     let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
 
-    // Initialize keystore
-    let keystore_path = std::path::PathBuf::from("./keystore");
-    let keystore = Arc::new(FilesystemKeyStore::<StdRng>::new(keystore_path).unwrap());
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("counter_contract_fpi")?;
+    let keystore = context.keystore()?;
 
-    let store_path = std::path::PathBuf::from("./store.sqlite3");
+    let store_path = context.store_path();
 
     let mut client = ClientBuilder::new()
         .rpc(rpc_client)
@@ -70,9 +58,6 @@ async fn main() -> Result<(), ClientError> {
     let count_reader_path = Path::new("../masm/accounts/count_reader.masm");
     let count_reader_code = fs::read_to_string(count_reader_path).unwrap();
 
-    // Prepare assembler (debug mode = true)
-    let assembler = TransactionKernel::assembler().with_debug_mode(true);
-
     // Compile the account code into `AccountComponent` with one storage slot
     let count_reader_component = AccountComponent::compile(
         &count_reader_code,
@@ -194,9 +179,8 @@ async fn main() -> Result<(), ClientError> {
         );
 
     let account_component_lib = create_library(
-        assembler.clone(),
-        "external_contract::count_reader_contract",
         &count_reader_code,
+        "external_contract::count_reader_contract",
     )
     .unwrap();
 
@@ -219,7 +203,7 @@ async fn main() -> Result<(), ClientError> {
 
     // Execute and submit the transaction
     let tx_id = client
-        .submit_new_transaction(count_reader_contract.id(), tx_request)
+        .submit_with_prover(count_reader_contract.id(), tx_request)
         .await
         .unwrap();
 
@@ -237,8 +221,8 @@ async fn main() -> Result<(), ClientError> {
     // Retrieve updated contract data to see the incremented counter
     let account_1 = client.get_account(counter_contract.id()).await.unwrap();
     println!(
-        "counter contract storage: {:?}",
-        account_1.unwrap().account().storage().get_item(0)
+        "counter contract count: {}",
+        read_u64_slot(account_1.unwrap().account(), 0)?
     );
 
     let account_2 = client
@@ -246,8 +230,8 @@ async fn main() -> Result<(), ClientError> {
         .await
         .unwrap();
     println!(
-        "count reader contract storage: {:?}",
-        account_2.unwrap().account().storage().get_item(0)
+        "count reader contract count: {}",
+        read_u64_slot(account_2.unwrap().account(), 0)?
     );
 
     Ok(())
@@ -0,0 +1,253 @@
+use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel};
+use miden_processor::{
+    execute, AdviceInputs, DebugHandler, DebugOptions, DefaultHost, ExecutionError,
+    ExecutionOptions, ProcessState, StackInputs,
+};
+use rand::RngCore;
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use miden_client::{
+    builder::ClientBuilder, rpc::GrpcClient, transaction::TransactionRequestBuilder,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
+    assembly::Library,
+    Word,
+};
+use tutorial_utils::{cli::SubmitWithProver, context::TutorialContext, helpers::create_library};
+
+/// A `debug` or `trace` decorator reached during execution.
+enum DebugEvent {
+    Stack { clk: String, values: Vec<u64> },
+    Trace { clk: String, id: u32 },
+}
+
+/// Debug handler recording events instead of printing them.
+///
+/// The host takes ownership of its handler, so the events are kept behind an `Arc` to read
+/// them once execution is over.
+#[derive(Default, Clone)]
+struct EventRecorder {
+    events: Arc<Mutex<Vec<DebugEvent>>>,
+}
+
+impl DebugHandler for EventRecorder {
+    fn on_debug(
+        &mut self,
+        process: &ProcessState,
+        options: &DebugOptions,
+    ) -> Result<(), ExecutionError> {
+        let stack = process.get_stack_state();
+        let count = match options {
+            DebugOptions::StackAll => stack.len(),
+            DebugOptions::StackTop(count) => usize::from(*count),
+            // `debug.mem`, `debug.local` and `debug.adv_stack` are not used by the contract
+            _ => return Ok(()),
+        };
+        let values = stack.iter().take(count).map(|felt| felt.as_int()).collect();
+
+        self.events.lock().unwrap().push(DebugEvent::Stack {
+            clk: process.clk().to_string(),
+            values,
+        });
+        Ok(())
+    }
+
+    fn on_trace(&mut self, process: &ProcessState, trace_id: u32) -> Result<(), ExecutionError> {
+        self.events.lock().unwrap().push(DebugEvent::Trace {
+            clk: process.clk().to_string(),
+            id: trace_id,
+        });
+        Ok(())
+    }
+}
+
+/// Names of the trace ids emitted by `debug_counter.masm`.
+fn trace_name(id: u32) -> &'static str {
+    match id {
+        1 => "enter sum_of_squares",
+        2 => "leave sum_of_squares",
+        3 => "store count",
+        _ => "unknown",
+    }
+}
+
+fn print_events(events: &[DebugEvent]) {
+    println!("{:>6}  {:<28}  STACK (top first)", "CYCLE", "EVENT");
+    for event in events {
+        match event {
+            DebugEvent::Stack { clk, values } => {
+                let values: Vec<String> = values.iter().map(u64::to_string).collect();
+                println!(
+                    "{clk:>6}  {:<28}  [{}]",
+                    format!("debug.stack.{}", values.len()),
+                    values.join(", ")
+                );
+            }
+            DebugEvent::Trace { clk, id } => {
+                println!(
+                    "{clk:>6}  {:<28}",
+                    format!("trace.{id} ({})", trace_name(*id))
+                );
+            }
+        }
+    }
+}
+
+/// Executes `sum_of_squares(a, b)` in the VM, returning the events recorded until the program
+/// finished or failed.
+fn run_sum_of_squares(
+    library: &Library,
+    a: u32,
+    b: u32,
+) -> Result<(Result<u64, ExecutionError>, Vec<DebugEvent>), Box<dyn std::error::Error>> {
+    let program = TransactionKernel::assembler()
+        .with_debug_mode(true)
+        .with_static_library(library)?
+        .assemble_program(format!(
+            "use.external_contract::debug_counter
+
+begin
+    push.{b} push.{a}
+    exec.debug_counter::sum_of_squares
+end
+"
+        ))?;
+
+    // Debug decorators are skipped unless debugging is enabled, trace decorators unless
+    // tracing is
+    let recorder = EventRecorder::default();
+    let mut host = DefaultHost::default().with_debug_handler(recorder.clone());
+    let options = ExecutionOptions::default()
+        .with_debugging(true)
+        .with_tracing();
+
+    let result = execute(
+        &program,
+        StackInputs::default(),
+        AdviceInputs::default(),
+        &mut host,
+        options,
+    )
+    .map(|trace| trace.stack_outputs().get_stack_item(0).unwrap().as_int());
+
+    let events = std::mem::take(&mut *recorder.events.lock().unwrap());
+    Ok((result, events))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let contract_path = Path::new("../masm/accounts/debug_counter.masm");
+    let contract_code = fs::read_to_string(contract_path)?;
+
+    // The decorators are only compiled in debug mode, which `create_library` enables too
+    let assembler = TransactionKernel::assembler().with_debug_mode(true);
+    let library = create_library(&contract_code, "external_contract::debug_counter")?;
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Capture the events of a successful execution
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Executing sum_of_squares(3, 4) with a recording debug handler");
+
+    let (result, events) = run_sum_of_squares(&library, 3, 4)?;
+    print_events(&events);
+    println!("Result: {}", result?);
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Capture the events leading to a failure
+    // -------------------------------------------------------------------------
+    // Events are recorded as they happen, so the ones reached before a failed assertion are
+    // still there to tell how far execution went.
+    println!("\n[STEP 2] Executing sum_of_squares(70000, 1), which overflows");
+
+    let (result, events) = run_sum_of_squares(&library, 70_000, 1)?;
+    print_events(&events);
+    match result {
+        Ok(value) => panic!("sum_of_squares(70000, 1) returned {value}"),
+        Err(err) => println!("Failed as expected: {err}"),
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: The same decorators inside a transaction
+    // -------------------------------------------------------------------------
+    // A client in debug mode executes transactions with debugging enabled: the transaction
+    // host prints `debug.stack` output to stdout. Trace ids are only delivered to hosts that
+    // enable tracing, which the transaction executor does not.
+    println!("\n[STEP 3] Calling add_sum_of_squares(3, 4) in a transaction");
+
+    let network = tutorial_utils::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("debug_events")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore)
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    let component = AccountComponent::compile(
+        &contract_code,
+        assembler,
+        vec![StorageSlot::Value(Word::default())],
+    )?
+    .with_supports_all_types();
+
+    let mut seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut seed);
+
+    let debug_counter = AccountBuilder::new(seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_component(component)
+        .with_auth_component(NoAuth)
+        .build()?;
+    client.add_account(&debug_counter, false).await?;
+    println!(
+        "debug_counter id: {}",
+        debug_counter.id().to_bech32(network.network_id())
+    );
+
+    let script_path = Path::new("../masm/scripts/debug_counter_script.masm");
+    let script_code = fs::read_to_string(script_path)?;
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_tx_script(&script_code)?;
+
+    let request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .build()?;
+    let tx_id = client
+        .submit_with_prover(debug_counter.id(), request)
+        .await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    client.sync_state().await?;
+    let account = client.get_account(debug_counter.id()).await?.unwrap();
+    println!(
+        "debug_counter storage: {:?}",
+        account.account().storage().get_item(0)?
+    );
+
+    Ok(())
+}
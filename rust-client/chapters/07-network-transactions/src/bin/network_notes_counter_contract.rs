@@ -2,90 +2,46 @@ use std::{fs, path::Path, sync::Arc};
 
 use miden_client::account::component::BasicWallet;
 use miden_client::{
-    address::NetworkId,
     auth::AuthSecretKey,
     builder::ClientBuilder,
     crypto::FeltRng,
-    keystore::FilesystemKeyStore,
     note::{
         Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteTag,
         NoteType,
     },
-    rpc::{Endpoint, GrpcClient},
-    store::TransactionFilter,
-    transaction::{OutputNote, TransactionId, TransactionRequestBuilder, TransactionStatus},
-    Client, ClientError, Felt, Word,
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Felt,
 };
 use miden_client_sqlite_store::ClientBuilderSqliteExt;
 use miden_lib::account::auth::{self, AuthRpoFalcon512};
 use miden_lib::transaction::TransactionKernel;
 use miden_objects::{
     account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
-    assembly::{Assembler, DefaultSourceManager, Library, LibraryPath, Module, ModuleKind},
+    assembly::Assembler,
 };
-use rand::{rngs::StdRng, RngCore};
+use rand::RngCore;
 use tokio::time::{sleep, Duration};
-
-/// Waits for a specific transaction to be committed.
-async fn wait_for_tx(
-    client: &mut Client<FilesystemKeyStore<StdRng>>,
-    tx_id: TransactionId,
-) -> Result<(), ClientError> {
-    loop {
-        client.sync_state().await?;
-
-        // Check transaction status
-        let txs = client
-            .get_transactions(TransactionFilter::Ids(vec![tx_id]))
-            .await?;
-        let tx_committed = if !txs.is_empty() {
-            matches!(txs[0].status, TransactionStatus::Committed { .. })
-        } else {
-            false
-        };
-
-        if tx_committed {
-            println!("✅ transaction {} committed", tx_id.to_hex());
-            break;
-        }
-
-        println!(
-            "Transaction {} not yet committed. Waiting...",
-            tx_id.to_hex()
-        );
-        sleep(Duration::from_secs(2)).await;
-    }
-    Ok(())
-}
-
-/// Creates a Miden library from the provided account code and library path.
-fn create_library(
-    account_code: String,
-    library_path: &str,
-) -> Result<Library, Box<dyn std::error::Error>> {
-    let assembler: Assembler = TransactionKernel::assembler().with_debug_mode(true);
-    let source_manager = Arc::new(DefaultSourceManager::default());
-    let module = Module::parser(ModuleKind::Library).parse_str(
-        LibraryPath::new(library_path)?,
-        account_code,
-        &source_manager,
-    )?;
-    let library = assembler.clone().assemble_library([module])?;
-    Ok(library)
-}
+use tutorial_utils::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{create_library, wait_for_tx},
+    storage::read_u64_slot,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize client
-    let endpoint = Endpoint::testnet();
+    let network = tutorial_utils::cli::network().await?;
+    let endpoint = network.endpoint().clone();
     let timeout_ms = 10_000;
     let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
 
-    // Initialize keystore
-    let keystore_path = std::path::PathBuf::from("./keystore");
-    let keystore = Arc::new(FilesystemKeyStore::<StdRng>::new(keystore_path).unwrap());
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("network_notes_counter_contract")?;
+    let keystore = context.keystore()?;
 
-    let store_path = std::path::PathBuf::from("./store.sqlite3");
+    let store_path = context.store_path();
 
     let mut client = ClientBuilder::new()
         .rpc(rpc_client)
@@ -126,7 +82,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!(
         "Alice's account ID: {:?}",
-        alice_account.id().to_bech32(NetworkId::Testnet)
+        alice_account.id().to_bech32(network.network_id())
     );
 
     // -------------------------------------------------------------------------
@@ -164,7 +120,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!(
         "contract id: {:?}",
-        counter_contract.id().to_bech32(NetworkId::Testnet)
+        counter_contract.id().to_bech32(network.network_id())
     );
 
     // -------------------------------------------------------------------------
@@ -177,7 +133,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let account_code = fs::read_to_string(Path::new("../masm/accounts/counter.masm")).unwrap();
     let library_path = "external_contract::counter_contract";
 
-    let library = create_library(account_code, library_path).unwrap();
+    let library = create_library(&account_code, library_path).unwrap();
 
     let tx_script = client
         .script_builder()
@@ -190,7 +146,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .unwrap();
 
     let tx_id = client
-        .submit_new_transaction(counter_contract.id(), tx_increment_request)
+        .submit_with_prover(counter_contract.id(), tx_increment_request)
         .await
         .unwrap();
 
@@ -212,7 +168,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let account_code = fs::read_to_string(Path::new("../masm/accounts/counter.masm")).unwrap();
 
     let library_path = "external_contract::counter_contract";
-    let library = create_library(account_code, library_path).unwrap();
+    let library = create_library(&account_code, library_path).unwrap();
 
     // Create and submit the network note that will increment the counter
     // Generate a random serial number for the note
@@ -247,7 +203,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .build()?;
 
     let note_tx_id = client
-        .submit_new_transaction(alice_account.id(), note_req)
+        .submit_with_prover(alice_account.id(), note_req)
         .await?;
 
     println!(
@@ -271,8 +227,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let new_account_state = client.get_account(counter_contract.id()).await.unwrap();
 
     if let Some(account) = new_account_state.as_ref() {
-        let count: Word = account.account().storage().get_item(0).unwrap().into();
-        let val = count.get(3).unwrap().as_int();
+        let val = read_u64_slot(account.account(), 0)?;
         assert_eq!(val, 2);
         println!("🔢 Final counter value: {}", val);
     }
@@ -0,0 +1,104 @@
+//! A template for testing a Miden contract, with the counter contract under test.
+//!
+//! Copy this crate next to your own contract, then point the functions below at its MASM. They
+//! are the only place that knows how the contract is built, so the tests never change when its
+//! code or storage layout does:
+//!
+//! - `tests/mock_chain.rs` runs the contract against the `MockChain` of `miden-testing`, an
+//!   in-memory chain which needs no node and proves blocks in milliseconds.
+//! - `tests/negative.rs` checks that the contract rejects what it must reject. A contract is only
+//!   as safe as the transactions it refuses, so give each of its rules a test of its own.
+//! - `tests/snapshots.rs` compares the procedure roots of the contract with the ones recorded in
+//!   `tests/snapshots`. Notes, scripts and other contracts call a procedure by its root, so a
+//!   change to a root breaks them even when every other test passes.
+//! - `tests/localnet.rs` deploys the contract and increments it on a `miden-node` started for
+//!   the test. It is ignored by default, since it needs the node installed, see
+//!   [`tutorial_utils::local_node`]:
+//!
+//! ```bash
+//! cargo test -p chapter-08-testing-contracts -- --include-ignored
+//! ```
+
+use std::{collections::BTreeMap, error::Error, fs, path::PathBuf};
+
+use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel, utils::ScriptBuilder};
+use miden_objects::{
+    account::{
+        Account, AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot,
+    },
+    assembly::Library,
+    transaction::TransactionScript,
+    Word,
+};
+
+/// Code of the contract, relative to the `masm` directory at the root of the repository.
+pub const CONTRACT_PATH: &str = "accounts/counter.masm";
+/// Script calling `increment_count`, relative to the `masm` directory.
+pub const INCREMENT_SCRIPT_PATH: &str = "scripts/counter_script.masm";
+/// Path under which scripts and notes import the contract.
+pub const LIBRARY_PATH: &str = "external_contract::counter_contract";
+/// Storage slot holding the count.
+pub const COUNT_SLOT: u8 = 0;
+
+/// Reads a MASM file relative to the `masm` directory at the root of the repository.
+pub fn masm(relative_path: &str) -> Result<String, Box<dyn Error>> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../../masm")
+        .join(relative_path);
+    fs::read_to_string(&path)
+        .map_err(|err| format!("failed to read {}: {err}", path.display()).into())
+}
+
+/// Compiles the contract into a component, with its storage initialized to a zero count.
+pub fn counter_component() -> Result<AccountComponent, Box<dyn Error>> {
+    Ok(AccountComponent::compile(
+        &masm(CONTRACT_PATH)?,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Value(Word::default())],
+    )?
+    .with_supports_all_types())
+}
+
+/// Returns a builder for a public counter contract. Mock chain tests finish it with
+/// `build_existing`, while a deployment calls `build` to get an account with its seed.
+pub fn counter_builder(init_seed: [u8; 32]) -> Result<AccountBuilder, Box<dyn Error>> {
+    Ok(AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(counter_component()?))
+}
+
+/// Compiles the contract into the library scripts link against to call it.
+pub fn counter_library() -> Result<Library, Box<dyn Error>> {
+    tutorial_utils::helpers::create_library(&masm(CONTRACT_PATH)?, LIBRARY_PATH)
+}
+
+/// Compiles the transaction script incrementing the count by one.
+pub fn increment_script() -> Result<TransactionScript, Box<dyn Error>> {
+    Ok(ScriptBuilder::new(true)
+        .with_dynamically_linked_library(&counter_library()?)?
+        .compile_tx_script(masm(INCREMENT_SCRIPT_PATH)?)?)
+}
+
+/// Returns the count stored in a counter contract.
+pub fn count(account: &Account) -> Result<u64, Box<dyn Error>> {
+    Ok(tutorial_utils::storage::read_u64_slot(account, COUNT_SLOT)?)
+}
+
+/// Returns the root of each procedure the contract exports, by name.
+pub fn procedure_roots(component: &AccountComponent) -> BTreeMap<String, Word> {
+    let library = component.library();
+    library
+        .exports()
+        .map(|export| {
+            let node_id = library.get_export_node_id(&export.name);
+            let root = library
+                .mast_forest()
+                .get_node_by_id(node_id)
+                .expect("exported procedures are in the library")
+                .digest();
+            (export.name.name.as_str().to_string(), root)
+        })
+        .collect()
+}
@@ -0,0 +1,40 @@
+use std::error::Error;
+
+use miden_objects::{
+    account::{Account, AccountId},
+    transaction::TransactionScript,
+};
+use miden_testing::MockChain;
+
+use chapter_08_testing_contracts::counter_builder;
+
+/// Builds a new counter contract, as already deployed on the mock chain.
+pub fn counter() -> Result<Account, Box<dyn Error>> {
+    Ok(counter_builder(rand::random())?.build_existing()?)
+}
+
+/// Builds a mock chain containing `accounts`.
+pub fn mock_chain(accounts: &[&Account]) -> Result<MockChain, Box<dyn Error>> {
+    let mut builder = MockChain::builder();
+    for account in accounts {
+        builder.add_account((*account).clone())?;
+    }
+    Ok(builder.build()?)
+}
+
+/// Runs `script` against `account_id` and includes the transaction in a new block.
+pub async fn run_script(
+    chain: &mut MockChain,
+    account_id: AccountId,
+    script: TransactionScript,
+) -> Result<(), Box<dyn Error>> {
+    let executed = chain
+        .build_tx_context(account_id, &[], &[])?
+        .tx_script(script)
+        .build()?
+        .execute()
+        .await?;
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(())
+}
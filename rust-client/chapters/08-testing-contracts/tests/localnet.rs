@@ -0,0 +1,53 @@
+//! The contract deploys and runs on a real node, with a real client and real proofs.
+
+use std::{error::Error, sync::Arc};
+
+use miden_client::{
+    builder::ClientBuilder, keystore::FilesystemKeyStore, rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rand::rngs::StdRng;
+use tutorial_utils::{
+    cli::SubmitWithProver, ephemeral::ClientDirs, helpers::wait_for_tx,
+    local_node::LocalNodeHarness,
+};
+
+use chapter_08_testing_contracts::{count, counter_builder, increment_script};
+
+#[tokio::test]
+#[ignore = "needs miden-node, see tutorial_utils::local_node"]
+async fn the_counter_increments_on_a_local_node() -> Result<(), Box<dyn Error>> {
+    let node = LocalNodeHarness::start().await?;
+    let rpc_client = Arc::new(GrpcClient::new(node.endpoint(), 10_000));
+
+    // Every node starts from an empty chain, so the client starts from an empty store and
+    // keystore of its own, even when MIDEN_TUTORIAL_STATE_DIR is set
+    let dirs = ClientDirs::new()?;
+    let keystore = Arc::new(FilesystemKeyStore::<StdRng>::new(dirs.keystore_path())?);
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(dirs.store_path())
+        .authenticator(keystore)
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+    client.sync_state().await?;
+
+    let counter = counter_builder(rand::random())?.build()?;
+    client.add_account(&counter, false).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .custom_script(increment_script()?)
+        .build()?;
+    let tx_id = client.submit_with_prover(counter.id(), request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let record = client
+        .get_account(counter.id())
+        .await?
+        .ok_or("the counter is not tracked by the client")?;
+    assert_eq!(count(record.account())?, 1);
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+//! The contract does what it should, on a chain held in memory.
+
+mod common;
+
+use std::error::Error;
+
+use chapter_08_testing_contracts::{count, increment_script};
+
+use common::{counter, mock_chain, run_script};
+
+#[tokio::test]
+async fn a_new_counter_starts_at_zero() -> Result<(), Box<dyn Error>> {
+    let counter = counter()?;
+    let chain = mock_chain(&[&counter])?;
+
+    assert_eq!(count(&chain.committed_account(counter.id())?)?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn each_increment_adds_one_to_the_count() -> Result<(), Box<dyn Error>> {
+    let counter = counter()?;
+    let mut chain = mock_chain(&[&counter])?;
+
+    for expected in 1..=3 {
+        run_script(&mut chain, counter.id(), increment_script()?).await?;
+        assert_eq!(count(&chain.committed_account(counter.id())?)?, expected);
+    }
+
+    Ok(())
+}
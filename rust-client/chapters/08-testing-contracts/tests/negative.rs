@@ -0,0 +1,71 @@
+//! The contract rejects what it must reject, and a rejected transaction changes nothing.
+
+mod common;
+
+use std::error::Error;
+
+use miden_lib::{account::auth::NoAuth, account::wallets::BasicWallet, utils::ScriptBuilder};
+use miden_objects::account::{AccountBuilder, AccountStorageMode, AccountType};
+
+use chapter_08_testing_contracts::{count, increment_script, COUNT_SLOT};
+
+use common::{counter, mock_chain, run_script};
+
+/// Builds a transaction script writing `value` to the count slot directly, instead of going
+/// through `increment_count`.
+fn direct_write(value: u64) -> String {
+    format!(
+        "
+use.miden::native_account
+
+begin
+    push.0.0.0.{value}
+    push.{COUNT_SLOT}
+    exec.native_account::set_item
+    dropw
+end
+"
+    )
+}
+
+#[tokio::test]
+async fn the_count_cannot_be_written_outside_the_contract() -> Result<(), Box<dyn Error>> {
+    let counter = counter()?;
+    let mut chain = mock_chain(&[&counter])?;
+
+    let script = ScriptBuilder::new(true).compile_tx_script(direct_write(99))?;
+    assert!(run_script(&mut chain, counter.id(), script).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn a_rejected_transaction_leaves_the_count_unchanged() -> Result<(), Box<dyn Error>> {
+    let counter = counter()?;
+    let mut chain = mock_chain(&[&counter])?;
+    run_script(&mut chain, counter.id(), increment_script()?).await?;
+
+    let script = ScriptBuilder::new(true).compile_tx_script(direct_write(99))?;
+    assert!(run_script(&mut chain, counter.id(), script).await.is_err());
+    assert_eq!(count(&chain.committed_account(counter.id())?)?, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn the_increment_script_fails_against_an_account_without_the_contract(
+) -> Result<(), Box<dyn Error>> {
+    let wallet = AccountBuilder::new(rand::random())
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(BasicWallet)
+        .build_existing()?;
+    let mut chain = mock_chain(&[&wallet])?;
+
+    assert!(run_script(&mut chain, wallet.id(), increment_script()?)
+        .await
+        .is_err());
+
+    Ok(())
+}
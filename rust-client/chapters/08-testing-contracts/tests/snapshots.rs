@@ -0,0 +1,63 @@
+//! The procedure roots of the contract only change on purpose.
+//!
+//! The roots are recorded in `tests/snapshots`, committed with the contract. The first run
+//! records them; commit the file it writes. After a deliberate change, record them again with
+//! `UPDATE_SNAPSHOTS=1`, then update every note, script and contract calling the changed
+//! procedures by root. On CI a missing snapshot fails the test instead, so that a snapshot which
+//! was never committed cannot pass unnoticed.
+
+use std::{env, error::Error, fs, path::PathBuf};
+
+use chapter_08_testing_contracts::{counter_component, procedure_roots};
+
+/// Environment variable recording the current roots instead of comparing them.
+const UPDATE_ENV: &str = "UPDATE_SNAPSHOTS";
+/// Environment variable set by CI, where the snapshot must already be committed.
+const CI_ENV: &str = "CI";
+
+/// Procedures the counter exports, all of which the snapshot covers.
+const PROCEDURES: [&str; 4] = [
+    "decrement_count",
+    "get_count",
+    "increment_count",
+    "reset_count",
+];
+
+#[test]
+fn procedure_roots_match_the_snapshot() -> Result<(), Box<dyn Error>> {
+    let roots_by_name = procedure_roots(&counter_component()?);
+    let names: Vec<&str> = roots_by_name.keys().map(String::as_str).collect();
+    assert_eq!(
+        names, PROCEDURES,
+        "the snapshot must cover every exported procedure"
+    );
+
+    let roots: String = roots_by_name
+        .iter()
+        .map(|(name, root)| format!("{name} {}\n", root.to_hex()))
+        .collect();
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots/counter.roots");
+    let first_run = !path.exists() && env::var_os(CI_ENV).is_none();
+    if first_run || env::var_os(UPDATE_ENV).is_some() {
+        fs::create_dir_all(path.parent().ok_or("the snapshot has no directory")?)?;
+        fs::write(&path, &roots)?;
+        println!("Recorded the procedure roots in {}", path.display());
+        return Ok(());
+    }
+
+    let snapshot = fs::read_to_string(&path).map_err(|err| {
+        format!(
+            "failed to read the snapshot {}: {err}. Run the test once outside CI and commit the \
+             file it records",
+            path.display()
+        )
+    })?;
+    assert_eq!(
+        snapshot, roots,
+        "the procedure roots of the contract changed: if this is intended, run the test again \
+         with {UPDATE_ENV}=1 and update every caller of the changed procedures"
+    );
+
+    Ok(())
+}
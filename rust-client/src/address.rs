@@ -0,0 +1,181 @@
+//! Parsing of user supplied account addresses.
+//!
+//! `AccountId::from_bech32` accepts addresses of any network and reports failures through a
+//! single opaque error. The helpers here check that the address belongs to the network the
+//! binary talks to and say what exactly is wrong with it.
+
+use std::fmt;
+
+use miden_client::{
+    account::AccountId,
+    address::{Address, AddressId, AddressInterface, NetworkId},
+};
+
+/// Why a string could not be used as an account address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    /// The string is not a valid bech32 address.
+    Malformed { input: String, reason: String },
+    /// The address is valid but encoded for another network.
+    WrongNetwork {
+        expected: NetworkId,
+        found: NetworkId,
+    },
+    /// The address advertises an interface the caller cannot send to.
+    WrongInterface {
+        expected: AddressInterface,
+        found: AddressInterface,
+    },
+    /// The address does not identify an account.
+    NotAnAccount,
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed { input, reason } => write!(f, "malformed address `{input}`: {reason}"),
+            Self::WrongNetwork { expected, found } => {
+                write!(f, "address is for network {found:?}, expected {expected:?}")
+            }
+            Self::WrongInterface { expected, found } => {
+                write!(f, "address has interface {found:?}, expected {expected:?}")
+            }
+            Self::NotAnAccount => write!(f, "address does not identify an account"),
+        }
+    }
+}
+
+impl std::error::Error for AddressError {}
+
+/// Decodes `input` as an address of any network.
+fn decode_any(input: &str) -> Result<(NetworkId, Address), AddressError> {
+    Address::decode(input.trim()).map_err(|err| AddressError::Malformed {
+        input: input.to_string(),
+        reason: err.to_string(),
+    })
+}
+
+/// Decodes `input` and checks that it is an address of `expected_network`.
+fn decode(input: &str, expected_network: &NetworkId) -> Result<Address, AddressError> {
+    let (network, address) = decode_any(input)?;
+    if &network != expected_network {
+        return Err(AddressError::WrongNetwork {
+            expected: expected_network.clone(),
+            found: network,
+        });
+    }
+    Ok(address)
+}
+
+/// Returns the network `input` is an address of, for inputs that carry their own network.
+pub fn address_network(input: &str) -> Result<NetworkId, AddressError> {
+    decode_any(input).map(|(network, _)| network)
+}
+
+/// Returns the id of the account behind a bech32 address of `expected_network`.
+///
+/// Any interface is accepted: use [`parse_wallet_address`] for addresses that will receive
+/// assets.
+pub fn parse_account_address(
+    input: &str,
+    expected_network: NetworkId,
+) -> Result<AccountId, AddressError> {
+    let address = decode(input, &expected_network)?;
+    match address.id() {
+        AddressId::AccountId(account_id) => Ok(account_id),
+        _ => Err(AddressError::NotAnAccount),
+    }
+}
+
+/// Like [`parse_account_address`], but also rejects addresses whose advertised interface is
+/// not a basic wallet, since P2ID notes sent to them could not be consumed.
+///
+/// Addresses without an interface are accepted.
+pub fn parse_wallet_address(
+    input: &str,
+    expected_network: NetworkId,
+) -> Result<AccountId, AddressError> {
+    let address = decode(input, &expected_network)?;
+    if let Some(interface) = address.interface() {
+        if interface != AddressInterface::BasicWallet {
+            return Err(AddressError::WrongInterface {
+                expected: AddressInterface::BasicWallet,
+                found: interface,
+            });
+        }
+    }
+    match address.id() {
+        AddressId::AccountId(account_id) => Ok(account_id),
+        _ => Err(AddressError::NotAnAccount),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_client::account::AccountId;
+    use miden_objects::account::{AccountIdVersion, AccountStorageMode, AccountType};
+
+    use super::*;
+
+    fn account_id() -> AccountId {
+        AccountId::dummy(
+            [7; 15],
+            AccountIdVersion::Version0,
+            AccountType::RegularAccountUpdatableCode,
+            AccountStorageMode::Public,
+        )
+    }
+
+    #[test]
+    fn parses_address_of_expected_network() {
+        let id = account_id();
+        let address = id.to_bech32(NetworkId::Testnet);
+
+        assert_eq!(parse_account_address(&address, NetworkId::Testnet), Ok(id));
+        assert_eq!(parse_wallet_address(&address, NetworkId::Testnet), Ok(id));
+    }
+
+    #[test]
+    fn ignores_surrounding_whitespace() {
+        let id = account_id();
+        let address = format!("  {}\n", id.to_bech32(NetworkId::Testnet));
+
+        assert_eq!(parse_account_address(&address, NetworkId::Testnet), Ok(id));
+    }
+
+    #[test]
+    fn rejects_address_of_other_network() {
+        let address = account_id().to_bech32(NetworkId::Mainnet);
+
+        assert_eq!(
+            parse_account_address(&address, NetworkId::Testnet),
+            Err(AddressError::WrongNetwork {
+                expected: NetworkId::Testnet,
+                found: NetworkId::Mainnet,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for input in ["", "counter", "0x303dd027d27adc0000012b07dbf1b4", "mtst1"] {
+            let result = parse_account_address(input, NetworkId::Testnet);
+            assert!(
+                matches!(result, Err(AddressError::Malformed { .. })),
+                "`{input}` was accepted: {result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_corrupted_checksum() {
+        let mut address = account_id().to_bech32(NetworkId::Testnet);
+        let last = address.pop().unwrap();
+        address.push(if last == 'q' { 'p' } else { 'q' });
+
+        assert!(matches!(
+            parse_account_address(&address, NetworkId::Testnet),
+            Err(AddressError::Malformed { .. })
+        ));
+    }
+}
@@ -0,0 +1,230 @@
+//! Planning and bookkeeping of token airdrops.
+//!
+//! An airdrop is a CSV of `address,amount` lines. [`parse_recipients`] validates every line up
+//! front, so that a typo is reported before anything is minted, and [`plan_batches`] splits
+//! the valid recipients into batches of at most a given number of notes, each distributed in a
+//! single transaction. [`Progress`] records on disk which batches are done, so an interrupted
+//! run resumes where it stopped instead of paying anybody twice.
+
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use miden_client::{account::AccountId, address::NetworkId};
+use serde::{Deserialize, Serialize};
+
+use crate::address::parse_wallet_address;
+
+/// A valid line of the airdrop CSV.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recipient {
+    /// Line of the CSV, starting at 1.
+    pub line: usize,
+    pub account_id: AccountId,
+    pub amount: u64,
+}
+
+/// A line of the airdrop CSV which is left out of the airdrop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejected {
+    pub line: usize,
+    pub content: String,
+    pub reason: String,
+}
+
+/// Parses the `address,amount` lines of `csv`, whose addresses must be for `network`.
+///
+/// Empty lines and a leading `address,amount` header are skipped. Lines with an invalid
+/// address, an amount that is not a positive integer, or an address already listed on an
+/// earlier line are rejected.
+pub fn parse_recipients(csv: &str, network: NetworkId) -> (Vec<Recipient>, Vec<Rejected>) {
+    let mut recipients = Vec::new();
+    let mut rejected = Vec::new();
+    let mut first_line_of: BTreeMap<AccountId, usize> = BTreeMap::new();
+
+    for (i, content) in csv.lines().enumerate() {
+        let line = i + 1;
+        let content = content.trim();
+        if content.is_empty() || (line == 1 && content.eq_ignore_ascii_case("address,amount")) {
+            continue;
+        }
+        let mut reject = |reason: String| {
+            rejected.push(Rejected {
+                line,
+                content: content.to_string(),
+                reason,
+            })
+        };
+
+        let Some((address, amount)) = content.split_once(',') else {
+            reject("expected `address,amount`".to_string());
+            continue;
+        };
+        let account_id = match parse_wallet_address(address, network.clone()) {
+            Ok(account_id) => account_id,
+            Err(err) => {
+                reject(err.to_string());
+                continue;
+            }
+        };
+        let amount = match amount.trim().parse::<u64>() {
+            Ok(amount) if amount > 0 => amount,
+            _ => {
+                reject(format!("`{}` is not a positive amount", amount.trim()));
+                continue;
+            }
+        };
+        if let Some(first_line) = first_line_of.get(&account_id) {
+            reject(format!("address already listed on line {first_line}"));
+            continue;
+        }
+
+        first_line_of.insert(account_id, line);
+        recipients.push(Recipient {
+            line,
+            account_id,
+            amount,
+        });
+    }
+
+    (recipients, rejected)
+}
+
+/// Splits `recipients` into batches of at most `max_notes` recipients, in CSV order.
+pub fn plan_batches(recipients: &[Recipient], max_notes: usize) -> Vec<Vec<Recipient>> {
+    assert!(max_notes > 0, "max_notes must be positive");
+    recipients
+        .chunks(max_notes)
+        .map(<[Recipient]>::to_vec)
+        .collect()
+}
+
+/// Returns the tokens `batch` distributes.
+pub fn batch_total(batch: &[Recipient]) -> u64 {
+    batch.iter().map(|recipient| recipient.amount).sum()
+}
+
+/// Where a batch of the airdrop stands.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchProgress {
+    /// Transaction sending the notes of the batch, recorded as soon as it is submitted.
+    pub distribution_tx: Option<String>,
+    /// The distribution transaction was committed.
+    pub committed: bool,
+}
+
+/// Progress of an airdrop, saved after every step.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    /// Number of recipients and tokens of the airdrop, to refuse resuming another airdrop.
+    pub recipients: usize,
+    pub total: u64,
+    pub batches: Vec<BatchProgress>,
+}
+
+impl Progress {
+    /// Returns the progress of a new airdrop of `batches`.
+    pub fn new(batches: &[Vec<Recipient>]) -> Self {
+        Self {
+            recipients: batches.iter().map(Vec::len).sum(),
+            total: batches.iter().map(|batch| batch_total(batch)).sum(),
+            batches: vec![BatchProgress::default(); batches.len()],
+        }
+    }
+
+    /// Loads the progress saved at `path`, or starts a new one for `batches` if there is none.
+    ///
+    /// Fails if the saved progress belongs to an airdrop with other recipients or batches.
+    pub fn load_or_new(path: impl AsRef<Path>, batches: &[Vec<Recipient>]) -> io::Result<Self> {
+        let fresh = Self::new(batches);
+        let saved: Self = match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(fresh),
+            Err(err) => return Err(err),
+        };
+
+        if (saved.recipients, saved.total, saved.batches.len())
+            != (fresh.recipients, fresh.total, fresh.batches.len())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the saved progress belongs to another airdrop",
+            ));
+        }
+        Ok(saved)
+    }
+
+    /// Writes the progress to `path` as pretty printed JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::{AccountIdVersion, AccountStorageMode, AccountType};
+
+    use super::*;
+
+    fn address(seed: u8) -> String {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            AccountType::RegularAccountUpdatableCode,
+            AccountStorageMode::Public,
+        )
+        .to_bech32(NetworkId::Testnet)
+    }
+
+    #[test]
+    fn invalid_lines_are_rejected_with_their_line_number() {
+        let csv = format!(
+            "address,amount\n{},10\nnot-an-address,5\n{},0\n{},7\n",
+            address(1),
+            address(2),
+            address(1)
+        );
+
+        let (recipients, rejected) = parse_recipients(&csv, NetworkId::Testnet);
+
+        assert_eq!(recipients.len(), 1);
+        assert_eq!(recipients[0].line, 2);
+        assert_eq!(recipients[0].amount, 10);
+        let rejected_lines: Vec<_> = rejected.iter().map(|line| line.line).collect();
+        assert_eq!(rejected_lines, [3, 4, 5]);
+        assert!(rejected[2].reason.contains("line 2"));
+    }
+
+    #[test]
+    fn batches_respect_the_note_limit() {
+        let csv: String = (1..=5)
+            .map(|seed| format!("{},{seed}\n", address(seed)))
+            .collect();
+        let (recipients, _) = parse_recipients(&csv, NetworkId::Testnet);
+
+        let batches = plan_batches(&recipients, 2);
+
+        let sizes: Vec<_> = batches.iter().map(Vec::len).collect();
+        assert_eq!(sizes, [2, 2, 1]);
+        assert_eq!(batch_total(&batches[0]), 3);
+    }
+
+    #[test]
+    fn progress_of_another_airdrop_is_not_resumed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("progress.json");
+        let (recipients, _) = parse_recipients(
+            &format!("{},10\n{},20\n", address(1), address(2)),
+            NetworkId::Testnet,
+        );
+
+        let mut progress = Progress::load_or_new(&path, &plan_batches(&recipients, 1)).unwrap();
+        progress.batches[0].distribution_tx = Some("0x01".to_string());
+        progress.save(&path).unwrap();
+
+        let resumed = Progress::load_or_new(&path, &plan_batches(&recipients, 1)).unwrap();
+        assert_eq!(resumed, progress);
+        assert!(Progress::load_or_new(&path, &plan_batches(&recipients[..1], 1)).is_err());
+    }
+}
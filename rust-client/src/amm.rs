@@ -0,0 +1,586 @@
+//! Off-chain side of the constant-product AMM (`masm/accounts/amm_pool.masm`) and of the swap
+//! offers market makers post next to it (`masm/notes/swap_offer_note.masm`).
+//!
+//! The pool holds the reserves of a pair of assets in its vault and accepts any swap which
+//! does not decrease the product of the two reserves. Takers compute the output of a swap with
+//! [`Pool::quote`] and request exactly that amount in an `amm_swap_note`.
+//!
+//! Liquidity providers deposit both assets at once and are credited with shares of the pool,
+//! which they later redeem for their part of both reserves, fees included. [`Pool::shares_for`]
+//! and [`Pool::withdrawal`] mirror the arithmetic of the contract.
+//!
+//! A [`SwapOffer`] is a limit order outside the pool: a note carrying the offered asset which
+//! any taker can fill by paying the requested asset to the maker, and which the maker can
+//! cancel by consuming it itself.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteExecutionMode, NoteId, NoteInputs, NoteMetadata,
+        NoteRecipient, NoteScript, NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, ClientError, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, note::WellKnownNote, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    asset::{Asset, FungibleAsset},
+    NoteError, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::{create_library, reply_note},
+    replacement::{self, Replacement},
+};
+
+/// Path under which the pool contract is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::amm_pool";
+/// Location of the pool contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/amm_pool.masm";
+/// Location of the note adding liquidity to the pool, relative to `rust-client`.
+pub const DEPOSIT_NOTE_PATH: &str = "../masm/notes/amm_deposit_note.masm";
+/// Location of the note swapping against the pool, relative to `rust-client`.
+pub const SWAP_NOTE_PATH: &str = "../masm/notes/amm_swap_note.masm";
+/// Location of the note redeeming liquidity shares, relative to `rust-client`.
+pub const WITHDRAW_NOTE_PATH: &str = "../masm/notes/amm_withdraw_note.masm";
+/// Location of the swap offer note, relative to `rust-client`.
+pub const OFFER_NOTE_PATH: &str = "../masm/notes/swap_offer_note.masm";
+
+/// Use case of the public tag under which swap offers are posted.
+pub const OFFER_USE_CASE: u16 = 1;
+
+const ASSET_A_SLOT: u8 = 0;
+const ASSET_B_SLOT: u8 = 1;
+const SHARES_SLOT: u8 = 3;
+const TOTAL_SHARES_SLOT: u8 = 4;
+
+/// Pair and reserves of a deployed pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pool {
+    pub id: AccountId,
+    pub asset_a: AccountId,
+    pub asset_b: AccountId,
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    /// Shares of all liquidity providers together.
+    pub total_shares: u64,
+}
+
+impl Pool {
+    /// Decodes the pair of a deployed pool account and reads its reserves from its vault.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let account_id = |slot| -> Result<AccountId, Box<dyn Error>> {
+            let value = storage.get_item(slot)?;
+            Ok(AccountId::try_from([value[3], value[2]])?)
+        };
+        let asset_a = account_id(ASSET_A_SLOT)?;
+        let asset_b = account_id(ASSET_B_SLOT)?;
+
+        Ok(Self {
+            id: account.id(),
+            asset_a,
+            asset_b,
+            reserve_a: account.vault().get_balance(asset_a)?,
+            reserve_b: account.vault().get_balance(asset_b)?,
+            total_shares: storage.get_item(TOTAL_SHARES_SLOT)?[3].as_int(),
+        })
+    }
+
+    /// Returns the reserves of `faucet_in` and of the other asset of the pair, and the faucet
+    /// of the other asset, or `None` if `faucet_in` is not part of the pair.
+    fn reserves(&self, faucet_in: AccountId) -> Option<(u64, u64, AccountId)> {
+        if faucet_in == self.asset_a {
+            Some((self.reserve_a, self.reserve_b, self.asset_b))
+        } else if faucet_in == self.asset_b {
+            Some((self.reserve_b, self.reserve_a, self.asset_a))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the most the pool pays for `asset_in`, following the constant product formula
+    /// `amount_out = reserve_out * amount_in / (reserve_in + amount_in)`, rounded down.
+    ///
+    /// Returns `None` if the asset is not part of the pair or the pool would pay nothing.
+    pub fn quote(&self, asset_in: FungibleAsset) -> Option<FungibleAsset> {
+        let (reserve_in, reserve_out, faucet_out) = self.reserves(asset_in.faucet_id())?;
+        let amount_in = u128::from(asset_in.amount());
+        let amount_out = u128::from(reserve_out) * amount_in / (u128::from(reserve_in) + amount_in);
+        let amount_out = u64::try_from(amount_out)
+            .ok()
+            .filter(|amount| *amount > 0)?;
+        FungibleAsset::new(faucet_out, amount_out).ok()
+    }
+
+    /// Returns the price of one token of `faucet_id` in tokens of the other asset of the pair,
+    /// or `None` if the asset is not part of the pair or the pool holds none of it.
+    pub fn price(&self, faucet_id: AccountId) -> Option<f64> {
+        let (reserve, other_reserve, _) = self.reserves(faucet_id)?;
+        (reserve > 0).then(|| other_reserve as f64 / reserve as f64)
+    }
+
+    /// Returns the shares minted by depositing `amount_a` of the first asset and `amount_b` of
+    /// the second, or `None` if the deposit mints none.
+    ///
+    /// The first deposit mints one share per token of the first asset. Later deposits mint
+    /// shares in proportion to the reserves, following the smaller side of the deposit.
+    pub fn shares_for(&self, amount_a: u64, amount_b: u64) -> Option<u64> {
+        let shares = if self.total_shares == 0 {
+            amount_a
+        } else {
+            let proportional = |amount: u64, reserve: u64| {
+                u128::from(amount) * u128::from(self.total_shares) / u128::from(reserve)
+            };
+            let shares =
+                proportional(amount_a, self.reserve_a).min(proportional(amount_b, self.reserve_b));
+            u64::try_from(shares).ok()?
+        };
+        (shares > 0).then_some(shares)
+    }
+
+    /// Returns the assets paid for redeeming `shares`, rounded down, or `None` if the pool
+    /// holds fewer shares or pays nothing of one of the assets.
+    pub fn withdrawal(&self, shares: u64) -> Option<(FungibleAsset, FungibleAsset)> {
+        if shares == 0 || shares > self.total_shares {
+            return None;
+        }
+        let part = |reserve: u64| {
+            let amount = u128::from(shares) * u128::from(reserve) / u128::from(self.total_shares);
+            u64::try_from(amount).ok().filter(|amount| *amount > 0)
+        };
+        Some((
+            FungibleAsset::new(self.asset_a, part(self.reserve_a)?).ok()?,
+            FungibleAsset::new(self.asset_b, part(self.reserve_b)?).ok()?,
+        ))
+    }
+
+    /// Returns the P2ID notes by which the pool pays `provider` for `shares`, in answer to a
+    /// withdraw note with serial number `request_serial_num`: the first asset at the serial
+    /// number plus one, the second at plus two.
+    pub fn withdrawal_notes(
+        &self,
+        provider: AccountId,
+        shares: u64,
+        request_serial_num: Word,
+    ) -> Result<[Note; 2], Box<dyn Error>> {
+        let (asset_a, asset_b) = self
+            .withdrawal(shares)
+            .ok_or("the shares cannot be redeemed in this pool")?;
+        let second_serial_num: Word = [
+            request_serial_num[0],
+            request_serial_num[1],
+            request_serial_num[2],
+            request_serial_num[3] + Felt::new(1),
+        ]
+        .into();
+        Ok([
+            reply_note(self.id, provider, asset_a, request_serial_num)?,
+            reply_note(self.id, provider, asset_b, second_serial_num)?,
+        ])
+    }
+}
+
+/// Returns the liquidity shares `provider` holds in the pool `account`.
+pub fn shares(account: &Account, provider: AccountId) -> Result<u64, Box<dyn Error>> {
+    let key: Word = [ZERO, ZERO, provider.suffix(), provider.prefix().as_felt()].into();
+    Ok(account.storage().get_map_item(SHARES_SLOT, key)?[3].as_int())
+}
+
+/// Deploys a public pool trading `asset_a` against `asset_b`, with empty reserves.
+pub async fn create_pool(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    asset_a: AccountId,
+    asset_b: AccountId,
+) -> Result<Account, Box<dyn Error>> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let account_word = |account_id: AccountId| -> Word {
+        [
+            ZERO,
+            ZERO,
+            account_id.suffix(),
+            account_id.prefix().as_felt(),
+        ]
+        .into()
+    };
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(account_word(asset_a)),
+            StorageSlot::Value(account_word(asset_b)),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the pool contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `sender` to the pool `pool_id`, running `script` with `inputs`.
+pub fn pool_note(
+    sender: AccountId,
+    pool_id: AccountId,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(pool_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+/// Has `sender` create `note`, then has the pool consume it right away, creating `replies`.
+///
+/// The pool is a `NoAuth` account, so the client of the sender can execute its transaction as
+/// long as it tracks the pool. Returns the id of the pool transaction.
+async fn submit_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    pool: &Pool,
+    note: Note,
+    replies: Vec<Note>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    client.submit_with_prover(sender, request).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .expected_output_recipients(
+            replies
+                .iter()
+                .map(|reply| reply.recipient().clone())
+                .collect(),
+        )
+        .build()?;
+    Ok(client.submit_with_prover(pool.id, request).await?)
+}
+
+/// Deposits `asset_a` and `asset_b` from the vault of `provider` into the reserves of `pool`,
+/// for the shares given by [`Pool::shares_for`]. `script` is the compiled
+/// [`DEPOSIT_NOTE_PATH`].
+pub async fn add_liquidity(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    provider: AccountId,
+    pool: &Pool,
+    asset_a: FungibleAsset,
+    asset_b: FungibleAsset,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let assets = vec![asset_a.into(), asset_b.into()];
+    let note = pool_note(provider, pool.id, vec![], assets, script, serial_num)?;
+    submit_note(client, provider, pool, note, vec![]).await
+}
+
+/// Redeems `shares` of `provider` for the assets given by [`Pool::withdrawal`], which `pool`
+/// sends back with the notes of [`Pool::withdrawal_notes`]. `script` is the compiled
+/// [`WITHDRAW_NOTE_PATH`].
+pub async fn remove_liquidity(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    provider: AccountId,
+    pool: &Pool,
+    shares: u64,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let outputs = pool.withdrawal_notes(provider, shares, serial_num)?;
+    let inputs = vec![Felt::new(shares), NoteTag::from_account_id(provider).into()];
+    let note = pool_note(provider, pool.id, inputs, vec![], script, serial_num)?;
+    submit_note(client, provider, pool, note, outputs.to_vec()).await
+}
+
+/// Swaps `asset_in` from the vault of `trader` for the amount quoted by `pool`, which sends it
+/// back with a P2ID note. `script` is the compiled [`SWAP_NOTE_PATH`].
+pub async fn swap(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    trader: AccountId,
+    pool: &Pool,
+    asset_in: FungibleAsset,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let asset_out = pool
+        .quote(asset_in)
+        .ok_or("the pool does not pay anything for this asset")?;
+
+    let serial_num = client.rng().draw_word();
+    let inputs = vec![
+        Felt::new(asset_out.amount()),
+        NoteTag::from_account_id(trader).into(),
+    ];
+    let note = pool_note(
+        trader,
+        pool.id,
+        inputs,
+        vec![asset_in.into()],
+        script,
+        serial_num,
+    )?;
+    let output = reply_note(pool.id, trader, asset_out, serial_num)?;
+    submit_note(client, trader, pool, note, vec![output]).await
+}
+
+/// A swap offer posted by a market maker: `offered` for `requested`, filled in full.
+#[derive(Debug, Clone)]
+pub struct SwapOffer {
+    pub note: Note,
+    pub offered: FungibleAsset,
+    pub requested: FungibleAsset,
+}
+
+impl SwapOffer {
+    /// Builds the offer note of `maker`, tagged for the public offer use case so that takers
+    /// can discover it. `script` is the compiled [`OFFER_NOTE_PATH`].
+    pub fn new(
+        maker: AccountId,
+        offered: FungibleAsset,
+        requested: FungibleAsset,
+        script: NoteScript,
+        serial_num: Word,
+    ) -> Result<Self, NoteError> {
+        let mut inputs = WellKnownNote::P2ID.script_root().to_vec();
+        inputs.extend([
+            Felt::new(requested.amount()),
+            requested.faucet_id().suffix(),
+            requested.faucet_id().prefix().as_felt(),
+            NoteTag::from_account_id(maker).into(),
+        ]);
+        let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+        let tag = NoteTag::for_public_use_case(OFFER_USE_CASE, 0, NoteExecutionMode::Local)?;
+        let metadata = NoteMetadata::new(
+            maker,
+            NoteType::Public,
+            tag,
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )?;
+        let note = Note::new(NoteAssets::new(vec![offered.into()])?, metadata, recipient);
+
+        Ok(Self {
+            note,
+            offered,
+            requested,
+        })
+    }
+
+    /// Returns the account which posted the offer.
+    pub fn maker(&self) -> AccountId {
+        self.note.metadata().sender()
+    }
+
+    /// Returns the P2ID note by which `taker` pays the maker when filling the offer.
+    pub fn payment(&self, taker: AccountId) -> Result<Note, NoteError> {
+        reply_note(taker, self.maker(), self.requested, self.note.serial_num())
+    }
+
+    /// Returns the id of the payment the maker receives once the offer is filled. The id of a
+    /// note does not depend on its sender, so it is known before anybody takes the offer.
+    pub fn payment_id(&self) -> Result<NoteId, NoteError> {
+        Ok(self.payment(self.maker())?.id())
+    }
+}
+
+/// Posts `offers` from the vault of their maker, in a single transaction.
+pub async fn post_offers(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    maker: AccountId,
+    offers: &[SwapOffer],
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(
+            offers
+                .iter()
+                .map(|offer| OutputNote::Full(offer.note.clone()))
+                .collect(),
+        )
+        .build()?;
+    Ok(client.submit_with_prover(maker, request).await?)
+}
+
+/// Fills `offer` from the vault of `taker`.
+pub async fn fill_offer(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    taker: AccountId,
+    offer: &SwapOffer,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(offer.note.clone(), None)])
+        .expected_output_recipients(vec![offer.payment(taker)?.recipient().clone()])
+        .build()?;
+    Ok(client.submit_with_prover(taker, request).await?)
+}
+
+/// Cancels `offer`: its maker consumes it and gets the offered asset back. Fails if somebody
+/// filled the offer first.
+pub async fn cancel_offer(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    offer: &SwapOffer,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(offer.note.clone(), None)])
+        .build()?;
+    Ok(client.submit_with_prover(offer.maker(), request).await?)
+}
+
+/// Replaces `offer` by `replacement` in one transaction of their maker, unless somebody filled
+/// `offer` first. See [`replacement::replace_note`].
+pub async fn replace_offer(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    offer: &SwapOffer,
+    replacement: &SwapOffer,
+) -> Result<Replacement, ClientError> {
+    replacement::replace_note(
+        client,
+        offer.maker(),
+        &offer.note,
+        vec![replacement.note.clone()],
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    fn faucet(seed: u8) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            AccountType::FungibleFaucet,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn pool(reserve_a: u64, reserve_b: u64) -> Pool {
+        Pool {
+            id: AccountId::dummy(
+                [1; 15],
+                AccountIdVersion::Version0,
+                AccountType::RegularAccountImmutableCode,
+                AccountStorageMode::Public,
+            ),
+            asset_a: faucet(2),
+            asset_b: faucet(3),
+            reserve_a,
+            reserve_b,
+            total_shares: 0,
+        }
+    }
+
+    #[test]
+    fn quote_keeps_the_product_of_the_reserves() {
+        let pool = pool(1_000, 100_000);
+        let asset_in = FungibleAsset::new(pool.asset_b, 10_000).unwrap();
+        let asset_out = pool.quote(asset_in).unwrap();
+
+        // 1_000 * 10_000 / 110_000 = 90.9, rounded down
+        assert_eq!(asset_out.faucet_id(), pool.asset_a);
+        assert_eq!(asset_out.amount(), 90);
+        assert!((1_000 - 90) * 110_000 >= 1_000 * 100_000);
+        assert!((1_000 - 91) * 110_000 < 1_000 * 100_000);
+    }
+
+    #[test]
+    fn quote_rejects_assets_outside_the_pair_and_empty_outputs() {
+        let pool = pool(1_000, 100_000);
+        let unknown = FungibleAsset::new(faucet(4), 10).unwrap();
+        let dust = FungibleAsset::new(pool.asset_b, 50).unwrap();
+
+        assert_eq!(pool.quote(unknown), None);
+        assert_eq!(pool.quote(dust), None);
+    }
+
+    #[test]
+    fn price_is_the_ratio_of_the_reserves() {
+        let pool = pool(1_000, 100_000);
+
+        assert_eq!(pool.price(pool.asset_a), Some(100.0));
+        assert_eq!(pool.price(pool.asset_b), Some(0.01));
+        assert_eq!(pool.price(faucet(4)), None);
+        assert_eq!(
+            Pool {
+                reserve_a: 0,
+                ..pool
+            }
+            .price(pool.asset_a),
+            None
+        );
+    }
+
+    #[test]
+    fn shares_follow_the_smaller_side_of_a_deposit() {
+        let empty = pool(0, 0);
+        assert_eq!(empty.shares_for(1_000, 100_000), Some(1_000));
+        assert_eq!(empty.shares_for(0, 100_000), None);
+
+        let pool = Pool {
+            total_shares: 1_000,
+            ..pool(1_000, 100_000)
+        };
+        assert_eq!(pool.shares_for(100, 10_000), Some(100));
+        // Twice as much USD as needed still mints the shares of the ETH
+        assert_eq!(pool.shares_for(100, 20_000), Some(100));
+        assert_eq!(pool.shares_for(100, 5_000), Some(50));
+    }
+
+    #[test]
+    fn withdrawal_pays_a_share_of_both_reserves() {
+        let pool = Pool {
+            total_shares: 1_000,
+            ..pool(1_100, 91_000)
+        };
+        let (eth, usd) = pool.withdrawal(250).unwrap();
+
+        assert_eq!(eth.faucet_id(), pool.asset_a);
+        assert_eq!(eth.amount(), 275);
+        assert_eq!(usd.amount(), 22_750);
+        assert_eq!(pool.withdrawal(0), None);
+        assert_eq!(pool.withdrawal(1_001), None);
+    }
+}
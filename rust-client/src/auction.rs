@@ -0,0 +1,523 @@
+//! Off-chain side of the English auction (`masm/accounts/auction.masm`).
+//!
+//! Every auction is its own contract account selling one lot for tokens of one faucet. The
+//! seller opens it by sending the lot with an `auction_list_note`. Bidders send their bid with
+//! an `auction_bid_note` before the end block: the contract keeps every accepted bid in a
+//! storage map and each bid has to beat the previous one, so the last bid is the highest. The
+//! tokens of a bid stay in the vault of the auction until the bid is outbid, at which point
+//! they are refunded to the bidder with a P2ID note. From the end block on, anybody can close
+//! the auction with an `auction_close_note`: the lot goes to the highest bidder and the highest
+//! bid to the seller, or the lot back to the seller if nobody bid.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, note::WellKnownNote, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    asset::{Asset, FungibleAsset},
+    block::BlockNumber,
+    NoteError, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{cli::SubmitWithProver, helpers::create_library};
+
+/// Path under which the auction contract is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::auction";
+/// Location of the auction contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/auction.masm";
+/// Location of the note listing the lot, relative to `rust-client`.
+pub const LIST_NOTE_PATH: &str = "../masm/notes/auction_list_note.masm";
+/// Location of the note placing a bid, relative to `rust-client`.
+pub const BID_NOTE_PATH: &str = "../masm/notes/auction_bid_note.masm";
+/// Location of the note closing the auction, relative to `rust-client`.
+pub const CLOSE_NOTE_PATH: &str = "../masm/notes/auction_close_note.masm";
+
+const SELLER_SLOT: u8 = 0;
+const CURRENCY_SLOT: u8 = 1;
+const RESERVE_SLOT: u8 = 2;
+const END_SLOT: u8 = 3;
+const LOT_SLOT: u8 = 4;
+const NUM_BIDS_SLOT: u8 = 5;
+const BIDS_SLOT: u8 = 6;
+const STATE_SLOT: u8 = 7;
+
+/// Where an auction stands, as stored by the contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuctionState {
+    /// The seller has not listed the lot yet.
+    AwaitingLot,
+    /// The lot is listed and bids are accepted until the end block.
+    Open,
+    /// The lot and the highest bid were paid out.
+    Closed,
+}
+
+impl TryFrom<u64> for AuctionState {
+    type Error = String;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::AwaitingLot),
+            1 => Ok(Self::Open),
+            2 => Ok(Self::Closed),
+            _ => Err(format!("unknown auction state {value}")),
+        }
+    }
+}
+
+/// A bid accepted by the auction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bid {
+    pub bidder: AccountId,
+    pub amount: u64,
+    /// Tag of the P2ID note refunding the bid once it is outbid.
+    pub refund_tag: NoteTag,
+}
+
+/// Terms, lot and bids of a deployed auction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Auction {
+    pub id: AccountId,
+    pub seller: AccountId,
+    /// Faucet of the tokens bids are paid in.
+    pub currency: AccountId,
+    /// Smallest amount the first bid can offer.
+    pub reserve: u64,
+    /// Block from which no bid is accepted and the auction can be closed.
+    pub end: BlockNumber,
+    pub lot: Option<Asset>,
+    pub state: AuctionState,
+    /// Accepted bids, from the first to the highest.
+    pub bids: Vec<Bid>,
+}
+
+impl Auction {
+    /// Decodes the terms, lot and bids of a deployed auction account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let account_id = |slot| -> Result<AccountId, Box<dyn Error>> {
+            let value = storage.get_item(slot)?;
+            Ok(AccountId::try_from([value[3], value[2]])?)
+        };
+
+        let lot = match storage.get_item(LOT_SLOT)? {
+            word if word == Word::default() => None,
+            word => Some(Asset::try_from(word)?),
+        };
+
+        let num_bids = storage.get_item(NUM_BIDS_SLOT)?[3].as_int();
+        let mut bids = Vec::new();
+        for index in 1..=num_bids {
+            let key: Word = [ZERO, ZERO, ZERO, Felt::new(index)].into();
+            let value = storage.get_map_item(BIDS_SLOT, key)?;
+            bids.push(Bid {
+                bidder: AccountId::try_from([value[3], value[2]])?,
+                amount: value[1].as_int(),
+                refund_tag: NoteTag::from(u32::try_from(value[0].as_int())?),
+            });
+        }
+
+        Ok(Self {
+            id: account.id(),
+            seller: account_id(SELLER_SLOT)?,
+            currency: account_id(CURRENCY_SLOT)?,
+            reserve: storage.get_item(RESERVE_SLOT)?[3].as_int(),
+            end: BlockNumber::from(u32::try_from(storage.get_item(END_SLOT)?[3].as_int())?),
+            lot,
+            state: AuctionState::try_from(storage.get_item(STATE_SLOT)?[3].as_int())?,
+            bids,
+        })
+    }
+
+    /// Returns the highest bid, if any.
+    pub fn highest_bid(&self) -> Option<&Bid> {
+        self.bids.last()
+    }
+
+    /// Returns the smallest amount the next bid can offer: the reserve for the first bid, one
+    /// more than the highest bid afterwards.
+    pub fn min_bid(&self) -> u64 {
+        self.highest_bid()
+            .map_or(self.reserve, |highest| highest.amount + 1)
+    }
+
+    /// Returns whether a bid executed against `block_num` can be accepted.
+    pub fn accepts_bids_at(&self, block_num: BlockNumber) -> bool {
+        self.state == AuctionState::Open && block_num < self.end
+    }
+
+    /// Returns whether the auction can be closed in a transaction executed against
+    /// `block_num`.
+    pub fn can_close_at(&self, block_num: BlockNumber) -> bool {
+        self.state == AuctionState::Open && block_num >= self.end
+    }
+
+    /// Returns the P2ID note by which the auction pays `asset` to `receiver`.
+    ///
+    /// The contract derives the serial number from the id of the auction and `index`, the
+    /// index of the bid paid out or 0 for the lot, which makes the note predictable: the client
+    /// needs its recipient to accept it as an output of the transaction.
+    pub fn payout_note(
+        &self,
+        index: u64,
+        receiver: AccountId,
+        asset: Asset,
+    ) -> Result<Note, NoteError> {
+        let serial_num = [
+            Felt::new(index),
+            ZERO,
+            self.id.suffix(),
+            self.id.prefix().as_felt(),
+        ]
+        .into();
+        let recipient = NoteRecipient::new(
+            serial_num,
+            WellKnownNote::P2ID.script(),
+            NoteInputs::new(vec![receiver.suffix(), receiver.prefix().as_felt()])?,
+        );
+        let metadata = NoteMetadata::new(
+            self.id,
+            NoteType::Public,
+            NoteTag::from_account_id(receiver),
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )?;
+        Ok(Note::new(
+            NoteAssets::new(vec![asset])?,
+            metadata,
+            recipient,
+        ))
+    }
+
+    /// Returns the notes the auction creates when it accepts a new bid: the refund of the
+    /// highest bid, if there is one.
+    pub fn payouts_for_bid(&self) -> Result<Vec<Note>, Box<dyn Error>> {
+        let Some(highest) = self.highest_bid() else {
+            return Ok(vec![]);
+        };
+        let refund = FungibleAsset::new(self.currency, highest.amount)?;
+        Ok(vec![self.payout_note(
+            self.bids.len() as u64,
+            highest.bidder,
+            refund.into(),
+        )?])
+    }
+
+    /// Returns the notes the auction creates when it is closed: the lot to the highest bidder
+    /// and the highest bid to the seller, or the lot back to the seller without any bid.
+    pub fn payouts_for_close(&self) -> Result<Vec<Note>, Box<dyn Error>> {
+        let lot = self.lot.ok_or("the lot is not listed")?;
+        let Some(highest) = self.highest_bid() else {
+            return Ok(vec![self.payout_note(0, self.seller, lot)?]);
+        };
+        let proceeds = FungibleAsset::new(self.currency, highest.amount)?;
+        Ok(vec![
+            self.payout_note(0, highest.bidder, lot)?,
+            self.payout_note(self.bids.len() as u64, self.seller, proceeds.into())?,
+        ])
+    }
+}
+
+/// Deploys a public auction in which `seller` sells a lot for tokens of `currency`. Bids
+/// start at `reserve` and are accepted until block `end`.
+pub async fn create_auction(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    seller: AccountId,
+    currency: AccountId,
+    reserve: u64,
+    end: BlockNumber,
+) -> Result<Account, Box<dyn Error>> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let seller_tag = NoteTag::from_account_id(seller);
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(
+                [
+                    ZERO,
+                    seller_tag.into(),
+                    seller.suffix(),
+                    seller.prefix().as_felt(),
+                ]
+                .into(),
+            ),
+            StorageSlot::Value([ZERO, ZERO, currency.suffix(), currency.prefix().as_felt()].into()),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::new(reserve)].into()),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::from(end.as_u32())].into()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the auction contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `sender` to the auction `auction_id`.
+pub fn auction_note(
+    sender: AccountId,
+    auction_id: AccountId,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(auction_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+/// Builds the note by which `bidder` bids `amount` tokens on `auction`. `script` is the
+/// compiled [`BID_NOTE_PATH`].
+pub fn bid_note(
+    bidder: AccountId,
+    auction: &Auction,
+    amount: u64,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, Box<dyn Error>> {
+    let bid = FungibleAsset::new(auction.currency, amount)?;
+    let inputs = vec![NoteTag::from_account_id(bidder).into()];
+    Ok(auction_note(
+        bidder,
+        auction.id,
+        inputs,
+        vec![bid.into()],
+        script,
+        serial_num,
+    )?)
+}
+
+/// Has `sender` create `note` for the auction.
+pub async fn send_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    note: &Note,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    Ok(client.submit_with_prover(sender, request).await?)
+}
+
+/// Has `auction` consume `note`, creating `payouts`.
+///
+/// The auction is a `NoAuth` account, so any client tracking it can execute its transactions.
+pub async fn consume_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    auction: &Auction,
+    note: &Note,
+    payouts: Vec<Note>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note.clone(), None)])
+        .expected_output_recipients(
+            payouts
+                .iter()
+                .map(|payout| payout.recipient().clone())
+                .collect(),
+        )
+        .build()?;
+    Ok(client.submit_with_prover(auction.id, request).await?)
+}
+
+/// Has the seller list `lot` in `auction`, which opens it. `script` is the compiled
+/// [`LIST_NOTE_PATH`].
+pub async fn list(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    auction: &Auction,
+    lot: Asset,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = auction_note(
+        auction.seller,
+        auction.id,
+        vec![],
+        vec![lot],
+        script,
+        serial_num,
+    )?;
+    send_note(client, auction.seller, &note).await?;
+    consume_note(client, auction, &note, vec![]).await
+}
+
+/// Has `bidder` bid `amount` tokens on `auction`, which refunds the bid it outbids. `script` is
+/// the compiled [`BID_NOTE_PATH`].
+///
+/// Fails without sending anything if the auction no longer accepts bids at the current sync
+/// height or `amount` is below [`Auction::min_bid`].
+pub async fn bid(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    bidder: AccountId,
+    auction: &Auction,
+    amount: u64,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    if !auction.accepts_bids_at(block_num) {
+        return Err(format!("the auction accepts no bid at block {block_num}").into());
+    }
+    if amount < auction.min_bid() {
+        return Err(format!("bids start at {}", auction.min_bid()).into());
+    }
+
+    let serial_num = client.rng().draw_word();
+    let note = bid_note(bidder, auction, amount, script, serial_num)?;
+    send_note(client, bidder, &note).await?;
+    consume_note(client, auction, &note, auction.payouts_for_bid()?).await
+}
+
+/// Has `closer` close `auction`, which pays out the lot and the highest bid. `script` is the
+/// compiled [`CLOSE_NOTE_PATH`].
+///
+/// Fails without sending anything if the auction cannot be closed at the current sync height.
+pub async fn close(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    closer: AccountId,
+    auction: &Auction,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    if !auction.can_close_at(block_num) {
+        return Err(format!("the auction cannot be closed at block {block_num}").into());
+    }
+
+    let serial_num = client.rng().draw_word();
+    let note = auction_note(closer, auction.id, vec![], vec![], script, serial_num)?;
+    send_note(client, closer, &note).await?;
+    consume_note(client, auction, &note, auction.payouts_for_close()?).await
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn bidder(seed: u8) -> AccountId {
+        account(seed, AccountType::RegularAccountUpdatableCode)
+    }
+
+    fn auction(bids: &[(u8, u64)]) -> Auction {
+        let currency = account(3, AccountType::FungibleFaucet);
+        let lot_faucet = account(4, AccountType::FungibleFaucet);
+        Auction {
+            id: account(1, AccountType::RegularAccountImmutableCode),
+            seller: bidder(2),
+            currency,
+            reserve: 50,
+            end: BlockNumber::from(100),
+            lot: Some(FungibleAsset::new(lot_faucet, 1).unwrap().into()),
+            state: AuctionState::Open,
+            bids: bids
+                .iter()
+                .map(|&(seed, amount)| Bid {
+                    bidder: bidder(seed),
+                    amount,
+                    refund_tag: NoteTag::from_account_id(bidder(seed)),
+                })
+                .collect(),
+        }
+    }
+
+    fn receiver(note: &Note) -> AccountId {
+        let inputs = note.recipient().inputs().values();
+        AccountId::try_from([inputs[1], inputs[0]]).unwrap()
+    }
+
+    #[test]
+    fn first_bid_starts_at_the_reserve_then_has_to_beat_the_highest() {
+        let unbid = auction(&[]);
+        assert_eq!(unbid.min_bid(), 50);
+        assert!(unbid.payouts_for_bid().unwrap().is_empty());
+
+        let bid = auction(&[(10, 50), (11, 70)]);
+        assert_eq!(bid.min_bid(), 71);
+        let refunds = bid.payouts_for_bid().unwrap();
+        assert_eq!(refunds.len(), 1);
+        assert_eq!(receiver(&refunds[0]), bidder(11));
+    }
+
+    #[test]
+    fn bids_are_accepted_until_the_end_block() {
+        let auction = auction(&[]);
+
+        assert!(auction.accepts_bids_at(99.into()));
+        assert!(!auction.accepts_bids_at(100.into()));
+        assert!(!auction.can_close_at(99.into()));
+        assert!(auction.can_close_at(100.into()));
+    }
+
+    #[test]
+    fn closing_pays_the_lot_to_the_highest_bidder_and_the_bid_to_the_seller() {
+        let unsold = auction(&[]);
+        let payouts = unsold.payouts_for_close().unwrap();
+        assert_eq!(payouts.len(), 1);
+        assert_eq!(receiver(&payouts[0]), unsold.seller);
+
+        let sold = auction(&[(10, 50), (11, 70)]);
+        let payouts = sold.payouts_for_close().unwrap();
+        assert_eq!(receiver(&payouts[0]), bidder(11));
+        assert_eq!(receiver(&payouts[1]), sold.seller);
+        let proceeds = Asset::from(FungibleAsset::new(sold.currency, 70).unwrap());
+        assert!(payouts[1].assets().iter().any(|asset| *asset == proceeds));
+    }
+}
@@ -0,0 +1,727 @@
+//! Off-chain side of the battleship game (`masm/accounts/battleship.masm`).
+//!
+//! A battleship board has to stay secret until the end of the game, which a public contract
+//! cannot offer. Each player therefore keeps its [`Board`] in a private account, as a storage map
+//! from every cell to `[salt_0, salt_1, salt_2, is_ship]`, and joins the public game with a
+//! `battleship_join_note` carrying only the root of that map and the stake of the game. The salts
+//! keep anybody from finding the board by hashing every possible one.
+//!
+//! Players then take turns: the shooter sends a `battleship_shot_note` naming a cell, and the
+//! player shot at answers with a `battleship_answer_note` claiming a hit or a miss. Claims are
+//! recorded without proof, so that playing reveals nothing but the claims. The first player to
+//! hit every ship cell of the other board is paid both stakes.
+//!
+//! A player can lie about a shot, so every claim can be challenged with a
+//! `battleship_challenge_note`. The challenged player has to open the cell within the challenge
+//! period with a `battleship_open_note` carrying the value of the cell, and the Merkle path from
+//! it to the board root in the advice inputs of the transaction: [`Board::open`] builds both from
+//! the storage map of the board. The game checks the path against the root the player joined
+//! with, so only the value committed to opens the cell, and only if it matches the claim. A
+//! player which cannot open the cell is slashed: once the period is over, a
+//! `battleship_slash_note` pays both stakes to the challenger.
+//!
+//! The game does not check that a board covers [`SHIP_CELLS`] cells, as that would reveal it: a
+//! player covering fewer never loses. A complete version has the boards opened in full once the
+//! game is over, with the same proofs.
+
+use std::{array, error::Error, fmt, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, note::WellKnownNote, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    asset::{Asset, FungibleAsset},
+    block::BlockNumber,
+    crypto::merkle::{InnerNodeInfo, MerklePath},
+    NoteError, ONE, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::{create_library, reply_note},
+    storage::{felt_key, read_account_id_slot, read_u64_slot},
+};
+
+/// Path under which the game contract is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::battleship";
+/// Location of the game contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/battleship.masm";
+/// Location of the component of the private account holding a board, relative to `rust-client`.
+pub const BOARD_PATH: &str = "../masm/accounts/battleship_board.masm";
+/// Location of the note joining a game, relative to `rust-client`.
+pub const JOIN_NOTE_PATH: &str = "../masm/notes/battleship_join_note.masm";
+/// Location of the note shooting at a cell, relative to `rust-client`.
+pub const SHOT_NOTE_PATH: &str = "../masm/notes/battleship_shot_note.masm";
+/// Location of the note answering a shot, relative to `rust-client`.
+pub const ANSWER_NOTE_PATH: &str = "../masm/notes/battleship_answer_note.masm";
+/// Location of the note challenging a claim, relative to `rust-client`.
+pub const CHALLENGE_NOTE_PATH: &str = "../masm/notes/battleship_challenge_note.masm";
+/// Location of the note opening a challenged cell, relative to `rust-client`.
+pub const OPEN_NOTE_PATH: &str = "../masm/notes/battleship_open_note.masm";
+/// Location of the note slashing a player which left a challenge unanswered, relative to
+/// `rust-client`.
+pub const SLASH_NOTE_PATH: &str = "../masm/notes/battleship_slash_note.masm";
+
+/// Number of rows and columns of a board.
+pub const GRID_SIZE: usize = 4;
+/// Number of cells of a board, numbered row by row.
+pub const NUM_CELLS: usize = GRID_SIZE * GRID_SIZE;
+/// Number of cells every board covers with ships.
+pub const SHIP_CELLS: usize = 3;
+
+const BOARD_SLOT: u8 = 0;
+
+const PLAYER1_SLOT: u8 = 0;
+const PLAYER2_SLOT: u8 = 1;
+const STAKE_SLOT: u8 = 2;
+const CHALLENGE_PERIOD_SLOT: u8 = 3;
+const BOARDS_SLOT: u8 = 4;
+const TURN_SLOT: u8 = 7;
+const PENDING_SHOT_SLOT: u8 = 8;
+const CLAIMS_SLOT: u8 = 9;
+const HITS1_SLOT: u8 = 10;
+const HITS2_SLOT: u8 = 11;
+const CHALLENGE_SLOT: u8 = 12;
+const WINNER_SLOT: u8 = 13;
+
+/// Key of `cell` in the storage map holding a board.
+fn cell_key(cell: usize) -> Word {
+    felt_key(cell as u64)
+}
+
+/// Key of the claim `player` (1 or 2) made for `cell` in the game.
+fn claim_key(player: u64, cell: usize) -> Word {
+    [ZERO, ZERO, Felt::new(cell as u64), Felt::new(player)].into()
+}
+
+/// The board of a player: which cells its ships cover, each hidden behind a salt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    /// `[salt_0, salt_1, salt_2, is_ship]` of every cell.
+    cells: [Word; NUM_CELLS],
+}
+
+impl Board {
+    /// Returns a board whose ships cover `ships`, with a fresh random salt for every cell.
+    pub fn new(ships: &[usize], rng: &mut impl FeltRng) -> Result<Self, Box<dyn Error>> {
+        if ships.len() != SHIP_CELLS {
+            return Err(format!(
+                "a board covers {SHIP_CELLS} cells with ships, got {}",
+                ships.len()
+            )
+            .into());
+        }
+        let mut covered = [false; NUM_CELLS];
+        for &cell in ships {
+            match covered.get_mut(cell) {
+                None => return Err(format!("cell {cell} is not on the board").into()),
+                Some(true) => return Err(format!("cell {cell} is covered twice").into()),
+                Some(is_ship) => *is_ship = true,
+            }
+        }
+
+        Ok(Self {
+            cells: array::from_fn(|cell| {
+                let salt = rng.draw_word();
+                [
+                    salt[0],
+                    salt[1],
+                    salt[2],
+                    if covered[cell] { ONE } else { ZERO },
+                ]
+                .into()
+            }),
+        })
+    }
+
+    /// Reads the board kept in the private account `account`.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let StorageSlot::Map(map) = &account.storage().slots()[BOARD_SLOT as usize] else {
+            return Err("the board slot of the account is not a map".into());
+        };
+        Ok(Self {
+            cells: array::from_fn(|cell| map.get(&cell_key(cell))),
+        })
+    }
+
+    /// Returns whether a ship covers `cell`.
+    pub fn is_ship(&self, cell: usize) -> bool {
+        self.cells[cell][3] == ONE
+    }
+
+    /// Returns the storage map holding the board.
+    pub fn to_storage_map(&self) -> Result<StorageMap, Box<dyn Error>> {
+        Ok(StorageMap::with_entries(
+            self.cells
+                .iter()
+                .enumerate()
+                .map(|(cell, value)| (cell_key(cell), *value)),
+        )?)
+    }
+
+    /// Returns the commitment a player joins a game with, the root of the storage map holding the
+    /// board.
+    pub fn commitment(&self) -> Result<Word, Box<dyn Error>> {
+        Ok(self.to_storage_map()?.root())
+    }
+
+    /// Opens `cell`: returns its value with the Merkle proof tying it to the commitment.
+    pub fn open(&self, cell: usize) -> Result<CellOpening, Box<dyn Error>> {
+        let value = *self
+            .cells
+            .get(cell)
+            .ok_or_else(|| format!("cell {cell} is not on the board"))?;
+
+        let proof = self.to_storage_map()?.open(&cell_key(cell));
+        let leaf = proof.leaf();
+        let merkle_nodes = MerklePath::from(proof.path().clone())
+            .inner_nodes(leaf.index().value(), leaf.hash())?
+            .collect();
+
+        Ok(CellOpening {
+            value,
+            merkle_nodes,
+            leaf: (leaf.hash(), leaf.to_elements()),
+        })
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..GRID_SIZE {
+            let cells: Vec<&str> = (0..GRID_SIZE)
+                .map(|column| match self.is_ship(row * GRID_SIZE + column) {
+                    true => "#",
+                    false => ".",
+                })
+                .collect();
+            writeln!(f, " {} ", cells.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A cell of a board, with what the game needs to check it against the board commitment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellOpening {
+    /// `[salt_0, salt_1, salt_2, is_ship]`, as stored for the cell.
+    pub value: Word,
+    /// Nodes of the Merkle path from the leaf of the cell to the root of the board, for the
+    /// Merkle store of the transaction.
+    pub merkle_nodes: Vec<InnerNodeInfo>,
+    /// Hash and elements of the leaf of the cell, for the advice map of the transaction.
+    pub leaf: (Word, Vec<Felt>),
+}
+
+/// A claim waiting for the challenged player to open its cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Challenge {
+    pub challenger: AccountId,
+    pub cell: usize,
+    /// Block from which the challenged player can be slashed.
+    pub deadline: BlockNumber,
+}
+
+/// Terms and state of a deployed game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: AccountId,
+    pub player1: AccountId,
+    pub player2: AccountId,
+    /// Asset each player stakes when joining.
+    pub stake: FungibleAsset,
+    /// Number of blocks a challenged player has to open the challenged cell.
+    pub challenge_period: u32,
+    /// Commitments to the boards of the first and the second player, once they joined.
+    pub boards: [Option<Word>; 2],
+    /// Player whose turn it is to shoot.
+    pub turn: AccountId,
+    /// Cell shot by [`Self::turn`], until the other player answers.
+    pub pending_shot: Option<usize>,
+    /// Claims of the first and the second player for the cells of their board, `true` for a hit.
+    pub claims: [[Option<bool>; NUM_CELLS]; 2],
+    /// Number of ship cells hit by the first and the second player.
+    pub hits: [u64; 2],
+    pub challenge: Option<Challenge>,
+    pub winner: Option<AccountId>,
+}
+
+impl Game {
+    /// Decodes the terms and state of a deployed game account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let value = |slot| read_u64_slot(account, slot);
+        let players = [
+            read_account_id_slot(account, PLAYER1_SLOT)?,
+            read_account_id_slot(account, PLAYER2_SLOT)?,
+        ];
+        let by_number = |number: u64| -> Result<AccountId, Box<dyn Error>> {
+            match number {
+                1 | 2 => Ok(players[number as usize - 1]),
+                _ => Err(format!("invalid player {number}").into()),
+            }
+        };
+
+        let Asset::Fungible(stake) = Asset::try_from(storage.get_item(STAKE_SLOT)?)? else {
+            return Err("the game holds a non-fungible stake".into());
+        };
+
+        let mut boards = [None; 2];
+        let mut claims = [[None; NUM_CELLS]; 2];
+        for (index, (board, board_claims)) in boards.iter_mut().zip(claims.iter_mut()).enumerate() {
+            let number = index as u64 + 1;
+            let root = storage.get_map_item(BOARDS_SLOT, felt_key(number))?;
+            *board = (root != Word::default()).then_some(root);
+
+            for (cell, claim) in board_claims.iter_mut().enumerate() {
+                *claim =
+                    match storage.get_map_item(CLAIMS_SLOT, claim_key(number, cell))?[3].as_int() {
+                        0 => None,
+                        1 => Some(false),
+                        2 => Some(true),
+                        other => return Err(format!("invalid claim {}", other - 1).into()),
+                    };
+            }
+        }
+
+        let challenge = storage.get_item(CHALLENGE_SLOT)?;
+        let challenge = match challenge[3].as_int() {
+            0 => None,
+            challenger => Some(Challenge {
+                challenger: by_number(challenger)?,
+                cell: challenge[2].as_int() as usize,
+                deadline: BlockNumber::from(u32::try_from(challenge[1].as_int())?),
+            }),
+        };
+
+        Ok(Self {
+            id: account.id(),
+            player1: players[0],
+            player2: players[1],
+            stake,
+            challenge_period: u32::try_from(value(CHALLENGE_PERIOD_SLOT)?)?,
+            boards,
+            turn: by_number(value(TURN_SLOT)?)?,
+            pending_shot: value(PENDING_SHOT_SLOT)?
+                .checked_sub(1)
+                .map(|cell| cell as usize),
+            claims,
+            hits: [value(HITS1_SLOT)?, value(HITS2_SLOT)?],
+            challenge,
+            winner: match value(WINNER_SLOT)? {
+                0 => None,
+                winner => Some(by_number(winner)?),
+            },
+        })
+    }
+
+    /// Returns the index of `player` in [`Self::boards`], [`Self::claims`] and [`Self::hits`].
+    pub fn index_of(&self, player: AccountId) -> Option<usize> {
+        [self.player1, self.player2]
+            .iter()
+            .position(|id| *id == player)
+    }
+
+    /// Returns the other player of the game.
+    pub fn opponent(&self, player: AccountId) -> AccountId {
+        if player == self.player1 {
+            self.player2
+        } else {
+            self.player1
+        }
+    }
+
+    /// Returns the player which wins if the pending shot is answered with `is_hit`: the shooter,
+    /// if the shot hits the last ship cell of the other board.
+    pub fn winner_after_answer(&self, is_hit: bool) -> Option<AccountId> {
+        let shooter = self.index_of(self.turn)?;
+        (is_hit && self.hits[shooter] + 1 == SHIP_CELLS as u64).then_some(self.turn)
+    }
+
+    /// Returns the P2ID note by which the game pays both stakes to `winner` in reply to the note
+    /// with serial number `serial_num`.
+    pub fn payout(&self, winner: AccountId, serial_num: Word) -> Result<Note, Box<dyn Error>> {
+        let pot = FungibleAsset::new(self.stake.faucet_id(), 2 * self.stake.amount())?;
+        Ok(reply_note(self.id, winner, pot, serial_num)?)
+    }
+}
+
+/// Creates the private account in which a player keeps `board`. The account never transacts: it
+/// only keeps the board with the other accounts of the client.
+pub async fn create_board_account(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    board: &Board,
+) -> Result<Account, Box<dyn Error>> {
+    let code = fs::read_to_string(BOARD_PATH)?;
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Map(board.to_storage_map()?)],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Private)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Deploys a public game between `player1`, who shoots first, and `player2`, each staking
+/// `stake`. A challenged player has `challenge_period` blocks to open the challenged cell.
+pub async fn create_game(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player1: AccountId,
+    player2: AccountId,
+    stake: FungibleAsset,
+    challenge_period: u32,
+) -> Result<Account, Box<dyn Error>> {
+    if player1 == player2 {
+        return Err("a player cannot play against itself".into());
+    }
+
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let player_word = |player: AccountId| -> Word {
+        [ZERO, ZERO, player.suffix(), player.prefix().as_felt()].into()
+    };
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(player_word(player1)),
+            StorageSlot::Value(player_word(player2)),
+            StorageSlot::Value(Asset::from(stake).into()),
+            StorageSlot::Value(felt_key(challenge_period.into())),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(felt_key(1)),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the game contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `sender` to the game `game_id`.
+pub fn game_note(
+    sender: AccountId,
+    game_id: AccountId,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(game_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+/// Has `sender` create `note` for the game, and the game consume it, creating `replies`. The
+/// Merkle path and leaf of `opening` are added to the advice inputs of the game transaction.
+///
+/// The game is a `NoAuth` account, so any client tracking it can execute its transactions.
+async fn send_to_game(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    game: &Game,
+    note: Note,
+    replies: Vec<Note>,
+    opening: Option<&CellOpening>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    client.submit_with_prover(sender, request).await?;
+
+    let mut request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .expected_output_recipients(
+            replies
+                .iter()
+                .map(|reply| reply.recipient().clone())
+                .collect(),
+        );
+    if let Some(opening) = opening {
+        request = request
+            .extend_merkle_store(opening.merkle_nodes.iter().cloned())
+            .extend_advice_map([opening.leaf.clone()]);
+    }
+    Ok(client.submit_with_prover(game.id, request.build()?).await?)
+}
+
+/// Joins `player` to `game` with the commitment to `board`, paying the stake from its vault.
+/// `script` is the compiled [`JOIN_NOTE_PATH`].
+pub async fn join(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player: AccountId,
+    game: &Game,
+    board: &Board,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let mut inputs = board.commitment()?.as_elements().to_vec();
+    inputs.push(NoteTag::from_account_id(player).into());
+
+    let serial_num = client.rng().draw_word();
+    let note = game_note(
+        player,
+        game.id,
+        inputs,
+        vec![game.stake.into()],
+        script,
+        serial_num,
+    )?;
+    send_to_game(client, player, game, note, vec![], None).await
+}
+
+/// Has `player` shoot at `cell` of the board of its opponent. `script` is the compiled
+/// [`SHOT_NOTE_PATH`].
+pub async fn shoot(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player: AccountId,
+    game: &Game,
+    cell: usize,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = game_note(
+        player,
+        game.id,
+        vec![Felt::new(cell as u64)],
+        vec![],
+        script,
+        serial_num,
+    )?;
+    send_to_game(client, player, game, note, vec![], None).await
+}
+
+/// Has `player` answer the pending shot of `game` with `is_hit`, paying out the shooter if the
+/// answer ends the game. `script` is the compiled [`ANSWER_NOTE_PATH`].
+///
+/// Nothing checks `is_hit` against the board of `player`: only a challenge does.
+pub async fn answer(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player: AccountId,
+    game: &Game,
+    is_hit: bool,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = game_note(
+        player,
+        game.id,
+        vec![Felt::from(is_hit as u8)],
+        vec![],
+        script,
+        serial_num,
+    )?;
+    let replies = match game.winner_after_answer(is_hit) {
+        Some(winner) => vec![game.payout(winner, serial_num)?],
+        None => vec![],
+    };
+    send_to_game(client, player, game, note, replies, None).await
+}
+
+/// Has `player` challenge the claim its opponent made for `cell`. `script` is the compiled
+/// [`CHALLENGE_NOTE_PATH`].
+pub async fn challenge(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player: AccountId,
+    game: &Game,
+    cell: usize,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = game_note(
+        player,
+        game.id,
+        vec![Felt::new(cell as u64)],
+        vec![],
+        script,
+        serial_num,
+    )?;
+    send_to_game(client, player, game, note, vec![], None).await
+}
+
+/// Has `player` open the challenged cell of `board`, the board it joined `game` with. `script`
+/// is the compiled [`OPEN_NOTE_PATH`].
+///
+/// Fails without sending anything unless a claim of `player` is challenged. A cell not matching
+/// the claim is rejected by the game.
+pub async fn open_cell(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player: AccountId,
+    game: &Game,
+    board: &Board,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let challenge = game
+        .challenge
+        .filter(|challenge| challenge.challenger == game.opponent(player))
+        .ok_or("no claim of the player is challenged")?;
+    let opening = board.open(challenge.cell)?;
+
+    let serial_num = client.rng().draw_word();
+    let note = game_note(
+        player,
+        game.id,
+        opening.value.as_elements().to_vec(),
+        vec![],
+        script,
+        serial_num,
+    )?;
+    send_to_game(client, player, game, note, vec![], Some(&opening)).await
+}
+
+/// Has `sender` request the slashing of the player which left the challenge of `game`
+/// unanswered, paying both stakes to the challenger. `script` is the compiled
+/// [`SLASH_NOTE_PATH`].
+///
+/// Fails without sending anything unless a challenge is open.
+pub async fn slash(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    game: &Game,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let challenge = game.challenge.ok_or("no challenge is open")?;
+
+    let serial_num = client.rng().draw_word();
+    let note = game_note(sender, game.id, vec![], vec![], script, serial_num)?;
+    let payout = game.payout(challenge.challenger, serial_num)?;
+    send_to_game(client, sender, game, note, vec![payout], None).await
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{account::AccountIdVersion, crypto::rand::RpoRandomCoin};
+
+    use super::*;
+
+    fn rng(seed: u64) -> RpoRandomCoin {
+        RpoRandomCoin::new([ZERO, ZERO, ZERO, Felt::new(seed)].into())
+    }
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn game(hits: [u64; 2]) -> Game {
+        let player1 = account(2, AccountType::RegularAccountUpdatableCode);
+        let faucet = account(4, AccountType::FungibleFaucet);
+        Game {
+            id: account(1, AccountType::RegularAccountImmutableCode),
+            player1,
+            player2: account(3, AccountType::RegularAccountUpdatableCode),
+            stake: FungibleAsset::new(faucet, 10).unwrap(),
+            challenge_period: 10,
+            boards: [None; 2],
+            turn: player1,
+            pending_shot: Some(5),
+            claims: [[None; NUM_CELLS]; 2],
+            hits,
+            challenge: None,
+            winner: None,
+        }
+    }
+
+    #[test]
+    fn board_must_cover_the_ship_cells_once() {
+        assert!(Board::new(&[0, 1, 2], &mut rng(1)).is_ok());
+        assert!(Board::new(&[0, 1], &mut rng(1)).is_err());
+        assert!(Board::new(&[0, 1, 1], &mut rng(1)).is_err());
+        assert!(Board::new(&[0, 1, NUM_CELLS], &mut rng(1)).is_err());
+    }
+
+    #[test]
+    fn salts_hide_the_ships_from_the_commitment() {
+        let board = Board::new(&[0, 5, 10], &mut rng(1)).unwrap();
+        let ships: Vec<usize> = (0..NUM_CELLS).filter(|cell| board.is_ship(*cell)).collect();
+        assert_eq!(ships, [0, 5, 10]);
+
+        // The same ships behind other salts are committed to differently
+        let other_salts = Board::new(&[0, 5, 10], &mut rng(2)).unwrap();
+        assert_ne!(
+            board.commitment().unwrap(),
+            other_salts.commitment().unwrap()
+        );
+
+        let opening = board.open(5).unwrap();
+        assert_eq!(opening.value[3], ONE);
+        assert!(opening.leaf.1.ends_with(opening.value.as_elements()));
+    }
+
+    #[test]
+    fn last_ship_cell_hit_wins_the_game() {
+        let game = game([SHIP_CELLS as u64 - 1, 0]);
+        assert_eq!(game.winner_after_answer(true), Some(game.player1));
+        assert_eq!(game.winner_after_answer(false), None);
+
+        let game = Game {
+            turn: game.player2,
+            ..game
+        };
+        assert_eq!(game.winner_after_answer(true), None);
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use miden_client::{
+    rpc::{domain::account::AccountStorageRequirements, GrpcClient, NodeRpcClient},
+    transaction::ForeignAccount,
+};
+use rust_client::address::parse_account_address;
+
+/// Counter contract deployed by the counter contract tutorial. Any public account id works.
+const DEFAULT_ACCOUNT: &str = "mtst1arjemrxne8lj5qz4mg9c8mtyxg954483";
+
+/// Storage slot extracted from the proven account state.
+const SLOT_INDEX: usize = 0;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // No client and no store: the account is read straight from the node
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = GrpcClient::new(&endpoint, timeout_ms);
+
+    let account_bech32 = rust_client::cli::positional_args()
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| DEFAULT_ACCOUNT.to_string());
+    let account_id = parse_account_address(&account_bech32, network.network_id())?;
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Fetch the account proof
+    // -------------------------------------------------------------------------
+    println!(
+        "\n[STEP 1] Fetching the state proof of {}",
+        account_id.to_bech32(network.network_id())
+    );
+
+    // The same request the client issues for every foreign account of an FPI transaction
+    let request = BTreeSet::from([ForeignAccount::public(
+        account_id,
+        AccountStorageRequirements::default(),
+    )?]);
+    let (block_num, proofs) = rpc_client
+        .get_account_proofs(&request, BTreeMap::new())
+        .await?;
+    let proof = proofs
+        .into_iter()
+        .find(|proof| proof.account_id() == account_id)
+        .ok_or("node returned no proof for the account")?;
+
+    println!("Proof is anchored at block {}", block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Verify the proof against the block header
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Verifying the proof against the block header");
+
+    let (block_header, _) = rpc_client
+        .get_block_header_by_number(Some(block_num), false)
+        .await?;
+
+    // The witness opens the account tree leaf holding the account commitment
+    let witness = proof.account_witness();
+    let computed_root = witness.clone().into_proof().compute_root();
+    assert_eq!(
+        computed_root,
+        block_header.account_root(),
+        "account witness does not match the account root of block {block_num}"
+    );
+    println!("Account tree root matches block {}", block_num);
+
+    // The account header and storage header must hash to the proven commitment
+    let account_header = proof
+        .account_header()
+        .ok_or("public accounts come with their headers")?;
+    let storage_header = proof
+        .storage_header()
+        .ok_or("public accounts come with their headers")?;
+
+    assert_eq!(account_header.commitment(), witness.state_commitment());
+    assert_eq!(
+        storage_header.compute_commitment(),
+        account_header.storage_commitment()
+    );
+    println!("Account and storage headers match the proven commitment");
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Extract a storage slot
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Reading storage slot {}", SLOT_INDEX);
+
+    let (slot_type, slot_value) = storage_header
+        .slots()
+        .nth(SLOT_INDEX)
+        .ok_or("account has no such storage slot")?;
+
+    println!("Slot type: {:?}", slot_type);
+    println!("Slot value: {:?}", slot_value);
+    println!("Nonce: {}", account_header.nonce());
+
+    Ok(())
+}
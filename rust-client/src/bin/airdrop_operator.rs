@@ -0,0 +1,309 @@
+//! Distributes the shared tutorial token to a list of recipients.
+//!
+//! ```text
+//! cargo run --release --features devnet --bin airdrop_operator -- <CSV> [BATCH_SIZE]
+//! ```
+//!
+//! `CSV` lists one `address,amount` per line, amounts in base units. Every line is validated
+//! before anything is sent. The valid recipients are paid in batches of `BATCH_SIZE` P2ID
+//! notes (20 by default), each batch in one transaction of the treasury of `devnet_setup`,
+//! which is topped up from the shared faucet as needed.
+//!
+//! Progress is saved to `<CSV>.progress.json` after every step, so running the command again
+//! after an interruption resumes the airdrop without paying anybody twice. Every run ends with
+//! a reconciliation report, on the console and in `<CSV>.report.csv`.
+
+use std::{fs, sync::Arc, time::Duration};
+
+use miden_client::{
+    asset::FungibleAsset,
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{create_p2id_note, NoteType},
+    rpc::GrpcClient,
+    store::TransactionFilter,
+    transaction::{OutputNote, TransactionRecord, TransactionRequestBuilder, TransactionStatus},
+    Client, ClientError, Felt,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::MAX_OUTPUT_NOTES_PER_TX;
+use rand::rngs::StdRng;
+use rust_client::{
+    airdrop::{batch_total, parse_recipients, plan_batches, BatchProgress, Progress, Recipient},
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    devnet,
+    format::AssetFormatter,
+    funding::FundingGuard,
+    helpers::wait_for_tx,
+    known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH},
+};
+
+const USAGE: &str = "usage: airdrop_operator <CSV> [BATCH_SIZE]";
+/// Notes sent per transaction unless `BATCH_SIZE` is given. Well below the protocol limit,
+/// which keeps proving a batch quick and the loss of an interrupted batch small.
+const DEFAULT_BATCH_SIZE: usize = 20;
+
+/// Returns the distribution transaction recorded for `batch`, or `None` if there is none or
+/// the client does not know it.
+async fn saved_transaction(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    batch: &BatchProgress,
+) -> Result<Option<TransactionRecord>, ClientError> {
+    let Some(tx_hex) = &batch.distribution_tx else {
+        return Ok(None);
+    };
+    client.sync_state().await?;
+    Ok(client
+        .get_transactions(TransactionFilter::All)
+        .await?
+        .into_iter()
+        .find(|tx| tx.id.to_hex() == *tx_hex))
+}
+
+/// Escapes `field` for a CSV line.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = rust_client::cli::positional_args();
+    let Some(csv_path) = args.first() else {
+        println!("{USAGE}");
+        return Ok(());
+    };
+    let batch_size = match args.get(1) {
+        Some(size) => size.parse()?,
+        None => DEFAULT_BATCH_SIZE,
+    };
+    if !(1..=MAX_OUTPUT_NOTES_PER_TX).contains(&batch_size) {
+        return Err(format!("BATCH_SIZE must be between 1 and {MAX_OUTPUT_NOTES_PER_TX}").into());
+    }
+    let progress_path = format!("{csv_path}.progress.json");
+    let report_path = format!("{csv_path}.report.csv");
+
+    let network = rust_client::cli::network().await?;
+    let registry = KnownAccounts::load(DEFAULT_REGISTRY_PATH)?;
+    let faucet_id = registry
+        .get(devnet::FAUCET)
+        .ok_or("no shared faucet registered, run `devnet_setup` first")?;
+    let treasury_id = registry
+        .get(devnet::TREASURY)
+        .ok_or("no treasury registered, run `devnet_setup` first")?;
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Validate the recipients and plan the batches
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Validating {csv_path}");
+
+    let (recipients, rejected) =
+        parse_recipients(&fs::read_to_string(csv_path)?, network.network_id());
+    for line in &rejected {
+        println!("Line {} rejected: {}", line.line, line.reason);
+    }
+    let batches = plan_batches(&recipients, batch_size);
+    let mut progress = Progress::load_or_new(&progress_path, &batches)?;
+    println!(
+        "{} recipients in {} batches, {} lines rejected",
+        recipients.len(),
+        batches.len(),
+        rejected.len()
+    );
+
+    // Initialize client
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, kept across runs: the treasury and faucet keys are there
+    let context = TutorialContext::persistent(devnet::STATE)?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client.clone())
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    let mut formatter = AssetFormatter::new(network.network_id());
+    formatter.load_token(rpc_client.as_ref(), faucet_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Distribute the batches
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Distributing");
+
+    // Batches are topped up one at a time, so the guard has to allow back to back top-ups
+    let largest_batch = batches.iter().map(|batch| batch_total(batch)).max();
+    let guard = FundingGuard::new(faucet_id)
+        .with_max_per_top_up(largest_batch.unwrap_or(0))
+        .with_min_interval(Duration::ZERO)
+        .with_log_path(context.dir().join("airdrop_funding.json"));
+
+    // The airdrop stops at the first failure: the report shows where, and the next run resumes
+    let mut failure = None;
+    for (index, batch) in batches.iter().enumerate() {
+        if progress.batches[index].committed {
+            continue;
+        }
+        let number = index + 1;
+
+        // A transaction recorded by an earlier run may have landed, or still be on its way.
+        // Only a discarded one is sent again.
+        match saved_transaction(&mut client, &progress.batches[index]).await {
+            Ok(Some(tx)) if matches!(tx.status, TransactionStatus::Discarded(_)) => {
+                println!("Batch {number}: previous transaction discarded, sending again");
+                progress.batches[index].distribution_tx = None;
+                progress.save(&progress_path)?;
+            }
+            Ok(_) => {}
+            Err(err) => {
+                failure = Some(format!("batch {number}: {err}"));
+                break;
+            }
+        }
+
+        if progress.batches[index].distribution_tx.is_none() {
+            let total = FungibleAsset::new(faucet_id, batch_total(batch))?;
+            if let Err(err) = guard
+                .ensure_funded(&mut client, treasury_id, &[total])
+                .await
+            {
+                failure = Some(format!(
+                    "batch {number}: could not fund the treasury: {err}"
+                ));
+                break;
+            }
+
+            let mut notes = Vec::with_capacity(batch.len());
+            for recipient in batch {
+                let note = create_p2id_note(
+                    treasury_id,
+                    recipient.account_id,
+                    vec![FungibleAsset::new(faucet_id, recipient.amount)?.into()],
+                    NoteType::Public,
+                    Felt::new(0),
+                    client.rng(),
+                )?;
+                notes.push(OutputNote::Full(note));
+            }
+            let request = TransactionRequestBuilder::new()
+                .own_output_notes(notes)
+                .build()?;
+
+            match client.submit_with_prover(treasury_id, request).await {
+                Ok(tx_id) => {
+                    // Recorded before waiting, so that an interrupted run never sends it twice
+                    progress.batches[index].distribution_tx = Some(tx_id.to_hex());
+                    progress.save(&progress_path)?;
+                    println!(
+                        "Batch {number}: sent {} to {} recipients",
+                        formatter.format_amount(faucet_id, total.amount()),
+                        batch.len()
+                    );
+                }
+                Err(err) => {
+                    failure = Some(format!("batch {number}: {err}"));
+                    break;
+                }
+            }
+        }
+
+        let Some(tx) = saved_transaction(&mut client, &progress.batches[index]).await? else {
+            failure = Some(format!(
+                "batch {number}: its transaction is unknown to this store"
+            ));
+            break;
+        };
+        if let Err(err) = wait_for_tx(&mut client, tx.id).await {
+            failure = Some(format!("batch {number}: {err}"));
+            break;
+        }
+        progress.batches[index].committed = true;
+        progress.save(&progress_path)?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Reconciliation report
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Reconciliation");
+
+    let mut report = String::from("line,address,amount,status,batch,transaction\n");
+    let (mut sent, mut pending) = (0u64, 0u64);
+    for (index, batch) in batches.iter().enumerate() {
+        let batch_progress = &progress.batches[index];
+        let status = match batch_progress {
+            BatchProgress {
+                committed: true, ..
+            } => "sent",
+            BatchProgress {
+                distribution_tx: Some(_),
+                ..
+            } => "submitted",
+            _ => "pending",
+        };
+        for Recipient {
+            line,
+            account_id,
+            amount,
+        } in batch
+        {
+            if batch_progress.committed {
+                sent += amount;
+            } else {
+                pending += amount;
+            }
+            let fields = [
+                line.to_string(),
+                account_id.to_bech32(network.network_id()),
+                amount.to_string(),
+                status.to_string(),
+                (index + 1).to_string(),
+                batch_progress.distribution_tx.clone().unwrap_or_default(),
+            ];
+            let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            report.push_str(&fields.join(","));
+            report.push('\n');
+        }
+    }
+    for line in &rejected {
+        let fields = [
+            line.line.to_string(),
+            csv_field(&line.content),
+            String::new(),
+            csv_field(&format!("rejected: {}", line.reason)),
+            String::new(),
+            String::new(),
+        ];
+        report.push_str(&fields.join(","));
+        report.push('\n');
+    }
+    fs::write(&report_path, report)?;
+
+    let committed = progress.batches.iter().filter(|b| b.committed).count();
+    println!("Batches committed: {committed}/{}", batches.len());
+    println!("Sent:     {}", formatter.format_amount(faucet_id, sent));
+    println!("Pending:  {}", formatter.format_amount(faucet_id, pending));
+    println!("Rejected: {} lines", rejected.len());
+    println!("Report written to {report_path}");
+
+    if let Some(failure) = failure {
+        return Err(format!("airdrop interrupted at {failure}, run again to resume").into());
+    }
+    assert_eq!(sent, progress.total);
+    println!("Airdrop complete");
+
+    Ok(())
+}
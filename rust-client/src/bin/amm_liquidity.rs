@@ -0,0 +1,228 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::{Account, AccountId},
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    amm::{self, Pool},
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+};
+
+/// ETH Alice deposits into the pool.
+const POOL_ETH: u64 = 1_000;
+/// USD Alice deposits into the pool.
+const POOL_USD: u64 = 100_000;
+/// USD Bob swaps for ETH.
+const SWAP_USD: u64 = 10_000;
+
+/// Returns the account `account_id` as last synced by the client.
+async fn account(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    Ok(client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?
+        .account()
+        .clone())
+}
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    Ok(account(client, account_id)
+        .await?
+        .vault()
+        .get_balance(faucet_id)?)
+}
+
+/// Waits for `count` notes to `account_id` and consumes them.
+async fn collect(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    account_id: AccountId,
+    count: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    wait_for_notes(client, network, account_id, count).await?;
+    let tx_id = consume_all_notes(client, account_id).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("amm_liquidity")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create accounts and tokens
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts, minting ETH and USD");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice (provider): {}",
+        alice.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Bob (trader):     {}",
+        bob.id().to_bech32(network.network_id())
+    );
+
+    let eth = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("ETH").unwrap()).await?;
+    let usd = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+    for (account_id, faucet_id, amount) in [
+        (alice.id(), eth.id(), POOL_ETH),
+        (alice.id(), usd.id(), POOL_USD),
+        (bob.id(), usd.id(), SWAP_USD),
+    ] {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet_id, amount)?,
+            account_id,
+            NoteType::Public,
+            client.rng(),
+        )?;
+        let tx_id = client.submit_with_prover(faucet_id, mint_request).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+    collect(&mut client, &network, alice.id(), 2).await?;
+    collect(&mut client, &network, bob.id(), 1).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice deposits both assets for liquidity shares
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Alice deposits {POOL_ETH} ETH and {POOL_USD} USD");
+
+    let pool_account = amm::create_pool(&mut client, eth.id(), usd.id()).await?;
+    println!(
+        "Pool: {}",
+        pool_account.id().to_bech32(network.network_id())
+    );
+    let pool = Pool::from_account(&pool_account)?;
+
+    let shares = pool
+        .shares_for(POOL_ETH, POOL_USD)
+        .ok_or("the deposit mints no share")?;
+    let deposit_script = amm::compile_note_script(&client, amm::DEPOSIT_NOTE_PATH)?;
+    let tx_id = amm::add_liquidity(
+        &mut client,
+        alice.id(),
+        &pool,
+        FungibleAsset::new(eth.id(), POOL_ETH)?,
+        FungibleAsset::new(usd.id(), POOL_USD)?,
+        deposit_script,
+    )
+    .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let pool_account = account(&client, pool.id).await?;
+    assert_eq!(amm::shares(&pool_account, alice.id())?, shares);
+    println!("Alice holds {shares} shares");
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Bob swaps USD for ETH
+    // -------------------------------------------------------------------------
+    // The output follows x * y = k: the reserves after the swap multiply to at least as much as
+    // before it.
+    println!("\n[STEP 3] Bob swaps {SWAP_USD} USD for ETH");
+
+    let pool = Pool::from_account(&pool_account)?;
+    let asset_in = FungibleAsset::new(usd.id(), SWAP_USD)?;
+    let asset_out = pool.quote(asset_in).ok_or("the pool pays nothing")?;
+    let swap_script = amm::compile_note_script(&client, amm::SWAP_NOTE_PATH)?;
+    let tx_id = amm::swap(&mut client, bob.id(), &pool, asset_in, swap_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, bob.id(), 1).await?;
+    println!("Bob received {} ETH", asset_out.amount());
+
+    let pool = Pool::from_account(&account(&client, pool.id).await?)?;
+    assert!(
+        u128::from(pool.reserve_a) * u128::from(pool.reserve_b)
+            >= u128::from(POOL_ETH) * u128::from(POOL_USD)
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Alice redeems the shares
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Alice redeems all {shares} shares");
+
+    let (eth_out, usd_out) = pool
+        .withdrawal(shares)
+        .ok_or("the shares cannot be redeemed")?;
+    let withdraw_script = amm::compile_note_script(&client, amm::WITHDRAW_NOTE_PATH)?;
+    let tx_id =
+        amm::remove_liquidity(&mut client, alice.id(), &pool, shares, withdraw_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, alice.id(), 2).await?;
+    println!(
+        "Alice received {} ETH and {} USD",
+        eth_out.amount(),
+        usd_out.amount()
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking balances");
+
+    // Alice held every share and takes the whole pool back, including what Bob paid in
+    assert_eq!(
+        balance(&client, alice.id(), eth.id()).await?,
+        POOL_ETH - asset_out.amount()
+    );
+    assert_eq!(
+        balance(&client, alice.id(), usd.id()).await?,
+        POOL_USD + SWAP_USD
+    );
+    assert_eq!(
+        balance(&client, bob.id(), eth.id()).await?,
+        asset_out.amount()
+    );
+    assert_eq!(balance(&client, bob.id(), usd.id()).await?, 0);
+
+    let pool_account = account(&client, pool.id).await?;
+    assert_eq!(pool_account.vault().assets().count(), 0);
+    assert_eq!(amm::shares(&pool_account, alice.id())?, 0);
+    assert_eq!(Pool::from_account(&pool_account)?.total_shares, 0);
+    println!("The pool is empty again and every token is accounted for");
+
+    Ok(())
+}
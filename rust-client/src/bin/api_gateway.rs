@@ -0,0 +1,329 @@
+use miden_lib::account::auth::NoAuth;
+use miden_lib::transaction::TransactionKernel;
+use rand::{rngs::StdRng, RngCore};
+use std::{fmt, fs, path::Path, sync::Arc};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    asset::Asset,
+    ZERO,
+};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, create_library,
+        wait_for_notes, wait_for_tx,
+    },
+};
+
+const CONTRACT_PATH: &str = "../masm/accounts/api_credits.masm";
+const LIBRARY_PATH: &str = "external_contract::api_credits";
+
+/// Credits bought with one token.
+const CREDITS_PER_TOKEN: u64 = 2;
+/// Tokens Alice pays for credits.
+const TOKENS_PAID: u64 = 2;
+/// Requests Alice sends, one more than she paid for.
+const REQUESTS: u64 = CREDITS_PER_TOKEN * TOKENS_PAID + 1;
+
+const CREDITS_SLOT: u8 = 1;
+const SERVED_SLOT: u8 = 2;
+
+/// Answer of the gateway to an API request.
+enum Response {
+    /// The request was paid for with a credit and served.
+    Ok(String),
+    /// The caller has no credits left, nothing was charged.
+    PaymentRequired,
+    /// The request note is not a request to this gateway.
+    BadRequest(&'static str),
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ok(body) => write!(f, "200 OK: {body}"),
+            Self::PaymentRequired => write!(f, "402 Payment Required: buy credits first"),
+            Self::BadRequest(reason) => write!(f, "400 Bad Request: {reason}"),
+        }
+    }
+}
+
+/// The off-chain half of the gateway: an API server that charges every request one credit of
+/// the on-chain contract before serving it.
+///
+/// Callers prove who they are by creating a request note from their own account, and hand the
+/// note to the server along with the request. The server checks the credits of the sender in
+/// the contract storage first, so that requests which cannot be paid for are refused without
+/// submitting a failing transaction.
+struct Gateway {
+    contract_id: AccountId,
+    request_script: NoteScript,
+}
+
+impl Gateway {
+    /// Returns the credits left to `caller`, as of the latest block.
+    async fn credits(
+        &self,
+        client: &mut Client<FilesystemKeyStore<StdRng>>,
+        caller: AccountId,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        client.sync_state().await?;
+        let record = client
+            .get_account(self.contract_id)
+            .await?
+            .ok_or("the gateway contract is not tracked by the client")?;
+        let key: Word = [ZERO, ZERO, caller.suffix(), caller.prefix().as_felt()].into();
+        Ok(record.account().storage().get_map_item(CREDITS_SLOT, key)?[3].as_int())
+    }
+
+    /// Charges `request` one credit and serves `path`.
+    async fn handle(
+        &self,
+        client: &mut Client<FilesystemKeyStore<StdRng>>,
+        path: &str,
+        request: Note,
+    ) -> Result<Response, Box<dyn std::error::Error>> {
+        if request.script().root() != self.request_script.root() {
+            return Ok(Response::BadRequest("not an API request note"));
+        }
+        if request.metadata().tag() != NoteTag::from_account_id(self.contract_id) {
+            return Ok(Response::BadRequest("request addressed to another gateway"));
+        }
+
+        let caller = request.metadata().sender();
+        if self.credits(client, caller).await? == 0 {
+            return Ok(Response::PaymentRequired);
+        }
+
+        // Consuming the note burns the credit. The request is only served once that
+        // transaction is committed, so that a credit can never be spent twice.
+        let charge = TransactionRequestBuilder::new()
+            .unauthenticated_input_notes([(request, None)])
+            .build()?;
+        let tx_id = client.submit_with_prover(self.contract_id, charge).await?;
+        wait_for_tx(client, tx_id).await?;
+
+        let body = match path {
+            "/v1/block/latest" => {
+                format!("{{\"block\": {}}}", client.get_sync_height().await?)
+            }
+            _ => "{\"error\": \"not found\"}".to_string(),
+        };
+        Ok(Response::Ok(body))
+    }
+}
+
+/// Builds a note sent by `sender` to the gateway `contract_id`.
+fn gateway_note(
+    sender: AccountId,
+    contract_id: AccountId,
+    script: NoteScript,
+    assets: Vec<Asset>,
+    serial_num: Word,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(vec![])?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(contract_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("api_gateway")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create Alice and the payment faucet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice and funding her");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    println!("Faucet: {}", faucet.id().to_bech32(network.network_id()));
+
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), TOKENS_PAID)?,
+        alice.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    client.submit_with_prover(faucet.id(), mint_request).await?;
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy the credits contract
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Deploying the credits contract of the gateway");
+
+    let contract_code = fs::read_to_string(Path::new(CONTRACT_PATH))?;
+    let pricing: Word = [
+        Felt::new(CREDITS_PER_TOKEN),
+        ZERO,
+        faucet.id().suffix(),
+        faucet.id().prefix().as_felt(),
+    ]
+    .into();
+    let contract_component = AccountComponent::compile(
+        &contract_code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(pricing),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let contract = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(contract_component)
+        .build()?;
+    client.add_account(&contract, false).await?;
+    println!("Gateway: {}", contract.id().to_bech32(network.network_id()));
+
+    let library = create_library(&contract_code, LIBRARY_PATH)?;
+    let compile = |path: &str| -> Result<NoteScript, Box<dyn std::error::Error>> {
+        Ok(client
+            .script_builder()
+            .with_dynamically_linked_library(&library)?
+            .compile_note_script(&fs::read_to_string(Path::new(path))?)?)
+    };
+    let purchase_script = compile("../masm/notes/buy_credits_note.masm")?;
+    let request_script = compile("../masm/notes/api_request_note.masm")?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Alice buys credits
+    // -------------------------------------------------------------------------
+    println!(
+        "\n[STEP 3] Alice pays {TOKENS_PAID} MID for {} credits",
+        TOKENS_PAID * CREDITS_PER_TOKEN
+    );
+
+    let payment = FungibleAsset::new(faucet.id(), TOKENS_PAID)?;
+    let purchase = gateway_note(
+        alice.id(),
+        contract.id(),
+        purchase_script,
+        vec![payment.into()],
+        client.rng().draw_word(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(purchase.clone())])
+        .build()?;
+    client.submit_with_prover(alice.id(), request).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(purchase, None)])
+        .build()?;
+    let tx_id = client.submit_with_prover(contract.id(), request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Alice calls the API until she runs out of credits
+    // -------------------------------------------------------------------------
+    // Each call is an off-chain request carrying a note Alice created on-chain. The gateway
+    // burns one credit per request by consuming the note, and refuses requests once her
+    // credits are spent without charging anything.
+    println!("\n[STEP 4] Alice sends {REQUESTS} requests to the gateway");
+
+    let gateway = Gateway {
+        contract_id: contract.id(),
+        request_script: request_script.clone(),
+    };
+    let mut served = 0;
+    for i in 1..=REQUESTS {
+        let note = gateway_note(
+            alice.id(),
+            contract.id(),
+            request_script.clone(),
+            vec![],
+            client.rng().draw_word(),
+        )?;
+        let request = TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(note.clone())])
+            .build()?;
+        let tx_id = client.submit_with_prover(alice.id(), request).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+
+        let response = gateway
+            .handle(&mut client, "/v1/block/latest", note)
+            .await?;
+        println!("Request {i}: {response}");
+        if let Response::Ok(_) = response {
+            served += 1;
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking the contract state");
+
+    let credits_left = gateway.credits(&mut client, alice.id()).await?;
+    let record = client.get_account(contract.id()).await?.unwrap();
+    let served_on_chain = record.account().storage().get_item(SERVED_SLOT)?[3].as_int();
+    let revenue = record.account().vault().get_balance(faucet.id())?;
+
+    println!("Requests served: {served}");
+    println!("Requests paid on-chain: {served_on_chain}");
+    println!("Credits left to Alice: {credits_left}");
+    println!("Gateway revenue: {revenue} MID");
+
+    assert_eq!(served, CREDITS_PER_TOKEN * TOKENS_PAID);
+    assert_eq!(served_on_chain, served);
+    assert_eq!(credits_left, 0);
+    assert_eq!(revenue, TOKENS_PAID);
+
+    Ok(())
+}
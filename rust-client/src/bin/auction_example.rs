@@ -0,0 +1,236 @@
+use rand::rngs::StdRng;
+use std::{sync::Arc, time::Duration};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    auction::{self, Auction, AuctionState},
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+};
+use tokio::time::sleep;
+
+/// Tokens minted to each bidder.
+const BUDGET: u64 = 100;
+/// Smallest amount the first bid can offer.
+const RESERVE: u64 = 40;
+/// Blocks during which the auction accepts bids.
+const DURATION_BLOCKS: u32 = 30;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+/// Reads the current lot and bids of the auction `auction_id`.
+async fn read_auction(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    auction_id: AccountId,
+) -> Result<Auction, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(auction_id)
+        .await?
+        .ok_or("the auction is not tracked by the client")?;
+    Auction::from_account(record.account())
+}
+
+/// Mints `amount` tokens of `faucet_id` to `account_id`, which consumes them.
+async fn fund(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    faucet_id: AccountId,
+    account_id: AccountId,
+    amount: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet_id, amount)?,
+        account_id,
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet_id, mint_request).await?;
+    wait_for_tx(client, tx_id).await?;
+    wait_for_notes(client, network, account_id, 1).await?;
+    let tx_id = consume_all_notes(client, account_id).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+/// Waits for the payout of the auction to `account_id` and consumes it.
+async fn collect(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    account_id: AccountId,
+) -> Result<(), Box<dyn std::error::Error>> {
+    wait_for_notes(client, network, account_id, 1).await?;
+    let tx_id = consume_all_notes(client, account_id).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("auction_example")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the seller, the bidders and the assets
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and assets");
+
+    let seller = create_basic_account(&mut client, &keystore).await?;
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!("Seller: {}", seller.id().to_bech32(network.network_id()));
+    println!("Alice:  {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:    {}", bob.id().to_bech32(network.network_id()));
+
+    let usd = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+    let art = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("ART").unwrap()).await?;
+
+    fund(&mut client, &network, art.id(), seller.id(), 1).await?;
+    fund(&mut client, &network, usd.id(), alice.id(), BUDGET).await?;
+    fund(&mut client, &network, usd.id(), bob.id(), BUDGET).await?;
+    println!("The seller holds 1 ART, Alice and Bob {BUDGET} USD each");
+
+    let list_script = auction::compile_note_script(&client, auction::LIST_NOTE_PATH)?;
+    let bid_script = auction::compile_note_script(&client, auction::BID_NOTE_PATH)?;
+    let close_script = auction::compile_note_script(&client, auction::CLOSE_NOTE_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: The seller opens an auction for the ART token
+    // -------------------------------------------------------------------------
+    // The auction stores the seller, the currency, the reserve and the end block. Listing the
+    // lot moves it into the vault of the auction, which opens the bidding.
+    println!("\n[STEP 2] The seller lists 1 ART, starting at {RESERVE} USD");
+
+    let end = client.get_sync_height().await?.as_u32() + DURATION_BLOCKS;
+    let account =
+        auction::create_auction(&mut client, seller.id(), usd.id(), RESERVE, end.into()).await?;
+    let listing = Auction::from_account(&account)?;
+    println!("Auction {} ends at block {end}", listing.id);
+
+    let lot = FungibleAsset::new(art.id(), 1)?.into();
+    let tx_id = auction::list(&mut client, &listing, lot, list_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Alice and Bob bid
+    // -------------------------------------------------------------------------
+    // Each bid escrows its tokens in the auction. A bid outbidding the highest bid makes the
+    // auction refund the outbid tokens with a P2ID note in the same transaction.
+    println!("\n[STEP 3] Alice bids 50 USD, Bob outbids her with 60 USD");
+
+    let state = read_auction(&client, listing.id).await?;
+    let tx_id = auction::bid(&mut client, alice.id(), &state, 50, bid_script.clone()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let state = read_auction(&client, listing.id).await?;
+    let tx_id = auction::bid(&mut client, bob.id(), &state, 60, bid_script.clone()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, alice.id()).await?;
+    println!(
+        "Alice got her 50 USD back and holds {} USD",
+        balance(&client, alice.id(), usd.id()).await?
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Alice has to beat the highest bid
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Alice tries 55 USD, then bids 75 USD");
+
+    let state = read_auction(&client, listing.id).await?;
+    let Err(err) = auction::bid(&mut client, alice.id(), &state, 55, bid_script.clone()).await
+    else {
+        return Err("the auction accepted a bid below the highest bid".into());
+    };
+    println!("Bid of 55 USD refused: {err}");
+
+    let tx_id = auction::bid(&mut client, alice.id(), &state, 75, bid_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, bob.id()).await?;
+    println!("Bob got his 60 USD back");
+
+    // -------------------------------------------------------------------------
+    // STEP 5: The auction ends and is closed
+    // -------------------------------------------------------------------------
+    // The block of a transaction is the block the client last synced to, so the auction can
+    // only be closed once the client has seen the end block.
+    println!("\n[STEP 5] Waiting for block {end} to close the auction");
+
+    let state = read_auction(&client, listing.id).await?;
+    while !state.can_close_at(client.get_sync_height().await?) {
+        sleep(Duration::from_secs(3)).await;
+        client.sync_state().await?;
+    }
+
+    let tx_id = auction::close(&mut client, bob.id(), &state, close_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, alice.id()).await?;
+    collect(&mut client, &network, seller.id()).await?;
+    println!("Alice received the ART token, the seller her 75 USD");
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Checking balances");
+
+    let state = read_auction(&client, listing.id).await?;
+    let history: Vec<String> = state
+        .bids
+        .iter()
+        .map(|bid| format!("{} USD", bid.amount))
+        .collect();
+    println!("Bids: {}", history.join(", "));
+
+    assert_eq!(state.state, AuctionState::Closed);
+    assert_eq!(state.bids.len(), 3);
+    assert_eq!(balance(&client, alice.id(), usd.id()).await?, BUDGET - 75);
+    assert_eq!(balance(&client, alice.id(), art.id()).await?, 1);
+    assert_eq!(balance(&client, bob.id(), usd.id()).await?, BUDGET);
+    assert_eq!(balance(&client, seller.id(), usd.id()).await?, 75);
+    assert_eq!(balance(&client, seller.id(), art.id()).await?, 0);
+    println!("Alice paid 75 USD for the ART token, Bob was refunded in full");
+
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use miden_lib::account::auth::NoAuth;
+use miden_lib::transaction::TransactionKernel;
+use rand::{rngs::StdRng, RngCore};
+use std::{fs, path::Path, sync::Arc};
+
+use miden_client::{
+    account::{Account, AccountId},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    rpc::GrpcClient,
+    transaction::{TransactionRequest, TransactionRequestBuilder},
+    Client, ClientError,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    crypto::hash::rpo::Rpo256,
+    Felt, Word, ZERO,
+};
+use rust_client::{
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{create_library, total_cycles, word_to_masm},
+};
+
+/// Number of map entries written by each approach.
+const NUM_ENTRIES: u64 = 8;
+
+/// Deploys a new batch map contract with an empty map in slot 0.
+async fn deploy_batch_map(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    contract_code: &str,
+) -> Result<Account, ClientError> {
+    let component = AccountComponent::compile(
+        contract_code,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Map(StorageMap::new())],
+    )
+    .unwrap()
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let contract = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()
+        .unwrap();
+
+    client.add_account(&contract, false).await?;
+    println!(
+        "Contract account ID: {:?}",
+        contract.id().to_bech32(network.network_id())
+    );
+
+    Ok(contract)
+}
+
+/// Executes `request` locally to measure its cycle count, then submits it to the network.
+async fn measure_and_submit(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    request: TransactionRequest,
+) -> Result<usize, ClientError> {
+    let tx_result = client
+        .execute_transaction(account_id, request.clone())
+        .await?;
+    let cycles = total_cycles(tx_result.executed_transaction().measurements());
+
+    let tx_id = client.submit_with_prover(account_id, request).await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    Ok(cycles)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("batched_map_writes")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Deploy two identical map contracts
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Deploying one contract per approach");
+
+    let contract_code = fs::read_to_string(Path::new("../masm/accounts/batch_map.masm")).unwrap();
+    let individual_contract = deploy_batch_map(&mut client, &network, &contract_code).await?;
+    let batched_contract = deploy_batch_map(&mut client, &network, &contract_code).await?;
+
+    let library = create_library(&contract_code, "external_contract::batch_map").unwrap();
+
+    let entries: Vec<(Word, Word)> = (1..=NUM_ENTRIES)
+        .map(|i| {
+            let key: Word = [ZERO, ZERO, ZERO, Felt::new(i)].into();
+            let value: Word = [Felt::new(i), Felt::new(i * 10), Felt::new(i * 100), ZERO].into();
+            (key, value)
+        })
+        .collect();
+
+    // -------------------------------------------------------------------------
+    // STEP 2: One transaction per map write
+    // -------------------------------------------------------------------------
+    println!(
+        "\n[STEP 2] Writing {} entries with {} transactions",
+        NUM_ENTRIES, NUM_ENTRIES
+    );
+
+    let mut individual_cycles = 0;
+    for (key, value) in &entries {
+        let script_code = format!(
+            "use.external_contract::batch_map\n\
+             begin\n    push.{}\n    push.{}\n    call.batch_map::set_entry\nend\n",
+            word_to_masm(*value),
+            word_to_masm(*key)
+        );
+        let tx_script = client
+            .script_builder()
+            .with_dynamically_linked_library(&library)?
+            .compile_tx_script(&script_code)?;
+
+        let request = TransactionRequestBuilder::new()
+            .custom_script(tx_script)
+            .build()?;
+        individual_cycles +=
+            measure_and_submit(&mut client, individual_contract.id(), request).await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: All map writes in one transaction, supplied via advice data
+    // -------------------------------------------------------------------------
+    println!(
+        "\n[STEP 3] Writing {} entries with a single transaction",
+        NUM_ENTRIES
+    );
+
+    // The entries travel in the advice map; only their commitment is part of the script
+    let entries_data: Vec<Felt> = entries
+        .iter()
+        .flat_map(|(key, value)| key.iter().chain(value.iter()).copied())
+        .collect();
+    let entries_commitment = Rpo256::hash_elements(&entries_data);
+
+    let script_code = format!(
+        "use.external_contract::batch_map\n\
+         begin\n    push.{}\n    push.{}\n    call.batch_map::set_entries\nend\n",
+        NUM_ENTRIES,
+        word_to_masm(entries_commitment)
+    );
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_tx_script(&script_code)?;
+
+    let request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .extend_advice_map([(entries_commitment, entries_data)])
+        .build()?;
+    let batched_cycles = measure_and_submit(&mut client, batched_contract.id(), request).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Compare
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Comparing both approaches");
+
+    client.sync_state().await?;
+
+    let individual_root = client
+        .get_account(individual_contract.id())
+        .await?
+        .unwrap()
+        .account()
+        .storage()
+        .get_item(0)?;
+    let batched_root = client
+        .get_account(batched_contract.id())
+        .await?
+        .unwrap()
+        .account()
+        .storage()
+        .get_item(0)?;
+
+    // Both contracts end up with exactly the same map
+    assert_eq!(individual_root, batched_root);
+    println!("Map root: {:?}", batched_root);
+
+    println!(
+        "{} transactions: {} cycles ({} per entry)",
+        NUM_ENTRIES,
+        individual_cycles,
+        individual_cycles / NUM_ENTRIES as usize
+    );
+    println!(
+        "1 transaction:  {} cycles ({} per entry)",
+        batched_cycles,
+        batched_cycles / NUM_ENTRIES as usize
+    );
+    println!(
+        "Batching saved {} cycles and {} transactions",
+        individual_cycles.saturating_sub(batched_cycles),
+        NUM_ENTRIES - 1
+    );
+
+    Ok(())
+}
@@ -0,0 +1,232 @@
+use rand::rngs::StdRng;
+use std::{sync::Arc, time::Duration};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    battleship::{self, Board, Game},
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+};
+use tokio::time::sleep;
+
+/// Amount each player stakes on the game.
+const STAKE: u64 = 50;
+/// Blocks a challenged player has to open the challenged cell.
+const CHALLENGE_PERIOD: u32 = 20;
+
+/// Reads the current state of the game `game_id`.
+async fn read_game(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    game_id: AccountId,
+) -> Result<Game, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(game_id)
+        .await?
+        .ok_or("the game is not tracked by the client")?;
+    Game::from_account(record.account())
+}
+
+/// Reads the board kept in the private account `board_id`.
+async fn read_board(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    board_id: AccountId,
+) -> Result<Board, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(board_id)
+        .await?
+        .ok_or("the board is not tracked by the client")?;
+    Board::from_account(record.account())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("battleship")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the players and fund their stakes
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice and Bob with {STAKE} tokens each");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    let stake = FungibleAsset::new(faucet.id(), STAKE)?;
+    for player in [alice.id(), bob.id()] {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            stake,
+            player,
+            NoteType::Public,
+            client.rng(),
+        )?;
+        let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        wait_for_notes(&mut client, &network, player, 1).await?;
+        let tx_id = consume_all_notes(&mut client, player).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Place the ships and deploy the game
+    // -------------------------------------------------------------------------
+    // Each board lives in a private account: the node only ever sees the root of the storage
+    // map holding it, which the salt of every cell keeps from giving the ships away.
+    println!("\n[STEP 2] Alice and Bob place their ships");
+
+    let alice_board = Board::new(&[0, 1, 2], client.rng())?;
+    let bob_board = Board::new(&[5, 9, 13], client.rng())?;
+    let alice_board_id = battleship::create_board_account(&mut client, &alice_board)
+        .await?
+        .id();
+    let bob_board_id = battleship::create_board_account(&mut client, &bob_board)
+        .await?
+        .id();
+    println!("Alice's board, kept in {alice_board_id}:\n{alice_board}");
+    println!("Bob's board, kept in {bob_board_id}:\n{bob_board}");
+
+    let account =
+        battleship::create_game(&mut client, alice.id(), bob.id(), stake, CHALLENGE_PERIOD).await?;
+    let game = Game::from_account(&account)?;
+    println!("Game: {}", game.id);
+
+    let join_script = battleship::compile_note_script(&client, battleship::JOIN_NOTE_PATH)?;
+    let shot_script = battleship::compile_note_script(&client, battleship::SHOT_NOTE_PATH)?;
+    let answer_script = battleship::compile_note_script(&client, battleship::ANSWER_NOTE_PATH)?;
+    let challenge_script =
+        battleship::compile_note_script(&client, battleship::CHALLENGE_NOTE_PATH)?;
+    let open_script = battleship::compile_note_script(&client, battleship::OPEN_NOTE_PATH)?;
+    let slash_script = battleship::compile_note_script(&client, battleship::SLASH_NOTE_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Both players join with their commitment and stake
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Alice and Bob join the game");
+
+    for (player, board_id) in [(alice.id(), alice_board_id), (bob.id(), bob_board_id)] {
+        let board = read_board(&client, board_id).await?;
+        let tx_id =
+            battleship::join(&mut client, player, &game, &board, join_script.clone()).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        println!("{player} joined with {}", board.commitment()?.to_hex());
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Play a round, in which Bob lies
+    // -------------------------------------------------------------------------
+    // Answers are taken as claimed: Bob answers the hit on cell 5 with a miss.
+    println!("\n[STEP 4] Alice and Bob shoot at each other");
+
+    let game = read_game(&client, game.id).await?;
+    let tx_id = battleship::shoot(&mut client, alice.id(), &game, 5, shot_script.clone()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    let game = read_game(&client, game.id).await?;
+    let tx_id =
+        battleship::answer(&mut client, bob.id(), &game, false, answer_script.clone()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Alice shoots at cell 5, Bob claims a miss");
+
+    let game = read_game(&client, game.id).await?;
+    let tx_id = battleship::shoot(&mut client, bob.id(), &game, 15, shot_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    let game = read_game(&client, game.id).await?;
+    let is_hit = read_board(&client, alice_board_id).await?.is_ship(15);
+    let tx_id = battleship::answer(&mut client, alice.id(), &game, is_hit, answer_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Bob shoots at cell 15, Alice claims a miss");
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Alice challenges the claim of Bob
+    // -------------------------------------------------------------------------
+    // Opening the cell takes a Merkle path to the root Bob joined with, so the only value Bob
+    // can open is the ship Bob placed, which contradicts the claim.
+    println!("\n[STEP 5] Alice challenges the miss Bob claimed");
+
+    let game = read_game(&client, game.id).await?;
+    let tx_id = battleship::challenge(&mut client, alice.id(), &game, 5, challenge_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let game = read_game(&client, game.id).await?;
+    let challenge = game.challenge.ok_or("the challenge was not recorded")?;
+    println!("Bob has until block {} to open cell 5", challenge.deadline);
+
+    let board = read_board(&client, bob_board_id).await?;
+    let Err(err) = battleship::open_cell(&mut client, bob.id(), &game, &board, open_script).await
+    else {
+        return Err("the game accepted a cell contradicting the claim".into());
+    };
+    println!("Bob cannot back the claim: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Slash Bob once the challenge period is over
+    // -------------------------------------------------------------------------
+    println!(
+        "\n[STEP 6] Waiting for block {} to slash Bob",
+        challenge.deadline
+    );
+    while client.get_sync_height().await? < challenge.deadline {
+        sleep(Duration::from_secs(3)).await;
+        client.sync_state().await?;
+    }
+
+    let tx_id = battleship::slash(&mut client, alice.id(), &game, slash_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 7: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 7] Checking the stakes went to Alice");
+
+    let game = read_game(&client, game.id).await?;
+    assert_eq!(game.winner, Some(alice.id()));
+    assert_eq!(game.challenge, None);
+
+    let record = client
+        .get_account(alice.id())
+        .await?
+        .ok_or("Alice is not tracked by the client")?;
+    let balance = record.account().vault().get_balance(faucet.id())?;
+    assert_eq!(balance, 2 * STAKE);
+    println!("Alice holds {balance} tokens: both stakes");
+
+    Ok(())
+}
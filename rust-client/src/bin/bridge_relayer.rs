@@ -0,0 +1,178 @@
+use miden_lib::account::auth::AuthRpoFalcon512;
+use rand::RngCore;
+use std::sync::Arc;
+
+use miden_client::{
+    account::component::{BasicFungibleFaucet, BasicWallet},
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{AccountBuilder, AccountStorageMode, AccountType},
+    asset::TokenSymbol,
+    Felt,
+};
+use rust_client::{
+    bridge_kit::{LockEvent, LockSource, MintRoutes, MockLockSource, ProcessedLocks},
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::wait_for_notes,
+};
+
+/// Chain id of the mocked source chain.
+const SOURCE_CHAIN: u64 = 11155111;
+/// Address of the locked token on the source chain.
+const SOURCE_TOKEN: &str = "0xfff9976782d46cc05630d1f6ebab18b2324d6b14";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("bridge_relayer")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Deploy the bridged token faucet and a recipient wallet
+    // -------------------------------------------------------------------------
+    // The relayer owns the faucet: it is the only party able to mint the bridged token.
+    println!("\n[STEP 1] Deploying the bridged token faucet and a recipient wallet");
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let faucet = AccountBuilder::new(init_seed)
+        .account_type(AccountType::FungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+        .with_component(
+            BasicFungibleFaucet::new(
+                TokenSymbol::new("WETH").unwrap(),
+                8,
+                Felt::new(1_000_000_000),
+            )
+            .unwrap(),
+        )
+        .build()
+        .unwrap();
+    client.add_account(&faucet, false).await?;
+    keystore.add_key(&key_pair).unwrap();
+    println!(
+        "Bridged token faucet: {}",
+        faucet.id().to_bech32(network.network_id())
+    );
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let recipient = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+        .with_component(BasicWallet)
+        .build()
+        .unwrap();
+    client.add_account(&recipient, false).await?;
+    keystore.add_key(&key_pair).unwrap();
+    println!(
+        "Recipient wallet: {}",
+        recipient.id().to_bech32(network.network_id())
+    );
+
+    let mut routes = MintRoutes::default();
+    routes.insert(SOURCE_CHAIN, SOURCE_TOKEN, faucet.id());
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Relay lock events
+    // -------------------------------------------------------------------------
+    // The mocked lock contract reports two deposits, then reports the first one again as a
+    // reorg would. Processed events are keyed per faucet, since a new faucet is a new bridge.
+    println!("\n[STEP 2] Relaying lock events from the source chain");
+
+    let lock = |nonce, amount| LockEvent {
+        source_chain: SOURCE_CHAIN,
+        nonce,
+        token: SOURCE_TOKEN.to_string(),
+        amount,
+        recipient: recipient.id(),
+    };
+    let mut source = MockLockSource::new([vec![lock(0, 250)], vec![lock(1, 100), lock(0, 250)]]);
+
+    let processed_path = format!("./bridge_processed_{}.json", faucet.id().to_hex());
+    let mut processed = ProcessedLocks::load(&processed_path)?;
+    let mut minted = 0;
+
+    while !source.is_exhausted() {
+        for event in source.poll().await? {
+            if processed.contains(&event) {
+                println!("Lock {} already minted, skipping", event.key());
+                continue;
+            }
+
+            let mint_request = routes.mint_request(&event, client.rng())?;
+            let faucet_id = routes.faucet_for(&event)?;
+            let tx_id = client.submit_with_prover(faucet_id, mint_request).await?;
+
+            // Record the event right after submitting, so a restart never mints it twice
+            processed.insert(&event);
+            processed.save(&processed_path)?;
+            minted += 1;
+
+            println!(
+                "Lock {}: minted {} to {}. TX: https://testnet.midenscan.com/tx/{:?}",
+                event.key(),
+                event.amount,
+                event.recipient.to_bech32(network.network_id()),
+                tx_id
+            );
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: The recipient consumes the minted notes
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Recipient consuming the bridged tokens");
+
+    wait_for_notes(&mut client, &network, recipient.id(), minted).await?;
+    let note_ids = client
+        .get_consumable_notes(Some(recipient.id()))
+        .await?
+        .iter()
+        .map(|(note, _)| note.id())
+        .collect();
+    let consume_request = TransactionRequestBuilder::new().build_consume_notes(note_ids)?;
+    client
+        .submit_with_prover(recipient.id(), consume_request)
+        .await?;
+
+    client.sync_state().await?;
+    let record = client.get_account(recipient.id()).await?.unwrap();
+    println!(
+        "Recipient balance: {} WETH base units",
+        record.account().vault().get_balance(faucet.id())?
+    );
+
+    Ok(())
+}
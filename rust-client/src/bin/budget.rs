@@ -0,0 +1,209 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{create_p2id_note, NoteType},
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client, Felt,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    budget::{self, bucket_id, Budget, Operation},
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+};
+
+/// Tokens Alice's budget starts with.
+const INCOME: u64 = 1_000;
+/// Buckets Alice splits the income into, and the tokens set aside in each.
+const BUCKETS: [(&str, u64); 2] = [("rent", 500), ("grocery", 300)];
+/// Rent Alice pays Bob, after moving the missing tokens from the groceries.
+const RENT: u64 = 550;
+
+/// Reads the current state of the budget `budget_id`.
+async fn read_budget(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    budget_id: AccountId,
+) -> Result<Budget, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(budget_id)
+        .await?
+        .ok_or("the budget is not tracked by the client")?;
+    Budget::from_account(record.account())
+}
+
+/// Prints every bucket of `budget` and its unallocated tokens.
+fn print_budget(budget: &Budget) {
+    for (name, _) in BUCKETS {
+        println!("{name:<12} {:>5} MID", budget.bucket(bucket_id(name)));
+    }
+    println!("{:<12} {:>5} MID", "unallocated", budget.unallocated());
+}
+
+/// Waits for a note to `account_id` and consumes it.
+async fn collect(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    account_id: AccountId,
+) -> Result<(), Box<dyn std::error::Error>> {
+    wait_for_notes(client, network, account_id, 1).await?;
+    let tx_id = consume_all_notes(client, account_id).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("budget")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the budget and fund it
+    // -------------------------------------------------------------------------
+    // The budget is a basic wallet with a budget component next to it: notes sending tokens
+    // to Alice land in the vault like in any other wallet.
+    println!("\n[STEP 1] Creating Alice's budget and paying in the income");
+
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let account = budget::create_budget_account(&mut client, &keystore, faucet.id()).await?;
+    let budget_id = account.id();
+    println!("Budget: {}", budget_id.to_bech32(network.network_id()));
+    println!("Bob:    {}", bob.id().to_bech32(network.network_id()));
+
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), INCOME)?,
+        budget_id,
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, budget_id).await?;
+    println!("Alice holds {INCOME} MID, all unallocated");
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Split the income into buckets
+    // -------------------------------------------------------------------------
+    // Allocating only writes storage: the tokens stay in the vault, the component records
+    // which bucket they belong to.
+    println!("\n[STEP 2] Allocating the income to buckets");
+
+    for (name, amount) in BUCKETS {
+        let operation = Operation::Allocate {
+            bucket: bucket_id(name),
+            amount,
+        };
+        let tx_id = budget::execute(&mut client, budget_id, &operation).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+    print_budget(&read_budget(&client, budget_id).await?);
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Pay the rent from its bucket
+    // -------------------------------------------------------------------------
+    // The rent went up: Alice moves the difference from the groceries, then pays Bob. Paying
+    // shrinks the bucket and moves the tokens out of the vault in the same procedure.
+    println!("\n[STEP 3] Paying {RENT} MID of rent to Bob");
+
+    let (rent, grocery) = (bucket_id("rent"), bucket_id("grocery"));
+    let reallocate = Operation::Reallocate {
+        from: grocery,
+        to: rent,
+        amount: RENT - BUCKETS[0].1,
+    };
+    let tx_id = budget::execute(&mut client, budget_id, &reallocate).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let payment = create_p2id_note(
+        budget_id,
+        bob.id(),
+        vec![FungibleAsset::new(faucet.id(), RENT)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let spend = Operation::Spend {
+        bucket: rent,
+        payment,
+    };
+    let tx_id = budget::execute(&mut client, budget_id, &spend).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, bob.id()).await?;
+    print_budget(&read_budget(&client, budget_id).await?);
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Overspending is rejected
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Spending more than a bucket holds");
+
+    let payment = create_p2id_note(
+        budget_id,
+        bob.id(),
+        vec![FungibleAsset::new(faucet.id(), 1)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let overspend = Operation::Spend {
+        bucket: rent,
+        payment,
+    };
+    let Err(err) = budget::execute(&mut client, budget_id, &overspend).await else {
+        return Err("the budget paid from an empty bucket".into());
+    };
+    println!("The rent bucket is empty: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Reading the budget back from storage and the vault");
+
+    let state = read_budget(&client, budget_id).await?;
+    let (_, groceries) = BUCKETS[1];
+    assert_eq!(state.balance, INCOME - RENT);
+    assert_eq!(state.bucket(rent), 0);
+    assert_eq!(state.bucket(grocery), groceries - (RENT - BUCKETS[0].1));
+    assert_eq!(state.unallocated(), INCOME - BUCKETS[0].1 - groceries);
+
+    let bob_record = client
+        .get_account(bob.id())
+        .await?
+        .ok_or("Bob is not tracked by the client")?;
+    assert_eq!(bob_record.account().vault().get_balance(faucet.id())?, RENT);
+    println!(
+        "Bob received {RENT} MID and Alice has {} MID left",
+        state.balance
+    );
+
+    Ok(())
+}
@@ -0,0 +1,302 @@
+use std::{fs, path::Path};
+use tokio::{
+    net::TcpStream,
+    time::{timeout, Duration},
+};
+
+use miden_client::{
+    account::{component::BasicFungibleFaucet, AccountId},
+    address::NetworkId,
+    rpc::{domain::account::FetchedAccount, Endpoint, GrpcClient, NodeRpcClient},
+};
+use rust_client::{
+    devnet,
+    known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH},
+    pragma,
+};
+
+const USAGE: &str = "usage: check_deps [testnet] [devnet]
+
+Checks that the accounts and services the tutorials depend on are live and compatible on
+each network (all networks by default). Exits with status 1 if any check fails.";
+
+/// Contract calling the oracle by id and procedure root.
+const ORACLE_READER_PATH: &str = "../masm/accounts/oracle_reader.masm";
+/// Known accounts label of the AggLayer bridge account, once one is deployed.
+const AGGLAYER_BRIDGE: &str = "agglayer.bridge";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where a dependency is found on a network.
+#[derive(Clone, Copy)]
+enum Source {
+    /// A fixed address, hardcoded in the tutorials.
+    Address(&'static str),
+    /// An account of the known accounts registry, e.g. deployed by `devnet_setup`.
+    Label(&'static str),
+    /// A service URL.
+    Url(&'static str),
+}
+
+/// What the dependency must be for the tutorials using it to work.
+#[derive(Clone, Copy)]
+enum Kind {
+    /// The Pragma oracle: a public account exporting `get_median`, with the id hardcoded in
+    /// `oracle_reader.masm`.
+    Oracle,
+    /// A public fungible faucet.
+    Faucet,
+    /// Any public account.
+    Contract,
+    /// A remote transaction prover accepting connections.
+    Prover,
+}
+
+struct Dependency {
+    name: &'static str,
+    used_by: &'static str,
+    kind: Kind,
+    testnet: Option<Source>,
+    devnet: Option<Source>,
+}
+
+const DEPENDENCIES: &[Dependency] = &[
+    Dependency {
+        name: "Pragma oracle",
+        used_by: "oracle_data_query, oracle_lending",
+        kind: Kind::Oracle,
+        testnet: Some(Source::Address(pragma::TESTNET_ORACLE)),
+        devnet: None,
+    },
+    Dependency {
+        name: "Public faucet",
+        used_by: "fund_account, devnet_setup",
+        kind: Kind::Faucet,
+        testnet: Some(Source::Label(devnet::FAUCET)),
+        devnet: None,
+    },
+    Dependency {
+        name: "Counter contract",
+        used_by: "counter_contract_increment, counter_contract_fpi, account_proof_read",
+        kind: Kind::Contract,
+        testnet: Some(Source::Address("mtst1arjemrxne8lj5qz4mg9c8mtyxg954483")),
+        devnet: None,
+    },
+    Dependency {
+        name: "Remote prover",
+        used_by: "delegated_prover, --prover",
+        kind: Kind::Prover,
+        testnet: Some(Source::Url("https://tx-prover.testnet.miden.io")),
+        devnet: Some(Source::Url("https://tx-prover.devnet.miden.io")),
+    },
+    Dependency {
+        name: "AggLayer bridge",
+        used_by: "bridge_relayer",
+        kind: Kind::Contract,
+        testnet: Some(Source::Label(AGGLAYER_BRIDGE)),
+        devnet: Some(Source::Label(AGGLAYER_BRIDGE)),
+    },
+];
+
+enum Status {
+    Ok(String),
+    Failed(String),
+    /// The dependency is not available on the network, or not configured locally.
+    Skipped(String),
+}
+
+impl Status {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Ok(_) => "ok",
+            Self::Failed(_) => "FAILED",
+            Self::Skipped(_) => "skipped",
+        }
+    }
+
+    fn detail(&self) -> &str {
+        match self {
+            Self::Ok(detail) | Self::Failed(detail) | Self::Skipped(detail) => detail,
+        }
+    }
+}
+
+/// Checks that a TCP connection to the host of `url` can be opened.
+async fn check_service(url: &str) -> Status {
+    let (default_port, rest) = match url.split_once("://") {
+        Some(("https", rest)) => (443, rest),
+        Some(("http", rest)) => (80, rest),
+        _ => return Status::Failed(format!("unsupported URL {url}")),
+    };
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => match port.parse() {
+            Ok(port) => (host, port),
+            Err(_) => return Status::Failed(format!("invalid port in {url}")),
+        },
+        None => (authority, default_port),
+    };
+
+    match timeout(CONNECT_TIMEOUT, TcpStream::connect((host, port))).await {
+        Ok(Ok(_)) => Status::Ok(format!("{host}:{port} accepts connections")),
+        Ok(Err(err)) => Status::Failed(format!("{host}:{port}: {err}")),
+        Err(_) => Status::Failed(format!(
+            "{host}:{port}: no answer within {CONNECT_TIMEOUT:?}"
+        )),
+    }
+}
+
+/// Fetches `account_id` from the node and checks it is what `kind` expects.
+async fn check_account(rpc: &GrpcClient, account_id: AccountId, kind: Kind) -> Status {
+    let account = match rpc.get_account_details(account_id).await {
+        Ok(FetchedAccount::Public(account, _)) => account,
+        Ok(FetchedAccount::Private(..)) => {
+            return Status::Failed("account is private, its state cannot be read".to_string())
+        }
+        Err(err) => return Status::Failed(format!("not found on the node: {err}")),
+    };
+
+    match kind {
+        Kind::Oracle => {
+            let root = pragma::get_median_root();
+            if !account
+                .code()
+                .procedure_roots()
+                .any(|proc_root| proc_root == root)
+            {
+                return Status::Failed("no longer exports get_median".to_string());
+            }
+
+            // oracle_reader.masm pushes the id with the prefix on top
+            let expected_push = format!(
+                "push.{}.{}",
+                account_id.suffix().as_int(),
+                account_id.prefix().as_felt().as_int()
+            );
+            match fs::read_to_string(Path::new(ORACLE_READER_PATH)) {
+                Ok(code) if code.contains(&expected_push) => {
+                    Status::Ok("exports get_median, matches oracle_reader.masm".to_string())
+                }
+                Ok(_) => Status::Failed(format!(
+                    "oracle_reader.masm calls another oracle, expected `{expected_push}`"
+                )),
+                Err(err) => Status::Failed(format!("cannot read {ORACLE_READER_PATH}: {err}")),
+            }
+        }
+        Kind::Faucet => match BasicFungibleFaucet::try_from(account.as_ref().clone()) {
+            Ok(faucet) => Status::Ok(format!(
+                "{} faucet, {} decimals",
+                faucet.symbol().to_string().unwrap_or_default(),
+                faucet.decimals()
+            )),
+            Err(err) => Status::Failed(format!("not a basic fungible faucet: {err}")),
+        },
+        Kind::Contract => Status::Ok(format!(
+            "public, {} procedures, nonce {}",
+            account.code().num_procedures(),
+            account.nonce()
+        )),
+        Kind::Prover => unreachable!("provers are located by URL"),
+    }
+}
+
+async fn check(
+    rpc: &GrpcClient,
+    network_id: NetworkId,
+    registry: &KnownAccounts,
+    dependency: &Dependency,
+    source: Source,
+) -> Status {
+    let account_id = match source {
+        Source::Url(url) => return check_service(url).await,
+        Source::Address(address) => match AccountId::from_bech32(address) {
+            Ok((found, _)) if found != network_id => {
+                return Status::Failed(format!("{address} is an address of {found:?}"))
+            }
+            Ok((_, account_id)) => account_id,
+            Err(err) => return Status::Failed(format!("invalid address {address}: {err}")),
+        },
+        Source::Label(label) => match registry.get(label) {
+            Some(account_id) => account_id,
+            None => return Status::Skipped(format!("no `{label}` in {DEFAULT_REGISTRY_PATH}")),
+        },
+    };
+    check_account(rpc, account_id, dependency.kind).await
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = rust_client::cli::positional_args();
+    let mut networks = vec![
+        ("testnet", Endpoint::testnet(), NetworkId::Testnet),
+        ("devnet", Endpoint::devnet(), NetworkId::Devnet),
+    ];
+    if args.iter().any(|arg| arg == "--help") {
+        println!("{USAGE}");
+        return Ok(());
+    }
+    if !args.is_empty() {
+        networks.retain(|(name, ..)| args.iter().any(|arg| arg == name));
+        if networks.is_empty() {
+            println!("{USAGE}");
+            return Ok(());
+        }
+    }
+
+    let registry = KnownAccounts::load(DEFAULT_REGISTRY_PATH)?;
+    let timeout_ms = 10_000;
+    let mut failures = 0;
+
+    println!(
+        "{:<9} {:<17} {:<8} DETAIL",
+        "NETWORK", "DEPENDENCY", "STATUS"
+    );
+    for (name, endpoint, network_id) in &networks {
+        let rpc = GrpcClient::new(endpoint, timeout_ms);
+
+        // Without a node nothing else on the network can be checked
+        let node = match rpc.get_block_header_by_number(None, false).await {
+            Ok((header, _)) => Status::Ok(format!("{endpoint}, block {}", header.block_num())),
+            Err(err) => Status::Failed(format!("{endpoint}: {err}")),
+        };
+        println!(
+            "{name:<9} {:<17} {:<8} {}",
+            "Node",
+            node.label(),
+            node.detail()
+        );
+        if let Status::Failed(_) = node {
+            failures += 1;
+            continue;
+        }
+
+        for dependency in DEPENDENCIES {
+            let source = match network_id {
+                NetworkId::Testnet => dependency.testnet,
+                NetworkId::Devnet => dependency.devnet,
+                _ => None,
+            };
+            let status = match source {
+                Some(source) => check(&rpc, *network_id, &registry, dependency, source).await,
+                None => Status::Skipped(format!("not available on {name}")),
+            };
+            if let Status::Failed(_) = status {
+                failures += 1;
+            }
+            println!(
+                "{name:<9} {:<17} {:<8} {} (used by {})",
+                dependency.name,
+                status.label(),
+                status.detail(),
+                dependency.used_by
+            );
+        }
+    }
+
+    if failures > 0 {
+        println!("\n{failures} check(s) failed");
+        std::process::exit(1);
+    }
+    println!("\nAll dependencies are ready");
+    Ok(())
+}
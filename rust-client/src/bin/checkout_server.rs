@@ -0,0 +1,305 @@
+//! A merchant checkout server taking payments in P2ID notes.
+//!
+//! ```text
+//! cargo run --release --features defi --bin checkout_server -- [LISTEN_ADDR] [WEBHOOK_URL]
+//! curl -X POST http://127.0.0.1:8081/orders \
+//!     -H 'content-type: application/json' -d '{"amount": 250}'
+//! curl http://127.0.0.1:8081/orders/1
+//! ```
+//!
+//! Creating an order returns a payment request for the shared token of `devnet_setup`, to be
+//! paid to the merchant account the server creates on startup. The main task owns the client:
+//! it watches the merchant account for incoming notes, marks the order a note pays as paid,
+//! consumes the note and posts the paid order to `WEBHOOK_URL`, if one is given.
+
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use miden_client::{
+    account::AccountId,
+    address::NetworkId,
+    asset::FungibleAsset,
+    builder::ClientBuilder,
+    note::{Note, NoteId},
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    devnet,
+    format::AssetFormatter,
+    helpers::create_basic_account,
+    known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH},
+    monitor::NoteMonitor,
+    payment_request::PaymentRequest,
+};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:8081";
+/// Time between two checks of the merchant account for incoming notes.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Deserialize)]
+struct NewOrder {
+    /// Price in base units of the shared token.
+    amount: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum OrderStatus {
+    Pending,
+    Paid,
+}
+
+/// An order as returned by the API and posted to the webhook.
+#[derive(Clone, Serialize)]
+struct OrderView {
+    order_id: u32,
+    price: String,
+    payment_request: String,
+    status: OrderStatus,
+    note_id: Option<String>,
+}
+
+struct Order {
+    request: PaymentRequest,
+    price: String,
+    paid_by: Option<NoteId>,
+}
+
+impl Order {
+    fn view(&self) -> OrderView {
+        OrderView {
+            order_id: self.request.reference,
+            price: self.price.clone(),
+            payment_request: self.request.to_string(),
+            status: match self.paid_by {
+                Some(_) => OrderStatus::Paid,
+                None => OrderStatus::Pending,
+            },
+            note_id: self.paid_by.map(|note_id| note_id.to_hex()),
+        }
+    }
+}
+
+/// State shared by the HTTP handlers and the payment monitor.
+struct Checkout {
+    /// Network of the addresses in the payment requests.
+    network_id: NetworkId,
+    merchant_id: AccountId,
+    faucet_id: AccountId,
+    formatter: AssetFormatter,
+    /// Orders by id, which is also the reference of their payment request.
+    orders: Mutex<BTreeMap<u32, Order>>,
+}
+
+impl Checkout {
+    /// Marks the pending order paid by `note` as paid and returns it.
+    fn settle(&self, note: &Note) -> Option<OrderView> {
+        let mut orders = self.orders.lock().unwrap();
+        let order = orders
+            .values_mut()
+            .find(|order| order.paid_by.is_none() && order.request.is_paid_by(note))?;
+        order.paid_by = Some(note.id());
+        Some(order.view())
+    }
+}
+
+type ApiError = (StatusCode, String);
+
+/// `POST /orders`: opens an order and returns its payment request.
+async fn create_order(
+    State(checkout): State<Arc<Checkout>>,
+    Json(new_order): Json<NewOrder>,
+) -> Result<(StatusCode, Json<OrderView>), ApiError> {
+    if new_order.amount == 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "amount must be positive".to_string(),
+        ));
+    }
+    let asset = FungibleAsset::new(checkout.faucet_id, new_order.amount)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
+
+    let mut orders = checkout.orders.lock().unwrap();
+    let order_id = orders.len() as u32 + 1;
+    let order = Order {
+        request: PaymentRequest::new(
+            checkout.network_id.clone(),
+            checkout.merchant_id,
+            asset,
+            order_id,
+        ),
+        price: checkout
+            .formatter
+            .format_amount(checkout.faucet_id, new_order.amount),
+        paid_by: None,
+    };
+    let view = order.view();
+    orders.insert(order_id, order);
+
+    println!("Order {order_id} opened for {}", view.price);
+    Ok((StatusCode::CREATED, Json(view)))
+}
+
+/// `GET /orders/{id}`: returns the order and whether it is paid.
+async fn get_order(
+    State(checkout): State<Arc<Checkout>>,
+    Path(order_id): Path<u32>,
+) -> Result<Json<OrderView>, ApiError> {
+    checkout
+        .orders
+        .lock()
+        .unwrap()
+        .get(&order_id)
+        .map(|order| Json(order.view()))
+        .ok_or((StatusCode::NOT_FOUND, format!("no order {order_id}")))
+}
+
+/// Posts the paid `order` to the merchant's webhook.
+async fn notify(
+    http: &reqwest::Client,
+    webhook_url: &str,
+    order: &OrderView,
+) -> Result<(), reqwest::Error> {
+    http.post(webhook_url)
+        .json(order)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = rust_client::cli::positional_args();
+    let listen_addr: SocketAddr = args
+        .first()
+        .map_or(DEFAULT_LISTEN_ADDR, String::as_str)
+        .parse()?;
+    let webhook_url = args.get(1).cloned();
+
+    let registry = KnownAccounts::load(DEFAULT_REGISTRY_PATH)?;
+    let faucet_id = registry
+        .get(devnet::FAUCET)
+        .ok_or("no shared faucet registered, run `devnet_setup` first")?;
+
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("checkout_server")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client.clone())
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // Create the merchant account and start the HTTP server
+    // -------------------------------------------------------------------------
+    let merchant = create_basic_account(&mut client, &keystore).await?;
+    let mut formatter = AssetFormatter::new(network.network_id());
+    formatter.load_token(rpc_client.as_ref(), faucet_id).await?;
+
+    let checkout = Arc::new(Checkout {
+        network_id: network.network_id(),
+        merchant_id: merchant.id(),
+        faucet_id,
+        formatter,
+        orders: Mutex::new(BTreeMap::new()),
+    });
+    let app = Router::new()
+        .route("/orders", post(create_order))
+        .route("/orders/{id}", get(get_order))
+        .with_state(checkout.clone());
+
+    let listener = tokio::net::TcpListener::bind(listen_addr).await?;
+    println!(
+        "Merchant {} taking orders on http://{listen_addr}/orders, Ctrl+C to stop",
+        merchant.id().to_bech32(network.network_id())
+    );
+    tokio::spawn(async move {
+        if let Err(err) = axum::serve(listener, app).await {
+            eprintln!("HTTP server stopped: {err}");
+        }
+    });
+
+    // -------------------------------------------------------------------------
+    // Watch the merchant account for payments
+    // -------------------------------------------------------------------------
+    // A note settles the first pending order it pays. Notes paying no order are left alone,
+    // so that they can be refunded or settled by hand.
+    let http = reqwest::Client::new();
+    let mut monitor = NoteMonitor::new(merchant.id());
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+
+        let notes = match monitor.poll(&mut client).await {
+            Ok(notes) => notes,
+            Err(err) => {
+                eprintln!("Could not check for payments: {err}");
+                continue;
+            }
+        };
+        for note in notes {
+            let Some(order) = checkout.settle(&note) else {
+                println!("Note {} pays no pending order", note.id().to_hex());
+                continue;
+            };
+            println!(
+                "Order {} paid by note {}",
+                order.order_id,
+                note.id().to_hex()
+            );
+
+            let request = TransactionRequestBuilder::new().build_consume_notes(vec![note.id()])?;
+            if let Err(err) = client.submit_with_prover(merchant.id(), request).await {
+                eprintln!(
+                    "Could not collect the payment of order {}: {err}",
+                    order.order_id
+                );
+            }
+            if let Some(webhook_url) = &webhook_url {
+                if let Err(err) = notify(&http, webhook_url, &order).await {
+                    eprintln!(
+                        "Could not notify the webhook of order {}: {err}",
+                        order.order_id
+                    );
+                }
+            }
+        }
+    }
+
+    println!("Checkout stopped");
+    Ok(())
+}
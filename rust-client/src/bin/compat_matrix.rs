@@ -0,0 +1,271 @@
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use rust_client::compat::{self, ApiChange, Chapter};
+use semver::Version;
+use serde::Deserialize;
+
+const USAGE: &str = "usage: compat_matrix [CHAPTER...]
+
+Checks that each chapter crate compiles against every miden-client minor release in the range
+it declares in `package.metadata.tutorial` (all chapters by default), and lists the APIs which
+changed in the releases it does not compile against. Exits with status 1 if any check fails.";
+
+/// Versions of miden-client published on crates.io.
+const CRATES_IO_URL: &str = "https://crates.io/api/v1/crates/miden-client";
+/// Target directory of the checks, kept between runs so that each release only builds once.
+const TARGET_DIR: &str = "./target/compat";
+
+#[derive(Deserialize)]
+struct CrateVersions {
+    versions: Vec<PublishedVersion>,
+}
+
+#[derive(Deserialize)]
+struct PublishedVersion {
+    num: String,
+    yanked: bool,
+}
+
+enum Status {
+    Ok,
+    /// The chapter does not compile; the APIs it uses which changed in the release.
+    Broken(Vec<ApiChange>),
+    /// Cargo failed before compiling anything, e.g. because no release satisfies the pins.
+    Failed(String),
+}
+
+impl Status {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Broken(_) | Self::Failed(_) => "FAILED",
+        }
+    }
+}
+
+/// Runs cargo with `args` in `dir`.
+fn cargo(dir: &Path, args: &[&str]) -> io::Result<Output> {
+    // Under `cargo run`, use the same cargo as the one running the check
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    Command::new(cargo).args(args).current_dir(dir).output()
+}
+
+/// Copies the workspace at `from` to `to`, without build outputs, run state or lockfile.
+fn copy_workspace(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if name == "target" || name == "state" || name == "Cargo.lock" {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            copy_workspace(&entry.path(), &to.join(&name))?;
+        } else {
+            fs::copy(entry.path(), to.join(&name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Fetches the published, non-yanked miden-client versions.
+async fn published_versions() -> Result<Vec<Version>, Box<dyn std::error::Error>> {
+    // crates.io rejects requests without a user agent
+    let response: CrateVersions = reqwest::Client::new()
+        .get(CRATES_IO_URL)
+        .header(reqwest::header::USER_AGENT, "miden-tutorials compat_matrix")
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(response
+        .versions
+        .into_iter()
+        .filter(|version| !version.yanked)
+        .filter_map(|version| Version::parse(&version.num).ok())
+        .collect())
+}
+
+/// Returns the first error cargo reported on `stderr`, or `fallback` if none is recognizable.
+fn first_error(stderr: &[u8], fallback: &str) -> String {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .find(|line| line.starts_with("error"))
+        .unwrap_or(fallback)
+        .to_string()
+}
+
+/// Pins the miden-client crates of every member manifest of the workspace copy at `dir` to
+/// `version`, and the VM crates to `vm_versions`. `manifests` are the original manifests, by
+/// their path relative to the workspace.
+fn pin_manifests(
+    dir: &Path,
+    manifests: &[(PathBuf, String)],
+    version: &Version,
+    vm_versions: &BTreeMap<String, Version>,
+) -> io::Result<()> {
+    for (path, manifest) in manifests {
+        fs::write(
+            dir.join(path),
+            compat::pin_lockstep_versions(manifest, version, vm_versions),
+        )?;
+    }
+    // Resolve from scratch against the pinned release
+    let _ = fs::remove_file(dir.join("Cargo.lock"));
+    Ok(())
+}
+
+/// Pins the workspace copy at `dir` to `version`: first the crates numbered like miden-client,
+/// then the VM crates to the versions those depend on, as cargo resolves them.
+fn pin_release(
+    dir: &Path,
+    manifests: &[(PathBuf, String)],
+    version: &Version,
+) -> io::Result<Result<(), String>> {
+    pin_manifests(dir, manifests, version, &BTreeMap::new())?;
+    let metadata = cargo(dir, &["metadata", "--format-version", "1"])?;
+    if !metadata.status.success() {
+        return Ok(Err(first_error(&metadata.stderr, "cargo metadata failed")));
+    }
+    let vm_versions = match compat::vm_versions(&String::from_utf8_lossy(&metadata.stdout)) {
+        Ok(vm_versions) => vm_versions,
+        Err(err) => return Ok(Err(err.to_string())),
+    };
+    pin_manifests(dir, manifests, version, &vm_versions)?;
+    Ok(Ok(()))
+}
+
+/// Checks `chapter` in the workspace copy at `dir`, whose dependencies are already pinned.
+fn check(dir: &Path, target_dir: &Path, chapter: &Chapter) -> io::Result<Status> {
+    let target_dir = target_dir.to_string_lossy();
+    let output = cargo(
+        dir,
+        &[
+            "check",
+            "--bins",
+            "--message-format=json",
+            "--target-dir",
+            &target_dir,
+            "-p",
+            &chapter.package,
+        ],
+    )?;
+    if output.status.success() {
+        return Ok(Status::Ok);
+    }
+
+    let changes = compat::api_changes(&String::from_utf8_lossy(&output.stdout));
+    if !changes.is_empty() {
+        return Ok(Status::Broken(changes));
+    }
+    Ok(Status::Failed(first_error(
+        &output.stderr,
+        "cargo check failed",
+    )))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = rust_client::cli::positional_args();
+    if args.iter().any(|arg| arg == "--help") {
+        println!("{USAGE}");
+        return Ok(());
+    }
+
+    let workspace = env::current_dir()?;
+    let metadata = cargo(
+        &workspace,
+        &["metadata", "--format-version", "1", "--no-deps"],
+    )?;
+    if !metadata.status.success() {
+        return Err(String::from_utf8_lossy(&metadata.stderr)
+            .into_owned()
+            .into());
+    }
+    let metadata = String::from_utf8_lossy(&metadata.stdout);
+    let mut chapters = compat::chapters(&metadata)?;
+    if !args.is_empty() {
+        chapters.retain(|chapter| args.contains(&chapter.package));
+        if chapters.is_empty() {
+            println!("{USAGE}");
+            return Ok(());
+        }
+    }
+
+    // Group the checks by release, so that the workspace is pinned once per release
+    let published = published_versions().await?;
+    let mut matrix: BTreeMap<Version, Vec<&Chapter>> = BTreeMap::new();
+    for chapter in &chapters {
+        let versions = compat::versions_to_check(&chapter.supported, published.iter().cloned());
+        if versions.is_empty() {
+            return Err(format!(
+                "no published miden-client release matches {} for {}",
+                chapter.supported, chapter.package
+            )
+            .into());
+        }
+        for version in versions {
+            matrix.entry(version).or_default().push(chapter);
+        }
+    }
+
+    let copy = tempfile::tempdir()?;
+    copy_workspace(&workspace, copy.path())?;
+    // The root manifest and the manifest of every member, as the copies are rewritten per release
+    let root = workspace.canonicalize()?;
+    let mut manifests = Vec::new();
+    for path in compat::member_manifests(&metadata)? {
+        let relative = path.canonicalize()?.strip_prefix(&root)?.to_path_buf();
+        manifests.push((relative, fs::read_to_string(&path)?));
+    }
+    let target_dir = workspace.join(TARGET_DIR);
+    let mut failures = 0;
+
+    println!("{:<36} {:<13} STATUS", "CHAPTER", "MIDEN-CLIENT");
+    for (version, chapters) in &matrix {
+        let pinned = pin_release(copy.path(), &manifests, version)?;
+
+        for chapter in chapters {
+            let status = match &pinned {
+                Ok(()) => check(copy.path(), &target_dir, chapter)?,
+                Err(error) => Status::Failed(error.clone()),
+            };
+            println!(
+                "{:<36} {:<13} {}",
+                chapter.package,
+                version.to_string(),
+                status.label()
+            );
+            match status {
+                Status::Ok => {}
+                Status::Broken(changes) => {
+                    failures += 1;
+                    println!("    APIs that changed in miden-client {version}:");
+                    for change in changes {
+                        println!("    - {change}");
+                    }
+                }
+                Status::Failed(error) => {
+                    failures += 1;
+                    println!("    {error}");
+                }
+            }
+        }
+    }
+
+    if failures > 0 {
+        println!(
+            "\n{failures} check(s) failed: update the chapters, or narrow the miden-client range \
+             they declare"
+        );
+        std::process::exit(1);
+    }
+    println!("\nEvery chapter builds with every release it declares");
+    Ok(())
+}
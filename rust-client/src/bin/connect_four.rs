@@ -0,0 +1,123 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId, builder::ClientBuilder, keystore::FilesystemKeyStore, rpc::GrpcClient,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    connect_four::{self, Disc, Game},
+    context::TutorialContext,
+    helpers::{create_basic_account, wait_for_tx},
+};
+
+/// Columns Alice and Bob drop their discs into, Alice first: Alice lines up four red discs on
+/// the bottom row.
+const MOVES: [usize; 7] = [3, 3, 4, 4, 2, 2, 5];
+
+/// Reads the current state of the game `game_id`.
+async fn read_game(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    game_id: AccountId,
+) -> Result<Game, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(game_id)
+        .await?
+        .ok_or("the game is not tracked by the client")?;
+    Game::from_account(record.account())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("connect_four")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the players and deploy the game
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice and Bob and deploying their game");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice (red):    {}",
+        alice.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Bob (yellow):   {}",
+        bob.id().to_bech32(network.network_id())
+    );
+
+    let account = connect_four::create_game(&mut client, alice.id(), bob.id()).await?;
+    let game_id = account.id();
+    println!("Game:           {game_id}");
+
+    let move_script = connect_four::compile_move_script(&client)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Drop the discs
+    // -------------------------------------------------------------------------
+    // Each move updates one packed column: the contract unpacks both board words into memory,
+    // looks for a line through the new disc and packs them back.
+    println!("\n[STEP 2] Alice and Bob take turns");
+
+    for column in MOVES {
+        let game = read_game(&client, game_id).await?;
+        let disc = game.next_disc().ok_or("the game ended early")?;
+        let player = game.player(disc);
+
+        let tx_id =
+            connect_four::drop_disc(&mut client, &game, player, column, move_script.clone())
+                .await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        println!("{disc} drops a disc into column {column}");
+
+        // The player who just moved cannot move again
+        if game.moves == 0 {
+            let game = read_game(&client, game_id).await?;
+            let Err(err) =
+                connect_four::drop_disc(&mut client, &game, player, 0, move_script.clone()).await
+            else {
+                return Err("the game accepted two moves in a row".into());
+            };
+            println!("Alice cannot move twice in a row: {err}");
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Reading the board back from storage");
+
+    let game = read_game(&client, game_id).await?;
+    let (low, high) = game.board.to_words();
+    println!("Columns 0 to 3: {low:?}");
+    println!("Columns 4 to 6: {high:?}");
+    println!("{}", game.board);
+
+    assert_eq!(game.winner, Some(Disc::Red));
+    println!("Alice wins after {} moves", game.moves);
+
+    Ok(())
+}
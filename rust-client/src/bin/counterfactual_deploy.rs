@@ -0,0 +1,193 @@
+use miden_lib::account::auth::AuthRpoFalcon512;
+use rand::RngCore;
+use std::sync::Arc;
+
+use miden_client::{
+    account::{
+        component::{BasicFungibleFaucet, BasicWallet},
+        Account,
+    },
+    asset::{Asset, FungibleAsset},
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    note::{create_p2id_note, NoteTag, NoteType},
+    rpc::{GrpcClient, NodeRpcClient},
+    transaction::{OutputNote, TransactionRequestBuilder},
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{AccountBuilder, AccountStorageMode, AccountType},
+    asset::TokenSymbol,
+    Felt, Word,
+};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{create_basic_account, wait_for_notes},
+};
+
+/// Number of deposits sent to the account before it exists.
+const NUM_DEPOSITS: u64 = 2;
+
+/// Builds the account a factory would deploy for `owner`.
+///
+/// The id is derived from the seed and from the commitments to the code and initial storage,
+/// so the same inputs always give the same id, and anyone knowing them can compute it without
+/// talking to the node.
+fn counterfactual_account(seed: [u8; 32], owner_commitment: Word) -> Account {
+    AccountBuilder::new(seed)
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(owner_commitment))
+        .with_component(BasicWallet)
+        .build()
+        .unwrap()
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("counterfactual_deploy")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client.clone())
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Compute the account id before deployment
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Computing the id of an account that does not exist yet");
+
+    let mut seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut seed);
+    let owner_key = AuthSecretKey::new_rpo_falcon512();
+    let owner_commitment = owner_key.public_key().to_commitment();
+
+    let account = counterfactual_account(seed, owner_commitment);
+    let predicted_id = account.id();
+    println!(
+        "Predicted id: {}",
+        predicted_id.to_bech32(network.network_id())
+    );
+
+    // Same inputs, same id. Any other owner (i.e. other initial storage) moves the account
+    // elsewhere, which is what makes the address safe to fund before deployment.
+    assert_eq!(
+        counterfactual_account(seed, owner_commitment).id(),
+        predicted_id
+    );
+    let other_owner = AuthSecretKey::new_rpo_falcon512();
+    assert_ne!(
+        counterfactual_account(seed, other_owner.public_key().to_commitment()).id(),
+        predicted_id
+    );
+    println!("Seed to keep for deployment: {}", hex_encode(&seed));
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Fund the account counterfactually
+    // -------------------------------------------------------------------------
+    // Notes can target any id, whether or not an account exists behind it. The client only
+    // syncs notes whose tags it tracks, so it starts tracking the tag of the predicted id now
+    // to pick up the deposits before the account is added to it.
+    println!("\n[STEP 2] Sending {NUM_DEPOSITS} deposits to the predicted id");
+
+    client
+        .add_note_tag(NoteTag::from_account_id(predicted_id))
+        .await?;
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let faucet = AccountBuilder::new(init_seed)
+        .account_type(AccountType::FungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+        .with_component(
+            BasicFungibleFaucet::new(TokenSymbol::new("MID").unwrap(), 8, Felt::new(1_000_000))
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    client.add_account(&faucet, false).await?;
+    keystore.add_key(&key_pair).unwrap();
+
+    for i in 1..=NUM_DEPOSITS {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet.id(), i * 100).unwrap(),
+            predicted_id,
+            NoteType::Public,
+            client.rng(),
+        )?;
+        let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+        println!("Deposit {i}: https://testnet.midenscan.com/tx/{:?}", tx_id);
+    }
+
+    // The node knows nothing about the account yet
+    let on_chain = rpc_client.get_account_details(predicted_id).await.is_ok();
+    println!("Account known to the node before deployment: {on_chain}");
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Deploy the account and sweep the deposits
+    // -------------------------------------------------------------------------
+    // The first transaction of a new account deploys it. Here that transaction consumes the
+    // deposits and forwards them to a treasury in one go.
+    println!("\n[STEP 3] Deploying the account and sweeping the deposits");
+
+    let treasury = create_basic_account(&mut client, &keystore).await?;
+
+    client.add_account(&account, false).await?;
+    keystore.add_key(&owner_key).unwrap();
+    wait_for_notes(&mut client, &network, predicted_id, NUM_DEPOSITS as usize).await?;
+
+    let deposits = client.get_consumable_notes(Some(predicted_id)).await?;
+    let total: u64 = deposits
+        .iter()
+        .flat_map(|(note, _)| note.assets().iter())
+        .filter_map(|asset| match asset {
+            Asset::Fungible(fungible) => Some(fungible.amount()),
+            _ => None,
+        })
+        .sum();
+    let sweep_note = create_p2id_note(
+        predicted_id,
+        treasury.id(),
+        vec![FungibleAsset::new(faucet.id(), total)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .authenticated_input_notes(deposits.iter().map(|(note, _)| (note.id(), None)))
+        .own_output_notes(vec![OutputNote::Full(sweep_note)])
+        .build()?;
+    let tx_id = client.submit_with_prover(predicted_id, request).await?;
+    println!(
+        "Deployed {} and swept {total} tokens to the treasury. View transaction on MidenScan: \
+         https://testnet.midenscan.com/tx/{:?}",
+        predicted_id.to_bech32(network.network_id()),
+        tx_id
+    );
+
+    Ok(())
+}
+
+/// Encodes bytes as lowercase hex.
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -0,0 +1,270 @@
+use rand::rngs::StdRng;
+use std::{sync::Arc, time::Duration};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    crowdfund::{self, Campaign, CampaignOutcome},
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+};
+use tokio::time::sleep;
+
+/// Tokens minted to each contributor.
+const BUDGET: u64 = 100;
+/// Blocks during which the campaigns accept contributions.
+const DURATION_BLOCKS: u32 = 20;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+/// Reads the current tally of the campaign `campaign_id`.
+async fn read_campaign(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    campaign_id: AccountId,
+) -> Result<Campaign, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(campaign_id)
+        .await?
+        .ok_or("the campaign is not tracked by the client")?;
+    Campaign::from_account(record.account())
+}
+
+/// Returns what `contributor` can still reclaim from the campaign `campaign_id`.
+async fn contribution_of(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    campaign_id: AccountId,
+    contributor: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(campaign_id)
+        .await?
+        .ok_or("the campaign is not tracked by the client")?;
+    crowdfund::contribution_of(record.account(), contributor)
+}
+
+/// Mints `amount` tokens of `faucet_id` to `account_id`, which consumes them.
+async fn fund(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    faucet_id: AccountId,
+    account_id: AccountId,
+    amount: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet_id, amount)?,
+        account_id,
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet_id, mint_request).await?;
+    wait_for_tx(client, tx_id).await?;
+    wait_for_notes(client, network, account_id, 1).await?;
+    let tx_id = consume_all_notes(client, account_id).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+/// Waits for the payout of a campaign to `account_id` and consumes it.
+async fn collect(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    account_id: AccountId,
+) -> Result<(), Box<dyn std::error::Error>> {
+    wait_for_notes(client, network, account_id, 1).await?;
+    let tx_id = consume_all_notes(client, account_id).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("crowdfund_example")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the creator, the contributors and the currency
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and assets");
+
+    let creator = create_basic_account(&mut client, &keystore).await?;
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!("Creator: {}", creator.id().to_bech32(network.network_id()));
+    println!("Alice:   {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:     {}", bob.id().to_bech32(network.network_id()));
+
+    let usd = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+    fund(&mut client, &network, usd.id(), alice.id(), BUDGET).await?;
+    fund(&mut client, &network, usd.id(), bob.id(), BUDGET).await?;
+    println!("Alice and Bob hold {BUDGET} USD each");
+
+    let contribute_script =
+        crowdfund::compile_note_script(&client, crowdfund::CONTRIBUTE_NOTE_PATH)?;
+    let withdraw_script = crowdfund::compile_note_script(&client, crowdfund::WITHDRAW_NOTE_PATH)?;
+    let reclaim_script = crowdfund::compile_note_script(&client, crowdfund::RECLAIM_NOTE_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: The creator starts two campaigns
+    // -------------------------------------------------------------------------
+    // Each campaign stores the creator, the currency, the goal and the deadline. The first one
+    // asks for little enough to be funded, the second one for more than Alice and Bob can give.
+    let deadline = client.get_sync_height().await?.as_u32() + DURATION_BLOCKS;
+    println!("\n[STEP 2] Starting two campaigns ending at block {deadline}");
+
+    let account =
+        crowdfund::create_campaign(&mut client, creator.id(), usd.id(), 100, deadline.into())
+            .await?;
+    let funded = Campaign::from_account(&account)?;
+    let account =
+        crowdfund::create_campaign(&mut client, creator.id(), usd.id(), 500, deadline.into())
+            .await?;
+    let failed = Campaign::from_account(&account)?;
+    println!("Campaign {} raises 100 USD", funded.id);
+    println!("Campaign {} raises 500 USD", failed.id);
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Alice and Bob contribute to both campaigns
+    // -------------------------------------------------------------------------
+    // Contributions stay in the vault of the campaign, which tallies them per contributor.
+    println!("\n[STEP 3] Alice and Bob contribute");
+
+    for (contributor, campaign, amount) in [
+        (alice.id(), &funded, 60),
+        (bob.id(), &funded, 50),
+        (alice.id(), &failed, 30),
+        (bob.id(), &failed, 20),
+    ] {
+        let tx_id = crowdfund::contribute(
+            &mut client,
+            contributor,
+            campaign,
+            amount,
+            contribute_script.clone(),
+        )
+        .await?;
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+
+    let funded = read_campaign(&client, funded.id).await?;
+    let failed = read_campaign(&client, failed.id).await?;
+    println!(
+        "First campaign raised {} of {} USD",
+        funded.raised, funded.goal
+    );
+    println!(
+        "Second campaign raised {} of {} USD",
+        failed.raised, failed.goal
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Wait for the deadline
+    // -------------------------------------------------------------------------
+    // The block of a transaction is the block the client last synced to, so the campaigns can
+    // only be settled once the client has seen the deadline.
+    println!("\n[STEP 4] Waiting for block {deadline}");
+
+    while funded.outcome_at(client.get_sync_height().await?) == CampaignOutcome::Running {
+        sleep(Duration::from_secs(3)).await;
+        client.sync_state().await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 5: The creator withdraws the funded campaign
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] The creator withdraws the first campaign");
+
+    let Err(err) =
+        crowdfund::reclaim(&mut client, alice.id(), &funded, 60, reclaim_script.clone()).await
+    else {
+        return Err("a contribution to a funded campaign was reclaimed".into());
+    };
+    println!("Alice cannot reclaim her contribution: {err}");
+
+    let tx_id = crowdfund::withdraw(&mut client, &funded, withdraw_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, creator.id()).await?;
+    println!(
+        "The creator received {} USD",
+        balance(&client, creator.id(), usd.id()).await?
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Alice and Bob reclaim their contributions to the failed campaign
+    // -------------------------------------------------------------------------
+    // The contract pays back exactly what each of them contributed, as stored in its map.
+    println!("\n[STEP 6] Alice and Bob reclaim the second campaign");
+
+    for contributor in [alice.id(), bob.id()] {
+        let amount = contribution_of(&client, failed.id, contributor).await?;
+        let tx_id = crowdfund::reclaim(
+            &mut client,
+            contributor,
+            &failed,
+            amount,
+            reclaim_script.clone(),
+        )
+        .await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        collect(&mut client, &network, contributor).await?;
+        println!("{contributor} got {amount} USD back");
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 7: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 7] Checking balances");
+
+    assert!(read_campaign(&client, funded.id).await?.withdrawn);
+    assert_eq!(contribution_of(&client, failed.id, alice.id()).await?, 0);
+    assert_eq!(contribution_of(&client, failed.id, bob.id()).await?, 0);
+    assert_eq!(balance(&client, creator.id(), usd.id()).await?, 110);
+    assert_eq!(balance(&client, alice.id(), usd.id()).await?, BUDGET - 60);
+    assert_eq!(balance(&client, bob.id(), usd.id()).await?, BUDGET - 50);
+    assert_eq!(balance(&client, failed.id, usd.id()).await?, 0);
+    println!("The creator got the funded campaign, the failed one was refunded in full");
+
+    Ok(())
+}
@@ -0,0 +1,238 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    treasury::{self, Treasury},
+};
+
+/// Yes-weight a proposal has to exceed, out of the 100 governance tokens minted.
+const THRESHOLD: u64 = 50;
+/// Blocks during which a proposal accepts votes.
+const VOTING_PERIOD: u32 = 50;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+/// Reads the current proposals of the treasury `treasury_id`.
+async fn read_treasury(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    treasury_id: AccountId,
+) -> Result<Treasury, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(treasury_id)
+        .await?
+        .ok_or("the treasury is not tracked by the client")?;
+    Treasury::from_account(record.account())
+}
+
+/// Mints `amount` tokens of `faucet_id` to `account_id`, which consumes them.
+async fn fund(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    faucet_id: AccountId,
+    account_id: AccountId,
+    amount: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet_id, amount)?,
+        account_id,
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet_id, mint_request).await?;
+    wait_for_tx(client, tx_id).await?;
+    wait_for_notes(client, network, account_id, 1).await?;
+    let tx_id = consume_all_notes(client, account_id).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("dao_treasury")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the holders and distribute the governance token
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and assets");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let carol = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+    println!("Carol: {}", carol.id().to_bech32(network.network_id()));
+
+    let gov = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("GOV").unwrap()).await?;
+    let usd = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+    for (holder, amount) in [(alice.id(), 50), (bob.id(), 30), (carol.id(), 20)] {
+        fund(&mut client, &network, gov.id(), holder, amount).await?;
+        println!("{holder} holds {amount} GOV");
+    }
+    fund(&mut client, &network, usd.id(), alice.id(), 100).await?;
+
+    let deposit_script = treasury::compile_note_script(&client, treasury::DEPOSIT_NOTE_PATH)?;
+    let propose_script = treasury::compile_note_script(&client, treasury::PROPOSE_NOTE_PATH)?;
+    let vote_script = treasury::compile_note_script(&client, treasury::VOTE_NOTE_PATH)?;
+    let execute_script = treasury::compile_note_script(&client, treasury::EXECUTE_NOTE_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy the treasury with a snapshot of the balances
+    // -------------------------------------------------------------------------
+    // The contract cannot read the vaults of the holders, so their GOV balances are written to
+    // its storage when it is deployed. Votes are weighted by this snapshot, whatever happens to
+    // the tokens afterwards.
+    println!("\n[STEP 2] Deploying the treasury");
+
+    let weights =
+        treasury::snapshot(&client, gov.id(), &[alice.id(), bob.id(), carol.id()]).await?;
+    let account =
+        treasury::create_treasury(&mut client, &weights, THRESHOLD, VOTING_PERIOD).await?;
+    let dao = Treasury::from_account(&account)?;
+    println!(
+        "Treasury {}: proposals need more than {THRESHOLD} of 100 GOV",
+        dao.id
+    );
+
+    let tx_id = treasury::deposit(
+        &mut client,
+        alice.id(),
+        &dao,
+        FungibleAsset::new(usd.id(), 100)?.into(),
+        deposit_script,
+    )
+    .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Alice deposited 100 USD");
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Carol proposes a grant to herself
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Carol proposes to pay her 40 USD");
+
+    let tx_id = treasury::propose(
+        &mut client,
+        carol.id(),
+        &dao,
+        carol.id(),
+        FungibleAsset::new(usd.id(), 40)?,
+        propose_script,
+    )
+    .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    let dao = read_treasury(&client, dao.id).await?;
+    let proposal = dao.proposal(0).ok_or("the proposal was not registered")?;
+    println!(
+        "Proposal {} accepts votes until block {}",
+        proposal.id, proposal.deadline
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 4: The holders vote
+    // -------------------------------------------------------------------------
+    // Alice's 50 GOV only reach the threshold, the proposal needs more than that.
+    println!("\n[STEP 4] Alice and Carol vote for the proposal");
+
+    let tx_id =
+        treasury::vote(&mut client, alice.id(), &dao, proposal, vote_script.clone()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    let dao = read_treasury(&client, dao.id).await?;
+    let proposal = dao.proposal(0).ok_or("the proposal was not registered")?;
+    println!("Yes-weight after Alice: {}", proposal.yes_weight);
+
+    let Err(err) = treasury::execute(
+        &mut client,
+        bob.id(),
+        &dao,
+        proposal,
+        execute_script.clone(),
+    )
+    .await
+    else {
+        return Err("a proposal at the threshold was executed".into());
+    };
+    println!("Bob cannot execute it yet: {err}");
+
+    let tx_id = treasury::vote(&mut client, carol.id(), &dao, proposal, vote_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    let dao = read_treasury(&client, dao.id).await?;
+    let proposal = dao.proposal(0).ok_or("the proposal was not registered")?;
+    println!("Yes-weight after Carol: {}", proposal.yes_weight);
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Bob executes the proposal
+    // -------------------------------------------------------------------------
+    // Anyone can execute a proposal which passed; the treasury pays its recipient.
+    println!("\n[STEP 5] Bob executes the proposal");
+
+    let tx_id = treasury::execute(&mut client, bob.id(), &dao, proposal, execute_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, carol.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, carol.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!(
+        "Carol received {} USD",
+        balance(&client, carol.id(), usd.id()).await?
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Checking balances");
+
+    let dao = read_treasury(&client, dao.id).await?;
+    assert!(dao.proposals[0].executed);
+    assert_eq!(dao.proposals[0].yes_weight, 70);
+    assert_eq!(balance(&client, carol.id(), usd.id()).await?, 40);
+    assert_eq!(balance(&client, dao.id, usd.id()).await?, 60);
+    println!("The proposal passed with 70 GOV and the treasury paid 40 USD");
+
+    Ok(())
+}
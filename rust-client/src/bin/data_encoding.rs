@@ -0,0 +1,82 @@
+use std::{fs, time::SystemTime};
+
+use miden_objects::{crypto::hash::rpo::Rpo256, Felt};
+use rust_client::{
+    encoding::{self, EncodingError},
+    rock_paper_scissors::Hand,
+};
+
+/// Handle packed the way the name service stores it.
+const HANDLE: &str = "alice@miden";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Everything runs in a local VM: no node is needed
+    let source = fs::read_to_string(encoding::LIBRARY_FILE_PATH)?;
+    let library = encoding::library(&source)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Pack a string into a word
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Packing the handle {HANDLE:?}");
+
+    let word = encoding::encode_string(HANDLE)?;
+    println!("Stored as {word:?}");
+    encoding::check_string(&library, word)?;
+    let decoded = encoding::decode_string(word)?;
+    assert_eq!(decoded, HANDLE);
+    println!("The library accepts it and it reads back as {decoded:?}");
+
+    // A hash also takes a word, but nothing reading the storage can tell the handle from it
+    let hash = Rpo256::hash(HANDLE.as_bytes());
+    assert_eq!(
+        encoding::decode_string(hash),
+        Err(EncodingError::NotAString)
+    );
+    let Err(err) = encoding::check_string(&library, hash) else {
+        return Err("the library accepted a hash as a string".into());
+    };
+    println!("Its hash is not a string: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Booleans and enums
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Checking booleans and enums passed by a note");
+
+    let hand = encoding::encode_variant(&Hand::Scissors, &Hand::ALL);
+    let num_hands = Felt::new(Hand::ALL.len() as u64);
+    encoding::execute_procedure(&library, "assert_variant", &[num_hands, hand])?;
+    println!(
+        "{:?} is variant {hand} of {num_hands}",
+        encoding::decode_variant(hand, &Hand::ALL)?
+    );
+
+    // A note can pass any element: the contract has to reject those no encoder produces
+    for (procedure, inputs) in [
+        ("assert_bool", vec![Felt::new(7)]),
+        ("assert_variant", vec![num_hands, Felt::new(3)]),
+    ] {
+        let Err(err) = encoding::execute_procedure(&library, procedure, &inputs) else {
+            return Err(format!("`{procedure}` accepted {inputs:?}").into());
+        };
+        println!("`encoding::{procedure}` rejects {inputs:?}: {err}");
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Timestamps and flags
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Packing a timestamp and flags");
+
+    let now = encoding::encode_timestamp(SystemTime::now())?;
+    encoding::execute_procedure(&library, "assert_timestamp", &[now])?;
+    println!("Now is {now} seconds since the Unix epoch");
+
+    // e.g. [is_verified, is_frozen, accepts_payments]
+    let flags = encoding::encode_flags(&[true, false, true])?;
+    let frozen =
+        encoding::execute_procedure(&library, "set_flag", &[Felt::new(1), Felt::new(1), flags])?;
+    let frozen = encoding::decode_flags(Felt::new(frozen), 3)?;
+    assert_eq!(frozen, [true, true, true]);
+    println!("Flags {flags} become {frozen:?} once frozen");
+
+    Ok(())
+}
@@ -0,0 +1,209 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{create_p2id_note, NoteType},
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, Felt,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    mirror::{ClientEvent, ClientMirror, SqliteSink},
+};
+
+/// Syncs `mirror` and prints the events it stored.
+async fn mirror_sync(
+    mirror: &mut ClientMirror<SqliteSink>,
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+) -> Result<Vec<ClientEvent>, Box<dyn std::error::Error>> {
+    let events = mirror.sync(client).await?;
+    for event in &events {
+        match event {
+            ClientEvent::NoteReceived(note) => println!(
+                "  note {} received by {} with {} asset(s)",
+                note.note_id.to_hex(),
+                note.account_id,
+                note.assets.len()
+            ),
+            ClientEvent::BalanceChanged {
+                account_id,
+                old,
+                new,
+                ..
+            } => println!("  balance of {account_id}: {old} -> {new}"),
+        }
+    }
+    if events.is_empty() {
+        println!("  nothing new");
+    }
+    Ok(events)
+}
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`, as mirrored in the database.
+fn mirrored_balance(
+    sink: &SqliteSink,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let amount: i64 = sink.connection().query_row(
+        "SELECT COALESCE(SUM(amount), 0) FROM balances WHERE account_id = ?1 AND faucet_id = ?2",
+        [account_id.to_hex(), faucet_id.to_hex()],
+        |row| row.get(0),
+    )?;
+    Ok(u64::try_from(amount)?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("db_mirror")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the accounts and the application database
+    // -------------------------------------------------------------------------
+    // The application database is separate from the client store: the client never reads it,
+    // the application queries it with its own schema.
+    println!("\n[STEP 1] Creating accounts and the application database");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+
+    let db_path = context.dir().join("app.sqlite3");
+    let mut mirror = ClientMirror::new(SqliteSink::open(&db_path)?, vec![alice.id(), bob.id()])?;
+    println!("Mirroring Alice and Bob into {}", db_path.display());
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice receives tokens
+    // -------------------------------------------------------------------------
+    // Every call to the mirror syncs the client and stores what changed since the last call.
+    println!("\n[STEP 2] Minting 100 MID to Alice");
+
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), 100)?,
+        alice.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    mirror_sync(&mut mirror, &mut client).await?;
+
+    let tx_id = consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    mirror_sync(&mut mirror, &mut client).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Alice pays Bob
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Alice sends 30 MID to Bob");
+
+    let note = create_p2id_note(
+        alice.id(),
+        bob.id(),
+        vec![FungibleAsset::new(faucet.id(), 30)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note)])
+        .build()?;
+    let tx_id = client.submit_with_prover(alice.id(), request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, bob.id(), 1).await?;
+    mirror_sync(&mut mirror, &mut client).await?;
+
+    let tx_id = consume_all_notes(&mut client, bob.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    mirror_sync(&mut mirror, &mut client).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Query the application database
+    // -------------------------------------------------------------------------
+    // These are questions of the application, answered with plain SQL on its own tables.
+    println!("\n[STEP 4] Balance history from the application database");
+
+    let received = {
+        let connection = mirror.sink().connection();
+        let mut statement = connection.prepare(
+            "SELECT account_id, old_amount, new_amount, block_num FROM balance_changes
+             ORDER BY rowid",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, u32>(3)?,
+            ))
+        })?;
+        for row in rows {
+            let (account_id, old, new, block_num) = row?;
+            println!("  block {block_num}: {account_id} {old} -> {new}");
+        }
+        let received: i64 =
+            connection.query_row("SELECT COUNT(*) FROM received_notes", [], |row| row.get(0))?;
+        println!("  {received} note(s) received in total");
+        received
+    };
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Restart the mirror
+    // -------------------------------------------------------------------------
+    // A new mirror resumes from the database, so nothing is reported twice.
+    println!("\n[STEP 5] Restarting the mirror");
+
+    drop(mirror);
+    let mut mirror = ClientMirror::new(SqliteSink::open(&db_path)?, vec![alice.id(), bob.id()])?;
+    let events = mirror_sync(&mut mirror, &mut client).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Checking the mirrored state");
+
+    let sink = mirror.sink();
+    assert!(events.is_empty());
+    assert_eq!(mirrored_balance(sink, alice.id(), faucet.id())?, 70);
+    assert_eq!(mirrored_balance(sink, bob.id(), faucet.id())?, 30);
+    assert_eq!(received, 2);
+    assert_eq!(sink.last_block()?, Some(client.get_sync_height().await?));
+    println!("The application database matches the client store");
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{NoteDetails, NoteFile, NoteTag, NoteType},
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    serial::{self, SerialDeriver},
+};
+
+/// Purpose of the notes Alice sends Bob.
+const PURPOSE: &str = "invoice";
+/// Number of the invoice Alice pays, used as the nonce of the note.
+const INVOICE: u64 = 1;
+/// Amount of the invoice.
+const AMOUNT: u64 = 30;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("deterministic_serials")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create accounts and fund Alice
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and assets");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+
+    let usd = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(usd.id(), 100)?,
+        alice.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(usd.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // Alice and Bob agree on a secret once, e.g. when Bob hands out the first invoice. Every
+    // note between them is keyed with it from then on.
+    let deriver = SerialDeriver::new(client.rng().draw_word());
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice pays the invoice with a private note
+    // -------------------------------------------------------------------------
+    // The serial number follows from the invoice, so Alice does not need to remember it, nor
+    // to send Bob anything besides the note itself.
+    println!("\n[STEP 2] Alice pays invoice #{INVOICE} with a private note");
+
+    let assets = vec![FungibleAsset::new(usd.id(), AMOUNT)?.into()];
+    let sent_at = client.get_sync_height().await?;
+    let payment = serial::p2id_note(
+        alice.id(),
+        bob.id(),
+        assets.clone(),
+        NoteType::Private,
+        deriver.serial_num(alice.id(), INVOICE, PURPOSE),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(payment.clone())])
+        .build()?;
+    let tx_id = client.submit_with_prover(alice.id(), request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Alice sent note {}", payment.id());
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Bob rebuilds the note from the invoice and claims it
+    // -------------------------------------------------------------------------
+    // Private notes only publish their id. Bob knows who pays which invoice and how much, which
+    // is all it takes to rebuild the note and look for its id on chain.
+    println!("\n[STEP 3] Bob rebuilds the note of invoice #{INVOICE}");
+
+    let expected = serial::p2id_note(
+        alice.id(),
+        bob.id(),
+        assets.clone(),
+        NoteType::Private,
+        deriver.serial_num(alice.id(), INVOICE, PURPOSE),
+    )?;
+    assert_eq!(expected.id(), payment.id());
+    println!("Bob expects note {}", expected.id());
+
+    let details: NoteDetails = expected.clone().into();
+    client
+        .import_note(NoteFile::NoteDetails {
+            details,
+            after_block_num: sent_at,
+            tag: Some(NoteTag::from_account_id(bob.id())),
+        })
+        .await?;
+    wait_for_notes(&mut client, &network, bob.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, bob.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!(
+        "Bob received {} USD",
+        balance(&client, bob.id(), usd.id()).await?
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 4: What deterministic serial numbers trade away
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Checking the trade-offs");
+
+    // Uniqueness rests on the nonce: paying the same invoice number twice yields the very same
+    // note. Both would share a nullifier, so only one of them could ever be consumed.
+    let same_nonce = serial::p2id_note(
+        alice.id(),
+        bob.id(),
+        assets.clone(),
+        NoteType::Private,
+        deriver.serial_num(alice.id(), INVOICE, PURPOSE),
+    )?;
+    assert_eq!(same_nonce.nullifier(), payment.nullifier());
+    let next_invoice = serial::p2id_note(
+        alice.id(),
+        bob.id(),
+        assets.clone(),
+        NoteType::Private,
+        deriver.serial_num(alice.id(), INVOICE + 1, PURPOSE),
+    )?;
+    assert_ne!(next_invoice.nullifier(), payment.nullifier());
+    println!("A nonce must never be reused for the same purpose");
+
+    // Privacy rests on the secret: an observer guessing the context cannot compute the nullifier
+    // of the payment, and so cannot tell that it was consumed.
+    let guess = serial::p2id_note(
+        alice.id(),
+        bob.id(),
+        assets.clone(),
+        NoteType::Private,
+        SerialDeriver::public().serial_num(alice.id(), INVOICE, PURPOSE),
+    )?;
+    assert_ne!(guess.nullifier(), payment.nullifier());
+
+    // Without a secret the serial number is public knowledge: anybody guessing the invoice
+    // number and amount links the note to its nullifier.
+    let unkeyed = serial::p2id_note(
+        alice.id(),
+        bob.id(),
+        assets,
+        NoteType::Private,
+        SerialDeriver::public().serial_num(alice.id(), INVOICE, PURPOSE),
+    )?;
+    assert_eq!(guess.nullifier(), unkeyed.nullifier());
+    println!("Only those holding the secret can link the note to its nullifier");
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking balances");
+
+    assert_eq!(balance(&client, alice.id(), usd.id()).await?, 100 - AMOUNT);
+    assert_eq!(balance(&client, bob.id(), usd.id()).await?, AMOUNT);
+    println!("Bob claimed the payment without Alice handing over the note");
+
+    Ok(())
+}
@@ -0,0 +1,252 @@
+//! Deploys the contracts shared by the tutorials and records them in the known accounts
+//! registry.
+//!
+//! ```text
+//! cargo run --release --features devnet --bin devnet_setup -- [--force]
+//! ```
+//!
+//! Contracts that are already registered and exist on the node are reused, so running the
+//! binary again only deploys what is missing. `--force` redeploys everything.
+
+use miden_lib::{
+    account::auth::{AuthRpoFalcon512, NoAuth},
+    transaction::TransactionKernel,
+};
+use rand::{rngs::StdRng, RngCore};
+use std::{fs, path::Path, sync::Arc};
+use tokio::time::{sleep, Duration};
+
+use miden_client::{
+    account::{component::BasicFungibleFaucet, AccountId},
+    asset::FungibleAsset,
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::{GrpcClient, NodeRpcClient},
+    transaction::TransactionRequestBuilder,
+    Client, ClientError,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    asset::TokenSymbol,
+    Felt, Word,
+};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    devnet,
+    helpers::{create_basic_account, wait_for_commit},
+    known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH},
+};
+
+/// Initial supply minted to the treasury.
+const INITIAL_SUPPLY: u64 = 100_000;
+
+/// Script of the deployment transactions of contracts without a constructor: the first
+/// transaction of a new account deploys it, even if it does nothing else.
+const DEPLOY_SCRIPT: &str = "begin push.0 drop end";
+
+/// Deploys a public contract without auth whose code is read from `masm_path`.
+async fn deploy_contract(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    masm_path: &str,
+    storage: Vec<StorageSlot>,
+) -> Result<AccountId, Box<dyn std::error::Error>> {
+    let code = fs::read_to_string(Path::new(masm_path))?;
+    let component = AccountComponent::compile(&code, TransactionKernel::assembler(), storage)?
+        .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let contract = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+    client.add_account(&contract, false).await?;
+
+    let tx_script = client.script_builder().compile_tx_script(DEPLOY_SCRIPT)?;
+    let request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .build()?;
+    client.submit_with_prover(contract.id(), request).await?;
+
+    Ok(contract.id())
+}
+
+/// Returns the account registered under `label` if it exists on the node, importing it into
+/// the client when needed.
+async fn existing(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    rpc_client: &GrpcClient,
+    registry: &KnownAccounts,
+    label: &str,
+) -> Result<Option<AccountId>, ClientError> {
+    let Some(account_id) = registry.get(label) else {
+        return Ok(None);
+    };
+    if rpc_client.get_account_details(account_id).await.is_err() {
+        println!("{label} is registered but unknown to the node, deploying it again");
+        return Ok(None);
+    }
+    if client.get_account(account_id).await?.is_none() {
+        client.import_account_by_id(account_id).await?;
+    }
+    Ok(Some(account_id))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let force = rust_client::cli::positional_args()
+        .iter()
+        .any(|arg| arg == "--force");
+
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, kept across runs
+    let context = TutorialContext::persistent(devnet::STATE)?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client.clone())
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    let mut registry = KnownAccounts::load(DEFAULT_REGISTRY_PATH)?;
+    if force {
+        for label in [
+            devnet::FAUCET,
+            devnet::TREASURY,
+            devnet::NAME_SERVICE,
+            devnet::ORACLE_READER,
+        ] {
+            registry.remove(label);
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Faucet and treasury
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Shared token");
+
+    let registered_faucet = existing(&mut client, &rpc_client, &registry, devnet::FAUCET).await?;
+    let registered_treasury =
+        existing(&mut client, &rpc_client, &registry, devnet::TREASURY).await?;
+    if registered_faucet.is_none() || registered_treasury.is_none() {
+        let mut init_seed = [0_u8; 32];
+        client.rng().fill_bytes(&mut init_seed);
+        let key_pair = AuthSecretKey::new_rpo_falcon512();
+        let faucet = AccountBuilder::new(init_seed)
+            .account_type(AccountType::FungibleFaucet)
+            .storage_mode(AccountStorageMode::Public)
+            .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+            .with_component(BasicFungibleFaucet::new(
+                TokenSymbol::new("TUT")?,
+                8,
+                Felt::new(1_000_000_000),
+            )?)
+            .build()?;
+        client.add_account(&faucet, false).await?;
+        keystore.add_key(&key_pair).unwrap();
+
+        // Minting deploys the faucet, consuming the minted note deploys the treasury
+        let treasury = create_basic_account(&mut client, &keystore).await?;
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet.id(), INITIAL_SUPPLY)?,
+            treasury.id(),
+            NoteType::Public,
+            client.rng(),
+        )?;
+        client.submit_with_prover(faucet.id(), mint_request).await?;
+
+        loop {
+            client.sync_state().await?;
+            let notes = client.get_consumable_notes(Some(treasury.id())).await?;
+            if !notes.is_empty() {
+                let note_ids = notes.iter().map(|(note, _)| note.id()).collect();
+                let consume_request =
+                    TransactionRequestBuilder::new().build_consume_notes(note_ids)?;
+                client
+                    .submit_with_prover(treasury.id(), consume_request)
+                    .await?;
+                break;
+            }
+            sleep(Duration::from_secs(3)).await;
+        }
+
+        registry.insert(devnet::FAUCET, faucet.id(), network.network_id());
+        registry.insert(devnet::TREASURY, treasury.id(), network.network_id());
+        println!("Deployed the faucet and minted {INITIAL_SUPPLY} TUT to the treasury");
+    } else {
+        println!("Reusing the registered faucet and treasury");
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Contracts
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Shared contracts");
+
+    let contracts = [
+        (
+            devnet::NAME_SERVICE,
+            "../masm/accounts/handle_registry.masm",
+            vec![StorageSlot::Map(StorageMap::new())],
+        ),
+        (
+            devnet::ORACLE_READER,
+            "../masm/accounts/oracle_reader.masm",
+            vec![StorageSlot::Value(Word::default())],
+        ),
+    ];
+    for (label, masm_path, storage) in contracts {
+        if existing(&mut client, &rpc_client, &registry, label)
+            .await?
+            .is_some()
+        {
+            println!("Reusing the registered {label}");
+            continue;
+        }
+        let contract_id = deploy_contract(&mut client, masm_path, storage).await?;
+        registry.insert(label, contract_id, network.network_id());
+        println!("Deployed {label}");
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Wait for the deployments and save the registry
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Waiting for the deployments to be committed");
+
+    wait_for_commit(&mut client).await?;
+    registry.save(DEFAULT_REGISTRY_PATH)?;
+
+    println!("\nShared accounts (saved to {DEFAULT_REGISTRY_PATH}):");
+    for label in [
+        devnet::FAUCET,
+        devnet::TREASURY,
+        devnet::NAME_SERVICE,
+        devnet::ORACLE_READER,
+    ] {
+        if let Some(account_id) = registry.get(label) {
+            println!("{label:<22} {}", account_id.to_bech32(network.network_id()));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,338 @@
+use rand::rngs::StdRng;
+use std::{collections::BTreeMap, sync::Arc};
+
+use miden_client::{
+    account::AccountId,
+    asset::{Asset, FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{create_p2id_note, NoteId, NoteType},
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, ClientError, Felt,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx},
+};
+
+/// Notes whose combined fungible value is at or below this amount are considered dust.
+const DUST_THRESHOLD: u64 = 10;
+
+/// Maximum number of input notes the planner puts into a single transaction.
+///
+/// The protocol allows far more (`MAX_INPUT_NOTES_PER_TX`), but services usually cap batches
+/// well below it to keep proving time predictable. A small value makes the chunking visible.
+const MAX_INPUT_NOTES: usize = 2;
+
+/// Dust notes discovered for a single account.
+struct DustNotes {
+    account_id: AccountId,
+    notes: Vec<(NoteId, Vec<FungibleAsset>)>,
+}
+
+/// A single transaction of the sweep plan executed by a source account.
+#[derive(Debug)]
+struct SweepBatch {
+    account_id: AccountId,
+    note_ids: Vec<NoteId>,
+    /// Assets forwarded to the collector. Only the last batch of every account forwards,
+    /// so the collector receives one note per account no matter how many batches it took.
+    forward: Option<Vec<FungibleAsset>>,
+}
+
+/// The full sweep: batches run by the source accounts, followed by the collector consuming
+/// one sweep note per source account.
+struct SweepPlan {
+    source_batches: Vec<SweepBatch>,
+    collector_batches: usize,
+}
+
+impl SweepPlan {
+    fn num_transactions(&self) -> usize {
+        self.source_batches.len() + self.collector_batches
+    }
+}
+
+/// Finds the consumable notes of every account whose combined fungible value is at or below
+/// `threshold`. Notes carrying non-fungible assets are never treated as dust.
+async fn discover_dust(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    account_ids: &[AccountId],
+    threshold: u64,
+) -> Result<Vec<DustNotes>, ClientError> {
+    let mut dust = Vec::new();
+
+    for account_id in account_ids {
+        let consumable_notes = client.get_consumable_notes(Some(*account_id)).await?;
+
+        let notes: Vec<(NoteId, Vec<FungibleAsset>)> = consumable_notes
+            .iter()
+            .filter_map(|(note, _)| {
+                let assets: Option<Vec<FungibleAsset>> = note
+                    .assets()
+                    .iter()
+                    .map(|asset| match asset {
+                        Asset::Fungible(fungible) => Some(*fungible),
+                        Asset::NonFungible(_) => None,
+                    })
+                    .collect();
+                let assets = assets?;
+                let value: u64 = assets.iter().map(|asset| asset.amount()).sum();
+                (value <= threshold).then_some((note.id(), assets))
+            })
+            .collect();
+
+        if !notes.is_empty() {
+            dust.push(DustNotes {
+                account_id: *account_id,
+                notes,
+            });
+        }
+    }
+
+    Ok(dust)
+}
+
+/// Plans the sweep with the minimum number of transactions.
+///
+/// Only the owner of a note can consume it, so every source account needs
+/// `ceil(notes / max_input_notes)` transactions of its own. Assets accumulate in the source
+/// vault between batches and are forwarded once, which leaves the collector with a single
+/// note per source account to consume.
+fn plan_sweep(dust: &[DustNotes], max_input_notes: usize) -> SweepPlan {
+    assert!(max_input_notes > 0, "max_input_notes must be positive");
+
+    let mut source_batches = Vec::new();
+
+    for account_dust in dust {
+        let mut totals: BTreeMap<AccountId, u64> = BTreeMap::new();
+        for (_, assets) in &account_dust.notes {
+            for asset in assets {
+                *totals.entry(asset.faucet_id()).or_default() += asset.amount();
+            }
+        }
+
+        let chunks: Vec<_> = account_dust.notes.chunks(max_input_notes).collect();
+        let num_chunks = chunks.len();
+
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            let forward = (i + 1 == num_chunks).then(|| {
+                totals
+                    .iter()
+                    .map(|(faucet_id, amount)| FungibleAsset::new(*faucet_id, *amount).unwrap())
+                    .collect()
+            });
+
+            source_batches.push(SweepBatch {
+                account_id: account_dust.account_id,
+                note_ids: chunk.iter().map(|(note_id, _)| *note_id).collect(),
+                forward,
+            });
+        }
+    }
+
+    SweepPlan {
+        source_batches,
+        collector_batches: dust.len().div_ceil(max_input_notes),
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("dust_collector")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create source accounts, the collector and a faucet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating 3 source accounts and a collector account");
+
+    let mut source_ids = vec![];
+    for i in 0..3 {
+        let account = create_basic_account(&mut client, &keystore).await?;
+        println!(
+            "Source account {}: {}",
+            i,
+            account.id().to_bech32(network.network_id())
+        );
+        source_ids.push(account.id());
+    }
+
+    let collector = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Collector account: {}",
+        collector.id().to_bech32(network.network_id())
+    );
+
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    println!(
+        "Faucet account ID: {}",
+        faucet.id().to_bech32(network.network_id())
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Scatter dust (and one large note) across the source accounts
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Minting small notes to every source account");
+
+    // Three dust notes per account, plus one note that is too large to be swept
+    let amounts: [u64; 4] = [1, 3, 5, 500];
+    let mut last_tx_id = None;
+    for source_id in &source_ids {
+        for amount in amounts {
+            let transaction_request = TransactionRequestBuilder::new()
+                .build_mint_fungible_asset(
+                    FungibleAsset::new(faucet.id(), amount).unwrap(),
+                    *source_id,
+                    NoteType::Public,
+                    client.rng(),
+                )
+                .unwrap();
+
+            let tx_id = client
+                .submit_with_prover(faucet.id(), transaction_request)
+                .await?;
+            println!("Minted {} tokens. TX: {:?}", amount, tx_id);
+            last_tx_id = Some(tx_id);
+        }
+    }
+
+    wait_for_tx(&mut client, last_tx_id.unwrap()).await?;
+    for source_id in &source_ids {
+        wait_for_notes(&mut client, &network, *source_id, amounts.len()).await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Discover dust and plan the sweep
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Discovering dust notes (<= {DUST_THRESHOLD} tokens)");
+
+    let dust = discover_dust(&mut client, &source_ids, DUST_THRESHOLD).await?;
+    for account_dust in &dust {
+        println!(
+            "{} holds {} dust notes",
+            account_dust.account_id.to_bech32(network.network_id()),
+            account_dust.notes.len()
+        );
+    }
+
+    let plan = plan_sweep(&dust, MAX_INPUT_NOTES);
+    println!(
+        "Sweep plan: {} source transactions + {} collector transactions (max {} input notes each)",
+        plan.source_batches.len(),
+        plan.collector_batches,
+        MAX_INPUT_NOTES
+    );
+
+    // 3 accounts x ceil(3 / 2) batches, then ceil(3 / 2) collector batches
+    assert_eq!(plan.num_transactions(), 8);
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Execute the source batches
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Consuming dust and forwarding it to the collector");
+
+    let mut expected_total = 0;
+    for batch in &plan.source_batches {
+        let mut builder = TransactionRequestBuilder::new()
+            .authenticated_input_notes(batch.note_ids.iter().map(|note_id| (*note_id, None)));
+
+        if let Some(assets) = &batch.forward {
+            expected_total += assets.iter().map(|asset| asset.amount()).sum::<u64>();
+
+            let sweep_note = create_p2id_note(
+                batch.account_id,
+                collector.id(),
+                assets.iter().map(|asset| (*asset).into()).collect(),
+                NoteType::Public,
+                Felt::new(0),
+                client.rng(),
+            )?;
+            builder = builder.own_output_notes(vec![OutputNote::Full(sweep_note)]);
+        }
+
+        let tx_id = client
+            .submit_with_prover(batch.account_id, builder.build().unwrap())
+            .await?;
+        println!(
+            "{} consumed {} notes (forwarding: {}). TX: {:?}",
+            batch.account_id.to_bech32(network.network_id()),
+            batch.note_ids.len(),
+            batch.forward.is_some(),
+            tx_id
+        );
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 5: The collector consolidates the sweep notes
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Collector consumes the sweep notes");
+
+    wait_for_notes(&mut client, &network, collector.id(), dust.len()).await?;
+
+    let sweep_notes: Vec<NoteId> = client
+        .get_consumable_notes(Some(collector.id()))
+        .await?
+        .iter()
+        .map(|(note, _)| note.id())
+        .collect();
+
+    for chunk in sweep_notes.chunks(MAX_INPUT_NOTES) {
+        let transaction_request = TransactionRequestBuilder::new()
+            .build_consume_notes(chunk.to_vec())
+            .unwrap();
+
+        let tx_id = client
+            .submit_with_prover(collector.id(), transaction_request)
+            .await?;
+        println!("Collector consumed {} notes. TX: {:?}", chunk.len(), tx_id);
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+
+    let collector_account = client.get_account(collector.id()).await?.unwrap();
+    let balance = collector_account
+        .account()
+        .vault()
+        .get_balance(faucet.id())
+        .unwrap();
+    println!("Collector balance: {}", balance);
+    assert_eq!(balance, expected_total);
+
+    // The large notes were left untouched
+    for source_id in &source_ids {
+        let remaining = client.get_consumable_notes(Some(*source_id)).await?;
+        assert_eq!(remaining.len(), 1);
+    }
+
+    println!(
+        "\nDust sweep finished in {} transactions!",
+        plan.num_transactions()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use miden_lib::account::auth::NoAuth;
+use miden_lib::transaction::TransactionKernel;
+use rand::RngCore;
+use std::{fs, path::Path};
+
+use miden_client::transaction::TransactionRequestBuilder;
+use miden_objects::{
+    account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
+    Word,
+};
+use rust_client::cli::SubmitWithProver;
+use rust_client::ephemeral::EphemeralClient;
+use rust_client::helpers::create_library;
+use rust_client::storage::read_u64_slot;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize an ephemeral client: store and keystore live in a temporary directory
+    let network = rust_client::cli::network().await?;
+    let mut client = EphemeralClient::new(network.endpoint().clone()).await?;
+    let client_dir = client.dir().to_path_buf();
+    println!("Client state is kept in {}", client_dir.display());
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Deploy a counter contract
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating counter contract.");
+
+    let counter_code = fs::read_to_string(Path::new("../masm/accounts/counter.masm")).unwrap();
+
+    let counter_component = AccountComponent::compile(
+        &counter_code,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Value(Word::default())],
+    )
+    .unwrap()
+    .with_supports_all_types();
+
+    let mut seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut seed);
+
+    let counter_contract = AccountBuilder::new(seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_component(counter_component)
+        .with_auth_component(NoAuth)
+        .build()
+        .unwrap();
+
+    client.add_account(&counter_contract, false).await?;
+    println!(
+        "Counter contract id: {:?}",
+        counter_contract.id().to_bech32(network.network_id())
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Increment the counter
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Incrementing the counter");
+
+    let script_code = fs::read_to_string(Path::new("../masm/scripts/counter_script.masm")).unwrap();
+
+    let account_component_lib =
+        create_library(&counter_code, "external_contract::counter_contract")?;
+
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&account_component_lib)?
+        .compile_tx_script(&script_code)?;
+
+    let tx_increment_request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .build()?;
+
+    let tx_id = client
+        .submit_with_prover(counter_contract.id(), tx_increment_request)
+        .await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    let account = client.get_account(counter_contract.id()).await?.unwrap();
+    let count = read_u64_slot(account.account(), 0)?;
+    println!("Counter value: {}", count);
+    assert_eq!(count, 1);
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Close the client, nothing is left on disk
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Closing the client");
+
+    client.close()?;
+    assert!(!client_dir.exists());
+    println!("Removed {}", client_dir.display());
+
+    Ok(())
+}
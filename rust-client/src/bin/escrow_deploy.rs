@@ -0,0 +1,128 @@
+use rand::RngCore;
+use std::{fs, path::Path, sync::Arc};
+
+use miden_client::{
+    account::AccountId, builder::ClientBuilder, rpc::GrpcClient,
+    transaction::TransactionRequestBuilder, Felt, Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::account::{AccountStorageMode, StorageSlot};
+use rust_client::{
+    context::TutorialContext,
+    deployer::Deployer,
+    helpers::{create_basic_account, wait_for_tx},
+};
+
+/// Fee charged by the escrow, in basis points.
+const FEE_RATE: u64 = 250;
+/// Number of blocks the escrow stays open after deployment.
+const OPEN_FOR_BLOCKS: u32 = 1_000;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("escrow_deploy")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the owner's wallet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating the owner's wallet");
+
+    let owner = create_basic_account(&mut client, &keystore).await?;
+    println!("Owner: {}", owner.id().to_bech32(network.network_id()));
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy the escrow with its constructor arguments
+    // -------------------------------------------------------------------------
+    // The account is created with empty slots. The deployment transaction then calls
+    // `constructor` with the owner, fee rate and deadline, which validates them and writes them
+    // into storage. The constructor refuses to run twice, so nobody can overwrite them later.
+    println!("\n[STEP 2] Deploying the escrow");
+
+    let deadline = sync_summary.block_num.as_u32() + OPEN_FOR_BLOCKS;
+    let escrow_code = fs::read_to_string(Path::new("../masm/accounts/escrow.masm"))?;
+    let deployer = Deployer::new(escrow_code.clone(), "external_contract::escrow")
+        .with_storage(vec![StorageSlot::Value(Word::default()); 4])
+        .with_arg(owner.id().prefix().as_felt())
+        .with_arg(owner.id().suffix())
+        .with_arg(Felt::new(FEE_RATE))
+        .with_arg(Felt::from(deadline));
+    println!("Deployment script:\n{}", deployer.script_source());
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let (escrow, tx_id) = deployer
+        .deploy(&mut client, init_seed, AccountStorageMode::Public)
+        .await?;
+    println!("Escrow: {}", escrow.id().to_bech32(network.network_id()));
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Check the initialized storage
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Reading the escrow storage");
+
+    let record = client.get_account(escrow.id()).await?.unwrap();
+    let storage = record.account().storage();
+    let owner_word = storage.get_item(0)?;
+    let stored_owner = AccountId::try_from([owner_word[3], owner_word[2]])?;
+    let stored_fee_rate = storage.get_item(1)?[3].as_int();
+    let stored_deadline = storage.get_item(2)?[3].as_int();
+    println!("owner:    {}", stored_owner.to_bech32(network.network_id()));
+    println!("fee rate: {stored_fee_rate} bps");
+    println!("deadline: block {stored_deadline}");
+
+    assert_eq!(stored_owner, owner.id());
+    assert_eq!(stored_fee_rate, FEE_RATE);
+    assert_eq!(stored_deadline, u64::from(deadline));
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Try to run the constructor again
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Calling the constructor a second time");
+
+    // Same code, but another owner and no fee
+    let overwrite = Deployer::new(escrow_code, "external_contract::escrow").with_args([
+        Felt::new(1),
+        Felt::new(2),
+        Felt::new(0),
+        Felt::from(deadline),
+    ]);
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&overwrite.library()?)?
+        .compile_tx_script(&overwrite.script_source())?;
+    let request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .build()?;
+    match client.execute_transaction(escrow.id(), request).await {
+        Ok(_) => panic!("the constructor ran twice"),
+        Err(err) => println!("Rejected as expected: {err}"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use rand::rngs::StdRng;
+use std::{sync::Arc, time::Duration};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_commit,
+        wait_for_notes, wait_for_tx,
+    },
+    trade_escrow::{self, Escrow, EscrowState},
+};
+use tokio::time::sleep;
+
+/// Price of each trade, locked by Alice for Bob.
+const PRICE: u64 = 100;
+/// Blocks after which Alice can take back funds she has not released.
+const TIMEOUT_BLOCKS: u32 = 10;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+/// Reads the current terms and funds of the escrow `escrow_id`.
+async fn read_escrow(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    escrow_id: AccountId,
+) -> Result<Escrow, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(escrow_id)
+        .await?
+        .ok_or("the escrow is not tracked by the client")?;
+    Escrow::from_account(record.account())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("escrow_example")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the buyer, the seller and the asset
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and funding Alice");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice (buyer):  {}",
+        alice.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Bob (seller):   {}",
+        bob.id().to_bech32(network.network_id())
+    );
+
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), 2 * PRICE)?,
+        alice.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let lock_script = trade_escrow::compile_note_script(&client, trade_escrow::LOCK_NOTE_PATH)?;
+    let release_script =
+        trade_escrow::compile_note_script(&client, trade_escrow::RELEASE_NOTE_PATH)?;
+    let withdraw_script =
+        trade_escrow::compile_note_script(&client, trade_escrow::WITHDRAW_NOTE_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice locks the price of a first trade
+    // -------------------------------------------------------------------------
+    // The escrow stores both parties, the asset and the block from which Alice can take the
+    // funds back. Every action is a note the escrow consumes, and the escrow checks the sender
+    // of each note against the parties it stores.
+    println!("\n[STEP 2] Alice locks {PRICE} USD for Bob");
+
+    let timeout = client.get_sync_height().await?.as_u32() + TIMEOUT_BLOCKS;
+    let account = trade_escrow::create_escrow(
+        &mut client,
+        alice.id(),
+        bob.id(),
+        faucet.id(),
+        timeout.into(),
+    )
+    .await?;
+    let first = Escrow::from_account(&account)?;
+    println!("Escrow {} times out at block {timeout}", first.id);
+
+    let tx_id = trade_escrow::lock(&mut client, &first, PRICE, lock_script.clone()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Alice releases the funds and Bob claims them
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Alice received the goods and releases the funds");
+
+    let tx_id = trade_escrow::release(&mut client, &first, release_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let first = read_escrow(&client, first.id).await?;
+    assert_eq!(first.state, EscrowState::Released);
+    println!("Escrow holds {} USD for Bob", first.funds);
+
+    let tx_id =
+        trade_escrow::withdraw(&mut client, bob.id(), &first, withdraw_script.clone()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, bob.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, bob.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Bob claimed the funds");
+
+    // -------------------------------------------------------------------------
+    // STEP 4: A second trade, which Alice tries to cancel too early
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Alice locks {PRICE} USD for a second trade, then asks for it back");
+
+    let timeout = client.get_sync_height().await?.as_u32() + TIMEOUT_BLOCKS;
+    let account = trade_escrow::create_escrow(
+        &mut client,
+        alice.id(),
+        bob.id(),
+        faucet.id(),
+        timeout.into(),
+    )
+    .await?;
+    let second = Escrow::from_account(&account)?;
+    println!("Escrow {} times out at block {timeout}", second.id);
+
+    let tx_id = trade_escrow::lock(&mut client, &second, PRICE, lock_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    let second = read_escrow(&client, second.id).await?;
+
+    let note = trade_escrow::withdraw_note(&mut client, alice.id(), &second, withdraw_script)?;
+    let payout = second.payout(alice.id(), note.serial_num())?;
+    let tx_id = trade_escrow::send_note(&mut client, alice.id(), &note).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let block_num = client.get_sync_height().await?;
+    let Err(err) =
+        trade_escrow::consume_note(&mut client, &second, &note, vec![payout.clone()]).await
+    else {
+        return Err("the escrow paid the buyer back before the timeout".into());
+    };
+    println!("Block {block_num}: the escrow rejects the withdrawal: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 5: The timeout passes and the escrow pays Alice back
+    // -------------------------------------------------------------------------
+    // The block of a transaction is the block the client last synced to, so the withdrawal
+    // is only accepted once the client has seen the timeout block.
+    println!("\n[STEP 5] Waiting for block {timeout}");
+
+    while second.withdrawable_by(client.get_sync_height().await?) != Some(alice.id()) {
+        sleep(Duration::from_secs(3)).await;
+        client.sync_state().await?;
+    }
+
+    let tx_id = trade_escrow::consume_note(&mut client, &second, &note, vec![payout]).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_commit(&mut client).await?;
+    println!(
+        "Block {}: Alice took her funds back",
+        client.get_sync_height().await?
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Checking balances");
+
+    let alice_funds = balance(&client, alice.id(), faucet.id()).await?;
+    let bob_funds = balance(&client, bob.id(), faucet.id()).await?;
+    println!("Alice: {alice_funds} USD, Bob: {bob_funds} USD");
+
+    assert_eq!(alice_funds, PRICE);
+    assert_eq!(bob_funds, PRICE);
+    for escrow_id in [first.id, second.id] {
+        let escrow = read_escrow(&client, escrow_id).await?;
+        assert_eq!(escrow.state, EscrowState::Closed);
+        assert_eq!(escrow.funds, 0);
+    }
+
+    Ok(())
+}
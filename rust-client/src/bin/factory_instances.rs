@@ -0,0 +1,148 @@
+use miden_lib::{account::auth::AuthRpoFalcon512, transaction::TransactionKernel};
+use rand::RngCore;
+use std::{fs, path::Path, sync::Arc};
+
+use miden_client::{auth::AuthSecretKey, builder::ClientBuilder, rpc::GrpcClient, Felt, Word};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::account::{
+    AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+};
+use rust_client::{
+    context::TutorialContext,
+    deployer::Deployer,
+    factory::Factory,
+    helpers::{create_basic_account, wait_for_commit},
+};
+
+/// Fee rate of the escrows created by the factory, in basis points.
+const FEE_RATE: u64 = 100;
+/// Number of blocks every escrow stays open.
+const OPEN_FOR_BLOCKS: u32 = 1_000;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("factory_instances")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Deploy the factory
+    // -------------------------------------------------------------------------
+    // The factory is a registry: it never holds the instances' state, only the map from each
+    // owner to its instance. Only the holder of its key can register instances.
+    println!("\n[STEP 1] Deploying the factory registry");
+
+    let factory_code = fs::read_to_string(Path::new("../masm/accounts/factory.masm"))?;
+    let component = AccountComponent::compile(
+        &factory_code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let factory_account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+        .with_component(component)
+        .build()?;
+    client.add_account(&factory_account, false).await?;
+    keystore.add_key(&key_pair).unwrap();
+    println!(
+        "Factory: {}",
+        factory_account.id().to_bech32(network.network_id())
+    );
+
+    let escrow_code = fs::read_to_string(Path::new("../masm/accounts/escrow.masm"))?;
+    let instance = Deployer::new(escrow_code, "external_contract::escrow")
+        .with_storage(vec![StorageSlot::Value(Word::default()); 4]);
+    let library = Deployer::new(factory_code, "external_contract::factory").library()?;
+    let factory = Factory::new(factory_account.id(), library, instance);
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Create one escrow per user
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Creating an escrow for Alice and for Bob");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let deadline = sync_summary.block_num.as_u32() + OPEN_FOR_BLOCKS;
+
+    for (name, owner) in [("Alice", &alice), ("Bob", &bob)] {
+        // The instance id is known before deployment
+        let predicted = factory.instance_id(owner.id())?;
+        let instance = factory
+            .create_instance(
+                &mut client,
+                owner.id(),
+                [Felt::new(FEE_RATE), Felt::from(deadline)],
+            )
+            .await?;
+        assert_eq!(instance.id(), predicted);
+        println!(
+            "{name}'s escrow: {}",
+            instance.id().to_bech32(network.network_id())
+        );
+        wait_for_commit(&mut client).await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Look the instances up in the registry
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Reading the registry");
+
+    let record = client.get_account(factory_account.id()).await?.unwrap();
+    for (name, owner) in [("Alice", &alice), ("Bob", &bob)] {
+        let registered = Factory::registered_instance(record.account(), owner.id())
+            .expect("every owner was registered");
+        println!("{name} => {}", registered.to_bech32(network.network_id()));
+    }
+    println!(
+        "Instances: {}",
+        record.account().storage().get_item(1)?[3].as_int()
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 4: A second instance for the same owner is refused
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Creating a second escrow for Alice");
+
+    match factory
+        .create_instance(
+            &mut client,
+            alice.id(),
+            [Felt::new(FEE_RATE), Felt::from(deadline)],
+        )
+        .await
+    {
+        Ok(_) => panic!("Alice got a second instance"),
+        Err(err) => println!("Refused as expected: {err}"),
+    }
+
+    Ok(())
+}
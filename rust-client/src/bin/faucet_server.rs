@@ -0,0 +1,285 @@
+//! Serves the shared tutorial token over HTTP, like the official testnet faucet.
+//!
+//! ```text
+//! cargo run --release --features defi --bin faucet_server -- [LISTEN_ADDR]
+//! curl -X POST http://127.0.0.1:8080/request-tokens \
+//!     -H 'content-type: application/json' -d '{"account_id": "mtst1...", "amount": 100}'
+//! ```
+//!
+//! Every request mints a public P2ID note from the faucet deployed by `devnet_setup` to the
+//! requested account. Each IP address and each account may receive tokens once per interval.
+//! The client is not shared with the HTTP handlers: they queue mint jobs, and the main task,
+//! which owns the client, submits them one at a time.
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, State},
+    http::StatusCode,
+    routing::post,
+    Json, Router,
+};
+use miden_client::{
+    account::AccountId,
+    address::NetworkId,
+    asset::FungibleAsset,
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{NoteId, NoteType},
+    rpc::GrpcClient,
+    transaction::{TransactionId, TransactionRequestBuilder},
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rand::rngs::StdRng;
+use rust_client::{
+    address::parse_account_address,
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    devnet,
+    known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH},
+    rate_limit::RateLimiter,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+
+const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:8080";
+/// Tokens sent when the request does not name an amount.
+const DEFAULT_AMOUNT: u64 = 100;
+/// Largest amount a single request may ask for.
+const MAX_AMOUNT: u64 = 1_000;
+/// Minimum time between two requests from the same IP address.
+const IP_INTERVAL: Duration = Duration::from_secs(60);
+/// Minimum time between two requests for the same account.
+const ACCOUNT_INTERVAL: Duration = Duration::from_secs(600);
+/// Mint jobs waiting for the client before new requests are turned away.
+const QUEUE_SIZE: usize = 32;
+
+#[derive(Deserialize)]
+struct TokenRequest {
+    /// Bech32 address of the account to fund.
+    account_id: String,
+    amount: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct TokenResponse {
+    tx_id: String,
+    note_id: String,
+    amount: u64,
+}
+
+/// A mint queued by a handler, answered by the task owning the client.
+struct MintJob {
+    account_id: AccountId,
+    amount: u64,
+    reply: oneshot::Sender<Result<(TransactionId, NoteId), String>>,
+}
+
+/// State shared by the HTTP handlers.
+struct FaucetState {
+    /// Network the addresses of the requests must be for.
+    network_id: NetworkId,
+    jobs: mpsc::Sender<MintJob>,
+    by_ip: Mutex<RateLimiter<IpAddr>>,
+    by_account: Mutex<RateLimiter<AccountId>>,
+}
+
+type ApiError = (StatusCode, String);
+
+fn too_many_requests(what: &str, retry_after: Duration) -> ApiError {
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        format!(
+            "{what} already received tokens, retry in {}s",
+            retry_after.as_secs()
+        ),
+    )
+}
+
+/// `POST /request-tokens`: checks the limits, then waits for the client to mint the tokens.
+async fn request_tokens(
+    State(state): State<Arc<FaucetState>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    Json(request): Json<TokenRequest>,
+) -> Result<Json<TokenResponse>, ApiError> {
+    let account_id = parse_account_address(&request.account_id, state.network_id.clone())
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
+    let amount = request.amount.unwrap_or(DEFAULT_AMOUNT);
+    if amount == 0 || amount > MAX_AMOUNT {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("amount must be between 1 and {MAX_AMOUNT}"),
+        ));
+    }
+
+    // Both limits are taken before minting, so that concurrent requests cannot slip through
+    let now = Instant::now();
+    let ip = peer.ip();
+    state
+        .by_ip
+        .lock()
+        .unwrap()
+        .try_acquire(ip, now)
+        .map_err(|retry_after| too_many_requests("this IP address", retry_after))?;
+    if let Err(retry_after) = state
+        .by_account
+        .lock()
+        .unwrap()
+        .try_acquire(account_id, now)
+    {
+        state.by_ip.lock().unwrap().release(&ip);
+        return Err(too_many_requests("this account", retry_after));
+    }
+
+    let (reply, minted) = oneshot::channel();
+    let job = MintJob {
+        account_id,
+        amount,
+        reply,
+    };
+    let result = match state.jobs.try_send(job) {
+        Ok(()) => minted
+            .await
+            .unwrap_or_else(|_| Err("the faucet shut down".to_string())),
+        Err(_) => Err("the faucet is busy, retry later".to_string()),
+    };
+
+    match result {
+        Ok((tx_id, note_id)) => Ok(Json(TokenResponse {
+            tx_id: tx_id.to_hex(),
+            note_id: note_id.to_hex(),
+            amount,
+        })),
+        Err(err) => {
+            // Nothing was sent, so the caller may retry right away
+            state.by_ip.lock().unwrap().release(&ip);
+            state.by_account.lock().unwrap().release(&account_id);
+            Err((StatusCode::SERVICE_UNAVAILABLE, err))
+        }
+    }
+}
+
+/// Mints `amount` tokens of `faucet_id` to `account_id` in a public P2ID note.
+async fn mint(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    faucet_id: AccountId,
+    account_id: AccountId,
+    amount: u64,
+) -> Result<(TransactionId, NoteId), Box<dyn std::error::Error>> {
+    client.sync_state().await?;
+    let request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet_id, amount)?,
+        account_id,
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let note_id = request
+        .expected_output_own_notes()
+        .first()
+        .map(|note| note.id())
+        .ok_or("the mint request creates no note")?;
+    let tx_id = client.submit_with_prover(faucet_id, request).await?;
+    Ok((tx_id, note_id))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = rust_client::cli::positional_args();
+    let listen_addr: SocketAddr = args
+        .first()
+        .map_or(DEFAULT_LISTEN_ADDR, String::as_str)
+        .parse()?;
+
+    let registry = KnownAccounts::load(DEFAULT_REGISTRY_PATH)?;
+    let faucet_id = registry
+        .get(devnet::FAUCET)
+        .ok_or("no shared faucet registered, run `devnet_setup` first")?;
+
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, kept across runs: minting needs the faucet key
+    let context = TutorialContext::persistent(devnet::STATE)?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore)
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // Start the HTTP server
+    // -------------------------------------------------------------------------
+    let (jobs, mut queue) = mpsc::channel(QUEUE_SIZE);
+    let state = Arc::new(FaucetState {
+        network_id: network.network_id(),
+        jobs,
+        by_ip: Mutex::new(RateLimiter::new(IP_INTERVAL)),
+        by_account: Mutex::new(RateLimiter::new(ACCOUNT_INTERVAL)),
+    });
+    let app = Router::new()
+        .route("/request-tokens", post(request_tokens))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(listen_addr).await?;
+    println!(
+        "Serving {} on http://{listen_addr}/request-tokens, Ctrl+C to stop",
+        registry.display(faucet_id, network.network_id())
+    );
+    tokio::spawn(async move {
+        let service = app.into_make_service_with_connect_info::<SocketAddr>();
+        if let Err(err) = axum::serve(listener, service).await {
+            eprintln!("HTTP server stopped: {err}");
+        }
+    });
+
+    // -------------------------------------------------------------------------
+    // Mint queued requests one at a time
+    // -------------------------------------------------------------------------
+    // Handing out tokens to the requests in order keeps the faucet transactions from
+    // conflicting with each other.
+    loop {
+        let job = tokio::select! {
+            job = queue.recv() => job,
+            _ = tokio::signal::ctrl_c() => None,
+        };
+        let Some(job) = job else {
+            break;
+        };
+
+        let result = mint(&mut client, faucet_id, job.account_id, job.amount).await;
+        match &result {
+            Ok((tx_id, _)) => println!(
+                "Sent {} tokens to {} in {}",
+                job.amount,
+                job.account_id.to_bech32(network.network_id()),
+                tx_id.to_hex()
+            ),
+            Err(err) => eprintln!(
+                "Could not fund {}: {err}",
+                job.account_id.to_bech32(network.network_id())
+            ),
+        }
+        // The handler may have given up waiting, in which case there is nobody to answer
+        let _ = job.reply.send(result.map_err(|err| err.to_string()));
+    }
+
+    println!("Faucet stopped");
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use std::fs;
+
+use miden_objects::assembly::Library;
+use rust_client::fixed_point::{self, to_decimal, Rounding, SCALE};
+
+/// Tokens deposited and borrowed, in base units of a token with six decimals.
+const PRINCIPAL: u32 = 1_234_567_891;
+/// Interest rate per period as a fixed-point number: 1.05, i.e. 5%.
+const RATE: u32 = 1_050_000;
+/// Periods the interest compounds over.
+const PERIODS: usize = 4;
+
+/// Reserves of a constant product pool, in base units.
+const RESERVE_IN: u32 = 2_000_000_000;
+const RESERVE_OUT: u32 = 12_345_678;
+/// Tokens swapped into the pool.
+const AMOUNT_IN: u32 = 250_000_000;
+
+/// Runs `fixed_point::<procedure>` in the VM, checks it agrees with the Rust reference and
+/// returns the result.
+fn checked(
+    library: &Library,
+    procedure: &str,
+    rounding: Rounding,
+    operands: [u32; 3],
+) -> Result<u32, Box<dyn std::error::Error>> {
+    let [a, b, c] = operands;
+    let (expected, operands) = match procedure {
+        "mul_div" => (fixed_point::mul_div(a, b, c, rounding)?, &operands[..]),
+        "mul" => (fixed_point::mul(a, b, rounding)?, &operands[..2]),
+        "div" => (fixed_point::div(a, b, rounding)?, &operands[..2]),
+        _ => return Err(format!("unknown procedure {procedure}").into()),
+    };
+    let operands: Vec<u64> = operands.iter().copied().map(u64::from).collect();
+
+    let actual = fixed_point::execute_procedure(library, procedure, rounding, &operands)?;
+    assert_eq!(actual, u64::from(expected), "{procedure}{operands:?}");
+    Ok(expected)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Everything runs in a local VM: no node is needed
+    let source = fs::read_to_string(fixed_point::LIBRARY_FILE_PATH)?;
+    let library = fixed_point::library(&source)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Compound interest
+    // -------------------------------------------------------------------------
+    // A lending pool rounds the interest it pays on deposits down and the interest it charges
+    // on loans up. Each rounding is below one base unit, but rounding the other way every
+    // period would let depositors and borrowers drain the pool one unit at a time.
+    println!(
+        "\n[STEP 1] {} tokens at a rate of {} per period",
+        to_decimal(PRINCIPAL),
+        to_decimal(RATE)
+    );
+
+    let mut deposit = PRINCIPAL;
+    let mut loan = PRINCIPAL;
+    for period in 1..=PERIODS {
+        deposit = checked(&library, "mul", Rounding::Down, [deposit, RATE, 0])?;
+        loan = checked(&library, "mul", Rounding::Up, [loan, RATE, 0])?;
+        println!("Period {period}: deposit {deposit}, loan {loan}");
+    }
+    println!(
+        "The pool keeps {} base units of margin after {PERIODS} periods",
+        loan - deposit
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Swap prices
+    // -------------------------------------------------------------------------
+    // A constant product pool pays `reserve_out * amount_in / (reserve_in + amount_in)`,
+    // rounded down, and charges `reserve_in * amount_out / (reserve_out - amount_out)` for an
+    // exact output, rounded up.
+    println!("\n[STEP 2] Swapping {AMOUNT_IN} into a pool of {RESERVE_IN} / {RESERVE_OUT}");
+
+    let spot = checked(
+        &library,
+        "div",
+        Rounding::Nearest,
+        [RESERVE_OUT, RESERVE_IN, 0],
+    )?;
+    println!("Spot price: {} out per unit in", to_decimal(spot));
+
+    let amount_out = checked(
+        &library,
+        "mul_div",
+        Rounding::Down,
+        [RESERVE_OUT, AMOUNT_IN, RESERVE_IN + AMOUNT_IN],
+    )?;
+    let cost = checked(
+        &library,
+        "mul_div",
+        Rounding::Up,
+        [RESERVE_IN, amount_out, RESERVE_OUT - amount_out],
+    )?;
+    let price = checked(
+        &library,
+        "div",
+        Rounding::Nearest,
+        [amount_out, AMOUNT_IN, 0],
+    )?;
+    println!(
+        "{AMOUNT_IN} in pays {amount_out} out, an average price of {}",
+        to_decimal(price)
+    );
+    println!("Buying exactly {amount_out} out costs {cost} in");
+    assert!(cost <= AMOUNT_IN);
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Results past u32
+    // -------------------------------------------------------------------------
+    // Products are computed on 64 bits, but results have to fit into a u32: the library
+    // fails instead of wrapping around.
+    println!("\n[STEP 3] Doubling {}", u32::MAX);
+
+    let reference = fixed_point::mul(u32::MAX, 2 * SCALE, Rounding::Down);
+    let Err(err) = fixed_point::execute_procedure(
+        &library,
+        "mul",
+        Rounding::Down,
+        &[u64::from(u32::MAX), u64::from(2 * SCALE)],
+    ) else {
+        return Err("the library returned a result past u32".into());
+    };
+    println!("Rust reference: {reference:?}");
+    println!("MASM: {err}");
+
+    Ok(())
+}
@@ -0,0 +1,413 @@
+use miden_lib::account::auth::{AuthRpoFalcon512, NoAuth};
+use miden_lib::transaction::TransactionKernel;
+use rand::RngCore;
+use std::{fs, path::Path, sync::Arc};
+
+use miden_client::{
+    account::AccountId,
+    asset::{Asset, FungibleAsset},
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    note::{
+        create_p2id_note, Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata,
+        NoteRecipient, NoteScript, NoteTag, NoteType,
+    },
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Felt, Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
+    asset::{NonFungibleAsset, NonFungibleAssetDetails},
+};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_library, wait_for_notes, word_to_masm,
+    },
+};
+
+/// Encodes the recipient, tag, aux, note type and execution hint of `note` as note inputs,
+/// so that a contract can re-create exactly this note as one of its outputs.
+fn output_note_inputs(note: &Note) -> Vec<Felt> {
+    let mut inputs = note.recipient().digest().to_vec();
+    inputs.extend([
+        note.metadata().tag().into(),
+        note.metadata().aux(),
+        Felt::from(note.metadata().note_type()),
+        Felt::new(u64::from(note.metadata().execution_hint())),
+    ]);
+    inputs
+}
+
+/// Builds a public note tagged for the fractional vault.
+fn vault_note(
+    sender: AccountId,
+    vault_id: AccountId,
+    script: NoteScript,
+    inputs: Vec<Felt>,
+    asset: Asset,
+    serial_num: Word,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(vault_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+
+    Ok(Note::new(
+        NoteAssets::new(vec![asset])?,
+        metadata,
+        recipient,
+    ))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("fractional_nft_vault")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create Alice and Bob
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts for Alice and Bob");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice's account ID: {:?}",
+        alice.id().to_bech32(network.network_id())
+    );
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Bob's account ID: {:?}",
+        bob.id().to_bech32(network.network_id())
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Mint an NFT to Alice
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Deploying an NFT collection and minting an NFT to Alice");
+
+    let collection_code =
+        fs::read_to_string(Path::new("../masm/accounts/nft_collection.masm")).unwrap();
+    let collection_component = AccountComponent::compile(
+        &collection_code,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Value(Word::default())],
+    )
+    .unwrap()
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let collection_key_pair = AuthSecretKey::new_rpo_falcon512();
+
+    let collection = AccountBuilder::new(init_seed)
+        .account_type(AccountType::NonFungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(
+            collection_key_pair.public_key().to_commitment(),
+        ))
+        .with_component(collection_component)
+        .build()
+        .unwrap();
+
+    client.add_account(&collection, false).await?;
+    keystore.add_key(&collection_key_pair).unwrap();
+
+    let nft_details =
+        NonFungibleAssetDetails::new(collection.id().prefix(), b"Fractional NFT".to_vec()).unwrap();
+    let nft = NonFungibleAsset::new(&nft_details).unwrap();
+
+    let mint_note = create_p2id_note(
+        collection.id(),
+        alice.id(),
+        vec![nft.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+
+    let script_code = fs::read_to_string(Path::new("../masm/scripts/mint_nft_script.masm"))
+        .unwrap()
+        .replace("{recipient}", &word_to_masm(mint_note.recipient().digest()))
+        .replace(
+            "{execution_hint}",
+            &u64::from(mint_note.metadata().execution_hint()).to_string(),
+        )
+        .replace(
+            "{note_type}",
+            &(mint_note.metadata().note_type() as u8).to_string(),
+        )
+        .replace("{aux}", "0")
+        .replace("{tag}", &u32::from(mint_note.metadata().tag()).to_string())
+        .replace("{nft_asset}", &word_to_masm(Word::from(nft)));
+
+    let collection_library =
+        create_library(&collection_code, "external_contract::nft_collection").unwrap();
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&collection_library)?
+        .compile_tx_script(&script_code)?;
+
+    let mint_request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .expected_output_recipients(vec![mint_note.recipient().clone()])
+        .build()?;
+    let tx_id = client
+        .submit_with_prover(collection.id(), mint_request)
+        .await?;
+    println!("Minted NFT. TX: {:?}", tx_id);
+
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Deploy the fractional vault
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Deploying the fractional vault");
+
+    // The vault is a fungible faucet: the fungible asset it issues are the shares
+    let vault_code =
+        fs::read_to_string(Path::new("../masm/accounts/fractional_vault.masm")).unwrap();
+    let vault_component = AccountComponent::compile(
+        &vault_code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )
+    .unwrap()
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    // No authentication: anyone may trigger the vault, its code enforces the rules
+    let vault = AccountBuilder::new(init_seed)
+        .account_type(AccountType::FungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(vault_component)
+        .build()
+        .unwrap();
+
+    client.add_account(&vault, false).await?;
+    println!(
+        "Vault account ID: {:?}",
+        vault.id().to_bech32(network.network_id())
+    );
+
+    let vault_library = create_library(&vault_code, "external_contract::fractional_vault").unwrap();
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Alice locks the NFT and receives 100 shares
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Alice fractionalizes her NFT into 100 shares");
+
+    let total_shares: u64 = 100;
+    let shares_note = create_p2id_note(
+        vault.id(),
+        alice.id(),
+        vec![FungibleAsset::new(vault.id(), total_shares)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+
+    let mut fractionalize_inputs = output_note_inputs(&shares_note);
+    fractionalize_inputs.insert(4, Felt::new(total_shares));
+
+    let fractionalize_code =
+        fs::read_to_string(Path::new("../masm/notes/fractionalize_note.masm")).unwrap();
+    let fractionalize_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&vault_library)?
+        .compile_note_script(&fractionalize_code)?;
+
+    let serial_num = client.rng().draw_word();
+    let fractionalize_note = vault_note(
+        alice.id(),
+        vault.id(),
+        fractionalize_script,
+        fractionalize_inputs,
+        nft.into(),
+        serial_num,
+    )?;
+
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(fractionalize_note.clone())])
+        .build()?;
+    let tx_id = client.submit_with_prover(alice.id(), request).await?;
+    println!("Alice created the fractionalize note. TX: {:?}", tx_id);
+
+    // Anyone can make the vault consume the note, here we do it ourselves
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(fractionalize_note, None)])
+        .expected_output_recipients(vec![shares_note.recipient().clone()])
+        .build()?;
+    let tx_id = client.submit_with_prover(vault.id(), request).await?;
+    println!(
+        "Vault locked the NFT and minted shares. TX: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Alice transfers 30 shares to Bob
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Alice transfers 30 shares to Bob");
+
+    let transfer_note = create_p2id_note(
+        alice.id(),
+        bob.id(),
+        vec![FungibleAsset::new(vault.id(), 30)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(transfer_note)])
+        .build()?;
+    client.submit_with_prover(alice.id(), request).await?;
+
+    wait_for_notes(&mut client, &network, bob.id(), 1).await?;
+    consume_all_notes(&mut client, bob.id()).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Redemption with only 70% of the shares is rejected
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Redeeming with 70 shares must fail");
+
+    let redeem_code =
+        fs::read_to_string(Path::new("../masm/notes/redeem_shares_note.masm")).unwrap();
+    let redeem_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&vault_library)?
+        .compile_note_script(&redeem_code)?;
+
+    let nft_note = create_p2id_note(
+        vault.id(),
+        alice.id(),
+        vec![nft.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+
+    // The partial redeem note is only built locally and executed against the vault, so
+    // Alice's shares are never put at risk
+    let serial_num = client.rng().draw_word();
+    let partial_redeem_note = vault_note(
+        alice.id(),
+        vault.id(),
+        redeem_script.clone(),
+        output_note_inputs(&nft_note),
+        FungibleAsset::new(vault.id(), 70)?.into(),
+        serial_num,
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(partial_redeem_note, None)])
+        .expected_output_recipients(vec![nft_note.recipient().clone()])
+        .build()?;
+
+    let result = client.execute_transaction(vault.id(), request).await;
+    assert!(result.is_err());
+    println!("Partial redemption rejected: {}", result.unwrap_err());
+
+    // -------------------------------------------------------------------------
+    // STEP 7: Bob sells his shares back and Alice redeems the NFT
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 7] Bob returns his 30 shares, Alice redeems with 100%");
+
+    let transfer_note = create_p2id_note(
+        bob.id(),
+        alice.id(),
+        vec![FungibleAsset::new(vault.id(), 30)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(transfer_note)])
+        .build()?;
+    client.submit_with_prover(bob.id(), request).await?;
+
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+
+    let serial_num = client.rng().draw_word();
+    let redeem_note = vault_note(
+        alice.id(),
+        vault.id(),
+        redeem_script,
+        output_note_inputs(&nft_note),
+        FungibleAsset::new(vault.id(), total_shares)?.into(),
+        serial_num,
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(redeem_note.clone())])
+        .build()?;
+    client.submit_with_prover(alice.id(), request).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(redeem_note, None)])
+        .expected_output_recipients(vec![nft_note.recipient().clone()])
+        .build()?;
+    let tx_id = client.submit_with_prover(vault.id(), request).await?;
+    println!(
+        "Vault burned the shares and released the NFT. TX: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+
+    client.sync_state().await?;
+
+    let alice_account = client.get_account(alice.id()).await?.unwrap();
+    let vault_account = client.get_account(vault.id()).await?.unwrap();
+
+    assert!(alice_account
+        .account()
+        .vault()
+        .has_non_fungible_asset(nft)?);
+    assert!(!vault_account
+        .account()
+        .vault()
+        .has_non_fungible_asset(nft)?);
+    assert_eq!(alice_account.account().vault().get_balance(vault.id())?, 0);
+
+    println!("Alice owns the whole NFT again and all shares were burned!");
+
+    Ok(())
+}
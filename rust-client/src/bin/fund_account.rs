@@ -0,0 +1,79 @@
+//! Tops an account up with the shared tutorial token before a run.
+//!
+//! ```text
+//! cargo run --release --features devnet --bin fund_account -- <ACCOUNT> <AMOUNT>
+//! ```
+//!
+//! `ACCOUNT` is a bech32 id or a label of the known accounts registry. The account is topped
+//! up to `AMOUNT` base units from the faucet deployed by `devnet_setup`, if it holds less.
+
+use std::sync::Arc;
+
+use miden_client::{asset::FungibleAsset, builder::ClientBuilder, rpc::GrpcClient};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    context::TutorialContext,
+    devnet,
+    funding::{FundingGuard, FundingOutcome},
+    known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH},
+};
+
+const USAGE: &str = "usage: fund_account <ACCOUNT> <AMOUNT>";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = rust_client::cli::positional_args();
+    let (Some(account), Some(amount)) = (args.first(), args.get(1)) else {
+        println!("{USAGE}");
+        return Ok(());
+    };
+    let amount: u64 = amount.parse()?;
+
+    let network = rust_client::cli::network().await?;
+    let registry = KnownAccounts::load(DEFAULT_REGISTRY_PATH)?;
+    let account_id = registry.resolve(account, network.network_id())?;
+    let faucet_id = registry
+        .get(devnet::FAUCET)
+        .ok_or("no shared faucet registered, run `devnet_setup` first")?;
+
+    // Initialize client
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, kept across runs
+    let context = TutorialContext::persistent(devnet::STATE)?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore)
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let guard = FundingGuard::new(faucet_id);
+    let required = [FungibleAsset::new(faucet_id, amount)?];
+    match guard
+        .ensure_funded(&mut client, account_id, &required)
+        .await?
+    {
+        FundingOutcome::AlreadyFunded => {
+            println!(
+                "{} already holds {amount} tokens",
+                registry.display(account_id, network.network_id())
+            )
+        }
+        FundingOutcome::ToppedUp { minted } => {
+            println!(
+                "Topped {} up with {minted} tokens",
+                registry.display(account_id, network.network_id())
+            )
+        }
+    }
+
+    Ok(())
+}
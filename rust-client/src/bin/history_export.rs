@@ -0,0 +1,273 @@
+//! Exports the transaction history of tracked accounts for accounting.
+//!
+//! ```text
+//! cargo run --release --features devnet --bin history_export -- [ACCOUNT...] [--format csv|json] [--out FILE]
+//!     [--uncommitted]
+//! ```
+//!
+//! Accounts are bech32 ids or labels from the known accounts registry; all tracked accounts are
+//! exported when none is given. Every asset moved by a transaction becomes one row.
+
+use miden_lib::note::WellKnownNote;
+use std::{collections::BTreeMap, sync::Arc};
+
+use miden_client::{
+    account::AccountId,
+    asset::Asset,
+    block::BlockNumber,
+    builder::ClientBuilder,
+    note::Note,
+    rpc::{GrpcClient, NodeRpcClient},
+    store::{NoteFilter, TransactionFilter},
+    transaction::{OutputNote, TransactionStatus},
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    context::TutorialContext,
+    devnet,
+    format::AssetFormatter,
+    known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH},
+};
+use serde::Serialize;
+
+/// One asset moved by a transaction.
+#[derive(Debug, Serialize)]
+struct HistoryRow {
+    transaction_id: String,
+    status: String,
+    /// Block the transaction was committed in, or executed against while pending.
+    block: u32,
+    /// Unix timestamp of the block, `None` while the transaction is not committed.
+    timestamp: Option<u32>,
+    account: String,
+    /// `in` for assets received through consumed notes, `out` for assets sent in new notes.
+    direction: &'static str,
+    faucet: String,
+    amount: String,
+    /// Sender of incoming notes, target of outgoing P2ID notes.
+    counterparty: Option<String>,
+}
+
+/// Returns the target of a P2ID note, read from its inputs.
+fn p2id_target(note: &Note) -> Option<AccountId> {
+    if note.script().root() != WellKnownNote::P2ID.script_root() {
+        return None;
+    }
+    match note.inputs().values() {
+        [suffix, prefix, ..] => AccountId::try_from([*prefix, *suffix]).ok(),
+        _ => None,
+    }
+}
+
+/// Escapes a CSV field if needed.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(rows: &[HistoryRow]) -> String {
+    let mut csv = String::from(
+        "transaction_id,status,block,timestamp,account,direction,faucet,amount,counterparty\n",
+    );
+    for row in rows {
+        let fields = [
+            row.transaction_id.clone(),
+            row.status.clone(),
+            row.block.to_string(),
+            row.timestamp.map(|t| t.to_string()).unwrap_or_default(),
+            row.account.clone(),
+            row.direction.to_string(),
+            row.faucet.clone(),
+            row.amount.clone(),
+            row.counterparty.clone().unwrap_or_default(),
+        ];
+        let line: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&line.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Parse the arguments
+    let args = rust_client::cli::positional_args();
+    let mut selected = Vec::new();
+    let mut format = "csv".to_string();
+    let mut out = None;
+    let mut uncommitted_only = false;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = args.next().ok_or("--format requires a value")?,
+            "--out" => out = Some(args.next().ok_or("--out requires a value")?),
+            "--uncommitted" => uncommitted_only = true,
+            _ => selected.push(arg),
+        }
+    }
+    if format != "csv" && format != "json" {
+        return Err(format!("unknown format `{format}`, expected csv or json").into());
+    }
+
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, kept across runs
+    let context = TutorialContext::persistent(devnet::STATE)?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client.clone())
+        .sqlite_store(store_path)
+        .authenticator(keystore)
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    client.sync_state().await?;
+
+    // -------------------------------------------------------------------------
+    // Select accounts and transactions
+    // -------------------------------------------------------------------------
+    let known_accounts = KnownAccounts::load(DEFAULT_REGISTRY_PATH)?;
+    let mut account_ids = Vec::new();
+    for account in &selected {
+        let account_id = known_accounts.resolve(account, network.network_id())?;
+        account_ids.push(account_id);
+    }
+    if account_ids.is_empty() {
+        account_ids = client
+            .get_account_headers()
+            .await?
+            .iter()
+            .map(|(header, _)| header.id())
+            .collect();
+    }
+
+    // `Uncommitted` narrows the export to transactions still waiting for a block. The account
+    // selection is applied on top, since the store filters transactions by state or id only.
+    let filter = if uncommitted_only {
+        TransactionFilter::Uncommitted
+    } else {
+        TransactionFilter::All
+    };
+    let mut transactions: Vec<_> = client
+        .get_transactions(filter)
+        .await?
+        .into_iter()
+        .filter(|tx| account_ids.contains(&tx.details.account_id))
+        .collect();
+    transactions.sort_by_key(|tx| tx.details.block_num);
+
+    // -------------------------------------------------------------------------
+    // Decode every transaction into rows
+    // -------------------------------------------------------------------------
+    let mut formatter = AssetFormatter::new(network.network_id());
+    let mut timestamps: BTreeMap<BlockNumber, u32> = BTreeMap::new();
+    let mut rows = Vec::new();
+
+    for tx in &transactions {
+        let details = &tx.details;
+        // Timestamps come from the header of the block the transaction was committed in
+        let committed_block = match tx.status {
+            TransactionStatus::Committed { block_number, .. } => Some(block_number),
+            _ => None,
+        };
+        let timestamp = match committed_block {
+            Some(block_num) => Some(match timestamps.get(&block_num) {
+                Some(timestamp) => *timestamp,
+                None => {
+                    let (header, _) = rpc_client
+                        .get_block_header_by_number(Some(block_num), false)
+                        .await?;
+                    timestamps.insert(block_num, header.timestamp());
+                    header.timestamp()
+                }
+            }),
+            None => None,
+        };
+
+        let mut movements: Vec<(&'static str, Asset, Option<AccountId>)> = Vec::new();
+
+        // Incoming: the notes consumed by the transaction, found through their nullifiers
+        let consumed_notes = client
+            .get_input_notes(NoteFilter::Nullifiers(
+                details.input_note_nullifiers.clone(),
+            ))
+            .await?;
+        for note in &consumed_notes {
+            let sender = note.metadata().map(|metadata| metadata.sender());
+            for asset in note.assets().iter() {
+                movements.push(("in", *asset, sender));
+            }
+        }
+
+        // Outgoing: the assets of the notes created by the transaction
+        for note in details.output_notes.iter() {
+            let target = match note {
+                OutputNote::Full(note) => p2id_target(note),
+                _ => None,
+            };
+            if let Some(assets) = note.assets() {
+                for asset in assets.iter() {
+                    movements.push(("out", *asset, target));
+                }
+            }
+        }
+
+        for (direction, asset, counterparty) in movements {
+            let faucet = match asset {
+                Asset::Fungible(fungible) => {
+                    formatter
+                        .load_token(rpc_client.as_ref(), fungible.faucet_id())
+                        .await?;
+                    fungible.faucet_id().to_bech32(network.network_id())
+                }
+                Asset::NonFungible(nft) => nft.faucet_id_prefix().to_string(),
+            };
+            rows.push(HistoryRow {
+                transaction_id: tx.id.to_hex(),
+                status: tx.status.to_string(),
+                block: committed_block.unwrap_or(details.block_num).as_u32(),
+                timestamp,
+                account: known_accounts.display(details.account_id, network.network_id()),
+                direction,
+                faucet,
+                amount: formatter.format(&asset),
+                counterparty: counterparty
+                    .map(|id| known_accounts.display(id, network.network_id())),
+            });
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // Write the export
+    // -------------------------------------------------------------------------
+    let contents = if format == "json" {
+        serde_json::to_string_pretty(&rows)?
+    } else {
+        to_csv(&rows)
+    };
+
+    match out {
+        Some(path) => {
+            std::fs::write(&path, contents)?;
+            println!(
+                "Exported {} rows from {} transactions to {path}",
+                rows.len(),
+                transactions.len()
+            );
+        }
+        None => print!("{contents}"),
+    }
+
+    Ok(())
+}
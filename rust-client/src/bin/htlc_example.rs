@@ -0,0 +1,260 @@
+use rand::rngs::StdRng;
+use std::{fs, path::Path, sync::Arc};
+use tokio::time::{sleep, Duration};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequest, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{block::BlockNumber, Hasher};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+};
+
+const NOTE_PATH: &str = "../masm/notes/htlc_note.masm";
+
+/// Tokens minted to Alice.
+const FUNDS: u64 = 100;
+/// Tokens Bob claims with the preimage.
+const CLAIMED: u64 = 40;
+/// Tokens Alice takes back after the refund block.
+const REFUNDED: u64 = 25;
+/// Blocks before Alice can take back the note Bob claims, far more than he needs.
+const CLAIM_WINDOW: u32 = 100;
+/// Blocks before Alice can take back the note Bob leaves alone.
+const REFUND_WINDOW: u32 = 5;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+/// Builds a hashed time-locked note from `sender` carrying `asset`, which `target` claims with
+/// the preimage of `hashlock` and `sender` takes back from block `refund_after` on.
+fn htlc_note(
+    sender: AccountId,
+    target: AccountId,
+    asset: FungibleAsset,
+    hashlock: Word,
+    refund_after: BlockNumber,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    let mut inputs = hashlock.to_vec();
+    inputs.extend([
+        target.prefix().as_felt(),
+        target.suffix(),
+        Felt::from(refund_after.as_u32()),
+    ]);
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(target),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(
+        NoteAssets::new(vec![asset.into()])?,
+        metadata,
+        recipient,
+    ))
+}
+
+/// Builds a request consuming `note` with `note_args`, the preimage when claiming.
+fn consume_request(note: &Note, note_args: Word) -> TransactionRequest {
+    TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note.clone(), Some(note_args))])
+        .build()
+        .unwrap()
+}
+
+/// Has the sender of `note` send it and waits until it is committed.
+async fn send_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    note: &Note,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    let tx_id = client
+        .submit_with_prover(note.metadata().sender(), request)
+        .await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("htlc_example")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create Alice and Bob, and fund Alice
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice and Bob, minting {FUNDS} tokens to Alice");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), FUNDS)?,
+        alice.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice locks tokens for Bob under a hashlock
+    // -------------------------------------------------------------------------
+    // Alice picks the secret and publishes only its hash. In a cross-chain atomic swap Bob
+    // locks his side under the same hash on the other chain, and Alice claiming it there
+    // reveals the secret Bob then uses here.
+    let preimage = client.rng().draw_word();
+    let hashlock = Hasher::hash_elements(preimage.as_elements());
+    println!("\n[STEP 2] Alice locks {CLAIMED} tokens for Bob under hashlock {hashlock}");
+
+    let script = client
+        .script_builder()
+        .compile_note_script(&fs::read_to_string(Path::new(NOTE_PATH))?)?;
+    let tip = client.sync_state().await?.block_num.as_u32();
+    let claimed = htlc_note(
+        alice.id(),
+        bob.id(),
+        FungibleAsset::new(faucet.id(), CLAIMED)?,
+        hashlock,
+        BlockNumber::from(tip + CLAIM_WINDOW),
+        script.clone(),
+        client.rng().draw_word(),
+    )?;
+    send_note(&mut client, &claimed).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Bob claims the tokens with the preimage
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Bob claims the note");
+
+    let guess = client.rng().draw_word();
+    let Err(err) = client
+        .execute_transaction(bob.id(), consume_request(&claimed, guess))
+        .await
+    else {
+        return Err("Bob claimed the note without the preimage".into());
+    };
+    println!("With a wrong preimage: {err}");
+
+    let tx_id = client
+        .submit_with_prover(bob.id(), consume_request(&claimed, preimage))
+        .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("With the preimage: claimed {CLAIMED} tokens");
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Alice takes back a note Bob did not claim
+    // -------------------------------------------------------------------------
+    let tip = client.sync_state().await?.block_num.as_u32();
+    let refund_after = BlockNumber::from(tip + REFUND_WINDOW);
+    println!(
+        "\n[STEP 4] Alice locks {REFUNDED} tokens for Bob, refundable from block {refund_after}"
+    );
+
+    let refunded = htlc_note(
+        alice.id(),
+        bob.id(),
+        FungibleAsset::new(faucet.id(), REFUNDED)?,
+        hashlock,
+        refund_after,
+        script,
+        client.rng().draw_word(),
+    )?;
+    send_note(&mut client, &refunded).await?;
+
+    let Err(err) = client
+        .execute_transaction(alice.id(), consume_request(&refunded, Word::default()))
+        .await
+    else {
+        return Err("Alice took the note back before the refund block".into());
+    };
+    println!("Before the refund block: {err}");
+
+    loop {
+        let block_num = client.sync_state().await?.block_num;
+        if block_num >= refund_after {
+            break;
+        }
+        println!("Block {block_num}, waiting for block {refund_after}...");
+        sleep(Duration::from_secs(3)).await;
+    }
+
+    let tx_id = client
+        .submit_with_prover(alice.id(), consume_request(&refunded, Word::default()))
+        .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("After the refund block: took back {REFUNDED} tokens");
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking balances");
+
+    let alice_funds = balance(&client, alice.id(), faucet.id()).await?;
+    let bob_funds = balance(&client, bob.id(), faucet.id()).await?;
+    println!("Alice: {alice_funds}, Bob: {bob_funds}");
+
+    assert_eq!(alice_funds, FUNDS - CLAIMED);
+    assert_eq!(bob_funds, CLAIMED);
+
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use std::{fs, path::Path, sync::Arc};
+use tokio::time::{sleep, Duration};
+
+use miden_client::{
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    note::{
+        create_p2id_note, Note, NoteAssets, NoteExecutionHint, NoteId, NoteInputs, NoteMetadata,
+        NoteRecipient, NoteTag, NoteType,
+    },
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequest, TransactionRequestBuilder},
+    Felt, Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+};
+
+/// Number of blocks after its creation before the note can be consumed.
+const NOT_BEFORE_OFFSET: u32 = 10;
+/// Nonce the consuming account must have reached, i.e. transactions it must have executed.
+const MIN_NONCE: u64 = 1;
+
+/// Builds a request consuming the kernel inputs note, passing `note_args` to its script.
+fn consume_request(note_id: NoteId, note_args: Word) -> TransactionRequest {
+    TransactionRequestBuilder::new()
+        .authenticated_input_notes([(note_id, Some(note_args))])
+        .build()
+        .unwrap()
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("kernel_inputs_note")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create Alice, Bob and a faucet, and fund Alice
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice, Bob and a faucet");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!("Bob: {}", bob.id().to_bech32(network.network_id()));
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    println!("Faucet: {}", faucet.id().to_bech32(network.network_id()));
+
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), 100).unwrap(),
+        alice.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    client.submit_with_prover(faucet.id(), mint_request).await?;
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice creates the kernel inputs note for Bob
+    // -------------------------------------------------------------------------
+    // Alice picks the serial number and the note inputs. The sender is not one of them: the
+    // kernel of Alice's transaction writes her id into the note metadata.
+    println!("\n[STEP 2] Alice creates the kernel inputs note");
+
+    let created_at = client.sync_state().await?.block_num.as_u32();
+    let not_before = created_at + NOT_BEFORE_OFFSET;
+    let serial_num = client.rng().draw_word();
+
+    let note_code = fs::read_to_string(Path::new("../masm/notes/kernel_inputs_note.masm"))?;
+    let note_script = client.script_builder().compile_note_script(&note_code)?;
+    let note_inputs = NoteInputs::new(vec![
+        Felt::from(not_before),
+        alice.id().prefix().as_felt(),
+        alice.id().suffix(),
+        Felt::new(MIN_NONCE),
+    ])?;
+    let kernel_note = Note::new(
+        NoteAssets::new(vec![FungibleAsset::new(faucet.id(), 50)?.into()])?,
+        NoteMetadata::new(
+            alice.id(),
+            NoteType::Public,
+            NoteTag::from_account_id(bob.id()),
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )?,
+        NoteRecipient::new(serial_num, note_script, note_inputs),
+    );
+
+    // A plain payment Bob can consume right away, to get his nonce above zero later on
+    let payment = create_p2id_note(
+        alice.id(),
+        bob.id(),
+        vec![FungibleAsset::new(faucet.id(), 10)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![
+            OutputNote::Full(kernel_note.clone()),
+            OutputNote::Full(payment.clone()),
+        ])
+        .build()?;
+    let tx_id = client.submit_with_prover(alice.id(), request).await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+    wait_for_tx(&mut client, tx_id).await?;
+
+    println!("Not before block: {not_before} (created at block {created_at})");
+    println!(
+        "Sender in metadata: {}",
+        kernel_note
+            .metadata()
+            .sender()
+            .to_bech32(network.network_id())
+    );
+    println!("Serial number, shared with Bob off-chain: {serial_num:?}");
+
+    // -------------------------------------------------------------------------
+    // STEP 3: The serial number and the block number
+    // -------------------------------------------------------------------------
+    // The script compares the serial number of the note being consumed with the note args,
+    // which Bob chooses. Only the right serial number passes.
+    println!("\n[STEP 3] Consuming with a wrong serial number, then too early");
+
+    wait_for_notes(&mut client, &network, bob.id(), 2).await?;
+
+    let wrong_serial_num = client.rng().draw_word();
+    match client
+        .execute_transaction(
+            bob.id(),
+            consume_request(kernel_note.id(), wrong_serial_num),
+        )
+        .await
+    {
+        Ok(_) => panic!("the note accepted a wrong serial number"),
+        Err(err) => println!("Rejected as expected: {err}"),
+    }
+
+    // `tx::get_block_number` is the reference block of the transaction: the block the client
+    // last synced to, not the one that will include the transaction
+    let reference_block = client.sync_state().await?.block_num.as_u32();
+    if reference_block < not_before {
+        println!("Reference block: {reference_block}");
+        match client
+            .execute_transaction(bob.id(), consume_request(kernel_note.id(), serial_num))
+            .await
+        {
+            Ok(_) => panic!("the note was consumed before block {not_before}"),
+            Err(err) => println!("Rejected as expected: {err}"),
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 4: The account nonce
+    // -------------------------------------------------------------------------
+    // Bob has not executed any transaction yet, so his nonce is still zero.
+    println!("\n[STEP 4] Waiting for block {not_before}, then consuming with a fresh account");
+
+    loop {
+        let block_num = client.sync_state().await?.block_num.as_u32();
+        if block_num >= not_before {
+            break;
+        }
+        println!("Block {block_num}, waiting for block {not_before}...");
+        sleep(Duration::from_secs(3)).await;
+    }
+
+    let nonce = client
+        .get_account(bob.id())
+        .await?
+        .unwrap()
+        .account()
+        .nonce();
+    println!("Bob's nonce: {nonce}");
+    match client
+        .execute_transaction(bob.id(), consume_request(kernel_note.id(), serial_num))
+        .await
+    {
+        Ok(_) => panic!("the note was consumed by an account with nonce {nonce}"),
+        Err(err) => println!("Rejected as expected: {err}"),
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Bob transacts once, then consumes the note
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Bob consumes the payment, then the kernel inputs note");
+
+    let request = TransactionRequestBuilder::new().build_consume_notes(vec![payment.id()])?;
+    let tx_id = client.submit_with_prover(bob.id(), request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let nonce = client
+        .get_account(bob.id())
+        .await?
+        .unwrap()
+        .account()
+        .nonce();
+    println!("Bob's nonce: {nonce}");
+
+    let tx_id = client
+        .submit_with_prover(bob.id(), consume_request(kernel_note.id(), serial_num))
+        .await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let balance = client
+        .get_account(bob.id())
+        .await?
+        .unwrap()
+        .account()
+        .vault()
+        .get_balance(faucet.id())?;
+    println!("Bob balance: {balance}");
+    assert_eq!(balance, 60);
+
+    Ok(())
+}
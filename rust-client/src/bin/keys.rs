@@ -0,0 +1,217 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use miden_client::{
+    account::{AccountId, StorageSlot},
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    rpc::GrpcClient,
+    utils::Deserializable,
+    Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    context::TutorialContext,
+    devnet,
+    known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH},
+};
+
+/// Location of the key labels, kept outside of the keystore directory so the keystore only
+/// contains key files.
+const LABELS_PATH: &str = "./key_labels.json";
+
+const USAGE: &str = "usage:
+  keys [list]                 list keys and the accounts they control
+  keys label <PUB_KEY> <LABEL> label the key whose public key starts with <PUB_KEY>
+  keys prune [--yes]          delete keys that control no account in the store";
+
+/// A key file of the keystore.
+struct KeyEntry {
+    path: PathBuf,
+    /// Hex encoded public key commitment, as stored in the account's auth component.
+    pub_key: String,
+}
+
+/// Reads all key files from `keystore_path`. Files which are not keys are reported and skipped.
+fn read_keys(keystore_path: &Path) -> io::Result<Vec<KeyEntry>> {
+    let mut keys = Vec::new();
+    for entry in fs::read_dir(keystore_path)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let key = decode_hex(contents.trim())
+            .and_then(|bytes| AuthSecretKey::read_from_bytes(&bytes).ok());
+        match key {
+            Some(key) => {
+                let pub_key: Word = key.public_key().to_commitment().into();
+                keys.push(KeyEntry {
+                    path,
+                    pub_key: pub_key.to_hex(),
+                });
+            }
+            None => println!("Skipping {}: not a key file", path.display()),
+        }
+    }
+    keys.sort_by(|a, b| a.pub_key.cmp(&b.pub_key));
+    Ok(keys)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn load_labels() -> io::Result<BTreeMap<String, String>> {
+    match fs::read_to_string(LABELS_PATH) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(err) => Err(err),
+    }
+}
+
+fn save_labels(labels: &BTreeMap<String, String>) -> io::Result<()> {
+    let contents = serde_json::to_string_pretty(labels)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    fs::write(LABELS_PATH, contents)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = rust_client::cli::positional_args();
+    let command = args.first().map(String::as_str).unwrap_or("list");
+
+    // The keys of the accounts created by `devnet_setup` and the binaries sharing its state
+    let context = TutorialContext::persistent(devnet::STATE)?;
+    let keystore = context.keystore()?;
+    let keys = read_keys(&context.keystore_path())?;
+    let mut labels = load_labels()?;
+
+    if command == "label" {
+        let (Some(prefix), Some(label)) = (args.get(1), args.get(2)) else {
+            println!("{USAGE}");
+            return Ok(());
+        };
+        let matches: Vec<&KeyEntry> = keys
+            .iter()
+            .filter(|key| key.pub_key.starts_with(prefix.as_str()))
+            .collect();
+        match matches.as_slice() {
+            [key] => {
+                labels.insert(key.pub_key.clone(), label.clone());
+                save_labels(&labels)?;
+                println!("Labeled {} as \"{label}\"", key.pub_key);
+            }
+            [] => println!("No key starts with {prefix}"),
+            _ => println!(
+                "{} keys start with {prefix}, use a longer prefix",
+                matches.len()
+            ),
+        }
+        return Ok(());
+    }
+
+    if command != "list" && command != "prune" {
+        println!("{USAGE}");
+        return Ok(());
+    }
+
+    // -------------------------------------------------------------------------
+    // Match keys with the accounts of the store
+    // -------------------------------------------------------------------------
+    // Auth components store the public key commitment in a value slot of the account storage,
+    // so a key controls every account holding its commitment in one of those slots.
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    let client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(context.store_path())
+        .authenticator(keystore)
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let mut controlled: BTreeMap<String, Vec<AccountId>> = BTreeMap::new();
+    for (header, _) in client.get_account_headers().await? {
+        let Some(record) = client.get_account(header.id()).await? else {
+            continue;
+        };
+        for slot in record.account().storage().slots() {
+            if let StorageSlot::Value(value) = slot {
+                controlled
+                    .entry(value.to_hex())
+                    .or_default()
+                    .push(header.id());
+            }
+        }
+    }
+
+    let known_accounts = KnownAccounts::load(DEFAULT_REGISTRY_PATH)?;
+    let orphaned: Vec<&KeyEntry> = keys
+        .iter()
+        .filter(|key| !controlled.contains_key(&key.pub_key))
+        .collect();
+
+    if command == "list" {
+        println!("{:<68} {:<16} ACCOUNTS", "PUBLIC KEY", "LABEL");
+        for key in &keys {
+            let label = labels.get(&key.pub_key).map(String::as_str).unwrap_or("-");
+            let accounts = controlled
+                .get(&key.pub_key)
+                .map(|ids| {
+                    ids.iter()
+                        .map(|id| known_accounts.display(*id, network.network_id()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .unwrap_or_else(|| "(orphaned)".to_string());
+            println!("{:<68} {:<16} {}", key.pub_key, label, accounts);
+        }
+        println!("\n{} keys, {} orphaned", keys.len(), orphaned.len());
+        return Ok(());
+    }
+
+    // -------------------------------------------------------------------------
+    // Prune orphaned keys
+    // -------------------------------------------------------------------------
+    // Keys of accounts which were never added to this store look orphaned too, so pruning is a
+    // dry run unless `--yes` is passed.
+    let confirmed = args.iter().any(|arg| arg == "--yes");
+    if orphaned.is_empty() {
+        println!("No orphaned keys");
+        return Ok(());
+    }
+
+    for key in &orphaned {
+        if confirmed {
+            fs::remove_file(&key.path)?;
+            labels.remove(&key.pub_key);
+            println!("Deleted {}", key.pub_key);
+        } else {
+            println!("Would delete {}", key.pub_key);
+        }
+    }
+
+    if confirmed {
+        save_labels(&labels)?;
+    } else {
+        println!("\nRun `keys prune --yes` to delete these keys");
+    }
+
+    Ok(())
+}
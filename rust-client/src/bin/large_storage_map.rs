@@ -0,0 +1,242 @@
+use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel};
+use rand::RngCore;
+use std::{fs, path::Path, sync::Arc};
+
+use miden_client::{
+    builder::ClientBuilder,
+    rpc::{
+        domain::account::{AccountStorageRequirements, StorageMapKey},
+        GrpcClient,
+    },
+    transaction::{ForeignAccount, TransactionRequestBuilder},
+    utils::Serializable,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    crypto::hash::rpo::Rpo256,
+    Felt, Word, ZERO,
+};
+use rust_client::{
+    cli::ProverOption,
+    context::TutorialContext,
+    helpers::{create_basic_account, create_library, total_cycles, wait_for_commit, word_to_masm},
+};
+
+/// Number of entries written per transaction.
+const BATCH_SIZE: u64 = 256;
+/// Number of batches, for a total of `BATCH_SIZE * NUM_BATCHES` entries.
+const NUM_BATCHES: u64 = 8;
+
+/// Returns the i-th entry written to the map.
+fn entry(i: u64) -> (Word, Word) {
+    let key: Word = [ZERO, ZERO, ZERO, Felt::new(i)].into();
+    let value: Word = [Felt::new(i), Felt::new(i * 2), Felt::new(i * 3), ZERO].into();
+    (key, value)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("large_storage_map")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Deploy the map contract
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Deploying the map contract");
+
+    let contract_code = fs::read_to_string(Path::new("../masm/accounts/batch_map.masm"))?;
+    let component = AccountComponent::compile(
+        &contract_code,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Map(StorageMap::new())],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let map_contract = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component.clone())
+        .build()?;
+    client.add_account(&map_contract, false).await?;
+    println!(
+        "Map contract: {}",
+        map_contract.id().to_bech32(network.network_id())
+    );
+
+    let library = create_library(&contract_code, "external_contract::batch_map")?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Grow the map batch by batch
+    // -------------------------------------------------------------------------
+    // The account commits to the map through its root only, so a transaction touching a few
+    // keys stays small however large the map is. What grows is the map itself, which the node
+    // and every client tracking the public account have to store.
+    println!(
+        "\n[STEP 2] Writing {} entries in batches of {}",
+        BATCH_SIZE * NUM_BATCHES,
+        BATCH_SIZE
+    );
+    println!(
+        "{:>8} {:>10} {:>14} {:>16} {:>14}",
+        "ENTRIES", "CYCLES", "PROVEN TX (B)", "KEY PROOF (B)", "MAP (B)"
+    );
+
+    let probe_key = entry(1).0;
+    for batch in 0..NUM_BATCHES {
+        let entries_data: Vec<Felt> = (batch * BATCH_SIZE + 1..=(batch + 1) * BATCH_SIZE)
+            .flat_map(|i| {
+                let (key, value) = entry(i);
+                [key, value]
+            })
+            .flat_map(|word| word.iter().copied().collect::<Vec<_>>())
+            .collect();
+        let entries_commitment = Rpo256::hash_elements(&entries_data);
+
+        let script_code = format!(
+            "use.external_contract::batch_map\n\
+             begin\n    push.{}\n    push.{}\n    call.batch_map::set_entries\nend\n",
+            BATCH_SIZE,
+            word_to_masm(entries_commitment)
+        );
+        let tx_script = client
+            .script_builder()
+            .with_dynamically_linked_library(&library)?
+            .compile_tx_script(&script_code)?;
+        let request = TransactionRequestBuilder::new()
+            .custom_script(tx_script)
+            .extend_advice_map([(entries_commitment, entries_data)])
+            .build()?;
+
+        // Proving is done by hand instead of through `submit_with_prover` to get hold of the
+        // proven transaction and measure it
+        let tx_result = client
+            .execute_transaction(map_contract.id(), request)
+            .await?;
+        let cycles = total_cycles(tx_result.executed_transaction().measurements());
+        let proven_size = match ProverOption::global().transaction_prover() {
+            Some(prover) => {
+                let proven = client.prove_transaction_with(&tx_result, prover).await?;
+                let proven_size = proven.to_bytes().len().to_string();
+                let height = client.submit_proven_transaction(proven, &tx_result).await?;
+                client.apply_transaction(&tx_result, height).await?;
+                proven_size
+            }
+            None => "-".to_string(),
+        };
+
+        let record = client.get_account(map_contract.id()).await?.unwrap();
+        let StorageSlot::Map(map) = &record.account().storage().slots()[0] else {
+            unreachable!("slot 0 of the contract is a map");
+        };
+        println!(
+            "{:>8} {:>10} {:>14} {:>16} {:>14}",
+            map.entries().count(),
+            cycles,
+            proven_size,
+            map.open(&probe_key).to_bytes().len(),
+            map.to_bytes().len()
+        );
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Read a single key from another account
+    // -------------------------------------------------------------------------
+    // With `AccountStorageRequirements`, the client fetches a Merkle proof for the requested
+    // key only: the foreign procedure reads it without the rest of the map being loaded.
+    println!("\n[STEP 3] Reading one key through foreign procedure invocation");
+
+    // The node must know the latest map state before it can prove entries of it
+    wait_for_commit(&mut client).await?;
+
+    let reader = create_basic_account(&mut client, &keystore).await?;
+
+    let get_entry_export = component
+        .library()
+        .exports()
+        .find(|export| export.name.name.as_str() == "get_entry")
+        .unwrap();
+    let get_entry_node = component
+        .library()
+        .get_export_node_id(&get_entry_export.name);
+    let get_entry_hash = component
+        .library()
+        .mast_forest()
+        .get_node_by_id(get_entry_node)
+        .unwrap()
+        .digest();
+
+    let read_key = entry(BATCH_SIZE * NUM_BATCHES / 2).0;
+    let expected_value = entry(BATCH_SIZE * NUM_BATCHES / 2).1;
+    let script_code = format!(
+        "
+use.miden::tx
+
+begin
+    push.{key}
+    push.{proc_hash}
+    push.{suffix}
+    push.{prefix}
+    # => [prefix, suffix, GET_ENTRY_HASH, KEY]
+
+    exec.tx::execute_foreign_procedure
+    # => [VALUE]
+
+    push.{expected}
+    assert_eqw.err=\"unexpected map value\"
+end
+",
+        key = word_to_masm(read_key),
+        proc_hash = word_to_masm(get_entry_hash),
+        suffix = map_contract.id().suffix(),
+        prefix = map_contract.id().prefix(),
+        expected = word_to_masm(expected_value),
+    );
+    let tx_script = client.script_builder().compile_tx_script(&script_code)?;
+
+    let foreign_account = ForeignAccount::public(
+        map_contract.id(),
+        AccountStorageRequirements::new([(0u8, &[StorageMapKey::from(read_key)])]),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .foreign_accounts([foreign_account])
+        .custom_script(tx_script)
+        .build()?;
+
+    // Execution is enough to check the read, nothing needs to be submitted
+    let tx_result = client.execute_transaction(reader.id(), request).await?;
+    println!(
+        "Read key {} of a {}-entry map in {} cycles",
+        BATCH_SIZE * NUM_BATCHES / 2,
+        BATCH_SIZE * NUM_BATCHES,
+        total_cycles(tx_result.executed_transaction().measurements())
+    );
+
+    Ok(())
+}
@@ -0,0 +1,357 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId,
+    address::NetworkId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{NoteScript, NoteType},
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::block::BlockNumber;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    daemon::Service,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_commit,
+        wait_for_notes, wait_for_tx,
+    },
+    lending::{self, Pool, PriceSource, RiskParams},
+};
+
+/// Risk parameters of the pool: borrow up to 50% of the collateral value, liquidation above
+/// 80%.
+const RISK: RiskParams = RiskParams {
+    max_ltv: 50,
+    liquidation_threshold: 80,
+};
+/// Price of one collateral token when Bob borrows, in borrowed tokens.
+const INITIAL_PRICE: u64 = 100;
+/// Price after the crash, below the liquidation price of Bob's position.
+const CRASHED_PRICE: u64 = 45;
+/// Collateral Bob deposits.
+const COLLATERAL: u64 = 10;
+/// Amount Bob borrows, 40% of the value of his collateral at the initial price.
+const LOAN: u64 = 400;
+/// Liquidity Alice supplies to the pool.
+const LIQUIDITY: u64 = 1_000;
+/// Borrowed tokens the bot holds to repay debts with.
+const BOT_FUNDS: u64 = 500;
+
+/// Repays the debt of every position of a pool that fell below the liquidation threshold, in
+/// exchange for its collateral.
+///
+/// Every iteration reads the pool and the price feed as of the latest block and checks each
+/// position the same way the contract does, so it only submits liquidations the pool accepts.
+/// Collateral seized by earlier liquidations is collected on the next iteration.
+struct LiquidationBot {
+    client: Client<FilesystemKeyStore<StdRng>>,
+    /// Network the addresses printed by the bot are for.
+    network_id: NetworkId,
+    pool_id: AccountId,
+    liquidator: AccountId,
+    script: NoteScript,
+    liquidations: usize,
+}
+
+impl LiquidationBot {
+    /// Returns the balance of the liquidator in tokens of `faucet_id`.
+    async fn balance(&self, faucet_id: AccountId) -> Result<u64, Box<dyn std::error::Error>> {
+        let record = self
+            .client
+            .get_account(self.liquidator)
+            .await?
+            .ok_or("the liquidator is not tracked by the client")?;
+        Ok(record.account().vault().get_balance(faucet_id)?)
+    }
+}
+
+impl Service for LiquidationBot {
+    async fn tick(
+        &mut self,
+        _last_processed: Option<BlockNumber>,
+    ) -> Result<BlockNumber, Box<dyn std::error::Error>> {
+        let sync_summary = self.client.sync_state().await?;
+
+        if !self
+            .client
+            .get_consumable_notes(Some(self.liquidator))
+            .await?
+            .is_empty()
+        {
+            println!("Collecting seized collateral");
+            consume_all_notes(&mut self.client, self.liquidator).await?;
+        }
+
+        let pool_record = self
+            .client
+            .get_account(self.pool_id)
+            .await?
+            .ok_or("the pool is not tracked by the client")?;
+        let pool = Pool::from_account(pool_record.account())?;
+        // The bot reads the price off-chain, which only a price feed allows
+        let PriceSource::Feed(price_feed) = pool.price_source else {
+            return Err("the bot only watches pools priced by a price feed".into());
+        };
+        let feed_record = self
+            .client
+            .get_account(price_feed)
+            .await?
+            .ok_or("the price feed is not tracked by the client")?;
+        let price = lending::price(feed_record.account())?;
+
+        let liquidatable: Vec<_> = pool
+            .positions
+            .iter()
+            .filter(|position| position.is_liquidatable(price, pool.risk))
+            .copied()
+            .collect();
+        println!(
+            "Block {}: price {price}, {} position(s), {} liquidatable",
+            sync_summary.block_num,
+            pool.positions.len(),
+            liquidatable.len()
+        );
+
+        let mut funds = self.balance(pool.debt_faucet).await?;
+        for position in liquidatable {
+            let owner = position.owner.to_bech32(self.network_id.clone());
+            if position.debt > funds {
+                println!(
+                    "Skipping {owner}: repaying {} needs more than the {funds} held",
+                    position.debt
+                );
+                continue;
+            }
+
+            // A failed liquidation, e.g. because somebody else was faster, must not stop the
+            // bot from handling the other positions
+            match lending::liquidate(
+                &mut self.client,
+                self.liquidator,
+                &pool,
+                &position,
+                self.script.clone(),
+            )
+            .await
+            {
+                Ok(tx_id) => {
+                    funds -= position.debt;
+                    self.liquidations += 1;
+                    println!(
+                        "Liquidated {owner}: repaid {}, seized {}. TX: {:?}",
+                        position.debt, position.collateral, tx_id
+                    );
+                }
+                Err(err) => println!("Could not liquidate {owner}: {err}"),
+            }
+        }
+
+        Ok(sync_summary.block_num)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("liquidation_bot")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the lender, the borrower, the bot and both assets
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and funding them");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let liquidator = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice (lender):   {}",
+        alice.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Bob (borrower):   {}",
+        bob.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Bot (liquidator): {}",
+        liquidator.id().to_bech32(network.network_id())
+    );
+
+    let collateral_faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("COL").unwrap()).await?;
+    let debt_faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+
+    for (account_id, faucet_id, amount) in [
+        (alice.id(), debt_faucet.id(), LIQUIDITY),
+        (bob.id(), collateral_faucet.id(), COLLATERAL),
+        (liquidator.id(), debt_faucet.id(), BOT_FUNDS),
+    ] {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet_id, amount)?,
+            account_id,
+            NoteType::Public,
+            client.rng(),
+        )?;
+        client.submit_with_prover(faucet_id, mint_request).await?;
+    }
+    for account_id in [alice.id(), bob.id(), liquidator.id()] {
+        wait_for_notes(&mut client, &network, account_id, 1).await?;
+        consume_all_notes(&mut client, account_id).await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy the price feed and the pool
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Deploying the price feed and the lending pool");
+
+    let price_feed = lending::create_price_feed(&mut client, &keystore, INITIAL_PRICE).await?;
+    let pool_account = lending::create_pool(
+        &mut client,
+        collateral_faucet.id(),
+        debt_faucet.id(),
+        PriceSource::Feed(price_feed.id()),
+        RISK,
+    )
+    .await?;
+    println!(
+        "Price feed: {}",
+        price_feed.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Pool:       {}",
+        pool_account.id().to_bech32(network.network_id())
+    );
+    let pool = Pool::from_account(&pool_account)?;
+
+    // The pool reads the price feed through FPI, so the feed must be on chain first
+    let tx_id = lending::set_price(&mut client, price_feed.id(), INITIAL_PRICE).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Alice supplies liquidity, Bob borrows against his collateral
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Alice supplies {LIQUIDITY} USD, Bob borrows {LOAN} USD");
+
+    let deposit_script = lending::compile_note_script(&client, lending::DEPOSIT_NOTE_PATH)?;
+    let borrow_script = lending::compile_note_script(&client, lending::BORROW_NOTE_PATH)?;
+
+    for (owner, asset) in [
+        (alice.id(), FungibleAsset::new(debt_faucet.id(), LIQUIDITY)?),
+        (
+            bob.id(),
+            FungibleAsset::new(collateral_faucet.id(), COLLATERAL)?,
+        ),
+    ] {
+        let tx_id =
+            lending::deposit(&mut client, owner, &pool, asset, deposit_script.clone()).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+    let tx_id = lending::borrow(&mut client, bob.id(), &pool, LOAN, borrow_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, bob.id(), 1).await?;
+    consume_all_notes(&mut client, bob.id()).await?;
+
+    let record = client.get_account(pool.id).await?.unwrap();
+    let position = Pool::from_account(record.account())?.position(bob.id());
+    println!(
+        "Bob's position: {} COL, {} USD debt, liquidatable below a price of {}",
+        position.collateral,
+        position.debt,
+        position.liquidation_price(RISK).unwrap()
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 4: The bot finds nothing to liquidate
+    // -------------------------------------------------------------------------
+    // A long-running bot would hand itself to `Daemon::run`, which calls `tick` every few
+    // seconds. The tutorial calls `tick` directly to interleave it with the price moves.
+    println!("\n[STEP 4] Running the bot at a price of {INITIAL_PRICE}");
+
+    let liquidate_script = lending::compile_note_script(&client, lending::LIQUIDATE_NOTE_PATH)?;
+    let mut bot = LiquidationBot {
+        client,
+        network_id: network.network_id(),
+        pool_id: pool.id,
+        liquidator: liquidator.id(),
+        script: liquidate_script,
+        liquidations: 0,
+    };
+    bot.tick(None).await?;
+    assert_eq!(bot.liquidations, 0);
+
+    // -------------------------------------------------------------------------
+    // STEP 5: The price crashes and the bot liquidates Bob
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] The price falls to {CRASHED_PRICE}");
+
+    let tx_id = lending::set_price(&mut bot.client, price_feed.id(), CRASHED_PRICE).await?;
+    wait_for_tx(&mut bot.client, tx_id).await?;
+
+    let last_processed = bot.tick(None).await?;
+    assert_eq!(bot.liquidations, 1);
+    wait_for_commit(&mut bot.client).await?;
+
+    // The next iteration collects the collateral sent by the pool
+    wait_for_notes(&mut bot.client, &network, bot.liquidator, 1).await?;
+    bot.tick(Some(last_processed)).await?;
+    wait_for_commit(&mut bot.client).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Checking balances and the pool");
+
+    let record = bot.client.get_account(pool.id).await?.unwrap();
+    let position = Pool::from_account(record.account())?.position(bob.id());
+    let bot_collateral = bot.balance(collateral_faucet.id()).await?;
+    let bot_funds = bot.balance(debt_faucet.id()).await?;
+    let bob_funds = bot
+        .client
+        .get_account(bob.id())
+        .await?
+        .unwrap()
+        .account()
+        .vault()
+        .get_balance(debt_faucet.id())?;
+
+    println!(
+        "Bob's position: {} COL, {} USD debt",
+        position.collateral, position.debt
+    );
+    println!("Bob keeps the loan: {bob_funds} USD");
+    println!("Bot: {bot_collateral} COL, {bot_funds} USD");
+
+    assert_eq!((position.collateral, position.debt), (0, 0));
+    assert_eq!(bob_funds, LOAN);
+    assert_eq!(bot_collateral, COLLATERAL);
+    assert_eq!(bot_funds, BOT_FUNDS - LOAN);
+
+    Ok(())
+}
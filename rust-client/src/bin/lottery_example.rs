@@ -0,0 +1,213 @@
+use rand::rngs::StdRng;
+use std::{sync::Arc, time::Duration};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    lottery::{self, Lottery, Phase, Secret},
+};
+use tokio::time::sleep;
+
+/// Price of a ticket.
+const TICKET_PRICE: u64 = 10;
+/// Blocks during which each phase lasts.
+const PHASE_BLOCKS: u32 = 20;
+
+/// Reads the current state of the lottery `lottery_id`.
+async fn read_lottery(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    lottery_id: AccountId,
+) -> Result<Lottery, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(lottery_id)
+        .await?
+        .ok_or("the lottery is not tracked by the client")?;
+    Lottery::from_account(record.account())
+}
+
+/// Syncs the client until `lottery` reaches `phase`.
+async fn wait_for_phase(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    lottery: &Lottery,
+    phase: Phase,
+) -> Result<(), Box<dyn std::error::Error>> {
+    while lottery.phase_at(client.get_sync_height().await?) != phase {
+        sleep(Duration::from_secs(3)).await;
+        client.sync_state().await?;
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("lottery_example")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the players, fund them and deploy the lottery
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating the players and the lottery");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let carol = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+    println!("Carol: {}", carol.id().to_bech32(network.network_id()));
+
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    for player in [alice.id(), bob.id(), carol.id()] {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet.id(), TICKET_PRICE)?,
+            player,
+            NoteType::Public,
+            client.rng(),
+        )?;
+        let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        wait_for_notes(&mut client, &network, player, 1).await?;
+        let tx_id = consume_all_notes(&mut client, player).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+
+    let sales_end = client.get_sync_height().await?.as_u32() + PHASE_BLOCKS;
+    let reveal_end = sales_end + PHASE_BLOCKS;
+    let account = lottery::create_lottery(
+        &mut client,
+        faucet.id(),
+        TICKET_PRICE,
+        sales_end.into(),
+        reveal_end.into(),
+    )
+    .await?;
+    let state = Lottery::from_account(&account)?;
+    println!(
+        "Lottery {}: tickets until block {sales_end}, reveals until block {reveal_end}",
+        state.id
+    );
+
+    let ticket_script = lottery::compile_note_script(&client, lottery::TICKET_NOTE_PATH)?;
+    let reveal_script = lottery::compile_note_script(&client, lottery::REVEAL_NOTE_PATH)?;
+    let draw_script = lottery::compile_note_script(&client, lottery::DRAW_NOTE_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Every player buys a ticket
+    // -------------------------------------------------------------------------
+    // Each ticket carries the hash of a secret only its player knows. The secrets decide the
+    // winner, so nobody can tell who wins while tickets are sold.
+    println!("\n[STEP 2] Alice, Bob and Carol buy a ticket for {TICKET_PRICE} tokens");
+
+    let mut players = Vec::new();
+    for player in [alice.id(), bob.id(), carol.id()] {
+        let secret = Secret::new(client.rng());
+        let tx_id =
+            lottery::buy_ticket(&mut client, player, &state, &secret, ticket_script.clone())
+                .await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        println!("{player} committed to {}", secret.commitment().to_hex());
+        players.push((player, secret));
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Every player reveals its secret
+    // -------------------------------------------------------------------------
+    // The lottery only accepts a secret hashing to the commitment of its player, so nobody can
+    // pick a secret after seeing the others.
+    println!("\n[STEP 3] Waiting for block {sales_end} to reveal the secrets");
+    wait_for_phase(&mut client, &state, Phase::Reveal).await?;
+
+    let (carol_id, _) = players[2];
+    let other_secret = Secret::new(client.rng());
+    let Err(err) = lottery::reveal(
+        &mut client,
+        carol_id,
+        &state,
+        &other_secret,
+        reveal_script.clone(),
+    )
+    .await
+    else {
+        return Err("the lottery accepted a secret not matching its commitment".into());
+    };
+    println!("Carol cannot swap in another secret: {err}");
+
+    for (player, secret) in &players {
+        let tx_id =
+            lottery::reveal(&mut client, *player, &state, secret, reveal_script.clone()).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        println!("{player} revealed its secret");
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Draw the winner
+    // -------------------------------------------------------------------------
+    // The seed is final once the reveal phase is over, so anybody can compute the winner and
+    // request the draw: here Alice does.
+    println!("\n[STEP 4] Waiting for block {reveal_end} to draw the winner");
+    wait_for_phase(&mut client, &state, Phase::Draw).await?;
+
+    let state = read_lottery(&client, state.id).await?;
+    let winner = state.winner().ok_or("no player revealed its secret")?;
+    println!("Seed {}, the winner is {winner}", state.seed.to_hex());
+
+    let tx_id = lottery::draw(&mut client, alice.id(), &state, draw_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, winner, 1).await?;
+    let tx_id = consume_all_notes(&mut client, winner).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking the jackpot was paid out");
+
+    let state = read_lottery(&client, state.id).await?;
+    assert!(state.drawn);
+    assert_eq!(state.num_tickets, 3);
+    assert_eq!(state.revealed.len(), 3);
+
+    let record = client
+        .get_account(winner)
+        .await?
+        .ok_or("the winner is not tracked by the client")?;
+    let prize = record.account().vault().get_balance(faucet.id())?;
+    assert_eq!(prize, state.jackpot());
+    println!("{winner} won {prize} tokens");
+
+    Ok(())
+}
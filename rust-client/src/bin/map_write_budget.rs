@@ -0,0 +1,144 @@
+use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel};
+use rand::RngCore;
+use std::{fs, sync::Arc};
+
+use miden_client::{builder::ClientBuilder, rpc::GrpcClient};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    Felt, Word, MAX_TX_EXECUTION_CYCLES, ZERO,
+};
+use rust_client::{
+    context::TutorialContext,
+    helpers::{create_library, wait_for_tx},
+    map_writer::{self, ChunkedWriter},
+};
+
+/// Cycle budget the transactions of this tutorial are kept within. The kernel accepts up to
+/// `MAX_TX_EXECUTION_CYCLES`, but proving a transaction that long takes far more time and memory
+/// than a client can usually afford.
+const CYCLE_BUDGET: usize = 1 << 16;
+/// Number of entries written to the map, more than fit in one transaction of the budget.
+const NUM_ENTRIES: u64 = 200;
+
+/// Returns the i-th entry written to the map.
+fn entry(i: u64) -> (Word, Word) {
+    let key: Word = [ZERO, ZERO, Felt::new(i), Felt::new(7)].into();
+    let value: Word = [Felt::new(i), Felt::new(i * i), ZERO, ZERO].into();
+    (key, value)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("map_write_budget")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Deploy the map contract
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Deploying the map contract");
+
+    let contract_code = fs::read_to_string(map_writer::CONTRACT_PATH)?;
+    let component = AccountComponent::compile(
+        &contract_code,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Map(StorageMap::new())],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let map_contract = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+    client.add_account(&map_contract, false).await?;
+    println!(
+        "Map contract: {}",
+        map_contract.id().to_bech32(network.network_id())
+    );
+
+    let library = create_library(&contract_code, map_writer::LIBRARY_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Probe how many insertions fit in one transaction
+    // -------------------------------------------------------------------------
+    // Batches are executed locally, without being proven or submitted: execution alone tells
+    // how many cycles they take.
+    println!("\n[STEP 2] Probing the number of insertions within {CYCLE_BUDGET} cycles");
+
+    let (capacity, cost) =
+        map_writer::probe_capacity(&mut client, map_contract.id(), &library, CYCLE_BUDGET).await?;
+    println!(
+        "A batch costs {} cycles plus {} per entry",
+        cost.base, cost.per_entry
+    );
+    println!("At most {capacity} insertions fit in {CYCLE_BUDGET} cycles");
+    println!(
+        "The kernel limit of {MAX_TX_EXECUTION_CYCLES} cycles would allow about {}",
+        cost.capacity(MAX_TX_EXECUTION_CYCLES as usize)
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Write more entries than fit in one transaction
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Writing {NUM_ENTRIES} entries in chunks of {capacity}");
+
+    let entries: Vec<(Word, Word)> = (1..=NUM_ENTRIES).map(entry).collect();
+    let writer = ChunkedWriter::new(map_contract.id(), library, capacity);
+    let tx_ids = writer.write(&mut client, &entries).await?;
+    for tx_id in &tx_ids {
+        println!("Submitted {tx_id:?}");
+    }
+    if let Some(tx_id) = tx_ids.last() {
+        wait_for_tx(&mut client, *tx_id).await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Checking the map");
+
+    assert_eq!(
+        tx_ids.len(),
+        map_writer::chunks(entries.len(), capacity).len()
+    );
+    let record = client
+        .get_account(map_contract.id())
+        .await?
+        .ok_or("the map contract is not tracked by the client")?;
+    let storage = record.account().storage();
+    for (key, value) in &entries {
+        assert_eq!(storage.get_map_item(0, *key)?, *value);
+    }
+    println!(
+        "All {NUM_ENTRIES} entries were written in {} transactions",
+        tx_ids.len()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,471 @@
+use rand::rngs::StdRng;
+use std::{fmt, sync::Arc};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{NoteScript, NoteType},
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::block::BlockNumber;
+use rust_client::{
+    amm::{self, Pool, SwapOffer},
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    daemon::Service,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_commit,
+        wait_for_notes, wait_for_tx,
+    },
+};
+
+/// Distance of each quote from the pool price, in basis points.
+const HALF_SPREAD_BPS: u64 = 100;
+/// Move of the pool price, in basis points, after which the bot replaces its quotes.
+const REQUOTE_BPS: u64 = 200;
+/// Share of the value of its inventory the bot aims to hold in ETH, in percent.
+const TARGET_BASE_SHARE: u64 = 50;
+/// ETH quoted on each side while the inventory is on target.
+const QUOTE_SIZE: u64 = 10;
+
+/// Liquidity Alice adds to the pool, a price of 100 USD per ETH.
+const POOL_ETH: u64 = 1_000;
+const POOL_USD: u64 = 100_000;
+/// Inventory of the bot.
+const BOT_ETH: u64 = 100;
+const BOT_USD: u64 = 10_000;
+/// USD Bob holds to fill an offer and to buy ETH from the pool.
+const BOB_USD: u64 = 20_000;
+/// USD Bob spends on ETH in the pool, pushing its price up by about 20%.
+const BOB_SWAP_USD: u64 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    /// Buys ETH with USD.
+    Bid,
+    /// Sells ETH for USD.
+    Ask,
+}
+
+/// An offer posted by the bot.
+struct Quote {
+    side: Side,
+    /// Price in USD per ETH the offer was quoted at.
+    price: f64,
+    offer: SwapOffer,
+    /// Whether the bot still considers the offer part of its quotes. An offer the bot failed
+    /// to cancel is no longer quoted, but is kept until its payment arrives.
+    live: bool,
+}
+
+impl fmt::Display for Quote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (side, eth) = match self.side {
+            Side::Bid => ("bid", self.offer.requested.amount()),
+            Side::Ask => ("ask", self.offer.offered.amount()),
+        };
+        write!(f, "{side} {eth} ETH at {:.2} USD", self.price)
+    }
+}
+
+/// Returns how much ETH to bid for and to ask for at `price`, given the inventory of the bot.
+///
+/// Each side quotes [`QUOTE_SIZE`] while the value of the inventory is split according to
+/// [`TARGET_BASE_SHARE`]. Holding more ETH than the target grows the ask and shrinks the bid,
+/// so that fills bring the inventory back to its target, and conversely. Neither side offers
+/// more than the bot holds.
+fn quote_sizes(price: f64, eth: u64, usd: u64) -> (u64, u64) {
+    let eth_value = eth as f64 * price;
+    let total = eth_value + usd as f64;
+    if total == 0.0 {
+        return (0, 0);
+    }
+
+    let share = eth_value / total;
+    let target = TARGET_BASE_SHARE as f64 / 100.0;
+    let ask = (QUOTE_SIZE as f64 * share / target).round() as u64;
+    let bid = (QUOTE_SIZE as f64 * (1.0 - share) / (1.0 - target)).round() as u64;
+
+    let bid_price = price * (1.0 - HALF_SPREAD_BPS as f64 / 10_000.0);
+    let affordable = (usd as f64 / bid_price).floor() as u64;
+    (bid.min(affordable), ask.min(eth))
+}
+
+/// Quotes both sides of the ETH/USD pair around the price of an AMM pool with swap offers.
+///
+/// Every iteration records the offers filled since the previous one, collects their payments
+/// and reads the pool price as of the latest block. When the price moved by [`REQUOTE_BPS`]
+/// or more, or a side was filled, the bot cancels its remaining offers and posts replacements
+/// around the new price.
+struct MarketMaker {
+    client: Client<FilesystemKeyStore<StdRng>>,
+    maker: AccountId,
+    pool_id: AccountId,
+    eth: AccountId,
+    usd: AccountId,
+    script: NoteScript,
+    quotes: Vec<Quote>,
+    quoted_price: Option<f64>,
+    fills: usize,
+    cancellations: usize,
+}
+
+impl MarketMaker {
+    /// Returns the balance of the bot in tokens of `faucet_id`.
+    async fn balance(&self, faucet_id: AccountId) -> Result<u64, Box<dyn std::error::Error>> {
+        let record = self
+            .client
+            .get_account(self.maker)
+            .await?
+            .ok_or("the maker is not tracked by the client")?;
+        Ok(record.account().vault().get_balance(faucet_id)?)
+    }
+
+    /// Returns the tokens of `faucet_id` held by the bot, in its vault or in open offers.
+    async fn inventory(&self, faucet_id: AccountId) -> Result<u64, Box<dyn std::error::Error>> {
+        let offered: u64 = self
+            .quotes
+            .iter()
+            .map(|quote| quote.offer.offered)
+            .filter(|asset| asset.faucet_id() == faucet_id)
+            .map(|asset| asset.amount())
+            .sum();
+        Ok(self.balance(faucet_id).await? + offered)
+    }
+
+    /// Builds the offer for `side` of `eth` ETH at `price`, rounding the USD leg in favour of
+    /// the bot.
+    fn quote(
+        &mut self,
+        side: Side,
+        price: f64,
+        eth: u64,
+    ) -> Result<Quote, Box<dyn std::error::Error>> {
+        let eth_asset = FungibleAsset::new(self.eth, eth)?;
+        let (offered, requested) = match side {
+            Side::Bid => {
+                let usd = (eth as f64 * price).floor() as u64;
+                (FungibleAsset::new(self.usd, usd)?, eth_asset)
+            }
+            Side::Ask => {
+                let usd = (eth as f64 * price).ceil() as u64;
+                (eth_asset, FungibleAsset::new(self.usd, usd)?)
+            }
+        };
+        let serial_num = self.client.rng().draw_word();
+        let offer = SwapOffer::new(
+            self.maker,
+            offered,
+            requested,
+            self.script.clone(),
+            serial_num,
+        )?;
+
+        Ok(Quote {
+            side,
+            price,
+            offer,
+            live: true,
+        })
+    }
+}
+
+impl Service for MarketMaker {
+    async fn tick(
+        &mut self,
+        _last_processed: Option<BlockNumber>,
+    ) -> Result<BlockNumber, Box<dyn std::error::Error>> {
+        let sync_summary = self.client.sync_state().await?;
+
+        // A taker fills an offer by paying the maker, so an offer whose payment arrived is gone
+        let mut filled_side = false;
+        let mut open = Vec::new();
+        for quote in std::mem::take(&mut self.quotes) {
+            if self
+                .client
+                .get_input_note(quote.offer.payment_id()?)
+                .await?
+                .is_some()
+            {
+                println!("Filled: {quote}");
+                self.fills += 1;
+                filled_side |= quote.live;
+            } else {
+                open.push(quote);
+            }
+        }
+        self.quotes = open;
+
+        if !self
+            .client
+            .get_consumable_notes(Some(self.maker))
+            .await?
+            .is_empty()
+        {
+            println!("Collecting payments");
+            consume_all_notes(&mut self.client, self.maker).await?;
+        }
+
+        let pool_record = self
+            .client
+            .get_account(self.pool_id)
+            .await?
+            .ok_or("the pool is not tracked by the client")?;
+        let pool = Pool::from_account(pool_record.account())?;
+        let price = pool.price(self.eth).ok_or("the pool holds no ETH")?;
+
+        let moved = match self.quoted_price {
+            Some(quoted) => (price - quoted).abs() / quoted * 10_000.0 >= REQUOTE_BPS as f64,
+            None => true,
+        };
+        println!(
+            "Block {}: pool price {price:.2} USD, {} open offer(s)",
+            sync_summary.block_num,
+            self.quotes.len()
+        );
+        if !moved && !filled_side {
+            return Ok(sync_summary.block_num);
+        }
+
+        // Pull the offers left at the old price. Cancelling fails if a taker was faster, in
+        // which case the offer is kept until its payment shows up on a later iteration.
+        let mut unconfirmed = Vec::new();
+        for mut quote in std::mem::take(&mut self.quotes) {
+            if !quote.live {
+                unconfirmed.push(quote);
+                continue;
+            }
+            match amm::cancel_offer(&mut self.client, &quote.offer).await {
+                Ok(tx_id) => {
+                    self.cancellations += 1;
+                    println!("Cancelled: {quote}. TX: {tx_id:?}");
+                }
+                Err(err) => {
+                    println!("Could not cancel {quote}: {err}");
+                    quote.live = false;
+                    unconfirmed.push(quote);
+                }
+            }
+        }
+        self.quotes = unconfirmed;
+
+        // Quote both sides around the new price, sized to steer the inventory to its target
+        let (bid_size, ask_size) = quote_sizes(
+            price,
+            self.balance(self.eth).await?,
+            self.balance(self.usd).await?,
+        );
+        let spread = HALF_SPREAD_BPS as f64 / 10_000.0;
+        let mut quotes = Vec::new();
+        if bid_size > 0 {
+            quotes.push(self.quote(Side::Bid, price * (1.0 - spread), bid_size)?);
+        }
+        if ask_size > 0 {
+            quotes.push(self.quote(Side::Ask, price * (1.0 + spread), ask_size)?);
+        }
+
+        if !quotes.is_empty() {
+            let offers: Vec<_> = quotes.iter().map(|quote| quote.offer.clone()).collect();
+            let tx_id = amm::post_offers(&mut self.client, self.maker, &offers).await?;
+            for quote in &quotes {
+                println!("Posted: {quote}");
+            }
+            println!("TX: {tx_id:?}");
+        }
+        self.quotes.extend(quotes);
+        self.quoted_price = Some(price);
+
+        Ok(sync_summary.block_num)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("market_maker")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the liquidity provider, the trader, the bot and both assets
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and funding them");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let maker = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice (liquidity provider): {}",
+        alice.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Bob (trader):               {}",
+        bob.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Bot (market maker):         {}",
+        maker.id().to_bech32(network.network_id())
+    );
+
+    let eth_faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("ETH").unwrap()).await?;
+    let usd_faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+
+    for (account_id, faucet_id, amount) in [
+        (alice.id(), eth_faucet.id(), POOL_ETH),
+        (alice.id(), usd_faucet.id(), POOL_USD),
+        (maker.id(), eth_faucet.id(), BOT_ETH),
+        (maker.id(), usd_faucet.id(), BOT_USD),
+        (bob.id(), usd_faucet.id(), BOB_USD),
+    ] {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet_id, amount)?,
+            account_id,
+            NoteType::Public,
+            client.rng(),
+        )?;
+        client.submit_with_prover(faucet_id, mint_request).await?;
+    }
+    for (account_id, notes) in [(alice.id(), 2), (maker.id(), 2), (bob.id(), 1)] {
+        wait_for_notes(&mut client, &network, account_id, notes).await?;
+        consume_all_notes(&mut client, account_id).await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy the pool and have Alice add liquidity
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Deploying the pool, Alice adds {POOL_ETH} ETH and {POOL_USD} USD");
+
+    let pool_account = amm::create_pool(&mut client, eth_faucet.id(), usd_faucet.id()).await?;
+    println!(
+        "Pool: {}",
+        pool_account.id().to_bech32(network.network_id())
+    );
+    let pool = Pool::from_account(&pool_account)?;
+
+    let deposit_script = amm::compile_note_script(&client, amm::DEPOSIT_NOTE_PATH)?;
+    let tx_id = amm::add_liquidity(
+        &mut client,
+        alice.id(),
+        &pool,
+        FungibleAsset::new(eth_faucet.id(), POOL_ETH)?,
+        FungibleAsset::new(usd_faucet.id(), POOL_USD)?,
+        deposit_script,
+    )
+    .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: The bot quotes around the pool price
+    // -------------------------------------------------------------------------
+    // A long-running bot would hand itself to `Daemon::run`, which calls `tick` every few
+    // seconds. The tutorial calls `tick` directly to interleave it with Bob's trades.
+    println!("\n[STEP 3] Running the bot");
+
+    let offer_script = amm::compile_note_script(&client, amm::OFFER_NOTE_PATH)?;
+    let mut bot = MarketMaker {
+        client,
+        maker: maker.id(),
+        pool_id: pool.id,
+        eth: eth_faucet.id(),
+        usd: usd_faucet.id(),
+        script: offer_script,
+        quotes: Vec::new(),
+        quoted_price: None,
+        fills: 0,
+        cancellations: 0,
+    };
+    let last_processed = bot.tick(None).await?;
+    wait_for_commit(&mut bot.client).await?;
+    assert_eq!(bot.quotes.len(), 2);
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Bob takes the ask, then buys ETH from the pool
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Bob takes the ask and buys ETH for {BOB_SWAP_USD} USD in the pool");
+
+    // Takers discover offers by syncing the public offer tag. The tutorial shares one client
+    // between all accounts, so Bob reads the offer straight from the bot.
+    let ask = bot
+        .quotes
+        .iter()
+        .find(|quote| quote.side == Side::Ask)
+        .map(|quote| quote.offer.clone())
+        .ok_or("the bot posted no ask")?;
+    let tx_id = amm::fill_offer(&mut bot.client, bob.id(), &ask).await?;
+    wait_for_tx(&mut bot.client, tx_id).await?;
+    println!(
+        "Bob paid {} USD for {} ETH",
+        ask.requested.amount(),
+        ask.offered.amount()
+    );
+
+    let swap_script = amm::compile_note_script(&bot.client, amm::SWAP_NOTE_PATH)?;
+    let record = bot.client.get_account(pool.id).await?.unwrap();
+    let pool = Pool::from_account(record.account())?;
+    let asset_in = FungibleAsset::new(usd_faucet.id(), BOB_SWAP_USD)?;
+    let asset_out = pool.quote(asset_in).ok_or("the pool pays nothing")?;
+    let tx_id = amm::swap(&mut bot.client, bob.id(), &pool, asset_in, swap_script).await?;
+    wait_for_tx(&mut bot.client, tx_id).await?;
+    println!(
+        "Bob swapped {BOB_SWAP_USD} USD for {} ETH",
+        asset_out.amount()
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 5: The bot collects the payment and replaces its stale bid
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Running the bot after the price moved");
+
+    wait_for_notes(&mut bot.client, &network, bot.maker, 1).await?;
+    bot.tick(Some(last_processed)).await?;
+    wait_for_commit(&mut bot.client).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Checking the bot's inventory");
+
+    let eth = bot.inventory(eth_faucet.id()).await?;
+    let usd = bot.inventory(usd_faucet.id()).await?;
+    println!(
+        "Bot: {} fill(s), {} cancellation(s), {} open offer(s)",
+        bot.fills,
+        bot.cancellations,
+        bot.quotes.len()
+    );
+    println!("Bot inventory, vault and open offers: {eth} ETH, {usd} USD");
+
+    assert_eq!(bot.fills, 1);
+    assert_eq!(bot.cancellations, 1);
+    assert_eq!(bot.quotes.len(), 2);
+    assert_eq!(eth, BOT_ETH - ask.offered.amount());
+    assert_eq!(usd, BOT_USD + ask.requested.amount());
+
+    Ok(())
+}
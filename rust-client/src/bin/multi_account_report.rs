@@ -0,0 +1,201 @@
+use miden_lib::account::auth::AuthRpoFalcon512;
+use rand::RngCore;
+use std::{collections::BTreeMap, sync::Arc};
+
+use miden_client::{
+    account::{component::BasicFungibleFaucet, AccountId},
+    asset::{Asset, FungibleAsset},
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    note::NoteType,
+    rpc::GrpcClient,
+    store::TransactionFilter,
+    transaction::TransactionRequestBuilder,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{AccountBuilder, AccountStorageMode, AccountType},
+    asset::TokenSymbol,
+    Felt,
+};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    format::AssetFormatter,
+    helpers::{create_basic_account_with_storage_mode, wait_for_notes},
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("multi_account_report")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client.clone())
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create a private and a public wallet
+    // -------------------------------------------------------------------------
+    // The node only stores a commitment to the private wallet's state, so its balance can only
+    // be read from the local store. The report below therefore reads both wallets from the
+    // store rather than from the node.
+    println!("\n[STEP 1] Creating a private and a public wallet");
+
+    let private_wallet =
+        create_basic_account_with_storage_mode(&mut client, &keystore, AccountStorageMode::Private)
+            .await?;
+    let public_wallet =
+        create_basic_account_with_storage_mode(&mut client, &keystore, AccountStorageMode::Public)
+            .await?;
+    let wallets = [
+        ("private", private_wallet.id()),
+        ("public", public_wallet.id()),
+    ];
+    for (kind, account_id) in wallets {
+        println!(
+            "{kind:>8} wallet: {}",
+            account_id.to_bech32(network.network_id())
+        );
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy a faucet and fund both wallets
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Funding both wallets");
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let faucet = AccountBuilder::new(init_seed)
+        .account_type(AccountType::FungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+        .with_component(
+            BasicFungibleFaucet::new(TokenSymbol::new("MID").unwrap(), 8, Felt::new(1_000_000))
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    client.add_account(&faucet, false).await?;
+    keystore.add_key(&key_pair).unwrap();
+
+    // The private wallet receives a private note, the public wallet a public one
+    let deposits = [
+        (private_wallet.id(), 300, NoteType::Private),
+        (public_wallet.id(), 200, NoteType::Public),
+    ];
+    for (account_id, amount, note_type) in deposits {
+        let asset = FungibleAsset::new(faucet.id(), amount).unwrap();
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            asset,
+            account_id,
+            note_type,
+            client.rng(),
+        )?;
+        client.submit_with_prover(faucet.id(), mint_request).await?;
+    }
+
+    for (_, account_id) in wallets {
+        wait_for_notes(&mut client, &network, account_id, 1).await?;
+        let note_ids = client
+            .get_consumable_notes(Some(account_id))
+            .await?
+            .iter()
+            .map(|(note, _)| note.id())
+            .collect();
+        let consume_request = TransactionRequestBuilder::new().build_consume_notes(note_ids)?;
+        client
+            .submit_with_prover(account_id, consume_request)
+            .await?;
+    }
+    client.sync_state().await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Unified balance report
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Balances");
+
+    let mut formatter = AssetFormatter::new(network.network_id());
+    let mut totals: BTreeMap<AccountId, u64> = BTreeMap::new();
+
+    println!("{:<8} {:<44} {:>20}", "KIND", "ACCOUNT", "BALANCE");
+    for (kind, account_id) in wallets {
+        let record = client
+            .get_account(account_id)
+            .await?
+            .expect("wallets were added to the store");
+        for asset in record.account().vault().assets() {
+            if let Asset::Fungible(fungible) = asset {
+                formatter
+                    .load_token(rpc_client.as_ref(), fungible.faucet_id())
+                    .await?;
+                *totals.entry(fungible.faucet_id()).or_default() += fungible.amount();
+            }
+            println!(
+                "{:<8} {:<44} {:>20}",
+                kind,
+                account_id.to_bech32(network.network_id()),
+                formatter.format(&asset)
+            );
+        }
+    }
+    for (faucet_id, amount) in &totals {
+        println!(
+            "{:<8} {:<44} {:>20}",
+            "total",
+            faucet_id.to_bech32(network.network_id()),
+            formatter.format_amount(*faucet_id, *amount)
+        );
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Merged transaction history
+    // -------------------------------------------------------------------------
+    // The store keeps the transactions of all tracked accounts, private ones included: filter
+    // them to the two wallets and order them by block.
+    println!("\n[STEP 4] Transaction history");
+
+    let kind_of: BTreeMap<AccountId, &str> = wallets
+        .iter()
+        .map(|(kind, account_id)| (*account_id, *kind))
+        .collect();
+    let mut history: Vec<_> = client
+        .get_transactions(TransactionFilter::All)
+        .await?
+        .into_iter()
+        .filter(|tx| kind_of.contains_key(&tx.details.account_id))
+        .collect();
+    history.sort_by_key(|tx| tx.details.block_num);
+
+    for tx in &history {
+        println!(
+            "block {:>8}  {:<8} in: {:>2} out: {:>2}  {}  {}",
+            tx.details.block_num,
+            kind_of[&tx.details.account_id],
+            tx.details.input_note_nullifiers.len(),
+            tx.details.output_notes.num_notes(),
+            tx.status,
+            tx.id.to_hex()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,263 @@
+use rand::rngs::StdRng;
+use std::{fs, sync::Arc};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{create_p2id_note, Note, NoteType},
+    rpc::GrpcClient,
+    transaction::{TransactionProver, TransactionRequestBuilder},
+    utils::{Deserializable, Serializable},
+    Client, Felt, RemoteTransactionProver, Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::{ProverOption, SubmitWithProver},
+    context::TutorialContext,
+    ephemeral::EphemeralClient,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_commit,
+        wait_for_notes, wait_for_tx,
+    },
+    multisig::{self, Approval, Proposal},
+};
+
+/// Remote prover used by the coordinator unless `--prover <url>` names another one.
+const DEFAULT_PROVER_URL: &str = "https://tx-prover.testnet.miden.io";
+/// Tokens held by the multisig.
+const FUNDS: u64 = 100;
+/// Tokens the signers agree to pay Bob.
+const PAYOUT: u64 = 40;
+/// Approvals needed for a payout.
+const THRESHOLD: u64 = 2;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+/// One party of the multisig, with a client, keystore and account of its own.
+struct Signer {
+    client: EphemeralClient,
+    key: AuthSecretKey,
+    account_id: AccountId,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize the coordinator client, which deploys the multisig and executes payouts
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("multisig_delegated_proving")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Set up the signers on their own clients
+    // -------------------------------------------------------------------------
+    // Each signer keeps its key in its own keystore and never shares it with the coordinator,
+    // which only learns the public keys.
+    println!("\n[STEP 1] Setting up two signers, each on a client of its own");
+
+    let mut signers = Vec::new();
+    for n in 1..=THRESHOLD {
+        let mut signer_client = EphemeralClient::new(endpoint.clone()).await?;
+        signer_client.sync_state().await?;
+        let signer_keystore = signer_client.keystore().clone();
+
+        let key = AuthSecretKey::new_rpo_falcon512();
+        signer_keystore.add_key(&key)?;
+        let account = create_basic_account(&mut signer_client, &signer_keystore).await?;
+        println!(
+            "Signer {n}: {} (state in {})",
+            account.id().to_bech32(network.network_id()),
+            signer_client.dir().display()
+        );
+
+        signers.push(Signer {
+            client: signer_client,
+            key,
+            account_id: account.id(),
+        });
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy and fund the multisig
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Deploying a {THRESHOLD}-of-{THRESHOLD} multisig with {FUNDS} tokens");
+
+    let public_keys: Vec<Word> = signers
+        .iter()
+        .map(|signer| signer.key.public_key().to_commitment().into())
+        .collect();
+    // The recovery key stays offline: this flow never spends through the basic wallet
+    let recovery_key = AuthSecretKey::new_rpo_falcon512();
+    let multisig = multisig::create_multisig(
+        &mut client,
+        &public_keys,
+        THRESHOLD,
+        recovery_key.public_key().to_commitment().into(),
+    )
+    .await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Multisig: {}",
+        multisig.id().to_bech32(network.network_id())
+    );
+    println!("Bob:      {}", bob.id().to_bech32(network.network_id()));
+
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), FUNDS)?,
+        multisig.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, multisig.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, multisig.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Propose a payout
+    // -------------------------------------------------------------------------
+    // The coordinator shares the payout note with the signers as bytes. Any channel works:
+    // here it is a file in the state directory of the coordinator.
+    println!("\n[STEP 3] Proposing to pay Bob {PAYOUT} tokens");
+
+    let payout = create_p2id_note(
+        multisig.id(),
+        bob.id(),
+        vec![FungibleAsset::new(faucet.id(), PAYOUT)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let proposal = Proposal::new(payout)?;
+    let proposal_path = context.dir().join("proposal.bin");
+    fs::write(&proposal_path, proposal.payout.to_bytes())?;
+    println!(
+        "Proposal {} written to {}",
+        proposal.commitment(),
+        proposal_path.display()
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Each signer approves on its own client
+    // -------------------------------------------------------------------------
+    // A signer rebuilds the proposal from the bytes it received, so it signs exactly the
+    // payout it was shown. It then sends the approval note from its own account and hands the
+    // serialized approval, signature included, back to the coordinator.
+    println!("\n[STEP 4] Collecting approvals");
+
+    let mut approval_paths = Vec::new();
+    for (n, signer) in signers.iter_mut().enumerate() {
+        let payout = Note::read_from_bytes(&fs::read(&proposal_path)?)?;
+        let proposal = Proposal::new(payout)?;
+
+        let script = multisig::compile_note_script(&signer.client)?;
+        let serial_num = signer.client.rng().draw_word();
+        let approval = Approval::new(
+            signer.account_id,
+            multisig.id(),
+            &proposal,
+            &signer.key,
+            script,
+            serial_num,
+        )?;
+        let tx_id = multisig::send_approval(&mut signer.client, &approval).await?;
+        wait_for_tx(&mut signer.client, tx_id).await?;
+
+        let approval_path = context.dir().join(format!("approval_{}.bin", n + 1));
+        fs::write(&approval_path, approval.to_bytes())?;
+        println!(
+            "Signer {} approved, {} bytes handed over",
+            n + 1,
+            approval.to_bytes().len()
+        );
+        approval_paths.push(approval_path);
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 5: The coordinator executes the payout
+    // -------------------------------------------------------------------------
+    // Executing runs on the coordinator, which holds the approvals but none of the signing
+    // keys. Only proving is delegated to the remote prover.
+    println!("\n[STEP 5] Executing the payout with {THRESHOLD} approvals");
+
+    let approvals = approval_paths
+        .iter()
+        .map(|path| Ok(Approval::read_from_bytes(&fs::read(path)?)?))
+        .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+
+    client.sync_state().await?;
+    let request = multisig::execute_request(&client, &proposal, &approvals)?;
+    println!("Executing transaction...");
+    let tx_result = client.execute_transaction(multisig.id(), request).await?;
+
+    let prover_url = match ProverOption::global() {
+        ProverOption::Remote(url) => url.clone(),
+        _ => DEFAULT_PROVER_URL.to_string(),
+    };
+    println!("Proving transaction with remote prover at {prover_url}...");
+    let tx_prover: Arc<dyn TransactionProver> = Arc::new(RemoteTransactionProver::new(prover_url));
+    let proven_transaction = client.prove_transaction_with(&tx_result, tx_prover).await?;
+
+    println!("Submitting proven transaction...");
+    let submission_height = client
+        .submit_proven_transaction(proven_transaction, &tx_result)
+        .await?;
+    client
+        .apply_transaction(&tx_result, submission_height)
+        .await?;
+    wait_for_tx(&mut client, tx_result.executed_transaction().id()).await?;
+    println!("Payout note: {}", proposal.payout.id());
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Checking balances");
+
+    wait_for_notes(&mut client, &network, bob.id(), 1).await?;
+    consume_all_notes(&mut client, bob.id()).await?;
+    wait_for_commit(&mut client).await?;
+
+    let multisig_funds = balance(&client, multisig.id(), faucet.id()).await?;
+    let bob_funds = balance(&client, bob.id(), faucet.id()).await?;
+    println!("Multisig: {multisig_funds}, Bob: {bob_funds}");
+
+    assert_eq!(multisig_funds, FUNDS - PAYOUT);
+    assert_eq!(bob_funds, PAYOUT);
+
+    Ok(())
+}
@@ -0,0 +1,220 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{create_p2id_note, NoteType},
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_commit,
+        wait_for_notes, wait_for_tx,
+    },
+    multisig::{self, Approval, Proposal},
+};
+
+/// Tokens held by the multisig.
+const FUNDS: u64 = 100;
+/// Tokens the signers agree to pay Bob.
+const PAYOUT: u64 = 40;
+/// Approvals needed for a payout.
+const THRESHOLD: u64 = 2;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("multisig_example")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the signers and the multisig
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating a {THRESHOLD}-of-3 multisig");
+
+    // None of these keys go into the keystore: the signers keep their own, and the recovery
+    // key stays offline. Each signer sends its approvals from an account of its own.
+    let signer_keys = [
+        AuthSecretKey::new_rpo_falcon512(),
+        AuthSecretKey::new_rpo_falcon512(),
+        AuthSecretKey::new_rpo_falcon512(),
+    ];
+    let recovery_key = AuthSecretKey::new_rpo_falcon512();
+    let mut signers = Vec::new();
+    for _ in &signer_keys {
+        signers.push(create_basic_account(&mut client, &keystore).await?);
+    }
+    let bob = create_basic_account(&mut client, &keystore).await?;
+
+    let public_keys: Vec<Word> = signer_keys
+        .iter()
+        .map(|key| key.public_key().to_commitment().into())
+        .collect();
+    let multisig = multisig::create_multisig(
+        &mut client,
+        &public_keys,
+        THRESHOLD,
+        recovery_key.public_key().to_commitment().into(),
+    )
+    .await?;
+    println!(
+        "Multisig: {}",
+        multisig.id().to_bech32(network.network_id())
+    );
+    println!("Bob:      {}", bob.id().to_bech32(network.network_id()));
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Fund the multisig
+    // -------------------------------------------------------------------------
+    // Consuming notes needs no signature, so anybody tracking the multisig can do it
+    println!("\n[STEP 2] Funding the multisig with {FUNDS} tokens");
+
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), FUNDS)?,
+        multisig.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, multisig.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, multisig.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Spending without approvals fails
+    // -------------------------------------------------------------------------
+    // A plain payment goes through the basic wallet, which the ACL of the multisig reserves
+    // to the recovery key.
+    println!("\n[STEP 3] Paying Bob directly from the vault");
+
+    let payment = create_p2id_note(
+        multisig.id(),
+        bob.id(),
+        vec![FungibleAsset::new(faucet.id(), PAYOUT)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(payment)])
+        .build()?;
+    let Err(err) = client.execute_transaction(multisig.id(), request).await else {
+        return Err("the multisig paid out without approvals".into());
+    };
+    println!("Rejected: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Propose a payout and collect approvals
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Proposing to pay Bob {PAYOUT} tokens");
+
+    let payout = create_p2id_note(
+        multisig.id(),
+        bob.id(),
+        vec![FungibleAsset::new(faucet.id(), PAYOUT)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let proposal = Proposal::new(payout)?;
+    println!("Proposal: {}", proposal.commitment());
+
+    let script = multisig::compile_note_script(&client)?;
+    let mut approvals = Vec::new();
+    for signer in [0, 2] {
+        let serial_num = client.rng().draw_word();
+        let approval = Approval::new(
+            signers[signer].id(),
+            multisig.id(),
+            &proposal,
+            &signer_keys[signer],
+            script.clone(),
+            serial_num,
+        )?;
+        let tx_id = multisig::send_approval(&mut client, &approval).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        println!("Signer {} approved", signer + 1);
+        approvals.push(approval);
+    }
+
+    // One approval is not enough
+    let request = multisig::execute_request(&client, &proposal, &approvals[..1])?;
+    let Err(err) = client.execute_transaction(multisig.id(), request).await else {
+        return Err("the multisig executed a payout with a single approval".into());
+    };
+    println!("With one approval: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Execute the payout with two approvals
+    // -------------------------------------------------------------------------
+    // A single transaction consumes both approval notes, verifying each signature, and emits
+    // the payout once the threshold is reached.
+    println!("\n[STEP 5] Executing the payout with {THRESHOLD} approvals");
+
+    let tx_id = multisig::execute(&mut client, multisig.id(), &proposal, &approvals).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Payout note: {}", proposal.payout.id());
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Checking balances");
+
+    wait_for_notes(&mut client, &network, bob.id(), 1).await?;
+    consume_all_notes(&mut client, bob.id()).await?;
+    wait_for_commit(&mut client).await?;
+
+    let multisig_funds = balance(&client, multisig.id(), faucet.id()).await?;
+    let bob_funds = balance(&client, bob.id(), faucet.id()).await?;
+    println!("Multisig: {multisig_funds}, Bob: {bob_funds}");
+
+    assert_eq!(multisig_funds, FUNDS - PAYOUT);
+    assert_eq!(bob_funds, PAYOUT);
+
+    Ok(())
+}
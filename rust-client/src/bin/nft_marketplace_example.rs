@@ -0,0 +1,247 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::{Account, AccountId},
+    asset::{FungibleAsset, NonFungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    nft::{self, NftMetadata},
+    nft_marketplace::{self, Marketplace},
+};
+
+/// Price of the NFT Bob buys.
+const PRICE: u64 = 200;
+/// Price of the NFT Alice takes back.
+const CANCELLED_PRICE: u64 = 300;
+
+/// Returns the account `account_id` as last synced by the client.
+async fn account(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    Ok(client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?
+        .account()
+        .clone())
+}
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    Ok(account(client, account_id)
+        .await?
+        .vault()
+        .get_balance(faucet_id)?)
+}
+
+/// Returns whether the vault of `account_id` holds `nft`.
+async fn owns(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    nft: NonFungibleAsset,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    Ok(account(client, account_id)
+        .await?
+        .vault()
+        .has_non_fungible_asset(nft)?)
+}
+
+/// Waits for `count` notes to `account_id` and consumes them.
+async fn collect(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    account_id: AccountId,
+    count: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    wait_for_notes(client, network, account_id, count).await?;
+    let tx_id = consume_all_notes(client, account_id).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("nft_marketplace_example")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create accounts, mint two NFTs to Alice and USD to Bob
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and assets");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice (seller): {}",
+        alice.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Bob (buyer):    {}",
+        bob.id().to_bech32(network.network_id())
+    );
+
+    let collection = nft::create_collection(&mut client, &keystore, alice.id(), 0).await?;
+    let mut nfts = Vec::new();
+    for number in 1..=2 {
+        let metadata = NftMetadata {
+            name: format!("Miden Tutorial NFT #{number}"),
+            description: "Traded in the NFT marketplace tutorial".to_string(),
+            image: format!("ipfs://miden-tutorial/{number}.png"),
+        };
+        let (tx_id, _) = nft::mint_nft(
+            &mut client,
+            collection.id(),
+            alice.id(),
+            &metadata,
+            NoteType::Public,
+        )
+        .await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        nfts.push(metadata.asset(collection.id())?);
+    }
+    collect(&mut client, &network, alice.id(), 2).await?;
+    let (sold, cancelled) = (nfts[0], nfts[1]);
+
+    let usd = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(usd.id(), 500)?,
+        bob.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(usd.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, bob.id(), 1).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy the marketplace and list both NFTs
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Alice lists both NFTs");
+
+    let marketplace_account = nft_marketplace::create_marketplace(&mut client, usd.id()).await?;
+    let marketplace = Marketplace::from_account(&marketplace_account)?;
+    println!(
+        "Marketplace: {}",
+        marketplace.id.to_bech32(network.network_id())
+    );
+
+    let list_script =
+        nft_marketplace::compile_note_script(&client, nft_marketplace::LIST_NOTE_PATH)?;
+    let buy_script = nft_marketplace::compile_note_script(&client, nft_marketplace::BUY_NOTE_PATH)?;
+    let cancel_script =
+        nft_marketplace::compile_note_script(&client, nft_marketplace::CANCEL_NOTE_PATH)?;
+
+    for (nft, price) in [(sold, PRICE), (cancelled, CANCELLED_PRICE)] {
+        let tx_id = nft_marketplace::list(
+            &mut client,
+            alice.id(),
+            &marketplace,
+            nft,
+            price,
+            list_script.clone(),
+        )
+        .await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        println!("Listed an NFT for {price} USD");
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Bob buys the first NFT
+    // -------------------------------------------------------------------------
+    // The buyer reads the price from the marketplace, not from the seller. Paying anything else
+    // makes the contract reject the note.
+    println!("\n[STEP 3] Bob buys the first NFT");
+
+    let listing = nft_marketplace::listing(&account(&client, marketplace.id).await?, sold)?
+        .ok_or("the NFT is not listed")?;
+    assert_eq!(listing.seller, alice.id());
+    let tx_id = nft_marketplace::buy(
+        &mut client,
+        bob.id(),
+        &marketplace,
+        sold,
+        &listing,
+        buy_script,
+    )
+    .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // One transaction created both payouts: the NFT to Bob and the USD to Alice
+    collect(&mut client, &network, bob.id(), 1).await?;
+    collect(&mut client, &network, alice.id(), 1).await?;
+    println!("Bob paid {} USD for the NFT", listing.price);
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Alice cancels the second listing
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Alice takes the second NFT back");
+
+    let tx_id = nft_marketplace::cancel(
+        &mut client,
+        alice.id(),
+        &marketplace,
+        cancelled,
+        cancel_script,
+    )
+    .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, alice.id(), 1).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking balances and listings");
+
+    assert!(owns(&client, bob.id(), sold).await?);
+    assert!(owns(&client, alice.id(), cancelled).await?);
+    assert!(!owns(&client, alice.id(), sold).await?);
+    assert_eq!(balance(&client, bob.id(), usd.id()).await?, 500 - PRICE);
+    assert_eq!(balance(&client, alice.id(), usd.id()).await?, PRICE);
+
+    let marketplace_account = account(&client, marketplace.id).await?;
+    assert_eq!(marketplace_account.vault().assets().count(), 0);
+    for nft in [sold, cancelled] {
+        assert_eq!(nft_marketplace::listing(&marketplace_account, nft)?, None);
+    }
+    println!("Bob owns the first NFT, Alice kept the second and earned {PRICE} USD");
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId,
+    asset::NonFungibleAsset,
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{create_p2id_note, NoteType},
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, Felt,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{consume_all_notes, create_basic_account, wait_for_notes, wait_for_tx},
+    nft::{self, NftMetadata},
+};
+
+/// Returns whether the vault of `account_id` holds `nft`.
+async fn owns(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    nft: NonFungibleAsset,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().has_non_fungible_asset(nft)?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("nft_minting")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create accounts and deploy the non-fungible faucet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and the NFT collection");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+
+    // A non-fungible faucet issues assets that are each unique, instead of amounts of a token.
+    // The collection is created by Alice and takes no royalty.
+    let collection = nft::create_collection(&mut client, &keystore, alice.id(), 0).await?;
+    println!(
+        "Collection: {}",
+        collection.id().to_bech32(network.network_id())
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Mint an NFT to Alice
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Minting an NFT to Alice");
+
+    // The asset commits to the hash of the metadata, which stays off chain
+    let metadata = NftMetadata {
+        name: "Miden Tutorial NFT #1".to_string(),
+        description: "Minted by the NFT minting tutorial".to_string(),
+        image: "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string(),
+    };
+    let (tx_id, mint_note) = nft::mint_nft(
+        &mut client,
+        collection.id(),
+        alice.id(),
+        &metadata,
+        NoteType::Public,
+    )
+    .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Minted into note {}", mint_note.id());
+
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let nft = metadata.asset(collection.id())?;
+    assert!(owns(&client, alice.id(), nft).await?);
+    println!("Alice holds the NFT");
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Alice transfers the NFT to Bob
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Alice sends the NFT to Bob with a P2ID note");
+
+    let transfer_note = create_p2id_note(
+        alice.id(),
+        bob.id(),
+        vec![nft.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(transfer_note)])
+        .build()?;
+    let tx_id = client.submit_with_prover(alice.id(), request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    wait_for_notes(&mut client, &network, bob.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, bob.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Verify ownership
+    // -------------------------------------------------------------------------
+    // Anybody handed the metadata recomputes the asset and looks for it in Bob's vault. Changing
+    // a single byte of the metadata yields another asset, which Bob does not hold.
+    println!("\n[STEP 4] Checking ownership");
+
+    let recomputed = metadata.asset(collection.id())?;
+    assert!(owns(&client, bob.id(), recomputed).await?);
+    assert!(!owns(&client, alice.id(), recomputed).await?);
+
+    let forged = NftMetadata {
+        name: "Miden Tutorial NFT #2".to_string(),
+        ..metadata.clone()
+    };
+    assert!(!owns(&client, bob.id(), forged.asset(collection.id())?).await?);
+    println!("Bob owns \"{}\"", metadata.name);
+
+    Ok(())
+}
@@ -0,0 +1,401 @@
+use miden_lib::account::auth::AuthRpoFalcon512;
+use miden_lib::note::WellKnownNote;
+use miden_lib::transaction::TransactionKernel;
+use rand::RngCore;
+use std::{fs, path::Path, sync::Arc};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    note::{
+        create_p2id_note, Note, NoteAssets, NoteExecutionHint, NoteExecutionMode, NoteInputs,
+        NoteMetadata, NoteRecipient, NoteTag, NoteType,
+    },
+    rpc::{domain::account::AccountStorageRequirements, GrpcClient},
+    transaction::{ForeignAccount, OutputNote, TransactionRequestBuilder},
+    Felt, Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
+    asset::{NonFungibleAsset, NonFungibleAssetDetails},
+    ZERO,
+};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, create_library,
+        wait_for_notes, word_to_masm,
+    },
+};
+
+/// Rebuilds the P2ID payout note created by the royalty sale note script.
+///
+/// The note script derives the serial number of every payout note from its own serial
+/// number by adding `serial_offset` to the last element.
+fn royalty_payout_note(
+    sale_serial_num: Word,
+    serial_offset: u64,
+    buyer_id: AccountId,
+    target_id: AccountId,
+    payment_asset: FungibleAsset,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    let serial_num = [
+        sale_serial_num[0],
+        sale_serial_num[1],
+        sale_serial_num[2],
+        Felt::new(sale_serial_num[3].as_int() + serial_offset),
+    ]
+    .into();
+
+    let inputs = NoteInputs::new(vec![target_id.suffix(), target_id.prefix().as_felt()])?;
+    let recipient = NoteRecipient::new(serial_num, WellKnownNote::P2ID.script(), inputs);
+    let metadata = NoteMetadata::new(
+        buyer_id,
+        NoteType::Public,
+        NoteTag::from_account_id(target_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    let vault = NoteAssets::new(vec![payment_asset.into()])?;
+
+    Ok(Note::new(vault, metadata, recipient))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("nft_royalty_sale")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create accounts and deploy the payment faucet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating creator, seller and buyer accounts");
+
+    let creator = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Creator's account ID: {:?}",
+        creator.id().to_bech32(network.network_id())
+    );
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice's (seller) account ID: {:?}",
+        alice.id().to_bech32(network.network_id())
+    );
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Bob's (buyer) account ID: {:?}",
+        bob.id().to_bech32(network.network_id())
+    );
+
+    println!("\nDeploying the payment faucet.");
+    let payment_faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+    println!(
+        "Payment faucet account ID: {:?}",
+        payment_faucet.id().to_bech32(network.network_id())
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy the NFT collection
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Deploying the NFT collection with a 10% royalty");
+
+    let royalty_percent: u64 = 10;
+    let collection_code =
+        fs::read_to_string(Path::new("../masm/accounts/nft_collection.masm")).unwrap();
+
+    // The royalty configuration lives in storage so anyone can read it before trading
+    let royalty_config: Word = [
+        Felt::new(royalty_percent),
+        ZERO,
+        creator.id().suffix(),
+        creator.id().prefix().as_felt(),
+    ]
+    .into();
+
+    let collection_component = AccountComponent::compile(
+        &collection_code,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Value(royalty_config)],
+    )
+    .unwrap()
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let collection_key_pair = AuthSecretKey::new_rpo_falcon512();
+
+    let collection = AccountBuilder::new(init_seed)
+        .account_type(AccountType::NonFungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(
+            collection_key_pair.public_key().to_commitment(),
+        ))
+        .with_component(collection_component)
+        .build()
+        .unwrap();
+
+    client.add_account(&collection, false).await?;
+    keystore.add_key(&collection_key_pair).unwrap();
+
+    println!(
+        "NFT collection account ID: {:?}",
+        collection.id().to_bech32(network.network_id())
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Mint an NFT to Alice
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Minting NFT #1 to Alice");
+
+    let nft_details =
+        NonFungibleAssetDetails::new(collection.id().prefix(), b"Miden Tutorial NFT #1".to_vec())
+            .unwrap();
+    let nft = NonFungibleAsset::new(&nft_details).unwrap();
+
+    // The collection mints the NFT straight into a P2ID note for Alice
+    let mint_note = create_p2id_note(
+        collection.id(),
+        alice.id(),
+        vec![nft.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+
+    let script_code = fs::read_to_string(Path::new("../masm/scripts/mint_nft_script.masm"))
+        .unwrap()
+        .replace("{recipient}", &word_to_masm(mint_note.recipient().digest()))
+        .replace(
+            "{execution_hint}",
+            &u64::from(mint_note.metadata().execution_hint()).to_string(),
+        )
+        .replace(
+            "{note_type}",
+            &(mint_note.metadata().note_type() as u8).to_string(),
+        )
+        .replace("{aux}", "0")
+        .replace("{tag}", &u32::from(mint_note.metadata().tag()).to_string())
+        .replace("{nft_asset}", &word_to_masm(Word::from(nft)));
+
+    let collection_library =
+        create_library(&collection_code, "external_contract::nft_collection").unwrap();
+
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&collection_library)?
+        .compile_tx_script(&script_code)?;
+
+    let mint_request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .expected_output_recipients(vec![mint_note.recipient().clone()])
+        .build()?;
+
+    let tx_id = client
+        .submit_with_prover(collection.id(), mint_request)
+        .await?;
+    println!("Minted NFT. TX: {:?}", tx_id);
+
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Fund Bob with payment tokens
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Minting 500 USD to Bob");
+
+    let bob_funds: u64 = 500;
+    let mint_request = TransactionRequestBuilder::new()
+        .build_mint_fungible_asset(
+            FungibleAsset::new(payment_faucet.id(), bob_funds).unwrap(),
+            bob.id(),
+            NoteType::Public,
+            client.rng(),
+        )
+        .unwrap();
+
+    let tx_id = client
+        .submit_with_prover(payment_faucet.id(), mint_request)
+        .await?;
+    println!("Minted payment tokens. TX: {:?}", tx_id);
+
+    wait_for_notes(&mut client, &network, bob.id(), 1).await?;
+    consume_all_notes(&mut client, bob.id()).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Alice lists the NFT with a royalty sale note
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Alice lists the NFT for 200 USD");
+
+    // The note checks the royalty terms against the collection when Bob consumes it, so
+    // Alice copies them from the collection: lowering the royalty would only make the sale fail
+    let collection_record = client.get_account(collection.id()).await?.unwrap();
+    let royalty_config: Word = collection_record.account().storage().get_item(0)?;
+    let royalty_percent = royalty_config[0].as_int();
+    let creator_id = AccountId::new_unchecked([royalty_config[3], royalty_config[2]]);
+
+    let price: u64 = 200;
+    let mut sale_inputs = WellKnownNote::P2ID.script_root().to_vec();
+    sale_inputs.extend([
+        Felt::new(price),
+        Felt::new(royalty_percent),
+        payment_faucet.id().suffix(),
+        payment_faucet.id().prefix().as_felt(),
+        alice.id().suffix(),
+        alice.id().prefix().as_felt(),
+        NoteTag::from_account_id(alice.id()).into(),
+        creator_id.suffix(),
+        creator_id.prefix().as_felt(),
+        NoteTag::from_account_id(creator_id).into(),
+        collection.id().suffix(),
+        collection.id().prefix().as_felt(),
+    ]);
+
+    let sale_code = fs::read_to_string(Path::new("../masm/notes/royalty_sale_note.masm")).unwrap();
+    let sale_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&collection_library)?
+        .compile_note_script(&sale_code)?;
+    let sale_serial_num = client.rng().draw_word();
+    let sale_recipient =
+        NoteRecipient::new(sale_serial_num, sale_script, NoteInputs::new(sale_inputs)?);
+
+    let marketplace_tag = NoteTag::for_public_use_case(0, 0, NoteExecutionMode::Local).unwrap();
+    let sale_metadata = NoteMetadata::new(
+        alice.id(),
+        NoteType::Public,
+        marketplace_tag,
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    let sale_note = Note::new(
+        NoteAssets::new(vec![nft.into()])?,
+        sale_metadata,
+        sale_recipient,
+    );
+
+    let list_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(sale_note.clone())])
+        .build()?;
+
+    let tx_id = client.submit_with_prover(alice.id(), list_request).await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Bob buys the NFT
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Bob consumes the sale note and pays for the NFT");
+
+    let royalty = price * royalty_percent / 100;
+    let seller_payout = royalty_payout_note(
+        sale_serial_num,
+        1,
+        bob.id(),
+        alice.id(),
+        FungibleAsset::new(payment_faucet.id(), price - royalty).unwrap(),
+    )?;
+    let creator_payout = royalty_payout_note(
+        sale_serial_num,
+        2,
+        bob.id(),
+        creator_id,
+        FungibleAsset::new(payment_faucet.id(), royalty).unwrap(),
+    )?;
+
+    // The sale note reads the royalty configuration of the collection through FPI
+    let collection_account =
+        ForeignAccount::public(collection.id(), AccountStorageRequirements::default())?;
+    let buy_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(sale_note, None)])
+        .foreign_accounts(vec![collection_account])
+        .expected_output_recipients(vec![
+            seller_payout.recipient().clone(),
+            creator_payout.recipient().clone(),
+        ])
+        .build()?;
+
+    let tx_id = client.submit_with_prover(bob.id(), buy_request).await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 7: Seller and creator collect their payouts
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 7] Alice and the creator consume their payout notes");
+
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+
+    wait_for_notes(&mut client, &network, creator_id, 1).await?;
+    consume_all_notes(&mut client, creator_id).await?;
+
+    client.sync_state().await?;
+
+    let alice_account = client.get_account(alice.id()).await?.unwrap();
+    let creator_account = client.get_account(creator_id).await?.unwrap();
+    let bob_account = client.get_account(bob.id()).await?.unwrap();
+
+    let alice_balance = alice_account
+        .account()
+        .vault()
+        .get_balance(payment_faucet.id())?;
+    let creator_balance = creator_account
+        .account()
+        .vault()
+        .get_balance(payment_faucet.id())?;
+    let bob_balance = bob_account
+        .account()
+        .vault()
+        .get_balance(payment_faucet.id())?;
+
+    println!("Alice (seller) balance: {}", alice_balance);
+    println!("Creator royalty balance: {}", creator_balance);
+    println!("Bob (buyer) balance: {}", bob_balance);
+
+    assert_eq!(alice_balance, price - royalty);
+    assert_eq!(creator_balance, royalty);
+    assert_eq!(bob_balance, bob_funds - price);
+    assert!(bob_account.account().vault().has_non_fungible_asset(nft)?);
+    assert!(!alice_account
+        .account()
+        .vault()
+        .has_non_fungible_asset(nft)?);
+
+    println!(
+        "\nRoyalty of {} USD was routed to the creator automatically!",
+        royalty
+    );
+
+    Ok(())
+}
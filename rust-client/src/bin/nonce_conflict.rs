@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{create_p2id_note, NoteType},
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequest, TransactionRequestBuilder},
+    Client, Felt,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rand::rngs::StdRng;
+use rust_client::{
+    cli::{ProverOption, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, fresh_account_state,
+        wait_for_commit, wait_for_notes, wait_for_tx,
+    },
+};
+
+/// Tokens minted to Alice.
+const FUNDS: u64 = 100;
+/// Amounts of the two payments Alice builds from the same state.
+const PAYMENTS: [u64; 2] = [30, 20];
+
+/// Builds a request sending `amount` tokens of `faucet_id` from `sender` to `receiver`.
+fn payment_request(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    receiver: AccountId,
+    faucet_id: AccountId,
+    amount: u64,
+) -> Result<TransactionRequest, Box<dyn std::error::Error>> {
+    let note = create_p2id_note(
+        sender,
+        receiver,
+        vec![FungibleAsset::new(faucet_id, amount)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    Ok(TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note)])
+        .build()?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("nonce_conflict")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    // Both transactions are submitted by hand to show what the node does with the second one
+    let prover = ProverOption::global()
+        .transaction_prover()
+        .ok_or("this tutorial submits transactions, run it without `--prover none`")?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create and fund Alice
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice, Bob and a faucet");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), FUNDS)?,
+        alice.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Execute two payments against the same state
+    // -------------------------------------------------------------------------
+    // Executing a transaction does not change the state the client holds: only applying it
+    // after submission does. Both payments therefore start from the same account state and
+    // move it to the same next nonce.
+    println!("\n[STEP 2] Executing two payments from the same state of Alice");
+
+    let state = fresh_account_state(&mut client, alice.id()).await?;
+    println!(
+        "Alice: nonce {}, commitment {}",
+        state.nonce(),
+        state.commitment()
+    );
+
+    let mut results = Vec::new();
+    for amount in PAYMENTS {
+        let request = payment_request(&mut client, alice.id(), bob.id(), faucet.id(), amount)?;
+        let tx_result = client.execute_transaction(alice.id(), request).await?;
+        let executed = tx_result.executed_transaction();
+        println!(
+            "Payment of {amount}: {:?}, from commitment {} to nonce {}",
+            executed.id(),
+            executed.initial_account().commitment(),
+            executed.final_account().nonce()
+        );
+        results.push(tx_result);
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Submit both
+    // -------------------------------------------------------------------------
+    // The first payment moves Alice to a new state. The second still starts from the old one,
+    // which is no longer the state of Alice on chain, so the node rejects it.
+    println!("\n[STEP 3] Submitting both payments");
+
+    let first = &results[0];
+    let proven = client.prove_transaction_with(first, prover.clone()).await?;
+    let height = client.submit_proven_transaction(proven, first).await?;
+    client.apply_transaction(first, height).await?;
+    println!(
+        "Payment of {} accepted. TX: {:?}",
+        PAYMENTS[0],
+        first.executed_transaction().id()
+    );
+
+    let second = &results[1];
+    let proven = client.prove_transaction_with(second, prover).await?;
+    let Err(err) = client.submit_proven_transaction(proven, second).await else {
+        return Err("the node accepted a transaction built from a stale state".into());
+    };
+    println!("Payment of {} rejected: {err}", PAYMENTS[1]);
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Detect the stale state and rebuild the payment
+    // -------------------------------------------------------------------------
+    // A rejection can have many causes. Comparing the state the transaction started from with
+    // the current state of the account tells whether it lost a race against another
+    // transaction, in which case building it again from the current state fixes it.
+    println!("\n[STEP 4] Rebuilding the rejected payment");
+
+    let state = fresh_account_state(&mut client, alice.id()).await?;
+    let stale = second.executed_transaction().initial_account().commitment() != state.commitment();
+    println!(
+        "Alice: nonce {}, commitment {}. The payment was built from a stale state: {stale}",
+        state.nonce(),
+        state.commitment()
+    );
+    assert!(stale);
+
+    let request = payment_request(&mut client, alice.id(), bob.id(), faucet.id(), PAYMENTS[1])?;
+    let tx_id = client.submit_with_prover(alice.id(), request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking balances");
+
+    wait_for_notes(&mut client, &network, bob.id(), PAYMENTS.len()).await?;
+    consume_all_notes(&mut client, bob.id()).await?;
+    wait_for_commit(&mut client).await?;
+
+    let alice_funds = fresh_account_state(&mut client, alice.id())
+        .await?
+        .vault()
+        .get_balance(faucet.id())?;
+    let bob_funds = fresh_account_state(&mut client, bob.id())
+        .await?
+        .vault()
+        .get_balance(faucet.id())?;
+    println!("Alice: {alice_funds}, Bob: {bob_funds}");
+
+    let paid: u64 = PAYMENTS.iter().sum();
+    assert_eq!(alice_funds, FUNDS - paid);
+    assert_eq!(bob_funds, paid);
+
+    Ok(())
+}
@@ -0,0 +1,237 @@
+use miden_lib::account::auth::AuthRpoFalcon512;
+use rand::{rngs::StdRng, RngCore};
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::time::{sleep, Duration};
+
+use miden_client::{
+    account::component::BasicFungibleFaucet,
+    asset::FungibleAsset,
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{NoteId, NoteTag, NoteType},
+    rpc::GrpcClient,
+    store::{InputNoteRecord, NoteFilter},
+    transaction::TransactionRequestBuilder,
+    Client, ClientError,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{AccountBuilder, AccountStorageMode, AccountType},
+    asset::TokenSymbol,
+    Felt,
+};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{create_basic_account, wait_for_notes},
+};
+
+/// Number of notes minted to the wallet.
+const NUM_NOTES: usize = 6;
+/// Number of notes per page in the pagination demo.
+const PAGE_SIZE: usize = 4;
+
+/// A page of input notes and the cursor to pass to get the next one.
+struct NotePage {
+    notes: Vec<InputNoteRecord>,
+    next: Option<NoteId>,
+}
+
+/// Returns up to `page_size` notes matching `filter` whose id comes after `after`.
+///
+/// The store has no native pagination, so the matching notes are loaded and ordered by id.
+/// Ordering by id rather than by position keeps the pages stable while notes are added or
+/// change state between two calls: resuming after a cursor never skips or repeats a note.
+async fn input_notes_page(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    filter: NoteFilter,
+    after: Option<NoteId>,
+    page_size: usize,
+) -> Result<NotePage, ClientError> {
+    let mut notes = client.get_input_notes(filter).await?;
+    notes.sort_by_key(|note| note.id().to_hex());
+    if let Some(after) = after {
+        notes.retain(|note| note.id().to_hex() > after.to_hex());
+    }
+
+    let has_more = notes.len() > page_size;
+    notes.truncate(page_size);
+    let next = if has_more {
+        notes.last().map(InputNoteRecord::id)
+    } else {
+        None
+    };
+
+    Ok(NotePage { notes, next })
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("note_queries")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Fill the store with notes in different states
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Minting {NUM_NOTES} notes and consuming half of them");
+
+    let wallet = create_basic_account(&mut client, &keystore).await?;
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let faucet = AccountBuilder::new(init_seed)
+        .account_type(AccountType::FungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+        .with_component(
+            BasicFungibleFaucet::new(TokenSymbol::new("MID").unwrap(), 8, Felt::new(1_000_000))
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    client.add_account(&faucet, false).await?;
+    keystore.add_key(&key_pair).unwrap();
+
+    for i in 1..=NUM_NOTES as u64 {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet.id(), i * 10).unwrap(),
+            wallet.id(),
+            NoteType::Public,
+            client.rng(),
+        )?;
+        client.submit_with_prover(faucet.id(), mint_request).await?;
+    }
+
+    wait_for_notes(&mut client, &network, wallet.id(), NUM_NOTES).await?;
+    let to_consume: Vec<NoteId> = client
+        .get_consumable_notes(Some(wallet.id()))
+        .await?
+        .iter()
+        .take(NUM_NOTES / 2)
+        .map(|(note, _)| note.id())
+        .collect();
+    let consume_request = TransactionRequestBuilder::new().build_consume_notes(to_consume)?;
+    client
+        .submit_with_prover(wallet.id(), consume_request)
+        .await?;
+
+    // Wait for the consumption to be committed so the note states settle
+    loop {
+        client.sync_state().await?;
+        if client
+            .get_input_notes(NoteFilter::Processing)
+            .await?
+            .is_empty()
+        {
+            break;
+        }
+        sleep(Duration::from_secs(3)).await;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Filter by state
+    // -------------------------------------------------------------------------
+    // `NoteFilter` selects notes by their lifecycle state, or by id / nullifier
+    println!("\n[STEP 2] Notes per state");
+
+    let state_filters = [
+        ("all", NoteFilter::All),
+        ("expected", NoteFilter::Expected),
+        ("committed", NoteFilter::Committed),
+        ("processing", NoteFilter::Processing),
+        ("consumed", NoteFilter::Consumed),
+        ("unverified", NoteFilter::Unverified),
+    ];
+    for (name, filter) in state_filters {
+        let notes = client.get_input_notes(filter).await?;
+        println!("{name:>10}: {}", notes.len());
+    }
+
+    let committed = client.get_input_notes(NoteFilter::Committed).await?;
+    if let Some(note) = committed.first() {
+        // A known id can be looked up directly
+        let same_note = client.get_input_note(note.id()).await?;
+        assert!(same_note.is_some());
+
+        let nullifier = note.nullifier();
+        let by_nullifier = client
+            .get_input_notes(NoteFilter::Nullifiers(vec![nullifier]))
+            .await?;
+        println!(
+            "Note {} found by id and by nullifier ({} match)",
+            note.id().to_hex(),
+            by_nullifier.len()
+        );
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Filter by tag and by account
+    // -------------------------------------------------------------------------
+    // There is no tag or account filter in the store: tags are read from the note metadata,
+    // and the notes an account can consume come from `get_consumable_notes`
+    println!("\n[STEP 3] Notes per tag and per account");
+
+    let mut per_tag: BTreeMap<u32, usize> = BTreeMap::new();
+    for note in client.get_input_notes(NoteFilter::All).await? {
+        if let Some(metadata) = note.metadata() {
+            *per_tag.entry(u32::from(metadata.tag())).or_default() += 1;
+        }
+    }
+    let wallet_tag = u32::from(NoteTag::from_account_id(wallet.id()));
+    for (tag, count) in &per_tag {
+        let marker = if *tag == wallet_tag { " (wallet)" } else { "" };
+        println!("tag {tag:>10}: {count} notes{marker}");
+    }
+
+    let consumable = client.get_consumable_notes(Some(wallet.id())).await?;
+    println!(
+        "{} notes consumable by {}",
+        consumable.len(),
+        wallet.id().to_bech32(network.network_id())
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Page through all notes
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Paging through all notes, {PAGE_SIZE} per page");
+
+    let mut cursor = None;
+    let mut page_number = 1;
+    loop {
+        let page = input_notes_page(&client, NoteFilter::All, cursor, PAGE_SIZE).await?;
+        println!("Page {page_number}:");
+        for note in &page.notes {
+            println!("  {} {}", note.id().to_hex(), note.state());
+        }
+
+        match page.next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+        page_number += 1;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use rand::rngs::StdRng;
+use std::{cell::Cell, rc::Rc, sync::Arc, time::Duration};
+
+use miden_client::{
+    asset::{Asset, FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{create_p2id_note, NoteType},
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, Felt,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{consume_all_notes, create_basic_account, create_basic_faucet},
+    sync_hooks::SyncHooks,
+};
+use tokio::time::sleep;
+
+/// Syncs through `hooks` until `done` returns true.
+async fn feed_until(
+    hooks: &mut SyncHooks,
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    done: impl Fn() -> bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    loop {
+        hooks.sync(client).await?;
+        if done() {
+            return Ok(());
+        }
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("notification_feed")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the accounts
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Register the callbacks of the feed
+    // -------------------------------------------------------------------------
+    // Every callback prints one line of the feed. The counters let the example wait for the
+    // events it expects; a real application would update its UI or database instead.
+    println!("\n[STEP 2] Registering the feed");
+
+    let committed = Rc::new(Cell::new(0));
+    let received = Rc::new(Cell::new(0));
+    let consumed = Rc::new(Cell::new(0));
+
+    let mut hooks = SyncHooks::new();
+    let counter = committed.clone();
+    hooks.on_transaction_committed(move |summary, tx_id| {
+        counter.set(counter.get() + 1);
+        println!(
+            "  [block {}] ✅ transaction {} committed",
+            summary.block_num,
+            tx_id.to_hex()
+        );
+    });
+    let counter = received.clone();
+    hooks.on_note_received(move |summary, note| {
+        counter.set(counter.get() + 1);
+        let amounts: Vec<String> = note
+            .assets()
+            .iter()
+            .map(|asset| match asset {
+                Asset::Fungible(asset) => format!("{} tokens", asset.amount()),
+                Asset::NonFungible(_) => "an NFT".to_string(),
+            })
+            .collect();
+        println!(
+            "  [block {}] 📨 note {} received with {}",
+            summary.block_num,
+            note.id().to_hex(),
+            amounts.join(", ")
+        );
+    });
+    let counter = consumed.clone();
+    hooks.on_note_consumed(move |summary, note_id| {
+        counter.set(counter.get() + 1);
+        println!(
+            "  [block {}] 🔥 note {} consumed",
+            summary.block_num,
+            note_id.to_hex()
+        );
+    });
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Mint to Alice and let her consume the note
+    // -------------------------------------------------------------------------
+    // The transactions are only submitted here; the feed reports them once they are committed.
+    println!("\n[STEP 3] Minting 100 MID to Alice");
+
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), 100)?,
+        alice.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    client.submit_with_prover(faucet.id(), mint_request).await?;
+    feed_until(&mut hooks, &mut client, || {
+        committed.get() >= 1 && received.get() >= 1
+    })
+    .await?;
+
+    consume_all_notes(&mut client, alice.id()).await?;
+    feed_until(&mut hooks, &mut client, || consumed.get() >= 1).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Alice pays Bob, who consumes the note
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Alice sends 40 MID to Bob");
+
+    let note = create_p2id_note(
+        alice.id(),
+        bob.id(),
+        vec![FungibleAsset::new(faucet.id(), 40)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note)])
+        .build()?;
+    client.submit_with_prover(alice.id(), request).await?;
+    feed_until(&mut hooks, &mut client, || received.get() >= 2).await?;
+
+    consume_all_notes(&mut client, bob.id()).await?;
+    feed_until(&mut hooks, &mut client, || consumed.get() >= 2).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking the feed");
+
+    // Mint, Alice consuming, Alice paying and Bob consuming
+    feed_until(&mut hooks, &mut client, || committed.get() >= 4).await?;
+    println!(
+        "{} transactions committed, {} notes received, {} notes consumed",
+        committed.get(),
+        received.get(),
+        consumed.get()
+    );
+
+    assert_eq!(committed.get(), 4);
+    assert_eq!(received.get(), 2);
+    assert_eq!(consumed.get(), 2);
+    let record = client
+        .get_account(bob.id())
+        .await?
+        .ok_or("Bob is not tracked by the client")?;
+    assert_eq!(record.account().vault().get_balance(faucet.id())?, 40);
+    println!("Every event was reported exactly once");
+
+    Ok(())
+}
@@ -0,0 +1,217 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{NoteScript, NoteType},
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    amm::{self, SwapOffer},
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_commit,
+        wait_for_notes, wait_for_tx,
+    },
+    replacement::{self, Replacement},
+};
+
+/// ETH Alice offers.
+const OFFERED_ETH: u64 = 10;
+/// USD Alice asks for in her first offer, and after each repricing.
+const PRICES: [u64; 3] = [1_000, 1_100, 1_200];
+/// USD Bob holds.
+const BOB_USD: u64 = 2_000;
+
+/// Builds the offer of `maker` selling [`OFFERED_ETH`] for `price` USD.
+fn offer(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    maker: AccountId,
+    eth_faucet: AccountId,
+    usd_faucet: AccountId,
+    price: u64,
+    script: &NoteScript,
+) -> Result<SwapOffer, Box<dyn std::error::Error>> {
+    let serial_num = client.rng().draw_word();
+    Ok(SwapOffer::new(
+        maker,
+        FungibleAsset::new(eth_faucet, OFFERED_ETH)?,
+        FungibleAsset::new(usd_faucet, price)?,
+        script.clone(),
+        serial_num,
+    )?)
+}
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("offer_replacement")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the maker, the taker and both assets
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and funding them");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice (maker): {}",
+        alice.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Bob (taker):   {}",
+        bob.id().to_bech32(network.network_id())
+    );
+
+    let eth_faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("ETH").unwrap()).await?;
+    let usd_faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+
+    for (account_id, faucet_id, amount) in [
+        (alice.id(), eth_faucet.id(), OFFERED_ETH),
+        (bob.id(), usd_faucet.id(), BOB_USD),
+    ] {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet_id, amount)?,
+            account_id,
+            NoteType::Public,
+            client.rng(),
+        )?;
+        client.submit_with_prover(faucet_id, mint_request).await?;
+    }
+    for account_id in [alice.id(), bob.id()] {
+        wait_for_notes(&mut client, &network, account_id, 1).await?;
+        consume_all_notes(&mut client, account_id).await?;
+    }
+    wait_for_commit(&mut client).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice offers her ETH
+    // -------------------------------------------------------------------------
+    println!(
+        "\n[STEP 2] Alice offers {OFFERED_ETH} ETH for {} USD",
+        PRICES[0]
+    );
+
+    let script = amm::compile_note_script(&client, amm::OFFER_NOTE_PATH)?;
+    let first = offer(
+        &mut client,
+        alice.id(),
+        eth_faucet.id(),
+        usd_faucet.id(),
+        PRICES[0],
+        &script,
+    )?;
+    let tx_id = amm::post_offers(&mut client, alice.id(), std::slice::from_ref(&first)).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Alice reprices the offer
+    // -------------------------------------------------------------------------
+    // The old offer is taken back and the new one posted by the same transaction, so Alice
+    // never has both offers out, nor none.
+    println!("\n[STEP 3] Alice raises her price to {} USD", PRICES[1]);
+
+    let second = offer(
+        &mut client,
+        alice.id(),
+        eth_faucet.id(),
+        usd_faucet.id(),
+        PRICES[1],
+        &script,
+    )?;
+    let Replacement::Replaced(tx_id) = amm::replace_offer(&mut client, &first, &second).await?
+    else {
+        return Err("nobody could have filled the first offer yet".into());
+    };
+    wait_for_tx(&mut client, tx_id).await?;
+    assert!(replacement::is_consumed(&client, &first.note).await?);
+    println!("Replaced the first offer. TX: {tx_id:?}");
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Bob fills the new offer before Alice reprices again
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Bob fills the offer at {} USD", PRICES[1]);
+
+    let tx_id = amm::fill_offer(&mut client, bob.id(), &second).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let third = offer(
+        &mut client,
+        alice.id(),
+        eth_faucet.id(),
+        usd_faucet.id(),
+        PRICES[2],
+        &script,
+    )?;
+    let outcome = amm::replace_offer(&mut client, &second, &third).await?;
+    println!(
+        "Alice tries to raise her price to {}: {outcome:?}",
+        PRICES[2]
+    );
+    assert_eq!(outcome, Replacement::AlreadyConsumed);
+
+    // Losing the race is not a loss: Bob paid the price of the offer he filled
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_commit(&mut client).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking balances");
+
+    let alice_eth = balance(&client, alice.id(), eth_faucet.id()).await?;
+    let alice_usd = balance(&client, alice.id(), usd_faucet.id()).await?;
+    let bob_eth = balance(&client, bob.id(), eth_faucet.id()).await?;
+    let bob_usd = balance(&client, bob.id(), usd_faucet.id()).await?;
+    println!("Alice: {alice_eth} ETH, {alice_usd} USD");
+    println!("Bob:   {bob_eth} ETH, {bob_usd} USD");
+
+    assert_eq!((alice_eth, alice_usd), (0, PRICES[1]));
+    assert_eq!((bob_eth, bob_usd), (OFFERED_ETH, BOB_USD - PRICES[1]));
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::{Account, AccountId},
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    fpi::warn_on_foreign_accounts,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    lending::{self, Pool, PriceSource, RiskParams},
+    pragma,
+};
+
+/// Risk parameters of the pool: borrow up to 50% of the collateral value, liquidation above
+/// 80%.
+const RISK: RiskParams = RiskParams {
+    max_ltv: 50,
+    liquidation_threshold: 80,
+};
+/// BTC Bob deposits as collateral.
+const COLLATERAL: u64 = 1;
+/// USD Bob borrows, within the limit as long as BTC trades above 20,000 USD.
+const LOAN: u64 = 10_000;
+/// USD Bob then tries to borrow on top, only allowed if BTC traded above 2,020,000 USD.
+const EXCESSIVE_LOAN: u64 = 1_000_000;
+/// USD Alice supplies to the pool.
+const LIQUIDITY: u64 = 50_000;
+
+/// Returns the account `account_id` as last synced by the client.
+async fn account(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    Ok(client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?
+        .account()
+        .clone())
+}
+
+/// Waits for `count` notes to `account_id` and consumes them.
+async fn collect(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    account_id: AccountId,
+    count: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    wait_for_notes(client, network, account_id, count).await?;
+    let tx_id = consume_all_notes(client, account_id).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client. Pragma only publishes prices on testnet.
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("oracle_lending")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the lender, the borrower and both assets
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts, minting USD to Alice and BTC to Bob");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice (lender): {}",
+        alice.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Bob (borrower): {}",
+        bob.id().to_bech32(network.network_id())
+    );
+
+    let btc = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("BTC").unwrap()).await?;
+    let usd = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+    for (account_id, faucet_id, amount) in [
+        (alice.id(), usd.id(), LIQUIDITY),
+        (bob.id(), btc.id(), COLLATERAL),
+    ] {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet_id, amount)?,
+            account_id,
+            NoteType::Public,
+            client.rng(),
+        )?;
+        let tx_id = client.submit_with_prover(faucet_id, mint_request).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+    collect(&mut client, &network, alice.id(), 1).await?;
+    collect(&mut client, &network, bob.id(), 1).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy a pool priced by the Pragma BTC/USD pair
+    // -------------------------------------------------------------------------
+    // The pool calls `get_median` on the oracle, which reads each publisher through a nested
+    // FPI call: every transaction of the pool loads all of them as foreign accounts.
+    println!("\n[STEP 2] Deploying the lending pool");
+
+    let price_source = PriceSource::Pragma {
+        oracle: pragma::testnet_oracle(),
+        pair: pragma::BTC_USD,
+    };
+    let pool_account =
+        lending::create_pool(&mut client, btc.id(), usd.id(), price_source, RISK).await?;
+    println!(
+        "Pool:   {}",
+        pool_account.id().to_bech32(network.network_id())
+    );
+    println!("Oracle: {}", pragma::TESTNET_ORACLE);
+    let pool = Pool::from_account(&pool_account)?;
+    assert_eq!(pool.price_source, price_source);
+
+    let foreign_accounts = price_source.foreign_accounts(&mut client).await?;
+    println!(
+        "Reading the price loads {} foreign accounts",
+        foreign_accounts.len()
+    );
+    warn_on_foreign_accounts(&foreign_accounts);
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Alice supplies liquidity, Bob deposits collateral and borrows
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Alice supplies {LIQUIDITY} USD, Bob borrows {LOAN} USD");
+
+    let deposit_script = lending::compile_note_script(&client, lending::DEPOSIT_NOTE_PATH)?;
+    let borrow_script = lending::compile_note_script(&client, lending::BORROW_NOTE_PATH)?;
+    for (owner, asset) in [
+        (alice.id(), FungibleAsset::new(usd.id(), LIQUIDITY)?),
+        (bob.id(), FungibleAsset::new(btc.id(), COLLATERAL)?),
+    ] {
+        let tx_id =
+            lending::deposit(&mut client, owner, &pool, asset, deposit_script.clone()).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+
+    let tx_id = lending::borrow(&mut client, bob.id(), &pool, LOAN, borrow_script.clone()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, bob.id(), 1).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 4: The oracle price caps what Bob can borrow
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Bob tries to borrow {EXCESSIVE_LOAN} USD more");
+
+    let Err(err) =
+        lending::borrow(&mut client, bob.id(), &pool, EXCESSIVE_LOAN, borrow_script).await
+    else {
+        return Err("the pool lent beyond the maximum loan-to-value ratio".into());
+    };
+    println!("The pool refuses: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    // The position stays healthy until BTC falls below its liquidation price. Below it, anyone
+    // may repay the debt with a `pool_liquidate_note` and take the collateral, as the
+    // `liquidation_bot` tutorial shows with a price feed it controls.
+    println!("\n[STEP 5] Checking the position");
+
+    let position = Pool::from_account(&account(&client, pool.id).await?)?.position(bob.id());
+    assert_eq!(position.collateral, COLLATERAL);
+    assert_eq!(position.debt, LOAN);
+    assert_eq!(
+        account(&client, bob.id())
+            .await?
+            .vault()
+            .get_balance(usd.id())?,
+        LOAN
+    );
+    println!(
+        "Bob's position: {} BTC, {} USD debt, liquidatable once BTC/USD falls below {}",
+        position.collateral,
+        position.debt,
+        position
+            .liquidation_price(RISK)
+            .ok_or("the position holds no debt")?
+    );
+
+    Ok(())
+}
@@ -0,0 +1,230 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{create_swap_note, NoteType},
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, Felt,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    order_book,
+};
+
+/// ETH sold by each order.
+const LOT_ETH: u64 = 10;
+/// USD Bob is ready to spend.
+const BUDGET_USD: u64 = 2_200;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+/// Waits for `count` notes to `account_id` and consumes them.
+async fn collect(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    account_id: AccountId,
+    count: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    wait_for_notes(client, network, account_id, count).await?;
+    let tx_id = consume_all_notes(client, account_id).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // The makers and the taker run clients of their own: the taker only learns about the orders
+    // through the network, never from the makers' store.
+    let maker_context = TutorialContext::new("order_book_example")?;
+    let maker_keystore = maker_context.keystore()?;
+    let mut makers = ClientBuilder::new()
+        .rpc(rpc_client.clone())
+        .sqlite_store(maker_context.store_path())
+        .authenticator(maker_keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let taker_context = TutorialContext::new("order_book_taker")?;
+    let taker_keystore = taker_context.keystore()?;
+    let mut taker = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(taker_context.store_path())
+        .authenticator(taker_keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = makers.sync_state().await?;
+    taker.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the makers, the taker and the tokens
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts, minting ETH to the makers and USD to Bob");
+
+    let alice = create_basic_account(&mut makers, &maker_keystore).await?;
+    let carol = create_basic_account(&mut makers, &maker_keystore).await?;
+    let bob = create_basic_account(&mut taker, &taker_keystore).await?;
+    println!(
+        "Alice (maker): {}",
+        alice.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Carol (maker): {}",
+        carol.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Bob (taker):   {}",
+        bob.id().to_bech32(network.network_id())
+    );
+
+    let eth = create_basic_faucet(
+        &mut makers,
+        &maker_keystore,
+        TokenSymbol::new("ETH").unwrap(),
+    )
+    .await?;
+    let usd = create_basic_faucet(
+        &mut makers,
+        &maker_keystore,
+        TokenSymbol::new("USD").unwrap(),
+    )
+    .await?;
+    for (account_id, faucet_id, amount) in [
+        (alice.id(), eth.id(), 2 * LOT_ETH),
+        (carol.id(), eth.id(), 2 * LOT_ETH),
+        (bob.id(), usd.id(), BUDGET_USD),
+    ] {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet_id, amount)?,
+            account_id,
+            NoteType::Public,
+            makers.rng(),
+        )?;
+        let tx_id = makers.submit_with_prover(faucet_id, mint_request).await?;
+        wait_for_tx(&mut makers, tx_id).await?;
+    }
+    collect(&mut makers, &network, alice.id(), 1).await?;
+    collect(&mut makers, &network, carol.id(), 1).await?;
+    collect(&mut taker, &network, bob.id(), 1).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: The makers post limit orders
+    // -------------------------------------------------------------------------
+    // Each order is a public SWAP note: anyone may consume it by paying the requested USD, which
+    // the note sends back to its maker.
+    println!("\n[STEP 2] Alice and Carol post four orders of {LOT_ETH} ETH");
+
+    for (maker, price) in [
+        (carol.id(), 1_000),
+        (alice.id(), 1_100),
+        (alice.id(), 1_050),
+        (carol.id(), 1_200),
+    ] {
+        let (note, _) = create_swap_note(
+            maker,
+            FungibleAsset::new(eth.id(), LOT_ETH)?.into(),
+            FungibleAsset::new(usd.id(), price)?.into(),
+            NoteType::Public,
+            Felt::new(0),
+            NoteType::Public,
+            Felt::new(0),
+            makers.rng(),
+        )?;
+        let request = TransactionRequestBuilder::new()
+            .own_output_notes(vec![OutputNote::Full(note)])
+            .build()?;
+        let tx_id = makers.submit_with_prover(maker, request).await?;
+        wait_for_tx(&mut makers, tx_id).await?;
+        println!("Posted {LOT_ETH} ETH for {price} USD");
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Bob discovers the book
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Bob follows the ETH/USD tag and builds the book");
+
+    let book = order_book::discover(&mut taker, eth.id(), usd.id()).await?;
+    for order in book.orders() {
+        println!(
+            "{} ETH for {} USD ({:.1} USD/ETH) from {}",
+            order.offered.amount(),
+            order.requested.amount(),
+            order.price(),
+            order.maker().to_bech32(network.network_id())
+        );
+    }
+    assert_eq!(book.orders().len(), 4);
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Bob fills the best orders the budget covers
+    // -------------------------------------------------------------------------
+    // Orders are taken from the cheapest on, and all of them go into a single transaction: Bob
+    // either gets every lot or none.
+    println!("\n[STEP 4] Bob spends up to {BUDGET_USD} USD");
+
+    let taken = book.take(BUDGET_USD);
+    let spent: u64 = taken.iter().map(|order| order.requested.amount()).sum();
+    let bought: u64 = taken.iter().map(|order| order.offered.amount()).sum();
+    assert_eq!(taken.len(), 2);
+    let tx_id = order_book::fill(&mut taker, bob.id(), &taken).await?;
+    wait_for_tx(&mut taker, tx_id).await?;
+    println!("Bob bought {bought} ETH for {spent} USD");
+
+    // Each filled order paid its maker back in a note of its own
+    collect(&mut makers, &network, carol.id(), 1).await?;
+    collect(&mut makers, &network, alice.id(), 1).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking balances and the book");
+
+    assert_eq!(balance(&taker, bob.id(), eth.id()).await?, bought);
+    assert_eq!(
+        balance(&taker, bob.id(), usd.id()).await?,
+        BUDGET_USD - spent
+    );
+    assert_eq!(balance(&makers, carol.id(), usd.id()).await?, 1_000);
+    assert_eq!(balance(&makers, alice.id(), usd.id()).await?, 1_050);
+
+    let book = order_book::discover(&mut taker, eth.id(), usd.id()).await?;
+    let left: Vec<u64> = book
+        .orders()
+        .iter()
+        .map(|order| order.requested.amount())
+        .collect();
+    assert_eq!(left, vec![1_100, 1_200]);
+    println!("Two orders are left in the book, from {} USD", left[0]);
+
+    Ok(())
+}
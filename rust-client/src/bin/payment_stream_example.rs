@@ -0,0 +1,232 @@
+use rand::rngs::StdRng;
+use std::{sync::Arc, time::Duration};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    payment_stream::{self, PaymentStream},
+};
+use tokio::time::sleep;
+
+/// Salary paid per block.
+const RATE: u64 = 10;
+/// Blocks the stream runs for unless cancelled.
+const DURATION_BLOCKS: u32 = 60;
+/// Blocks between two actions of the employee or the employer.
+const PAY_PERIOD_BLOCKS: u32 = 5;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+/// Reads the current state of the payment stream `stream_id`.
+async fn read_stream(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    stream_id: AccountId,
+) -> Result<PaymentStream, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(stream_id)
+        .await?
+        .ok_or("the payment stream is not tracked by the client")?;
+    PaymentStream::from_account(record.account())
+}
+
+/// Waits for a note to `account_id` and consumes it.
+async fn collect(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    account_id: AccountId,
+) -> Result<(), Box<dyn std::error::Error>> {
+    wait_for_notes(client, network, account_id, 1).await?;
+    let tx_id = consume_all_notes(client, account_id).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+/// Syncs the client until it reaches block `block_num`.
+async fn wait_for_block(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    block_num: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    while client.get_sync_height().await?.as_u32() < block_num {
+        sleep(Duration::from_secs(3)).await;
+        client.sync_state().await?;
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("payment_stream_example")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the employer, the employee and the token
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and assets");
+
+    let employer = create_basic_account(&mut client, &keystore).await?;
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Employer: {}",
+        employer.id().to_bech32(network.network_id())
+    );
+    println!("Alice:    {}", alice.id().to_bech32(network.network_id()));
+
+    let budget = RATE * u64::from(DURATION_BLOCKS);
+    let usd = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(usd.id(), budget)?,
+        employer.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(usd.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, employer.id()).await?;
+    println!("The employer holds {budget} USD");
+
+    let deposit_script =
+        payment_stream::compile_note_script(&client, payment_stream::DEPOSIT_NOTE_PATH)?;
+    let withdraw_script =
+        payment_stream::compile_note_script(&client, payment_stream::WITHDRAW_NOTE_PATH)?;
+    let cancel_script =
+        payment_stream::compile_note_script(&client, payment_stream::CANCEL_NOTE_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: The employer opens and funds the stream
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Streaming {RATE} USD per block to Alice");
+
+    let account = payment_stream::create_stream(
+        &mut client,
+        employer.id(),
+        alice.id(),
+        usd.id(),
+        RATE,
+        DURATION_BLOCKS,
+    )
+    .await?;
+    let stream = PaymentStream::from_account(&account)?;
+    println!(
+        "Stream {} runs from block {} to block {}",
+        stream.id, stream.start, stream.end
+    );
+
+    let tx_id =
+        payment_stream::deposit(&mut client, employer.id(), &stream, budget, deposit_script)
+            .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("The employer deposited {budget} USD");
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Alice pulls the first pay
+    // -------------------------------------------------------------------------
+    // The contract pays whatever accrued by the block of the transaction, which is the block
+    // the client last synced to.
+    let payday = stream.start.as_u32() + PAY_PERIOD_BLOCKS;
+    println!("\n[STEP 3] Alice withdraws at block {payday}");
+
+    wait_for_block(&mut client, payday).await?;
+    let stream = read_stream(&client, stream.id).await?;
+    let (tx_id, first_pay) =
+        payment_stream::withdraw(&mut client, &stream, withdraw_script.clone()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, alice.id()).await?;
+    println!("Alice withdrew {first_pay} USD");
+
+    // -------------------------------------------------------------------------
+    // STEP 4: The employer cancels the stream
+    // -------------------------------------------------------------------------
+    // Cancelling refunds what has yet to accrue. What Alice earned until then stays in the
+    // contract for Alice to withdraw.
+    let cancel_at = payday + PAY_PERIOD_BLOCKS;
+    println!("\n[STEP 4] The employer cancels the stream at block {cancel_at}");
+
+    wait_for_block(&mut client, cancel_at).await?;
+    let stream = read_stream(&client, stream.id).await?;
+    let (tx_id, refund) = payment_stream::cancel(&mut client, &stream, cancel_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, employer.id()).await?;
+    let stream = read_stream(&client, stream.id).await?;
+    println!(
+        "The employer got {refund} USD back, the stream ended at block {}",
+        stream.end
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Alice withdraws the rest of the pay
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Alice withdraws what accrued until the cancellation");
+
+    let (tx_id, last_pay) =
+        payment_stream::withdraw(&mut client, &stream, withdraw_script.clone()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, alice.id()).await?;
+    println!("Alice withdrew {last_pay} USD");
+
+    let stream = read_stream(&client, stream.id).await?;
+    let Err(err) = payment_stream::withdraw(&mut client, &stream, withdraw_script).await else {
+        return Err("a cancelled stream kept paying".into());
+    };
+    println!("Nothing more accrues: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Checking balances");
+
+    let earned = stream.accrued_at(stream.end);
+    assert_eq!(stream.withdrawn, earned);
+    assert_eq!(first_pay + last_pay, earned);
+    assert_eq!(earned + refund, budget);
+    assert_eq!(balance(&client, alice.id(), usd.id()).await?, earned);
+    assert_eq!(balance(&client, employer.id(), usd.id()).await?, refund);
+    assert_eq!(balance(&client, stream.id, usd.id()).await?, 0);
+    println!("Alice earned {earned} USD, the employer kept {refund} USD");
+
+    Ok(())
+}
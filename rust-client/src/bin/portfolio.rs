@@ -0,0 +1,171 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use miden_client::{
+    account::AccountId,
+    asset::Asset,
+    builder::ClientBuilder,
+    rpc::GrpcClient,
+    store::{NoteFilter, TransactionFilter},
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    context::TutorialContext,
+    devnet,
+    format::AssetFormatter,
+    known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH},
+};
+
+/// Number of most recent transactions shown in the report.
+const RECENT_TRANSACTIONS: usize = 10;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, kept across runs
+    let context = TutorialContext::persistent(devnet::STATE)?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client.clone())
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    let known_accounts = KnownAccounts::load(DEFAULT_REGISTRY_PATH)?;
+    let mut formatter = AssetFormatter::new(network.network_id());
+
+    // -------------------------------------------------------------------------
+    // Assets per account
+    // -------------------------------------------------------------------------
+    println!("\n=== Assets ===");
+    println!("{:<44} {:>24}", "ACCOUNT", "BALANCE");
+
+    let mut totals: BTreeMap<AccountId, u64> = BTreeMap::new();
+    let mut nft_count = 0;
+    let account_headers = client.get_account_headers().await?;
+
+    for (header, _) in &account_headers {
+        let Some(record) = client.get_account(header.id()).await? else {
+            continue;
+        };
+
+        let assets: Vec<Asset> = record.account().vault().assets().collect();
+        if assets.is_empty() {
+            println!(
+                "{:<44} {:>24}",
+                known_accounts.display(header.id(), network.network_id()),
+                "-"
+            );
+            continue;
+        }
+
+        for asset in assets {
+            match asset {
+                Asset::Fungible(fungible) => {
+                    formatter
+                        .load_token(rpc_client.as_ref(), fungible.faucet_id())
+                        .await?;
+                    *totals.entry(fungible.faucet_id()).or_default() += fungible.amount();
+                }
+                Asset::NonFungible(_) => nft_count += 1,
+            }
+            println!(
+                "{:<44} {:>24}",
+                known_accounts.display(header.id(), network.network_id()),
+                formatter.format(&asset)
+            );
+        }
+    }
+
+    println!("\n=== Totals per faucet ===");
+    for (faucet_id, amount) in &totals {
+        println!(
+            "{:<44} {:>24}",
+            known_accounts.display(*faucet_id, network.network_id()),
+            formatter.format_amount(*faucet_id, *amount)
+        );
+    }
+    if nft_count > 0 {
+        println!("{:<44} {:>24}", "NFTs", nft_count);
+    }
+
+    // -------------------------------------------------------------------------
+    // Pending incoming notes
+    // -------------------------------------------------------------------------
+    println!("\n=== Pending incoming notes ===");
+
+    let mut pending = 0;
+    for (header, _) in &account_headers {
+        let consumable_notes = client.get_consumable_notes(Some(header.id())).await?;
+        for (note, _) in consumable_notes {
+            pending += 1;
+            let mut contents = Vec::new();
+            for asset in note.assets().iter() {
+                if let Asset::Fungible(fungible) = asset {
+                    formatter
+                        .load_token(rpc_client.as_ref(), fungible.faucet_id())
+                        .await?;
+                }
+                contents.push(formatter.format(asset));
+            }
+            println!(
+                "{:<44} note {} [{}]",
+                known_accounts.display(header.id(), network.network_id()),
+                note.id().to_hex(),
+                contents.join(", ")
+            );
+        }
+    }
+
+    // Notes we expect but which are not yet committed on-chain
+    let expected_notes = client.get_input_notes(NoteFilter::Expected).await?;
+    for note in expected_notes {
+        pending += 1;
+        println!(
+            "{:<44} note {} (not yet committed)",
+            "-",
+            note.id().to_hex()
+        );
+    }
+
+    if pending == 0 {
+        println!("No pending notes");
+    }
+
+    // -------------------------------------------------------------------------
+    // Recent transactions
+    // -------------------------------------------------------------------------
+    println!("\n=== Recent transactions ===");
+
+    let mut transactions = client.get_transactions(TransactionFilter::All).await?;
+    transactions.sort_by_key(|tx| std::cmp::Reverse(tx.details.block_num));
+
+    for tx in transactions.iter().take(RECENT_TRANSACTIONS) {
+        println!(
+            "block {:>8}  {:<44} in: {:>2} out: {:>2}  {}  {}",
+            tx.details.block_num,
+            known_accounts.display(tx.details.account_id, network.network_id()),
+            tx.details.input_note_nullifiers.len(),
+            tx.details.output_notes.num_notes(),
+            tx.status,
+            tx.id.to_hex()
+        );
+    }
+    if transactions.is_empty() {
+        println!("No transactions");
+    }
+
+    Ok(())
+}
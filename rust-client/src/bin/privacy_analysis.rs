@@ -0,0 +1,262 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::{Account, AccountId},
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{create_p2id_note, Note, NoteDetails, NoteFile, NoteTag, NoteType},
+    rpc::{
+        domain::{account::FetchedAccount, note::FetchedNote},
+        GrpcClient, NodeRpcClient,
+    },
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, Felt,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::account::AccountStorageMode;
+use rust_client::{
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account_with_storage_mode, create_basic_faucet,
+        wait_for_notes, wait_for_tx,
+    },
+    privacy::{self, Field, Observation},
+};
+
+/// Amount paid in both workflows.
+const AMOUNT: u64 = 50;
+
+/// Mints 100 tokens of `faucet_id` to `account_id` and consumes them.
+async fn fund(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    faucet_id: AccountId,
+    account_id: AccountId,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet_id, 100)?,
+        account_id,
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet_id, request).await?;
+    wait_for_tx(client, tx_id).await?;
+    wait_for_notes(client, network, account_id, 1).await?;
+    let tx_id = consume_all_notes(client, account_id).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+/// Pays `AMOUNT` from `sender` to `receiver` with a P2ID note of `note_type`, and has the
+/// receiver consume it.
+async fn pay(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    faucet_id: AccountId,
+    sender: &Account,
+    receiver: &Account,
+    note_type: NoteType,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    let note = create_p2id_note(
+        sender.id(),
+        receiver.id(),
+        vec![FungibleAsset::new(faucet_id, AMOUNT)?.into()],
+        note_type,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let sent_at = client.get_sync_height().await?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    let tx_id = client.submit_with_prover(sender.id(), request).await?;
+    wait_for_tx(client, tx_id).await?;
+
+    // A private note has to reach its receiver off chain, here by importing its details
+    if note_type == NoteType::Private {
+        let details: NoteDetails = note.clone().into();
+        client
+            .import_note(NoteFile::NoteDetails {
+                details,
+                after_block_num: sent_at,
+                tag: Some(NoteTag::from_account_id(receiver.id())),
+            })
+            .await?;
+    }
+    wait_for_notes(client, network, receiver.id(), 1).await?;
+    let tx_id = consume_all_notes(client, receiver.id()).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(note)
+}
+
+/// Queries the node the way an outside observer would, knowing the note id and the account
+/// ids, and records which fields of the payment it could read back.
+async fn observe(
+    observer: &dyn NodeRpcClient,
+    note: &Note,
+    sender: AccountId,
+    receiver: AccountId,
+    faucet_id: AccountId,
+) -> Result<Observation, Box<dyn std::error::Error>> {
+    let mut observation = Observation::default();
+
+    let fetched = observer
+        .get_notes_by_id(&[note.id()])
+        .await?
+        .pop()
+        .ok_or("the node does not know the note")?;
+    // The metadata is published for every note, private ones included
+    observation.record(Field::Sender, fetched.metadata().sender() == sender);
+    observation.record(
+        Field::Tag,
+        fetched.metadata().tag() == NoteTag::from_account_id(receiver),
+    );
+    match fetched {
+        FetchedNote::Public(public_note, _) => {
+            let amount = public_note
+                .assets()
+                .iter()
+                .map(|asset| asset.unwrap_fungible())
+                .filter(|asset| asset.faucet_id() == faucet_id)
+                .map(|asset| asset.amount())
+                .sum::<u64>();
+            observation.record(Field::Assets, amount == AMOUNT);
+
+            let inputs = public_note.inputs().values();
+            let target = AccountId::try_from([inputs[1], inputs[0]])?;
+            observation.record(Field::Receiver, target == receiver);
+
+            // Knowing the whole note, the observer computes its nullifier and sees it published
+            let consumed_at = observer
+                .get_nullifier_commit_height(&public_note.nullifier(), 0.into())
+                .await?;
+            observation.record(Field::Consumption, consumed_at.is_some());
+        }
+        // Only the id and the metadata: assets, inputs and serial number stay with the parties
+        FetchedNote::Private(..) => {}
+    }
+
+    for (field, account_id) in [
+        (Field::SenderBalance, sender),
+        (Field::ReceiverBalance, receiver),
+    ] {
+        let visible = match observer.get_account_details(account_id).await? {
+            FetchedAccount::Public(account, _) => account.vault().get_balance(faucet_id).is_ok(),
+            FetchedAccount::Private(..) => false,
+        };
+        observation.record(field, visible);
+    }
+
+    Ok(observation)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("privacy_analysis")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create a public and a private pair of accounts
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and funding the senders");
+
+    let alice =
+        create_basic_account_with_storage_mode(&mut client, &keystore, AccountStorageMode::Public)
+            .await?;
+    let bob =
+        create_basic_account_with_storage_mode(&mut client, &keystore, AccountStorageMode::Public)
+            .await?;
+    let carol =
+        create_basic_account_with_storage_mode(&mut client, &keystore, AccountStorageMode::Private)
+            .await?;
+    let dave =
+        create_basic_account_with_storage_mode(&mut client, &keystore, AccountStorageMode::Private)
+            .await?;
+
+    let usd = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("USD").unwrap()).await?;
+    fund(&mut client, &network, usd.id(), alice.id()).await?;
+    fund(&mut client, &network, usd.id(), carol.id()).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Make the same payment in both workflows
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Paying {AMOUNT} USD publicly and privately");
+
+    let public_note = pay(
+        &mut client,
+        &network,
+        usd.id(),
+        &alice,
+        &bob,
+        NoteType::Public,
+    )
+    .await?;
+    println!("Public payment:  note {}", public_note.id());
+    let private_note = pay(
+        &mut client,
+        &network,
+        usd.id(),
+        &carol,
+        &dave,
+        NoteType::Private,
+    )
+    .await?;
+    println!("Private payment: note {}", private_note.id());
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Observe both payments from the outside
+    // -------------------------------------------------------------------------
+    // The observer has a connection of its own and no store: everything it learns comes from
+    // the node. It is handed the ids, the worst case for the parties.
+    println!("\n[STEP 3] Querying the node as an outside observer");
+
+    let observer = GrpcClient::new(&endpoint, timeout_ms);
+    let public = observe(&observer, &public_note, alice.id(), bob.id(), usd.id()).await?;
+    let private = observe(&observer, &private_note, carol.id(), dave.id(), usd.id()).await?;
+
+    println!("\n{}", privacy::comparison_table(&public, &private));
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Verify the privacy model
+    // -------------------------------------------------------------------------
+    println!("[STEP 4] Checking what each workflow revealed");
+
+    assert_eq!(public.visible(), Field::ALL.to_vec());
+    // The metadata of a private note is still public: who sent it, and the tag derived from
+    // the receiver id
+    assert_eq!(private.visible(), vec![Field::Sender, Field::Tag]);
+    let hidden = privacy::hidden_fields(&public, &private);
+    println!(
+        "Going private hid: {}",
+        hidden
+            .iter()
+            .map(|field| field.label())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,318 @@
+//! Private payment delivered off-band, in two terminals.
+//!
+//! Private notes only publish their commitment on-chain, so the sender has to hand the note to
+//! the receiver through some other channel. Here the channel is a file:
+//!
+//! ```text
+//! # terminal 2: the receiver creates a wallet in its own store
+//! cargo run --release --features guides --bin private_note_offband -- receiver-setup
+//! # terminal 1: the sender mints a private note to that wallet and exports it with its proof
+//! cargo run --release --features guides --bin private_note_offband -- send <RECEIVER_ID>
+//! # terminal 2: the receiver verifies the inclusion proof and consumes the note
+//! cargo run --release --features guides --bin private_note_offband -- receive
+//! # terminal 1: the sender detects the consumption through the note's nullifier
+//! cargo run --release --features guides --bin private_note_offband -- check
+//! ```
+//!
+//! The sender and the receiver keep their store and keystore apart, in the persistent contexts
+//! `private_note_offband/sender` and `private_note_offband/receiver`, and the exported note in
+//! `private_note_offband`. The steps span several runs, so they need the testnet: with `--local`
+//! every run starts a node of its own.
+
+use rand::rngs::StdRng;
+use std::{path::PathBuf, sync::Arc};
+use tokio::time::Duration;
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{NoteFile, NoteType},
+    rpc::{GrpcClient, NodeRpcClient},
+    store::{NoteFilter, OutputNoteState},
+    transaction::TransactionRequestBuilder,
+    utils::{Deserializable, Serializable},
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::account::AccountStorageMode;
+use rust_client::{
+    address::parse_wallet_address,
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{create_basic_account_with_storage_mode, create_basic_faucet},
+};
+
+/// Context holding the note handed from the sender to the receiver.
+const STATE: &str = "private_note_offband";
+const SENDER_STATE: &str = "private_note_offband/sender";
+const RECEIVER_STATE: &str = "private_note_offband/receiver";
+/// The note and its inclusion proof, as handed from the sender to the receiver.
+const NOTE_FILE_NAME: &str = "note.bin";
+
+const USAGE: &str =
+    "usage: private_note_offband <receiver-setup | send <RECEIVER_ID> | receive | check>";
+
+/// A party of the exchange: its client, and the context holding its store and keystore.
+struct Party {
+    client: Client<FilesystemKeyStore<StdRng>>,
+    keystore: Arc<FilesystemKeyStore<StdRng>>,
+    /// Keeps the directory of the context alive while the client uses it.
+    _context: TutorialContext,
+}
+
+/// Builds the client of the party whose store and keystore live in the context `state`, so that
+/// sender and receiver never share state.
+async fn party(network: &Network, state: &str) -> Result<Party, Box<dyn std::error::Error>> {
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(network.endpoint(), timeout_ms));
+
+    let context = TutorialContext::persistent(state)?;
+    let keystore = context.keystore()?;
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(context.store_path())
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    Ok(Party {
+        client,
+        keystore,
+        _context: context,
+    })
+}
+
+/// Returns the path of the file the note is handed over in, in the shared context of the
+/// exchange.
+fn note_file_path(shared: &TutorialContext) -> PathBuf {
+    shared.dir().join(NOTE_FILE_NAME)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = rust_client::cli::positional_args();
+    let command = args.first().map(String::as_str).unwrap_or_default();
+    if !matches!(command, "receiver-setup" | "send" | "receive" | "check") {
+        println!("{USAGE}");
+        return Ok(());
+    }
+
+    let network = rust_client::cli::network().await?;
+    let shared = TutorialContext::persistent(STATE)?;
+    match command {
+        "receiver-setup" => receiver_setup(&network).await,
+        "send" => match args.get(1) {
+            Some(receiver) => {
+                let receiver = parse_wallet_address(receiver, network.network_id())?;
+                send(&network, &shared, receiver).await
+            }
+            None => {
+                println!("{USAGE}");
+                Ok(())
+            }
+        },
+        "receive" => receive(&network, &shared).await,
+        _ => check(&network, &shared).await,
+    }
+}
+
+/// Receiver: creates the wallet the private note will be addressed to.
+async fn receiver_setup(network: &Network) -> Result<(), Box<dyn std::error::Error>> {
+    let Party {
+        mut client,
+        keystore,
+        _context,
+    } = party(network, RECEIVER_STATE).await?;
+
+    let receiver =
+        create_basic_account_with_storage_mode(&mut client, &keystore, AccountStorageMode::Private)
+            .await?;
+
+    println!(
+        "Receiver wallet: {}",
+        receiver.id().to_bech32(network.network_id())
+    );
+    println!("Pass it to `private_note_offband send` in the sender's terminal");
+
+    Ok(())
+}
+
+/// Sender: mints a private note to `receiver` and exports it together with its inclusion proof.
+async fn send(
+    network: &Network,
+    shared: &TutorialContext,
+    receiver: AccountId,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Party {
+        mut client,
+        keystore,
+        _context,
+    } = party(network, SENDER_STATE).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Deploy a faucet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Deploying a faucet");
+
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("PRV").unwrap()).await?;
+    println!("Faucet: {}", faucet.id().to_bech32(network.network_id()));
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Mint a private note to the receiver
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Minting a private note of 100 tokens to the receiver");
+
+    let asset = FungibleAsset::new(faucet.id(), 100).unwrap();
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        asset,
+        receiver,
+        NoteType::Private,
+        client.rng(),
+    )?;
+    let note = mint_request
+        .expected_output_own_notes()
+        .pop()
+        .expect("minting creates one note");
+
+    let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+    println!("Mint tx: https://testnet.midenscan.com/tx/{:?}", tx_id);
+    println!("Only the commitment of note {} goes on-chain", note.id());
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Wait for the inclusion proof and export the note
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Waiting for the note to be included in a block");
+
+    let inclusion_proof = loop {
+        client.sync_state().await?;
+        let record = client.get_output_note(note.id()).await?;
+        if let Some(proof) = record.as_ref().and_then(|record| record.inclusion_proof()) {
+            break proof.clone();
+        }
+        tokio::time::sleep(Duration::from_secs(3)).await;
+    };
+    println!(
+        "Note included in block {}",
+        inclusion_proof.location().block_num()
+    );
+
+    let note_file = NoteFile::NoteWithProof(note, inclusion_proof);
+    let note_file_path = note_file_path(shared);
+    std::fs::write(&note_file_path, note_file.to_bytes())?;
+    println!("Exported note and proof to {}", note_file_path.display());
+    println!("Hand this file to the receiver, then run `private_note_offband receive` there");
+
+    Ok(())
+}
+
+/// Receiver: verifies the exported note against the chain, imports it and consumes it.
+async fn receive(
+    network: &Network,
+    shared: &TutorialContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Party {
+        mut client,
+        _context,
+        ..
+    } = party(network, RECEIVER_STATE).await?;
+
+    let NoteFile::NoteWithProof(note, inclusion_proof) =
+        NoteFile::read_from_bytes(&std::fs::read(note_file_path(shared))?)?
+    else {
+        return Err("expected a note with its inclusion proof".into());
+    };
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Verify the inclusion proof independently
+    // -------------------------------------------------------------------------
+    // The file comes from the sender, so don't trust it: fetch the header of the block the note
+    // claims to be in straight from the node and check the note's Merkle path against its note
+    // root.
+    println!("\n[STEP 1] Verifying the note's inclusion proof");
+
+    let block_num = inclusion_proof.location().block_num();
+    let rpc_client = GrpcClient::new(network.endpoint(), 10_000);
+    let (block_header, _) = rpc_client
+        .get_block_header_by_number(Some(block_num), false)
+        .await?;
+
+    let note_index = u64::from(inclusion_proof.location().node_index_in_block());
+    inclusion_proof
+        .note_path()
+        .verify(note_index, note.commitment(), &block_header.note_root())
+        .map_err(|err| format!("note {} is not in block {block_num}: {err}", note.id()))?;
+    println!(
+        "Note {} is committed in block {block_num} (note root {})",
+        note.id(),
+        block_header.note_root()
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Import and consume the note
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Importing and consuming the note");
+
+    let receiver = client
+        .get_account_headers()
+        .await?
+        .first()
+        .map(|(header, _)| header.id())
+        .ok_or("no receiver wallet, run `private_note_offband receiver-setup` first")?;
+
+    let note_id = client
+        .import_note(NoteFile::NoteWithProof(note, inclusion_proof))
+        .await?;
+
+    let consume_request = TransactionRequestBuilder::new().build_consume_notes(vec![note_id])?;
+    let tx_id = client.submit_with_prover(receiver, consume_request).await?;
+    println!("Consume tx: https://testnet.midenscan.com/tx/{:?}", tx_id);
+    println!("Run `private_note_offband check` in the sender's terminal");
+
+    Ok(())
+}
+
+/// Sender: detects that the receiver consumed the note by watching its nullifier.
+async fn check(
+    network: &Network,
+    shared: &TutorialContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Party {
+        mut client,
+        _context,
+        ..
+    } = party(network, SENDER_STATE).await?;
+
+    let note_file = NoteFile::read_from_bytes(&std::fs::read(note_file_path(shared))?)?;
+    let NoteFile::NoteWithProof(note, _) = note_file else {
+        return Err("expected a note with its inclusion proof".into());
+    };
+
+    // The nullifier is derived from the note's secret serial number, so only the sender and the
+    // receiver can link it to the note. Consuming the note publishes the nullifier.
+    let nullifier = note.nullifier();
+    println!("\nWatching nullifier {nullifier} of note {}", note.id());
+
+    loop {
+        client.sync_state().await?;
+        let records = client
+            .get_output_notes(NoteFilter::Nullifiers(vec![nullifier]))
+            .await?;
+        if records
+            .iter()
+            .any(|record| matches!(record.state(), OutputNoteState::Consumed { .. }))
+        {
+            println!("The receiver consumed the note: its nullifier is on-chain");
+            return Ok(());
+        }
+        println!("Nullifier not published yet. Waiting...");
+        tokio::time::sleep(Duration::from_secs(3)).await;
+    }
+}
@@ -0,0 +1,212 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{create_p2ide_note, Note, NoteType},
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequest, TransactionRequestBuilder},
+    Client, Felt,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::block::BlockNumber;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+};
+
+/// Tokens minted to Alice.
+const FUNDS: u64 = 100;
+/// Tokens Bob collects in time.
+const COLLECTED: u64 = 30;
+/// Tokens Bob leaves for Alice to reclaim.
+const RECLAIMED: u64 = 20;
+/// Blocks Bob has to consume the note Alice reclaims.
+const RECLAIM_OFFSET: u32 = 5;
+/// Blocks Bob has to consume the note he collects, far more than he needs.
+const COLLECT_OFFSET: u32 = 100;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+/// Builds a request consuming `note`.
+fn consume_request(note: &Note) -> TransactionRequest {
+    TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note.clone(), None)])
+        .build()
+        .unwrap()
+}
+
+/// Has `sender` send `asset` to `target` in a note it can reclaim from `reclaim_height`.
+async fn send_recallable(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    target: AccountId,
+    asset: FungibleAsset,
+    reclaim_height: BlockNumber,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    let note = create_p2ide_note(
+        sender,
+        target,
+        vec![asset.into()],
+        Some(reclaim_height),
+        None,
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    let tx_id = client.submit_with_prover(sender, request).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(note)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("recallable_p2id")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create Alice and Bob, and fund Alice
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice and Bob, minting {FUNDS} tokens to Alice");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), FUNDS)?,
+        alice.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Bob consumes a recallable note before the reclaim height
+    // -------------------------------------------------------------------------
+    // A P2IDE note is a P2ID note with a reclaim height: until then only the target can
+    // consume it, from then on the sender can take it back as long as the target has not.
+    let tip = client.sync_state().await?.block_num.as_u32();
+    let reclaim_height = BlockNumber::from(tip + COLLECT_OFFSET);
+    println!(
+        "\n[STEP 2] Alice sends Bob {COLLECTED} tokens, reclaimable from block {reclaim_height}"
+    );
+
+    let collected = send_recallable(
+        &mut client,
+        alice.id(),
+        bob.id(),
+        FungibleAsset::new(faucet.id(), COLLECTED)?,
+        reclaim_height,
+    )
+    .await?;
+
+    let Err(err) = client
+        .execute_transaction(alice.id(), consume_request(&collected))
+        .await
+    else {
+        return Err("Alice reclaimed the note before the reclaim height".into());
+    };
+    println!("Alice reclaiming early is rejected: {err}");
+
+    let tx_id = client
+        .submit_with_prover(bob.id(), consume_request(&collected))
+        .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Bob consumed the note");
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Alice reclaims a note Bob left alone
+    // -------------------------------------------------------------------------
+    let tip = client.sync_state().await?.block_num.as_u32();
+    let reclaim_height = BlockNumber::from(tip + RECLAIM_OFFSET);
+    println!(
+        "\n[STEP 3] Alice sends Bob {RECLAIMED} tokens, reclaimable from block {reclaim_height}"
+    );
+
+    let reclaimed = send_recallable(
+        &mut client,
+        alice.id(),
+        bob.id(),
+        FungibleAsset::new(faucet.id(), RECLAIMED)?,
+        reclaim_height,
+    )
+    .await?;
+
+    loop {
+        let block_num = client.sync_state().await?.block_num;
+        if block_num >= reclaim_height {
+            break;
+        }
+        println!("Block {block_num}, waiting for block {reclaim_height}...");
+        sleep(Duration::from_secs(3)).await;
+    }
+
+    let tx_id = client
+        .submit_with_prover(alice.id(), consume_request(&reclaimed))
+        .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Alice reclaimed the note");
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Checking balances");
+
+    let alice_funds = balance(&client, alice.id(), faucet.id()).await?;
+    let bob_funds = balance(&client, bob.id(), faucet.id()).await?;
+    println!("Alice: {alice_funds}, Bob: {bob_funds}");
+
+    assert_eq!(alice_funds, FUNDS - COLLECTED);
+    assert_eq!(bob_funds, COLLECTED);
+
+    Ok(())
+}
@@ -0,0 +1,331 @@
+use miden_lib::account::auth::NoAuth;
+use miden_lib::note::WellKnownNote;
+use miden_lib::transaction::TransactionKernel;
+use rand::RngCore;
+use std::{fs, path::Path, sync::Arc};
+use tokio::time::{sleep, Duration};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Felt, Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
+    ZERO,
+};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, create_library,
+        wait_for_notes,
+    },
+};
+
+/// Minimum deposit required to interact with the contract.
+const MIN_DEPOSIT: u64 = 5;
+
+/// Builds an interaction note for the deposit counter together with the P2ID note the
+/// contract creates when it refunds the deposit.
+fn interaction_note(
+    sender: AccountId,
+    contract_id: AccountId,
+    script: NoteScript,
+    increment_by: u64,
+    deposit: FungibleAsset,
+    serial_num: Word,
+) -> Result<(Note, Note), Box<dyn std::error::Error>> {
+    let refund_tag = NoteTag::from_account_id(sender);
+
+    let inputs = NoteInputs::new(vec![Felt::new(increment_by), refund_tag.into()])?;
+    let recipient = NoteRecipient::new(serial_num, script, inputs);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(contract_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    let note = Note::new(NoteAssets::new(vec![deposit.into()])?, metadata, recipient);
+
+    // The note script derives the refund serial number by incrementing its own serial number
+    let refund_serial_num = [
+        serial_num[0],
+        serial_num[1],
+        serial_num[2],
+        Felt::new(serial_num[3].as_int() + 1),
+    ]
+    .into();
+    let refund_recipient = NoteRecipient::new(
+        refund_serial_num,
+        WellKnownNote::P2ID.script(),
+        NoteInputs::new(vec![sender.suffix(), sender.prefix().as_felt()])?,
+    );
+    let refund_metadata = NoteMetadata::new(
+        contract_id,
+        NoteType::Public,
+        refund_tag,
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    let refund_note = Note::new(
+        NoteAssets::new(vec![deposit.into()])?,
+        refund_metadata,
+        refund_recipient,
+    );
+
+    Ok((note, refund_note))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("refundable_deposit")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create Alice, Mallory and the deposit faucet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and funding them");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice's account ID: {:?}",
+        alice.id().to_bech32(network.network_id())
+    );
+    let mallory = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Mallory's account ID: {:?}",
+        mallory.id().to_bech32(network.network_id())
+    );
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    println!(
+        "Faucet account ID: {:?}",
+        faucet.id().to_bech32(network.network_id())
+    );
+
+    let starting_balance: u64 = 10;
+    for account_id in [alice.id(), mallory.id()] {
+        let mint_request = TransactionRequestBuilder::new()
+            .build_mint_fungible_asset(
+                FungibleAsset::new(faucet.id(), starting_balance).unwrap(),
+                account_id,
+                NoteType::Public,
+                client.rng(),
+            )
+            .unwrap();
+        client.submit_with_prover(faucet.id(), mint_request).await?;
+    }
+    for account_id in [alice.id(), mallory.id()] {
+        wait_for_notes(&mut client, &network, account_id, 1).await?;
+        consume_all_notes(&mut client, account_id).await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy the counter guarded by deposits
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Deploying the deposit-guarded counter contract");
+
+    let contract_code =
+        fs::read_to_string(Path::new("../masm/accounts/deposit_counter.masm")).unwrap();
+
+    let deposit_config: Word = [
+        Felt::new(MIN_DEPOSIT),
+        ZERO,
+        faucet.id().suffix(),
+        faucet.id().prefix().as_felt(),
+    ]
+    .into();
+
+    let contract_component = AccountComponent::compile(
+        &contract_code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(deposit_config),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )
+    .unwrap()
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let contract = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(contract_component)
+        .build()
+        .unwrap();
+
+    client.add_account(&contract, false).await?;
+    println!(
+        "Contract account ID: {:?}",
+        contract.id().to_bech32(network.network_id())
+    );
+
+    let library = create_library(&contract_code, "external_contract::deposit_counter").unwrap();
+    let note_code =
+        fs::read_to_string(Path::new("../masm/notes/deposit_increment_note.masm")).unwrap();
+    let note_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?;
+
+    let deposit = FungibleAsset::new(faucet.id(), MIN_DEPOSIT).unwrap();
+
+    // -------------------------------------------------------------------------
+    // STEP 3: A deposit below the minimum is rejected outright
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Interacting with a deposit below the minimum fails");
+
+    let (cheap_note, _) = interaction_note(
+        alice.id(),
+        contract.id(),
+        note_script.clone(),
+        1,
+        FungibleAsset::new(faucet.id(), 1).unwrap(),
+        client.rng().draw_word(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(cheap_note, None)])
+        .build()?;
+    let result = client.execute_transaction(contract.id(), request).await;
+    assert!(result.is_err());
+    println!("Rejected: {}", result.unwrap_err());
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Alice follows the rules and gets her deposit back
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Alice increments by 1 and is refunded");
+
+    let (alice_note, alice_refund) = interaction_note(
+        alice.id(),
+        contract.id(),
+        note_script.clone(),
+        1,
+        deposit,
+        client.rng().draw_word(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(alice_note.clone())])
+        .build()?;
+    client.submit_with_prover(alice.id(), request).await?;
+
+    // The contract operator consumes the interaction note
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(alice_note, None)])
+        .expected_output_recipients(vec![alice_refund.recipient().clone()])
+        .build()?;
+    let tx_id = client.submit_with_prover(contract.id(), request).await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Mallory breaks the rules and loses her deposit
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Mallory tries to increment by 1000 and loses the deposit");
+
+    let (mallory_note, _) = interaction_note(
+        mallory.id(),
+        contract.id(),
+        note_script,
+        1000,
+        deposit,
+        client.rng().draw_word(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(mallory_note.clone())])
+        .build()?;
+    client.submit_with_prover(mallory.id(), request).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(mallory_note, None)])
+        .build()?;
+    let tx_id = client.submit_with_prover(contract.id(), request).await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    sleep(Duration::from_secs(5)).await;
+    client.sync_state().await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Checking balances and contract state");
+
+    let alice_balance = client
+        .get_account(alice.id())
+        .await?
+        .unwrap()
+        .account()
+        .vault()
+        .get_balance(faucet.id())?;
+    let mallory_balance = client
+        .get_account(mallory.id())
+        .await?
+        .unwrap()
+        .account()
+        .vault()
+        .get_balance(faucet.id())?;
+
+    let contract_record = client.get_account(contract.id()).await?.unwrap();
+    let count = contract_record.account().storage().get_item(0)?[3].as_int();
+    let kept = contract_record.account().storage().get_item(3)?[3].as_int();
+    let contract_balance = contract_record.account().vault().get_balance(faucet.id())?;
+
+    println!("Counter value: {}", count);
+    println!("Alice balance: {}", alice_balance);
+    println!("Mallory balance: {}", mallory_balance);
+    println!("Deposits kept by the contract: {}", kept);
+
+    assert_eq!(count, 1);
+    assert_eq!(alice_balance, starting_balance);
+    assert_eq!(mallory_balance, starting_balance - MIN_DEPOSIT);
+    assert_eq!(kept, MIN_DEPOSIT);
+    assert_eq!(contract_balance, MIN_DEPOSIT);
+
+    println!("\nValid interactions are free, spam costs the deposit!");
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use rand::rngs::StdRng;
+use std::{collections::BTreeSet, sync::Arc};
+use tokio::time::Duration;
+
+use miden_client::{
+    account::AccountId,
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{NoteId, NoteTag},
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::block::BlockNumber;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    daemon::{Daemon, Service},
+    known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH},
+};
+
+/// How often the relayer syncs with the node.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Consumes the notes of a contract on its behalf.
+struct Relayer {
+    client: Client<FilesystemKeyStore<StdRng>>,
+    contract_id: AccountId,
+    /// Notes whose consumption failed, e.g. because their script rejects the contract. They
+    /// are not retried, so a single bad note cannot stall the relayer.
+    rejected: BTreeSet<NoteId>,
+}
+
+impl Service for Relayer {
+    async fn tick(
+        &mut self,
+        _last_processed: Option<BlockNumber>,
+    ) -> Result<BlockNumber, Box<dyn std::error::Error>> {
+        // The store remembers which notes were consumed, so resuming needs no extra state
+        let sync_summary = self.client.sync_state().await?;
+
+        let consumable_notes = self
+            .client
+            .get_consumable_notes(Some(self.contract_id))
+            .await?;
+        let note_ids: Vec<_> = consumable_notes
+            .iter()
+            .map(|(note, _)| note.id())
+            .filter(|note_id| !self.rejected.contains(note_id))
+            .collect();
+
+        // One note per transaction: a failing note only fails its own transaction
+        for note_id in note_ids {
+            let consume_request =
+                TransactionRequestBuilder::new().build_consume_notes(vec![note_id])?;
+            match self
+                .client
+                .submit_with_prover(self.contract_id, consume_request)
+                .await
+            {
+                Ok(tx_id) => println!(
+                    "Block {}: consumed note {}. TX: https://testnet.midenscan.com/tx/{:?}",
+                    sync_summary.block_num,
+                    note_id.to_hex(),
+                    tx_id
+                ),
+                Err(err) => {
+                    println!("Could not consume note {}: {err}", note_id.to_hex());
+                    self.rejected.insert(note_id);
+                }
+            }
+        }
+
+        Ok(sync_summary.block_num)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // The contract to relay for, as a bech32 id or a label from the known accounts registry
+    let target = rust_client::cli::positional_args()
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| "counter".to_string());
+    let network = rust_client::cli::network().await?;
+    let contract_id =
+        KnownAccounts::load(DEFAULT_REGISTRY_PATH)?.resolve(&target, network.network_id())?;
+
+    // Initialize client
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("relayer")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore)
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Track the contract and the notes tagged to it
+    // -------------------------------------------------------------------------
+    // Network accounts have their notes consumed by the operator. A contract deployed in local
+    // execution mode has no such service: somebody has to execute its transactions. As long as
+    // the contract doesn't require a signature (e.g. `NoAuth`), any client tracking it can.
+    println!(
+        "\n[STEP 1] Relaying notes for {}",
+        contract_id.to_bech32(network.network_id())
+    );
+
+    if client.get_account(contract_id).await?.is_none() {
+        client.import_account_by_id(contract_id).await?;
+    }
+
+    // Public notes are only synced when their tag is tracked. Senders tag notes for a contract
+    // with `NoteTag::from_account_id`.
+    let tag = NoteTag::from_account_id(contract_id);
+    client.add_note_tag(tag).await?;
+    println!("Tracking note tag {}", u32::from(tag));
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Consume notes as they arrive
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Waiting for notes. Press Ctrl+C to stop.");
+
+    let mut relayer = Relayer {
+        client,
+        contract_id,
+        rejected: BTreeSet::new(),
+    };
+    Daemon::new(format!("relayer_{}", contract_id.to_hex()))
+        .with_poll_interval(POLL_INTERVAL)
+        .run(&mut relayer)
+        .await
+}
@@ -0,0 +1,144 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId, builder::ClientBuilder, keystore::FilesystemKeyStore, rpc::GrpcClient,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    context::TutorialContext,
+    helpers::{create_basic_account, wait_for_tx},
+    rock_paper_scissors::{self, Game, Hand, HiddenHand, Outcome},
+};
+
+/// Reads the current state of the game `game_id`.
+async fn read_game(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    game_id: AccountId,
+) -> Result<Game, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(game_id)
+        .await?
+        .ok_or("the game is not tracked by the client")?;
+    Game::from_account(record.account())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("rock_paper_scissors")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the players and deploy the game
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice, Bob and their game");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+
+    let account = rock_paper_scissors::create_game(&mut client, alice.id(), bob.id()).await?;
+    let game = Game::from_account(&account)?;
+    println!("Game: {}", game.id);
+
+    let commit_script =
+        rock_paper_scissors::compile_note_script(&client, rock_paper_scissors::COMMIT_NOTE_PATH)?;
+    let reveal_script =
+        rock_paper_scissors::compile_note_script(&client, rock_paper_scissors::REVEAL_NOTE_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Both players commit to their hand
+    // -------------------------------------------------------------------------
+    // A commitment is the hash of the hand and a random salt: without the salt, anybody could
+    // hash the three hands and compare.
+    println!("\n[STEP 2] Alice and Bob commit to their hand");
+
+    let alice_hand = HiddenHand::new(Hand::Rock, client.rng());
+    let bob_hand = HiddenHand::new(Hand::Scissors, client.rng());
+    for (player, hand) in [(alice.id(), &alice_hand), (bob.id(), &bob_hand)] {
+        let tx_id =
+            rock_paper_scissors::commit(&mut client, player, &game, hand, commit_script.clone())
+                .await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        println!("{player} committed to {}", hand.commitment().to_hex());
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Both players reveal their hand
+    // -------------------------------------------------------------------------
+    // Alice reveals first, so Bob knows Alice's hand before revealing. Switching to the hand
+    // which beats it does not help: the game only accepts the hand Bob committed to.
+    println!("\n[STEP 3] Alice and Bob reveal their hand");
+
+    let game = read_game(&client, game.id).await?;
+    let tx_id = rock_paper_scissors::reveal(
+        &mut client,
+        alice.id(),
+        &game,
+        &alice_hand,
+        reveal_script.clone(),
+    )
+    .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Alice revealed {:?}", alice_hand.hand);
+
+    let switched = HiddenHand {
+        hand: Hand::Paper,
+        ..bob_hand
+    };
+    let Err(err) = rock_paper_scissors::reveal(
+        &mut client,
+        bob.id(),
+        &game,
+        &switched,
+        reveal_script.clone(),
+    )
+    .await
+    else {
+        return Err("the game accepted a hand not matching its commitment".into());
+    };
+    println!("Bob cannot switch to paper: {err}");
+
+    let tx_id =
+        rock_paper_scissors::reveal(&mut client, bob.id(), &game, &bob_hand, reveal_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Bob revealed {:?}", bob_hand.hand);
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Checking the winner");
+
+    let game = read_game(&client, game.id).await?;
+    assert_eq!(game.hands, [Some(alice_hand.hand), Some(bob_hand.hand)]);
+    assert!(alice_hand.hand.beats(bob_hand.hand));
+    assert_eq!(game.outcome, Outcome::Winner(alice.id()));
+    println!(
+        "{:?} beats {:?}: Alice wins",
+        alice_hand.hand, bob_hand.hand
+    );
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use std::fs;
+
+use rust_client::checked_math::{self, Operation, MAX_AMOUNT};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Everything runs in a local VM: no node is needed
+    let source = fs::read_to_string(checked_math::LIBRARY_FILE_PATH)?;
+    let library = checked_math::library(&source)?;
+
+    let pitfalls = [
+        ("Withdraw 25 from a balance of 10", Operation::Sub, 10, 25),
+        (
+            "Deposit the maximum amount twice",
+            Operation::Add,
+            MAX_AMOUNT,
+            MAX_AMOUNT,
+        ),
+        (
+            "Buy 2^32 units at a price of 2^32",
+            Operation::Mul,
+            1 << 32,
+            1 << 32,
+        ),
+    ];
+
+    for (step, (description, operation, a, b)) in pitfalls.into_iter().enumerate() {
+        println!("\n[STEP {}] {description}", step + 1);
+
+        // The bare instruction computes in the field and wraps around without an error
+        let wrapped = checked_math::unchecked(operation, a, b)?;
+        assert_eq!(wrapped, operation.in_field(a, b));
+        println!("`{}` returns {wrapped}", operation.name());
+
+        // The checked procedure rejects a result which is not an amount
+        let Err(err) = checked_math::checked(&library, operation, a, b) else {
+            return Err(format!("checked `{}` accepted {a} and {b}", operation.name()).into());
+        };
+        assert!(format!("{err:?}").contains(operation.error()));
+        println!(
+            "`checked_math::{}` fails: {}",
+            operation.name(),
+            operation.error()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,282 @@
+use std::{fs, path::Path, sync::Arc};
+
+use miden_client::account::component::BasicWallet;
+use miden_client::{
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteTag,
+        NoteType,
+    },
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Felt, Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_lib::account::auth::{self, AuthRpoFalcon512};
+use miden_lib::transaction::TransactionKernel;
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    assembly::Assembler,
+    block::BlockNumber,
+};
+use rand::RngCore;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{create_library, wait_for_tx},
+};
+use tokio::time::{sleep, Duration};
+
+/// A vesting tranche that should be released by the scheduler after `execute_after`.
+struct Schedule {
+    id: u64,
+    execute_after: u32,
+    tranche: u64,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("scheduled_transactions")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create Basic User Account
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating a new account for Alice");
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+
+    let alice_account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+        .with_component(BasicWallet)
+        .build()
+        .unwrap();
+
+    client.add_account(&alice_account, false).await?;
+    keystore.add_key(&key_pair).unwrap();
+
+    println!(
+        "Alice's account ID: {:?}",
+        alice_account.id().to_bech32(network.network_id())
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Create and deploy the network scheduler
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Deploying the network scheduler");
+
+    let scheduler_code = fs::read_to_string(Path::new("../masm/accounts/scheduler.masm")).unwrap();
+
+    let assembler: Assembler = TransactionKernel::assembler().with_debug_mode(true);
+    let scheduler_component = AccountComponent::compile(
+        &scheduler_code,
+        assembler.clone(),
+        vec![
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )
+    .unwrap()
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let scheduler = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Network)
+        .with_auth_component(auth::NoAuth)
+        .with_component(scheduler_component)
+        .build()
+        .unwrap();
+
+    client.add_account(&scheduler, false).await.unwrap();
+
+    println!(
+        "scheduler id: {:?}",
+        scheduler.id().to_bech32(network.network_id())
+    );
+
+    let library = create_library(&scheduler_code, "external_contract::scheduler").unwrap();
+
+    let script_code =
+        fs::read_to_string(Path::new("../masm/scripts/scheduler_init_script.masm")).unwrap();
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_tx_script(&script_code)?;
+
+    let deploy_request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .build()
+        .unwrap();
+    let tx_id = client
+        .submit_with_prover(scheduler.id(), deploy_request)
+        .await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Create three scheduled notes
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Scheduling three vesting tranches");
+
+    let current_block = client.get_sync_height().await?.as_u32();
+    let schedules = [
+        Schedule {
+            id: 1,
+            execute_after: current_block + 5,
+            tranche: 100,
+        },
+        Schedule {
+            id: 2,
+            execute_after: current_block + 10,
+            tranche: 150,
+        },
+        Schedule {
+            id: 3,
+            execute_after: current_block + 15,
+            tranche: 250,
+        },
+    ];
+
+    let note_code = fs::read_to_string(Path::new("../masm/notes/scheduled_note.masm")).unwrap();
+    let note_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?;
+
+    let mut scheduled_notes = vec![];
+    for schedule in &schedules {
+        let inputs = NoteInputs::new(vec![
+            Felt::new(schedule.execute_after as u64),
+            Felt::new(schedule.id),
+            Felt::new(schedule.tranche),
+        ])?;
+        let recipient = NoteRecipient::new(client.rng().draw_word(), note_script.clone(), inputs);
+
+        // The execution hint tells the network transaction builder not to bother with the
+        // note before its block
+        let metadata = NoteMetadata::new(
+            alice_account.id(),
+            NoteType::Public,
+            NoteTag::from_account_id(scheduler.id()),
+            NoteExecutionHint::after_block(BlockNumber::from(schedule.execute_after))?,
+            Felt::new(0),
+        )?;
+
+        scheduled_notes.push(OutputNote::Full(Note::new(
+            NoteAssets::default(),
+            metadata,
+            recipient,
+        )));
+        println!(
+            "Schedule {} releases {} tokens after block {}",
+            schedule.id, schedule.tranche, schedule.execute_after
+        );
+    }
+
+    let note_request = TransactionRequestBuilder::new()
+        .own_output_notes(scheduled_notes)
+        .build()?;
+    let tx_id = client
+        .submit_with_prover(alice_account.id(), note_request)
+        .await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Watch the schedules fire
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Waiting for the network to execute the schedules");
+
+    let mut executed_blocks: Vec<u64> = vec![];
+    while executed_blocks.len() < schedules.len() {
+        sleep(Duration::from_secs(5)).await;
+        client.sync_state().await?;
+
+        let Some(record) = client.get_account(scheduler.id()).await? else {
+            continue;
+        };
+        let storage = record.account().storage();
+
+        let count = storage.get_item(0)?[3].as_int() as usize;
+        while executed_blocks.len() < count {
+            let schedule = &schedules[executed_blocks.len()];
+            let key: Word = [
+                Felt::new(0),
+                Felt::new(0),
+                Felt::new(0),
+                Felt::new(schedule.id),
+            ]
+            .into();
+            let executed_at = storage.get_map_item(1, key)?[3].as_int();
+
+            println!(
+                "⏰ schedule {} fired in block {} (scheduled after {})",
+                schedule.id, executed_at, schedule.execute_after
+            );
+            assert!(executed_at >= schedule.execute_after as u64);
+            executed_blocks.push(executed_at);
+        }
+
+        println!(
+            "Block {}: {}/{} schedules executed",
+            client.get_sync_height().await?,
+            count,
+            schedules.len()
+        );
+    }
+
+    // The scheduler enforces the order on-chain, the blocks must reflect it as well
+    assert!(executed_blocks.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    let record = client.get_account(scheduler.id()).await?.unwrap();
+    let released = record.account().storage().get_item(2)?[3].as_int();
+    let expected: u64 = schedules.iter().map(|schedule| schedule.tranche).sum();
+    assert_eq!(released, expected);
+
+    println!(
+        "\nAll schedules fired in order, {} tokens released!",
+        released
+    );
+
+    Ok(())
+}
@@ -0,0 +1,253 @@
+use miden_lib::note::WellKnownNote;
+use std::{fs, path::Path, sync::Arc};
+use tokio::time::{sleep, Duration};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteTag,
+        NoteType,
+    },
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Felt, Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{crypto::hash::rpo::Rpo256, ZERO};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes},
+};
+
+/// Encodes an intent to pay `target` as the note args expected by the signed intent note.
+fn intent(target: AccountId) -> Word {
+    [
+        ZERO,
+        NoteTag::from_account_id(target).into(),
+        target.suffix(),
+        target.prefix().as_felt(),
+    ]
+    .into()
+}
+
+/// Builds the P2ID note the relayer creates when it executes an intent to pay `target`.
+fn intent_payment_note(
+    intent_note: &Note,
+    relayer: AccountId,
+    target: AccountId,
+) -> Result<Note, Box<dyn std::error::Error>> {
+    let serial_num = intent_note.serial_num();
+    let payment_serial_num = [
+        serial_num[0],
+        serial_num[1],
+        serial_num[2],
+        Felt::new(serial_num[3].as_int() + 1),
+    ]
+    .into();
+
+    let recipient = NoteRecipient::new(
+        payment_serial_num,
+        WellKnownNote::P2ID.script(),
+        NoteInputs::new(vec![target.suffix(), target.prefix().as_felt()])?,
+    );
+    let metadata = NoteMetadata::new(
+        relayer,
+        NoteType::Public,
+        NoteTag::from_account_id(target),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+
+    Ok(Note::new(intent_note.assets().clone(), metadata, recipient))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("signed_intent_relayer")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create accounts. The user only has a key pair, no account at all
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice, Bob, the relayer and the user's key");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice's account ID: {:?}",
+        alice.id().to_bech32(network.network_id())
+    );
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Bob's account ID: {:?}",
+        bob.id().to_bech32(network.network_id())
+    );
+    let relayer = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Relayer account ID: {:?}",
+        relayer.id().to_bech32(network.network_id())
+    );
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    println!(
+        "Faucet account ID: {:?}",
+        faucet.id().to_bech32(network.network_id())
+    );
+
+    // Never added to the keystore: only the user holds this key
+    let user_key = AuthSecretKey::new_rpo_falcon512();
+    let user_pub_key = user_key.public_key().to_commitment();
+
+    let mint_request = TransactionRequestBuilder::new()
+        .build_mint_fungible_asset(
+            FungibleAsset::new(faucet.id(), 100).unwrap(),
+            alice.id(),
+            NoteType::Public,
+            client.rng(),
+        )
+        .unwrap();
+    client.submit_with_prover(faucet.id(), mint_request).await?;
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice pays the user with a note locked to the user's key
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Alice sends 50 tokens to the user's key");
+
+    let note_code = fs::read_to_string(Path::new("../masm/notes/signed_intent_note.masm")).unwrap();
+    let note_script = client.script_builder().compile_note_script(&note_code)?;
+
+    let mut note_inputs = user_pub_key.to_vec();
+    note_inputs.extend(WellKnownNote::P2ID.script_root().iter());
+
+    let payment = FungibleAsset::new(faucet.id(), 50).unwrap();
+    let intent_note = Note::new(
+        NoteAssets::new(vec![payment.into()])?,
+        NoteMetadata::new(
+            alice.id(),
+            NoteType::Public,
+            NoteTag::for_local_use_case(0, 0)?,
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )?,
+        NoteRecipient::new(
+            client.rng().draw_word(),
+            note_script,
+            NoteInputs::new(note_inputs)?,
+        ),
+    );
+
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(intent_note.clone())])
+        .build()?;
+    let tx_id = client.submit_with_prover(alice.id(), request).await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 3: The user signs an intent to pay Bob, off-chain
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] The user signs an intent to pay Bob");
+
+    let bob_intent = intent(bob.id());
+    let message = Rpo256::merge(&[bob_intent, intent_note.serial_num()]);
+    let signature = user_key.sign(message);
+
+    // The note script looks the signature up under hash(PUB_KEY, MSG)
+    let signature_key = Rpo256::merge(&[user_pub_key, message]);
+    let signature_data = signature.to_prepared_signature(message);
+
+    // -------------------------------------------------------------------------
+    // STEP 4: A relayer cannot redirect the payment to itself
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Redirecting the signed intent fails");
+
+    let forged_request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(intent_note.clone(), Some(intent(relayer.id())))])
+        .extend_advice_map([(signature_key, signature_data.clone())])
+        .build()?;
+    let result = client
+        .execute_transaction(relayer.id(), forged_request)
+        .await;
+    assert!(result.is_err());
+    println!("Rejected: {}", result.unwrap_err());
+
+    // -------------------------------------------------------------------------
+    // STEP 5: The relayer submits the intent and pays the fee
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] The relayer executes the intent");
+
+    let bob_payment = intent_payment_note(&intent_note, relayer.id(), bob.id())?;
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(intent_note, Some(bob_intent))])
+        .extend_advice_map([(signature_key, signature_data)])
+        .expected_output_recipients(vec![bob_payment.recipient().clone()])
+        .build()?;
+    let tx_id = client.submit_with_prover(relayer.id(), request).await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Bob receives the payment
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Bob consumes the payment");
+
+    wait_for_notes(&mut client, &network, bob.id(), 1).await?;
+    consume_all_notes(&mut client, bob.id()).await?;
+
+    sleep(Duration::from_secs(5)).await;
+    client.sync_state().await?;
+
+    let bob_balance = client
+        .get_account(bob.id())
+        .await?
+        .unwrap()
+        .account()
+        .vault()
+        .get_balance(faucet.id())?;
+    let relayer_balance = client
+        .get_account(relayer.id())
+        .await?
+        .unwrap()
+        .account()
+        .vault()
+        .get_balance(faucet.id())?;
+
+    println!("Bob balance: {}", bob_balance);
+    println!("Relayer balance: {}", relayer_balance);
+    assert_eq!(bob_balance, 50);
+    assert_eq!(relayer_balance, 0);
+
+    println!("\nThe user paid Bob without ever holding a fee balance!");
+
+    Ok(())
+}
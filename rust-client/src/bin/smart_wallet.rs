@@ -0,0 +1,376 @@
+use miden_lib::account::auth::NoAuth;
+use miden_lib::transaction::TransactionKernel;
+use rand::{rngs::StdRng, RngCore};
+use std::{fs, path::Path, sync::Arc};
+
+use miden_client::{
+    account::{component::BasicWallet, AccountId},
+    asset::{FungibleAsset, TokenSymbol},
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        create_p2id_note, Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata,
+        NoteRecipient, NoteTag, NoteType,
+    },
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Felt, Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    ONE, ZERO,
+};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    encoding,
+    helpers::{
+        create_basic_account, create_basic_faucet, create_library, wait_for_notes, word_to_masm,
+    },
+};
+
+/// Handle registered for the smart wallet.
+const HANDLE: &str = "alice@miden";
+
+/// Number of guardian approvals needed to replace the owner key.
+const RECOVERY_THRESHOLD: u64 = 2;
+
+/// Generates a new key pair, stores it in the keystore and returns its public key commitment.
+fn new_key(keystore: &Arc<FilesystemKeyStore<StdRng>>) -> Word {
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    keystore.add_key(&key_pair).unwrap();
+    key_pair.public_key().to_commitment()
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("smart_wallet")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await.unwrap();
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the smart wallet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice's smart wallet");
+
+    // The handle is stored as a short string, so anyone reading the storage can read it back
+    let handle = encoding::encode_string(HANDLE)?;
+
+    // In a real wallet each key lives on a different device; here they share one keystore
+    let owner_key = new_key(&keystore);
+    let guardian_keys = [new_key(&keystore), new_key(&keystore), new_key(&keystore)];
+    let session_key = new_key(&keystore);
+    let new_owner_key = new_key(&keystore);
+
+    let guardians = StorageMap::with_entries(
+        guardian_keys
+            .iter()
+            .map(|key| (*key, Word::from([ZERO, ZERO, ZERO, ONE]))),
+    )?;
+
+    let wallet_code = fs::read_to_string(Path::new("../masm/accounts/smart_wallet.masm")).unwrap();
+    let wallet_component = AccountComponent::compile(
+        &wallet_code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(handle),
+            StorageSlot::Value(owner_key),
+            StorageSlot::Map(guardians),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::new(RECOVERY_THRESHOLD)].into()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )
+    .unwrap()
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    // The smart wallet component is the auth component of the account
+    let wallet = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(wallet_component)
+        .with_component(BasicWallet)
+        .build()
+        .unwrap();
+
+    client.add_account(&wallet, false).await?;
+    println!(
+        "Smart wallet ID: {:?}",
+        wallet.id().to_bech32(network.network_id())
+    );
+
+    let wallet_library = create_library(&wallet_code, "external_contract::smart_wallet").unwrap();
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Register the handle with the name service
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Registering the handle {}", HANDLE);
+
+    let registry_code =
+        fs::read_to_string(Path::new("../masm/accounts/handle_registry.masm")).unwrap();
+    let registry_component = AccountComponent::compile(
+        &registry_code,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Map(StorageMap::new())],
+    )
+    .unwrap()
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let registry = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(registry_component)
+        .build()
+        .unwrap();
+
+    client.add_account(&registry, false).await?;
+    println!(
+        "Handle registry ID: {:?}",
+        registry.id().to_bech32(network.network_id())
+    );
+
+    let registry_library =
+        create_library(&registry_code, "external_contract::handle_registry").unwrap();
+    let register_code =
+        fs::read_to_string(Path::new("../masm/notes/register_handle_note.masm")).unwrap();
+    let register_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&registry_library)?
+        .compile_note_script(&register_code)?;
+
+    let register_note = Note::new(
+        NoteAssets::new(vec![])?,
+        NoteMetadata::new(
+            wallet.id(),
+            NoteType::Public,
+            NoteTag::from_account_id(registry.id()),
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )?,
+        NoteRecipient::new(
+            client.rng().draw_word(),
+            register_script,
+            NoteInputs::new(handle.to_vec())?,
+        ),
+    );
+
+    // Every transaction of the smart wallet names the key that signs it in the auth args
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(register_note.clone())])
+        .auth_arg(owner_key)
+        .build()?;
+    client.submit_with_prover(wallet.id(), request).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(register_note, None)])
+        .build()?;
+    let tx_id = client.submit_with_prover(registry.id(), request).await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    // Anyone can now resolve the handle to the wallet
+    let owner = client
+        .get_account(registry.id())
+        .await?
+        .unwrap()
+        .account()
+        .storage()
+        .get_map_item(0, handle)?;
+    let resolved = AccountId::new_unchecked([owner[3], owner[2]]);
+    println!(
+        "{} resolves to {}",
+        HANDLE,
+        resolved.to_bech32(network.network_id())
+    );
+    assert_eq!(resolved, wallet.id());
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Fund the wallet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Funding the smart wallet");
+
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+
+    let mint_request = TransactionRequestBuilder::new()
+        .build_mint_fungible_asset(
+            FungibleAsset::new(faucet.id(), 100).unwrap(),
+            resolved,
+            NoteType::Public,
+            client.rng(),
+        )
+        .unwrap();
+    client.submit_with_prover(faucet.id(), mint_request).await?;
+
+    wait_for_notes(&mut client, &network, wallet.id(), 1).await?;
+    let note_ids: Vec<_> = client
+        .get_consumable_notes(Some(wallet.id()))
+        .await?
+        .iter()
+        .map(|(note, _)| (note.id(), None))
+        .collect();
+    let request = TransactionRequestBuilder::new()
+        .authenticated_input_notes(note_ids)
+        .auth_arg(owner_key)
+        .build()?;
+    client.submit_with_prover(wallet.id(), request).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 4: The owner adds a session key
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Adding a session key valid for 100 blocks");
+
+    let expires_at = client.get_sync_height().await?.as_u32() + 100;
+    let script_code = format!(
+        "use.external_contract::smart_wallet\n\
+         begin\n    push.{}\n    push.{}\n    call.smart_wallet::add_session_key\nend\n",
+        expires_at,
+        word_to_masm(session_key)
+    );
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&wallet_library)?
+        .compile_tx_script(&script_code)?;
+    let request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .auth_arg(owner_key)
+        .build()?;
+    client.submit_with_prover(wallet.id(), request).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 5: The session key pays Bob, but cannot manage the wallet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Paying Bob with the session key");
+
+    let payment = create_p2id_note(
+        wallet.id(),
+        bob.id(),
+        vec![FungibleAsset::new(faucet.id(), 10)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(payment)])
+        .auth_arg(session_key)
+        .build()?;
+    let tx_id = client.submit_with_prover(wallet.id(), request).await?;
+    println!(
+        "View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    let script_code = format!(
+        "use.external_contract::smart_wallet\n\
+         begin\n    push.{}\n    call.smart_wallet::revoke_session_key\nend\n",
+        word_to_masm(session_key)
+    );
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&wallet_library)?
+        .compile_tx_script(&script_code)?;
+    let request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .auth_arg(session_key)
+        .build()?;
+    let result = client.execute_transaction(wallet.id(), request).await;
+    assert!(result.is_err());
+    println!("Session key cannot revoke keys: {}", result.unwrap_err());
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Alice loses her key, two guardians recover the wallet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Recovering the wallet with two guardians");
+
+    for guardian_key in guardian_keys.iter().take(RECOVERY_THRESHOLD as usize) {
+        let script_code = format!(
+            "use.external_contract::smart_wallet\n\
+             begin\n    push.{}\n    push.{}\n    call.smart_wallet::approve_recovery\nend\n",
+            word_to_masm(new_owner_key),
+            word_to_masm(*guardian_key)
+        );
+        let tx_script = client
+            .script_builder()
+            .with_dynamically_linked_library(&wallet_library)?
+            .compile_tx_script(&script_code)?;
+        let request = TransactionRequestBuilder::new()
+            .custom_script(tx_script)
+            .auth_arg(*guardian_key)
+            .build()?;
+        let tx_id = client.submit_with_prover(wallet.id(), request).await?;
+        println!(
+            "Guardian approval: https://testnet.midenscan.com/tx/{:?}",
+            tx_id
+        );
+    }
+
+    let wallet_state = client.get_account(wallet.id()).await?.unwrap();
+    assert_eq!(wallet_state.account().storage().get_item(1)?, new_owner_key);
+    println!("Owner key replaced");
+
+    // -------------------------------------------------------------------------
+    // STEP 7: The new owner key controls the wallet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 7] Paying Bob with the new owner key");
+
+    let payment = create_p2id_note(
+        wallet.id(),
+        bob.id(),
+        vec![FungibleAsset::new(faucet.id(), 20)?.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(payment)])
+        .auth_arg(new_owner_key)
+        .build()?;
+    client.submit_with_prover(wallet.id(), request).await?;
+
+    let balance = client
+        .get_account(wallet.id())
+        .await?
+        .unwrap()
+        .account()
+        .vault()
+        .get_balance(faucet.id())?;
+    println!("Smart wallet balance: {}", balance);
+    assert_eq!(balance, 70);
+
+    Ok(())
+}
@@ -0,0 +1,281 @@
+use miden_lib::account::auth::NoAuth;
+use miden_lib::transaction::TransactionKernel;
+use rand::{rngs::StdRng, RngCore};
+use std::{fs, sync::Arc};
+
+use miden_client::{
+    account::{Account, AccountId},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    rpc::GrpcClient,
+    transaction::{TransactionId, TransactionRequestBuilder},
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    Word, ZERO,
+};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{create_basic_account, create_library, wait_for_tx},
+    migration::{self, SlotKind},
+};
+
+/// Location of the first version of the loyalty contract, relative to `rust-client`.
+const V1_PATH: &str = "../masm/accounts/loyalty_v1.masm";
+/// Location of the second version, which adds a slot, relative to `rust-client`.
+const V2_PATH: &str = "../masm/accounts/loyalty_v2.masm";
+/// Path under which both versions are linked into transaction scripts.
+const LIBRARY_PATH: &str = "external_contract::loyalty";
+
+/// Layout the second version of the contract expects.
+const V2_LAYOUT: [SlotKind; 3] = [SlotKind::Map, SlotKind::Value, SlotKind::Value];
+const POINTS_SLOT: u8 = 0;
+const TOTAL_REDEEMED_SLOT: u8 = 2;
+
+/// Returns the initial storage of the first version, with an empty slot reserved for later
+/// versions if `reserve` is set.
+fn v1_storage(reserve: bool) -> Vec<StorageSlot> {
+    let mut slots = vec![
+        StorageSlot::Map(StorageMap::new()),
+        StorageSlot::Value(Word::default()),
+    ];
+    if reserve {
+        slots.push(StorageSlot::Value(Word::default()));
+    }
+    slots
+}
+
+/// Returns the initial storage of the second version.
+fn v2_storage() -> Vec<StorageSlot> {
+    vec![
+        StorageSlot::Map(StorageMap::new()),
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Value(Word::default()),
+    ]
+}
+
+/// Deploys a public loyalty contract running `code` with `storage`.
+async fn deploy(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    code: &str,
+    storage: Vec<StorageSlot>,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    let component = AccountComponent::compile(code, TransactionKernel::assembler(), storage)?
+        .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let contract = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&contract, false).await?;
+    Ok(contract)
+}
+
+/// Calls `procedure` of the loyalty contract `contract_id`, running `code`, for `points` of
+/// `member`.
+async fn call(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    contract_id: AccountId,
+    code: &str,
+    procedure: &str,
+    member: AccountId,
+    points: u64,
+) -> Result<TransactionId, Box<dyn std::error::Error>> {
+    let library = create_library(code, LIBRARY_PATH)?;
+    let script_code = format!(
+        "use.external_contract::loyalty\n\
+         begin\n    push.{}\n    push.{}\n    push.{points}\n    call.loyalty::{procedure}\nend\n",
+        member.suffix(),
+        member.prefix().as_felt(),
+    );
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_tx_script(&script_code)?;
+
+    let request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .build()?;
+    Ok(client.submit_with_prover(contract_id, request).await?)
+}
+
+/// Returns the state of the contract `contract_id` as the client knows it.
+async fn read_contract(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    contract_id: AccountId,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(contract_id)
+        .await?
+        .ok_or("the contract is not tracked by the client")?;
+    Ok(record.account().clone())
+}
+
+/// Returns the points of `member` in the contract `contract_id`.
+async fn points_of(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    contract_id: AccountId,
+    member: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let key: Word = [ZERO, ZERO, member.suffix(), member.prefix().as_felt()].into();
+    let contract = read_contract(client, contract_id).await?;
+    Ok(contract.storage().get_map_item(POINTS_SLOT, key)?[3].as_int())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("storage_migration")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    let v1_code = fs::read_to_string(V1_PATH)?;
+    let v2_code = fs::read_to_string(V2_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Deploy the first version twice and award points
+    // -------------------------------------------------------------------------
+    // Both contracts run the same code. The second one is deployed with an additional empty
+    // slot, which the first version never touches.
+    println!("\n[STEP 1] Deploying version 1, with and without a reserved slot");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+
+    let legacy = deploy(&mut client, &v1_code, v1_storage(false)).await?;
+    let reserved = deploy(&mut client, &v1_code, v1_storage(true)).await?;
+    println!("Contract without reserved slot: {}", legacy.id());
+    println!("Contract with a reserved slot:  {}", reserved.id());
+
+    for contract in [legacy.id(), reserved.id()] {
+        for (member, points) in [(alice.id(), 120), (bob.id(), 80)] {
+            let tx_id = call(&mut client, contract, &v1_code, "award", member, points).await?;
+            wait_for_tx(&mut client, tx_id).await?;
+        }
+    }
+    println!("Alice has 120 points and Bob 80 in both contracts");
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Run version 2 on the storage of version 1
+    // -------------------------------------------------------------------------
+    // Version 2 counts redeemed points in slot 2. Nothing checks the layout when the account
+    // is built, but the kernel rejects the first access to the missing slot.
+    println!("\n[STEP 2] Running version 2 on the layout of version 1");
+
+    let legacy = read_contract(&client, legacy.id()).await?;
+    let Err(err) = migration::check_layout(legacy.storage().slots(), &V2_LAYOUT) else {
+        return Err("the layout of version 1 fits version 2".into());
+    };
+    println!("Layout check: {err}");
+
+    let mismatched = deploy(&mut client, &v2_code, legacy.storage().slots().to_vec()).await?;
+    let Err(err) = call(
+        &mut client,
+        mismatched.id(),
+        &v2_code,
+        "redeem",
+        alice.id(),
+        50,
+    )
+    .await
+    else {
+        return Err("version 2 ran without its slot".into());
+    };
+    println!("Redeeming fails: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Deploy version 2 and migrate the storage of the old contract
+    // -------------------------------------------------------------------------
+    // The points map is copied entry by entry into the initial storage of the new account, and
+    // the slot added by version 2 starts from its default.
+    println!("\n[STEP 3] Deploying version 2 with the migrated storage");
+
+    let entries = migration::map_entries(legacy.storage().slots(), POINTS_SLOT.into())?;
+    let migrated = migration::deploy_migrated(&mut client, &legacy, &v2_code, v2_storage()).await?;
+    println!(
+        "Contract {} took over {} members from {}",
+        migrated.id(),
+        entries.len(),
+        legacy.id()
+    );
+
+    let tx_id = call(
+        &mut client,
+        migrated.id(),
+        &v2_code,
+        "redeem",
+        alice.id(),
+        50,
+    )
+    .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Alice redeemed 50 points on the migrated contract");
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Move the contract with the reserved slot
+    // -------------------------------------------------------------------------
+    // The code of a deployed account cannot be replaced, so version 2 still needs an account of
+    // its own. But the old storage already fits its layout: it carries over slot for slot,
+    // nothing is appended and readers addressing the slots by index are unaffected.
+    println!("\n[STEP 4] Moving the contract with the reserved slot to version 2");
+
+    let reserved = read_contract(&client, reserved.id()).await?;
+    migration::check_layout(reserved.storage().slots(), &V2_LAYOUT)?;
+    let slots = migration::migrate_storage(reserved.storage().slots(), v2_storage())?;
+    assert_eq!(slots, reserved.storage().slots());
+    println!("The storage of the contract fits version 2 as it is");
+
+    let upgraded =
+        migration::deploy_migrated(&mut client, &reserved, &v2_code, v2_storage()).await?;
+    let tx_id = call(&mut client, upgraded.id(), &v2_code, "redeem", bob.id(), 30).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Bob redeemed 30 points on {}", upgraded.id());
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking the points");
+
+    assert_eq!(points_of(&client, migrated.id(), alice.id()).await?, 70);
+    assert_eq!(points_of(&client, migrated.id(), bob.id()).await?, 80);
+    assert_eq!(points_of(&client, upgraded.id(), bob.id()).await?, 50);
+    assert_eq!(points_of(&client, legacy.id(), alice.id()).await?, 120);
+    let migrated = read_contract(&client, migrated.id()).await?;
+    assert_eq!(
+        migrated.storage().get_item(TOTAL_REDEEMED_SLOT)?[3].as_int(),
+        50
+    );
+    println!("The migrated contracts kept every member and run version 2");
+
+    Ok(())
+}
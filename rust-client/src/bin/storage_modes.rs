@@ -0,0 +1,196 @@
+use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel};
+use rand::{rngs::StdRng, RngCore};
+use std::{fs, path::Path, sync::Arc};
+
+use miden_client::{
+    account::{Account, AccountId},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    rpc::{domain::account::FetchedAccount, GrpcClient, NodeRpcClient},
+    transaction::{TransactionRequestBuilder, TransactionScript},
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
+    Word,
+};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    ephemeral::EphemeralClient,
+    helpers::{create_library, wait_for_commit},
+};
+
+const MODES: [AccountStorageMode; 3] = [
+    AccountStorageMode::Private,
+    AccountStorageMode::Public,
+    AccountStorageMode::Network,
+];
+
+/// Builds a counter contract with the given storage mode.
+fn counter_contract(
+    code: &str,
+    storage_mode: AccountStorageMode,
+    seed: [u8; 32],
+) -> Result<Account, Box<dyn std::error::Error>> {
+    let component = AccountComponent::compile(
+        code,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Value(Word::default())],
+    )?
+    .with_supports_all_types();
+
+    Ok(AccountBuilder::new(seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(storage_mode)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?)
+}
+
+/// Increments the counter `times` times, waiting for each transaction to be committed.
+async fn increment(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    counter_id: AccountId,
+    script: &TransactionScript,
+    times: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for _ in 0..times {
+        let request = TransactionRequestBuilder::new()
+            .custom_script(script.clone())
+            .build()?;
+        client.submit_with_prover(counter_id, request).await?;
+
+        // Wait for the transaction to be committed before building on top of it
+        wait_for_commit(client).await?;
+    }
+    Ok(())
+}
+
+/// What an outside observer learns about an account from the node.
+struct Observation {
+    mode: AccountStorageMode,
+    /// Counter value read from the node, if its state is public.
+    visible_count: Option<u64>,
+    /// Whether a client that never saw the account can import it.
+    importable: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("storage_modes")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client.clone())
+        .sqlite_store(store_path)
+        .authenticator(keystore)
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    let counter_code = fs::read_to_string(Path::new("../masm/accounts/counter.masm"))?;
+    let script_code = fs::read_to_string(Path::new("../masm/scripts/counter_script.masm"))?;
+    let library = create_library(&counter_code, "external_contract::counter_contract")?;
+    let script = client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_tx_script(&script_code)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Deploy the same counter in every storage mode
+    // -------------------------------------------------------------------------
+    // Deploying is the first increment, so every counter ends up at 2
+    println!("\n[STEP 1] Deploying a counter in each storage mode and incrementing it twice");
+
+    let mut counters = Vec::new();
+    for mode in MODES {
+        let mut seed = [0_u8; 32];
+        client.rng().fill_bytes(&mut seed);
+        let counter = counter_contract(&counter_code, mode, seed)?;
+        client.add_account(&counter, false).await?;
+
+        increment(&mut client, counter.id(), &script, 2).await?;
+        println!(
+            "{:>8} counter: {}",
+            mode.to_string(),
+            counter.id().to_bech32(network.network_id())
+        );
+        counters.push((mode, counter.id()));
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Look at the counters from the outside
+    // -------------------------------------------------------------------------
+    // A fresh client with an empty store plays the role of a stranger knowing only the ids
+    println!("\n[STEP 2] Observing the counters from a fresh client");
+
+    let mut stranger = EphemeralClient::new(endpoint.clone()).await?;
+    stranger.sync_state().await?;
+
+    let mut observations = Vec::new();
+    for (mode, counter_id) in counters {
+        let visible_count = match rpc_client.get_account_details(counter_id).await? {
+            FetchedAccount::Public(account, _) => Some(account.storage().get_item(0)?[3].as_int()),
+            // Only the commitment to the state is on-chain
+            FetchedAccount::Private(..) => None,
+        };
+        let importable = stranger.import_account_by_id(counter_id).await.is_ok();
+
+        observations.push(Observation {
+            mode,
+            visible_count,
+            importable,
+        });
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Comparison
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Comparison\n");
+    println!(
+        "{:<8} | {:<14} | {:<10} | {:<28} | {}",
+        "MODE", "STATE ON NODE", "IMPORTABLE", "WHO CAN EXECUTE", "SYNC"
+    );
+    for observation in &observations {
+        let state = match observation.visible_count {
+            Some(count) => format!("count = {count}"),
+            None => "commitment".to_string(),
+        };
+        let (executors, sync) = match observation.mode {
+            AccountStorageMode::Private => {
+                ("holders of the state", "client keeps the state itself")
+            }
+            AccountStorageMode::Public => {
+                ("anyone (auth permitting)", "client pulls state updates")
+            }
+            AccountStorageMode::Network => {
+                ("operator, for tagged notes", "client pulls state updates")
+            }
+        };
+        println!(
+            "{:<8} | {:<14} | {:<10} | {:<28} | {}",
+            observation.mode.to_string(),
+            state,
+            if observation.importable { "yes" } else { "no" },
+            executors,
+            sync
+        );
+    }
+
+    stranger.close()?;
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::block::BlockNumber;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    subscription,
+};
+
+/// Tokens minted to Alice.
+const FUNDS: u64 = 100;
+/// Tokens Bob charges every period.
+const PRICE: u64 = 25;
+/// Blocks between two renewals, enough for Bob to pull a renewal well within its period.
+const PERIOD: u32 = 20;
+/// Periods Alice authorizes.
+const PERIODS: u32 = 3;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+/// Syncs until the chain reaches `block`.
+async fn wait_until_block(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    block: BlockNumber,
+) -> Result<(), Box<dyn std::error::Error>> {
+    loop {
+        let block_num = client.sync_state().await?.block_num;
+        if block_num >= block {
+            return Ok(());
+        }
+        println!("Block {block_num}, waiting for block {block}...");
+        sleep(Duration::from_secs(3)).await;
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("subscription_payments")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the subscriber and the merchant, and fund the subscriber
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice and Bob, minting {FUNDS} tokens to Alice");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice (subscriber): {}",
+        alice.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Bob (merchant):     {}",
+        bob.id().to_bech32(network.network_id())
+    );
+
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), FUNDS)?,
+        alice.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice authorizes the renewals
+    // -------------------------------------------------------------------------
+    // Bob cannot take tokens out of Alice's account, so Alice sends them ahead of time: one
+    // recallable note per period, which only opens when its period starts.
+    println!("\n[STEP 2] Alice subscribes for {PERIODS} periods of {PERIOD} blocks");
+
+    let (tx_id, subscription) = subscription::subscribe(
+        &mut client,
+        alice.id(),
+        bob.id(),
+        FungibleAsset::new(faucet.id(), PRICE)?,
+        PERIOD,
+        PERIODS,
+    )
+    .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    for (index, renewal) in subscription.renewals.iter().enumerate() {
+        println!(
+            "Renewal {}: {PRICE} tokens, due at block {}, lapses at block {}",
+            index + 1,
+            renewal.due,
+            renewal.lapses
+        );
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Bob pulls the first two renewals
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Bob pulls the renewal of each period as it starts");
+
+    for renewal in &subscription.renewals[..2] {
+        wait_until_block(&mut client, renewal.due).await?;
+        let tx_id = subscription::pull(&mut client, bob.id(), renewal).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        println!("Bob pulled the renewal due at block {}", renewal.due);
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Bob cannot pull the third renewal early
+    // -------------------------------------------------------------------------
+    let third = &subscription.renewals[2];
+    println!(
+        "\n[STEP 4] Bob tries to pull the renewal due at block {}",
+        third.due
+    );
+
+    let Err(err) = subscription::pull(&mut client, bob.id(), third).await else {
+        return Err("Bob pulled a renewal before its period".into());
+    };
+    println!("The renewal is rejected: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    // The third renewal stays locked until its period starts. If Bob does not pull it by the
+    // end of that period, Alice can take it back.
+    println!("\n[STEP 5] Checking balances");
+
+    let bob_balance = balance(&client, bob.id(), faucet.id()).await?;
+    let alice_balance = balance(&client, alice.id(), faucet.id()).await?;
+    assert_eq!(bob_balance, 2 * PRICE);
+    assert_eq!(alice_balance, FUNDS - u64::from(PERIODS) * PRICE);
+    println!(
+        "Bob received {bob_balance} tokens, {PRICE} more are locked until block {}",
+        third.due
+    );
+    println!("Alice holds {alice_balance} tokens");
+
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_commit,
+        wait_for_notes, wait_for_tx,
+    },
+    partial_swap::{self, PartialSwap},
+};
+
+/// Tokens A Alice offers.
+const OFFERED_A: u64 = 100;
+/// Tokens B Alice asks for the whole offer, a price of 2 B per A.
+const REQUESTED_B: u64 = 200;
+/// Tokens B Bob pays for part of the offer.
+const BOB_FILL: u64 = 50;
+/// Tokens B Carol pays for the rest of the offer.
+const CAROL_FILL: u64 = REQUESTED_B - BOB_FILL;
+/// Tokens B minted to Bob, more than he spends.
+const BOB_B: u64 = 100;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("swap_note_example")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the maker, both takers and both assets
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and funding them");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let carol = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Alice (maker): {}",
+        alice.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Bob (taker):   {}",
+        bob.id().to_bech32(network.network_id())
+    );
+    println!(
+        "Carol (taker): {}",
+        carol.id().to_bech32(network.network_id())
+    );
+
+    let faucet_a =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("A").unwrap()).await?;
+    let faucet_b =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("B").unwrap()).await?;
+
+    for (account_id, faucet_id, amount) in [
+        (alice.id(), faucet_a.id(), OFFERED_A),
+        (bob.id(), faucet_b.id(), BOB_B),
+        (carol.id(), faucet_b.id(), CAROL_FILL),
+    ] {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet_id, amount)?,
+            account_id,
+            NoteType::Public,
+            client.rng(),
+        )?;
+        client.submit_with_prover(faucet_id, mint_request).await?;
+    }
+    for account_id in [alice.id(), bob.id(), carol.id()] {
+        wait_for_notes(&mut client, &network, account_id, 1).await?;
+        consume_all_notes(&mut client, account_id).await?;
+    }
+    wait_for_commit(&mut client).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice offers A for B
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Alice offers {OFFERED_A} A for {REQUESTED_B} B, in any quantity");
+
+    let script = partial_swap::compile_note_script(&client)?;
+    let serial_num = client.rng().draw_word();
+    let swap = PartialSwap::new(
+        alice.id(),
+        alice.id(),
+        FungibleAsset::new(faucet_a.id(), OFFERED_A)?,
+        FungibleAsset::new(faucet_b.id(), REQUESTED_B)?,
+        script,
+        serial_num,
+    )?;
+    let tx_id = partial_swap::post(&mut client, &swap).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Bob fills part of the offer
+    // -------------------------------------------------------------------------
+    // The amount Bob pays is passed to the note as note args. The note pays Alice and sends
+    // the rest of the offer to a new note, at the same price.
+    println!("\n[STEP 3] Bob pays {BOB_FILL} B");
+
+    let (tx_id, fill) = partial_swap::fill(&mut client, bob.id(), &swap, BOB_FILL).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    let leftover = fill
+        .leftover
+        .ok_or("a partial fill must leave part of the offer")?;
+    println!(
+        "Bob bought {} A, {} A are left for {} B in note {}",
+        fill.bought.amount(),
+        leftover.offered.amount(),
+        leftover.requested.amount(),
+        leftover.note.id()
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Carol fills the leftover note
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Carol pays {CAROL_FILL} B for the rest");
+
+    let (tx_id, fill) = partial_swap::fill(&mut client, carol.id(), &leftover, CAROL_FILL).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    assert!(fill.leftover.is_none());
+    println!("Carol bought {} A", fill.bought.amount());
+
+    // Alice collects both payments
+    wait_for_notes(&mut client, &network, alice.id(), 2).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_commit(&mut client).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking balances");
+
+    let mut balances = Vec::new();
+    for (name, account_id) in [
+        ("Alice", alice.id()),
+        ("Bob", bob.id()),
+        ("Carol", carol.id()),
+    ] {
+        let a = balance(&client, account_id, faucet_a.id()).await?;
+        let b = balance(&client, account_id, faucet_b.id()).await?;
+        println!("{name}: {a} A, {b} B");
+        balances.push((a, b));
+    }
+
+    let bob_bought = BOB_FILL * OFFERED_A / REQUESTED_B;
+    assert_eq!(balances[0], (0, REQUESTED_B));
+    assert_eq!(balances[1], (bob_bought, BOB_B - BOB_FILL));
+    assert_eq!(balances[2], (OFFERED_A - bob_bought, 0));
+
+    Ok(())
+}
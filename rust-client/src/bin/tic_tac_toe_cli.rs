@@ -0,0 +1,226 @@
+use rand::{rngs::StdRng, RngCore};
+use std::{io::Write, sync::Arc};
+use tokio::time::{sleep, Duration};
+
+use miden_client::{
+    account::AccountId, builder::ClientBuilder, keystore::FilesystemKeyStore, rpc::GrpcClient,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::account::AccountStorageMode;
+use rust_client::{
+    context::TutorialContext,
+    helpers::{create_basic_account, wait_for_tx},
+    known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH},
+    tic_tac_toe::{self, GameState},
+};
+
+const USAGE: &str = "usage:
+  tic_tac_toe_cli wallet                    create a wallet to play with
+  tic_tac_toe_cli new <PLAYER> <OPPONENT>   deploy a game, <PLAYER> places X and moves first
+  tic_tac_toe_cli play <GAME> <PLAYER>      play the game <GAME> as <PLAYER>
+
+Accounts are given as bech32 addresses or labels of known_accounts.json.";
+
+/// How long to wait between two checks for the move of the opponent.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Syncs and returns the current state of `game_id`, importing the game on first use.
+async fn load_game(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    game_id: AccountId,
+) -> Result<GameState, Box<dyn std::error::Error>> {
+    client.sync_state().await?;
+    if client.get_account(game_id).await?.is_none() {
+        client.import_account_by_id(game_id).await?;
+    }
+    GameState::load(client, game_id).await
+}
+
+/// Asks for a field on stdin until the player picks a free one.
+fn read_move(game: &GameState) -> std::io::Result<usize> {
+    loop {
+        print!("Your move (0-8): ");
+        std::io::stdout().flush()?;
+
+        let mut line = String::new();
+        if std::io::stdin().read_line(&mut line)? == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        match line.trim().parse::<usize>() {
+            Ok(field) if game.is_free(field) => return Ok(field),
+            Ok(field) => println!("Field {field} is not free"),
+            Err(_) => println!("Enter a field index between 0 and 8"),
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = rust_client::cli::positional_args();
+    let command = args.first().map(String::as_str).unwrap_or_default();
+    if !matches!(command, "wallet" | "new" | "play") {
+        println!("{USAGE}");
+        return Ok(());
+    }
+
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, kept across runs
+    let context = TutorialContext::persistent("tic_tac_toe_cli")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    client.sync_state().await?;
+    let known_accounts = KnownAccounts::load(DEFAULT_REGISTRY_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // wallet: create an account to play with
+    // -------------------------------------------------------------------------
+    if command == "wallet" {
+        let wallet = create_basic_account(&mut client, &keystore).await?;
+        println!("Wallet: {}", wallet.id().to_bech32(network.network_id()));
+        return Ok(());
+    }
+
+    // -------------------------------------------------------------------------
+    // new: deploy a game between two players
+    // -------------------------------------------------------------------------
+    // Both players are fixed by the constructor. Whoever deploys the game does not need to be
+    // one of them.
+    if command == "new" {
+        let (Some(player), Some(opponent)) = (args.get(1), args.get(2)) else {
+            println!("{USAGE}");
+            return Ok(());
+        };
+        let player = known_accounts.resolve(player, network.network_id())?;
+        let opponent = known_accounts.resolve(opponent, network.network_id())?;
+
+        let mut seed = [0_u8; 32];
+        client.rng().fill_bytes(&mut seed);
+        let (game, tx_id) =
+            tic_tac_toe::deployer(player, opponent, tic_tac_toe::DEFAULT_TURN_TIMEOUT)?
+                .deploy(&mut client, seed, AccountStorageMode::Public)
+                .await?;
+        wait_for_tx(&mut client, tx_id).await?;
+
+        println!("Game: {}", game.id().to_bech32(network.network_id()));
+        println!(
+            "X: {}\nO: {}",
+            known_accounts.display(player, network.network_id()),
+            known_accounts.display(opponent, network.network_id())
+        );
+        return Ok(());
+    }
+
+    // -------------------------------------------------------------------------
+    // play: alternate between our moves and waiting for the opponent
+    // -------------------------------------------------------------------------
+    let (Some(game_id), Some(player)) = (args.get(1), args.get(2)) else {
+        println!("{USAGE}");
+        return Ok(());
+    };
+    let game_id = known_accounts.resolve(game_id, network.network_id())?;
+    let player = known_accounts.resolve(player, network.network_id())?;
+    if client.get_account(player).await?.is_none() {
+        return Err("the player account is not in the local store".into());
+    }
+
+    let mut game = load_game(&mut client, game_id).await?;
+    let mark = game
+        .mark_of(player)
+        .ok_or("the player does not play this game")?;
+    let opponent = game.player(mark.opponent());
+    println!(
+        "Playing {mark} against {}",
+        known_accounts.display(opponent, network.network_id())
+    );
+
+    let script = tic_tac_toe::compile_note_script(&client, tic_tac_toe::MOVE_NOTE_PATH)?;
+    let timeout_script =
+        tic_tac_toe::compile_note_script(&client, tic_tac_toe::CLAIM_TIMEOUT_NOTE_PATH)?;
+    loop {
+        println!("\n{game}");
+        let Some(next) = game.next_mark() else {
+            break;
+        };
+
+        if next == mark {
+            let field = read_move(&game)?;
+            let tx_id =
+                tic_tac_toe::play_move(&mut client, &game, player, field, script.clone()).await?;
+            wait_for_tx(&mut client, tx_id).await?;
+            game = load_game(&mut client, game_id).await?;
+            continue;
+        }
+
+        // The move of the opponent shows up as a new version of the game account
+        println!(
+            "Waiting for {} to move until block {}...",
+            known_accounts.display(game.player(next), network.network_id()),
+            game.timeout_block()
+        );
+        let moves = game.moves;
+        while game.moves == moves && !game.is_finalized() {
+            sleep(POLL_INTERVAL).await;
+            game = load_game(&mut client, game_id).await?;
+
+            // An opponent who stops responding loses once the turn timeout passed. The claim
+            // is rejected if the move comes in first.
+            let block_num = client.get_sync_height().await?.as_u32();
+            if game.can_claim_timeout(player, block_num.into()) {
+                println!("The opponent ran out of time, claiming the game");
+                match tic_tac_toe::submit_timeout_claim(
+                    &mut client,
+                    &game,
+                    player,
+                    timeout_script.clone(),
+                )
+                .await
+                {
+                    Ok(tx_id) => wait_for_tx(&mut client, tx_id).await?,
+                    Err(err) => println!("Claim rejected: {err}"),
+                }
+                game = load_game(&mut client, game_id).await?;
+            }
+        }
+    }
+
+    // A full board without a winner stays open until a player claims the draw. Both players
+    // may try at once, only the first claim goes through.
+    if game.can_claim_draw() {
+        println!("The board is full, claiming the draw");
+        let script = tic_tac_toe::compile_note_script(&client, tic_tac_toe::CLAIM_DRAW_NOTE_PATH)?;
+        match tic_tac_toe::submit_draw_claim(&mut client, &game, player, script).await {
+            Ok(tx_id) => wait_for_tx(&mut client, tx_id).await?,
+            Err(err) => println!("Claim rejected: {err}"),
+        }
+        while !game.is_finalized() {
+            game = load_game(&mut client, game_id).await?;
+            if !game.is_finalized() {
+                sleep(POLL_INTERVAL).await;
+            }
+        }
+    }
+
+    match game.winner {
+        Some(winner) if winner == mark => println!("You won!"),
+        Some(winner) => println!("{winner} won, better luck next time"),
+        None => println!("Draw"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use rand::{rngs::StdRng, RngCore};
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::account::AccountStorageMode;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    tic_tac_toe::{self, GameState},
+};
+
+/// Tokens each player starts with.
+const STARTING_BALANCE: u64 = 10;
+/// Tokens every move must carry.
+const MOVE_DEPOSIT: u64 = 2;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client.get_account(account_id).await?.unwrap();
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("tic_tac_toe_deposits")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the players and fund them
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice and Bob and funding them");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+
+    for account_id in [alice.id(), bob.id()] {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet.id(), STARTING_BALANCE)?,
+            account_id,
+            NoteType::Public,
+            client.rng(),
+        )?;
+        client.submit_with_prover(faucet.id(), mint_request).await?;
+    }
+    for account_id in [alice.id(), bob.id()] {
+        wait_for_notes(&mut client, &network, account_id, 1).await?;
+        consume_all_notes(&mut client, account_id).await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy a game whose moves must carry a deposit
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 2] Deploying a game taking a deposit of {MOVE_DEPOSIT} MID per move");
+
+    let move_deposit = FungibleAsset::new(faucet.id(), MOVE_DEPOSIT)?;
+    let mut seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut seed);
+    let (game, tx_id) = tic_tac_toe::deposit_deployer(
+        alice.id(),
+        bob.id(),
+        tic_tac_toe::DEFAULT_TURN_TIMEOUT,
+        move_deposit,
+    )?
+    .deploy(&mut client, seed, AccountStorageMode::Public)
+    .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Game: {}", game.id().to_bech32(network.network_id()));
+
+    let move_script =
+        tic_tac_toe::compile_note_script(&client, tic_tac_toe::DEPOSIT_MOVE_NOTE_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Bob moves out of turn and loses his deposit
+    // -------------------------------------------------------------------------
+    // Alice moves first. The game still consumes the note of Bob, so nobody can clog it with
+    // moves it cannot process, but it leaves the board untouched and keeps the deposit.
+    println!("\n[STEP 3] Bob tries to move before Alice");
+
+    let state = GameState::load(&client, game.id()).await?;
+    let tx_id =
+        tic_tac_toe::play_deposit_move(&mut client, &state, bob.id(), 4, move_script.clone())
+            .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let state = GameState::load(&client, game.id()).await?;
+    println!(
+        "Moves played: {}, deposits kept: {} MID",
+        state.moves, state.kept_deposits
+    );
+    assert_eq!(state.moves, 0);
+    assert_eq!(state.kept_deposits, MOVE_DEPOSIT);
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Play until Alice completes the top row
+    // -------------------------------------------------------------------------
+    // Every valid move gets its deposit back with a P2ID note to the player.
+    println!("\n[STEP 4] Playing the game");
+
+    for (player, field) in [
+        (alice.id(), 0),
+        (bob.id(), 3),
+        (alice.id(), 1),
+        (bob.id(), 4),
+        (alice.id(), 2),
+    ] {
+        let state = GameState::load(&client, game.id()).await?;
+        let tx_id =
+            tic_tac_toe::play_deposit_move(&mut client, &state, player, field, move_script.clone())
+                .await?;
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+
+    let state = GameState::load(&client, game.id()).await?;
+    println!("{state}");
+    println!("Winner: {}", state.winner.ok_or("the game has no winner")?);
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Collect the refunds
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Collecting the refunded deposits");
+
+    wait_for_notes(&mut client, &network, alice.id(), 3).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_notes(&mut client, &network, bob.id(), 2).await?;
+    consume_all_notes(&mut client, bob.id()).await?;
+    client.sync_state().await?;
+
+    let alice_balance = balance(&client, alice.id(), faucet.id()).await?;
+    let bob_balance = balance(&client, bob.id(), faucet.id()).await?;
+    let kept = balance(&client, game.id(), faucet.id()).await?;
+    println!("Alice balance: {alice_balance}");
+    println!("Bob balance:   {bob_balance}");
+    println!("Game balance:  {kept}");
+
+    assert_eq!(alice_balance, STARTING_BALANCE);
+    assert_eq!(bob_balance, STARTING_BALANCE - MOVE_DEPOSIT);
+    assert_eq!(kept, MOVE_DEPOSIT);
+
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use rand::{rngs::StdRng, RngCore};
+use std::sync::Arc;
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::account::AccountStorageMode;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    tic_tac_toe::{self, GameState},
+    wager_escrow::{self, WagerEscrow},
+};
+
+/// Tokens each player starts with.
+const STARTING_BALANCE: u64 = 100;
+/// Tokens each player stakes on the game.
+const STAKE: u64 = 40;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client.get_account(account_id).await?.unwrap();
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("tic_tac_toe_escrowed")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the players and fund them
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice and Bob and funding them");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+
+    for account_id in [alice.id(), bob.id()] {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet.id(), STARTING_BALANCE)?,
+            account_id,
+            NoteType::Public,
+            client.rng(),
+        )?;
+        client.submit_with_prover(faucet.id(), mint_request).await?;
+    }
+    for account_id in [alice.id(), bob.id()] {
+        wait_for_notes(&mut client, &network, account_id, 1).await?;
+        consume_all_notes(&mut client, account_id).await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy the game and its escrow
+    // -------------------------------------------------------------------------
+    // Each contract needs the id of the other: the game reports its winner to the escrow, and
+    // the escrow only accepts the report from its game. The escrow takes its game as a
+    // constructor argument, which is not part of its id, so its id is known up front and the
+    // game is deployed first.
+    println!("\n[STEP 2] Deploying the game and its wager escrow");
+
+    let stake = FungibleAsset::new(faucet.id(), STAKE)?;
+    let mut escrow_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut escrow_seed);
+    let escrow_id = wager_escrow::escrow_id(stake, escrow_seed)?;
+
+    let settle_script = wager_escrow::compile_note_script(&client, wager_escrow::SETTLE_NOTE_PATH)?;
+    let mut game_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut game_seed);
+    let (game, tx_id) = tic_tac_toe::escrowed_deployer(
+        alice.id(),
+        bob.id(),
+        tic_tac_toe::DEFAULT_TURN_TIMEOUT,
+        escrow_id,
+        &settle_script,
+    )?
+    .deploy(&mut client, game_seed, AccountStorageMode::Public)
+    .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Game:   {}", game.id().to_bech32(network.network_id()));
+
+    let (escrow, tx_id) = wager_escrow::deployer(game.id(), stake)?
+        .deploy(&mut client, escrow_seed, AccountStorageMode::Public)
+        .await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    assert_eq!(escrow.id(), escrow_id);
+    println!("Escrow: {}", escrow.id().to_bech32(network.network_id()));
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Deposit the stakes into the escrow
+    // -------------------------------------------------------------------------
+    // The escrow checks every deposit against the game through FPI: the sender must be one of
+    // its players, and the game must not have started. The game itself never sees the stakes.
+    println!("\n[STEP 3] Depositing {STAKE} MID each into the escrow");
+
+    let deposit_script =
+        wager_escrow::compile_note_script(&client, wager_escrow::DEPOSIT_NOTE_PATH)?;
+    for player in [alice.id(), bob.id()] {
+        let state = WagerEscrow::load(&client, escrow.id()).await?;
+        let tx_id =
+            wager_escrow::deposit(&mut client, &state, player, deposit_script.clone()).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+
+    let state = WagerEscrow::load(&client, escrow.id()).await?;
+    println!(
+        "Deposits: {}, pot: {} MID",
+        state.depositors.len(),
+        balance(&client, escrow.id(), faucet.id()).await?
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Play until Alice completes the top row
+    // -------------------------------------------------------------------------
+    // The winning move makes the game create a settlement note for the escrow. Its serial
+    // number is derived from the game id, so `play_move` can announce the note to the client.
+    println!("\n[STEP 4] Playing the game");
+
+    let move_script = tic_tac_toe::compile_note_script(&client, tic_tac_toe::MOVE_NOTE_PATH)?;
+    for (player, field) in [
+        (alice.id(), 0),
+        (bob.id(), 3),
+        (alice.id(), 1),
+        (bob.id(), 4),
+        (alice.id(), 2),
+    ] {
+        let state = GameState::load(&client, game.id()).await?;
+        let tx_id =
+            tic_tac_toe::play_move(&mut client, &state, player, field, move_script.clone()).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+
+    let game_state = GameState::load(&client, game.id()).await?;
+    println!("{game_state}");
+    println!(
+        "Winner: {}",
+        game_state.winner.ok_or("the game has no winner")?
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 5: The escrow pays out the winner
+    // -------------------------------------------------------------------------
+    // The escrow consumes the note the game created, checks that it comes from its game, and
+    // sends both stakes to Alice with a P2ID note.
+    println!("\n[STEP 5] Settling the escrow and collecting the pot");
+
+    let state = WagerEscrow::load(&client, escrow.id()).await?;
+    let tx_id = wager_escrow::settle(&mut client, &state, &game_state, settle_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    consume_all_notes(&mut client, alice.id()).await?;
+    client.sync_state().await?;
+
+    let alice_balance = balance(&client, alice.id(), faucet.id()).await?;
+    let bob_balance = balance(&client, bob.id(), faucet.id()).await?;
+    let pot = balance(&client, escrow.id(), faucet.id()).await?;
+    println!("Alice balance:  {alice_balance}");
+    println!("Bob balance:    {bob_balance}");
+    println!("Escrow balance: {pot}");
+
+    assert!(WagerEscrow::load(&client, escrow.id()).await?.settled);
+    assert_eq!(alice_balance, STARTING_BALANCE + STAKE);
+    assert_eq!(bob_balance, STARTING_BALANCE - STAKE);
+    assert_eq!(pot, 0);
+
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use rand::{rngs::StdRng, RngCore};
+use std::{collections::BTreeMap, sync::Arc};
+
+use miden_client::{
+    account::AccountId, builder::ClientBuilder, keystore::FilesystemKeyStore, rpc::GrpcClient,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::account::AccountStorageMode;
+use rust_client::{
+    context::TutorialContext,
+    helpers::{create_basic_account, wait_for_tx},
+    known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH},
+    ranking::{self, PlayerStats, Ranking, INITIAL_RATING},
+    tic_tac_toe::{self, GameState},
+};
+
+/// Moves by which the player placing X completes the top row.
+const WINNING_LINE: [usize; 5] = [0, 3, 1, 4, 2];
+
+/// Prints the players of `ranking` by decreasing rating, naming them with `name`.
+fn print_leaderboard(ranking: &Ranking, name: impl Fn(AccountId) -> String) {
+    println!(
+        "{:>4}  {:<44}  {:>6}  {:>4}  {:>6}",
+        "#", "Player", "Rating", "Wins", "Losses"
+    );
+    for (rank, (player, stats)) in ranking.leaderboard().into_iter().enumerate() {
+        println!(
+            "{:>4}  {:<44}  {:>6}  {:>4}  {:>6}",
+            rank + 1,
+            name(player),
+            stats.rating,
+            stats.wins,
+            stats.losses
+        );
+    }
+}
+
+/// Returns the ranking as last updated by the client.
+async fn ranking_state(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    ranking_id: AccountId,
+) -> Result<Ranking, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(ranking_id)
+        .await?
+        .ok_or("the ranking is not tracked by the client")?;
+    Ranking::from_account(record.account())
+}
+
+/// Plays a ranked game in which `winner` beats `loser`, then has the ranking consume the
+/// report the game sent.
+async fn play_ranked_game(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    organizer: AccountId,
+    ranking_id: AccountId,
+    winner: AccountId,
+    loser: AccountId,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let report_script = ranking::compile_note_script(client, ranking::REPORT_NOTE_PATH)?;
+    let mut seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut seed);
+    let (game, tx_id) = tic_tac_toe::ranked_deployer(
+        winner,
+        loser,
+        tic_tac_toe::DEFAULT_TURN_TIMEOUT,
+        ranking_id,
+        &report_script,
+    )?
+    .deploy(client, seed, AccountStorageMode::Public)
+    .await?;
+    wait_for_tx(client, tx_id).await?;
+
+    // Without the registration the ranking would reject the report of the game
+    let tx_id = ranking::register_game(client, organizer, ranking_id, game.id()).await?;
+    wait_for_tx(client, tx_id).await?;
+
+    // The winner places X and moves first
+    let move_script = tic_tac_toe::compile_note_script(client, tic_tac_toe::MOVE_NOTE_PATH)?;
+    for (turn, field) in WINNING_LINE.into_iter().enumerate() {
+        let player = if turn % 2 == 0 { winner } else { loser };
+        let state = GameState::load(client, game.id()).await?;
+        let tx_id =
+            tic_tac_toe::play_move(client, &state, player, field, move_script.clone()).await?;
+        wait_for_tx(client, tx_id).await?;
+    }
+
+    let state = GameState::load(client, game.id()).await?;
+    let mark = state.winner.ok_or("the game has no winner")?;
+    let report = state
+        .report_for_win(mark, report_script)?
+        .ok_or("the game is not ranked")?;
+    let tx_id = ranking::consume_note(client, ranking_id, report).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // With the address or label of a ranking, only print its leaderboard
+    if let Some(input) = rust_client::cli::positional_args().into_iter().next() {
+        let known = KnownAccounts::load(DEFAULT_REGISTRY_PATH)?;
+        let ranking_id = known.resolve(&input, network.network_id())?;
+        let ranking = Ranking::fetch(rpc_client.as_ref(), ranking_id).await?;
+        print_leaderboard(&ranking, |player| {
+            known.display(player, network.network_id())
+        });
+        return Ok(());
+    }
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("tic_tac_toe_leaderboard")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the organizer, the players and the ranking
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating the organizer, Alice, Bob, Carol and the ranking");
+
+    let organizer = create_basic_account(&mut client, &keystore).await?;
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let carol = create_basic_account(&mut client, &keystore).await?;
+    let ranking = ranking::create_ranking(&mut client, organizer.id()).await?;
+    println!("Ranking: {}", ranking.id().to_bech32(network.network_id()));
+
+    let names = BTreeMap::from([
+        (alice.id(), "Alice"),
+        (bob.id(), "Bob"),
+        (carol.id(), "Carol"),
+    ]);
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice beats Bob
+    // -------------------------------------------------------------------------
+    // The winning move makes the game create the report. Its sender is the game, which the
+    // organizer registered, so the ranking trusts it without asking either player.
+    println!("\n[STEP 2] Alice beats Bob");
+
+    play_ranked_game(
+        &mut client,
+        organizer.id(),
+        ranking.id(),
+        alice.id(),
+        bob.id(),
+    )
+    .await?;
+    let state = ranking_state(&client, ranking.id()).await?;
+    println!("Alice is rated {}", state.player(alice.id()).rating);
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Carol beats Alice
+    // -------------------------------------------------------------------------
+    // Carol plays a first game against a higher rated player. The gap is less than a
+    // step of the rating scale, so the ratings move as much as between new players.
+    println!("\n[STEP 3] Carol beats Alice");
+
+    play_ranked_game(
+        &mut client,
+        organizer.id(),
+        ranking.id(),
+        carol.id(),
+        alice.id(),
+    )
+    .await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Printing the leaderboard");
+
+    let state = ranking_state(&client, ranking.id()).await?;
+    print_leaderboard(&state, |player| {
+        names
+            .get(&player)
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| player.to_bech32(network.network_id()))
+    });
+
+    let first = ranking::rating_change(INITIAL_RATING, INITIAL_RATING);
+    let alice_rating = INITIAL_RATING + first;
+    let second = ranking::rating_change(INITIAL_RATING, alice_rating);
+    assert_eq!(
+        state.player(alice.id()),
+        PlayerStats {
+            rating: alice_rating - second,
+            wins: 1,
+            losses: 1,
+        }
+    );
+    assert_eq!(state.player(bob.id()).rating, INITIAL_RATING - first);
+    assert_eq!(state.player(carol.id()).rating, INITIAL_RATING + second);
+    assert_eq!(state.leaderboard()[0].0, carol.id());
+
+    Ok(())
+}
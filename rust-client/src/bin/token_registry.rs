@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use miden_client::{
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    format::AssetFormatter,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    token_registry::{self, icon_hash, TokenMetadata, TokenRegistry},
+};
+
+/// Icons of the two tokens. A wallet downloads them from wherever the issuer hosts them and
+/// checks them against the hash in the registry.
+const MID_ICON: &[u8] = b"<svg viewBox=\"0 0 1 1\"><circle r=\"1\" fill=\"#ff5500\"/></svg>";
+const GEM_ICON: &[u8] = b"<svg viewBox=\"0 0 1 1\"><rect width=\"1\" height=\"1\"/></svg>";
+
+/// Base units of each token minted to Alice.
+const MINTED: u64 = 1_250_000;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("token_registry")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client.clone())
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create two tokens, a registry and a wallet holding both tokens
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating the MID and GEM faucets, the registry and Alice's wallet");
+
+    let mid = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    let gem = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("GEM").unwrap()).await?;
+    let registry = token_registry::create_registry(&mut client).await?;
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Registry: {}",
+        registry.id().to_bech32(network.network_id())
+    );
+    println!("Alice:    {}", alice.id().to_bech32(network.network_id()));
+
+    for faucet in [&mid, &gem] {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet.id(), MINTED)?,
+            alice.id(),
+            NoteType::Public,
+            client.rng(),
+        )?;
+        let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+    }
+    wait_for_notes(&mut client, &network, alice.id(), 2).await?;
+    let tx_id = consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Each faucet registers its token
+    // -------------------------------------------------------------------------
+    // The registry reads the faucet id from the sender of the registration note, so neither
+    // faucet can register metadata for the other one's token.
+    println!("\n[STEP 2] Registering both tokens");
+
+    let tokens = [
+        (
+            &mid,
+            TokenMetadata {
+                symbol: "MID".to_string(),
+                decimals: 8,
+                icon_hash: icon_hash(MID_ICON),
+            },
+        ),
+        (
+            &gem,
+            TokenMetadata {
+                symbol: "GEM".to_string(),
+                decimals: 8,
+                icon_hash: icon_hash(GEM_ICON),
+            },
+        ),
+    ];
+    for (faucet, metadata) in &tokens {
+        let tx_id =
+            token_registry::register(&mut client, faucet.id(), registry.id(), metadata).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        println!("Registered {}", metadata.symbol);
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Format Alice's vault from the registry alone
+    // -------------------------------------------------------------------------
+    // One request for the registry replaces one request per faucet: the formatter never
+    // contacts MID or GEM.
+    println!("\n[STEP 3] Formatting Alice's vault with the registry");
+
+    let fetched = TokenRegistry::fetch(rpc_client.as_ref(), registry.id()).await?;
+    let formatter = AssetFormatter::new(network.network_id()).with_registry(&fetched);
+
+    let alice_record = client
+        .get_account(alice.id())
+        .await?
+        .ok_or("Alice is not tracked by the client")?;
+    for asset in alice_record.account().vault().assets() {
+        println!("  {}", formatter.format(&asset));
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Registered decimals cannot change
+    // -------------------------------------------------------------------------
+    // Changing the decimals would change the value of every amount already shown to users,
+    // so the registry only lets a faucet replace its symbol and icon.
+    println!("\n[STEP 4] Registering GEM again with other decimals");
+
+    let changed = TokenMetadata {
+        decimals: 2,
+        ..tokens[1].1.clone()
+    };
+    let Err(err) = token_registry::register(&mut client, gem.id(), registry.id(), &changed).await
+    else {
+        return Err("the registry accepted new decimals for GEM".into());
+    };
+    println!("The registry kept the decimals of GEM: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking the registry against what the faucets registered");
+
+    for (faucet, metadata) in &tokens {
+        assert_eq!(fetched.get(faucet.id()), Some(metadata));
+    }
+    assert_eq!(
+        formatter.format(&FungibleAsset::new(mid.id(), MINTED)?.into()),
+        "0.0125 MID"
+    );
+    println!(
+        "Both tokens are registered, and the GEM icon hashes to {}",
+        fetched
+            .get(gem.id())
+            .map(|metadata| metadata.icon_hash.to_hex())
+            .unwrap_or_default()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use miden_lib::account::auth::AuthRpoFalcon512;
+use rand::RngCore;
+use std::{io::Write, sync::Arc};
+
+use miden_client::{
+    account::component::BasicFungibleFaucet,
+    asset::FungibleAsset,
+    auth::AuthSecretKey,
+    builder::ClientBuilder,
+    note::{create_p2id_note, NoteType},
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder, TransactionResult},
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{AccountBuilder, AccountStorageMode, AccountType},
+    asset::TokenSymbol,
+    Felt,
+};
+use rust_client::{
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{create_basic_account, total_cycles, wait_for_notes},
+};
+
+/// Prints what a transaction would do: the notes it creates, how it changes the account and
+/// what it costs.
+fn print_preview(network: &Network, tx_result: &TransactionResult) {
+    let executed = tx_result.executed_transaction();
+
+    println!("Output notes:");
+    for note in tx_result.created_notes().iter() {
+        let assets = match note {
+            OutputNote::Full(note) => format!("{:?}", note.assets().iter().collect::<Vec<_>>()),
+            _ => "(assets not disclosed)".to_string(),
+        };
+        println!(
+            "  {} {:?} tag {} {}",
+            note.id().to_hex(),
+            note.metadata().note_type(),
+            u32::from(note.metadata().tag()),
+            assets
+        );
+    }
+
+    let delta = tx_result.account_delta();
+    println!("Account changes:");
+    for (faucet_id, amount) in delta.vault().fungible().iter() {
+        println!(
+            "  {:+} of faucet {}",
+            amount,
+            faucet_id.to_bech32(network.network_id())
+        );
+    }
+    let changed_slots = delta.storage().values().len() + delta.storage().maps().len();
+    println!("  {changed_slots} storage slots changed");
+
+    println!("Cost:");
+    println!("  fee: {:?}", executed.fee());
+    println!("  {} cycles", total_cycles(executed.measurements()));
+}
+
+/// Asks the user to confirm on stdin. `--yes` confirms without asking.
+fn confirm(question: &str) -> bool {
+    if rust_client::cli::positional_args()
+        .iter()
+        .any(|arg| arg == "--yes")
+    {
+        return true;
+    }
+
+    print!("{question} [y/N] ");
+    std::io::stdout().flush().ok();
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).ok();
+    matches!(answer.trim(), "y" | "Y" | "yes")
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("transaction_preview")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Fund Alice's wallet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating and funding Alice's wallet");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let faucet = AccountBuilder::new(init_seed)
+        .account_type(AccountType::FungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+        .with_component(
+            BasicFungibleFaucet::new(TokenSymbol::new("MID").unwrap(), 8, Felt::new(1_000_000))
+                .unwrap(),
+        )
+        .build()
+        .unwrap();
+    client.add_account(&faucet, false).await?;
+    keystore.add_key(&key_pair).unwrap();
+
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), 100).unwrap(),
+        alice.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    client.submit_with_prover(faucet.id(), mint_request).await?;
+
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    let note_ids = client
+        .get_consumable_notes(Some(alice.id()))
+        .await?
+        .iter()
+        .map(|(note, _)| note.id())
+        .collect();
+    let consume_request = TransactionRequestBuilder::new().build_consume_notes(note_ids)?;
+    client
+        .submit_with_prover(alice.id(), consume_request)
+        .await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Preview a payment to Bob
+    // -------------------------------------------------------------------------
+    // Executing a transaction runs it against the local copy of the account without proving
+    // or submitting it, and doesn't touch the store. The authenticator is asked for a
+    // signature during execution, but the result never leaves this machine: if the user
+    // declines, it is simply dropped.
+    println!("\n[STEP 2] Previewing a payment of 40 tokens to Bob");
+
+    let payment = create_p2id_note(
+        alice.id(),
+        bob.id(),
+        vec![FungibleAsset::new(faucet.id(), 40).unwrap().into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let payment_request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(payment)])
+        .build()?;
+
+    let preview = client
+        .execute_transaction(alice.id(), payment_request.clone())
+        .await?;
+    print_preview(&network, &preview);
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Submit only after confirmation
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 3] Confirming");
+
+    if !confirm("Sign and submit this transaction?") {
+        println!("Cancelled, nothing was submitted");
+        return Ok(());
+    }
+
+    // The transaction is executed again: the chain may have moved since the preview, so the
+    // submitted transaction is built against the latest state
+    let tx_id = client
+        .submit_with_prover(alice.id(), payment_request)
+        .await?;
+    println!(
+        "Submitted. View transaction on MidenScan: https://testnet.midenscan.com/tx/{:?}",
+        tx_id
+    );
+
+    Ok(())
+}
@@ -0,0 +1,218 @@
+use rand::rngs::StdRng;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    replacement::Replacement,
+    unclaimed::{self, PaymentStatus, PendingPayment},
+};
+
+/// Tokens minted to Alice.
+const FUNDS: u64 = 100;
+/// Tokens of the payment Bob claims.
+const CLAIMED: u64 = 30;
+/// Tokens of the payment Bob never claims.
+const UNCLAIMED: u64 = 20;
+/// Blocks Bob has to claim a payment before Alice may take it back.
+const TIMEOUT_BLOCKS: u32 = 5;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("unclaimed_payment")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create Alice and Bob, and fund Alice
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating Alice and Bob, minting {FUNDS} tokens to Alice");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), FUNDS)?,
+        alice.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(faucet.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    wait_for_notes(&mut client, &network, alice.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, alice.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice pays Bob twice, Bob only claims the first payment
+    // -------------------------------------------------------------------------
+    // Both payments are recallable: Bob has TIMEOUT_BLOCKS blocks to consume them, after which
+    // Alice may take back whatever is left.
+    println!("\n[STEP 2] Alice sends Bob {CLAIMED} and {UNCLAIMED} tokens");
+
+    let mut pending: Vec<PendingPayment> = Vec::new();
+    for amount in [CLAIMED, UNCLAIMED] {
+        let (tx_id, payment) = unclaimed::send_recallable(
+            &mut client,
+            alice.id(),
+            bob.id(),
+            vec![FungibleAsset::new(faucet.id(), amount)?.into()],
+            TIMEOUT_BLOCKS,
+        )
+        .await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        println!(
+            "Sent note {}, reclaimable from block {}",
+            payment.note.id(),
+            payment.reclaim_height
+        );
+        pending.push(payment);
+    }
+
+    wait_for_notes(&mut client, &network, bob.id(), 2).await?;
+    let consume_request =
+        TransactionRequestBuilder::new().build_consume_notes(vec![pending[0].note.id()])?;
+    let tx_id = client.submit_with_prover(bob.id(), consume_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("Bob claimed the first payment and ignores the second");
+
+    // Taking a payment back before its deadline fails, whether or not Bob is still around
+    let Err(err) = unclaimed::reclaim(&mut client, alice.id(), &pending[1]).await else {
+        return Err("Alice reclaimed a payment before its deadline".into());
+    };
+    println!("Reclaiming early is refused: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Detect the payment Bob never claimed
+    // -------------------------------------------------------------------------
+    // Alice's store is enough: the synced nullifiers tell which notes were consumed, and the
+    // block height which deadlines passed.
+    println!("\n[STEP 3] Waiting for the deadlines");
+
+    let statuses = loop {
+        let mut statuses = Vec::new();
+        for payment in &pending {
+            statuses.push(unclaimed::status(&mut client, payment).await?);
+        }
+        if !statuses
+            .iter()
+            .any(|status| matches!(status, PaymentStatus::Pending { .. }))
+        {
+            break statuses;
+        }
+        println!("Statuses: {statuses:?}, waiting...");
+        sleep(Duration::from_secs(3)).await;
+    };
+    println!("Statuses: {statuses:?}");
+    assert_eq!(
+        statuses,
+        vec![PaymentStatus::Settled, PaymentStatus::Overdue]
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Reclaim the overdue payment
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Alice takes the unclaimed payment back");
+
+    for (payment, status) in pending.iter().zip(&statuses) {
+        if *status != PaymentStatus::Overdue {
+            continue;
+        }
+        match unclaimed::reclaim(&mut client, alice.id(), payment).await? {
+            Replacement::Replaced(tx_id) => {
+                wait_for_tx(&mut client, tx_id).await?;
+                println!("Reclaimed note {}", payment.note.id());
+            }
+            // Bob woke up just in time, the payment went through after all
+            Replacement::AlreadyConsumed => {
+                println!("Bob claimed note {} at the last moment", payment.note.id())
+            }
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Clean up
+    // -------------------------------------------------------------------------
+    // Every payment is settled now, one way or the other, so Alice stops following them. On
+    // Bob's side, syncing marks the reclaimed note as consumed and it stops showing up as a
+    // payment Bob could still claim.
+    println!("\n[STEP 5] Dropping settled payments");
+
+    let mut still_open = Vec::new();
+    for payment in pending {
+        if unclaimed::status(&mut client, &payment).await? != PaymentStatus::Settled {
+            still_open.push(payment);
+        }
+    }
+    let pending = still_open;
+    assert!(pending.is_empty());
+
+    client.sync_state().await?;
+    let stale = client.get_consumable_notes(Some(bob.id())).await?;
+    assert!(stale.is_empty());
+    println!("No payment left to follow, no stale note left for Bob");
+
+    // -------------------------------------------------------------------------
+    // STEP 6: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 6] Checking balances");
+
+    let alice_balance = balance(&client, alice.id(), faucet.id()).await?;
+    let bob_balance = balance(&client, bob.id(), faucet.id()).await?;
+    println!("Alice: {alice_balance}, Bob: {bob_balance}");
+    assert_eq!(alice_balance, FUNDS - CLAIMED);
+    assert_eq!(bob_balance, CLAIMED);
+
+    Ok(())
+}
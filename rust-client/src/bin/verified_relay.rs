@@ -0,0 +1,252 @@
+use rand::rngs::StdRng;
+use std::{fs, sync::Arc};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{create_p2id_note, NoteDetails, NoteFile, NoteTag, NoteType},
+    rpc::GrpcClient,
+    transaction::{OutputNote, TransactionRequestBuilder},
+    utils::{Deserializable, Serializable},
+    Client, Felt,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    ephemeral::EphemeralClient,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    relay::{self, RelayError, RelayPolicy, RelayRequest},
+};
+
+/// Tokens minted to Alice.
+const FUNDS: u64 = 100;
+/// Tokens Alice pays Bob through the relayer.
+const PAYMENT: u64 = 30;
+/// Tokens the relayer charges per transaction.
+const RELAY_FEE: u64 = 2;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize the relayer client, which never holds a key of Alice
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("verified_relay")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client.clone())
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Set up the relayer, Bob, and Alice on a separate client
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating the relayer, Bob, and Alice on a separate client");
+
+    let relayer = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+
+    let mut alice_client = EphemeralClient::new(endpoint.clone()).await?;
+    alice_client.sync_state().await?;
+    let alice_keystore = alice_client.keystore().clone();
+    let alice = create_basic_account(&mut alice_client, &alice_keystore).await?;
+    let faucet = create_basic_faucet(
+        &mut alice_client,
+        &alice_keystore,
+        TokenSymbol::new("MID").unwrap(),
+    )
+    .await?;
+    println!("Relayer: {}", relayer.id().to_bech32(network.network_id()));
+    println!("Alice:   {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:     {}", bob.id().to_bech32(network.network_id()));
+
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(faucet.id(), FUNDS)?,
+        alice.id(),
+        NoteType::Public,
+        alice_client.rng(),
+    )?;
+    let tx_id = alice_client
+        .submit_with_prover(faucet.id(), mint_request)
+        .await?;
+    wait_for_tx(&mut alice_client, tx_id).await?;
+    wait_for_notes(&mut alice_client, &network, alice.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut alice_client, alice.id()).await?;
+    wait_for_tx(&mut alice_client, tx_id).await?;
+
+    let policy = RelayPolicy {
+        relayer_id: relayer.id(),
+        fee: FungibleAsset::new(faucet.id(), RELAY_FEE)?,
+    };
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Alice proves a payment and hands it over
+    // -------------------------------------------------------------------------
+    // The payment to Bob is private: the relayer only learns about it what Alice chooses to
+    // disclose. The fee note is disclosed in full so the relayer can check it gets paid.
+    println!("\n[STEP 2] Alice proves a payment of {PAYMENT} MID to Bob with a fee of {RELAY_FEE}");
+
+    let payment = create_p2id_note(
+        alice.id(),
+        bob.id(),
+        vec![FungibleAsset::new(faucet.id(), PAYMENT)?.into()],
+        NoteType::Private,
+        Felt::new(0),
+        alice_client.rng(),
+    )?;
+    let fee = create_p2id_note(
+        alice.id(),
+        relayer.id(),
+        vec![policy.fee.into()],
+        NoteType::Public,
+        Felt::new(0),
+        alice_client.rng(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![
+            OutputNote::Full(payment.clone()),
+            OutputNote::Full(fee),
+        ])
+        .build()?;
+    let (tx_result, relay_request) = relay::prepare(&mut alice_client, alice.id(), request).await?;
+
+    // Any channel works for the handover, here it is a file in the state directory
+    let request_path = context.dir().join("relay_request.bin");
+    fs::write(&request_path, relay_request.to_bytes())?;
+    println!(
+        "Proven transaction {:?} written to {} ({} bytes)",
+        relay_request.transaction.id(),
+        request_path.display(),
+        relay_request.to_bytes().len()
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 3: The relayer verifies the transaction, then submits it
+    // -------------------------------------------------------------------------
+    // The relayer reads nothing but the bytes it received. It checks the proof and every note
+    // Alice claims before spending anything on the submission.
+    println!("\n[STEP 3] The relayer verifies and submits the transaction");
+
+    let received = RelayRequest::read_from_bytes(&fs::read(&request_path)?)?;
+    println!(
+        "Transaction of {} creating {} notes, {} of them disclosed",
+        received
+            .transaction
+            .account_id()
+            .to_bech32(network.network_id()),
+        received.transaction.output_notes().num_notes(),
+        received.claimed_notes.len()
+    );
+    let block_num = relay::relay(rpc_client.as_ref(), &policy, received).await?;
+    println!("Proof and fee verified, submitted at block {block_num}");
+
+    // The relayer reports the block back, and the client of Alice applies the transaction
+    alice_client
+        .apply_transaction(&tx_result, block_num)
+        .await?;
+    wait_for_tx(&mut alice_client, tx_result.executed_transaction().id()).await?;
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Requests the relayer refuses
+    // -------------------------------------------------------------------------
+    // A transaction without a fee is refused even though its proof is valid. Claiming a fee
+    // note the transaction does not create does not help: the note id gives it away.
+    println!("\n[STEP 4] Handing over a transaction without a fee");
+
+    let unpaid = create_p2id_note(
+        alice.id(),
+        bob.id(),
+        vec![FungibleAsset::new(faucet.id(), PAYMENT)?.into()],
+        NoteType::Private,
+        Felt::new(0),
+        alice_client.rng(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(unpaid)])
+        .build()?;
+    let (_, mut unpaid_request) = relay::prepare(&mut alice_client, alice.id(), request).await?;
+
+    let Err(RelayError::MissingFee(_)) = policy.check(&unpaid_request) else {
+        return Err("the relayer accepted a transaction without a fee".into());
+    };
+    println!("Refused: no fee");
+
+    let forged_fee = create_p2id_note(
+        alice.id(),
+        relayer.id(),
+        vec![policy.fee.into()],
+        NoteType::Public,
+        Felt::new(0),
+        alice_client.rng(),
+    )?;
+    unpaid_request.claimed_notes.push(forged_fee);
+    let Err(err @ RelayError::UnknownNote(_)) = policy.check(&unpaid_request) else {
+        return Err("the relayer accepted a fee the transaction does not pay".into());
+    };
+    println!("Refused: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Collecting the payment and the fee");
+
+    wait_for_notes(&mut client, &network, relayer.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, relayer.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    // Bob gets the details of the private payment from Alice, off-chain, and imports them
+    let details: NoteDetails = payment.into();
+    client
+        .import_note(NoteFile::NoteDetails {
+            details,
+            after_block_num: block_num,
+            tag: Some(NoteTag::from_account_id(bob.id())),
+        })
+        .await?;
+    wait_for_notes(&mut client, &network, bob.id(), 1).await?;
+    let tx_id = consume_all_notes(&mut client, bob.id()).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+
+    let relayer_balance = balance(&client, relayer.id(), faucet.id()).await?;
+    let bob_balance = balance(&client, bob.id(), faucet.id()).await?;
+    let alice_balance = balance(&alice_client, alice.id(), faucet.id()).await?;
+    println!("Relayer balance: {relayer_balance}");
+    println!("Bob balance:     {bob_balance}");
+    println!("Alice balance:   {alice_balance}");
+
+    assert_eq!(relayer_balance, RELAY_FEE);
+    assert_eq!(bob_balance, PAYMENT);
+    assert_eq!(alice_balance, FUNDS - PAYMENT - RELAY_FEE);
+
+    Ok(())
+}
@@ -0,0 +1,383 @@
+use miden_lib::account::auth::NoAuth;
+use miden_lib::note::WellKnownNote;
+use miden_lib::transaction::TransactionKernel;
+use rand::{rngs::StdRng, RngCore};
+use std::{fs, path::Path, sync::Arc};
+
+use miden_client::{
+    account::{Account, AccountId},
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    rpc::{domain::account::FetchedAccount, GrpcClient, NodeRpcClient},
+    transaction::{OutputNote, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountCode, AccountComponent, AccountStorageMode, AccountType, StorageSlot,
+    },
+    ZERO,
+};
+use rust_client::{
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, create_library,
+        wait_for_notes, wait_for_tx,
+    },
+};
+
+/// Minimum deposit required by the deposit counter.
+const MIN_DEPOSIT: u64 = 5;
+
+/// Procedure appended to the deposit counter by the look-alike contract. Everything else is
+/// unchanged, so notes written for the genuine contract work with it too.
+const BACKDOOR: &str = "
+#! Moves an asset of the contract into the output note `note_idx`, for anyone who asks.
+#!
+#! Inputs:  [ASSET, note_idx]
+#! Outputs: []
+export.withdraw
+    exec.native_account::remove_asset
+    # => [ASSET, note_idx]
+
+    exec.output_note::add_asset
+    # => [note_idx]
+
+    drop exec.sys::truncate_stack
+    # => []
+end
+";
+
+/// Builds a deposit counter from `code`, accepting deposits issued by `faucet_id`.
+fn deposit_counter(
+    code: &str,
+    faucet_id: AccountId,
+    seed: [u8; 32],
+) -> Result<Account, Box<dyn std::error::Error>> {
+    let deposit_config: Word = [
+        Felt::new(MIN_DEPOSIT),
+        ZERO,
+        faucet_id.suffix(),
+        faucet_id.prefix().as_felt(),
+    ]
+    .into();
+
+    let component = AccountComponent::compile(
+        code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(deposit_config),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    Ok(AccountBuilder::new(seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?)
+}
+
+/// Builds an interaction note incrementing the counter by 1, together with the P2ID note the
+/// contract creates when it refunds the deposit.
+fn interaction_note(
+    sender: AccountId,
+    contract_id: AccountId,
+    script: NoteScript,
+    deposit: FungibleAsset,
+    serial_num: Word,
+) -> Result<(Note, Note), Box<dyn std::error::Error>> {
+    let refund_tag = NoteTag::from_account_id(sender);
+
+    let inputs = NoteInputs::new(vec![Felt::new(1), refund_tag.into()])?;
+    let recipient = NoteRecipient::new(serial_num, script, inputs);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(contract_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    let note = Note::new(NoteAssets::new(vec![deposit.into()])?, metadata, recipient);
+
+    // The note script derives the refund serial number by incrementing its own serial number
+    let refund_serial_num = [
+        serial_num[0],
+        serial_num[1],
+        serial_num[2],
+        Felt::new(serial_num[3].as_int() + 1),
+    ]
+    .into();
+    let refund_recipient = NoteRecipient::new(
+        refund_serial_num,
+        WellKnownNote::P2ID.script(),
+        NoteInputs::new(vec![sender.suffix(), sender.prefix().as_felt()])?,
+    );
+    let refund_metadata = NoteMetadata::new(
+        contract_id,
+        NoteType::Public,
+        refund_tag,
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    let refund_note = Note::new(
+        NoteAssets::new(vec![deposit.into()])?,
+        refund_metadata,
+        refund_recipient,
+    );
+
+    Ok((note, refund_note))
+}
+
+/// Sends a deposit from `sender` to `contract_id`, then consumes it on the contract side as
+/// its operator would. Returns once the refund reached `sender`.
+async fn deposit(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    sender: AccountId,
+    contract_id: AccountId,
+    script: NoteScript,
+    asset: FungibleAsset,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let serial_num = client.rng().draw_word();
+    let (note, refund) = interaction_note(sender, contract_id, script, asset, serial_num)?;
+
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    client.submit_with_prover(sender, request).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .expected_output_recipients(vec![refund.recipient().clone()])
+        .build()?;
+    let tx_id = client.submit_with_prover(contract_id, request).await?;
+    wait_for_tx(client, tx_id).await?;
+
+    wait_for_notes(client, network, sender, 1).await?;
+    consume_all_notes(client, sender).await?;
+    Ok(())
+}
+
+/// Fetches the code of `account_id` from the node and checks that it is exactly `expected`.
+///
+/// Private accounts only publish a commitment to their state, so their code cannot be checked
+/// and they are never trusted.
+async fn verify_code(
+    rpc: &GrpcClient,
+    account_id: AccountId,
+    expected: &AccountCode,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let account = match rpc.get_account_details(account_id).await? {
+        FetchedAccount::Public(account, _) => account,
+        FetchedAccount::Private(..) => {
+            println!("  private account, its code cannot be checked");
+            return Ok(false);
+        }
+    };
+    let code = account.code();
+
+    // Exporting the procedures we call is not enough: any additional procedure can move the
+    // assets we send. Only the commitment covers every procedure, including the auth one.
+    let expected_roots: Vec<Word> = expected.procedure_roots().collect();
+    let roots: Vec<Word> = code.procedure_roots().collect();
+    let missing = expected_roots
+        .iter()
+        .filter(|root| !roots.contains(root))
+        .count();
+    let extra = roots
+        .iter()
+        .filter(|root| !expected_roots.contains(root))
+        .count();
+
+    println!(
+        "  expected code commitment: {}",
+        expected.commitment().to_hex()
+    );
+    println!("  fetched code commitment:  {}", code.commitment().to_hex());
+    println!("  missing procedures: {missing}, extra procedures: {extra}");
+
+    Ok(code.commitment() == expected.commitment())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("verify_counterparty")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client.clone())
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create Alice, Bob, Mallory and the deposit faucet
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and funding them");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let mallory = create_basic_account(&mut client, &keystore).await?;
+    let faucet =
+        create_basic_faucet(&mut client, &keystore, TokenSymbol::new("MID").unwrap()).await?;
+    println!("Alice:   {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:     {}", bob.id().to_bech32(network.network_id()));
+    println!("Mallory: {}", mallory.id().to_bech32(network.network_id()));
+
+    let starting_balance: u64 = 10;
+    for account_id in [alice.id(), bob.id(), mallory.id()] {
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            FungibleAsset::new(faucet.id(), starting_balance)?,
+            account_id,
+            NoteType::Public,
+            client.rng(),
+        )?;
+        client.submit_with_prover(faucet.id(), mint_request).await?;
+    }
+    for account_id in [alice.id(), bob.id(), mallory.id()] {
+        wait_for_notes(&mut client, &network, account_id, 1).await?;
+        consume_all_notes(&mut client, account_id).await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Bob deploys a deposit counter, Mallory a look-alike
+    // -------------------------------------------------------------------------
+    // Both contracts go on-chain with a first deposit of their operator. Mallory's contract
+    // behaves exactly like Bob's, but lets anyone withdraw its assets.
+    println!("\n[STEP 2] Deploying the genuine contract and a look-alike");
+
+    let contract_code = fs::read_to_string(Path::new("../masm/accounts/deposit_counter.masm"))?;
+    let look_alike_code = format!("{contract_code}{BACKDOOR}");
+
+    let library = create_library(&contract_code, "external_contract::deposit_counter")?;
+    let note_code = fs::read_to_string(Path::new("../masm/notes/deposit_increment_note.masm"))?;
+    let note_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?;
+    let min_deposit = FungibleAsset::new(faucet.id(), MIN_DEPOSIT)?;
+
+    let mut contracts = Vec::new();
+    for (operator, code) in [(bob.id(), &contract_code), (mallory.id(), &look_alike_code)] {
+        let mut seed = [0_u8; 32];
+        client.rng().fill_bytes(&mut seed);
+        let contract = deposit_counter(code, faucet.id(), seed)?;
+        client.add_account(&contract, false).await?;
+
+        deposit(
+            &mut client,
+            &network,
+            operator,
+            contract.id(),
+            note_script.clone(),
+            min_deposit,
+        )
+        .await?;
+        contracts.push(contract.id());
+    }
+    println!(
+        "Bob's contract:     {}",
+        contracts[0].to_bech32(network.network_id())
+    );
+    println!(
+        "Mallory's contract: {}",
+        contracts[1].to_bech32(network.network_id())
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Compile the code Alice expects
+    // -------------------------------------------------------------------------
+    // The code commitment depends on the components and the auth procedure only: storage and
+    // seed do not change it, so any instance built from the audited source will do.
+    println!("\n[STEP 3] Compiling the expected code from the audited source");
+
+    let expected = deposit_counter(&contract_code, faucet.id(), [0_u8; 32])?
+        .code()
+        .clone();
+    println!(
+        "Expected code commitment: {}",
+        expected.commitment().to_hex()
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Verify each counterparty before depositing
+    // -------------------------------------------------------------------------
+    // Alice only knows the ids of the contracts. She fetches their code from the node and
+    // deposits only into the one whose code is exactly what she expects.
+    println!("\n[STEP 4] Alice verifies the contracts before depositing");
+
+    for contract_id in &contracts {
+        println!("{}", contract_id.to_bech32(network.network_id()));
+        if !verify_code(&rpc_client, *contract_id, &expected).await? {
+            println!("  code does not match, refusing to deposit");
+            continue;
+        }
+
+        println!("  code matches, depositing {MIN_DEPOSIT} MID");
+        deposit(
+            &mut client,
+            &network,
+            alice.id(),
+            *contract_id,
+            note_script.clone(),
+            min_deposit,
+        )
+        .await?;
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Check the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking the contract counters");
+
+    client.sync_state().await?;
+    let mut counts = Vec::new();
+    for contract_id in &contracts {
+        let record = client.get_account(*contract_id).await?.unwrap();
+        counts.push(record.account().storage().get_item(0)?[3].as_int());
+    }
+    let alice_balance = client
+        .get_account(alice.id())
+        .await?
+        .unwrap()
+        .account()
+        .vault()
+        .get_balance(faucet.id())?;
+
+    println!("Bob's counter:     {}", counts[0]);
+    println!("Mallory's counter: {}", counts[1]);
+    println!("Alice balance:     {alice_balance}");
+
+    // Alice interacted with Bob's contract only and got her deposit back
+    assert_eq!(counts, [2, 1]);
+    assert_eq!(alice_balance, starting_balance);
+
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use rand::rngs::StdRng;
+use std::{sync::Arc, time::Duration};
+
+use miden_client::{
+    account::AccountId,
+    asset::{FungibleAsset, TokenSymbol},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    rpc::GrpcClient,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    cli::{Network, SubmitWithProver},
+    context::TutorialContext,
+    helpers::{
+        consume_all_notes, create_basic_account, create_basic_faucet, wait_for_notes, wait_for_tx,
+    },
+    vesting::{self, Vesting},
+};
+use tokio::time::sleep;
+
+/// Tokens vesting for the beneficiary.
+const TOTAL: u64 = 1_000;
+/// Blocks over which the tokens unlock, 10 per block.
+const DURATION_BLOCKS: u32 = 100;
+/// Blocks the beneficiary waits before claiming what vested so far.
+const CLAIM_AFTER_BLOCKS: u32 = 10;
+
+/// Returns the balance of `account_id` in tokens of `faucet_id`.
+async fn balance(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    faucet_id: AccountId,
+) -> Result<u64, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or("the account is not tracked by the client")?;
+    Ok(record.account().vault().get_balance(faucet_id)?)
+}
+
+/// Reads the current state of the vesting contract `vesting_id`.
+async fn read_vesting(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    vesting_id: AccountId,
+) -> Result<Vesting, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(vesting_id)
+        .await?
+        .ok_or("the vesting contract is not tracked by the client")?;
+    Vesting::from_account(record.account())
+}
+
+/// Waits for a note to `account_id` and consumes it.
+async fn collect(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    account_id: AccountId,
+) -> Result<(), Box<dyn std::error::Error>> {
+    wait_for_notes(client, network, account_id, 1).await?;
+    let tx_id = consume_all_notes(client, account_id).await?;
+    wait_for_tx(client, tx_id).await?;
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("vesting_example")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the employer, the beneficiary and the token
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating accounts and assets");
+
+    let employer = create_basic_account(&mut client, &keystore).await?;
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    println!(
+        "Employer: {}",
+        employer.id().to_bech32(network.network_id())
+    );
+    println!("Alice:    {}", alice.id().to_bech32(network.network_id()));
+
+    let tok = create_basic_faucet(&mut client, &keystore, TokenSymbol::new("TOK").unwrap()).await?;
+    let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+        FungibleAsset::new(tok.id(), TOTAL)?,
+        employer.id(),
+        NoteType::Public,
+        client.rng(),
+    )?;
+    let tx_id = client.submit_with_prover(tok.id(), mint_request).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, employer.id()).await?;
+    println!("The employer holds {TOTAL} TOK");
+
+    let deposit_script = vesting::compile_note_script(&client, vesting::DEPOSIT_NOTE_PATH)?;
+    let withdraw_script = vesting::compile_note_script(&client, vesting::WITHDRAW_NOTE_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Deploy and fund the vesting contract
+    // -------------------------------------------------------------------------
+    // The contract records the current block as the start of the vesting. From there, a
+    // hundredth of the tokens unlocks with every block.
+    println!("\n[STEP 2] Deploying the vesting contract for Alice");
+
+    let account =
+        vesting::create_vesting(&mut client, alice.id(), tok.id(), TOTAL, DURATION_BLOCKS).await?;
+    let schedule = Vesting::from_account(&account)?;
+    println!(
+        "Vesting {}: {} TOK per block from block {} to block {}",
+        schedule.id,
+        schedule.rate,
+        schedule.start,
+        schedule.start.as_u32() + schedule.duration
+    );
+
+    let tx_id =
+        vesting::deposit(&mut client, employer.id(), &schedule, TOTAL, deposit_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    println!("The employer deposited {TOTAL} TOK");
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Alice tries to withdraw everything right away
+    // -------------------------------------------------------------------------
+    // The withdrawal note is executed against the contract without being sent first, so the
+    // contract's refusal is all there is to see: nothing reaches the network.
+    println!("\n[STEP 3] Alice asks for all {TOTAL} TOK");
+
+    let early =
+        vesting::withdrawal_request(&mut client, &schedule, TOTAL, withdraw_script.clone())?;
+    let payout = schedule.payout(TOTAL, early.serial_num())?;
+    let Err(err) = vesting::consume_note(&mut client, &schedule, &early, vec![payout]).await else {
+        return Err("the vesting contract paid out unvested tokens".into());
+    };
+    println!("The contract rejects the withdrawal: {err}");
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Alice claims what vested so far
+    // -------------------------------------------------------------------------
+    // The block of a transaction is the block the client last synced to, so Alice can claim
+    // what vested at the sync height of the client, and no more.
+    let claim_at = schedule.start.as_u32() + CLAIM_AFTER_BLOCKS;
+    println!("\n[STEP 4] Waiting for block {claim_at} before claiming");
+
+    while client.get_sync_height().await?.as_u32() < claim_at {
+        sleep(Duration::from_secs(3)).await;
+        client.sync_state().await?;
+    }
+
+    let block_num = client.get_sync_height().await?;
+    let claimed = schedule.claimable_at(block_num);
+    println!("{claimed} TOK vested by block {block_num}");
+
+    let tx_id = vesting::withdraw(&mut client, &schedule, claimed, withdraw_script).await?;
+    wait_for_tx(&mut client, tx_id).await?;
+    collect(&mut client, &network, alice.id()).await?;
+    println!(
+        "Alice received {} TOK",
+        balance(&client, alice.id(), tok.id()).await?
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 5: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 5] Checking balances");
+
+    let schedule = read_vesting(&client, schedule.id).await?;
+    assert!(claimed > 0 && claimed < TOTAL);
+    assert_eq!(schedule.withdrawn, claimed);
+    assert_eq!(balance(&client, alice.id(), tok.id()).await?, claimed);
+    assert_eq!(
+        balance(&client, schedule.id, tok.id()).await?,
+        TOTAL - claimed
+    );
+    println!(
+        "Alice claimed {claimed} TOK, {} TOK stay locked in the contract",
+        TOTAL - claimed
+    );
+
+    Ok(())
+}
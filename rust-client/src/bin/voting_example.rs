@@ -0,0 +1,175 @@
+use rand::rngs::StdRng;
+use std::{sync::Arc, time::Duration};
+
+use miden_client::{
+    account::AccountId, builder::ClientBuilder, keystore::FilesystemKeyStore, rpc::GrpcClient,
+    Client,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rust_client::{
+    context::TutorialContext,
+    helpers::{create_basic_account, wait_for_tx},
+    voting::{self, Ballot, Phase, Poll},
+};
+use tokio::time::sleep;
+
+/// Choices on the ballot, in the order the poll numbers them.
+const CHOICES: [&str; 2] = ["no", "yes"];
+/// Blocks during which each phase lasts.
+const PHASE_BLOCKS: u32 = 15;
+
+/// Reads the current tally of the poll `poll_id`.
+async fn read_poll(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    poll_id: AccountId,
+) -> Result<Poll, Box<dyn std::error::Error>> {
+    let record = client
+        .get_account(poll_id)
+        .await?
+        .ok_or("the poll is not tracked by the client")?;
+    Poll::from_account(record.account())
+}
+
+/// Syncs the client until `poll` reaches `phase`.
+async fn wait_for_phase(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    poll: &Poll,
+    phase: Phase,
+) -> Result<(), Box<dyn std::error::Error>> {
+    while poll.phase_at(client.get_sync_height().await?) != phase {
+        sleep(Duration::from_secs(3)).await;
+        client.sync_state().await?;
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, in a directory of their own for this run
+    let context = TutorialContext::new("voting_example")?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client)
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let sync_summary = client.sync_state().await?;
+    println!("Latest block: {}", sync_summary.block_num);
+
+    // -------------------------------------------------------------------------
+    // STEP 1: Create the voters and the poll
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 1] Creating the voters and the poll");
+
+    let alice = create_basic_account(&mut client, &keystore).await?;
+    let bob = create_basic_account(&mut client, &keystore).await?;
+    let carol = create_basic_account(&mut client, &keystore).await?;
+    println!("Alice: {}", alice.id().to_bech32(network.network_id()));
+    println!("Bob:   {}", bob.id().to_bech32(network.network_id()));
+    println!("Carol: {}", carol.id().to_bech32(network.network_id()));
+
+    let commit_end = client.get_sync_height().await?.as_u32() + PHASE_BLOCKS;
+    let reveal_end = commit_end + PHASE_BLOCKS;
+    let account = voting::create_poll(
+        &mut client,
+        CHOICES.len() as u32,
+        commit_end.into(),
+        reveal_end.into(),
+    )
+    .await?;
+    let poll = Poll::from_account(&account)?;
+    println!(
+        "Poll {}: commitments until block {commit_end}, reveals until block {reveal_end}",
+        poll.id
+    );
+
+    let commit_script = voting::compile_note_script(&client, voting::COMMIT_NOTE_PATH)?;
+    let reveal_script = voting::compile_note_script(&client, voting::REVEAL_NOTE_PATH)?;
+
+    // -------------------------------------------------------------------------
+    // STEP 2: Every voter commits to a ballot
+    // -------------------------------------------------------------------------
+    // Only the hash of a ballot is sent, so the poll and everybody reading it see who voted
+    // but not how. Each voter keeps its ballot, choice and salt, for the reveal.
+    println!("\n[STEP 2] Alice, Bob and Carol commit to their ballots");
+
+    let voters = [(alice.id(), 1), (bob.id(), 1), (carol.id(), 0)];
+    let mut ballots = Vec::new();
+    for (voter, choice) in voters {
+        let ballot = Ballot::new(choice, client.rng());
+        let tx_id =
+            voting::commit(&mut client, voter, &poll, &ballot, commit_script.clone()).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        println!("{voter} committed to {}", ballot.commitment().to_hex());
+        ballots.push((voter, ballot));
+    }
+
+    let state = read_poll(&client, poll.id).await?;
+    println!(
+        "{} commitments registered, tally {:?}",
+        state.num_commitments, state.tally
+    );
+
+    // -------------------------------------------------------------------------
+    // STEP 3: Every voter reveals its ballot
+    // -------------------------------------------------------------------------
+    // The poll only counts a ballot hashing to the commitment of its voter, so a voter cannot
+    // change its vote once it sees how the others voted.
+    println!("\n[STEP 3] Waiting for block {commit_end} to reveal the ballots");
+    wait_for_phase(&mut client, &poll, Phase::Reveal).await?;
+
+    let (carol_id, carol_ballot) = ballots[2];
+    let switched = Ballot {
+        choice: 1,
+        ..carol_ballot
+    };
+    let Err(err) = voting::reveal(
+        &mut client,
+        carol_id,
+        &poll,
+        &switched,
+        reveal_script.clone(),
+    )
+    .await
+    else {
+        return Err("the poll counted a ballot not matching its commitment".into());
+    };
+    println!("Carol cannot switch her vote: {err}");
+
+    for (voter, ballot) in &ballots {
+        let tx_id =
+            voting::reveal(&mut client, *voter, &poll, ballot, reveal_script.clone()).await?;
+        wait_for_tx(&mut client, tx_id).await?;
+        println!("{voter} revealed \"{}\"", CHOICES[ballot.choice as usize]);
+    }
+
+    // -------------------------------------------------------------------------
+    // STEP 4: Verify the outcome
+    // -------------------------------------------------------------------------
+    println!("\n[STEP 4] Waiting for block {reveal_end} to read the final tally");
+    wait_for_phase(&mut client, &poll, Phase::Closed).await?;
+
+    let state = read_poll(&client, poll.id).await?;
+    for (choice, votes) in CHOICES.iter().zip(&state.tally) {
+        println!("{choice}: {votes}");
+    }
+
+    assert_eq!(state.num_commitments, 3);
+    assert_eq!(state.num_reveals, 3);
+    assert_eq!(state.tally, [1, 2]);
+    println!("\"yes\" wins with 2 votes to 1");
+
+    Ok(())
+}
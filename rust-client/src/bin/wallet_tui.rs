@@ -0,0 +1,620 @@
+use rand::rngs::StdRng;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use miden_client::{
+    account::AccountId,
+    address::NetworkId,
+    asset::{Asset, FungibleAsset},
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    note::{create_p2id_note, NoteId, NoteType},
+    rpc::GrpcClient,
+    store::NoteFilter,
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use qrcode::{render::unicode::Dense1x2, QrCode};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{Block, List, ListItem, ListState, Paragraph, Tabs, Wrap},
+    DefaultTerminal, Frame,
+};
+use rust_client::{
+    cli::SubmitWithProver,
+    context::TutorialContext,
+    devnet,
+    format::{parse_amount, AssetFormatter},
+    known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH},
+};
+
+/// How often the wallet syncs with the node on its own.
+const SYNC_INTERVAL: Duration = Duration::from_secs(15);
+/// How long to wait for a key press before checking whether a sync is due.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+const ACCOUNTS_HELP: &str = "up/down: select account  tab: next view  r: sync  q: quit";
+const BALANCE_HELP: &str = "tab: next view  r: sync  q: quit";
+const SEND_HELP: &str = "up/down: field  left/right: token  enter: send  tab: next view  esc: quit";
+const RECEIVE_HELP: &str = "tab: next view  r: sync  q: quit";
+const NOTES_HELP: &str = "c: consume all  tab: next view  r: sync  q: quit";
+
+/// Views of the wallet, in the order of the tabs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Accounts,
+    Balance,
+    Send,
+    Receive,
+    Notes,
+}
+
+impl View {
+    const ALL: [View; 5] = [
+        View::Accounts,
+        View::Balance,
+        View::Send,
+        View::Receive,
+        View::Notes,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Accounts => "Accounts",
+            Self::Balance => "Balance",
+            Self::Send => "Send",
+            Self::Receive => "Receive",
+            Self::Notes => "Pending notes",
+        }
+    }
+
+    fn help(self) -> &'static str {
+        match self {
+            Self::Accounts => ACCOUNTS_HELP,
+            Self::Balance => BALANCE_HELP,
+            Self::Send => SEND_HELP,
+            Self::Receive => RECEIVE_HELP,
+            Self::Notes => NOTES_HELP,
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|view| *view == self).unwrap_or(0)
+    }
+
+    /// Returns the view `step` tabs away, wrapping around.
+    fn shift(self, step: isize) -> Self {
+        let count = Self::ALL.len() as isize;
+        Self::ALL[(self.index() as isize + step).rem_euclid(count) as usize]
+    }
+}
+
+/// A regular account of the store, with the assets of its vault.
+struct WalletAccount {
+    id: AccountId,
+    name: String,
+    assets: Vec<Asset>,
+}
+
+impl WalletAccount {
+    /// Returns the fungible assets of the vault, the tokens the account can send.
+    fn tokens(&self) -> Vec<FungibleAsset> {
+        self.assets
+            .iter()
+            .filter_map(|asset| match asset {
+                Asset::Fungible(fungible) => Some(*fungible),
+                Asset::NonFungible(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// A note the selected account can consume, or a note the client expects but which is not
+/// committed yet.
+struct PendingNote {
+    id: NoteId,
+    contents: String,
+    consumable: bool,
+}
+
+/// Field of the send form receiving the typed characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Recipient,
+    Token,
+    Amount,
+}
+
+#[derive(Debug)]
+struct SendForm {
+    recipient: String,
+    token: usize,
+    amount: String,
+    field: Field,
+}
+
+/// What the main loop has to do after a key press. Everything touching the client runs there,
+/// outside of the key handling.
+enum Action {
+    None,
+    Sync,
+    Send,
+    ConsumeNotes,
+    Quit,
+}
+
+struct App {
+    view: View,
+    accounts: Vec<WalletAccount>,
+    selection: ListState,
+    notes: Vec<PendingNote>,
+    form: SendForm,
+    block_num: u32,
+    status: String,
+    formatter: AssetFormatter,
+    known_accounts: KnownAccounts,
+    network_id: NetworkId,
+}
+
+impl App {
+    fn selected(&self) -> Option<&WalletAccount> {
+        self.accounts.get(self.selection.selected()?)
+    }
+
+    /// Handles a key press and returns what the main loop has to do next.
+    fn handle_key(&mut self, code: KeyCode) -> Action {
+        match code {
+            KeyCode::Esc => return Action::Quit,
+            KeyCode::Tab => {
+                self.view = self.view.shift(1);
+                return Action::None;
+            }
+            KeyCode::BackTab => {
+                self.view = self.view.shift(-1);
+                return Action::None;
+            }
+            _ => {}
+        }
+
+        // The send form takes every other key as input
+        if self.view == View::Send {
+            return self.handle_form_key(code);
+        }
+
+        match code {
+            KeyCode::Char('q') => Action::Quit,
+            KeyCode::Char('r') => Action::Sync,
+            KeyCode::Char('c') if self.view == View::Notes => Action::ConsumeNotes,
+            KeyCode::Up if self.view == View::Accounts => {
+                self.selection.select_previous();
+                Action::Sync
+            }
+            KeyCode::Down if self.view == View::Accounts => {
+                self.selection.select_next();
+                Action::Sync
+            }
+            _ => Action::None,
+        }
+    }
+
+    fn handle_form_key(&mut self, code: KeyCode) -> Action {
+        let token_count = self.token_count();
+        let form = &mut self.form;
+        match (code, form.field) {
+            (KeyCode::Enter, _) => return Action::Send,
+            (KeyCode::Up, Field::Amount) => form.field = Field::Token,
+            (KeyCode::Up, _) => form.field = Field::Recipient,
+            (KeyCode::Down, Field::Recipient) => form.field = Field::Token,
+            (KeyCode::Down, _) => form.field = Field::Amount,
+            (KeyCode::Left, Field::Token) if token_count > 0 => {
+                form.token = (form.token + token_count - 1) % token_count;
+            }
+            (KeyCode::Right, Field::Token) if token_count > 0 => {
+                form.token = (form.token + 1) % token_count;
+            }
+            (KeyCode::Backspace, Field::Recipient) => {
+                form.recipient.pop();
+            }
+            (KeyCode::Backspace, Field::Amount) => {
+                form.amount.pop();
+            }
+            (KeyCode::Char(c), Field::Recipient) => form.recipient.push(c),
+            (KeyCode::Char(c), Field::Amount) if c.is_ascii_digit() || c == '.' => {
+                form.amount.push(c);
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    /// Returns the number of tokens the selected account can send.
+    fn token_count(&self) -> usize {
+        self.selected().map_or(0, |account| account.tokens().len())
+    }
+
+    /// Returns the asset described by the send form.
+    fn form_asset(&self) -> Result<FungibleAsset, Box<dyn std::error::Error>> {
+        let tokens = self.selected().ok_or("no account selected")?.tokens();
+        let held = tokens
+            .get(self.form.token)
+            .ok_or("the account holds no token")?;
+        let decimals = self
+            .formatter
+            .token(held.faucet_id())
+            .map_or(0, |token| token.decimals);
+
+        let amount = parse_amount(&self.form.amount, decimals)?;
+        if amount == 0 || amount > held.amount() {
+            return Err(format!(
+                "the amount must be between 0 and {}",
+                self.formatter.format(&Asset::from(*held))
+            )
+            .into());
+        }
+        Ok(FungibleAsset::new(held.faucet_id(), amount)?)
+    }
+}
+
+/// Syncs the client and reloads the accounts, their assets and the pending notes of the
+/// selected account.
+async fn sync(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    rpc_client: &GrpcClient,
+    app: &mut App,
+) -> Result<(), Box<dyn std::error::Error>> {
+    app.block_num = client.sync_state().await?.block_num.as_u32();
+
+    let mut accounts = Vec::new();
+    for (header, _) in client.get_account_headers().await? {
+        // Faucets issue tokens rather than hold them
+        if header.id().is_faucet() {
+            continue;
+        }
+        let Some(record) = client.get_account(header.id()).await? else {
+            continue;
+        };
+        let assets: Vec<Asset> = record.account().vault().assets().collect();
+        for asset in &assets {
+            if let Asset::Fungible(fungible) = asset {
+                app.formatter
+                    .load_token(rpc_client, fungible.faucet_id())
+                    .await?;
+            }
+        }
+        accounts.push(WalletAccount {
+            id: header.id(),
+            name: app
+                .known_accounts
+                .display(header.id(), app.network_id.clone()),
+            assets,
+        });
+    }
+
+    // Keep the same account selected when the list changes
+    let selected_id = app.selected().map(|account| account.id);
+    app.selection.select(
+        selected_id
+            .and_then(|id| accounts.iter().position(|account| account.id == id))
+            .or((!accounts.is_empty()).then_some(0)),
+    );
+    app.accounts = accounts;
+
+    app.notes.clear();
+    if let Some(account_id) = app.selected().map(|account| account.id) {
+        for (note, _) in client.get_consumable_notes(Some(account_id)).await? {
+            let mut contents = Vec::new();
+            for asset in note.assets().iter() {
+                if let Asset::Fungible(fungible) = asset {
+                    app.formatter
+                        .load_token(rpc_client, fungible.faucet_id())
+                        .await?;
+                }
+                contents.push(app.formatter.format(asset));
+            }
+            app.notes.push(PendingNote {
+                id: note.id(),
+                contents: contents.join(", "),
+                consumable: true,
+            });
+        }
+    }
+    for note in client.get_input_notes(NoteFilter::Expected).await? {
+        app.notes.push(PendingNote {
+            id: note.id(),
+            contents: "not yet committed".to_string(),
+            consumable: false,
+        });
+    }
+    Ok(())
+}
+
+/// Sends the asset of the send form to its recipient with a P2ID note.
+async fn send(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    app: &App,
+) -> Result<TransactionId, Box<dyn std::error::Error>> {
+    let sender = app.selected().ok_or("no account selected")?.id;
+    let recipient = app
+        .known_accounts
+        .resolve(&app.form.recipient, app.network_id.clone())?;
+    let asset = app.form_asset()?;
+
+    let note = create_p2id_note(
+        sender,
+        recipient,
+        vec![asset.into()],
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note)])
+        .build()?;
+    Ok(client.submit_with_prover(sender, request).await?)
+}
+
+/// Consumes every consumable note of the selected account in a single transaction.
+async fn consume_notes(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    app: &App,
+) -> Result<TransactionId, Box<dyn std::error::Error>> {
+    let account_id = app.selected().ok_or("no account selected")?.id;
+    let note_ids: Vec<NoteId> = app
+        .notes
+        .iter()
+        .filter(|note| note.consumable)
+        .map(|note| note.id)
+        .collect();
+    if note_ids.is_empty() {
+        return Err("no note to consume".into());
+    }
+
+    let request = TransactionRequestBuilder::new().build_consume_notes(note_ids)?;
+    Ok(client.submit_with_prover(account_id, request).await?)
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let [tabs_area, body_area, status_area] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+        Constraint::Length(4),
+    ])
+    .areas(frame.area());
+
+    let tabs = Tabs::new(View::ALL.map(View::title))
+        .select(app.view.index())
+        .highlight_style(Style::new().bold().reversed())
+        .block(Block::bordered().title(format!(" Miden wallet - block {} ", app.block_num)));
+    frame.render_widget(tabs, tabs_area);
+
+    match app.view {
+        View::Accounts => draw_accounts(frame, app, body_area),
+        View::Balance => draw_balance(frame, app, body_area),
+        View::Send => draw_send(frame, app, body_area),
+        View::Receive => draw_receive(frame, app, body_area),
+        View::Notes => draw_notes(frame, app, body_area),
+    }
+
+    let status = Paragraph::new(vec![
+        Line::from(app.status.as_str()),
+        Line::from(app.view.help()),
+    ])
+    .wrap(Wrap { trim: true })
+    .block(Block::bordered());
+    frame.render_widget(status, status_area);
+}
+
+fn draw_accounts(frame: &mut Frame, app: &mut App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .accounts
+        .iter()
+        .map(|account| {
+            ListItem::new(format!(
+                "{:<44} {} assets",
+                account.name,
+                account.assets.len()
+            ))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(Block::bordered().title(" Accounts of the store "))
+        .highlight_style(Style::new().reversed())
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, area, &mut app.selection);
+}
+
+fn draw_balance(frame: &mut Frame, app: &App, area: Rect) {
+    let lines: Vec<Line> = match app.selected() {
+        Some(account) if account.assets.is_empty() => vec![Line::from("The vault is empty")],
+        Some(account) => account
+            .assets
+            .iter()
+            .map(|asset| Line::from(app.formatter.format(asset)))
+            .collect(),
+        None => vec![Line::from("No account selected")],
+    };
+    let title = app.selected().map_or(String::new(), |account| {
+        format!(" Balance of {} ", account.name)
+    });
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(title)),
+        area,
+    );
+}
+
+fn draw_send(frame: &mut Frame, app: &App, area: Rect) {
+    let token = app
+        .selected()
+        .and_then(|account| account.tokens().get(app.form.token).copied())
+        .map_or("no token to send".to_string(), |held| {
+            format!("{} available", app.formatter.format(&Asset::from(held)))
+        });
+    let field = |name: &str, value: &str, field: Field| {
+        let line = Line::from(format!("{name:<10} {value}"));
+        if app.form.field == field {
+            line.reversed()
+        } else {
+            line
+        }
+    };
+
+    let lines = vec![
+        field("To", &app.form.recipient, Field::Recipient),
+        field("Token", &token, Field::Token),
+        field("Amount", &app.form.amount, Field::Amount),
+        Line::from(""),
+        Line::from("Send to a bech32 address or a label of known_accounts.json."),
+    ];
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Send with a P2ID note ")),
+        area,
+    );
+}
+
+fn draw_receive(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(account) = app.selected() else {
+        frame.render_widget(
+            Paragraph::new("No account selected").block(Block::bordered()),
+            area,
+        );
+        return;
+    };
+
+    let address = account.id.to_bech32(app.network_id.clone());
+    let mut lines = vec![Line::from(address.clone()), Line::from("")];
+    // Light modules on a dark terminal, the way most terminals are set up
+    if let Ok(code) = QrCode::new(address.as_bytes()) {
+        let qr = code
+            .render::<Dense1x2>()
+            .dark_color(Dense1x2::Light)
+            .light_color(Dense1x2::Dark)
+            .build();
+        lines.extend(qr.lines().map(|line| Line::from(line.to_string())));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(
+        "Notes sent to this address show up under Pending notes once committed.",
+    ));
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Receive ")),
+        area,
+    );
+}
+
+fn draw_notes(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = if app.notes.is_empty() {
+        vec![ListItem::new("No pending notes")]
+    } else {
+        app.notes
+            .iter()
+            .map(|note| ListItem::new(format!("{}  {}", note.id.to_hex(), note.contents)))
+            .collect()
+    };
+    frame.render_widget(
+        List::new(items).block(Block::bordered().title(" Pending notes ")),
+        area,
+    );
+}
+
+/// Runs the wallet until the user quits. Errors of the actions are shown in the status line
+/// rather than ending the wallet.
+async fn run(
+    terminal: &mut DefaultTerminal,
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    rpc_client: &GrpcClient,
+    app: &mut App,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut last_sync = Instant::now();
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+
+        let action = if event::poll(POLL_INTERVAL)? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => app.handle_key(key.code),
+                _ => Action::None,
+            }
+        } else if last_sync.elapsed() >= SYNC_INTERVAL {
+            Action::Sync
+        } else {
+            Action::None
+        };
+
+        let result = match action {
+            Action::None => continue,
+            Action::Quit => return Ok(()),
+            Action::Sync => sync(client, rpc_client, app).await.map(|_| None),
+            Action::Send => send(client, app).await.map(Some),
+            Action::ConsumeNotes => consume_notes(client, app).await.map(Some),
+        };
+        app.status = match result {
+            Ok(Some(tx_id)) => {
+                app.form.amount.clear();
+                format!("Submitted transaction {}", tx_id.to_hex())
+            }
+            Ok(None) => app.status.clone(),
+            Err(err) => format!("Error: {err}"),
+        };
+
+        // Show the effect of a transaction right away
+        if let Err(err) = sync(client, rpc_client, app).await {
+            app.status = format!("Error: {err}");
+        }
+        last_sync = Instant::now();
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Initialize client
+    let network = rust_client::cli::network().await?;
+    let endpoint = network.endpoint().clone();
+    let timeout_ms = 10_000;
+    let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+    // Initialize keystore and store, kept across runs: the wallet shows the accounts of
+    // devnet_setup and of the other binaries sharing its state
+    let context = TutorialContext::persistent(devnet::STATE)?;
+    let keystore = context.keystore()?;
+
+    let store_path = context.store_path();
+
+    let mut client = ClientBuilder::new()
+        .rpc(rpc_client.clone())
+        .sqlite_store(store_path)
+        .authenticator(keystore.clone())
+        .in_debug_mode(true.into())
+        .build()
+        .await?;
+
+    let mut app = App {
+        view: View::Accounts,
+        accounts: Vec::new(),
+        selection: ListState::default(),
+        notes: Vec::new(),
+        form: SendForm {
+            recipient: String::new(),
+            token: 0,
+            amount: String::new(),
+            field: Field::Recipient,
+        },
+        block_num: 0,
+        status: "Syncing...".to_string(),
+        formatter: AssetFormatter::new(network.network_id()),
+        known_accounts: KnownAccounts::load(DEFAULT_REGISTRY_PATH)?,
+        network_id: network.network_id(),
+    };
+    sync(&mut client, &rpc_client, &mut app).await?;
+    app.status = format!("{} accounts in the store", app.accounts.len());
+
+    // The terminal is restored even when the wallet stops on an error
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut client, &rpc_client, &mut app).await;
+    ratatui::restore();
+    result
+}
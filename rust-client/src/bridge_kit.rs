@@ -0,0 +1,191 @@
+//! Building blocks for a lock-and-mint bridge from another chain into Miden.
+//!
+//! A lock-and-mint bridge has three parts:
+//!
+//! - a lock contract on the source chain, which holds deposited tokens and emits a
+//!   [`LockEvent`] per deposit. Relayers read it through the [`LockSource`] trait, which is the
+//!   only piece to implement per source chain,
+//! - one fungible faucet per bridged token on Miden, owned by the relayer. [`MintRoutes`] maps
+//!   source tokens to those faucets and builds the mint transaction for each event,
+//! - a relayer, which polls the lock source, skips events it already minted
+//!   ([`ProcessedLocks`]) and submits the mints. See the `bridge_relayer` binary.
+//!
+//! Bridging back (burn on Miden, unlock on the source chain) mirrors this flow and is left out.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    convert::Infallible,
+    fmt, fs, io,
+    path::Path,
+};
+
+use miden_client::{
+    account::AccountId,
+    asset::FungibleAsset,
+    crypto::FeltRng,
+    note::NoteType,
+    transaction::{TransactionRequest, TransactionRequestBuilder},
+};
+use serde::{Deserialize, Serialize};
+
+/// A deposit locked on the source chain, to be minted on Miden.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockEvent {
+    /// Identifier of the source chain.
+    pub source_chain: u64,
+    /// Deposit counter of the lock contract, unique per source chain.
+    pub nonce: u64,
+    /// Address of the token on the source chain.
+    pub token: String,
+    /// Amount in base units. Bridged faucets use the decimals of the source token.
+    pub amount: u64,
+    /// Miden account receiving the minted tokens.
+    pub recipient: AccountId,
+}
+
+impl LockEvent {
+    /// Returns the key identifying this deposit across polls.
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.source_chain, self.nonce)
+    }
+}
+
+/// Reads deposits from a lock contract on the source chain.
+#[allow(async_fn_in_trait)]
+pub trait LockSource {
+    type Error: std::error::Error;
+
+    /// Returns the lock events observed since the previous call. Sources may return an event
+    /// more than once (e.g. after a reorg or a restart), relayers deduplicate by
+    /// [`LockEvent::key`].
+    async fn poll(&mut self) -> Result<Vec<LockEvent>, Self::Error>;
+}
+
+/// A lock source replaying a fixed list of events, a batch per poll.
+///
+/// Stands in for a real chain in tutorials and tests.
+#[derive(Debug, Default)]
+pub struct MockLockSource {
+    batches: VecDeque<Vec<LockEvent>>,
+}
+
+impl MockLockSource {
+    pub fn new(batches: impl IntoIterator<Item = Vec<LockEvent>>) -> Self {
+        Self {
+            batches: batches.into_iter().collect(),
+        }
+    }
+
+    /// Returns true once all batches have been polled.
+    pub fn is_exhausted(&self) -> bool {
+        self.batches.is_empty()
+    }
+}
+
+impl LockSource for MockLockSource {
+    type Error = Infallible;
+
+    async fn poll(&mut self) -> Result<Vec<LockEvent>, Self::Error> {
+        Ok(self.batches.pop_front().unwrap_or_default())
+    }
+}
+
+/// Errors raised while turning a lock event into a mint.
+#[derive(Debug)]
+pub enum BridgeError {
+    /// No faucet is registered for the token of the event.
+    UnknownToken { source_chain: u64, token: String },
+    /// The amount cannot be minted by the faucet.
+    InvalidAmount(String),
+}
+
+impl fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownToken {
+                source_chain,
+                token,
+            } => write!(f, "no faucet for token {token} of chain {source_chain}"),
+            Self::InvalidAmount(err) => write!(f, "invalid amount: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for BridgeError {}
+
+/// Maps source chain tokens to the Miden faucets minting their bridged version.
+#[derive(Debug, Default)]
+pub struct MintRoutes {
+    faucets: BTreeMap<(u64, String), AccountId>,
+}
+
+impl MintRoutes {
+    /// Registers `faucet_id` as the faucet of `token` on `source_chain`.
+    pub fn insert(&mut self, source_chain: u64, token: impl Into<String>, faucet_id: AccountId) {
+        self.faucets.insert((source_chain, token.into()), faucet_id);
+    }
+
+    /// Returns the faucet minting the bridged version of the event's token.
+    pub fn faucet_for(&self, event: &LockEvent) -> Result<AccountId, BridgeError> {
+        self.faucets
+            .get(&(event.source_chain, event.token.clone()))
+            .copied()
+            .ok_or_else(|| BridgeError::UnknownToken {
+                source_chain: event.source_chain,
+                token: event.token.clone(),
+            })
+    }
+
+    /// Builds the transaction minting `event.amount` to `event.recipient` as a public P2ID
+    /// note. It has to be executed against the faucet returned by [`Self::faucet_for`].
+    pub fn mint_request(
+        &self,
+        event: &LockEvent,
+        rng: &mut impl FeltRng,
+    ) -> Result<TransactionRequest, BridgeError> {
+        let faucet_id = self.faucet_for(event)?;
+        let asset = FungibleAsset::new(faucet_id, event.amount)
+            .map_err(|err| BridgeError::InvalidAmount(err.to_string()))?;
+
+        TransactionRequestBuilder::new()
+            .build_mint_fungible_asset(asset, event.recipient, NoteType::Public, rng)
+            .map_err(|err| BridgeError::InvalidAmount(err.to_string()))
+    }
+}
+
+/// Keys of the lock events already minted, persisted so that a restarted relayer does not mint
+/// a deposit twice.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProcessedLocks {
+    keys: BTreeSet<String>,
+}
+
+impl ProcessedLocks {
+    /// Loads the processed events from `path`, returning an empty set if the file does not
+    /// exist.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes the processed events to `path` as pretty printed JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, contents)
+    }
+
+    /// Returns true if `event` was already minted.
+    pub fn contains(&self, event: &LockEvent) -> bool {
+        self.keys.contains(&event.key())
+    }
+
+    /// Records `event` as minted.
+    pub fn insert(&mut self, event: &LockEvent) {
+        self.keys.insert(event.key());
+    }
+}
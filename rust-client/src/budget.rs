@@ -0,0 +1,317 @@
+//! Off-chain side of the budgeting account (`masm/accounts/budget.masm`).
+//!
+//! A budget is a regular wallet with one more component. The basic wallet receives and sends
+//! assets as usual, while the budget component splits the balance of one token into buckets,
+//! such as rent or groceries, tracked in a storage map. Tokens in no bucket are unallocated:
+//! the budget stores the sum of its buckets and compares it with the vault balance.
+//!
+//! The owner drives the budget with transaction scripts against the own account. Allocating,
+//! releasing and reallocating only touch storage. Spending from a bucket touches both: the same
+//! procedure shrinks the bucket and moves the tokens from the vault into a payment note, so the
+//! two can never drift apart.
+//!
+//! The budget procedures do not check their caller. The account uses `AuthRpoFalcon512`, which
+//! requires the owner's signature on every transaction, so no one else can call them.
+
+use std::{collections::BTreeMap, error::Error, fs};
+
+use miden_client::{
+    account::{component::BasicWallet, Account, AccountId},
+    auth::AuthSecretKey,
+    keystore::FilesystemKeyStore,
+    note::Note,
+    transaction::{TransactionId, TransactionRequest, TransactionRequestBuilder},
+    Client, Word,
+};
+use miden_lib::{account::auth::AuthRpoFalcon512, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    asset::Asset,
+    ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::{create_library, word_to_masm},
+};
+
+/// Path under which the budget component is linked into the owner's scripts.
+pub const LIBRARY_PATH: &str = "external_contract::budget";
+/// Location of the budget component, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/budget.masm";
+
+/// Storage slot holding `[faucet_prefix, faucet_suffix, 0, 0]` of the budgeted token.
+pub const CURRENCY_SLOT: u8 = 0;
+/// Storage slot mapping `[bucket, 0, 0, 0]` to `[amount, 0, 0, 0]`.
+pub const BUCKETS_SLOT: u8 = 1;
+/// Storage slot holding the sum of all buckets.
+pub const ALLOCATED_SLOT: u8 = 2;
+
+/// Returns the bucket key of `name`: its first 7 bytes read as a little-endian integer, which
+/// always fits into a field element.
+pub fn bucket_id(name: &str) -> u64 {
+    let mut bytes = [0_u8; 8];
+    for (byte, name_byte) in bytes.iter_mut().zip(name.bytes().take(7)) {
+        *byte = name_byte;
+    }
+    u64::from_le_bytes(bytes)
+}
+
+/// State of a budget, read from a copy of its account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Budget {
+    pub id: AccountId,
+    /// Faucet of the budgeted token.
+    pub currency: AccountId,
+    /// Tokens of the currency in the vault.
+    pub balance: u64,
+    /// Sum of all buckets.
+    pub allocated: u64,
+    /// Tokens in every non-empty bucket.
+    pub buckets: BTreeMap<u64, u64>,
+}
+
+impl Budget {
+    /// Decodes the storage and the vault of a budget account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let currency = storage.get_item(CURRENCY_SLOT)?;
+        let currency = AccountId::try_from([currency[3], currency[2]])?;
+
+        let StorageSlot::Map(buckets_map) = &storage.slots()[BUCKETS_SLOT as usize] else {
+            return Err("the buckets slot of the budget is not a map".into());
+        };
+        let buckets = buckets_map
+            .entries()
+            .map(|(key, value)| (key[3].as_int(), value[3].as_int()))
+            .filter(|&(_, amount)| amount > 0)
+            .collect();
+
+        Ok(Self {
+            id: account.id(),
+            currency,
+            balance: account.vault().get_balance(currency)?,
+            allocated: storage.get_item(ALLOCATED_SLOT)?[3].as_int(),
+            buckets,
+        })
+    }
+
+    /// Returns the tokens in `bucket`.
+    pub fn bucket(&self, bucket: u64) -> u64 {
+        self.buckets.get(&bucket).copied().unwrap_or(0)
+    }
+
+    /// Returns the tokens of the vault in no bucket, 0 if the wallet interface spent allocated
+    /// tokens.
+    pub fn unallocated(&self) -> u64 {
+        self.balance.saturating_sub(self.allocated)
+    }
+}
+
+/// A change the owner makes to the budget, executed as a transaction script.
+#[derive(Debug, Clone)]
+pub enum Operation {
+    /// Sets unallocated tokens aside in a bucket.
+    Allocate { bucket: u64, amount: u64 },
+    /// Returns tokens of a bucket to the unallocated ones.
+    Release { bucket: u64, amount: u64 },
+    /// Moves tokens from one bucket to another.
+    Reallocate { from: u64, to: u64, amount: u64 },
+    /// Pays a bucket out into `payment`, a public note carrying exactly one fungible asset of
+    /// the budgeted token.
+    Spend { bucket: u64, payment: Note },
+}
+
+impl Operation {
+    /// Returns the transaction script calling the budget component, linked under
+    /// [`LIBRARY_PATH`].
+    pub fn script_code(&self) -> Result<String, Box<dyn Error>> {
+        let call = match self {
+            Self::Allocate { bucket, amount } => {
+                format!("    push.{amount}.{bucket}\n    call.budget::allocate\n")
+            }
+            Self::Release { bucket, amount } => {
+                format!("    push.{amount}.{bucket}\n    call.budget::release\n")
+            }
+            Self::Reallocate { from, to, amount } => {
+                format!("    push.{amount}.{to}.{from}\n    call.budget::reallocate\n")
+            }
+            Self::Spend { bucket, payment } => {
+                let assets: Vec<_> = payment.assets().iter().collect();
+                let [Asset::Fungible(asset)] = assets.as_slice() else {
+                    return Err("a payment must carry exactly one fungible asset".into());
+                };
+                format!(
+                    "    push.{}\n    push.{}.{}.{bucket}\n    call.budget::spend\n",
+                    word_to_masm(payment.recipient().digest()),
+                    payment.metadata().tag().as_u32(),
+                    asset.amount(),
+                )
+            }
+        };
+        Ok(format!(
+            "use.external_contract::budget\n\
+             use.std::sys\n\
+             begin\n{call}    exec.sys::truncate_stack\nend\n"
+        ))
+    }
+}
+
+/// Creates a public budget of `currency` tokens on top of a basic wallet, and adds the key of
+/// its owner to `keystore`.
+pub async fn create_budget_account(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    keystore: &FilesystemKeyStore<StdRng>,
+    currency: AccountId,
+) -> Result<Account, Box<dyn Error>> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value([ZERO, ZERO, currency.suffix(), currency.prefix().as_felt()].into()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+        .with_component(BasicWallet)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    keystore.add_key(&key_pair)?;
+    Ok(account)
+}
+
+/// Builds the transaction applying `operation`, to be executed against the budget.
+pub fn request(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    operation: &Operation,
+) -> Result<TransactionRequest, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_tx_script(&operation.script_code()?)?;
+
+    let mut builder = TransactionRequestBuilder::new().custom_script(tx_script);
+    if let Operation::Spend { payment, .. } = operation {
+        builder = builder.expected_output_recipients(vec![payment.recipient().clone()]);
+    }
+    Ok(builder.build()?)
+}
+
+/// Applies `operation` to the budget `budget_id`.
+pub async fn execute(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    budget_id: AccountId,
+    operation: &Operation,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = request(client, operation)?;
+    Ok(client.submit_with_prover(budget_id, request).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_lib::note::WellKnownNote;
+    use miden_objects::{
+        account::AccountIdVersion,
+        asset::FungibleAsset,
+        note::{
+            NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteTag,
+            NoteType,
+        },
+        Felt,
+    };
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn payment(assets: Vec<Asset>) -> Note {
+        let receiver = account(1, AccountType::RegularAccountUpdatableCode);
+        let recipient = NoteRecipient::new(
+            Word::default(),
+            WellKnownNote::P2ID.script(),
+            NoteInputs::new(vec![receiver.suffix(), receiver.prefix().as_felt()]).unwrap(),
+        );
+        let metadata = NoteMetadata::new(
+            account(2, AccountType::RegularAccountUpdatableCode),
+            NoteType::Public,
+            NoteTag::from_account_id(receiver),
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )
+        .unwrap();
+        Note::new(NoteAssets::new(assets).unwrap(), metadata, recipient)
+    }
+
+    #[test]
+    fn scripts_push_arguments_in_call_order() {
+        let code = Operation::Reallocate {
+            from: 1,
+            to: 2,
+            amount: 30,
+        }
+        .script_code()
+        .unwrap();
+        // `from` ends up on top of the stack
+        assert!(code.contains("push.30.2.1\n    call.budget::reallocate"));
+
+        let faucet = account(3, AccountType::FungibleFaucet);
+        let note = payment(vec![FungibleAsset::new(faucet, 25).unwrap().into()]);
+        let code = Operation::Spend {
+            bucket: 7,
+            payment: note.clone(),
+        }
+        .script_code()
+        .unwrap();
+        let tag = note.metadata().tag().as_u32();
+        assert!(code.contains(&format!("push.{tag}.25.7\n    call.budget::spend")));
+        assert!(code.contains(&word_to_masm(note.recipient().digest())));
+    }
+
+    #[test]
+    fn payment_must_carry_one_fungible_asset() {
+        let operation = Operation::Spend {
+            bucket: 1,
+            payment: payment(vec![]),
+        };
+        assert!(operation.script_code().is_err());
+    }
+
+    #[test]
+    fn unallocated_tokens_never_go_negative() {
+        let budget = Budget {
+            id: account(1, AccountType::RegularAccountUpdatableCode),
+            currency: account(3, AccountType::FungibleFaucet),
+            balance: 40,
+            allocated: 50,
+            buckets: BTreeMap::from([(bucket_id("rent"), 50)]),
+        };
+        assert_eq!(budget.unallocated(), 0);
+        assert_eq!(budget.bucket(bucket_id("rent")), 50);
+        assert_eq!(budget.bucket(bucket_id("food")), 0);
+        assert_ne!(bucket_id("rent"), bucket_id("food"));
+    }
+}
@@ -0,0 +1,211 @@
+//! Checked arithmetic on amounts, in MASM (`masm/lib/checked_math.masm`).
+//!
+//! The `add`, `sub` and `mul` instructions compute in the field of the VM: results wrap around
+//! modulo p = 2^64 - 2^32 + 1 without any error. A contract subtracting a withdrawal larger than a
+//! balance gets a balance close to 2^64 instead of failing, and a price times a quantity past
+//! the modulus comes out small. The checked procedures take amounts of fungible assets and fail
+//! with an error naming the operation instead.
+//!
+//! [`unchecked`] runs the bare instruction and [`checked`] the library procedure, both in a
+//! local VM, so the two can be compared on the same operands.
+
+use std::error::Error;
+
+use miden_objects::{assembly::Library, asset::FungibleAsset, Felt};
+
+use crate::helpers::{create_library, execute_program};
+
+/// Path under which the library is linked into programs.
+pub const LIBRARY_PATH: &str = "tutorial::checked_math";
+/// Location of the library, relative to `rust-client`.
+pub const LIBRARY_FILE_PATH: &str = "../masm/lib/checked_math.masm";
+
+/// Largest amount the checked procedures accept and return.
+pub const MAX_AMOUNT: u64 = FungibleAsset::MAX_AMOUNT;
+
+/// An arithmetic operation on two amounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Add,
+    Sub,
+    Mul,
+}
+
+impl Operation {
+    pub const ALL: [Self; 3] = [Self::Add, Self::Sub, Self::Mul];
+
+    /// Returns the name of both the instruction and the checked procedure.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Sub => "sub",
+            Self::Mul => "mul",
+        }
+    }
+
+    /// Returns the error the checked procedure fails with when the result is not an amount.
+    pub fn error(self) -> &'static str {
+        match self {
+            Self::Add => "Amount addition overflows",
+            Self::Sub => "Amount subtraction underflows",
+            Self::Mul => "Amount multiplication overflows",
+        }
+    }
+
+    /// Returns `a <op> b` computed on integers, or `None` when it is not an amount.
+    pub fn reference(self, a: u64, b: u64) -> Option<u64> {
+        if a > MAX_AMOUNT || b > MAX_AMOUNT {
+            return None;
+        }
+        let result = match self {
+            Self::Add => a.checked_add(b),
+            Self::Sub => a.checked_sub(b),
+            Self::Mul => a.checked_mul(b),
+        };
+        result.filter(|result| *result <= MAX_AMOUNT)
+    }
+
+    /// Returns `a <op> b` computed in the field, the way the bare instruction computes it.
+    pub fn in_field(self, a: u64, b: u64) -> u64 {
+        let (a, b) = (Felt::new(a), Felt::new(b));
+        let result = match self {
+            Self::Add => a + b,
+            Self::Sub => a - b,
+            Self::Mul => a * b,
+        };
+        result.as_int()
+    }
+}
+
+/// Compiles the checked math library from its source.
+pub fn library(source: &str) -> Result<Library, Box<dyn Error>> {
+    create_library(source, LIBRARY_PATH)
+}
+
+/// Executes the bare instruction of `operation` on `a` and `b` in the VM.
+pub fn unchecked(operation: Operation, a: u64, b: u64) -> Result<u64, Box<dyn Error>> {
+    execute_program(
+        &format!(
+            "begin
+    push.{a} push.{b} {}
+    # => [result, pad(16)]
+
+    swap drop
+end
+",
+            operation.name()
+        ),
+        None,
+    )
+}
+
+/// Executes `checked_math::<operation>` on `a` and `b` in the VM.
+pub fn checked(
+    library: &Library,
+    operation: Operation,
+    a: u64,
+    b: u64,
+) -> Result<u64, Box<dyn Error>> {
+    execute_program(
+        &format!(
+            "use.{LIBRARY_PATH}
+
+begin
+    push.{a} push.{b}
+    exec.checked_math::{}
+    # => [result, pad(16)]
+
+    swap drop
+end
+",
+            operation.name()
+        ),
+        Some(library),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    /// Modulus of the field, p = 2^64 - 2^32 + 1.
+    const MODULUS: u64 = 0xffff_ffff_0000_0001;
+
+    fn checked_math_library() -> Library {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(LIBRARY_FILE_PATH);
+        library(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn bare_instructions_wrap_around_the_field() {
+        let cases = [
+            // A withdrawal of 25 from a balance of 10
+            (Operation::Sub, 10, 25),
+            (Operation::Add, MAX_AMOUNT, MAX_AMOUNT),
+            // A price of 2^32 times a quantity of 2^32
+            (Operation::Mul, 1 << 32, 1 << 32),
+        ];
+
+        for (operation, a, b) in cases {
+            let result = unchecked(operation, a, b).unwrap();
+            assert_eq!(result, operation.in_field(a, b), "{operation:?}");
+            assert_eq!(operation.reference(a, b), None, "{operation:?}");
+        }
+        assert_eq!(Operation::Sub.in_field(10, 25), MODULUS - 15);
+        assert_eq!(Operation::Mul.in_field(1 << 32, 1 << 32), (1 << 32) - 1);
+    }
+
+    #[test]
+    fn checked_procedures_fail_instead_of_wrapping() {
+        let library = checked_math_library();
+        let cases = [
+            (Operation::Sub, 10, 25),
+            (Operation::Add, MAX_AMOUNT, 1),
+            (Operation::Mul, 1 << 32, 1 << 32),
+        ];
+
+        for (operation, a, b) in cases {
+            let err = checked(&library, operation, a, b).unwrap_err();
+            assert!(
+                format!("{err:?}").contains(operation.error()),
+                "{operation:?}: {err:?}"
+            );
+        }
+
+        let err = checked(&library, Operation::Add, MAX_AMOUNT + 1, 0).unwrap_err();
+        assert!(format!("{err:?}").contains("Amount exceeds the maximum amount"));
+    }
+
+    #[test]
+    fn checked_procedures_agree_with_integer_math() {
+        let library = checked_math_library();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..64 {
+            // Small amounts, amounts around the square root of the maximum and any amount
+            let mut amount = || match rng.random_range(0..3) {
+                0 => rng.random_range(0..1_000),
+                1 => rng.random_range(0..1 << 33),
+                _ => rng.random_range(0..=MAX_AMOUNT),
+            };
+            let (a, b) = (amount(), amount());
+
+            for operation in Operation::ALL {
+                let result = checked(&library, operation, a, b);
+                match operation.reference(a, b) {
+                    Some(expected) => {
+                        assert_eq!(result.unwrap(), expected, "{operation:?}({a}, {b})")
+                    }
+                    None => assert!(
+                        format!("{:?}", result.unwrap_err()).contains(operation.error()),
+                        "{operation:?}({a}, {b})"
+                    ),
+                }
+            }
+        }
+    }
+}
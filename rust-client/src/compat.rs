@@ -0,0 +1,435 @@
+//! Compatibility of the chapter crates with miden-client releases.
+//!
+//! Each crate under `chapters` declares the miden-client versions it is meant to build with:
+//!
+//! ```toml
+//! [package.metadata.tutorial]
+//! miden-client = ">=0.12, <0.13"
+//! ```
+//!
+//! `compat_matrix` checks every chapter against the latest patch of each minor release in its
+//! range, with the crates released alongside miden-client pinned to the same release. When
+//! a chapter does not compile, [`api_changes`] turns the compiler errors into the list of APIs
+//! which moved, disappeared or changed signature in that release.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+/// Crates released together with miden-client, which must all come from the same release.
+pub const LOCKSTEP_CRATES: &[&str] = &[
+    "miden-assembly",
+    "miden-client",
+    "miden-client-sqlite-store",
+    "miden-crypto",
+    "miden-lib",
+    "miden-objects",
+    "miden-processor",
+    "miden-testing",
+    "miden-tx",
+];
+
+/// The [`LOCKSTEP_CRATES`] of the VM, numbered apart from miden-client. They are pinned to the
+/// versions the other crates of the release depend on, found by [`vm_versions`].
+pub const VM_CRATES: &[&str] = &["miden-assembly", "miden-crypto", "miden-processor"];
+
+/// A chapter crate and the miden-client versions it supports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chapter {
+    pub package: String,
+    pub supported: VersionReq,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    packages: Vec<Package>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+    manifest_path: String,
+    #[serde(default)]
+    metadata: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct ResolvedMetadata {
+    resolve: Option<Resolve>,
+}
+
+#[derive(Deserialize)]
+struct Resolve {
+    nodes: Vec<Node>,
+}
+
+#[derive(Deserialize)]
+struct Node {
+    id: String,
+    #[serde(default)]
+    deps: Vec<NodeDep>,
+}
+
+#[derive(Deserialize)]
+struct NodeDep {
+    pkg: String,
+}
+
+/// Returns the chapter crates found in the output of `cargo metadata --format-version 1`.
+///
+/// Fails if a chapter does not declare a valid miden-client version range.
+pub fn chapters(cargo_metadata: &str) -> Result<Vec<Chapter>, Box<dyn Error>> {
+    let metadata: Metadata = serde_json::from_str(cargo_metadata)?;
+    let mut chapters = Vec::new();
+    for package in metadata.packages {
+        let in_chapters = Path::new(&package.manifest_path)
+            .parent()
+            .and_then(Path::parent)
+            .and_then(Path::file_name)
+            .is_some_and(|dir| dir == "chapters");
+        if !in_chapters {
+            continue;
+        }
+
+        let range = package.metadata["tutorial"]["miden-client"]
+            .as_str()
+            .ok_or_else(|| {
+                format!(
+                    "{} does not declare `package.metadata.tutorial.miden-client`",
+                    package.name
+                )
+            })?;
+        let supported = VersionReq::parse(range).map_err(|err| {
+            format!(
+                "{}: invalid miden-client range `{range}`: {err}",
+                package.name
+            )
+        })?;
+        chapters.push(Chapter {
+            package: package.name,
+            supported,
+        });
+    }
+    chapters.sort_by(|a, b| a.package.cmp(&b.package));
+    Ok(chapters)
+}
+
+/// Returns the manifests of the workspace members found in the output of
+/// `cargo metadata --format-version 1 --no-deps`.
+pub fn member_manifests(cargo_metadata: &str) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let metadata: Metadata = serde_json::from_str(cargo_metadata)?;
+    let mut manifests: Vec<PathBuf> = metadata
+        .packages
+        .into_iter()
+        .map(|package| PathBuf::from(package.manifest_path))
+        .collect();
+    manifests.sort();
+    Ok(manifests)
+}
+
+/// Returns the name and version of the package `id` of `cargo metadata`, in either the
+/// `registry+https://...#name@1.2.3` format or the older `name 1.2.3 (registry+https://...)`.
+fn package_name_and_version(id: &str) -> Option<(&str, Version)> {
+    let (name, version) = match id.rsplit_once('#') {
+        Some((_, spec)) => spec.rsplit_once('@')?,
+        None => {
+            let mut parts = id.split(' ');
+            (parts.next()?, parts.next()?)
+        }
+    };
+    Some((name, Version::parse(version).ok()?))
+}
+
+/// Returns the versions of the [`VM_CRATES`] the other [`LOCKSTEP_CRATES`] depend on, found in
+/// the output of `cargo metadata --format-version 1` for a workspace whose miden-client crates
+/// are pinned to one release.
+pub fn vm_versions(cargo_metadata: &str) -> Result<BTreeMap<String, Version>, Box<dyn Error>> {
+    let metadata: ResolvedMetadata = serde_json::from_str(cargo_metadata)?;
+    let resolve = metadata
+        .resolve
+        .ok_or("the metadata has no dependency graph, it was read with --no-deps")?;
+
+    let mut versions = BTreeMap::new();
+    for node in resolve.nodes {
+        let released_with_client = package_name_and_version(&node.id)
+            .is_some_and(|(name, _)| LOCKSTEP_CRATES.contains(&name) && !VM_CRATES.contains(&name));
+        if !released_with_client {
+            continue;
+        }
+        for dep in node.deps {
+            if let Some((name, version)) = package_name_and_version(&dep.pkg) {
+                if VM_CRATES.contains(&name) {
+                    versions.insert(name.to_string(), version);
+                }
+            }
+        }
+    }
+    Ok(versions)
+}
+
+/// Returns the latest stable patch of each minor release in `published` matching `supported`,
+/// from the oldest to the newest.
+pub fn versions_to_check(
+    supported: &VersionReq,
+    published: impl IntoIterator<Item = Version>,
+) -> Vec<Version> {
+    let mut latest: BTreeMap<(u64, u64), Version> = BTreeMap::new();
+    for version in published {
+        if !version.pre.is_empty() || !supported.matches(&version) {
+            continue;
+        }
+        let entry = latest
+            .entry((version.major, version.minor))
+            .or_insert_with(|| version.clone());
+        if version > *entry {
+            *entry = version;
+        }
+    }
+    latest.into_values().collect()
+}
+
+/// Rewrites the dependency sections of a manifest so that miden-client resolves to exactly
+/// `version` and the other [`LOCKSTEP_CRATES`] to its minor release, except for the
+/// [`VM_CRATES`], which resolve to exactly their version in `vm_versions`.
+///
+/// Every section whose header ends in `dependencies]` is rewritten: `[workspace.dependencies]`,
+/// `[dependencies]`, `[dev-dependencies]`, `[build-dependencies]` and their `[target.*]`
+/// variants. Dependencies taken from the workspace are left alone, as are VM crates missing from
+/// `vm_versions`.
+pub fn pin_lockstep_versions(
+    manifest: &str,
+    version: &Version,
+    vm_versions: &BTreeMap<String, Version>,
+) -> String {
+    let mut in_dependencies = false;
+    let mut pinned = String::with_capacity(manifest.len());
+    for line in manifest.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_dependencies = trimmed.ends_with("dependencies]");
+        }
+
+        let name = trimmed.split('=').next().unwrap_or_default().trim();
+        let requirement = if !in_dependencies || !LOCKSTEP_CRATES.contains(&name) {
+            None
+        } else if VM_CRATES.contains(&name) {
+            vm_versions.get(name).map(|version| format!("={version}"))
+        } else if name == "miden-client" {
+            Some(format!("={version}"))
+        } else {
+            Some(format!("~{}.{}", version.major, version.minor))
+        };
+
+        // The version is either in a table, `name = { version = "..." }`, or the whole value,
+        // `name = "..."`
+        let value_start = line.find("version = \"").map_or_else(
+            || {
+                let value = line.find('=')? + 1;
+                let quote = value + line[value..].find(|c: char| !c.is_whitespace())?;
+                line[quote..].starts_with('"').then_some(quote + 1)
+            },
+            |start| Some(start + "version = \"".len()),
+        );
+        match (requirement, value_start) {
+            (Some(requirement), Some(value_start)) => {
+                let value_end = value_start + line[value_start..].find('"').unwrap_or(0);
+                pinned.push_str(&line[..value_start]);
+                pinned.push_str(&requirement);
+                pinned.push_str(&line[value_end..]);
+            }
+            _ => pinned.push_str(line),
+        }
+        pinned.push('\n');
+    }
+    pinned
+}
+
+/// An API a chapter uses which does not compile against a release.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ApiChange {
+    /// The item named by the compiler, e.g. `miden_client::note::create_swap_note`.
+    pub api: String,
+    /// The rustc error code, e.g. `E0432` for an unresolved import.
+    pub code: Option<String>,
+    /// The rustc error message.
+    pub message: String,
+    /// Where the chapter or the shared crate uses the API, as `file:line`.
+    pub location: String,
+}
+
+impl fmt::Display for ApiChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` at {}: {}", self.api, self.location, self.message)?;
+        if let Some(code) = &self.code {
+            write!(f, " ({code})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Extracts the APIs which failed to compile from the output of
+/// `cargo check --message-format=json`.
+///
+/// Each error names its API by the first item the compiler quotes in backticks, or by the whole
+/// message when it quotes none. The same error reported for several binaries is listed once.
+pub fn api_changes(cargo_output: &str) -> Vec<ApiChange> {
+    let mut changes = BTreeSet::new();
+    for line in cargo_output.lines() {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        let message = &entry["message"];
+        if entry["reason"] != "compiler-message" || message["level"] != "error" {
+            continue;
+        }
+        // Summaries such as "aborting due to 3 previous errors" point at no code
+        let Some(span) = message["spans"]
+            .as_array()
+            .and_then(|spans| spans.iter().find(|span| span["is_primary"] == true))
+        else {
+            continue;
+        };
+
+        let text = message["message"].as_str().unwrap_or_default().to_string();
+        let api = text
+            .split('`')
+            .nth(1)
+            .map_or_else(|| text.clone(), str::to_string);
+        changes.insert(ApiChange {
+            api,
+            code: message["code"]["code"].as_str().map(str::to_string),
+            message: text,
+            location: format!(
+                "{}:{}",
+                span["file_name"].as_str().unwrap_or("?"),
+                span["line_start"]
+            ),
+        });
+    }
+    changes.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(nums: &[&str]) -> Vec<Version> {
+        nums.iter()
+            .map(|num| Version::parse(num).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn only_the_latest_stable_patch_of_each_supported_minor_is_checked() {
+        let supported = VersionReq::parse(">=0.11, <0.13").unwrap();
+        let published = versions(&[
+            "0.10.2",
+            "0.11.0",
+            "0.11.4",
+            "0.11.2",
+            "0.12.0",
+            "0.12.1",
+            "0.13.0-rc.1",
+            "0.13.0",
+        ]);
+
+        assert_eq!(
+            versions_to_check(&supported, published),
+            versions(&["0.11.4", "0.12.1"])
+        );
+    }
+
+    #[test]
+    fn pinning_rewrites_the_lockstep_crates_of_every_dependency_section() {
+        let manifest = "\
+[package.metadata.tutorial]
+miden-client = \">=0.11, <0.13\"
+
+[workspace.dependencies]
+miden-client = { version = \"0.12\", features = [\"testing\"] }
+miden-lib = { version = \"0.12\", default-features = false }
+miden-crypto = { version = \"0.17.1\" }
+miden-processor = \"0.19\"
+miden-assembly = \"0.18.3\"
+rand = { version = \"0.9\" }
+
+[dependencies]
+miden-objects = { workspace = true }
+miden-tx = { version = \"0.12\", default-features = false }
+
+[dev-dependencies]
+miden-testing = \"0.12\"
+";
+        let vm_versions = BTreeMap::from([
+            ("miden-crypto".to_string(), Version::new(0, 15, 9)),
+            ("miden-processor".to_string(), Version::new(0, 17, 1)),
+        ]);
+        let pinned = pin_lockstep_versions(manifest, &Version::new(0, 11, 4), &vm_versions);
+
+        assert_eq!(
+            pinned,
+            "\
+[package.metadata.tutorial]
+miden-client = \">=0.11, <0.13\"
+
+[workspace.dependencies]
+miden-client = { version = \"=0.11.4\", features = [\"testing\"] }
+miden-lib = { version = \"~0.11\", default-features = false }
+miden-crypto = { version = \"=0.15.9\" }
+miden-processor = \"=0.17.1\"
+miden-assembly = \"0.18.3\"
+rand = { version = \"0.9\" }
+
+[dependencies]
+miden-objects = { workspace = true }
+miden-tx = { version = \"~0.11\", default-features = false }
+
+[dev-dependencies]
+miden-testing = \"~0.11\"
+"
+        );
+    }
+
+    #[test]
+    fn vm_versions_are_the_ones_the_client_crates_depend_on() {
+        let metadata = r#"{"packages":[],"resolve":{"nodes":[
+            {"id":"registry+https://github.com/rust-lang/crates.io-index#miden-objects@0.11.6","deps":[
+                {"pkg":"registry+https://github.com/rust-lang/crates.io-index#miden-processor@0.17.1"},
+                {"pkg":"registry+https://github.com/rust-lang/crates.io-index#rand@0.9.2"}]},
+            {"id":"miden-lib 0.11.6 (registry+https://github.com/rust-lang/crates.io-index)","deps":[
+                {"pkg":"miden-crypto 0.15.9 (registry+https://github.com/rust-lang/crates.io-index)"}]},
+            {"id":"path+file:///tutorials/rust-client#0.1.0","deps":[
+                {"pkg":"registry+https://github.com/rust-lang/crates.io-index#miden-processor@0.19.0"}]}
+        ]}}"#;
+
+        assert_eq!(
+            vm_versions(metadata).unwrap(),
+            BTreeMap::from([
+                ("miden-crypto".to_string(), Version::new(0, 15, 9)),
+                ("miden-processor".to_string(), Version::new(0, 17, 1)),
+            ])
+        );
+        assert!(vm_versions(r#"{"packages":[],"resolve":null}"#).is_err());
+    }
+
+    #[test]
+    fn compiler_errors_become_one_change_per_api() {
+        let error = r#"{"reason":"compiler-message","message":{"level":"error","message":"unresolved import `miden_client::note::create_swap_note`","code":{"code":"E0432"},"spans":[{"file_name":"chapters/02-notes/src/bin/swap.rs","line_start":12,"is_primary":true}]}}"#;
+        let summary = r#"{"reason":"compiler-message","message":{"level":"error","message":"aborting due to 1 previous error","code":null,"spans":[]}}"#;
+        let warning = r#"{"reason":"compiler-message","message":{"level":"warning","message":"unused import `Felt`","code":null,"spans":[{"file_name":"src/lib.rs","line_start":1,"is_primary":true}]}}"#;
+        let output = [error, error, summary, warning, "error: could not compile"].join("\n");
+
+        let changes = api_changes(&output);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].api, "miden_client::note::create_swap_note");
+        assert_eq!(changes[0].code.as_deref(), Some("E0432"));
+        assert_eq!(changes[0].location, "chapters/02-notes/src/bin/swap.rs:12");
+    }
+}
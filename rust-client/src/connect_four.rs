@@ -0,0 +1,454 @@
+//! Off-chain side of the connect-four game (`masm/accounts/connect_four.masm`).
+//!
+//! A 7x6 board has 42 cells, too many for the one-map-entry-per-field layout of tic-tac-toe to
+//! stay cheap: every move would read dozens of map entries to look for a line. The contract packs
+//! the board instead. Each column is a u32, its height in the lowest 3 bits and then one bit per
+//! disc from the bottom row up, set for the yellow discs:
+//!
+//! ```text
+//!  bit:     8   7   6   5   4   3 | 2   1   0
+//!           row 5 .. row 0 owners | height
+//! ```
+//!
+//! The seven columns fill two storage words, columns 0 to 3 in the first and 4 to 6 in the
+//! second. Dropping a disc increments the height and sets the owner bit of the new row, without
+//! touching any other column. To look for a line, the contract unpacks both words into memory
+//! once and reads cells from there.
+//!
+//! Players move by sending a `connect_four_move_note` naming a column. [`Board`] decodes the two
+//! words, replays moves exactly like the contract and renders the board.
+
+use std::{error::Error, fmt, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel};
+use miden_objects::{
+    account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
+    NoteError, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::create_library,
+    storage::{read_account_id_slot, read_u64_slot},
+};
+
+/// Path under which the game contract is linked into the move note.
+pub const LIBRARY_PATH: &str = "external_contract::connect_four";
+/// Location of the game contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/connect_four.masm";
+/// Location of the note dropping a disc, relative to `rust-client`.
+pub const MOVE_NOTE_PATH: &str = "../masm/notes/connect_four_move_note.masm";
+
+/// Storage slot holding `[player_prefix, player_suffix, 0, 0]` of the player dropping red discs.
+pub const PLAYER1_SLOT: u8 = 0;
+/// Storage slot holding `[player_prefix, player_suffix, 0, 0]` of the player dropping yellow
+/// discs.
+pub const PLAYER2_SLOT: u8 = 1;
+/// Storage slot holding the first four columns of the board.
+pub const BOARD_LOW_SLOT: u8 = 2;
+/// Storage slot holding the last three columns of the board.
+pub const BOARD_HIGH_SLOT: u8 = 3;
+/// Storage slot counting the discs dropped.
+pub const MOVES_SLOT: u8 = 4;
+/// Storage slot holding the mark of the winner, [`DRAW`] for a full board without a line, 0
+/// while the game is running.
+pub const WINNER_SLOT: u8 = 5;
+
+/// Value of [`WINNER_SLOT`] for a game finished without a winner.
+pub const DRAW: u64 = 3;
+
+/// Number of columns of the board, numbered from the left.
+pub const NUM_COLUMNS: usize = 7;
+/// Number of rows of the board, numbered from the bottom.
+pub const NUM_ROWS: usize = 6;
+
+/// Bits of a packed column holding its height.
+const HEIGHT_MASK: u32 = 0b111;
+/// Position of the owner bit of the bottom row in a packed column.
+const OWNER_SHIFT: usize = 3;
+/// Directions along which a line is looked for, as (row, column) steps.
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+/// Disc dropped by a player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disc {
+    /// Dropped by player 1, who moves first.
+    Red,
+    /// Dropped by player 2.
+    Yellow,
+}
+
+impl Disc {
+    /// Decodes a mark as stored by the contract.
+    fn from_value(value: u64) -> Option<Self> {
+        match value {
+            1 => Some(Self::Red),
+            2 => Some(Self::Yellow),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Disc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Red => write!(f, "R"),
+            Self::Yellow => write!(f, "Y"),
+        }
+    }
+}
+
+/// A board, as packed by the contract.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Board {
+    columns: [u32; NUM_COLUMNS],
+}
+
+impl Board {
+    /// Decodes the two storage words of a board, rejecting columns the contract never writes.
+    pub fn from_words(low: Word, high: Word) -> Result<Self, Box<dyn Error>> {
+        if high[3] != ZERO {
+            return Err("the last element of the board is not empty".into());
+        }
+
+        let mut columns = [0; NUM_COLUMNS];
+        for (column, value) in columns.iter_mut().zip(low.iter().chain(high.iter())) {
+            let packed = u32::try_from(value.as_int())?;
+            let height = (packed & HEIGHT_MASK) as usize;
+            // Owner bits only exist for the discs in the column
+            if height > NUM_ROWS || packed >> (OWNER_SHIFT + height) != 0 {
+                return Err(format!("{packed:#b} is not a packed column").into());
+            }
+            *column = packed;
+        }
+        Ok(Self { columns })
+    }
+
+    /// Returns the two storage words holding the board.
+    pub fn to_words(&self) -> (Word, Word) {
+        let [c0, c1, c2, c3, c4, c5, c6] = self.columns.map(Felt::from);
+        ([c0, c1, c2, c3].into(), [c4, c5, c6, ZERO].into())
+    }
+
+    /// Returns the number of discs in `column`.
+    pub fn height(&self, column: usize) -> usize {
+        (self.columns[column] & HEIGHT_MASK) as usize
+    }
+
+    /// Returns the disc at `column` and `row`, counting rows from the bottom.
+    pub fn cell(&self, column: usize, row: usize) -> Option<Disc> {
+        if column >= NUM_COLUMNS || row >= self.height(column) {
+            return None;
+        }
+        match (self.columns[column] >> (OWNER_SHIFT + row)) & 1 {
+            0 => Some(Disc::Red),
+            _ => Some(Disc::Yellow),
+        }
+    }
+
+    /// Returns whether `column` is on the board and has room for another disc.
+    pub fn can_drop(&self, column: usize) -> bool {
+        column < NUM_COLUMNS && self.height(column) < NUM_ROWS
+    }
+
+    /// Drops `disc` into `column` like the contract does, and returns the row it lands on.
+    pub fn drop_disc(&mut self, column: usize, disc: Disc) -> Result<usize, Box<dyn Error>> {
+        if !self.can_drop(column) {
+            return Err(format!("no disc can be dropped into column {column}").into());
+        }
+        let row = self.height(column);
+        let owner_bit = u32::from(disc == Disc::Yellow) << (OWNER_SHIFT + row);
+        self.columns[column] += owner_bit + 1;
+        Ok(row)
+    }
+
+    /// Returns whether the disc at `column` and `row` is part of a line of four.
+    pub fn has_line(&self, column: usize, row: usize) -> bool {
+        let Some(disc) = self.cell(column, row) else {
+            return false;
+        };
+        // Counts the discs of the same color from the cell, one way along a direction
+        let run = |dr: isize, dc: isize| {
+            (1..4)
+                .map(|step| (row as isize + dr * step, column as isize + dc * step))
+                .take_while(|&(r, c)| {
+                    r >= 0 && c >= 0 && self.cell(c as usize, r as usize) == Some(disc)
+                })
+                .count()
+        };
+        DIRECTIONS
+            .iter()
+            .any(|&(dr, dc)| run(dr, dc) + run(-dr, -dc) >= 3)
+    }
+}
+
+/// Renders the board top row first, with the column numbers below it.
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in (0..NUM_ROWS).rev() {
+            let cells: Vec<String> = (0..NUM_COLUMNS)
+                .map(|column| match self.cell(column, row) {
+                    Some(disc) => disc.to_string(),
+                    None => ".".to_string(),
+                })
+                .collect();
+            writeln!(f, "| {} |", cells.join(" "))?;
+        }
+        let numbers: Vec<String> = (0..NUM_COLUMNS).map(|column| column.to_string()).collect();
+        writeln!(f, "  {}  ", numbers.join(" "))
+    }
+}
+
+/// State of a game, read from a copy of its account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: AccountId,
+    pub player1: AccountId,
+    pub player2: AccountId,
+    pub board: Board,
+    pub moves: u64,
+    pub winner: Option<Disc>,
+    /// Whether the board filled up without a line.
+    pub draw: bool,
+}
+
+impl Game {
+    /// Decodes the storage of a deployed game account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let winner = read_u64_slot(account, WINNER_SLOT)?;
+        Ok(Self {
+            id: account.id(),
+            player1: read_account_id_slot(account, PLAYER1_SLOT)?,
+            player2: read_account_id_slot(account, PLAYER2_SLOT)?,
+            board: Board::from_words(
+                storage.get_item(BOARD_LOW_SLOT)?,
+                storage.get_item(BOARD_HIGH_SLOT)?,
+            )?,
+            moves: read_u64_slot(account, MOVES_SLOT)?,
+            winner: Disc::from_value(winner),
+            draw: winner == DRAW,
+        })
+    }
+
+    /// Returns whether the game has a winner or ended in a draw.
+    pub fn is_over(&self) -> bool {
+        self.winner.is_some() || self.draw
+    }
+
+    /// Returns the disc of the player whose turn it is, or `None` once the game is over.
+    pub fn next_disc(&self) -> Option<Disc> {
+        if self.is_over() {
+            None
+        } else if self.moves % 2 == 0 {
+            Some(Disc::Red)
+        } else {
+            Some(Disc::Yellow)
+        }
+    }
+
+    /// Returns the player dropping `disc`.
+    pub fn player(&self, disc: Disc) -> AccountId {
+        match disc {
+            Disc::Red => self.player1,
+            Disc::Yellow => self.player2,
+        }
+    }
+}
+
+/// Deploys a public game between `player1`, who drops red discs and moves first, and
+/// `player2`.
+pub async fn create_game(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player1: AccountId,
+    player2: AccountId,
+) -> Result<Account, Box<dyn Error>> {
+    if player1 == player2 {
+        return Err("a player cannot play against itself".into());
+    }
+
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let player_word = |player: AccountId| -> Word {
+        [ZERO, ZERO, player.suffix(), player.prefix().as_felt()].into()
+    };
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(player_word(player1)),
+            StorageSlot::Value(player_word(player2)),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note dropping a disc, linked against the game contract.
+pub fn compile_move_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(MOVE_NOTE_PATH)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds the note by which `player` drops a disc into `column` of the game `game_id`.
+pub fn move_note(
+    player: AccountId,
+    game_id: AccountId,
+    column: usize,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let metadata = NoteMetadata::new(
+        player,
+        NoteType::Public,
+        NoteTag::from_account_id(game_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(
+        NoteAssets::new(vec![])?,
+        metadata,
+        NoteRecipient::new(
+            serial_num,
+            script,
+            NoteInputs::new(vec![Felt::new(column as u64)])?,
+        ),
+    ))
+}
+
+/// Has `player` drop a disc into `column` of `game`: the player creates the move note, then
+/// the game consumes it right away. `script` is the compiled [`MOVE_NOTE_PATH`].
+///
+/// The game is a `NoAuth` account, so the client of the player can execute its transaction as
+/// long as it tracks the game. Returns the id of the game transaction.
+pub async fn drop_disc(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    game: &Game,
+    player: AccountId,
+    column: usize,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = move_note(player, game.id, column, script, serial_num)?;
+
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    client.submit_with_prover(player, request).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .build()?;
+    Ok(client.submit_with_prover(game.id, request).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replays `moves`, alternating red and yellow discs.
+    fn play(moves: &[usize]) -> Board {
+        let mut board = Board::default();
+        for (turn, &column) in moves.iter().enumerate() {
+            let disc = if turn % 2 == 0 {
+                Disc::Red
+            } else {
+                Disc::Yellow
+            };
+            board.drop_disc(column, disc).unwrap();
+        }
+        board
+    }
+
+    #[test]
+    fn columns_are_packed_into_two_words() {
+        // Red at the bottom of column 0, yellow above it, red alone in column 6
+        let board = play(&[0, 0, 6]);
+        let (low, high) = board.to_words();
+
+        // Height 2, owner bits 0b10: the second disc is yellow
+        assert_eq!(low[0].as_int(), 0b10_010);
+        assert_eq!(high[2].as_int(), 0b0_001);
+        assert_eq!(Board::from_words(low, high).unwrap(), board);
+        assert_eq!(board.cell(0, 1), Some(Disc::Yellow));
+        assert_eq!(board.cell(0, 2), None);
+
+        let full = play(&[3; NUM_ROWS]);
+        assert!(!full.can_drop(3));
+        let mut overfull = full;
+        assert!(overfull.drop_disc(3, Disc::Red).is_err());
+        assert_eq!(full.to_string().lines().next(), Some("| . . . Y . . . |"));
+    }
+
+    #[test]
+    fn malformed_columns_are_rejected() {
+        let word = |column: u64| -> Word { [Felt::new(column), ZERO, ZERO, ZERO].into() };
+        // A height of 7, an owner bit above the height and a value past a u32
+        for column in [0b111, 0b1000_001, 1 << 32] {
+            assert!(Board::from_words(word(column), Word::default()).is_err());
+        }
+        let high: Word = [ZERO, ZERO, ZERO, Felt::new(1)].into();
+        assert!(Board::from_words(Word::default(), high).is_err());
+    }
+
+    #[test]
+    fn lines_are_found_in_every_direction() {
+        let cases: [(&[usize], usize); 4] = [
+            // Horizontal: red on the bottom row of columns 0 to 3
+            (&[0, 0, 1, 1, 2, 2, 3], 3),
+            // Vertical: red stacked in column 4
+            (&[4, 5, 4, 5, 4, 5, 4], 4),
+            // Rising diagonal from column 0 to 3
+            (&[0, 1, 1, 2, 2, 3, 2, 3, 3, 6, 3], 3),
+            // Falling diagonal from column 6 to 3
+            (&[6, 5, 5, 4, 4, 3, 4, 3, 3, 0, 3], 3),
+        ];
+
+        for (moves, last) in cases {
+            let board = play(moves);
+            let row = board.height(last) - 1;
+            assert!(board.has_line(last, row), "{moves:?}\n{board}");
+
+            let before = play(&moves[..moves.len() - 1]);
+            assert!(
+                (0..NUM_COLUMNS).all(|column| {
+                    (0..before.height(column)).all(|row| !before.has_line(column, row))
+                }),
+                "{moves:?}\n{before}"
+            );
+        }
+    }
+}
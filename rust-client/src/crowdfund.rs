@@ -0,0 +1,412 @@
+//! Off-chain side of the crowdfunding contract (`masm/accounts/crowdfund.masm`).
+//!
+//! Every campaign is its own contract account raising tokens of one faucet towards a goal.
+//! Contributors send their tokens with a `crowdfund_contribute_note` before the deadline, and
+//! the contract tallies them per contributor in a storage map. From the deadline on, the
+//! campaign is settled one of two ways: if the goal was met, the creator withdraws everything
+//! raised with a `crowdfund_withdraw_note`; if it was missed, every contributor reclaims exactly
+//! what it contributed with a `crowdfund_reclaim_note`. Both pay out with a P2ID note whose
+//! serial number is the serial number of the requesting note plus one.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, note::WellKnownNote, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    asset::{Asset, FungibleAsset},
+    block::BlockNumber,
+    NoteError, ONE, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::{create_library, reply_note},
+};
+
+/// Path under which the crowdfunding contract is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::crowdfund";
+/// Location of the crowdfunding contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/crowdfund.masm";
+/// Location of the note contributing to a campaign, relative to `rust-client`.
+pub const CONTRIBUTE_NOTE_PATH: &str = "../masm/notes/crowdfund_contribute_note.masm";
+/// Location of the note withdrawing the raised funds, relative to `rust-client`.
+pub const WITHDRAW_NOTE_PATH: &str = "../masm/notes/crowdfund_withdraw_note.masm";
+/// Location of the note reclaiming a contribution, relative to `rust-client`.
+pub const RECLAIM_NOTE_PATH: &str = "../masm/notes/crowdfund_reclaim_note.masm";
+
+const CREATOR_SLOT: u8 = 0;
+const CURRENCY_SLOT: u8 = 1;
+const GOAL_SLOT: u8 = 2;
+const DEADLINE_SLOT: u8 = 3;
+const RAISED_SLOT: u8 = 4;
+const CONTRIBUTIONS_SLOT: u8 = 5;
+const WITHDRAWN_SLOT: u8 = 6;
+
+/// How a campaign is settled in a transaction executed against a given block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CampaignOutcome {
+    /// The deadline is not reached, contributions are accepted.
+    Running,
+    /// The goal was met, the creator can withdraw the funds.
+    Funded,
+    /// The goal was missed, every contributor can reclaim its contribution.
+    Failed,
+}
+
+/// Terms and tally of a deployed campaign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Campaign {
+    pub id: AccountId,
+    pub creator: AccountId,
+    /// Faucet of the tokens contributions are paid in.
+    pub currency: AccountId,
+    pub goal: u64,
+    /// Block from which no contribution is accepted and the campaign can be settled.
+    pub deadline: BlockNumber,
+    /// Total contributed, reclaimed contributions included.
+    pub raised: u64,
+    /// Whether the creator withdrew the funds.
+    pub withdrawn: bool,
+}
+
+impl Campaign {
+    /// Decodes the terms and tally of a deployed campaign account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let account_id = |slot| -> Result<AccountId, Box<dyn Error>> {
+            let value = storage.get_item(slot)?;
+            Ok(AccountId::try_from([value[3], value[2]])?)
+        };
+        let deadline = u32::try_from(storage.get_item(DEADLINE_SLOT)?[3].as_int())?;
+
+        Ok(Self {
+            id: account.id(),
+            creator: account_id(CREATOR_SLOT)?,
+            currency: account_id(CURRENCY_SLOT)?,
+            goal: storage.get_item(GOAL_SLOT)?[3].as_int(),
+            deadline: BlockNumber::from(deadline),
+            raised: storage.get_item(RAISED_SLOT)?[3].as_int(),
+            withdrawn: storage.get_item(WITHDRAWN_SLOT)?[3] == ONE,
+        })
+    }
+
+    /// Returns how the campaign is settled in a transaction executed against `block_num`.
+    pub fn outcome_at(&self, block_num: BlockNumber) -> CampaignOutcome {
+        if block_num < self.deadline {
+            CampaignOutcome::Running
+        } else if self.raised >= self.goal {
+            CampaignOutcome::Funded
+        } else {
+            CampaignOutcome::Failed
+        }
+    }
+
+    /// Returns the P2ID note by which the campaign pays the raised funds to the creator in reply
+    /// to the withdrawal note with serial number `serial_num`.
+    pub fn withdrawal(&self, serial_num: Word) -> Result<Note, Box<dyn Error>> {
+        let funds = FungibleAsset::new(self.currency, self.raised)?;
+        Ok(reply_note(self.id, self.creator, funds, serial_num)?)
+    }
+
+    /// Returns the P2ID note by which the campaign pays back the `amount` contributed by
+    /// `contributor` in reply to the reclaim note with serial number `serial_num`.
+    pub fn refund(
+        &self,
+        contributor: AccountId,
+        amount: u64,
+        serial_num: Word,
+    ) -> Result<Note, Box<dyn Error>> {
+        let funds = FungibleAsset::new(self.currency, amount)?;
+        Ok(reply_note(self.id, contributor, funds, serial_num)?)
+    }
+}
+
+/// Returns what `contributor` contributed to the campaign `account` and has not reclaimed yet.
+pub fn contribution_of(account: &Account, contributor: AccountId) -> Result<u64, Box<dyn Error>> {
+    let key: Word = [
+        ZERO,
+        ZERO,
+        contributor.suffix(),
+        contributor.prefix().as_felt(),
+    ]
+    .into();
+    Ok(account.storage().get_map_item(CONTRIBUTIONS_SLOT, key)?[3].as_int())
+}
+
+/// Deploys a public campaign in which `creator` raises `goal` tokens of `currency`, accepting
+/// contributions until block `deadline`.
+pub async fn create_campaign(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    creator: AccountId,
+    currency: AccountId,
+    goal: u64,
+    deadline: BlockNumber,
+) -> Result<Account, Box<dyn Error>> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let account_word = |account_id: AccountId| -> Word {
+        [
+            ZERO,
+            ZERO,
+            account_id.suffix(),
+            account_id.prefix().as_felt(),
+        ]
+        .into()
+    };
+    let value_word = |value: u64| -> Word { [ZERO, ZERO, ZERO, Felt::new(value)].into() };
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(account_word(creator)),
+            StorageSlot::Value(account_word(currency)),
+            StorageSlot::Value(value_word(goal)),
+            StorageSlot::Value(value_word(deadline.as_u32().into())),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the crowdfunding contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `sender` to the campaign `campaign_id`.
+pub fn campaign_note(
+    sender: AccountId,
+    campaign_id: AccountId,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(campaign_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+/// Builds a note by which `sender` asks `campaign` for a payout to itself. `script` is the
+/// compiled [`WITHDRAW_NOTE_PATH`] or [`RECLAIM_NOTE_PATH`].
+pub fn payout_request(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    campaign: &Campaign,
+    script: NoteScript,
+) -> Result<Note, NoteError> {
+    let serial_num = client.rng().draw_word();
+    let inputs = vec![NoteTag::from_account_id(sender).into()];
+    campaign_note(sender, campaign.id, inputs, vec![], script, serial_num)
+}
+
+/// Has `sender` create `note` for the campaign.
+pub async fn send_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    note: &Note,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    Ok(client.submit_with_prover(sender, request).await?)
+}
+
+/// Has `campaign` consume `note`, creating `replies`.
+///
+/// The campaign is a `NoAuth` account, so any client tracking it can execute its transactions.
+pub async fn consume_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    campaign: &Campaign,
+    note: &Note,
+    replies: Vec<Note>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note.clone(), None)])
+        .expected_output_recipients(
+            replies
+                .iter()
+                .map(|reply| reply.recipient().clone())
+                .collect(),
+        )
+        .build()?;
+    Ok(client.submit_with_prover(campaign.id, request).await?)
+}
+
+/// Contributes `amount` tokens from the vault of `contributor` to `campaign`. `script` is the
+/// compiled [`CONTRIBUTE_NOTE_PATH`].
+///
+/// Fails without sending anything if the deadline is reached at the current sync height.
+pub async fn contribute(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    contributor: AccountId,
+    campaign: &Campaign,
+    amount: u64,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    if campaign.outcome_at(block_num) != CampaignOutcome::Running {
+        return Err(
+            format!("the campaign stopped accepting contributions at block {block_num}").into(),
+        );
+    }
+
+    let serial_num = client.rng().draw_word();
+    let asset = FungibleAsset::new(campaign.currency, amount)?;
+    let note = campaign_note(
+        contributor,
+        campaign.id,
+        vec![],
+        vec![asset.into()],
+        script,
+        serial_num,
+    )?;
+    send_note(client, contributor, &note).await?;
+    consume_note(client, campaign, &note, vec![]).await
+}
+
+/// Has the creator withdraw the funds raised by `campaign`, which pays them with a P2ID note.
+/// `script` is the compiled [`WITHDRAW_NOTE_PATH`].
+///
+/// Fails without sending anything unless the campaign is funded at the current sync height.
+pub async fn withdraw(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    campaign: &Campaign,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    if campaign.outcome_at(block_num) != CampaignOutcome::Funded || campaign.withdrawn {
+        return Err(format!("the funds cannot be withdrawn at block {block_num}").into());
+    }
+
+    let note = payout_request(client, campaign.creator, campaign, script)?;
+    let payout = campaign.withdrawal(note.serial_num())?;
+    send_note(client, campaign.creator, &note).await?;
+    consume_note(client, campaign, &note, vec![payout]).await
+}
+
+/// Has `contributor` reclaim the `amount` it contributed to `campaign`, which pays it back with
+/// a P2ID note. `amount` is read with [`contribution_of`]. `script` is the compiled
+/// [`RECLAIM_NOTE_PATH`].
+///
+/// Fails without sending anything unless the campaign failed at the current sync height.
+pub async fn reclaim(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    contributor: AccountId,
+    campaign: &Campaign,
+    amount: u64,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    if campaign.outcome_at(block_num) != CampaignOutcome::Failed || amount == 0 {
+        return Err(format!("{contributor} cannot reclaim anything at block {block_num}").into());
+    }
+
+    let note = payout_request(client, contributor, campaign, script)?;
+    let payout = campaign.refund(contributor, amount, note.serial_num())?;
+    send_note(client, contributor, &note).await?;
+    consume_note(client, campaign, &note, vec![payout]).await
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn campaign(raised: u64) -> Campaign {
+        Campaign {
+            id: account(1, AccountType::RegularAccountImmutableCode),
+            creator: account(2, AccountType::RegularAccountUpdatableCode),
+            currency: account(3, AccountType::FungibleFaucet),
+            goal: 100,
+            deadline: BlockNumber::from(50),
+            raised,
+            withdrawn: false,
+        }
+    }
+
+    #[test]
+    fn campaign_runs_until_the_deadline() {
+        let campaign = campaign(150);
+
+        assert_eq!(campaign.outcome_at(49.into()), CampaignOutcome::Running);
+        assert_eq!(campaign.outcome_at(50.into()), CampaignOutcome::Funded);
+    }
+
+    #[test]
+    fn campaign_meeting_its_goal_exactly_is_funded() {
+        assert_eq!(campaign(100).outcome_at(50.into()), CampaignOutcome::Funded);
+        assert_eq!(campaign(99).outcome_at(50.into()), CampaignOutcome::Failed);
+    }
+
+    #[test]
+    fn refunds_pay_the_contributor_its_amount() {
+        let campaign = campaign(60);
+        let contributor = account(4, AccountType::RegularAccountUpdatableCode);
+        let serial_num: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)].into();
+
+        let refund = campaign.refund(contributor, 25, serial_num).unwrap();
+        let expected = reply_note(
+            campaign.id,
+            contributor,
+            FungibleAsset::new(campaign.currency, 25).unwrap(),
+            serial_num,
+        )
+        .unwrap();
+        assert_eq!(refund.id(), expected.id());
+        assert_ne!(refund.id(), campaign.withdrawal(serial_num).unwrap().id());
+    }
+}
@@ -0,0 +1,141 @@
+//! A minimal runtime for long-running example services.
+//!
+//! Relayers, monitors and indexers all follow the same loop: sync, process what is new, sleep,
+//! repeat. [`Daemon`] runs that loop for a [`Service`] and adds what a production service
+//! needs on top:
+//!
+//! - Ctrl+C stops the daemon between two iterations, never in the middle of one, so a
+//!   transaction is never left half-submitted,
+//! - the last processed block is checkpointed to disk after every iteration,
+//! - on restart the service is handed the checkpoint to resume from.
+
+use std::{
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use miden_objects::block::BlockNumber;
+use serde::{Deserialize, Serialize};
+
+/// Directory holding the checkpoints of all daemons, relative to the `rust-client` directory.
+pub const CHECKPOINT_DIR: &str = "./daemons";
+
+/// One iteration of a long-running service.
+#[allow(async_fn_in_trait)]
+pub trait Service {
+    /// Processes everything up to the chain tip and returns the last processed block.
+    ///
+    /// `last_processed` is the block returned by the previous iteration, or the checkpointed
+    /// block when resuming after a restart. It is `None` on the very first run.
+    async fn tick(
+        &mut self,
+        last_processed: Option<BlockNumber>,
+    ) -> Result<BlockNumber, Box<dyn Error>>;
+}
+
+/// Progress of a daemon, persisted between runs.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Checkpoint {
+    last_processed_block: Option<u32>,
+}
+
+/// Runs a [`Service`] until Ctrl+C, checkpointing its progress.
+#[derive(Debug, Clone)]
+pub struct Daemon {
+    name: String,
+    checkpoint_path: PathBuf,
+    poll_interval: Duration,
+}
+
+impl Daemon {
+    /// Creates a daemon checkpointing to `./daemons/<name>.json` and polling every 5 seconds.
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        let checkpoint_path = Path::new(CHECKPOINT_DIR).join(format!("{name}.json"));
+        Self {
+            name,
+            checkpoint_path,
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+
+    /// Sets the pause between two iterations.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Returns the last processed block recorded by a previous run.
+    pub fn checkpoint(&self) -> io::Result<Option<BlockNumber>> {
+        let checkpoint: Checkpoint = match fs::read_to_string(&self.checkpoint_path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Checkpoint::default(),
+            Err(err) => return Err(err),
+        };
+        Ok(checkpoint.last_processed_block.map(BlockNumber::from))
+    }
+
+    /// Records `block_num` as the last processed block.
+    ///
+    /// The checkpoint is written to a temporary file first and then renamed, so a crash while
+    /// writing never leaves a truncated checkpoint behind.
+    pub fn save_checkpoint(&self, block_num: BlockNumber) -> io::Result<()> {
+        let checkpoint = Checkpoint {
+            last_processed_block: Some(block_num.as_u32()),
+        };
+        let contents = serde_json::to_string_pretty(&checkpoint)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        fs::create_dir_all(CHECKPOINT_DIR)?;
+        let tmp_path = self.checkpoint_path.with_extension("json.tmp");
+        fs::write(&tmp_path, contents)?;
+        fs::rename(tmp_path, &self.checkpoint_path)
+    }
+
+    /// Runs `service` until Ctrl+C is pressed or an iteration fails.
+    ///
+    /// The checkpoint is only advanced after an iteration succeeds, so a failed iteration is
+    /// retried from the same block on the next start.
+    pub async fn run(&self, service: &mut impl Service) -> Result<(), Box<dyn Error>> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_signal = stop.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                println!("\nStopping after the current iteration...");
+                stop_signal.store(true, Ordering::SeqCst);
+            }
+        });
+
+        let mut last_processed = self.checkpoint()?;
+        match last_processed {
+            Some(block_num) => println!("[{}] Resuming after block {block_num}", self.name),
+            None => println!("[{}] Starting from scratch", self.name),
+        }
+
+        while !stop.load(Ordering::SeqCst) {
+            let block_num = service.tick(last_processed).await?;
+            self.save_checkpoint(block_num)?;
+            last_processed = Some(block_num);
+
+            // Sleep in short steps so that Ctrl+C is honored promptly
+            let step = Duration::from_millis(200);
+            let mut slept = Duration::ZERO;
+            while slept < self.poll_interval && !stop.load(Ordering::SeqCst) {
+                tokio::time::sleep(step).await;
+                slept += step;
+            }
+        }
+
+        if let Some(block_num) = last_processed {
+            println!("[{}] Stopped after block {block_num}", self.name);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+//! Deployment of contracts whose initial storage depends on deployment-time arguments.
+//!
+//! A contract cannot receive arguments when its account is built: its storage starts with the
+//! slots given to the component. Values known only at deployment (an owner, a rate, a deadline)
+//! are instead passed to a `constructor` procedure by the deployment transaction, which writes
+//! them into storage. [`Deployer`] keeps the contract code and its constructor arguments
+//! together so that the deployment script always matches the procedure it calls.
+
+use std::error::Error;
+
+use miden_client::{
+    assembly::Library,
+    keystore::FilesystemKeyStore,
+    transaction::{TransactionId, TransactionRequestBuilder},
+    Client,
+};
+use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        Account, AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot,
+    },
+    Felt,
+};
+use rand::rngs::StdRng;
+
+use crate::{cli::SubmitWithProver, helpers::create_library};
+
+/// Name of the procedure called by the deployment transaction, unless overridden.
+pub const DEFAULT_CONSTRUCTOR: &str = "constructor";
+
+/// Contract code packaged with the arguments of its constructor.
+#[derive(Debug, Clone)]
+pub struct Deployer {
+    code: String,
+    library_path: String,
+    storage: Vec<StorageSlot>,
+    constructor: String,
+    args: Vec<Felt>,
+}
+
+impl Deployer {
+    /// Creates a deployer for `code`, linked under `library_path` (e.g.
+    /// `external_contract::escrow`) in the deployment script.
+    pub fn new(code: impl Into<String>, library_path: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            library_path: library_path.into(),
+            storage: Vec::new(),
+            constructor: DEFAULT_CONSTRUCTOR.to_string(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Sets the storage slots the account is created with, before the constructor runs.
+    pub fn with_storage(mut self, storage: Vec<StorageSlot>) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Calls `procedure` instead of [`DEFAULT_CONSTRUCTOR`].
+    pub fn with_constructor(mut self, procedure: impl Into<String>) -> Self {
+        self.constructor = procedure.into();
+        self
+    }
+
+    /// Appends a constructor argument. Arguments end up on the stack in the order they are
+    /// added, the first one on top.
+    pub fn with_arg(mut self, arg: impl Into<Felt>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Appends several constructor arguments, see [`Self::with_arg`].
+    pub fn with_args(mut self, args: impl IntoIterator<Item = Felt>) -> Self {
+        self.args.extend(args);
+        self
+    }
+
+    /// Compiles the contract into a library that scripts can link against.
+    pub fn library(&self) -> Result<Library, Box<dyn Error>> {
+        create_library(&self.code, &self.library_path)
+    }
+
+    /// Builds the contract account. Its storage holds the initial slots only until the
+    /// deployment transaction has run the constructor.
+    pub fn build_account(
+        &self,
+        seed: [u8; 32],
+        storage_mode: AccountStorageMode,
+    ) -> Result<Account, Box<dyn Error>> {
+        let component = AccountComponent::compile(
+            &self.code,
+            TransactionKernel::assembler(),
+            self.storage.clone(),
+        )?
+        .with_supports_all_types();
+
+        Ok(AccountBuilder::new(seed)
+            .account_type(AccountType::RegularAccountImmutableCode)
+            .storage_mode(storage_mode)
+            .with_auth_component(NoAuth)
+            .with_component(component)
+            .build()?)
+    }
+
+    /// Returns the source of the deployment script, pushing the arguments and calling the
+    /// constructor.
+    pub fn script_source(&self) -> String {
+        let module = self
+            .library_path
+            .rsplit("::")
+            .next()
+            .unwrap_or(&self.library_path);
+        let pushes: String = self
+            .args
+            .iter()
+            .rev()
+            .map(|arg| format!("    push.{}\n", arg.as_int()))
+            .collect();
+
+        format!(
+            "use.{}\n\nbegin\n{}    call.{}::{}\nend\n",
+            self.library_path, pushes, module, self.constructor
+        )
+    }
+
+    /// Creates the account in `client` and submits the transaction running its constructor.
+    pub async fn deploy(
+        &self,
+        client: &mut Client<FilesystemKeyStore<StdRng>>,
+        seed: [u8; 32],
+        storage_mode: AccountStorageMode,
+    ) -> Result<(Account, TransactionId), Box<dyn Error>> {
+        let account = self.build_account(seed, storage_mode)?;
+        client.add_account(&account, false).await?;
+
+        let tx_script = client
+            .script_builder()
+            .with_dynamically_linked_library(&self.library()?)?
+            .compile_tx_script(&self.script_source())?;
+        let request = TransactionRequestBuilder::new()
+            .custom_script(tx_script)
+            .build()?;
+        let tx_id = client.submit_with_prover(account.id(), request).await?;
+
+        Ok((account, tx_id))
+    }
+}
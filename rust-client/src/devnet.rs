@@ -0,0 +1,43 @@
+//! The shared set of contracts deployed by the `devnet_setup` binary.
+//!
+//! `devnet_setup` deploys a faucet, a name service and an oracle reader once per network and
+//! stores their ids in the known accounts registry under the labels below. Tutorials can reuse
+//! them through [`shared_account`] instead of deploying their own copy on every run.
+
+use miden_client::{account::AccountId, keystore::FilesystemKeyStore, Client, ClientError};
+use rand::rngs::StdRng;
+
+use crate::known_accounts::{KnownAccounts, DEFAULT_REGISTRY_PATH};
+
+/// Name of the [`TutorialContext`](crate::context::TutorialContext) holding the keys and store
+/// of the shared accounts, opened by `devnet_setup` and the binaries working on its accounts.
+pub const STATE: &str = "devnet";
+
+/// Fungible faucet of the shared token. Minting requires its key, which only the keystore that
+/// ran `devnet_setup` holds.
+pub const FAUCET: &str = "devnet.faucet";
+/// Wallet holding the initial supply of the shared token.
+pub const TREASURY: &str = "devnet.treasury";
+/// Handle registry (`masm/accounts/handle_registry.masm`).
+pub const NAME_SERVICE: &str = "devnet.name_service";
+/// Oracle reader (`masm/accounts/oracle_reader.masm`).
+pub const ORACLE_READER: &str = "devnet.oracle_reader";
+
+/// Returns the shared account registered under `label`, importing it into `client` if the
+/// client does not track it yet. Returns `None` if `devnet_setup` has not registered it.
+pub async fn shared_account(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    label: &str,
+) -> Result<Option<AccountId>, ClientError> {
+    let Some(account_id) = KnownAccounts::load(DEFAULT_REGISTRY_PATH)
+        .ok()
+        .and_then(|registry| registry.get(label))
+    else {
+        return Ok(None);
+    };
+
+    if client.get_account(account_id).await?.is_none() {
+        client.import_account_by_id(account_id).await?;
+    }
+    Ok(Some(account_id))
+}
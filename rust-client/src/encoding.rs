@@ -0,0 +1,338 @@
+//! Packing strings, booleans, enums, timestamps and flags into field elements, in Rust and with
+//! the checks of `masm/lib/encoding.masm`.
+//!
+//! Storage slots and note inputs only hold field elements, so every richer value needs an
+//! encoding both the client and the contract agree on. The ones below are the conventions the
+//! tutorials share:
+//!
+//! - a short string of up to [`MAX_STRING_LEN`] bytes takes a word, [`BYTES_PER_ELEMENT`] bytes
+//!   per element, little-endian and padded with zeros;
+//! - a boolean is 0 or 1;
+//! - an enum is the index of its variant in a list of all of them, e.g. `Hand::ALL`;
+//! - a timestamp is a u32 number of seconds since the Unix epoch, like block timestamps;
+//! - up to 32 flags are the bits of a u32, the first flag in the lowest bit.
+//!
+//! Decoders reject every value the encoders never produce, so a value read back from storage
+//! either decodes to what was written or fails.
+
+use std::{
+    error::Error,
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use miden_objects::{assembly::Library, Felt, Word, ONE, ZERO};
+
+use crate::helpers::{create_library, execute_program};
+
+/// Path under which the library is linked into programs.
+pub const LIBRARY_PATH: &str = "tutorial::encoding";
+/// Location of the library, relative to `rust-client`.
+pub const LIBRARY_FILE_PATH: &str = "../masm/lib/encoding.masm";
+
+/// Bytes of a string each element of a word holds: 7 bytes are below the field modulus.
+pub const BYTES_PER_ELEMENT: usize = 7;
+/// Longest string that fits into a word.
+pub const MAX_STRING_LEN: usize = 4 * BYTES_PER_ELEMENT;
+/// Number of flags that fit into an element.
+pub const MAX_FLAGS: usize = 32;
+
+/// Reasons a value cannot be encoded, or an element does not decode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodingError {
+    /// The string has more than [`MAX_STRING_LEN`] bytes.
+    StringTooLong(usize),
+    /// The string holds a zero byte, which would be read back as padding.
+    NulInString,
+    /// The word is not the encoding of a UTF-8 string.
+    NotAString,
+    NotABool(u64),
+    /// The element is not the index of a variant.
+    UnknownVariant(u64),
+    /// The time is before the Unix epoch or past what a u32 of seconds holds.
+    TimestampOutOfRange,
+    /// More than [`MAX_FLAGS`] flags were given.
+    TooManyFlags(usize),
+    /// The element has bits set past the flags it is read as.
+    NotFlags(u64),
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StringTooLong(len) => {
+                write!(f, "string of {len} bytes is longer than {MAX_STRING_LEN}")
+            }
+            Self::NulInString => write!(f, "string holds a zero byte"),
+            Self::NotAString => write!(f, "word is not an encoded string"),
+            Self::NotABool(value) => write!(f, "{value} is not a boolean"),
+            Self::UnknownVariant(value) => write!(f, "{value} is not a variant of the enum"),
+            Self::TimestampOutOfRange => write!(f, "timestamp does not fit into a u32"),
+            Self::TooManyFlags(count) => write!(f, "{count} flags do not fit into a u32"),
+            Self::NotFlags(value) => write!(f, "{value} has bits set past the flags"),
+        }
+    }
+}
+
+impl Error for EncodingError {}
+
+/// Packs `string` into a word, e.g. a handle registered with the name service.
+pub fn encode_string(string: &str) -> Result<Word, EncodingError> {
+    let bytes = string.as_bytes();
+    if bytes.len() > MAX_STRING_LEN {
+        return Err(EncodingError::StringTooLong(bytes.len()));
+    }
+    if bytes.contains(&0) {
+        return Err(EncodingError::NulInString);
+    }
+
+    let mut elements = [ZERO; 4];
+    for (element, chunk) in elements.iter_mut().zip(bytes.chunks(BYTES_PER_ELEMENT)) {
+        let mut padded = [0_u8; 8];
+        padded[..chunk.len()].copy_from_slice(chunk);
+        *element = Felt::new(u64::from_le_bytes(padded));
+    }
+    Ok(Word::from(elements))
+}
+
+/// Unpacks a string packed by [`encode_string`].
+pub fn decode_string(word: Word) -> Result<String, EncodingError> {
+    let mut bytes = Vec::with_capacity(MAX_STRING_LEN);
+    for element in word.iter() {
+        let le_bytes = element.as_int().to_le_bytes();
+        if le_bytes[BYTES_PER_ELEMENT] != 0 {
+            return Err(EncodingError::NotAString);
+        }
+        bytes.extend_from_slice(&le_bytes[..BYTES_PER_ELEMENT]);
+    }
+
+    // Zeros are only ever padding, at the end
+    let len = bytes
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |last| last + 1);
+    bytes.truncate(len);
+    if bytes.contains(&0) {
+        return Err(EncodingError::NotAString);
+    }
+    String::from_utf8(bytes).map_err(|_| EncodingError::NotAString)
+}
+
+/// Returns 1 for `true` and 0 for `false`.
+pub fn encode_bool(value: bool) -> Felt {
+    if value {
+        ONE
+    } else {
+        ZERO
+    }
+}
+
+/// Reads an element encoded by [`encode_bool`].
+pub fn decode_bool(value: Felt) -> Result<bool, EncodingError> {
+    match value.as_int() {
+        0 => Ok(false),
+        1 => Ok(true),
+        other => Err(EncodingError::NotABool(other)),
+    }
+}
+
+/// Returns the index of `variant` in `variants`, the list of all variants of its enum.
+///
+/// # Panics
+/// Panics if `variant` is not in `variants`.
+pub fn encode_variant<T: PartialEq>(variant: &T, variants: &[T]) -> Felt {
+    let index = variants
+        .iter()
+        .position(|candidate| candidate == variant)
+        .expect("the variant is missing from the list of variants");
+    Felt::new(index as u64)
+}
+
+/// Returns the variant at index `value` of `variants`.
+pub fn decode_variant<T: Copy>(value: Felt, variants: &[T]) -> Result<T, EncodingError> {
+    usize::try_from(value.as_int())
+        .ok()
+        .and_then(|index| variants.get(index))
+        .copied()
+        .ok_or(EncodingError::UnknownVariant(value.as_int()))
+}
+
+/// Returns `time` in seconds since the Unix epoch, dropping fractions of a second.
+pub fn encode_timestamp(time: SystemTime) -> Result<Felt, EncodingError> {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| EncodingError::TimestampOutOfRange)?
+        .as_secs();
+    u32::try_from(seconds)
+        .map(Felt::from)
+        .map_err(|_| EncodingError::TimestampOutOfRange)
+}
+
+/// Reads a timestamp encoded by [`encode_timestamp`].
+pub fn decode_timestamp(value: Felt) -> Result<SystemTime, EncodingError> {
+    let seconds = u32::try_from(value.as_int()).map_err(|_| EncodingError::TimestampOutOfRange)?;
+    Ok(UNIX_EPOCH + Duration::from_secs(seconds.into()))
+}
+
+/// Packs `flags` into the bits of an element, the first flag in the lowest bit.
+pub fn encode_flags(flags: &[bool]) -> Result<Felt, EncodingError> {
+    if flags.len() > MAX_FLAGS {
+        return Err(EncodingError::TooManyFlags(flags.len()));
+    }
+    let bits = flags.iter().enumerate().fold(0_u64, |bits, (index, flag)| {
+        bits | (u64::from(*flag) << index)
+    });
+    Ok(Felt::new(bits))
+}
+
+/// Unpacks `count` flags packed by [`encode_flags`].
+pub fn decode_flags(value: Felt, count: usize) -> Result<Vec<bool>, EncodingError> {
+    if count > MAX_FLAGS {
+        return Err(EncodingError::TooManyFlags(count));
+    }
+    let bits = value.as_int();
+    if bits >> count != 0 {
+        return Err(EncodingError::NotFlags(bits));
+    }
+    Ok((0..count).map(|index| (bits >> index) & 1 == 1).collect())
+}
+
+/// Compiles the encoding library from its source.
+pub fn library(source: &str) -> Result<Library, Box<dyn Error>> {
+    create_library(source, LIBRARY_PATH)
+}
+
+/// Executes `encoding::<procedure>` on the stack `inputs`, top first, and returns the value it
+/// leaves on top of the stack.
+pub fn execute_procedure(
+    library: &Library,
+    procedure: &str,
+    inputs: &[Felt],
+) -> Result<u64, Box<dyn Error>> {
+    let pushes: Vec<String> = inputs
+        .iter()
+        .rev()
+        .map(|input| format!("push.{}", input.as_int()))
+        .collect();
+    let code = format!(
+        "use.{LIBRARY_PATH}
+
+begin
+    {}
+    exec.encoding::{procedure}
+    # => [result, pad(16)]
+
+    swap drop
+end
+",
+        pushes.join(" ")
+    );
+    execute_program(&code, Some(library))
+}
+
+/// Executes `encoding::assert_short_string` on `word`.
+pub fn check_string(library: &Library, word: Word) -> Result<(), Box<dyn Error>> {
+    let inputs: Vec<Felt> = word.iter().rev().copied().collect();
+    execute_procedure(library, "assert_short_string", &inputs).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+
+    fn encoding_library() -> Library {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(LIBRARY_FILE_PATH);
+        library(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn strings_round_trip_through_a_word() {
+        let library = encoding_library();
+        let longest = "x".repeat(MAX_STRING_LEN);
+        for string in ["alice@miden", "a", "émoji 🦀", longest.as_str()] {
+            let word = encode_string(string).unwrap();
+            assert_eq!(decode_string(word).unwrap(), string);
+            check_string(&library, word).unwrap();
+        }
+
+        let too_long = "x".repeat(MAX_STRING_LEN + 1);
+        assert_eq!(
+            encode_string(&too_long),
+            Err(EncodingError::StringTooLong(MAX_STRING_LEN + 1))
+        );
+        assert_eq!(encode_string("a\0b"), Err(EncodingError::NulInString));
+    }
+
+    #[test]
+    fn words_which_are_not_strings_are_rejected() {
+        let library = encoding_library();
+        let cases = [
+            (Word::default(), "String is empty"),
+            (
+                Word::from([Felt::new(1 << 56), ZERO, ZERO, ZERO]),
+                "String element holds more than 7 bytes",
+            ),
+            (
+                Word::from([Felt::new(97), ZERO, Felt::new(98), ZERO]),
+                "String has an empty element before a non-empty one",
+            ),
+        ];
+
+        for (word, error) in cases {
+            let err = check_string(&library, word).unwrap_err();
+            assert!(format!("{err:?}").contains(error), "{word:?}: {err:?}");
+        }
+        assert_eq!(decode_string(cases[1].0), Err(EncodingError::NotAString));
+        assert_eq!(decode_string(cases[2].0), Err(EncodingError::NotAString));
+        assert_eq!(decode_string(Word::default()).unwrap(), "");
+    }
+
+    #[test]
+    fn scalars_round_trip_and_match_the_library() {
+        let library = encoding_library();
+
+        for value in [false, true] {
+            let felt = encode_bool(value);
+            assert_eq!(decode_bool(felt), Ok(value));
+            execute_procedure(&library, "assert_bool", &[felt]).unwrap();
+        }
+        assert_eq!(decode_bool(Felt::new(7)), Err(EncodingError::NotABool(7)));
+        assert!(execute_procedure(&library, "assert_bool", &[Felt::new(7)]).is_err());
+
+        let variants = ["rock", "paper", "scissors"];
+        let felt = encode_variant(&"scissors", &variants);
+        assert_eq!(decode_variant(felt, &variants), Ok("scissors"));
+        assert_eq!(
+            decode_variant(Felt::new(3), &variants),
+            Err(EncodingError::UnknownVariant(3))
+        );
+        let num_variants = Felt::new(variants.len() as u64);
+        execute_procedure(&library, "assert_variant", &[num_variants, felt]).unwrap();
+        assert!(
+            execute_procedure(&library, "assert_variant", &[num_variants, Felt::new(3)]).is_err()
+        );
+
+        let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(decode_timestamp(encode_timestamp(time).unwrap()), Ok(time));
+        let past_u32 = UNIX_EPOCH + Duration::from_secs(1 << 32);
+        assert_eq!(
+            encode_timestamp(past_u32),
+            Err(EncodingError::TimestampOutOfRange)
+        );
+
+        let flags = [true, false, true, true];
+        let felt = encode_flags(&flags).unwrap();
+        assert_eq!(felt.as_int(), 0b1101);
+        assert_eq!(decode_flags(felt, flags.len()).unwrap(), flags);
+        assert_eq!(decode_flags(felt, 2), Err(EncodingError::NotFlags(0b1101)));
+        for (index, flag) in flags.into_iter().enumerate() {
+            let index = Felt::new(index as u64);
+            let is_set = execute_procedure(&library, "get_flag", &[index, felt]).unwrap();
+            assert_eq!(is_set, u64::from(flag));
+        }
+        let cleared = execute_procedure(&library, "set_flag", &[Felt::new(2), ZERO, felt]).unwrap();
+        assert_eq!(cleared, 0b1001);
+    }
+}
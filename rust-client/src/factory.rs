@@ -0,0 +1,157 @@
+//! Per-user contract instances created through a factory account.
+//!
+//! Accounts cannot deploy other accounts. A factory in Miden is therefore split in two: a
+//! registry contract (`masm/accounts/factory.masm`) mapping every owner to its instance, and
+//! off-chain code deploying each instance as its own account and registering it. [`Factory`]
+//! is that off-chain half. It derives the seed of an instance from the factory and owner ids,
+//! so the id of an owner's instance is known before it exists.
+
+use std::error::Error;
+
+use miden_client::{
+    account::{Account, AccountId},
+    assembly::Library,
+    keystore::FilesystemKeyStore,
+    transaction::TransactionRequestBuilder,
+    Client,
+};
+use miden_objects::{account::AccountStorageMode, crypto::hash::rpo::Rpo256, Felt, Word, ZERO};
+use rand::rngs::StdRng;
+
+use crate::{cli::SubmitWithProver, deployer::Deployer};
+
+/// Storage slot of the factory holding the owner => instance map.
+pub const INSTANCES_SLOT: u8 = 0;
+
+/// Off-chain side of a factory contract.
+#[derive(Debug, Clone)]
+pub struct Factory {
+    factory_id: AccountId,
+    library: Library,
+    instance: Deployer,
+    storage_mode: AccountStorageMode,
+}
+
+impl Factory {
+    /// Creates the factory for the deployed registry `factory_id`, whose code is compiled into
+    /// `library` under `external_contract::factory`.
+    ///
+    /// `instance` describes the contract deployed for every owner. Its constructor receives
+    /// the owner id first (`[owner_prefix, owner_suffix, ...]`), followed by the arguments
+    /// given to [`Self::create_instance`].
+    pub fn new(factory_id: AccountId, library: Library, instance: Deployer) -> Self {
+        Self {
+            factory_id,
+            library,
+            instance,
+            storage_mode: AccountStorageMode::Public,
+        }
+    }
+
+    /// Sets the storage mode of the instances. Instances are public by default.
+    pub fn with_storage_mode(mut self, storage_mode: AccountStorageMode) -> Self {
+        self.storage_mode = storage_mode;
+        self
+    }
+
+    /// Returns the seed of the instance of `owner`.
+    pub fn instance_seed(&self, owner: AccountId) -> [u8; 32] {
+        Rpo256::hash_elements(&[
+            self.factory_id.prefix().as_felt(),
+            self.factory_id.suffix(),
+            owner.prefix().as_felt(),
+            owner.suffix(),
+        ])
+        .as_bytes()
+    }
+
+    /// Computes the id of the instance of `owner`, whether or not it is deployed.
+    ///
+    /// Constructor arguments only reach storage after deployment, so they do not change the id.
+    pub fn instance_id(&self, owner: AccountId) -> Result<AccountId, Box<dyn Error>> {
+        let account = self
+            .instance
+            .build_account(self.instance_seed(owner), self.storage_mode)?;
+        Ok(account.id())
+    }
+
+    /// Returns the key under which the instance of `owner` is registered.
+    pub fn registry_key(owner: AccountId) -> Word {
+        [ZERO, ZERO, owner.suffix(), owner.prefix().as_felt()].into()
+    }
+
+    /// Reads the instance of `owner` from a copy of the factory account.
+    pub fn registered_instance(factory: &Account, owner: AccountId) -> Option<AccountId> {
+        let value = factory
+            .storage()
+            .get_map_item(INSTANCES_SLOT, Self::registry_key(owner))
+            .ok()?;
+        if value == Word::default() {
+            return None;
+        }
+        AccountId::try_from([value[3], value[2]]).ok()
+    }
+
+    /// Returns the source of the transaction script registering `instance` for `owner`.
+    pub fn registration_script(owner: AccountId, instance: AccountId) -> String {
+        format!(
+            "use.external_contract::factory
+
+begin
+    push.{instance_suffix}
+    push.{instance_prefix}
+    push.{owner_suffix}
+    push.{owner_prefix}
+    call.factory::register
+end
+",
+            instance_suffix = instance.suffix(),
+            instance_prefix = instance.prefix(),
+            owner_suffix = owner.suffix(),
+            owner_prefix = owner.prefix(),
+        )
+    }
+
+    /// Deploys the instance of `owner` and registers it in the factory.
+    ///
+    /// Fails before deploying anything if the factory already has an instance for `owner`.
+    /// The factory must be tracked by `client`, and its keys must be in the keystore when the
+    /// registry is protected by an auth component.
+    pub async fn create_instance(
+        &self,
+        client: &mut Client<FilesystemKeyStore<StdRng>>,
+        owner: AccountId,
+        args: impl IntoIterator<Item = Felt>,
+    ) -> Result<Account, Box<dyn Error>> {
+        let factory = client
+            .get_account(self.factory_id)
+            .await?
+            .ok_or("the factory is not tracked by the client")?;
+        if let Some(existing) = Self::registered_instance(factory.account(), owner) {
+            return Err(format!("owner already has instance {}", existing.to_hex()).into());
+        }
+
+        // Deploy the instance, passing the owner to its constructor
+        let deployer = self
+            .instance
+            .clone()
+            .with_arg(owner.prefix().as_felt())
+            .with_arg(owner.suffix())
+            .with_args(args);
+        let (instance, _) = deployer
+            .deploy(client, self.instance_seed(owner), self.storage_mode)
+            .await?;
+
+        // Register it in the factory
+        let tx_script = client
+            .script_builder()
+            .with_dynamically_linked_library(&self.library)?
+            .compile_tx_script(&Self::registration_script(owner, instance.id()))?;
+        let request = TransactionRequestBuilder::new()
+            .custom_script(tx_script)
+            .build()?;
+        client.submit_with_prover(self.factory_id, request).await?;
+
+        Ok(instance)
+    }
+}
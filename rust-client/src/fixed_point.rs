@@ -0,0 +1,281 @@
+//! Fixed-point arithmetic, in MASM (`masm/lib/fixed_point.masm`) and as a Rust reference.
+//!
+//! The VM only computes on integers, so decimals are stored scaled: a fixed-point number `x`
+//! stands for `x / SCALE`. Multiplying or dividing two of them has to scale the result back, and
+//! the digits past the last decimal have to go somewhere. Every operation therefore takes a
+//! [`Rounding`]: a contract rounds what it pays out down and what it charges up, so that
+//! rounding never drains it one unit at a time.
+//!
+//! The Rust functions compute exactly what the MASM procedures compute, so off-chain code can
+//! predict a contract's result, and the tests check both agree.
+
+use std::{error::Error, fmt};
+
+use miden_objects::assembly::Library;
+
+use crate::helpers::{create_library, execute_program};
+
+/// Path under which the library is linked into programs.
+pub const LIBRARY_PATH: &str = "tutorial::fixed_point";
+/// Location of the library, relative to `rust-client`.
+pub const LIBRARY_FILE_PATH: &str = "../masm/lib/fixed_point.masm";
+
+/// 1.0 as a fixed-point number: six decimals.
+pub const SCALE: u32 = 1_000_000;
+
+/// How the digits past the last one kept are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward zero.
+    Down,
+    /// Away from zero.
+    Up,
+    /// To the nearest value, halves away from zero.
+    Nearest,
+}
+
+impl Rounding {
+    pub const ALL: [Self; 3] = [Self::Down, Self::Up, Self::Nearest];
+
+    /// Returns the mode the MASM procedures take on top of the stack.
+    pub fn mode(self) -> u64 {
+        match self {
+            Self::Down => 0,
+            Self::Up => 1,
+            Self::Nearest => 2,
+        }
+    }
+}
+
+/// Reasons a fixed-point operation has no result, mirroring the errors of the MASM library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixedPointError {
+    DivisionByZero,
+    /// The result does not fit into a u32.
+    Overflow,
+}
+
+impl fmt::Display for FixedPointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DivisionByZero => write!(f, "fixed-point division by zero"),
+            Self::Overflow => write!(f, "fixed-point result does not fit into a u32"),
+        }
+    }
+}
+
+impl std::error::Error for FixedPointError {}
+
+/// Returns `a * b / c`, rounded according to `rounding`, like `fixed_point::mul_div`.
+pub fn mul_div(a: u32, b: u32, c: u32, rounding: Rounding) -> Result<u32, FixedPointError> {
+    if c == 0 {
+        return Err(FixedPointError::DivisionByZero);
+    }
+
+    let product = u64::from(a) * u64::from(b);
+    let divisor = u64::from(c);
+    let (quotient, remainder) = (product / divisor, product % divisor);
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => remainder != 0,
+        Rounding::Nearest => remainder >= divisor - remainder,
+    };
+
+    u32::try_from(quotient + u64::from(round_up)).map_err(|_| FixedPointError::Overflow)
+}
+
+/// Returns `a * b / SCALE`, like `fixed_point::mul`: the product of two fixed-point numbers,
+/// or of an amount by a fixed-point rate.
+pub fn mul(a: u32, b: u32, rounding: Rounding) -> Result<u32, FixedPointError> {
+    mul_div(a, b, SCALE, rounding)
+}
+
+/// Returns `a * SCALE / b`, like `fixed_point::div`: the quotient of two fixed-point numbers,
+/// or the ratio of two amounts as a fixed-point number.
+pub fn div(a: u32, b: u32, rounding: Rounding) -> Result<u32, FixedPointError> {
+    mul_div(a, SCALE, b, rounding)
+}
+
+/// Renders a fixed-point number with all its decimals, e.g. `1.050000`.
+pub fn to_decimal(value: u32) -> String {
+    format!("{}.{:06}", value / SCALE, value % SCALE)
+}
+
+/// Compiles the fixed-point library from its source.
+pub fn library(source: &str) -> Result<Library, Box<dyn Error>> {
+    create_library(source, LIBRARY_PATH)
+}
+
+/// Executes `fixed_point::<procedure>` in the VM on the `operands` it takes below the rounding
+/// mode, and returns the value it leaves on top of the stack.
+///
+/// Operands are `u64` so that values the library rejects can be passed as well.
+pub fn execute_procedure(
+    library: &Library,
+    procedure: &str,
+    rounding: Rounding,
+    operands: &[u64],
+) -> Result<u64, Box<dyn Error>> {
+    let mut inputs = vec![rounding.mode()];
+    inputs.extend(operands);
+    run(library, procedure, &inputs)
+}
+
+/// Executes `fixed_point::<procedure>` on the stack `inputs`, top first.
+fn run(library: &Library, procedure: &str, inputs: &[u64]) -> Result<u64, Box<dyn Error>> {
+    let pushes: Vec<String> = inputs
+        .iter()
+        .rev()
+        .map(|input| format!("push.{input}"))
+        .collect();
+    let code = format!(
+        "use.{LIBRARY_PATH}
+
+begin
+    {}
+    exec.fixed_point::{procedure}
+    # => [result, pad(16)]
+
+    swap drop
+end
+",
+        pushes.join(" ")
+    );
+    execute_program(&code, Some(library))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    /// Cases each property is checked on.
+    const CASES: usize = 64;
+
+    fn fixed_point_library() -> Library {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(LIBRARY_FILE_PATH);
+        library(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    /// Returns a u32 drawn from ranges where rounding and overflows happen.
+    fn operand(rng: &mut StdRng) -> u32 {
+        match rng.random_range(0..3) {
+            0 => rng.random_range(0..1_000),
+            1 => rng.random_range(0..10 * SCALE),
+            _ => rng.random(),
+        }
+    }
+
+    /// Checks the VM agrees with the reference on a result, or on the error it fails with.
+    fn assert_agrees(
+        expected: Result<u32, FixedPointError>,
+        actual: Result<u64, Box<dyn Error>>,
+        case: &str,
+    ) {
+        match (expected, actual) {
+            (Ok(expected), Ok(actual)) => assert_eq!(u64::from(expected), actual, "{case}"),
+            (Err(expected), Err(actual)) => {
+                let message = match expected {
+                    FixedPointError::DivisionByZero => "Fixed-point division by zero",
+                    FixedPointError::Overflow => "Fixed-point result does not fit into a u32",
+                };
+                assert!(
+                    format!("{actual:?}").contains(message),
+                    "{case}: {actual:?}"
+                );
+            }
+            (expected, actual) => panic!("{case}: expected {expected:?}, got {actual:?}"),
+        }
+    }
+
+    #[test]
+    fn rounding_modes_handle_the_remainder() {
+        // 7 / 2 = 3.5, 10 / 3 = 3.33.., 20 / 3 = 6.66..
+        assert_eq!(mul_div(7, 1, 2, Rounding::Down), Ok(3));
+        assert_eq!(mul_div(7, 1, 2, Rounding::Up), Ok(4));
+        assert_eq!(mul_div(7, 1, 2, Rounding::Nearest), Ok(4));
+        assert_eq!(mul_div(10, 1, 3, Rounding::Nearest), Ok(3));
+        assert_eq!(mul_div(20, 1, 3, Rounding::Nearest), Ok(7));
+        // An exact quotient is never rounded
+        assert_eq!(mul_div(9, 1, 3, Rounding::Up), Ok(3));
+
+        assert_eq!(mul(1_500_000, 2_500_000, Rounding::Down), Ok(3_750_000));
+        assert_eq!(div(SCALE, 3 * SCALE, Rounding::Up), Ok(333_334));
+        assert_eq!(to_decimal(1_050_000), "1.050000");
+    }
+
+    #[test]
+    fn results_past_u32_are_rejected() {
+        // The product is past u32 but the quotient is not
+        assert_eq!(mul_div(u32::MAX, 4, 4, Rounding::Up), Ok(u32::MAX));
+        assert_eq!(
+            mul(u32::MAX, 2 * SCALE, Rounding::Down),
+            Err(FixedPointError::Overflow)
+        );
+        // Rounding up can carry the quotient past u32
+        assert_eq!(
+            mul_div(u32::MAX, 3, 2, Rounding::Up),
+            Err(FixedPointError::Overflow)
+        );
+        assert_eq!(
+            div(1, 0, Rounding::Down),
+            Err(FixedPointError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn masm_mul_div_agrees_with_the_reference() {
+        let library = fixed_point_library();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..CASES {
+            let (a, b, c) = (operand(&mut rng), operand(&mut rng), operand(&mut rng));
+            for rounding in Rounding::ALL {
+                let operands = [a, b, c].map(u64::from);
+                assert_agrees(
+                    mul_div(a, b, c, rounding),
+                    execute_procedure(&library, "mul_div", rounding, &operands),
+                    &format!("mul_div({a}, {b}, {c}, {rounding:?})"),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn masm_mul_and_div_agree_with_the_reference() {
+        let library = fixed_point_library();
+        let mut rng = StdRng::seed_from_u64(2);
+
+        for _ in 0..CASES {
+            let (a, b) = (operand(&mut rng), operand(&mut rng));
+            for rounding in Rounding::ALL {
+                let operands = [u64::from(a), u64::from(b)];
+                assert_agrees(
+                    mul(a, b, rounding),
+                    execute_procedure(&library, "mul", rounding, &operands),
+                    &format!("mul({a}, {b}, {rounding:?})"),
+                );
+                assert_agrees(
+                    div(a, b, rounding),
+                    execute_procedure(&library, "div", rounding, &operands),
+                    &format!("div({a}, {b}, {rounding:?})"),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn masm_rejects_operands_past_u32_and_unknown_modes() {
+        let library = fixed_point_library();
+
+        let result = execute_procedure(&library, "mul", Rounding::Down, &[1 << 32, 1]);
+        assert!(format!("{:?}", result.unwrap_err())
+            .contains("Fixed-point operand does not fit into a u32"));
+
+        let result = run(&library, "mul", &[3, 1, 1]);
+        assert!(format!("{:?}", result.unwrap_err()).contains("Unknown rounding mode"));
+    }
+}
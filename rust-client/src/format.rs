@@ -0,0 +1,160 @@
+//! Human readable formatting of assets.
+//!
+//! Vaults only store raw base-unit amounts and faucet ids. [`AssetFormatter`] looks up the
+//! token symbol and decimals of every faucet once and renders amounts like `12.5 MID`, or takes
+//! them all at once from a [`TokenRegistry`].
+
+use std::{collections::BTreeMap, error::Error};
+
+use miden_client::{
+    account::{component::BasicFungibleFaucet, AccountId},
+    address::NetworkId,
+    asset::Asset,
+    rpc::{domain::account::FetchedAccount, NodeRpcClient, RpcError},
+};
+
+use crate::token_registry::TokenRegistry;
+
+/// Display information of a fungible token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl TokenInfo {
+    /// Placeholder used for faucets whose metadata cannot be read (e.g. private faucets).
+    pub fn unknown() -> Self {
+        Self {
+            symbol: "???".to_string(),
+            decimals: 0,
+        }
+    }
+}
+
+/// Renders a base-unit `amount` with the given number of `decimals`, trimming trailing zeros.
+///
+/// `format_amount(1_250_000, 6)` returns `"1.25"`.
+pub fn format_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+
+    let scale = 10u64.pow(decimals as u32);
+    let whole = amount / scale;
+    let fraction = amount % scale;
+    if fraction == 0 {
+        return whole.to_string();
+    }
+
+    let fraction = format!("{:0width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+/// Parses an amount written with at most `decimals` decimals into base units, the inverse of
+/// [`format_amount`].
+///
+/// `parse_amount("1.25", 6)` returns `Ok(1_250_000)`.
+pub fn parse_amount(input: &str, decimals: u8) -> Result<u64, Box<dyn Error>> {
+    let input = input.trim();
+    let (whole, fraction) = input.split_once('.').unwrap_or((input, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err("the amount is empty".into());
+    }
+    if fraction.len() > decimals as usize {
+        return Err(format!("the token has only {decimals} decimals").into());
+    }
+
+    let digits = format!("{whole}{fraction:0<width$}", width = decimals as usize);
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("`{input}` is not an amount").into());
+    }
+    Ok(digits.parse::<u64>()?)
+}
+
+/// Formats assets using cached token metadata.
+#[derive(Debug)]
+pub struct AssetFormatter {
+    /// Network of the faucet addresses shown for unknown tokens.
+    network: NetworkId,
+    tokens: BTreeMap<AccountId, TokenInfo>,
+}
+
+impl AssetFormatter {
+    pub fn new(network: NetworkId) -> Self {
+        Self {
+            network,
+            tokens: BTreeMap::new(),
+        }
+    }
+
+    /// Registers the token information of a faucet without querying the node.
+    pub fn with_token(mut self, faucet_id: AccountId, token: TokenInfo) -> Self {
+        self.tokens.insert(faucet_id, token);
+        self
+    }
+
+    /// Registers the token information of every token of `registry`, read from the node in a
+    /// single request instead of one per faucet.
+    pub fn with_registry(mut self, registry: &TokenRegistry) -> Self {
+        for (faucet_id, metadata) in &registry.tokens {
+            self.tokens.insert(*faucet_id, metadata.info());
+        }
+        self
+    }
+
+    /// Returns the token information of `faucet_id`, fetching the faucet from the node the
+    /// first time it is requested.
+    pub async fn load_token(
+        &mut self,
+        rpc: &dyn NodeRpcClient,
+        faucet_id: AccountId,
+    ) -> Result<&TokenInfo, RpcError> {
+        if !self.tokens.contains_key(&faucet_id) {
+            let token = match rpc.get_account_details(faucet_id).await? {
+                FetchedAccount::Public(account, _) => {
+                    match BasicFungibleFaucet::try_from(account.as_ref().clone()) {
+                        Ok(faucet) => TokenInfo {
+                            symbol: faucet.symbol().to_string().unwrap_or_default(),
+                            decimals: faucet.decimals(),
+                        },
+                        Err(_) => TokenInfo::unknown(),
+                    }
+                }
+                FetchedAccount::Private(..) => TokenInfo::unknown(),
+            };
+            self.tokens.insert(faucet_id, token);
+        }
+
+        Ok(&self.tokens[&faucet_id])
+    }
+
+    /// Returns the cached token information of `faucet_id`, if any.
+    pub fn token(&self, faucet_id: AccountId) -> Option<&TokenInfo> {
+        self.tokens.get(&faucet_id)
+    }
+
+    /// Formats a base-unit amount of the token issued by `faucet_id`.
+    pub fn format_amount(&self, faucet_id: AccountId, amount: u64) -> String {
+        match self.tokens.get(&faucet_id) {
+            Some(token) => format!("{} {}", format_amount(amount, token.decimals), token.symbol),
+            None => format!(
+                "{} (faucet {})",
+                amount,
+                faucet_id.to_bech32(self.network.clone())
+            ),
+        }
+    }
+
+    /// Formats a single asset.
+    pub fn format(&self, asset: &Asset) -> String {
+        match asset {
+            Asset::Fungible(fungible) => {
+                self.format_amount(fungible.faucet_id(), fungible.amount())
+            }
+            Asset::NonFungible(non_fungible) => {
+                format!("NFT (collection {})", non_fungible.faucet_id_prefix())
+            }
+        }
+    }
+}
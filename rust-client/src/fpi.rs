@@ -0,0 +1,41 @@
+//! Helpers for transactions using foreign procedure invocation (FPI).
+//!
+//! Every foreign account adds its code, storage header and requested storage map entries to
+//! the transaction inputs, so execution and proving time grow with the foreign-account set.
+//! The `fpi_scaling` benchmark measures this growth.
+
+use miden_client::transaction::ForeignAccount;
+
+/// Maximum number of foreign accounts a transaction can load. The transaction kernel reserves
+/// memory for 64 accounts, one of which is the native account.
+pub const MAX_FOREIGN_ACCOUNTS: usize = 63;
+
+/// Number of foreign accounts above which proving time becomes impractical for interactive
+/// use on a typical machine.
+pub const PRACTICAL_FOREIGN_ACCOUNTS: usize = 16;
+
+/// Returns a warning if a transaction reading `num_foreign_accounts` foreign accounts is likely
+/// to exceed practical limits, or `None` if the set is reasonably small.
+pub fn foreign_accounts_warning(num_foreign_accounts: usize) -> Option<String> {
+    if num_foreign_accounts > MAX_FOREIGN_ACCOUNTS {
+        Some(format!(
+            "{num_foreign_accounts} foreign accounts exceed the kernel limit of \
+             {MAX_FOREIGN_ACCOUNTS}, the transaction will fail"
+        ))
+    } else if num_foreign_accounts > PRACTICAL_FOREIGN_ACCOUNTS {
+        Some(format!(
+            "{num_foreign_accounts} foreign accounts is above the practical limit of \
+             {PRACTICAL_FOREIGN_ACCOUNTS}, expect slow proving; consider aggregating the data \
+             into fewer accounts"
+        ))
+    } else {
+        None
+    }
+}
+
+/// Prints a warning to stderr if `foreign_accounts` is likely to exceed practical limits.
+pub fn warn_on_foreign_accounts(foreign_accounts: &[ForeignAccount]) {
+    if let Some(warning) = foreign_accounts_warning(foreign_accounts.len()) {
+        eprintln!("warning: {warning}");
+    }
+}
@@ -0,0 +1,287 @@
+//! Pre-flight funding checks for testnet runs.
+//!
+//! Most tutorial failures happen halfway through a run, when an account turns out not to hold
+//! the tokens a transaction spends. [`FundingGuard`] checks the balances an account needs
+//! before anything is submitted and tops the account up from a faucet whose key is in the
+//! keystore (typically the one deployed by `devnet_setup`). Top-ups are rate limited per
+//! account, and the time of the last one is kept on disk so the limit holds across runs.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use miden_client::{
+    account::AccountId,
+    asset::{Asset, FungibleAsset},
+    keystore::FilesystemKeyStore,
+    note::NoteType,
+    store::TransactionFilter,
+    transaction::{TransactionRequestBuilder, TransactionStatus},
+    Client, ClientError,
+};
+use miden_objects::AssetError;
+use rand::rngs::StdRng;
+use tokio::time::sleep;
+
+use crate::cli::SubmitWithProver;
+
+/// Default location of the top-up log, relative to the `rust-client` directory.
+pub const DEFAULT_FUNDING_LOG_PATH: &str = "./funding_log.json";
+
+/// Why an account could not be funded.
+#[derive(Debug)]
+pub enum FundingError {
+    /// The account misses an asset the guard's faucet does not issue.
+    Insufficient {
+        faucet_id: AccountId,
+        balance: u64,
+        required: u64,
+    },
+    /// The shortfall is larger than a single top-up may mint.
+    AboveLimit {
+        missing: u64,
+        max_per_top_up: u64,
+    },
+    /// The account was topped up too recently.
+    RateLimited {
+        retry_after: Duration,
+    },
+    /// The account is not tracked by the client.
+    UnknownAccount(AccountId),
+    Asset(AssetError),
+    Client(ClientError),
+    Io(io::Error),
+}
+
+impl fmt::Display for FundingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Insufficient {
+                faucet_id,
+                balance,
+                required,
+            } => write!(
+                f,
+                "account holds {balance} of faucet {}, {required} required",
+                faucet_id.to_hex()
+            ),
+            Self::AboveLimit {
+                missing,
+                max_per_top_up,
+            } => write!(
+                f,
+                "account misses {missing} tokens, more than the {max_per_top_up} a top-up mints"
+            ),
+            Self::RateLimited { retry_after } => write!(
+                f,
+                "account was topped up recently, retry in {}s",
+                retry_after.as_secs()
+            ),
+            Self::UnknownAccount(account_id) => {
+                write!(
+                    f,
+                    "account {} is not tracked by the client",
+                    account_id.to_hex()
+                )
+            }
+            Self::Asset(err) => write!(f, "invalid top-up: {err}"),
+            Self::Client(err) => write!(f, "client error: {err}"),
+            Self::Io(err) => write!(f, "cannot access the funding log: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for FundingError {}
+
+impl From<ClientError> for FundingError {
+    fn from(err: ClientError) -> Self {
+        Self::Client(err)
+    }
+}
+
+impl From<io::Error> for FundingError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// What [`FundingGuard::ensure_funded`] had to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FundingOutcome {
+    /// The account already held everything it needs.
+    AlreadyFunded,
+    /// The account was topped up with `minted` tokens of the guard's faucet.
+    ToppedUp { minted: u64 },
+}
+
+/// Checks that accounts hold what they need, topping them up from a faucet if they don't.
+#[derive(Debug, Clone)]
+pub struct FundingGuard {
+    faucet_id: AccountId,
+    max_per_top_up: u64,
+    min_interval: Duration,
+    log_path: PathBuf,
+}
+
+impl FundingGuard {
+    /// Creates a guard minting from `faucet_id`, at most 10 000 base units per top-up and one
+    /// top-up per account every 10 minutes.
+    pub fn new(faucet_id: AccountId) -> Self {
+        Self {
+            faucet_id,
+            max_per_top_up: 10_000,
+            min_interval: Duration::from_secs(600),
+            log_path: PathBuf::from(DEFAULT_FUNDING_LOG_PATH),
+        }
+    }
+
+    /// Sets the largest amount a single top-up may mint.
+    pub fn with_max_per_top_up(mut self, max_per_top_up: u64) -> Self {
+        self.max_per_top_up = max_per_top_up;
+        self
+    }
+
+    /// Sets the minimum time between two top-ups of the same account.
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// Sets where the time of the last top-up of every account is recorded.
+    pub fn with_log_path(mut self, log_path: impl Into<PathBuf>) -> Self {
+        self.log_path = log_path.into();
+        self
+    }
+
+    /// Makes sure `account_id` holds every asset in `required`, which lists each faucet once.
+    ///
+    /// Missing tokens of the guard's faucet are minted to the account and consumed by it
+    /// before returning, so the caller can go on spending them right away. Missing tokens of
+    /// any other faucet (e.g. the fee token) cannot be minted and fail the check.
+    pub async fn ensure_funded(
+        &self,
+        client: &mut Client<FilesystemKeyStore<StdRng>>,
+        account_id: AccountId,
+        required: &[FungibleAsset],
+    ) -> Result<FundingOutcome, FundingError> {
+        client.sync_state().await?;
+        let record = client
+            .get_account(account_id)
+            .await?
+            .ok_or(FundingError::UnknownAccount(account_id))?;
+        let vault = record.account().vault();
+
+        let mut missing = 0;
+        for asset in required {
+            let balance = vault.get_balance(asset.faucet_id()).unwrap_or(0);
+            if balance >= asset.amount() {
+                continue;
+            }
+            if asset.faucet_id() != self.faucet_id {
+                return Err(FundingError::Insufficient {
+                    faucet_id: asset.faucet_id(),
+                    balance,
+                    required: asset.amount(),
+                });
+            }
+            missing = asset.amount() - balance;
+        }
+        if missing == 0 {
+            return Ok(FundingOutcome::AlreadyFunded);
+        }
+        if missing > self.max_per_top_up {
+            return Err(FundingError::AboveLimit {
+                missing,
+                max_per_top_up: self.max_per_top_up,
+            });
+        }
+
+        // Check the rate limit before touching the chain
+        let mut log = load_log(&self.log_path)?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        if let Some(last) = log.get(&account_id.to_hex()) {
+            let elapsed = Duration::from_secs(now.saturating_sub(*last));
+            if elapsed < self.min_interval {
+                return Err(FundingError::RateLimited {
+                    retry_after: self.min_interval - elapsed,
+                });
+            }
+        }
+
+        // Mint exactly the shortfall and wait until the account can consume it
+        let asset = FungibleAsset::new(self.faucet_id, missing).map_err(FundingError::Asset)?;
+        let mint_request = TransactionRequestBuilder::new().build_mint_fungible_asset(
+            asset,
+            account_id,
+            NoteType::Public,
+            client.rng(),
+        )?;
+        let mint_tx = client
+            .submit_with_prover(self.faucet_id, mint_request)
+            .await?;
+        log.insert(account_id.to_hex(), now);
+        save_log(&self.log_path, &log)?;
+
+        let note_id = loop {
+            client.sync_state().await?;
+            let consumable = client.get_consumable_notes(Some(account_id)).await?;
+            let minted = consumable.iter().find(|(note, _)| {
+                note.metadata()
+                    .is_some_and(|metadata| metadata.sender() == self.faucet_id)
+                    && note
+                        .assets()
+                        .iter()
+                        .any(|minted| *minted == Asset::from(asset))
+            });
+            if let Some((note, _)) = minted {
+                break note.id();
+            }
+            sleep(Duration::from_secs(3)).await;
+        };
+        let consume_request =
+            TransactionRequestBuilder::new().build_consume_notes(vec![note_id])?;
+        let consume_tx = client
+            .submit_with_prover(account_id, consume_request)
+            .await?;
+
+        // Wait for the consumption so that the tokens are spendable by the next transaction
+        loop {
+            client.sync_state().await?;
+            let txs = client
+                .get_transactions(TransactionFilter::Ids(vec![mint_tx, consume_tx]))
+                .await?;
+            let committed = txs
+                .iter()
+                .filter(|tx| matches!(tx.status, TransactionStatus::Committed { .. }))
+                .count();
+            if committed == 2 {
+                break;
+            }
+            sleep(Duration::from_secs(3)).await;
+        }
+
+        Ok(FundingOutcome::ToppedUp { minted: missing })
+    }
+}
+
+/// Reads the time of the last top-up of every account, keyed by account id.
+fn load_log(path: &Path) -> io::Result<BTreeMap<String, u64>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(err) => Err(err),
+    }
+}
+
+fn save_log(path: &Path, log: &BTreeMap<String, u64>) -> io::Result<()> {
+    let contents = serde_json::to_string_pretty(log)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    fs::write(path, contents)
+}
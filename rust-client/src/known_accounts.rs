@@ -0,0 +1,102 @@
+//! A small on-disk registry of labeled account ids.
+//!
+//! Tutorials print raw bech32 ids everywhere. The registry lets binaries store the accounts
+//! they deploy under a human readable label (`"counter"`, `"faucet"`, ...) and lets read-only
+//! tools display those labels instead of ids.
+
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use miden_client::{account::AccountId, address::NetworkId};
+use serde::{Deserialize, Serialize};
+
+use crate::address::{parse_account_address, AddressError};
+
+/// Default location of the registry, relative to the `rust-client` directory.
+pub const DEFAULT_REGISTRY_PATH: &str = "./known_accounts.json";
+
+/// Labeled account ids, stored as bech32 strings.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct KnownAccounts {
+    accounts: BTreeMap<String, String>,
+}
+
+impl KnownAccounts {
+    /// Loads the registry from `path`, returning an empty registry if the file does not exist.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes the registry to `path` as pretty printed JSON.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(path, contents)
+    }
+
+    /// Stores `account_id` under `label` as an address of `network`, replacing any previous
+    /// entry with the same label.
+    pub fn insert(&mut self, label: impl Into<String>, account_id: AccountId, network: NetworkId) {
+        self.accounts
+            .insert(label.into(), account_id.to_bech32(network));
+    }
+
+    /// Removes the entry stored under `label`.
+    pub fn remove(&mut self, label: &str) -> Option<AccountId> {
+        self.accounts
+            .remove(label)
+            .and_then(|address| AccountId::from_bech32(&address).ok())
+            .map(|(_, account_id)| account_id)
+    }
+
+    /// Returns the account id stored under `label`.
+    pub fn get(&self, label: &str) -> Option<AccountId> {
+        self.accounts
+            .get(label)
+            .and_then(|address| AccountId::from_bech32(address).ok())
+            .map(|(_, account_id)| account_id)
+    }
+
+    /// Resolves `input` as a bech32 address of `network`, or else as a registered label.
+    ///
+    /// Only strings that are not addresses at all are looked up as labels, so a valid address
+    /// of another network is reported as such rather than as an unknown label.
+    pub fn resolve(&self, input: &str, network: NetworkId) -> Result<AccountId, AddressError> {
+        match parse_account_address(input, network) {
+            Err(AddressError::Malformed { input, reason }) => {
+                self.get(input.trim()).ok_or(AddressError::Malformed {
+                    reason: format!("{reason}, and no account is registered under this label"),
+                    input,
+                })
+            }
+            result => result,
+        }
+    }
+
+    /// Returns the label of `account_id`, if it is registered.
+    pub fn label_of(&self, account_id: AccountId) -> Option<&str> {
+        self.iter()
+            .find(|(_, stored)| *stored == account_id)
+            .map(|(label, _)| label)
+    }
+
+    /// Returns the label of `account_id`, falling back to its address on `network`.
+    pub fn display(&self, account_id: AccountId, network: NetworkId) -> String {
+        self.label_of(account_id)
+            .map(str::to_string)
+            .unwrap_or_else(|| account_id.to_bech32(network))
+    }
+
+    /// Iterates over all `(label, account_id)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, AccountId)> + '_ {
+        self.accounts.iter().filter_map(|(label, address)| {
+            AccountId::from_bech32(address)
+                .ok()
+                .map(|(_, account_id)| (label.as_str(), account_id))
+        })
+    }
+}
@@ -0,0 +1,601 @@
+//! Off-chain side of the lending pool (`masm/accounts/lending_pool.masm`).
+//!
+//! Borrowers deposit a collateral asset into the pool and borrow another asset against it, up
+//! to a maximum loan-to-value ratio. The pool values the collateral at a price read through FPI,
+//! either from a price feed (`masm/accounts/price_publisher.masm`) or from a pair of the Pragma
+//! oracle, so every transaction checking a ratio must load the [`PriceSource`] as foreign
+//! accounts. Once the price falls far enough for a debt to pass the liquidation threshold,
+//! anyone can repay the whole debt with a `pool_liquidate_note` and receive the whole collateral
+//! of the position.
+//!
+//! [`Pool`] decodes the configuration and positions of a pool account, and [`Position`]
+//! mirrors the health check of the contract so that a bot can find liquidatable positions
+//! without executing a transaction.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    auth::AuthSecretKey,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    rpc::domain::account::AccountStorageRequirements,
+    transaction::{ForeignAccount, OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{
+    account::auth::{AuthRpoFalcon512, NoAuth},
+    note::WellKnownNote,
+    transaction::TransactionKernel,
+};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    asset::{Asset, FungibleAsset},
+    NoteError, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::{create_library, reply_note},
+    pragma,
+};
+
+/// Path under which the pool contract is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::lending_pool";
+/// Location of the pool contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/lending_pool.masm";
+/// Location of the note depositing collateral or liquidity, relative to `rust-client`.
+pub const DEPOSIT_NOTE_PATH: &str = "../masm/notes/pool_deposit_note.masm";
+/// Location of the note borrowing from the pool, relative to `rust-client`.
+pub const BORROW_NOTE_PATH: &str = "../masm/notes/pool_borrow_note.masm";
+/// Location of the note liquidating a position, relative to `rust-client`.
+pub const LIQUIDATE_NOTE_PATH: &str = "../masm/notes/pool_liquidate_note.masm";
+
+/// Path under which the price feed is linked into the script setting its price.
+pub const PRICE_FEED_LIBRARY_PATH: &str = "external_contract::price_publisher";
+/// Location of the price feed contract, relative to `rust-client`.
+pub const PRICE_FEED_PATH: &str = "../masm/accounts/price_publisher.masm";
+/// Location of the script publishing a new price, relative to `rust-client`.
+pub const SET_PRICE_SCRIPT_PATH: &str = "../masm/scripts/set_price_script.masm";
+
+const COLLATERAL_FAUCET_SLOT: u8 = 0;
+const DEBT_FAUCET_SLOT: u8 = 1;
+const ORACLE_SLOT: u8 = 2;
+const RISK_SLOT: u8 = 4;
+const POSITIONS_SLOT: usize = 5;
+const PRICE_PAIR_SLOT: u8 = 7;
+/// Slot of the price in the price feed.
+const PRICE_SLOT: u8 = 0;
+
+/// Risk parameters of a pool, in percent of the collateral value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RiskParams {
+    /// Largest debt a borrow may leave the position with.
+    pub max_ltv: u64,
+    /// Debt above which the position can be liquidated.
+    pub liquidation_threshold: u64,
+}
+
+/// Where a pool reads the price of the collateral.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceSource {
+    /// A price feed created with [`create_price_feed`], quoting the collateral in borrowed tokens.
+    Feed(AccountId),
+    /// A pair of the Pragma oracle, quoted with [`pragma::PRICE_DECIMALS`] decimals. The price
+    /// of one collateral token is the quote in whole borrowed tokens.
+    Pragma { oracle: AccountId, pair: u64 },
+}
+
+impl PriceSource {
+    /// Returns the account the pool calls for the price.
+    pub fn oracle(&self) -> AccountId {
+        match self {
+            Self::Feed(price_feed) => *price_feed,
+            Self::Pragma { oracle, .. } => *oracle,
+        }
+    }
+
+    /// Returns the number the pool divides the quote of the oracle by to get the price.
+    pub fn scale(&self) -> u64 {
+        match self {
+            Self::Feed(_) => 1,
+            Self::Pragma { .. } => 10_u64.pow(pragma::PRICE_DECIMALS),
+        }
+    }
+
+    /// Returns the price the pool uses for `quote`, as returned by the oracle.
+    pub fn price(&self, quote: u64) -> u64 {
+        quote / self.scale()
+    }
+
+    /// Returns the accounts a transaction reading the price must load. For Pragma, this imports
+    /// the oracle and its publishers into the client.
+    pub async fn foreign_accounts(
+        &self,
+        client: &mut Client<FilesystemKeyStore<StdRng>>,
+    ) -> Result<Vec<ForeignAccount>, Box<dyn Error>> {
+        match *self {
+            Self::Feed(price_feed) => Ok(vec![ForeignAccount::public(
+                price_feed,
+                AccountStorageRequirements::default(),
+            )?]),
+            Self::Pragma { oracle, pair } => {
+                Ok(pragma::get_oracle_foreign_accounts(client, oracle, pair).await?)
+            }
+        }
+    }
+
+    /// Returns the storage of the pool describing the source: the root of the price procedure,
+    /// the PAIR word passed to it and the scale of its quotes.
+    fn storage(&self) -> Result<[Word; 3], Box<dyn Error>> {
+        let (root, pair) = match self {
+            Self::Feed(_) => (get_price_root()?, 0),
+            Self::Pragma { pair, .. } => (pragma::get_median_root(), *pair),
+        };
+        Ok([
+            root,
+            [ZERO, ZERO, ZERO, Felt::new(pair)].into(),
+            [ZERO, ZERO, ZERO, Felt::new(self.scale())].into(),
+        ])
+    }
+}
+
+/// Collateral and debt of a borrower.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub owner: AccountId,
+    /// Collateral tokens deposited.
+    pub collateral: u64,
+    /// Borrowed tokens owed to the pool.
+    pub debt: u64,
+}
+
+impl Position {
+    /// Returns whether the pool accepts a liquidation of the position at `price`, the same way
+    /// `lending_pool::liquidate` decides it.
+    pub fn is_liquidatable(&self, price: u64, risk: RiskParams) -> bool {
+        let debt = u128::from(self.debt) * 100;
+        let limit = u128::from(self.collateral)
+            * u128::from(price)
+            * u128::from(risk.liquidation_threshold);
+        self.debt > 0 && debt > limit
+    }
+
+    /// Returns the price below which the position can be liquidated, or `None` if it holds
+    /// no debt.
+    pub fn liquidation_price(&self, risk: RiskParams) -> Option<u64> {
+        if self.debt == 0 {
+            return None;
+        }
+        let per_price = u128::from(self.collateral) * u128::from(risk.liquidation_threshold);
+        if per_price == 0 {
+            return Some(u64::MAX);
+        }
+        let price = (u128::from(self.debt) * 100).div_ceil(per_price);
+        Some(u64::try_from(price).unwrap_or(u64::MAX))
+    }
+}
+
+/// Configuration and positions of a deployed pool.
+#[derive(Debug, Clone)]
+pub struct Pool {
+    pub id: AccountId,
+    pub collateral_faucet: AccountId,
+    pub debt_faucet: AccountId,
+    pub price_source: PriceSource,
+    pub risk: RiskParams,
+    /// Positions holding collateral or debt.
+    pub positions: Vec<Position>,
+}
+
+impl Pool {
+    /// Decodes the storage of a deployed pool account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let account_id = |slot| -> Result<AccountId, Box<dyn Error>> {
+            let value = storage.get_item(slot)?;
+            Ok(AccountId::try_from([value[3], value[2]])?)
+        };
+
+        let risk = storage.get_item(RISK_SLOT)?;
+        let StorageSlot::Map(positions_map) = &storage.slots()[POSITIONS_SLOT] else {
+            return Err("the positions slot of the pool is not a map".into());
+        };
+        let mut positions = Vec::new();
+        for (key, value) in positions_map.entries() {
+            let position = Position {
+                owner: AccountId::try_from([key[3], key[2]])?,
+                collateral: value[3].as_int(),
+                debt: value[2].as_int(),
+            };
+            if position.collateral > 0 || position.debt > 0 {
+                positions.push(position);
+            }
+        }
+
+        // Only Pragma pools pass a pair to their oracle
+        let oracle = account_id(ORACLE_SLOT)?;
+        let pair = storage.get_item(PRICE_PAIR_SLOT)?[3].as_int();
+        let price_source = if pair == 0 {
+            PriceSource::Feed(oracle)
+        } else {
+            PriceSource::Pragma { oracle, pair }
+        };
+
+        Ok(Self {
+            id: account.id(),
+            collateral_faucet: account_id(COLLATERAL_FAUCET_SLOT)?,
+            debt_faucet: account_id(DEBT_FAUCET_SLOT)?,
+            price_source,
+            risk: RiskParams {
+                max_ltv: risk[3].as_int(),
+                liquidation_threshold: risk[2].as_int(),
+            },
+            positions,
+        })
+    }
+
+    /// Returns the position of `owner`, empty if it never deposited.
+    pub fn position(&self, owner: AccountId) -> Position {
+        self.positions
+            .iter()
+            .find(|position| position.owner == owner)
+            .copied()
+            .unwrap_or(Position {
+                owner,
+                collateral: 0,
+                debt: 0,
+            })
+    }
+}
+
+/// Returns the latest price published by a price feed account.
+pub fn price(price_feed: &Account) -> Result<u64, Box<dyn Error>> {
+    Ok(price_feed.storage().get_item(PRICE_SLOT)?[3].as_int())
+}
+
+/// Returns the price feed component publishing `price`.
+fn price_feed_component(price: u64) -> Result<AccountComponent, Box<dyn Error>> {
+    let code = fs::read_to_string(PRICE_FEED_PATH)?;
+    let price: Word = [ZERO, ZERO, ZERO, Felt::new(price)].into();
+    Ok(AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Value(price)],
+    )?
+    .with_supports_all_types())
+}
+
+/// Returns the root of the `get_price` procedure the pool calls on the price feed.
+pub fn get_price_root() -> Result<Word, Box<dyn Error>> {
+    let component = price_feed_component(0)?;
+    let export = component
+        .library()
+        .exports()
+        .find(|export| export.name.name.as_str() == "get_price")
+        .ok_or("the price feed does not export get_price")?;
+    let node_id = component.library().get_export_node_id(&export.name);
+    Ok(component
+        .library()
+        .mast_forest()
+        .get_node_by_id(node_id)
+        .ok_or("get_price is not in the price feed library")?
+        .digest())
+}
+
+/// Creates a public price feed publishing `price`, and adds its key to `keystore`. Only the
+/// holder of the key can publish a new price with [`set_price`].
+pub async fn create_price_feed(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    keystore: &FilesystemKeyStore<StdRng>,
+    price: u64,
+) -> Result<Account, Box<dyn Error>> {
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+        .with_component(price_feed_component(price)?)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    keystore.add_key(&key_pair)?;
+    Ok(account)
+}
+
+/// Publishes `price` on the price feed `price_feed_id`.
+pub async fn set_price(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    price_feed_id: AccountId,
+    price: u64,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let library = create_library(
+        &fs::read_to_string(PRICE_FEED_PATH)?,
+        PRICE_FEED_LIBRARY_PATH,
+    )?;
+    let script_code =
+        fs::read_to_string(SET_PRICE_SCRIPT_PATH)?.replace("{price}", &price.to_string());
+    let script = client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_tx_script(&script_code)?;
+
+    let request = TransactionRequestBuilder::new()
+        .custom_script(script)
+        .build()?;
+    Ok(client.submit_with_prover(price_feed_id, request).await?)
+}
+
+/// Deploys a public pool lending `debt_faucet` tokens against `collateral_faucet` tokens,
+/// valued at the price of `price_source`.
+pub async fn create_pool(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    collateral_faucet: AccountId,
+    debt_faucet: AccountId,
+    price_source: PriceSource,
+    risk: RiskParams,
+) -> Result<Account, Box<dyn Error>> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let account_word = |account_id: AccountId| -> Word {
+        [
+            ZERO,
+            ZERO,
+            account_id.suffix(),
+            account_id.prefix().as_felt(),
+        ]
+        .into()
+    };
+    let risk: Word = [
+        ZERO,
+        ZERO,
+        Felt::new(risk.liquidation_threshold),
+        Felt::new(risk.max_ltv),
+    ]
+    .into();
+    let [price_root, price_pair, price_scale] = price_source.storage()?;
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(account_word(collateral_faucet)),
+            StorageSlot::Value(account_word(debt_faucet)),
+            StorageSlot::Value(account_word(price_source.oracle())),
+            StorageSlot::Value(price_root),
+            StorageSlot::Value(risk),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+            StorageSlot::Value(price_pair),
+            StorageSlot::Value(price_scale),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the pool contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `sender` to the pool `pool_id`.
+fn pool_note(
+    sender: AccountId,
+    pool_id: AccountId,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(pool_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+/// Has `sender` create `note`, then has the pool consume it right away, creating `replies`.
+///
+/// The pool is a `NoAuth` account, so the client of the sender can execute its transaction as
+/// long as it tracks the pool. Returns the id of the pool transaction.
+async fn submit_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    pool: &Pool,
+    note: Note,
+    replies: Vec<Note>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    client.submit_with_prover(sender, request).await?;
+
+    let foreign_accounts = pool.price_source.foreign_accounts(client).await?;
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .foreign_accounts(foreign_accounts)
+        .expected_output_recipients(
+            replies
+                .iter()
+                .map(|reply| reply.recipient().clone())
+                .collect(),
+        )
+        .build()?;
+    Ok(client.submit_with_prover(pool.id, request).await?)
+}
+
+/// Deposits `asset` into `pool` on behalf of `owner`. `script` is the compiled
+/// [`DEPOSIT_NOTE_PATH`].
+pub async fn deposit(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    owner: AccountId,
+    pool: &Pool,
+    asset: FungibleAsset,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = pool_note(
+        owner,
+        pool.id,
+        vec![],
+        vec![asset.into()],
+        script,
+        serial_num,
+    )?;
+    submit_note(client, owner, pool, note, vec![]).await
+}
+
+/// Borrows `amount` from `pool` on behalf of `owner`. `script` is the compiled
+/// [`BORROW_NOTE_PATH`].
+pub async fn borrow(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    owner: AccountId,
+    pool: &Pool,
+    amount: u64,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let inputs = vec![Felt::new(amount), NoteTag::from_account_id(owner).into()];
+    let note = pool_note(owner, pool.id, inputs, vec![], script, serial_num)?;
+    let loan = reply_note(
+        pool.id,
+        owner,
+        FungibleAsset::new(pool.debt_faucet, amount)?,
+        serial_num,
+    )?;
+    submit_note(client, owner, pool, note, vec![loan]).await
+}
+
+/// Repays the whole debt of `position` on behalf of `liquidator`, who receives its collateral.
+/// `script` is the compiled [`LIQUIDATE_NOTE_PATH`].
+pub async fn liquidate(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    liquidator: AccountId,
+    pool: &Pool,
+    position: &Position,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let inputs = vec![
+        position.owner.prefix().as_felt(),
+        position.owner.suffix(),
+        NoteTag::from_account_id(liquidator).into(),
+    ];
+    let repayment = FungibleAsset::new(pool.debt_faucet, position.debt)?;
+    let note = pool_note(
+        liquidator,
+        pool.id,
+        inputs,
+        vec![repayment.into()],
+        script,
+        serial_num,
+    )?;
+    let seized = reply_note(
+        pool.id,
+        liquidator,
+        FungibleAsset::new(pool.collateral_faucet, position.collateral)?,
+        serial_num,
+    )?;
+    submit_note(client, liquidator, pool, note, vec![seized]).await
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    const RISK: RiskParams = RiskParams {
+        max_ltv: 50,
+        liquidation_threshold: 80,
+    };
+
+    fn position(collateral: u64, debt: u64) -> Position {
+        Position {
+            owner: AccountId::dummy(
+                [7; 15],
+                AccountIdVersion::Version0,
+                AccountType::RegularAccountUpdatableCode,
+                AccountStorageMode::Public,
+            ),
+            collateral,
+            debt,
+        }
+    }
+
+    #[test]
+    fn position_is_liquidatable_below_its_liquidation_price() {
+        let position = position(10, 400);
+        let liquidation_price = position.liquidation_price(RISK).unwrap();
+
+        assert_eq!(liquidation_price, 50);
+        assert!(!position.is_liquidatable(liquidation_price, RISK));
+        assert!(position.is_liquidatable(liquidation_price - 1, RISK));
+    }
+
+    #[test]
+    fn liquidation_price_rounds_up() {
+        // 100 * 100 / (3 * 80) = 41.67: at 41 the debt is above the threshold, at 42 it is not
+        let position = position(3, 100);
+
+        assert_eq!(position.liquidation_price(RISK), Some(42));
+        assert!(position.is_liquidatable(41, RISK));
+        assert!(!position.is_liquidatable(42, RISK));
+    }
+
+    #[test]
+    fn pragma_quotes_are_priced_in_whole_borrowed_tokens() {
+        let oracle = position(0, 0).owner;
+        let feed = PriceSource::Feed(oracle);
+        let pragma = PriceSource::Pragma {
+            oracle,
+            pair: pragma::BTC_USD,
+        };
+
+        // A feed quotes the price itself, Pragma quotes it with 6 decimals, rounded down
+        assert_eq!(feed.price(95_000), 95_000);
+        assert_eq!(pragma.price(95_000_999_999), 95_000);
+        assert_eq!(pragma.price(999_999), 0);
+    }
+
+    #[test]
+    fn position_without_debt_is_never_liquidatable() {
+        let position = position(10, 0);
+
+        assert_eq!(position.liquidation_price(RISK), None);
+        assert!(!position.is_liquidatable(0, RISK));
+    }
+}
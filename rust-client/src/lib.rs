@@ -0,0 +1,144 @@
+//! Helpers shared by the Miden tutorial binaries.
+//!
+//! The helpers the tutorial chapters use live in the `tutorial-utils` crate and are re-exported
+//! here, so that the other examples keep reaching them under `crate::` and `rust_client::`.
+//!
+//! Every example sits behind a cargo feature, and a module is only compiled with the features of
+//! the examples using it. `fpi` and `proving` also back the benchmarks, so they always build.
+
+pub use tutorial_utils::{cli, context, ephemeral, helpers, local_node, storage};
+
+#[cfg(any(
+    feature = "games",
+    feature = "defi",
+    feature = "bridge",
+    feature = "oracle",
+    feature = "guides",
+    feature = "devnet",
+    feature = "tui"
+))]
+pub mod address;
+#[cfg(feature = "devnet")]
+pub mod airdrop;
+#[cfg(feature = "defi")]
+pub mod amm;
+#[cfg(feature = "defi")]
+pub mod auction;
+#[cfg(feature = "games")]
+pub mod battleship;
+#[cfg(feature = "bridge")]
+pub mod bridge_kit;
+#[cfg(feature = "governance")]
+pub mod budget;
+#[cfg(feature = "defi")]
+pub mod checked_math;
+#[cfg(feature = "tools")]
+pub mod compat;
+#[cfg(feature = "games")]
+pub mod connect_four;
+#[cfg(feature = "defi")]
+pub mod crowdfund;
+#[cfg(any(feature = "defi", feature = "bridge"))]
+pub mod daemon;
+#[cfg(any(feature = "games", feature = "defi"))]
+pub mod deployer;
+#[cfg(any(
+    feature = "defi",
+    feature = "oracle",
+    feature = "devnet",
+    feature = "tui"
+))]
+pub mod devnet;
+#[cfg(any(feature = "governance", feature = "guides"))]
+pub mod encoding;
+#[cfg(feature = "defi")]
+pub mod factory;
+#[cfg(feature = "defi")]
+pub mod fixed_point;
+#[cfg(any(
+    feature = "defi",
+    feature = "guides",
+    feature = "devnet",
+    feature = "tui"
+))]
+pub mod format;
+pub mod fpi;
+#[cfg(any(feature = "defi", feature = "devnet"))]
+pub mod funding;
+#[cfg(any(
+    feature = "games",
+    feature = "defi",
+    feature = "bridge",
+    feature = "oracle",
+    feature = "devnet",
+    feature = "tui"
+))]
+pub mod known_accounts;
+#[cfg(any(feature = "defi", feature = "oracle"))]
+pub mod lending;
+#[cfg(feature = "games")]
+pub mod lottery;
+#[cfg(feature = "guides")]
+pub mod map_writer;
+#[cfg(feature = "guides")]
+pub mod migration;
+#[cfg(feature = "tools")]
+pub mod mirror;
+#[cfg(feature = "defi")]
+pub mod monitor;
+#[cfg(feature = "governance")]
+pub mod multisig;
+#[cfg(feature = "defi")]
+pub mod nft;
+#[cfg(feature = "defi")]
+pub mod nft_marketplace;
+#[cfg(feature = "defi")]
+pub mod order_book;
+#[cfg(feature = "defi")]
+pub mod partial_swap;
+#[cfg(feature = "defi")]
+pub mod payment_request;
+#[cfg(feature = "defi")]
+pub mod payment_stream;
+#[cfg(any(feature = "defi", feature = "oracle", feature = "devnet"))]
+pub mod pragma;
+#[cfg(feature = "guides")]
+pub mod privacy;
+pub mod proving;
+#[cfg(feature = "games")]
+pub mod ranking;
+#[cfg(feature = "defi")]
+pub mod rate_limit;
+#[cfg(feature = "bridge")]
+pub mod relay;
+#[cfg(feature = "defi")]
+pub mod replacement;
+#[cfg(any(feature = "games", feature = "guides"))]
+pub mod rock_paper_scissors;
+#[cfg(any(feature = "bridge", feature = "guides"))]
+pub mod serial;
+#[cfg(feature = "defi")]
+pub mod subscription;
+#[cfg(feature = "guides")]
+pub mod sync_hooks;
+#[cfg(feature = "games")]
+pub mod tic_tac_toe;
+#[cfg(any(
+    feature = "defi",
+    feature = "guides",
+    feature = "devnet",
+    feature = "tui"
+))]
+pub mod token_registry;
+#[cfg(feature = "defi")]
+pub mod trade_escrow;
+#[cfg(feature = "governance")]
+pub mod treasury;
+#[cfg(feature = "defi")]
+pub mod unclaimed;
+#[cfg(feature = "defi")]
+pub mod vesting;
+#[cfg(feature = "governance")]
+pub mod voting;
+#[cfg(feature = "games")]
+pub mod wager_escrow;
@@ -0,0 +1,441 @@
+//! Off-chain side of the lottery contract (`masm/accounts/lottery.masm`).
+//!
+//! A contract has no source of randomness: everything a transaction reads is known to whoever
+//! executes it, and a block hash can be influenced by whoever produces the block. The lottery
+//! therefore lets its players make the randomness. Until the sales end block, every player buys a
+//! ticket with a `lottery_ticket_note` carrying the ticket price and the hash of a [`Secret`].
+//! From the sales end block until the reveal end block, every player reveals its secret with a
+//! `lottery_reveal_note`; the lottery checks it against the commitment and hashes it into its
+//! seed. From the reveal end block on, anybody can send a `lottery_draw_note`: the lottery picks
+//! the winner among the players which revealed from the seed and pays it the price of every ticket
+//! sold with a P2ID note whose serial number is the serial number of the draw note plus one.
+//!
+//! No player can predict the seed while tickets are sold, since it depends on the secrets of all
+//! others. Once the others revealed, the last player to reveal does know it, but holding back its
+//! secret only takes it out of the draw. A player holding several tickets could choose which of
+//! them to reveal, though: lotteries with real stakes add a deposit forfeited by unrevealed
+//! tickets, or draw from a randomness beacon.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, note::WellKnownNote, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    asset::{Asset, FungibleAsset},
+    block::BlockNumber,
+    Hasher, NoteError, ONE, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::{create_library, reply_note},
+};
+
+/// Path under which the lottery contract is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::lottery";
+/// Location of the lottery contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/lottery.masm";
+/// Location of the note buying a ticket, relative to `rust-client`.
+pub const TICKET_NOTE_PATH: &str = "../masm/notes/lottery_ticket_note.masm";
+/// Location of the note revealing a secret, relative to `rust-client`.
+pub const REVEAL_NOTE_PATH: &str = "../masm/notes/lottery_reveal_note.masm";
+/// Location of the note drawing the winner, relative to `rust-client`.
+pub const DRAW_NOTE_PATH: &str = "../masm/notes/lottery_draw_note.masm";
+
+const CURRENCY_SLOT: u8 = 0;
+const TICKET_PRICE_SLOT: u8 = 1;
+const SALES_END_SLOT: u8 = 2;
+const REVEAL_END_SLOT: u8 = 3;
+const NUM_TICKETS_SLOT: u8 = 5;
+const SEED_SLOT: u8 = 6;
+const REVEALED_SLOT: u8 = 7;
+const NUM_REVEALS_SLOT: u8 = 8;
+const DRAWN_SLOT: u8 = 9;
+
+/// The contribution of a player to the randomness of the draw, hidden behind its commitment
+/// until revealed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Secret(pub Word);
+
+impl Secret {
+    /// Returns a fresh random secret.
+    pub fn new(rng: &mut impl FeltRng) -> Self {
+        Self(rng.draw_word())
+    }
+
+    /// Returns the commitment registered with the ticket.
+    pub fn commitment(&self) -> Word {
+        Hasher::hash_elements(self.0.as_elements())
+    }
+}
+
+/// Which notes a lottery accepts in a transaction executed against a given block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Tickets are sold.
+    Sales,
+    /// Secrets matching their commitment are mixed into the seed.
+    Reveal,
+    /// The winner can be drawn.
+    Draw,
+}
+
+/// Terms and state of a deployed lottery.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lottery {
+    pub id: AccountId,
+    /// Faucet of the tokens tickets are paid in.
+    pub currency: AccountId,
+    pub ticket_price: u64,
+    /// Block from which tickets are rejected and reveals accepted.
+    pub sales_end: BlockNumber,
+    /// Block from which reveals are rejected and the winner can be drawn.
+    pub reveal_end: BlockNumber,
+    pub num_tickets: u64,
+    /// Hash of every secret revealed so far.
+    pub seed: Word,
+    /// Players which revealed their secret, in the order they revealed.
+    pub revealed: Vec<AccountId>,
+    /// Whether the jackpot was paid out.
+    pub drawn: bool,
+}
+
+impl Lottery {
+    /// Decodes the terms and state of a deployed lottery account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let value =
+            |slot| -> Result<u64, Box<dyn Error>> { Ok(storage.get_item(slot)?[3].as_int()) };
+        let block = |slot| -> Result<BlockNumber, Box<dyn Error>> {
+            Ok(BlockNumber::from(u32::try_from(value(slot)?)?))
+        };
+        let currency = storage.get_item(CURRENCY_SLOT)?;
+
+        let mut revealed = Vec::new();
+        for index in 0..value(NUM_REVEALS_SLOT)? {
+            let key: Word = [ZERO, ZERO, ZERO, Felt::new(index)].into();
+            let player = storage.get_map_item(REVEALED_SLOT, key)?;
+            revealed.push(AccountId::try_from([player[3], player[2]])?);
+        }
+
+        Ok(Self {
+            id: account.id(),
+            currency: AccountId::try_from([currency[3], currency[2]])?,
+            ticket_price: value(TICKET_PRICE_SLOT)?,
+            sales_end: block(SALES_END_SLOT)?,
+            reveal_end: block(REVEAL_END_SLOT)?,
+            num_tickets: value(NUM_TICKETS_SLOT)?,
+            seed: storage.get_item(SEED_SLOT)?,
+            revealed,
+            drawn: storage.get_item(DRAWN_SLOT)?[3] == ONE,
+        })
+    }
+
+    /// Returns the phase of the lottery in a transaction executed against `block_num`.
+    pub fn phase_at(&self, block_num: BlockNumber) -> Phase {
+        if block_num < self.sales_end {
+            Phase::Sales
+        } else if block_num < self.reveal_end {
+            Phase::Reveal
+        } else {
+            Phase::Draw
+        }
+    }
+
+    /// Returns the price of every ticket sold, revealed or not.
+    pub fn jackpot(&self) -> u64 {
+        self.num_tickets * self.ticket_price
+    }
+
+    /// Returns the player the lottery draws with its current seed, computed like the contract:
+    /// the low 32 bits of the last element of the seed, modulo the number of reveals. `None`
+    /// until a player revealed.
+    pub fn winner(&self) -> Option<AccountId> {
+        let num_reveals = u32::try_from(self.revealed.len()).ok().filter(|n| *n > 0)?;
+        let seed_lo = self.seed[3].as_int() as u32;
+        self.revealed.get((seed_lo % num_reveals) as usize).copied()
+    }
+
+    /// Returns the P2ID note by which the lottery pays the jackpot to the winner in reply to the
+    /// draw note with serial number `serial_num`.
+    pub fn payout(&self, serial_num: Word) -> Result<Note, Box<dyn Error>> {
+        let winner = self.winner().ok_or("no player revealed its secret")?;
+        let jackpot = FungibleAsset::new(self.currency, self.jackpot())?;
+        Ok(reply_note(self.id, winner, jackpot, serial_num)?)
+    }
+}
+
+/// Deploys a public lottery selling tickets for `ticket_price` tokens of `currency` until block
+/// `sales_end`, and accepting reveals from then until block `reveal_end`.
+pub async fn create_lottery(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    currency: AccountId,
+    ticket_price: u64,
+    sales_end: BlockNumber,
+    reveal_end: BlockNumber,
+) -> Result<Account, Box<dyn Error>> {
+    if sales_end >= reveal_end {
+        return Err("the reveal phase must end after ticket sales".into());
+    }
+
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let value_word = |value: u64| -> Word { [ZERO, ZERO, ZERO, Felt::new(value)].into() };
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value([ZERO, ZERO, currency.suffix(), currency.prefix().as_felt()].into()),
+            StorageSlot::Value(value_word(ticket_price)),
+            StorageSlot::Value(value_word(sales_end.as_u32().into())),
+            StorageSlot::Value(value_word(reveal_end.as_u32().into())),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the lottery contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `sender` to the lottery `lottery_id`.
+pub fn lottery_note(
+    sender: AccountId,
+    lottery_id: AccountId,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(lottery_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+/// Has `sender` create `note` for the lottery, and the lottery consume it, creating `replies`.
+///
+/// The lottery is a `NoAuth` account, so any client tracking it can execute its transactions.
+async fn send_to_lottery(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    lottery: &Lottery,
+    note: Note,
+    replies: Vec<Note>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    client.submit_with_prover(sender, request).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .expected_output_recipients(
+            replies
+                .iter()
+                .map(|reply| reply.recipient().clone())
+                .collect(),
+        )
+        .build()?;
+    Ok(client.submit_with_prover(lottery.id, request).await?)
+}
+
+/// Has `player` buy a ticket committing to `secret`, paying the ticket price from its vault.
+/// `script` is the compiled [`TICKET_NOTE_PATH`].
+///
+/// Fails without sending anything unless tickets are sold at the current sync height.
+pub async fn buy_ticket(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player: AccountId,
+    lottery: &Lottery,
+    secret: &Secret,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    if lottery.phase_at(block_num) != Phase::Sales {
+        return Err(format!("the lottery no longer sells tickets at block {block_num}").into());
+    }
+
+    let serial_num = client.rng().draw_word();
+    let price = FungibleAsset::new(lottery.currency, lottery.ticket_price)?;
+    let note = lottery_note(
+        player,
+        lottery.id,
+        secret.commitment().as_elements().to_vec(),
+        vec![price.into()],
+        script,
+        serial_num,
+    )?;
+    send_to_lottery(client, player, lottery, note, vec![]).await
+}
+
+/// Reveals `secret`, which `player` committed to with its ticket, to the lottery. `script` is the
+/// compiled [`REVEAL_NOTE_PATH`].
+///
+/// Fails without sending anything unless the lottery is in its reveal phase at the current sync
+/// height. A secret not matching the commitment is rejected by the lottery.
+pub async fn reveal(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player: AccountId,
+    lottery: &Lottery,
+    secret: &Secret,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    if lottery.phase_at(block_num) != Phase::Reveal {
+        return Err(format!("the lottery does not accept reveals at block {block_num}").into());
+    }
+
+    let serial_num = client.rng().draw_word();
+    let note = lottery_note(
+        player,
+        lottery.id,
+        secret.0.as_elements().to_vec(),
+        vec![],
+        script,
+        serial_num,
+    )?;
+    send_to_lottery(client, player, lottery, note, vec![]).await
+}
+
+/// Has `sender` request the draw of `lottery`, read after the reveal phase, which pays the
+/// jackpot to the winner with a P2ID note. `script` is the compiled [`DRAW_NOTE_PATH`].
+///
+/// Fails without sending anything unless the reveal phase is over at the current sync height,
+/// and a player revealed.
+pub async fn draw(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    lottery: &Lottery,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    if lottery.phase_at(block_num) != Phase::Draw || lottery.drawn {
+        return Err(format!("the lottery cannot be drawn at block {block_num}").into());
+    }
+    let winner = lottery.winner().ok_or("no player revealed its secret")?;
+
+    let serial_num = client.rng().draw_word();
+    let inputs = vec![NoteTag::from_account_id(winner).into()];
+    let note = lottery_note(sender, lottery.id, inputs, vec![], script, serial_num)?;
+    let payout = lottery.payout(serial_num)?;
+    send_to_lottery(client, sender, lottery, note, vec![payout]).await
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn lottery(seed: u64, revealed: Vec<AccountId>) -> Lottery {
+        Lottery {
+            id: account(1, AccountType::RegularAccountImmutableCode),
+            currency: account(2, AccountType::FungibleFaucet),
+            ticket_price: 10,
+            sales_end: BlockNumber::from(10),
+            reveal_end: BlockNumber::from(20),
+            num_tickets: 4,
+            seed: [ZERO, ZERO, ZERO, Felt::new(seed)].into(),
+            revealed,
+            drawn: false,
+        }
+    }
+
+    #[test]
+    fn phases_follow_the_schedule() {
+        let lottery = lottery(0, vec![]);
+
+        assert_eq!(lottery.phase_at(9.into()), Phase::Sales);
+        assert_eq!(lottery.phase_at(10.into()), Phase::Reveal);
+        assert_eq!(lottery.phase_at(19.into()), Phase::Reveal);
+        assert_eq!(lottery.phase_at(20.into()), Phase::Draw);
+    }
+
+    #[test]
+    fn winner_is_drawn_from_the_low_bits_of_the_seed() {
+        let players: Vec<AccountId> = (3..6)
+            .map(|seed| account(seed, AccountType::RegularAccountUpdatableCode))
+            .collect();
+
+        assert_eq!(lottery(7, players.clone()).winner(), Some(players[1]));
+        // The high 32 bits do not take part in the draw
+        assert_eq!(
+            lottery((1 << 32) + 7, players.clone()).winner(),
+            Some(players[1])
+        );
+        assert_eq!(lottery(0, vec![]).winner(), None);
+    }
+
+    #[test]
+    fn payout_pays_every_ticket_to_the_winner() {
+        let winner = account(3, AccountType::RegularAccountUpdatableCode);
+        let lottery = lottery(7, vec![winner]);
+        let serial_num: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)].into();
+
+        let expected = reply_note(
+            lottery.id,
+            winner,
+            FungibleAsset::new(lottery.currency, 40).unwrap(),
+            serial_num,
+        )
+        .unwrap();
+        assert_eq!(lottery.payout(serial_num).unwrap().id(), expected.id());
+    }
+}
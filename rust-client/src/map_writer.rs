@@ -0,0 +1,249 @@
+//! Writing large batches of map entries within the cycle budget of a transaction.
+//!
+//! `batch_map::set_entries` writes any number of entries in one call, but every insertion costs
+//! cycles and a transaction cannot run more than its budget. The cost of a batch grows linearly
+//! with its size: a fixed part for the kernel, the script and piping the entries to memory, and
+//! a part per entry for hashing its way down the map. [`CycleCost`] captures both from two
+//! measurements, [`probe_capacity`] finds the largest batch fitting a budget, and a
+//! [`ChunkedWriter`] splits an update of any size into transactions of that size.
+
+use std::ops::Range;
+
+use miden_client::{
+    account::AccountId,
+    keystore::FilesystemKeyStore,
+    transaction::{TransactionId, TransactionRequest, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_objects::{assembly::Library, crypto::hash::rpo::Rpo256, ZERO};
+use rand::rngs::StdRng;
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::{total_cycles, word_to_masm},
+};
+
+/// Location of the batch map contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/batch_map.masm";
+/// Path under which the contract is linked into transaction scripts.
+pub const LIBRARY_PATH: &str = "external_contract::batch_map";
+
+/// Batch size of the second measurement of [`probe_capacity`]. Large enough for the cost per
+/// entry to stand out from the noise of the fixed part.
+const PROBE_BATCH: usize = 32;
+
+/// Cycles spent by a batch of map writes, as a fixed part plus a part per entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleCost {
+    pub base: usize,
+    pub per_entry: usize,
+}
+
+impl CycleCost {
+    /// Derives the cost from the cycles of two batches of different sizes. The cost per entry is
+    /// rounded up, so that [`CycleCost::capacity`] errs on the safe side.
+    pub fn from_measurements(small: (usize, usize), large: (usize, usize)) -> Self {
+        let ((small_entries, small_cycles), (large_entries, large_cycles)) = (small, large);
+        assert!(
+            large_entries > small_entries,
+            "the measurements must be of different batch sizes"
+        );
+
+        let per_entry = large_cycles
+            .saturating_sub(small_cycles)
+            .div_ceil(large_entries - small_entries);
+        Self {
+            base: small_cycles.saturating_sub(per_entry * small_entries),
+            per_entry,
+        }
+    }
+
+    /// Returns the cycles a batch of `entries` is expected to take.
+    pub fn cycles(&self, entries: usize) -> usize {
+        self.base + self.per_entry * entries
+    }
+
+    /// Returns the largest batch expected to fit in `budget` cycles.
+    pub fn capacity(&self, budget: usize) -> usize {
+        budget.saturating_sub(self.base) / self.per_entry.max(1)
+    }
+}
+
+/// Splits `total` entries into consecutive ranges of at most `chunk_size` entries.
+pub fn chunks(total: usize, chunk_size: usize) -> Vec<Range<usize>> {
+    assert!(chunk_size > 0, "chunks must hold at least one entry");
+    (0..total)
+        .step_by(chunk_size)
+        .map(|start| start..total.min(start + chunk_size))
+        .collect()
+}
+
+/// Builds a request writing `entries` to the map with a single call of `set_entries`. The entries
+/// travel in the advice map; only their commitment is part of the script.
+pub fn set_entries_request(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    library: &Library,
+    entries: &[(Word, Word)],
+) -> Result<TransactionRequest, Box<dyn std::error::Error>> {
+    let entries_data: Vec<Felt> = entries
+        .iter()
+        .flat_map(|(key, value)| key.iter().chain(value.iter()).copied())
+        .collect();
+    let entries_commitment = Rpo256::hash_elements(&entries_data);
+
+    let script_code = format!(
+        "use.external_contract::batch_map\n\
+         begin\n    push.{}\n    push.{}\n    call.batch_map::set_entries\nend\n",
+        entries.len(),
+        word_to_masm(entries_commitment)
+    );
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(library)?
+        .compile_tx_script(&script_code)?;
+
+    Ok(TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .extend_advice_map([(entries_commitment, entries_data)])
+        .build()?)
+}
+
+/// Executes a write of `entries` to the map of `contract_id` without submitting it, and returns
+/// the cycles it took.
+pub async fn measure(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    contract_id: AccountId,
+    library: &Library,
+    entries: &[(Word, Word)],
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let request = set_entries_request(client, library, entries)?;
+    let tx_result = client.execute_transaction(contract_id, request).await?;
+    Ok(total_cycles(
+        tx_result.executed_transaction().measurements(),
+    ))
+}
+
+/// Returns the i-th of the throwaway entries [`probe_capacity`] measures with.
+fn probe_entry(i: usize) -> (Word, Word) {
+    let i = Felt::new(i as u64 + 1);
+    ([ZERO, ZERO, ZERO, i].into(), [i, i, i, i].into())
+}
+
+/// Finds the largest number of entries the map of `contract_id` takes in one transaction of at
+/// most `budget` cycles, returning it with the cost model it was derived from.
+///
+/// The capacity is first estimated from two measurements, then corrected by executing batches
+/// around the estimate until the largest one fitting the budget is found. Nothing is submitted.
+pub async fn probe_capacity(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    contract_id: AccountId,
+    library: &Library,
+    budget: usize,
+) -> Result<(usize, CycleCost), Box<dyn std::error::Error>> {
+    let entries = |count: usize| (0..count).map(probe_entry).collect::<Vec<_>>();
+
+    let single = measure(client, contract_id, library, &entries(1)).await?;
+    let batch = measure(client, contract_id, library, &entries(PROBE_BATCH)).await?;
+    let cost = CycleCost::from_measurements((1, single), (PROBE_BATCH, batch));
+
+    let mut capacity = cost.capacity(budget);
+    while capacity > 0 && measure(client, contract_id, library, &entries(capacity)).await? > budget
+    {
+        capacity -= 1;
+    }
+    while measure(client, contract_id, library, &entries(capacity + 1)).await? <= budget {
+        capacity += 1;
+    }
+    if capacity == 0 {
+        return Err(format!("not even a single entry fits in {budget} cycles").into());
+    }
+
+    Ok((capacity, cost))
+}
+
+/// Writes entries to a batch map contract, in as many transactions as it takes.
+#[derive(Debug, Clone)]
+pub struct ChunkedWriter {
+    pub contract_id: AccountId,
+    pub chunk_size: usize,
+    library: Library,
+}
+
+impl ChunkedWriter {
+    /// Creates a writer submitting at most `chunk_size` entries per transaction.
+    pub fn new(contract_id: AccountId, library: Library, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunks must hold at least one entry");
+        Self {
+            contract_id,
+            chunk_size,
+            library,
+        }
+    }
+
+    /// Creates a writer whose chunks fit in `budget` cycles, as found by [`probe_capacity`].
+    pub async fn within_budget(
+        client: &mut Client<FilesystemKeyStore<StdRng>>,
+        contract_id: AccountId,
+        library: Library,
+        budget: usize,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let (capacity, _) = probe_capacity(client, contract_id, &library, budget).await?;
+        Ok(Self::new(contract_id, library, capacity))
+    }
+
+    /// Writes `entries`, one transaction per chunk, and returns the ids of the transactions.
+    ///
+    /// Every transaction is applied to the local state of the contract before the next one is
+    /// executed, so the chunks are submitted back to back without waiting for blocks. If one of
+    /// them fails, the chunks before it stay written.
+    pub async fn write(
+        &self,
+        client: &mut Client<FilesystemKeyStore<StdRng>>,
+        entries: &[(Word, Word)],
+    ) -> Result<Vec<TransactionId>, Box<dyn std::error::Error>> {
+        let mut tx_ids = Vec::new();
+        for range in chunks(entries.len(), self.chunk_size) {
+            let request = set_entries_request(client, &self.library, &entries[range])?;
+            tx_ids.push(client.submit_with_prover(self.contract_id, request).await?);
+        }
+        Ok(tx_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cost_is_derived_from_two_measurements() {
+        let cost = CycleCost::from_measurements((1, 5_300), (32, 67_300));
+        assert_eq!(
+            cost,
+            CycleCost {
+                base: 3_300,
+                per_entry: 2_000
+            }
+        );
+        assert_eq!(cost.cycles(10), 23_300);
+    }
+
+    #[test]
+    fn capacity_never_exceeds_the_budget() {
+        // 61_995 cycles over 31 entries are rounded up to 2_000 per entry
+        let cost = CycleCost::from_measurements((1, 5_300), (32, 67_295));
+        assert_eq!(cost.per_entry, 2_000);
+
+        let capacity = cost.capacity(65_536);
+        assert_eq!(capacity, 31);
+        assert!(cost.cycles(capacity) <= 65_536);
+        assert!(cost.cycles(capacity + 1) > 65_536);
+        assert_eq!(cost.capacity(1_000), 0);
+    }
+
+    #[test]
+    fn chunks_cover_every_entry_once() {
+        assert_eq!(chunks(10, 4), vec![0..4, 4..8, 8..10]);
+        assert_eq!(chunks(8, 4), vec![0..4, 4..8]);
+        assert!(chunks(0, 4).is_empty());
+    }
+}
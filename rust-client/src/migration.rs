@@ -0,0 +1,232 @@
+//! Moving the state of a contract to an account running a newer version of its component.
+//!
+//! The storage of an account is laid out when the account is created: a number of slots, each
+//! holding either a value or a map, which the code of its component addresses by index. A new
+//! version of a component expecting an additional slot cannot run on an account laid out for
+//! the old one, as the kernel rejects any access past the last slot of a component. Neither the
+//! code nor the layout of a deployed account can change afterwards, which leaves two strategies:
+//!
+//! - reserve slots upfront: the first version is deployed with empty slots that later versions
+//!   will use, so the state of an old account already fits the new layout and carries over slot
+//!   for slot, and readers addressing slots by index keep working;
+//! - deploy new and migrate: a new account is created with the new component, its storage
+//!   copied from the old account, map entries included, and the new slots appended.
+//!
+//! Either way the old account is left as it is. Clients have to be pointed at the new one, and a
+//! contract holding assets needs a procedure of its own to hand them over.
+
+use std::{error::Error, fmt};
+
+use miden_client::{account::Account, keystore::FilesystemKeyStore, Client, Word};
+use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel};
+use miden_objects::account::{AccountBuilder, AccountComponent, StorageMap, StorageSlot};
+use rand::{rngs::StdRng, RngCore};
+
+/// What a storage slot holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotKind {
+    Value,
+    Map,
+}
+
+impl SlotKind {
+    pub fn of(slot: &StorageSlot) -> Self {
+        match slot {
+            StorageSlot::Value(_) => Self::Value,
+            StorageSlot::Map(_) => Self::Map,
+        }
+    }
+}
+
+/// Why the storage of an account does not fit the layout a component expects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The storage has fewer slots than the layout.
+    MissingSlots { expected: usize, found: usize },
+    /// A slot holds a value where the layout expects a map, or the reverse.
+    KindMismatch {
+        index: usize,
+        expected: SlotKind,
+        found: SlotKind,
+    },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingSlots { expected, found } => {
+                write!(f, "storage has {found} slots, the layout needs {expected}")
+            }
+            Self::KindMismatch {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "slot {index} holds a {found:?}, the layout needs a {expected:?}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// Returns the layout of `slots`.
+pub fn layout_of(slots: &[StorageSlot]) -> Vec<SlotKind> {
+    slots.iter().map(SlotKind::of).collect()
+}
+
+/// Checks that `slots` fit `layout`: every slot of the layout exists and holds the same kind.
+/// Slots past the end of the layout are ignored.
+pub fn check_layout(slots: &[StorageSlot], layout: &[SlotKind]) -> Result<(), LayoutError> {
+    if slots.len() < layout.len() {
+        return Err(LayoutError::MissingSlots {
+            expected: layout.len(),
+            found: slots.len(),
+        });
+    }
+    for (index, (slot, &expected)) in slots.iter().zip(layout).enumerate() {
+        let found = SlotKind::of(slot);
+        if found != expected {
+            return Err(LayoutError::KindMismatch {
+                index,
+                expected,
+                found,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the entries of the map in slot `index` of `slots`.
+pub fn map_entries(
+    slots: &[StorageSlot],
+    index: usize,
+) -> Result<Vec<(Word, Word)>, Box<dyn Error>> {
+    match slots.get(index) {
+        Some(StorageSlot::Map(map)) => {
+            Ok(map.entries().map(|(key, value)| (*key, *value)).collect())
+        }
+        Some(StorageSlot::Value(_)) => Err(format!("slot {index} holds a value").into()),
+        None => Err(format!("there is no slot {index}").into()),
+    }
+}
+
+/// Builds the storage of an account running a new version of a component from `old`, the
+/// storage of an account running the previous one. `defaults` is the initial storage of the new
+/// version: every slot `old` has is copied over it, map entries included, and the slots added by
+/// the new version keep their defaults.
+///
+/// Fails if the new version does not keep the slots of the old one at the same indices.
+pub fn migrate_storage(
+    old: &[StorageSlot],
+    defaults: Vec<StorageSlot>,
+) -> Result<Vec<StorageSlot>, Box<dyn Error>> {
+    check_layout(&defaults, &layout_of(old))?;
+
+    let mut slots = defaults;
+    for (index, slot) in old.iter().enumerate() {
+        slots[index] = match slot {
+            StorageSlot::Value(value) => StorageSlot::Value(*value),
+            StorageSlot::Map(_) => {
+                StorageSlot::Map(StorageMap::with_entries(map_entries(old, index)?)?)
+            }
+        };
+    }
+    Ok(slots)
+}
+
+/// Deploys an account running the component compiled from `code`, with the storage of `old`
+/// migrated by [`migrate_storage`]. The new account has the type and storage mode of `old`.
+///
+/// Like the contracts of the tutorials, both accounts are expected to be `NoAuth` accounts, whose
+/// storage only holds the slots of their component.
+pub async fn deploy_migrated(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    old: &Account,
+    code: &str,
+    defaults: Vec<StorageSlot>,
+) -> Result<Account, Box<dyn Error>> {
+    let slots = migrate_storage(old.storage().slots(), defaults)?;
+    let component = AccountComponent::compile(code, TransactionKernel::assembler(), slots)?
+        .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(old.id().account_type())
+        .storage_mode(old.id().storage_mode())
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_client::Felt;
+    use miden_objects::ZERO;
+
+    use super::*;
+
+    fn value(value: u64) -> StorageSlot {
+        StorageSlot::Value([ZERO, ZERO, ZERO, Felt::new(value)].into())
+    }
+
+    fn map(entries: &[(u64, u64)]) -> StorageSlot {
+        let word = |value: u64| -> Word { [ZERO, ZERO, ZERO, Felt::new(value)].into() };
+        StorageSlot::Map(
+            StorageMap::with_entries(entries.iter().map(|&(key, value)| (word(key), word(value))))
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn layout_check_reports_missing_slots_and_ignores_extra_ones() {
+        let v1 = vec![map(&[]), value(0)];
+        let v2_layout = [SlotKind::Map, SlotKind::Value, SlotKind::Value];
+
+        assert_eq!(
+            check_layout(&v1, &v2_layout),
+            Err(LayoutError::MissingSlots {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(check_layout(&v1, &v2_layout[..1]), Ok(()));
+    }
+
+    #[test]
+    fn layout_check_reports_slots_of_another_kind() {
+        let slots = vec![value(0), value(0)];
+
+        assert_eq!(
+            check_layout(&slots, &[SlotKind::Map, SlotKind::Value]),
+            Err(LayoutError::KindMismatch {
+                index: 0,
+                expected: SlotKind::Map,
+                found: SlotKind::Value
+            })
+        );
+    }
+
+    #[test]
+    fn migration_copies_old_slots_and_keeps_new_defaults() {
+        let old = vec![map(&[(1, 120), (2, 80)]), value(200)];
+        let defaults = vec![map(&[]), value(0), value(7)];
+
+        let migrated = migrate_storage(&old, defaults).unwrap();
+        assert_eq!(migrated.len(), 3);
+        let mut entries = map_entries(&migrated, 0).unwrap();
+        entries.sort_by_key(|(key, _)| key[3].as_int());
+        let mut expected = map_entries(&old, 0).unwrap();
+        expected.sort_by_key(|(key, _)| key[3].as_int());
+        assert_eq!(entries, expected);
+        assert_eq!(migrated[1], old[1]);
+        assert_eq!(migrated[2], value(7));
+
+        assert!(migrate_storage(&old, vec![value(0), value(0)]).is_err());
+    }
+}
@@ -0,0 +1,443 @@
+//! Mirroring client events into a database owned by the application.
+//!
+//! The client store answers the questions the client needs answered. A dapp backend usually has
+//! questions of its own, such as the balance history of its users or the notes each of them
+//! received, and keeps a query model for them next to the client store. A [`ClientMirror`]
+//! feeds such a model: after every sync it compares the watched accounts with what it mirrored
+//! so far and hands the differences, as [`ClientEvent`]s, to an [`EventSink`].
+//!
+//! The sink stores the events of a sync together with the block they were observed at, in one
+//! database transaction, and the mirror restarts from whatever the sink holds. A crash between
+//! two syncs therefore never leaves the model half-updated, and events are neither lost nor
+//! repeated across restarts. [`SqliteSink`] is a ready-made sink; any other database only needs
+//! to implement the two methods of the trait.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    path::Path,
+};
+
+use miden_client::{
+    account::AccountId,
+    asset::Asset,
+    keystore::FilesystemKeyStore,
+    note::{Note, NoteId},
+    Client,
+};
+use miden_objects::block::BlockNumber;
+use rand::rngs::StdRng;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::helpers::{sync_with_retry, DEFAULT_SYNC_ATTEMPTS, DEFAULT_SYNC_BACKOFF};
+
+/// Fungible balances of the watched accounts, keyed by `(account, faucet)`.
+pub type Balances = BTreeMap<(AccountId, AccountId), u64>;
+
+/// A note which became consumable by a watched account.
+///
+/// Notes are observed while they are consumable, so a note consumed between two syncs of the
+/// mirror only shows up through the balance change it causes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedNote {
+    pub note_id: NoteId,
+    pub account_id: AccountId,
+    pub assets: Vec<Asset>,
+}
+
+/// A change observed by the mirror between two syncs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientEvent {
+    /// A note became consumable by a watched account.
+    NoteReceived(ReceivedNote),
+    /// The balance of a watched account in tokens of `faucet_id` changed.
+    BalanceChanged {
+        account_id: AccountId,
+        faucet_id: AccountId,
+        old: u64,
+        new: u64,
+    },
+}
+
+/// What a sink has mirrored so far: the last known balances and every note already reported.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot {
+    pub balances: Balances,
+    pub notes: BTreeSet<NoteId>,
+}
+
+impl Snapshot {
+    /// Returns the events turning this snapshot into the observed `balances` and `notes`.
+    ///
+    /// A balance missing from `balances` is zero, as the vault drops assets it no longer holds.
+    /// Notes already in the snapshot are not reported again.
+    pub fn changes(&self, balances: &Balances, notes: Vec<ReceivedNote>) -> Vec<ClientEvent> {
+        let mut events: Vec<ClientEvent> = notes
+            .into_iter()
+            .filter(|note| !self.notes.contains(&note.note_id))
+            .map(ClientEvent::NoteReceived)
+            .collect();
+
+        let keys: BTreeSet<_> = self.balances.keys().chain(balances.keys()).collect();
+        for &(account_id, faucet_id) in keys {
+            let old = self
+                .balances
+                .get(&(account_id, faucet_id))
+                .copied()
+                .unwrap_or(0);
+            let new = balances.get(&(account_id, faucet_id)).copied().unwrap_or(0);
+            if old != new {
+                events.push(ClientEvent::BalanceChanged {
+                    account_id,
+                    faucet_id,
+                    old,
+                    new,
+                });
+            }
+        }
+        events
+    }
+
+    /// Records `events` in the snapshot.
+    pub fn apply(&mut self, events: &[ClientEvent]) {
+        for event in events {
+            match event {
+                ClientEvent::NoteReceived(note) => {
+                    self.notes.insert(note.note_id);
+                }
+                ClientEvent::BalanceChanged {
+                    account_id,
+                    faucet_id,
+                    new: 0,
+                    ..
+                } => {
+                    self.balances.remove(&(*account_id, *faucet_id));
+                }
+                ClientEvent::BalanceChanged {
+                    account_id,
+                    faucet_id,
+                    new,
+                    ..
+                } => {
+                    self.balances.insert((*account_id, *faucet_id), *new);
+                }
+            }
+        }
+    }
+}
+
+/// Storage the mirrored events are written to.
+pub trait EventSink {
+    /// Returns everything the sink has mirrored so far, an empty snapshot for a new sink.
+    fn load(&self) -> Result<Snapshot, Box<dyn Error>>;
+
+    /// Stores `events`, observed when the client synced to `block_num`, all or nothing.
+    fn apply(
+        &mut self,
+        block_num: BlockNumber,
+        events: &[ClientEvent],
+    ) -> Result<(), Box<dyn Error>>;
+}
+
+/// Mirrors the notes and balances of a set of accounts into an [`EventSink`].
+#[derive(Debug)]
+pub struct ClientMirror<S> {
+    accounts: Vec<AccountId>,
+    sink: S,
+    snapshot: Snapshot,
+}
+
+impl<S: EventSink> ClientMirror<S> {
+    /// Mirrors `accounts` into `sink`, picking up where the sink left off.
+    pub fn new(sink: S, accounts: Vec<AccountId>) -> Result<Self, Box<dyn Error>> {
+        let snapshot = sink.load()?;
+        Ok(Self {
+            accounts,
+            sink,
+            snapshot,
+        })
+    }
+
+    /// Returns the sink the mirror writes to.
+    pub fn sink(&self) -> &S {
+        &self.sink
+    }
+
+    /// Syncs the client, stores the changes since the previous sync in the sink and returns
+    /// them. Accounts the client does not track are skipped.
+    pub async fn sync(
+        &mut self,
+        client: &mut Client<FilesystemKeyStore<StdRng>>,
+    ) -> Result<Vec<ClientEvent>, Box<dyn Error>> {
+        let summary = sync_with_retry(client, DEFAULT_SYNC_ATTEMPTS, DEFAULT_SYNC_BACKOFF).await?;
+
+        let mut balances = Balances::new();
+        let mut notes = Vec::new();
+        for &account_id in &self.accounts {
+            let Some(record) = client.get_account(account_id).await? else {
+                continue;
+            };
+            for asset in record.account().vault().assets() {
+                if let Asset::Fungible(asset) = asset {
+                    balances.insert((account_id, asset.faucet_id()), asset.amount());
+                }
+            }
+            for (record, _) in client.get_consumable_notes(Some(account_id)).await? {
+                if let Ok(note) = Note::try_from(record) {
+                    notes.push(ReceivedNote {
+                        note_id: note.id(),
+                        account_id,
+                        assets: note.assets().iter().copied().collect(),
+                    });
+                }
+            }
+        }
+
+        let events = self.snapshot.changes(&balances, notes);
+        self.sink.apply(summary.block_num, &events)?;
+        self.snapshot.apply(&events);
+        Ok(events)
+    }
+}
+
+/// An [`EventSink`] writing to a SQLite database with the schema below.
+///
+/// - `balances`: current balance of every account in every token,
+/// - `balance_changes`: every balance change with the block it was observed at,
+/// - `received_notes`: every note received, with its assets as `faucet:amount` pairs,
+/// - `mirror_state`: the block of the last sync, a single row.
+#[derive(Debug)]
+pub struct SqliteSink {
+    connection: Connection,
+}
+
+impl SqliteSink {
+    /// Opens the database at `path`, creating it and its tables if needed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS balances (
+                account_id TEXT NOT NULL,
+                faucet_id TEXT NOT NULL,
+                amount INTEGER NOT NULL,
+                PRIMARY KEY (account_id, faucet_id)
+            );
+            CREATE TABLE IF NOT EXISTS balance_changes (
+                account_id TEXT NOT NULL,
+                faucet_id TEXT NOT NULL,
+                old_amount INTEGER NOT NULL,
+                new_amount INTEGER NOT NULL,
+                block_num INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS received_notes (
+                note_id TEXT PRIMARY KEY,
+                account_id TEXT NOT NULL,
+                assets TEXT NOT NULL,
+                block_num INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS mirror_state (
+                id INTEGER PRIMARY KEY CHECK (id = 0),
+                block_num INTEGER NOT NULL
+            );",
+        )?;
+        Ok(Self { connection })
+    }
+
+    /// Returns the connection, to run the queries of the application.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Returns the block of the last sync mirrored, if any.
+    pub fn last_block(&self) -> Result<Option<BlockNumber>, Box<dyn Error>> {
+        let block_num: Option<u32> = self
+            .connection
+            .query_row(
+                "SELECT block_num FROM mirror_state WHERE id = 0",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(block_num.map(BlockNumber::from))
+    }
+}
+
+impl EventSink for SqliteSink {
+    fn load(&self) -> Result<Snapshot, Box<dyn Error>> {
+        let mut snapshot = Snapshot::default();
+
+        let mut statement = self
+            .connection
+            .prepare("SELECT account_id, faucet_id, amount FROM balances WHERE amount > 0")?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (account_id, faucet_id, amount) = row?;
+            let key = (
+                AccountId::from_hex(&account_id)?,
+                AccountId::from_hex(&faucet_id)?,
+            );
+            snapshot.balances.insert(key, u64::try_from(amount)?);
+        }
+
+        let mut statement = self
+            .connection
+            .prepare("SELECT note_id FROM received_notes")?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+        for row in rows {
+            snapshot.notes.insert(NoteId::try_from_hex(&row?)?);
+        }
+
+        Ok(snapshot)
+    }
+
+    fn apply(
+        &mut self,
+        block_num: BlockNumber,
+        events: &[ClientEvent],
+    ) -> Result<(), Box<dyn Error>> {
+        let block_num = block_num.as_u32();
+        let tx = self.connection.transaction()?;
+
+        for event in events {
+            match event {
+                ClientEvent::NoteReceived(note) => {
+                    let assets: Vec<String> = note
+                        .assets
+                        .iter()
+                        .map(|asset| match asset {
+                            Asset::Fungible(asset) => {
+                                format!("{}:{}", asset.faucet_id().to_hex(), asset.amount())
+                            }
+                            Asset::NonFungible(asset) => {
+                                format!("{}:nft", asset.faucet_id_prefix().to_hex())
+                            }
+                        })
+                        .collect();
+                    tx.execute(
+                        "INSERT OR IGNORE INTO received_notes (note_id, account_id, assets, block_num)
+                         VALUES (?1, ?2, ?3, ?4)",
+                        params![
+                            note.note_id.to_hex(),
+                            note.account_id.to_hex(),
+                            assets.join(","),
+                            block_num
+                        ],
+                    )?;
+                }
+                ClientEvent::BalanceChanged {
+                    account_id,
+                    faucet_id,
+                    old,
+                    new,
+                } => {
+                    let (old, new) = (i64::try_from(*old)?, i64::try_from(*new)?);
+                    tx.execute(
+                        "INSERT INTO balance_changes
+                         (account_id, faucet_id, old_amount, new_amount, block_num)
+                         VALUES (?1, ?2, ?3, ?4, ?5)",
+                        params![account_id.to_hex(), faucet_id.to_hex(), old, new, block_num],
+                    )?;
+                    tx.execute(
+                        "INSERT INTO balances (account_id, faucet_id, amount) VALUES (?1, ?2, ?3)
+                         ON CONFLICT (account_id, faucet_id) DO UPDATE SET amount = excluded.amount",
+                        params![account_id.to_hex(), faucet_id.to_hex(), new],
+                    )?;
+                }
+            }
+        }
+
+        tx.execute(
+            "INSERT INTO mirror_state (id, block_num) VALUES (0, ?1)
+             ON CONFLICT (id) DO UPDATE SET block_num = excluded.block_num",
+            params![block_num],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::{AccountIdVersion, AccountStorageMode, AccountType};
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn wallet() -> AccountId {
+        account(1, AccountType::RegularAccountUpdatableCode)
+    }
+
+    fn faucet() -> AccountId {
+        account(2, AccountType::FungibleFaucet)
+    }
+
+    #[test]
+    fn balance_changes_are_reported_until_applied() {
+        let mut snapshot = Snapshot::default();
+        let balances = Balances::from([((wallet(), faucet()), 100)]);
+
+        let events = snapshot.changes(&balances, vec![]);
+        assert_eq!(
+            events,
+            [ClientEvent::BalanceChanged {
+                account_id: wallet(),
+                faucet_id: faucet(),
+                old: 0,
+                new: 100,
+            }]
+        );
+
+        snapshot.apply(&events);
+        assert!(snapshot.changes(&balances, vec![]).is_empty());
+    }
+
+    #[test]
+    fn emptied_balance_is_reported_as_zero() {
+        let mut snapshot = Snapshot::default();
+        snapshot.balances.insert((wallet(), faucet()), 100);
+
+        let events = snapshot.changes(&Balances::new(), vec![]);
+        assert_eq!(
+            events,
+            [ClientEvent::BalanceChanged {
+                account_id: wallet(),
+                faucet_id: faucet(),
+                old: 100,
+                new: 0,
+            }]
+        );
+
+        snapshot.apply(&events);
+        assert_eq!(snapshot, Snapshot::default());
+    }
+
+    #[test]
+    fn notes_are_reported_once() {
+        let mut snapshot = Snapshot::default();
+        let note = ReceivedNote {
+            note_id: NoteId::new(Default::default(), Default::default()),
+            account_id: wallet(),
+            assets: vec![],
+        };
+
+        let events = snapshot.changes(&Balances::new(), vec![note.clone()]);
+        assert_eq!(events, [ClientEvent::NoteReceived(note.clone())]);
+
+        snapshot.apply(&events);
+        assert!(snapshot.changes(&Balances::new(), vec![note]).is_empty());
+    }
+}
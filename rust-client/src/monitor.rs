@@ -0,0 +1,59 @@
+//! Watching an account for incoming notes.
+//!
+//! Services reacting to payments only care about the notes they have not handled yet. A
+//! [`NoteMonitor`] syncs the client and returns the notes which became consumable by its
+//! account since the previous poll, each one exactly once.
+
+use std::collections::BTreeSet;
+
+use miden_client::{
+    account::AccountId,
+    keystore::FilesystemKeyStore,
+    note::{Note, NoteId},
+    Client, ClientError,
+};
+use rand::rngs::StdRng;
+
+use crate::helpers::{sync_with_retry, DEFAULT_SYNC_ATTEMPTS, DEFAULT_SYNC_BACKOFF};
+
+/// Returns the notes consumable by one account as they arrive.
+#[derive(Debug)]
+pub struct NoteMonitor {
+    account_id: AccountId,
+    seen: BTreeSet<NoteId>,
+}
+
+impl NoteMonitor {
+    /// Watches `account_id`. Notes consumable at the first poll are returned by it too.
+    pub fn new(account_id: AccountId) -> Self {
+        Self {
+            account_id,
+            seen: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the account watched by the monitor.
+    pub fn account_id(&self) -> AccountId {
+        self.account_id
+    }
+
+    /// Syncs the client and returns the notes consumable by the account which no previous poll
+    /// returned. Notes whose details the client does not know are skipped.
+    pub async fn poll(
+        &mut self,
+        client: &mut Client<FilesystemKeyStore<StdRng>>,
+    ) -> Result<Vec<Note>, ClientError> {
+        sync_with_retry(client, DEFAULT_SYNC_ATTEMPTS, DEFAULT_SYNC_BACKOFF).await?;
+
+        let mut new_notes = Vec::new();
+        for (record, _) in client.get_consumable_notes(Some(self.account_id)).await? {
+            if !self.seen.insert(record.id()) {
+                continue;
+            }
+            if let Ok(note) = Note::try_from(record) {
+                new_notes.push(note);
+            }
+        }
+        Ok(new_notes)
+    }
+}
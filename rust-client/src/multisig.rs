@@ -0,0 +1,375 @@
+//! Off-chain side of the multisig wallet (`masm/accounts/multisig.masm`).
+//!
+//! The multisig stores the public keys of its signers and the number of approvals a payout
+//! needs. A payout is a note the multisig emits from its vault, described by a [`Proposal`].
+//! Each signer approves it by signing the proposal and sending an [`Approval`] note to the
+//! multisig. Whoever executes the payout gathers the signatures, since they are too large for
+//! note inputs, and consumes the approval notes in the same transaction which emits the payout.
+//!
+//! The account is authenticated by `AuthRpoFalcon512Acl`: consuming approvals and executing
+//! payouts need no signature, while spending through the basic wallet interface needs the
+//! signature of a recovery key.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{component::BasicWallet, Account, AccountId},
+    auth::AuthSecretKey,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequest, TransactionRequestBuilder},
+    utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
+    Client, Felt, Word,
+};
+use miden_lib::{
+    account::auth::{AuthRpoFalcon512Acl, AuthRpoFalcon512AclConfig},
+    transaction::TransactionKernel,
+};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    asset::{Asset, FungibleAsset},
+    crypto::hash::rpo::Rpo256,
+    NoteError, ONE, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::{create_library, word_to_masm},
+};
+
+/// Path under which the multisig contract is linked into its notes and scripts.
+pub const LIBRARY_PATH: &str = "external_contract::multisig";
+/// Location of the multisig contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/multisig.masm";
+/// Location of the approval note, relative to `rust-client`.
+pub const APPROVAL_NOTE_PATH: &str = "../masm/notes/multisig_approval_note.masm";
+
+/// A payout of the multisig waiting for approvals: a note carrying one fungible asset.
+#[derive(Debug, Clone)]
+pub struct Proposal {
+    pub payout: Note,
+    pub asset: FungibleAsset,
+}
+
+impl Proposal {
+    /// Wraps `payout`, which must carry exactly one fungible asset.
+    pub fn new(payout: Note) -> Result<Self, Box<dyn Error>> {
+        let assets: Vec<_> = payout.assets().iter().collect();
+        let [Asset::Fungible(asset)] = assets.as_slice() else {
+            return Err("a payout must carry exactly one fungible asset".into());
+        };
+        let asset = *asset;
+
+        Ok(Self { payout, asset })
+    }
+
+    /// Returns hash(RECIPIENT, ASSET), under which the multisig counts the approvals.
+    pub fn commitment(&self) -> Word {
+        Rpo256::merge(&[
+            self.payout.recipient().digest(),
+            Asset::from(self.asset).into(),
+        ])
+    }
+
+    /// Returns the message signers sign to approve the payout from `multisig_id`. It includes
+    /// the id so that an approval cannot be replayed against another multisig sharing a signer.
+    pub fn message(&self, multisig_id: AccountId) -> Word {
+        let account_word = [
+            ZERO,
+            ZERO,
+            multisig_id.suffix(),
+            multisig_id.prefix().as_felt(),
+        ]
+        .into();
+        Rpo256::merge(&[self.commitment(), account_word])
+    }
+}
+
+/// The approval of a [`Proposal`] by one signer.
+#[derive(Debug, Clone)]
+pub struct Approval {
+    /// Note recording the approval, sent to the multisig.
+    pub note: Note,
+    /// Key under which the multisig looks up the signature in the advice map.
+    pub signature_key: Word,
+    /// Signature of the signer, prepared for the advice stack.
+    pub signature: Vec<Felt>,
+}
+
+impl Approval {
+    /// Signs `proposal` with `signer_key` and builds the approval note `sender` sends to the
+    /// multisig `multisig_id`. `script` is the compiled [`APPROVAL_NOTE_PATH`].
+    ///
+    /// The signature does not depend on the sender, so signers can use any account to send
+    /// their approval.
+    pub fn new(
+        sender: AccountId,
+        multisig_id: AccountId,
+        proposal: &Proposal,
+        signer_key: &AuthSecretKey,
+        script: NoteScript,
+        serial_num: Word,
+    ) -> Result<Self, NoteError> {
+        let public_key: Word = signer_key.public_key().to_commitment().into();
+        let message = proposal.message(multisig_id);
+
+        let mut inputs = public_key.to_vec();
+        inputs.extend(proposal.commitment().iter());
+        let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+        let metadata = NoteMetadata::new(
+            sender,
+            NoteType::Public,
+            NoteTag::from_account_id(multisig_id),
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )?;
+
+        Ok(Self {
+            note: Note::new(NoteAssets::default(), metadata, recipient),
+            signature_key: Rpo256::merge(&[public_key, message]),
+            signature: signer_key.sign(message).to_prepared_signature(message),
+        })
+    }
+}
+
+// Signers usually run their own clients, so approvals are serialized to hand them over to
+// whoever executes the payout.
+impl Serializable for Approval {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.note.write_into(target);
+        self.signature_key.write_into(target);
+        self.signature.write_into(target);
+    }
+}
+
+impl Deserializable for Approval {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(Self {
+            note: Note::read_from(source)?,
+            signature_key: Word::read_from(source)?,
+            signature: Vec::<Felt>::read_from(source)?,
+        })
+    }
+}
+
+/// Deploys a public multisig whose payouts need `threshold` approvals of `signer_keys`.
+/// Spending through the wallet interface instead needs a signature of `recovery_key`.
+pub async fn create_multisig(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    signer_keys: &[Word],
+    threshold: u64,
+    recovery_key: Word,
+) -> Result<Account, Box<dyn Error>> {
+    if threshold == 0 || threshold > signer_keys.len() as u64 {
+        return Err(format!(
+            "threshold must be between 1 and {}, got {threshold}",
+            signer_keys.len()
+        )
+        .into());
+    }
+
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let signers = StorageMap::with_entries(
+        signer_keys
+            .iter()
+            .map(|key| (*key, Word::from([ZERO, ZERO, ZERO, ONE]))),
+    )?;
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Map(signers),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::new(threshold)].into()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    // Approvals and payouts go through the multisig component, which checks the signatures
+    // itself. Only moving assets out through the basic wallet requires the recovery key.
+    let auth = AuthRpoFalcon512Acl::new(
+        recovery_key.into(),
+        AuthRpoFalcon512AclConfig::new()
+            .with_auth_trigger_procedures(vec![BasicWallet::move_asset_to_note_digest()])
+            .with_allow_unauthorized_input_notes(true)
+            .with_allow_unauthorized_output_notes(true),
+    )?;
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(auth)
+        .with_component(BasicWallet)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the approval note script, linked against the multisig contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(APPROVAL_NOTE_PATH)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Has the sender of `approval` send its approval note.
+pub async fn send_approval(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    approval: &Approval,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(approval.note.clone())])
+        .build()?;
+    Ok(client
+        .submit_with_prover(approval.note.metadata().sender(), request)
+        .await?)
+}
+
+/// Builds the transaction which consumes `approvals` and executes `proposal`, to be executed
+/// against the multisig.
+pub fn execute_request(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    proposal: &Proposal,
+    approvals: &[Approval],
+) -> Result<TransactionRequest, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let script_code = format!(
+        "use.external_contract::multisig\n\
+         begin\n    push.{}\n    push.{}\n    push.{}\n    call.multisig::execute\nend\n",
+        proposal.payout.metadata().tag().as_u32(),
+        word_to_masm(Asset::from(proposal.asset).into()),
+        word_to_masm(proposal.payout.recipient().digest())
+    );
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_tx_script(&script_code)?;
+
+    Ok(TransactionRequestBuilder::new()
+        .unauthenticated_input_notes(
+            approvals
+                .iter()
+                .map(|approval| (approval.note.clone(), None)),
+        )
+        .extend_advice_map(
+            approvals
+                .iter()
+                .map(|approval| (approval.signature_key, approval.signature.clone())),
+        )
+        .custom_script(tx_script)
+        .expected_output_recipients(vec![proposal.payout.recipient().clone()])
+        .build()?)
+}
+
+/// Consumes `approvals` and executes `proposal` on `multisig_id` in a single transaction.
+pub async fn execute(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    multisig_id: AccountId,
+    proposal: &Proposal,
+    approvals: &[Approval],
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = execute_request(client, proposal, approvals)?;
+    Ok(client.submit_with_prover(multisig_id, request).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_lib::note::WellKnownNote;
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn payout(assets: Vec<Asset>) -> Note {
+        let sender = account(1, AccountType::RegularAccountImmutableCode);
+        let recipient = NoteRecipient::new(
+            Word::default(),
+            WellKnownNote::P2ID.script(),
+            NoteInputs::new(vec![]).unwrap(),
+        );
+        let metadata = NoteMetadata::new(
+            sender,
+            NoteType::Public,
+            NoteTag::from_account_id(sender),
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )
+        .unwrap();
+        Note::new(NoteAssets::new(assets).unwrap(), metadata, recipient)
+    }
+
+    fn tokens(amount: u64) -> Asset {
+        FungibleAsset::new(account(2, AccountType::FungibleFaucet), amount)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn payout_must_carry_one_fungible_asset() {
+        assert!(Proposal::new(payout(vec![tokens(10)])).is_ok());
+        assert!(Proposal::new(payout(vec![])).is_err());
+    }
+
+    #[test]
+    fn commitment_covers_the_asset() {
+        let small = Proposal::new(payout(vec![tokens(10)])).unwrap();
+        let large = Proposal::new(payout(vec![tokens(11)])).unwrap();
+
+        assert_ne!(small.commitment(), large.commitment());
+    }
+
+    #[test]
+    fn message_is_bound_to_the_multisig() {
+        let proposal = Proposal::new(payout(vec![tokens(10)])).unwrap();
+        let first = account(3, AccountType::RegularAccountImmutableCode);
+        let second = account(4, AccountType::RegularAccountImmutableCode);
+
+        assert_ne!(proposal.message(first), proposal.message(second));
+    }
+
+    #[test]
+    fn approval_survives_serialization() {
+        let proposal = Proposal::new(payout(vec![tokens(10)])).unwrap();
+        let sender = account(5, AccountType::RegularAccountImmutableCode);
+        let multisig_id = account(6, AccountType::RegularAccountImmutableCode);
+        let approval = Approval::new(
+            sender,
+            multisig_id,
+            &proposal,
+            &AuthSecretKey::new_rpo_falcon512(),
+            WellKnownNote::P2ID.script(),
+            Word::default(),
+        )
+        .unwrap();
+
+        let decoded = Approval::read_from_bytes(&approval.to_bytes()).unwrap();
+
+        assert_eq!(decoded.note, approval.note);
+        assert_eq!(decoded.signature_key, approval.signature_key);
+        assert_eq!(decoded.signature, approval.signature);
+    }
+}
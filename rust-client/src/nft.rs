@@ -0,0 +1,196 @@
+//! Deploying an NFT collection (`masm/accounts/nft_collection.masm`) and minting NFTs from it.
+//!
+//! A non-fungible asset is a single word: the id prefix of the faucet that minted it and the hash
+//! of its data. The data itself never lands on chain, only its commitment does. [`NftMetadata`]
+//! is serialized to JSON to form that data, so whoever is handed the metadata can recompute the
+//! asset with [`NftMetadata::asset`] and check that a vault holds it.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    auth::AuthSecretKey,
+    keystore::FilesystemKeyStore,
+    note::{create_p2id_note, Note, NoteType},
+    transaction::{TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::AuthRpoFalcon512, transaction::TransactionKernel};
+use miden_objects::{
+    account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
+    asset::{NonFungibleAsset, NonFungibleAssetDetails},
+    AssetError, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::{create_library, word_to_masm},
+};
+
+/// Path under which the collection contract is linked into the mint script.
+pub const LIBRARY_PATH: &str = "external_contract::nft_collection";
+/// Location of the collection contract, relative to `rust-client`.
+pub const COLLECTION_PATH: &str = "../masm/accounts/nft_collection.masm";
+/// Location of the transaction script minting an NFT into a note, relative to `rust-client`.
+pub const MINT_SCRIPT_PATH: &str = "../masm/scripts/mint_nft_script.masm";
+
+/// Off-chain description of an NFT, committed to by the asset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftMetadata {
+    pub name: String,
+    pub description: String,
+    /// Location of the image, e.g. an IPFS URI.
+    pub image: String,
+}
+
+impl NftMetadata {
+    /// Returns the data the asset commits to.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("NFT metadata is always serializable")
+    }
+
+    /// Returns the NFT `collection` mints for this metadata.
+    pub fn asset(&self, collection: AccountId) -> Result<NonFungibleAsset, AssetError> {
+        let details = NonFungibleAssetDetails::new(collection.prefix(), self.to_bytes())?;
+        NonFungibleAsset::new(&details)
+    }
+}
+
+/// Deploys a public NFT collection paying `royalty_percent` of every royalty sale to `creator`,
+/// and adds its key to `keystore`.
+pub async fn create_collection(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    keystore: &FilesystemKeyStore<StdRng>,
+    creator: AccountId,
+    royalty_percent: u64,
+) -> Result<Account, Box<dyn Error>> {
+    let code = fs::read_to_string(COLLECTION_PATH)?;
+    let royalty_config: Word = [
+        Felt::new(royalty_percent),
+        ZERO,
+        creator.suffix(),
+        creator.prefix().as_felt(),
+    ]
+    .into();
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Value(royalty_config)],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::NonFungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    keystore.add_key(&key_pair)?;
+
+    Ok(account)
+}
+
+/// Has `collection` mint the NFT of `metadata` straight into a P2ID note for `receiver`, and
+/// returns the transaction with the note.
+pub async fn mint_nft(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    collection: AccountId,
+    receiver: AccountId,
+    metadata: &NftMetadata,
+    note_type: NoteType,
+) -> Result<(TransactionId, Note), Box<dyn Error>> {
+    let nft = metadata.asset(collection)?;
+    let note = create_p2id_note(
+        collection,
+        receiver,
+        vec![nft.into()],
+        note_type,
+        Felt::new(0),
+        client.rng(),
+    )?;
+
+    let script_code = fs::read_to_string(MINT_SCRIPT_PATH)?
+        .replace("{recipient}", &word_to_masm(note.recipient().digest()))
+        .replace(
+            "{execution_hint}",
+            &u64::from(note.metadata().execution_hint()).to_string(),
+        )
+        .replace(
+            "{note_type}",
+            &(note.metadata().note_type() as u8).to_string(),
+        )
+        .replace("{aux}", "0")
+        .replace("{tag}", &u32::from(note.metadata().tag()).to_string())
+        .replace("{nft_asset}", &word_to_masm(Word::from(nft)));
+
+    let library = create_library(&fs::read_to_string(COLLECTION_PATH)?, LIBRARY_PATH)?;
+    let tx_script = client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_tx_script(&script_code)?;
+
+    let request = TransactionRequestBuilder::new()
+        .custom_script(tx_script)
+        .expected_output_recipients(vec![note.recipient().clone()])
+        .build()?;
+    let tx_id = client.submit_with_prover(collection, request).await?;
+
+    Ok((tx_id, note))
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    fn collection(seed: u8) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            AccountType::NonFungibleFaucet,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn metadata(name: &str) -> NftMetadata {
+        NftMetadata {
+            name: name.to_string(),
+            description: "A tutorial NFT".to_string(),
+            image: "ipfs://tutorial".to_string(),
+        }
+    }
+
+    #[test]
+    fn metadata_round_trips_through_its_bytes() {
+        let metadata = metadata("Miden #1");
+        let decoded: NftMetadata = serde_json::from_slice(&metadata.to_bytes()).unwrap();
+
+        assert_eq!(decoded, metadata);
+    }
+
+    #[test]
+    fn the_asset_is_recomputed_from_the_metadata() {
+        let nft = metadata("Miden #1").asset(collection(1)).unwrap();
+
+        assert_eq!(metadata("Miden #1").asset(collection(1)).unwrap(), nft);
+        assert_eq!(nft.faucet_id_prefix(), collection(1).prefix());
+    }
+
+    #[test]
+    fn every_metadata_and_collection_mints_a_distinct_asset() {
+        let nft = metadata("Miden #1").asset(collection(1)).unwrap();
+
+        assert_ne!(metadata("Miden #2").asset(collection(1)).unwrap(), nft);
+        assert_ne!(metadata("Miden #1").asset(collection(2)).unwrap(), nft);
+    }
+}
@@ -0,0 +1,407 @@
+//! Off-chain side of the NFT marketplace (`masm/accounts/nft_marketplace.masm`).
+//!
+//! Sellers list NFTs minted with [`crate::nft`] by sending them to the marketplace in a
+//! `nft_list_note` with a price. A buyer consumes a listing by sending a `nft_buy_note` carrying
+//! the payment: in the same transaction, the marketplace sends the NFT to the buyer and the
+//! payment to the seller, or fails without touching either. A seller can take an NFT back with
+//! a `nft_cancel_note` as long as it is not sold. Every payout is a P2ID note whose serial number
+//! is the serial number of the requesting note plus an offset, 1 for the note to the sender of
+//! the request and 2 for the proceeds of a sale.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, note::WellKnownNote, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    asset::{Asset, FungibleAsset, NonFungibleAsset},
+    NoteError, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{cli::SubmitWithProver, helpers::create_library};
+
+/// Path under which the marketplace contract is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::nft_marketplace";
+/// Location of the marketplace contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/nft_marketplace.masm";
+/// Location of the note listing an NFT, relative to `rust-client`.
+pub const LIST_NOTE_PATH: &str = "../masm/notes/nft_list_note.masm";
+/// Location of the note buying a listed NFT, relative to `rust-client`.
+pub const BUY_NOTE_PATH: &str = "../masm/notes/nft_buy_note.masm";
+/// Location of the note cancelling a listing, relative to `rust-client`.
+pub const CANCEL_NOTE_PATH: &str = "../masm/notes/nft_cancel_note.masm";
+
+const LISTINGS_SLOT: u8 = 0;
+const CURRENCY_SLOT: u8 = 1;
+
+/// Offset of the serial number of the note paid to the sender of a request.
+pub const SENDER_PAYOUT_OFFSET: u64 = 1;
+/// Offset of the serial number of the note paying the proceeds of a sale to the seller.
+pub const SELLER_PAYOUT_OFFSET: u64 = 2;
+
+/// An NFT for sale in the marketplace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Listing {
+    pub seller: AccountId,
+    /// Amount of the marketplace currency the NFT sells for.
+    pub price: u64,
+    /// Tag of the P2ID note paying the proceeds to the seller.
+    pub seller_tag: NoteTag,
+}
+
+impl Listing {
+    /// Decodes a value of the listings map, `None` for the empty word of an NFT that is not
+    /// listed.
+    pub fn from_word(word: Word) -> Result<Option<Self>, Box<dyn Error>> {
+        if word == Word::default() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            seller: AccountId::try_from([word[3], word[2]])?,
+            price: word[1].as_int(),
+            seller_tag: NoteTag::from(u32::try_from(word[0].as_int())?),
+        }))
+    }
+
+    /// Encodes the listing the way the contract stores it.
+    pub fn to_word(&self) -> Word {
+        [
+            self.seller_tag.into(),
+            Felt::new(self.price),
+            self.seller.suffix(),
+            self.seller.prefix().as_felt(),
+        ]
+        .into()
+    }
+}
+
+/// A deployed marketplace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Marketplace {
+    pub id: AccountId,
+    /// Faucet of the tokens prices are paid in.
+    pub currency: AccountId,
+}
+
+impl Marketplace {
+    /// Decodes a deployed marketplace account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let currency = account.storage().get_item(CURRENCY_SLOT)?;
+        Ok(Self {
+            id: account.id(),
+            currency: AccountId::try_from([currency[3], currency[2]])?,
+        })
+    }
+
+    /// Returns `price` tokens of the marketplace currency.
+    pub fn price(&self, price: u64) -> Result<FungibleAsset, Box<dyn Error>> {
+        Ok(FungibleAsset::new(self.currency, price)?)
+    }
+
+    /// Returns the P2ID note by which the marketplace pays `asset` to `receiver` in reply to the
+    /// note with serial number `request_serial_num`, `offset` being [`SENDER_PAYOUT_OFFSET`] or
+    /// [`SELLER_PAYOUT_OFFSET`].
+    pub fn payout_note(
+        &self,
+        receiver: AccountId,
+        asset: Asset,
+        request_serial_num: Word,
+        offset: u64,
+    ) -> Result<Note, NoteError> {
+        let serial_num = [
+            request_serial_num[0],
+            request_serial_num[1],
+            request_serial_num[2],
+            Felt::new(request_serial_num[3].as_int() + offset),
+        ]
+        .into();
+        let recipient = NoteRecipient::new(
+            serial_num,
+            WellKnownNote::P2ID.script(),
+            NoteInputs::new(vec![receiver.suffix(), receiver.prefix().as_felt()])?,
+        );
+        let metadata = NoteMetadata::new(
+            self.id,
+            NoteType::Public,
+            NoteTag::from_account_id(receiver),
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )?;
+        Ok(Note::new(
+            NoteAssets::new(vec![asset])?,
+            metadata,
+            recipient,
+        ))
+    }
+}
+
+/// Reads the listing of `nft` from the state of the marketplace `account`.
+pub fn listing(
+    account: &Account,
+    nft: NonFungibleAsset,
+) -> Result<Option<Listing>, Box<dyn Error>> {
+    Listing::from_word(
+        account
+            .storage()
+            .get_map_item(LISTINGS_SLOT, Word::from(nft))?,
+    )
+}
+
+/// Deploys a public marketplace in which prices are paid in tokens of `currency`.
+pub async fn create_marketplace(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    currency: AccountId,
+) -> Result<Account, Box<dyn Error>> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value([ZERO, ZERO, currency.suffix(), currency.prefix().as_felt()].into()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the marketplace contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `sender` to the marketplace `marketplace_id`.
+pub fn marketplace_note(
+    sender: AccountId,
+    marketplace_id: AccountId,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(marketplace_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+/// Returns the `[NFT, tag]` inputs of the buy and cancel notes of `sender`.
+fn nft_inputs(nft: NonFungibleAsset, sender: AccountId) -> Vec<Felt> {
+    let mut inputs = Word::from(nft).to_vec();
+    inputs.push(NoteTag::from_account_id(sender).into());
+    inputs
+}
+
+/// Has `sender` create `note` for the marketplace.
+pub async fn send_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    note: &Note,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    Ok(client.submit_with_prover(sender, request).await?)
+}
+
+/// Has the marketplace consume `note`, creating `replies`.
+///
+/// The marketplace is a `NoAuth` account, so any client tracking it can execute its
+/// transactions.
+pub async fn consume_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    marketplace: &Marketplace,
+    note: &Note,
+    replies: Vec<Note>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note.clone(), None)])
+        .expected_output_recipients(
+            replies
+                .iter()
+                .map(|reply| reply.recipient().clone())
+                .collect(),
+        )
+        .build()?;
+    Ok(client.submit_with_prover(marketplace.id, request).await?)
+}
+
+/// Has `seller` list `nft` for `price`. `script` is the compiled [`LIST_NOTE_PATH`].
+pub async fn list(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    seller: AccountId,
+    marketplace: &Marketplace,
+    nft: NonFungibleAsset,
+    price: u64,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let inputs = vec![Felt::new(price), NoteTag::from_account_id(seller).into()];
+    let note = marketplace_note(
+        seller,
+        marketplace.id,
+        inputs,
+        vec![nft.into()],
+        script,
+        serial_num,
+    )?;
+    send_note(client, seller, &note).await?;
+    consume_note(client, marketplace, &note, vec![]).await
+}
+
+/// Has `buyer` pay the price of `listing` for `nft`. `script` is the compiled
+/// [`BUY_NOTE_PATH`].
+pub async fn buy(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    buyer: AccountId,
+    marketplace: &Marketplace,
+    nft: NonFungibleAsset,
+    listing: &Listing,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let payment = marketplace.price(listing.price)?;
+    let note = marketplace_note(
+        buyer,
+        marketplace.id,
+        nft_inputs(nft, buyer),
+        vec![payment.into()],
+        script,
+        serial_num,
+    )?;
+    let replies = vec![
+        marketplace.payout_note(buyer, nft.into(), serial_num, SENDER_PAYOUT_OFFSET)?,
+        marketplace.payout_note(
+            listing.seller,
+            payment.into(),
+            serial_num,
+            SELLER_PAYOUT_OFFSET,
+        )?,
+    ];
+    send_note(client, buyer, &note).await?;
+    consume_note(client, marketplace, &note, replies).await
+}
+
+/// Has `seller` take `nft` back from the marketplace. `script` is the compiled
+/// [`CANCEL_NOTE_PATH`].
+pub async fn cancel(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    seller: AccountId,
+    marketplace: &Marketplace,
+    nft: NonFungibleAsset,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = marketplace_note(
+        seller,
+        marketplace.id,
+        nft_inputs(nft, seller),
+        vec![],
+        script,
+        serial_num,
+    )?;
+    let reply = marketplace.payout_note(seller, nft.into(), serial_num, SENDER_PAYOUT_OFFSET)?;
+    send_note(client, seller, &note).await?;
+    consume_note(client, marketplace, &note, vec![reply]).await
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn marketplace() -> Marketplace {
+        Marketplace {
+            id: account(1, AccountType::RegularAccountImmutableCode),
+            currency: account(2, AccountType::FungibleFaucet),
+        }
+    }
+
+    #[test]
+    fn an_empty_map_value_is_no_listing() {
+        assert_eq!(Listing::from_word(Word::default()).unwrap(), None);
+    }
+
+    #[test]
+    fn listings_round_trip_through_their_storage_word() {
+        let seller = account(3, AccountType::RegularAccountUpdatableCode);
+        let listing = Listing {
+            seller,
+            price: 250,
+            seller_tag: NoteTag::from_account_id(seller),
+        };
+
+        assert_eq!(
+            Listing::from_word(listing.to_word()).unwrap(),
+            Some(listing)
+        );
+    }
+
+    #[test]
+    fn the_two_payouts_of_a_sale_are_distinct_notes() {
+        let marketplace = marketplace();
+        let buyer = account(4, AccountType::RegularAccountUpdatableCode);
+        let seller = account(3, AccountType::RegularAccountUpdatableCode);
+        let payment: Asset = marketplace.price(250).unwrap().into();
+        let serial_num: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)].into();
+
+        let to_buyer = marketplace
+            .payout_note(buyer, payment, serial_num, SENDER_PAYOUT_OFFSET)
+            .unwrap();
+        let to_seller = marketplace
+            .payout_note(seller, payment, serial_num, SELLER_PAYOUT_OFFSET)
+            .unwrap();
+
+        assert_eq!(to_buyer.serial_num()[3], Felt::new(5));
+        assert_eq!(to_seller.serial_num()[3], Felt::new(6));
+        assert_ne!(to_buyer.id(), to_seller.id());
+    }
+}
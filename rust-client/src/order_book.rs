@@ -0,0 +1,297 @@
+//! An order book of limit orders posted as standard SWAP notes.
+//!
+//! A SWAP note offers the asset it carries for a requested asset, at the price fixed by the two
+//! amounts. Every public SWAP note for a pair and note type shares the same tag, so a taker who
+//! tracks that tag receives each new order of the pair on its next sync. [`OrderBook`] sorts the
+//! discovered orders from the cheapest to the most expensive and picks the ones a budget covers,
+//! which [`fill`] then consumes in a single transaction.
+
+use std::{cmp::Ordering, error::Error};
+
+use miden_client::{
+    account::AccountId,
+    asset::{Asset, FungibleAsset},
+    keystore::FilesystemKeyStore,
+    note::{Note, NoteTag, NoteType},
+    store::NoteFilter,
+    transaction::{TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::note::{utils::build_swap_tag, WellKnownNote};
+use miden_objects::NoteError;
+use rand::rngs::StdRng;
+
+use crate::cli::SubmitWithProver;
+
+/// A SWAP note offering `offered` for `requested`, filled in full.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Order {
+    pub note: Note,
+    pub offered: FungibleAsset,
+    pub requested: FungibleAsset,
+}
+
+impl Order {
+    /// Decodes a SWAP note trading two fungible assets, or returns `None` for any other note.
+    pub fn from_note(note: Note) -> Option<Self> {
+        if note.recipient().script().root() != WellKnownNote::SWAP.script_root() {
+            return None;
+        }
+        // The requested asset follows the recipient of the payback note in the inputs
+        let inputs = note.inputs().values();
+        let requested: [Felt; 4] = inputs.get(4..8)?.try_into().ok()?;
+        let Asset::Fungible(requested) = Asset::try_from(Word::from(requested)).ok()? else {
+            return None;
+        };
+        let assets: Vec<Asset> = note.assets().iter().copied().collect();
+        let [Asset::Fungible(offered)] = assets[..] else {
+            return None;
+        };
+
+        Some(Self {
+            note,
+            offered,
+            requested,
+        })
+    }
+
+    /// Returns the account which posted the order.
+    pub fn maker(&self) -> AccountId {
+        self.note.metadata().sender()
+    }
+
+    /// Returns the price of one offered token in requested tokens.
+    pub fn price(&self) -> f64 {
+        self.requested.amount() as f64 / self.offered.amount() as f64
+    }
+
+    /// Compares the prices of two orders exactly, without rounding them.
+    fn cmp_price(&self, other: &Self) -> Ordering {
+        let own = u128::from(self.requested.amount()) * u128::from(other.offered.amount());
+        let others = u128::from(other.requested.amount()) * u128::from(self.offered.amount());
+        own.cmp(&others)
+    }
+}
+
+/// Orders selling one asset for another, from the best price to the worst.
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+    orders: Vec<Order>,
+}
+
+impl OrderBook {
+    /// Builds the book of the orders among `notes` which sell `offered_faucet` tokens for
+    /// `requested_faucet` tokens. Other notes are ignored.
+    pub fn new(
+        notes: impl IntoIterator<Item = Note>,
+        offered_faucet: AccountId,
+        requested_faucet: AccountId,
+    ) -> Self {
+        let mut orders: Vec<Order> = notes
+            .into_iter()
+            .filter_map(Order::from_note)
+            .filter(|order| {
+                order.offered.faucet_id() == offered_faucet
+                    && order.requested.faucet_id() == requested_faucet
+            })
+            .collect();
+        // Ties go to the order offering more, then to the note id to keep the order stable
+        orders.sort_by(|a, b| {
+            a.cmp_price(b)
+                .then(b.offered.amount().cmp(&a.offered.amount()))
+                .then(a.note.id().cmp(&b.note.id()))
+        });
+        Self { orders }
+    }
+
+    pub fn orders(&self) -> &[Order] {
+        &self.orders
+    }
+
+    /// Returns the cheapest order, if any.
+    pub fn best(&self) -> Option<&Order> {
+        self.orders.first()
+    }
+
+    /// Returns the orders a taker with `budget` requested tokens fills, starting from the best
+    /// price. SWAP notes are filled in full, so the walk stops at the first order the rest of
+    /// the budget does not cover.
+    pub fn take(&self, budget: u64) -> Vec<&Order> {
+        let mut left = budget;
+        self.orders
+            .iter()
+            .take_while(|order| match left.checked_sub(order.requested.amount()) {
+                Some(rest) => {
+                    left = rest;
+                    true
+                }
+                None => false,
+            })
+            .collect()
+    }
+}
+
+/// Returns the tag shared by the public SWAP notes selling `offered_faucet` tokens for
+/// `requested_faucet` tokens.
+pub fn swap_tag(
+    offered_faucet: AccountId,
+    requested_faucet: AccountId,
+) -> Result<NoteTag, NoteError> {
+    // The tag only depends on the faucets of the assets, not on their amounts
+    let offered = FungibleAsset::new(offered_faucet, 1)?.into();
+    let requested = FungibleAsset::new(requested_faucet, 1)?.into();
+    build_swap_tag(NoteType::Public, &offered, &requested)
+}
+
+/// Has the client follow the SWAP tag of the pair, syncs, and returns the book of the committed
+/// orders it knows of.
+pub async fn discover(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    offered_faucet: AccountId,
+    requested_faucet: AccountId,
+) -> Result<OrderBook, Box<dyn Error>> {
+    let tag = swap_tag(offered_faucet, requested_faucet)?;
+    client.add_note_tag(tag).await?;
+    client.sync_state().await?;
+
+    let notes = client
+        .get_input_notes(NoteFilter::Committed)
+        .await?
+        .into_iter()
+        .filter(|record| {
+            record
+                .metadata()
+                .is_some_and(|metadata| metadata.tag() == tag)
+        })
+        .filter_map(|record| Note::try_from(record).ok());
+    Ok(OrderBook::new(notes, offered_faucet, requested_faucet))
+}
+
+/// Fills all of `orders` from the vault of `taker`, in one transaction.
+pub async fn fill(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    taker: AccountId,
+    orders: &[&Order],
+) -> Result<TransactionId, Box<dyn Error>> {
+    let note_ids = orders.iter().map(|order| order.note.id()).collect();
+    let request = TransactionRequestBuilder::new().build_consume_notes(note_ids)?;
+    Ok(client.submit_with_prover(taker, request).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_client::note::create_swap_note;
+    use miden_objects::{
+        account::{AccountIdVersion, AccountStorageMode, AccountType},
+        crypto::rand::RpoRandomCoin,
+    };
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn eth() -> AccountId {
+        account(1, AccountType::FungibleFaucet)
+    }
+
+    fn usd() -> AccountId {
+        account(2, AccountType::FungibleFaucet)
+    }
+
+    /// Builds a SWAP note selling `offered` of `offered_faucet` for `requested` of
+    /// `requested_faucet`.
+    fn swap(
+        offered_faucet: AccountId,
+        offered: u64,
+        requested_faucet: AccountId,
+        requested: u64,
+    ) -> Note {
+        let (note, _) = create_swap_note(
+            account(3, AccountType::RegularAccountUpdatableCode),
+            FungibleAsset::new(offered_faucet, offered).unwrap().into(),
+            FungibleAsset::new(requested_faucet, requested)
+                .unwrap()
+                .into(),
+            NoteType::Public,
+            Felt::new(0),
+            NoteType::Public,
+            Felt::new(0),
+            &mut RpoRandomCoin::new(Word::from([Felt::new(offered); 4])),
+        )
+        .unwrap();
+        note
+    }
+
+    fn requested_amounts(orders: &[&Order]) -> Vec<u64> {
+        orders
+            .iter()
+            .map(|order| order.requested.amount())
+            .collect()
+    }
+
+    #[test]
+    fn orders_are_sorted_by_exact_price() {
+        // Prices are compared as fractions: 301 / 3 is below 101 / 1, which is below 210 / 2
+        let book = OrderBook::new(
+            [
+                swap(eth(), 2, usd(), 210),
+                swap(eth(), 1, usd(), 101),
+                swap(eth(), 3, usd(), 301),
+            ],
+            eth(),
+            usd(),
+        );
+        let prices: Vec<(u64, u64)> = book
+            .orders()
+            .iter()
+            .map(|order| (order.offered.amount(), order.requested.amount()))
+            .collect();
+
+        assert_eq!(prices, vec![(3, 301), (1, 101), (2, 210)]);
+        assert_eq!(book.best().unwrap().requested.amount(), 301);
+    }
+
+    #[test]
+    fn the_book_only_keeps_swaps_of_its_pair() {
+        let book = OrderBook::new(
+            [swap(eth(), 1, usd(), 100), swap(usd(), 100, eth(), 1)],
+            eth(),
+            usd(),
+        );
+
+        assert_eq!(book.orders().len(), 1);
+        assert_eq!(book.orders()[0].offered.faucet_id(), eth());
+        assert_eq!(
+            book.orders()[0].maker(),
+            account(3, AccountType::RegularAccountUpdatableCode)
+        );
+    }
+
+    #[test]
+    fn taking_stops_at_the_first_order_the_budget_does_not_cover() {
+        let book = OrderBook::new(
+            [
+                swap(eth(), 10, usd(), 1_000),
+                swap(eth(), 10, usd(), 1_050),
+                swap(eth(), 10, usd(), 1_100),
+                swap(eth(), 1, usd(), 120),
+            ],
+            eth(),
+            usd(),
+        );
+
+        assert_eq!(requested_amounts(&book.take(2_200)), vec![1_000, 1_050]);
+        assert_eq!(requested_amounts(&book.take(999)), Vec::<u64>::new());
+        assert_eq!(
+            requested_amounts(&book.take(10_000)),
+            vec![1_000, 1_050, 1_100, 120]
+        );
+    }
+}
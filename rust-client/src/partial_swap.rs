@@ -0,0 +1,260 @@
+//! Off-chain side of the partially fillable swap note (`masm/notes/swapp_note.masm`).
+//!
+//! A [`PartialSwap`] offers the asset it carries at a fixed price, in any quantity. Each fill
+//! pays the maker with a P2ID note and re-creates the swap with what is left of the offer, so
+//! a large order can be taken by several counterparties. Both notes are predictable from the
+//! swap and the fill, which [`PartialSwap::fill`] computes the same way the note script does.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::AccountId,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteExecutionMode, NoteInputs, NoteMetadata,
+        NoteRecipient, NoteScript, NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::note::WellKnownNote;
+use miden_objects::{asset::FungibleAsset, NoteError, ZERO};
+use rand::rngs::StdRng;
+
+use crate::{cli::SubmitWithProver, helpers::reply_note};
+
+/// Location of the partially fillable swap note, relative to `rust-client`.
+pub const NOTE_PATH: &str = "../masm/notes/swapp_note.masm";
+
+/// Use case of the public tag under which partially fillable swaps are posted.
+pub const SWAPP_USE_CASE: u16 = 2;
+
+/// Compiles the partially fillable swap note script.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let note_code = fs::read_to_string(NOTE_PATH)?;
+    Ok(client.script_builder().compile_note_script(&note_code)?)
+}
+
+/// A partially fillable swap: `offered` for `requested`, or any fraction of it at that price.
+#[derive(Debug, Clone)]
+pub struct PartialSwap {
+    pub note: Note,
+    pub maker: AccountId,
+    pub offered: FungibleAsset,
+    pub requested: FungibleAsset,
+}
+
+/// What a fill of a [`PartialSwap`] does.
+#[derive(Debug, Clone)]
+pub struct Fill {
+    /// Part of the offered asset the taker receives.
+    pub bought: FungibleAsset,
+    /// P2ID note paying the maker.
+    pub payment: Note,
+    /// The swap re-created with the rest of the offer, unless the fill took all of it.
+    pub leftover: Option<PartialSwap>,
+}
+
+impl PartialSwap {
+    /// Builds the swap posted by `sender` on behalf of `maker`. `script` is the compiled
+    /// [`NOTE_PATH`].
+    ///
+    /// The maker posts the first swap of an offer itself. Leftover swaps are sent by the taker
+    /// of the previous fill, but still pay and belong to the maker.
+    pub fn new(
+        sender: AccountId,
+        maker: AccountId,
+        offered: FungibleAsset,
+        requested: FungibleAsset,
+        script: NoteScript,
+        serial_num: Word,
+    ) -> Result<Self, NoteError> {
+        let tag = NoteTag::for_public_use_case(SWAPP_USE_CASE, 0, NoteExecutionMode::Local)?;
+        let mut inputs = WellKnownNote::P2ID.script_root().to_vec();
+        inputs.extend([
+            Felt::new(requested.amount()),
+            requested.faucet_id().suffix(),
+            requested.faucet_id().prefix().as_felt(),
+            NoteTag::from_account_id(maker).into(),
+            maker.suffix(),
+            maker.prefix().as_felt(),
+            tag.into(),
+        ]);
+        let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+        let metadata = NoteMetadata::new(
+            sender,
+            NoteType::Public,
+            tag,
+            NoteExecutionHint::always(),
+            Felt::new(0),
+        )?;
+        let note = Note::new(NoteAssets::new(vec![offered.into()])?, metadata, recipient);
+
+        Ok(Self {
+            note,
+            maker,
+            offered,
+            requested,
+        })
+    }
+
+    /// Returns the notes created when `taker` pays `amount` of the requested asset.
+    ///
+    /// Fails if `amount` is zero, above the requested amount, or too small to buy anything: the
+    /// note script rounds the part bought down, so such a fill would pay for nothing.
+    pub fn fill(&self, taker: AccountId, amount: u64) -> Result<Fill, Box<dyn Error>> {
+        if amount == 0 || amount > self.requested.amount() {
+            return Err(format!(
+                "fill must be between 1 and {}, got {amount}",
+                self.requested.amount()
+            )
+            .into());
+        }
+        let bought = u128::from(amount) * u128::from(self.offered.amount())
+            / u128::from(self.requested.amount());
+        let bought = u64::try_from(bought)?;
+        if bought == 0 {
+            return Err(format!("a fill of {amount} buys nothing").into());
+        }
+
+        let serial_num = self.note.serial_num();
+        let payment = reply_note(
+            taker,
+            self.maker,
+            FungibleAsset::new(self.requested.faucet_id(), amount)?,
+            serial_num,
+        )?;
+
+        let leftover = if amount < self.requested.amount() {
+            let leftover_serial_num = [
+                serial_num[0],
+                serial_num[1],
+                serial_num[2],
+                Felt::new(serial_num[3].as_int() + 2),
+            ]
+            .into();
+            Some(Self::new(
+                taker,
+                self.maker,
+                FungibleAsset::new(self.offered.faucet_id(), self.offered.amount() - bought)?,
+                FungibleAsset::new(self.requested.faucet_id(), self.requested.amount() - amount)?,
+                self.note.script().clone(),
+                leftover_serial_num,
+            )?)
+        } else {
+            None
+        };
+
+        Ok(Fill {
+            bought: FungibleAsset::new(self.offered.faucet_id(), bought)?,
+            payment,
+            leftover,
+        })
+    }
+}
+
+/// Posts `swap` from the vault of its maker.
+pub async fn post(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    swap: &PartialSwap,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(swap.note.clone())])
+        .build()?;
+    Ok(client.submit_with_prover(swap.maker, request).await?)
+}
+
+/// Has `taker` pay `amount` of the requested asset of `swap`, passed to the note script as
+/// note args. Returns the transaction along with the notes it creates.
+pub async fn fill(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    taker: AccountId,
+    swap: &PartialSwap,
+    amount: u64,
+) -> Result<(TransactionId, Fill), Box<dyn Error>> {
+    let fill = swap.fill(taker, amount)?;
+    let note_args: Word = [ZERO, ZERO, ZERO, Felt::new(amount)].into();
+
+    let mut recipients = vec![fill.payment.recipient().clone()];
+    recipients.extend(
+        fill.leftover
+            .iter()
+            .map(|leftover| leftover.note.recipient().clone()),
+    );
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(swap.note.clone(), Some(note_args))])
+        .expected_output_recipients(recipients)
+        .build()?;
+    let tx_id = client.submit_with_prover(taker, request).await?;
+
+    Ok((tx_id, fill))
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::{AccountIdVersion, AccountStorageMode, AccountType};
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    /// 100 tokens of one faucet for 200 of another.
+    fn swap() -> PartialSwap {
+        let maker = account(1, AccountType::RegularAccountUpdatableCode);
+        PartialSwap::new(
+            maker,
+            maker,
+            FungibleAsset::new(account(2, AccountType::FungibleFaucet), 100).unwrap(),
+            FungibleAsset::new(account(3, AccountType::FungibleFaucet), 200).unwrap(),
+            // The script does not matter off-chain
+            WellKnownNote::P2ID.script(),
+            Word::default(),
+        )
+        .unwrap()
+    }
+
+    fn taker() -> AccountId {
+        account(4, AccountType::RegularAccountUpdatableCode)
+    }
+
+    #[test]
+    fn partial_fill_leaves_the_rest_at_the_same_price() {
+        let swap = swap();
+        let fill = swap.fill(taker(), 50).unwrap();
+        let leftover = fill.leftover.unwrap();
+
+        assert_eq!(fill.bought.amount(), 25);
+        assert_eq!(leftover.offered.amount(), 75);
+        assert_eq!(leftover.requested.amount(), 150);
+        assert_eq!(leftover.maker, swap.maker);
+        assert_eq!(leftover.note.metadata().sender(), taker());
+        assert_ne!(leftover.note.serial_num(), fill.payment.serial_num());
+    }
+
+    #[test]
+    fn full_fill_leaves_nothing() {
+        let fill = swap().fill(taker(), 200).unwrap();
+
+        assert_eq!(fill.bought.amount(), 100);
+        assert!(fill.leftover.is_none());
+    }
+
+    #[test]
+    fn fill_must_buy_something_within_the_offer() {
+        let swap = swap();
+
+        assert!(swap.fill(taker(), 0).is_err());
+        assert!(swap.fill(taker(), 201).is_err());
+        // 1 * 100 / 200 rounds down to nothing
+        assert!(swap.fill(taker(), 1).is_err());
+    }
+}
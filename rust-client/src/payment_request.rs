@@ -0,0 +1,272 @@
+//! Payment requests a merchant hands out to be paid with a P2ID note.
+//!
+//! A [`PaymentRequest`] names the account to pay, the asset it expects and a reference chosen
+//! by the merchant. The payer sends a P2ID note carrying the asset, with the reference as the
+//! `aux` value of the note metadata, so that the merchant can tell which request an incoming
+//! note pays. Requests are shared as URIs:
+//!
+//! ```text
+//! miden:<account>?faucet=<faucet>&amount=<amount>&ref=<reference>
+//! ```
+
+use std::{error::Error, fmt, str::FromStr};
+
+use miden_client::{
+    account::AccountId,
+    address::NetworkId,
+    asset::{Asset, FungibleAsset},
+    crypto::FeltRng,
+    note::{create_p2id_note, Note, NoteType},
+    Felt,
+};
+use miden_lib::note::WellKnownNote;
+use miden_objects::NoteError;
+
+use crate::address::{address_network, parse_account_address, AddressError};
+
+/// Scheme of payment request URIs.
+pub const SCHEME: &str = "miden:";
+
+/// Why a payment request URI could not be parsed.
+#[derive(Debug)]
+pub enum PaymentRequestError {
+    /// The URI does not start with `miden:`.
+    Scheme,
+    /// A query parameter is missing.
+    Missing(&'static str),
+    /// A query parameter has an invalid value.
+    Invalid {
+        field: &'static str,
+        reason: String,
+    },
+    Address(AddressError),
+}
+
+impl fmt::Display for PaymentRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scheme => write!(f, "payment requests start with `{SCHEME}`"),
+            Self::Missing(field) => write!(f, "payment request has no `{field}`"),
+            Self::Invalid { field, reason } => write!(f, "invalid `{field}`: {reason}"),
+            Self::Address(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl Error for PaymentRequestError {}
+
+impl From<AddressError> for PaymentRequestError {
+    fn from(err: AddressError) -> Self {
+        Self::Address(err)
+    }
+}
+
+/// A request to pay `asset` to `recipient`, identified by `reference`. The URI gives both
+/// accounts as addresses of `network`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentRequest {
+    pub network: NetworkId,
+    pub recipient: AccountId,
+    pub asset: FungibleAsset,
+    pub reference: u32,
+}
+
+impl PaymentRequest {
+    pub fn new(
+        network: NetworkId,
+        recipient: AccountId,
+        asset: FungibleAsset,
+        reference: u32,
+    ) -> Self {
+        Self {
+            network,
+            recipient,
+            asset,
+            reference,
+        }
+    }
+
+    /// Builds the P2ID note paying the request from `sender`.
+    pub fn payment_note(
+        &self,
+        sender: AccountId,
+        rng: &mut impl FeltRng,
+    ) -> Result<Note, NoteError> {
+        create_p2id_note(
+            sender,
+            self.recipient,
+            vec![self.asset.into()],
+            NoteType::Public,
+            Felt::from(self.reference),
+            rng,
+        )
+    }
+
+    /// Returns whether `note` pays the request: a P2ID note to the recipient, carrying the
+    /// reference and at least the requested amount.
+    pub fn is_paid_by(&self, note: &Note) -> bool {
+        let is_p2id_to_recipient = note.script().root() == WellKnownNote::P2ID.script_root()
+            && note.inputs().values()
+                == [self.recipient.suffix(), self.recipient.prefix().as_felt()];
+        let carries_reference = note.metadata().aux() == Felt::from(self.reference);
+        let pays_enough = note.assets().iter().any(|asset| match asset {
+            Asset::Fungible(paid) => {
+                paid.faucet_id() == self.asset.faucet_id() && paid.amount() >= self.asset.amount()
+            }
+            Asset::NonFungible(_) => false,
+        });
+
+        is_p2id_to_recipient && carries_reference && pays_enough
+    }
+}
+
+impl fmt::Display for PaymentRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{SCHEME}{}?faucet={}&amount={}&ref={}",
+            self.recipient.to_bech32(self.network.clone()),
+            self.asset.faucet_id().to_bech32(self.network.clone()),
+            self.asset.amount(),
+            self.reference
+        )
+    }
+}
+
+impl FromStr for PaymentRequest {
+    type Err = PaymentRequestError;
+
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        let rest = uri
+            .strip_prefix(SCHEME)
+            .ok_or(PaymentRequestError::Scheme)?;
+        let (recipient, query) = rest.split_once('?').unwrap_or((rest, ""));
+        // The recipient sets the network, and the faucet must be on the same one
+        let network = address_network(recipient)?;
+        let recipient = parse_account_address(recipient, network.clone())?;
+
+        let param = |field: &'static str| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix(field)?.strip_prefix('='))
+                .ok_or(PaymentRequestError::Missing(field))
+        };
+        let faucet_id = parse_account_address(param("faucet")?, network.clone())?;
+        let amount: u64 = param("amount")?
+            .parse()
+            .map_err(|err| invalid("amount", err))?;
+        let reference: u32 = param("ref")?.parse().map_err(|err| invalid("ref", err))?;
+        let asset = FungibleAsset::new(faucet_id, amount).map_err(|err| invalid("amount", err))?;
+
+        Ok(Self::new(network, recipient, asset, reference))
+    }
+}
+
+fn invalid(field: &'static str, err: impl fmt::Display) -> PaymentRequestError {
+    PaymentRequestError::Invalid {
+        field,
+        reason: err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{
+        account::{AccountIdVersion, AccountStorageMode, AccountType},
+        crypto::rand::RpoRandomCoin,
+        Word,
+    };
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn request(amount: u64) -> PaymentRequest {
+        let merchant = account(1, AccountType::RegularAccountUpdatableCode);
+        let faucet_id = account(2, AccountType::FungibleFaucet);
+        PaymentRequest::new(
+            NetworkId::Testnet,
+            merchant,
+            FungibleAsset::new(faucet_id, amount).unwrap(),
+            7,
+        )
+    }
+
+    fn with_amount(request: &PaymentRequest, amount: u64) -> PaymentRequest {
+        PaymentRequest {
+            asset: FungibleAsset::new(request.asset.faucet_id(), amount).unwrap(),
+            ..request.clone()
+        }
+    }
+
+    fn payer() -> AccountId {
+        account(3, AccountType::RegularAccountUpdatableCode)
+    }
+
+    #[test]
+    fn uri_round_trips() {
+        let request = request(1_250);
+        let parsed: PaymentRequest = request.to_string().parse().unwrap();
+
+        assert_eq!(parsed, request);
+    }
+
+    #[test]
+    fn uri_needs_every_field() {
+        let uri = request(1_250).to_string();
+        let without_ref = uri.split("&ref=").next().unwrap();
+
+        assert!(matches!(
+            without_ref.parse::<PaymentRequest>(),
+            Err(PaymentRequestError::Missing("ref"))
+        ));
+        assert!(matches!(
+            uri.replacen(SCHEME, "http:", 1).parse::<PaymentRequest>(),
+            Err(PaymentRequestError::Scheme)
+        ));
+    }
+
+    #[test]
+    fn uri_accounts_must_share_a_network() {
+        let request = request(1_250);
+        let faucet_on_mainnet = request.to_string().replace(
+            &request.asset.faucet_id().to_bech32(NetworkId::Testnet),
+            &request.asset.faucet_id().to_bech32(NetworkId::Mainnet),
+        );
+
+        assert!(matches!(
+            faucet_on_mainnet.parse::<PaymentRequest>(),
+            Err(PaymentRequestError::Address(
+                AddressError::WrongNetwork { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn payment_must_carry_the_reference_and_the_amount() {
+        let mut rng = RpoRandomCoin::new(Word::default());
+        let request = request(100);
+
+        let exact = request.payment_note(payer(), &mut rng).unwrap();
+        assert!(request.is_paid_by(&exact));
+
+        let other_order = PaymentRequest {
+            reference: 8,
+            ..request.clone()
+        };
+        let wrong_reference = other_order.payment_note(payer(), &mut rng).unwrap();
+        assert!(!request.is_paid_by(&wrong_reference));
+
+        let underpaid = with_amount(&request, 99)
+            .payment_note(payer(), &mut rng)
+            .unwrap();
+        assert!(!request.is_paid_by(&underpaid));
+    }
+}
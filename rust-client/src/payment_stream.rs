@@ -0,0 +1,395 @@
+//! Off-chain side of the payment stream contract (`masm/accounts/payment_stream.masm`).
+//!
+//! A payment stream pays an employee a salary per block. The employer deploys the stream with a
+//! rate and a duration and funds it with a `payment_stream_deposit_note`. From the block the
+//! stream was deployed at until its end, `rate` tokens accrue with every block. Whenever it
+//! likes, the employee pulls everything accrued since its last withdrawal with a
+//! `payment_stream_withdraw_note`; the contract keeps track of what was withdrawn so far. The
+//! employer can cancel the stream with a `payment_stream_cancel_note`: the stream ends at the
+//! current block, and what would have accrued afterwards is refunded. Payouts and refunds are
+//! P2ID notes whose serial number is the serial number of the requesting note plus one.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, note::WellKnownNote, transaction::TransactionKernel};
+use miden_objects::{
+    account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    block::BlockNumber,
+    NoteError, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::{create_library, reply_note},
+};
+
+/// Path under which the payment stream contract is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::payment_stream";
+/// Location of the payment stream contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/payment_stream.masm";
+/// Location of the note funding a stream, relative to `rust-client`.
+pub const DEPOSIT_NOTE_PATH: &str = "../masm/notes/payment_stream_deposit_note.masm";
+/// Location of the note withdrawing the accrued tokens, relative to `rust-client`.
+pub const WITHDRAW_NOTE_PATH: &str = "../masm/notes/payment_stream_withdraw_note.masm";
+/// Location of the note cancelling a stream, relative to `rust-client`.
+pub const CANCEL_NOTE_PATH: &str = "../masm/notes/payment_stream_cancel_note.masm";
+
+const EMPLOYER_SLOT: u8 = 0;
+const EMPLOYEE_SLOT: u8 = 1;
+const CURRENCY_SLOT: u8 = 2;
+const START_SLOT: u8 = 3;
+const END_SLOT: u8 = 4;
+const RATE_SLOT: u8 = 5;
+const WITHDRAWN_SLOT: u8 = 6;
+
+/// Terms and state of a deployed payment stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaymentStream {
+    pub id: AccountId,
+    pub employer: AccountId,
+    pub employee: AccountId,
+    /// Faucet of the streamed tokens.
+    pub currency: AccountId,
+    /// Block from which tokens accrue.
+    pub start: BlockNumber,
+    /// Block from which no more tokens accrue, the block of the cancellation if the stream was
+    /// cancelled.
+    pub end: BlockNumber,
+    /// Tokens accruing per block.
+    pub rate: u64,
+    /// Total withdrawn by the employee so far.
+    pub withdrawn: u64,
+}
+
+impl PaymentStream {
+    /// Decodes the terms and state of a deployed payment stream account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let account_id = |slot| -> Result<AccountId, Box<dyn Error>> {
+            let value = storage.get_item(slot)?;
+            Ok(AccountId::try_from([value[3], value[2]])?)
+        };
+        let value =
+            |slot| -> Result<u64, Box<dyn Error>> { Ok(storage.get_item(slot)?[3].as_int()) };
+
+        Ok(Self {
+            id: account.id(),
+            employer: account_id(EMPLOYER_SLOT)?,
+            employee: account_id(EMPLOYEE_SLOT)?,
+            currency: account_id(CURRENCY_SLOT)?,
+            start: BlockNumber::from(u32::try_from(value(START_SLOT)?)?),
+            end: BlockNumber::from(u32::try_from(value(END_SLOT)?)?),
+            rate: value(RATE_SLOT)?,
+            withdrawn: value(WITHDRAWN_SLOT)?,
+        })
+    }
+
+    /// Returns `block_num` moved into the lifetime of the stream.
+    fn clamp(&self, block_num: BlockNumber) -> u32 {
+        block_num
+            .as_u32()
+            .clamp(self.start.as_u32(), self.end.as_u32())
+    }
+
+    /// Returns the tokens accrued in a transaction executed against `block_num`, withdrawn ones
+    /// included.
+    pub fn accrued_at(&self, block_num: BlockNumber) -> u64 {
+        self.rate * u64::from(self.clamp(block_num) - self.start.as_u32())
+    }
+
+    /// Returns what the employee withdraws in a transaction executed against `block_num`.
+    pub fn owed_at(&self, block_num: BlockNumber) -> u64 {
+        self.accrued_at(block_num).saturating_sub(self.withdrawn)
+    }
+
+    /// Returns what the employer gets back by cancelling the stream in a transaction executed
+    /// against `block_num`, or `None` if the stream ended by then.
+    pub fn refund_at(&self, block_num: BlockNumber) -> Option<u64> {
+        (block_num < self.end)
+            .then(|| self.rate * u64::from(self.end.as_u32() - self.clamp(block_num)))
+    }
+
+    /// Returns the P2ID note by which the stream pays `amount` tokens to `receiver` in reply to
+    /// the note with serial number `serial_num`.
+    pub fn payment(
+        &self,
+        receiver: AccountId,
+        amount: u64,
+        serial_num: Word,
+    ) -> Result<Note, Box<dyn Error>> {
+        let tokens = FungibleAsset::new(self.currency, amount)?;
+        Ok(reply_note(self.id, receiver, tokens, serial_num)?)
+    }
+}
+
+/// Deploys a public stream by which `employer` pays `employee` `rate` tokens of `currency` per
+/// block for `duration` blocks, starting at the current sync height.
+///
+/// The stream is to be funded with `rate * duration` tokens afterwards, with [`deposit`].
+pub async fn create_stream(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    employer: AccountId,
+    employee: AccountId,
+    currency: AccountId,
+    rate: u64,
+    duration: u32,
+) -> Result<Account, Box<dyn Error>> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let start = client.get_sync_height().await?.as_u32();
+    let account_word = |account_id: AccountId| -> Word {
+        [
+            ZERO,
+            ZERO,
+            account_id.suffix(),
+            account_id.prefix().as_felt(),
+        ]
+        .into()
+    };
+    let value_word = |value: u64| -> Word { [ZERO, ZERO, ZERO, Felt::new(value)].into() };
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(account_word(employer)),
+            StorageSlot::Value(account_word(employee)),
+            StorageSlot::Value(account_word(currency)),
+            StorageSlot::Value(value_word(start.into())),
+            StorageSlot::Value(value_word((start + duration).into())),
+            StorageSlot::Value(value_word(rate)),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the payment stream contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `sender` to the payment stream `stream_id`.
+pub fn stream_note(
+    sender: AccountId,
+    stream_id: AccountId,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(stream_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+/// Builds a note by which `sender` asks `stream` for a payout to itself. `script` is the
+/// compiled [`WITHDRAW_NOTE_PATH`] or [`CANCEL_NOTE_PATH`].
+pub fn payout_request(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    stream: &PaymentStream,
+    script: NoteScript,
+) -> Result<Note, NoteError> {
+    let serial_num = client.rng().draw_word();
+    let inputs = vec![NoteTag::from_account_id(sender).into()];
+    stream_note(sender, stream.id, inputs, vec![], script, serial_num)
+}
+
+/// Has `sender` create `note` for the payment stream.
+pub async fn send_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    note: &Note,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    Ok(client.submit_with_prover(sender, request).await?)
+}
+
+/// Has `stream` consume `note`, creating `replies`.
+///
+/// The stream is a `NoAuth` account, so any client tracking it can execute its transactions.
+pub async fn consume_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    stream: &PaymentStream,
+    note: &Note,
+    replies: Vec<Note>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note.clone(), None)])
+        .expected_output_recipients(
+            replies
+                .iter()
+                .map(|reply| reply.recipient().clone())
+                .collect(),
+        )
+        .build()?;
+    Ok(client.submit_with_prover(stream.id, request).await?)
+}
+
+/// Funds `stream` with `amount` tokens from the vault of `funder`. `script` is the compiled
+/// [`DEPOSIT_NOTE_PATH`].
+pub async fn deposit(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    funder: AccountId,
+    stream: &PaymentStream,
+    amount: u64,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let asset = FungibleAsset::new(stream.currency, amount)?;
+    let note = stream_note(
+        funder,
+        stream.id,
+        vec![],
+        vec![asset.into()],
+        script,
+        serial_num,
+    )?;
+    send_note(client, funder, &note).await?;
+    consume_note(client, stream, &note, vec![]).await
+}
+
+/// Has the employee withdraw everything `stream` accrued since the last withdrawal, and returns
+/// the transaction with the amount paid. `script` is the compiled [`WITHDRAW_NOTE_PATH`].
+///
+/// Fails without sending anything if nothing is owed at the current sync height.
+pub async fn withdraw(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    stream: &PaymentStream,
+    script: NoteScript,
+) -> Result<(TransactionId, u64), Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    let owed = stream.owed_at(block_num);
+    if owed == 0 {
+        return Err(
+            format!("nothing accrued since the last withdrawal at block {block_num}").into(),
+        );
+    }
+
+    let note = payout_request(client, stream.employee, stream, script)?;
+    let payout = stream.payment(stream.employee, owed, note.serial_num())?;
+    send_note(client, stream.employee, &note).await?;
+    let tx_id = consume_note(client, stream, &note, vec![payout]).await?;
+    Ok((tx_id, owed))
+}
+
+/// Has the employer cancel `stream`, and returns the transaction with the amount refunded.
+/// `script` is the compiled [`CANCEL_NOTE_PATH`].
+///
+/// Fails without sending anything if the stream ended at the current sync height.
+pub async fn cancel(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    stream: &PaymentStream,
+    script: NoteScript,
+) -> Result<(TransactionId, u64), Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    let refund = stream
+        .refund_at(block_num)
+        .ok_or_else(|| format!("the stream ended by block {block_num}"))?;
+
+    let note = payout_request(client, stream.employer, stream, script)?;
+    let payout = stream.payment(stream.employer, refund, note.serial_num())?;
+    send_note(client, stream.employer, &note).await?;
+    let tx_id = consume_note(client, stream, &note, vec![payout]).await?;
+    Ok((tx_id, refund))
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn stream(withdrawn: u64) -> PaymentStream {
+        PaymentStream {
+            id: account(1, AccountType::RegularAccountImmutableCode),
+            employer: account(2, AccountType::RegularAccountUpdatableCode),
+            employee: account(3, AccountType::RegularAccountUpdatableCode),
+            currency: account(4, AccountType::FungibleFaucet),
+            start: BlockNumber::from(100),
+            end: BlockNumber::from(160),
+            rate: 5,
+            withdrawn,
+        }
+    }
+
+    #[test]
+    fn tokens_accrue_per_block_within_the_lifetime_of_the_stream() {
+        let stream = stream(0);
+
+        assert_eq!(stream.accrued_at(90.into()), 0);
+        assert_eq!(stream.accrued_at(110.into()), 50);
+        assert_eq!(stream.accrued_at(160.into()), 300);
+        assert_eq!(stream.accrued_at(500.into()), 300);
+    }
+
+    #[test]
+    fn withdrawals_only_pay_what_accrued_since_the_last_one() {
+        let stream = stream(50);
+
+        assert_eq!(stream.owed_at(110.into()), 0);
+        assert_eq!(stream.owed_at(120.into()), 50);
+        assert_eq!(stream.owed_at(200.into()), 250);
+    }
+
+    #[test]
+    fn cancelling_refunds_what_has_yet_to_accrue() {
+        let stream = stream(0);
+
+        assert_eq!(stream.refund_at(90.into()), Some(300));
+        assert_eq!(stream.refund_at(130.into()), Some(150));
+        assert_eq!(stream.refund_at(159.into()), Some(5));
+        assert_eq!(stream.refund_at(160.into()), None);
+    }
+}
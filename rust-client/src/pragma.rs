@@ -0,0 +1,91 @@
+//! The Pragma oracle deployed on testnet.
+//!
+//! Pragma aggregates the prices published by several publisher accounts, and its `get_median`
+//! procedure reads each of them through a nested FPI call. A transaction calling it must
+//! therefore load the oracle and every publisher as foreign accounts, which
+//! [`get_oracle_foreign_accounts`] collects.
+
+use miden_client::{
+    account::AccountId,
+    keystore::FilesystemKeyStore,
+    rpc::domain::account::{AccountStorageRequirements, StorageMapKey},
+    transaction::ForeignAccount,
+    Client, ClientError,
+};
+use miden_objects::{Felt, Word, ZERO};
+use rand::rngs::StdRng;
+
+/// Address of the Pragma oracle on testnet.
+pub const TESTNET_ORACLE: &str = "mtst1qq0zffxzdykm7qqqqdt24cc2du5ghx99";
+/// Procedure root of `get_median` in the oracle.
+pub const GET_MEDIAN_ROOT: &str =
+    "0xb86237a8c9cd35acfef457e47282cc4da43df676df410c988eab93095d8fb3b9";
+/// Id of the BTC/USD pair, the only pair published on testnet.
+pub const BTC_USD: u64 = 120195681;
+/// Decimals of the prices returned by `get_median`.
+pub const PRICE_DECIMALS: u32 = 6;
+
+/// Returns the id of the Pragma oracle on testnet.
+pub fn testnet_oracle() -> AccountId {
+    let (_, account_id) = AccountId::from_bech32(TESTNET_ORACLE).expect("valid oracle address");
+    account_id
+}
+
+/// Returns the root of `get_median`.
+pub fn get_median_root() -> Word {
+    Word::try_from(GET_MEDIAN_ROOT).expect("valid procedure root")
+}
+
+/// Import the oracle + its publishers and return the ForeignAccount list
+/// Due to Pragma's decentralized oracle architecture, we need to get the
+/// list of all data publisher accounts to read price from via a nested FPI call
+pub async fn get_oracle_foreign_accounts(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    oracle_account_id: AccountId,
+    trading_pair: u64,
+) -> Result<Vec<ForeignAccount>, ClientError> {
+    client.import_account_by_id(oracle_account_id).await?;
+
+    let oracle_record = client
+        .get_account(oracle_account_id)
+        .await
+        .expect("RPC failed")
+        .expect("oracle account not found");
+
+    let storage = oracle_record.account().storage();
+    let publisher_count = storage.get_item(1).unwrap()[0].as_int();
+
+    let publisher_ids: Vec<AccountId> = (1..publisher_count.saturating_sub(1))
+        .map(|i| {
+            let digest = storage.get_item(2 + i as u8).unwrap();
+            let words: Word = digest.into();
+            AccountId::new_unchecked([words[3], words[2]])
+        })
+        .collect();
+
+    let mut foreign_accounts = Vec::with_capacity(publisher_ids.len() + 1);
+
+    for pid in publisher_ids {
+        client.import_account_by_id(pid).await?;
+
+        foreign_accounts.push(ForeignAccount::public(
+            pid,
+            AccountStorageRequirements::new([(
+                1u8,
+                &[StorageMapKey::from([
+                    ZERO,
+                    ZERO,
+                    ZERO,
+                    Felt::new(trading_pair),
+                ])],
+            )]),
+        )?);
+    }
+
+    foreign_accounts.push(ForeignAccount::public(
+        oracle_account_id,
+        AccountStorageRequirements::default(),
+    )?);
+
+    Ok(foreign_accounts)
+}
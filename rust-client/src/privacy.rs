@@ -0,0 +1,159 @@
+//! What an outside observer learns about a payment from the node.
+//!
+//! A payment touches three things the node keeps: the note, the accounts on both ends and,
+//! once the note is consumed, its nullifier. Public notes and accounts are stored in full,
+//! private ones only as commitments. An [`Observation`] records which [`Field`]s of a payment an
+//! observer could read back, so that two workflows can be compared field by field.
+
+use std::collections::BTreeSet;
+
+/// Piece of a payment an observer may or may not learn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Field {
+    /// Account that created the note, from the note metadata.
+    Sender,
+    /// Tag the note is routed with, from the note metadata.
+    Tag,
+    /// Assets carried by the note.
+    Assets,
+    /// Account the note pays, from the inputs of the P2ID note.
+    Receiver,
+    /// Vault of the sender, from the account state.
+    SenderBalance,
+    /// Vault of the receiver, from the account state.
+    ReceiverBalance,
+    /// Whether the note was consumed, from its nullifier.
+    Consumption,
+}
+
+impl Field {
+    /// Every field, in the order they are reported in.
+    pub const ALL: [Field; 7] = [
+        Field::Sender,
+        Field::Tag,
+        Field::Assets,
+        Field::Receiver,
+        Field::SenderBalance,
+        Field::ReceiverBalance,
+        Field::Consumption,
+    ];
+
+    /// Returns the name of the field in reports.
+    pub fn label(self) -> &'static str {
+        match self {
+            Field::Sender => "note sender",
+            Field::Tag => "note tag",
+            Field::Assets => "note assets",
+            Field::Receiver => "note receiver",
+            Field::SenderBalance => "sender balance",
+            Field::ReceiverBalance => "receiver balance",
+            Field::Consumption => "note consumed",
+        }
+    }
+}
+
+/// Fields of one payment an observer could read back from the node.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Observation {
+    visible: BTreeSet<Field>,
+}
+
+impl Observation {
+    /// Records whether `field` was visible.
+    pub fn record(&mut self, field: Field, visible: bool) {
+        if visible {
+            self.visible.insert(field);
+        } else {
+            self.visible.remove(&field);
+        }
+    }
+
+    pub fn is_visible(&self, field: Field) -> bool {
+        self.visible.contains(&field)
+    }
+
+    /// Returns the visible fields, in the order of [`Field::ALL`].
+    pub fn visible(&self) -> Vec<Field> {
+        self.visible.iter().copied().collect()
+    }
+}
+
+/// Returns the fields visible in `public` that `private` hides.
+pub fn hidden_fields(public: &Observation, private: &Observation) -> Vec<Field> {
+    public
+        .visible
+        .difference(&private.visible)
+        .copied()
+        .collect()
+}
+
+/// Renders both observations side by side, one field per line.
+pub fn comparison_table(public: &Observation, private: &Observation) -> String {
+    let cell = |observation: &Observation, field| {
+        if observation.is_visible(field) {
+            "visible"
+        } else {
+            "hidden"
+        }
+    };
+
+    let mut table = format!("{:<18} | {:<8} | {}\n", "FIELD", "PUBLIC", "PRIVATE");
+    for field in Field::ALL {
+        table.push_str(&format!(
+            "{:<18} | {:<8} | {}\n",
+            field.label(),
+            cell(public, field),
+            cell(private, field)
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observation(fields: &[Field]) -> Observation {
+        let mut observation = Observation::default();
+        for &field in fields {
+            observation.record(field, true);
+        }
+        observation
+    }
+
+    #[test]
+    fn recording_a_field_as_hidden_clears_it() {
+        let mut observation = observation(&[Field::Assets, Field::Sender]);
+        observation.record(Field::Assets, false);
+
+        assert!(!observation.is_visible(Field::Assets));
+        assert_eq!(observation.visible(), vec![Field::Sender]);
+    }
+
+    #[test]
+    fn hidden_fields_are_the_ones_only_the_public_workflow_shows() {
+        let public = observation(&Field::ALL);
+        let private = observation(&[Field::Sender, Field::Tag]);
+
+        assert_eq!(
+            hidden_fields(&public, &private),
+            vec![
+                Field::Assets,
+                Field::Receiver,
+                Field::SenderBalance,
+                Field::ReceiverBalance,
+                Field::Consumption,
+            ]
+        );
+        assert!(hidden_fields(&private, &public).is_empty());
+    }
+
+    #[test]
+    fn the_table_has_a_line_per_field() {
+        let table = comparison_table(&observation(&[Field::Assets]), &Observation::default());
+
+        assert_eq!(table.lines().count(), Field::ALL.len() + 1);
+        assert!(table.contains("note assets        | visible  | hidden"));
+        assert!(table.contains("note sender        | hidden   | hidden"));
+    }
+}
@@ -0,0 +1,163 @@
+//! Fitting transaction proofs into the memory of small devices.
+//!
+//! The prover has no memory setting. What it allocates grows with the execution trace, whose
+//! length is the number of cycles of the transaction rounded up to a power of two, so a phone
+//! or a Raspberry Pi runs out of memory on long transactions only. Such a device can keep its
+//! transactions short, splitting a large job into several transactions, or delegate the ones
+//! that do not fit to a remote prover.
+//!
+//! [`MemoryBudget`] makes that call from the memory a proof took per trace row on the device,
+//! measured with [`reset_peak_rss`] and [`peak_rss`]. Both read `/proc` and only work on Linux.
+
+use std::{env, fs, io};
+
+/// Environment variable setting the memory a device can spend on proving, in MiB.
+pub const MEMORY_LIMIT_ENV: &str = "MIDEN_PROVER_MEMORY_MIB";
+
+/// Memory limit used when [`MEMORY_LIMIT_ENV`] is not set, the memory of a small single-board
+/// computer once the system took its share.
+pub const DEFAULT_MEMORY_LIMIT_MIB: u64 = 1024;
+
+/// Shortest execution trace the VM produces, in rows.
+pub const MIN_TRACE_ROWS: u64 = 64;
+
+/// Returns the number of rows of the execution trace of a transaction running `cycles`
+/// cycles.
+pub fn trace_rows(cycles: u64) -> u64 {
+    cycles.next_power_of_two().max(MIN_TRACE_ROWS)
+}
+
+/// Returns the value of `field`, given in kB, of the contents of `/proc/self/status`, in
+/// bytes.
+fn status_field(status: &str, field: &str) -> Option<u64> {
+    let line = status
+        .lines()
+        .find(|line| line.split(':').next() == Some(field))?;
+    let kib = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kib * 1024)
+}
+
+/// Returns the peak resident memory of this process since it started or since the last
+/// [`reset_peak_rss`], in bytes.
+pub fn peak_rss() -> io::Result<u64> {
+    let status = fs::read_to_string("/proc/self/status")?;
+    status_field(&status, "VmHWM").ok_or_else(|| io::Error::other("no VmHWM in /proc/self/status"))
+}
+
+/// Returns the current resident memory of this process, in bytes.
+pub fn current_rss() -> io::Result<u64> {
+    let status = fs::read_to_string("/proc/self/status")?;
+    status_field(&status, "VmRSS").ok_or_else(|| io::Error::other("no VmRSS in /proc/self/status"))
+}
+
+/// Resets the peak returned by [`peak_rss`] to the current resident memory, so that the next
+/// reading covers only what runs in between.
+pub fn reset_peak_rss() -> io::Result<()> {
+    fs::write("/proc/self/clear_refs", "5")
+}
+
+/// Returns the memory limit set by [`MEMORY_LIMIT_ENV`], or the default one, in bytes.
+pub fn memory_limit() -> Result<u64, String> {
+    let mib = match env::var(MEMORY_LIMIT_ENV) {
+        Ok(value) => value
+            .parse::<u64>()
+            .map_err(|_| format!("invalid value `{value}` for {MEMORY_LIMIT_ENV}, expected MiB"))?,
+        Err(_) => DEFAULT_MEMORY_LIMIT_MIB,
+    };
+    Ok(mib * 1024 * 1024)
+}
+
+/// Memory a device can spend on proving, and what a proof costs on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryBudget {
+    /// Most resident memory the process may reach while proving, in bytes.
+    pub limit: u64,
+    /// Resident memory of the process before proving, in bytes.
+    pub baseline: u64,
+    /// Memory a proof takes per row of the execution trace, in bytes.
+    pub bytes_per_row: u64,
+}
+
+impl MemoryBudget {
+    /// Builds the budget of a process at `baseline` bytes from proofs measured on the device,
+    /// given as `(trace_rows, peak_rss)` pairs. The costliest proof per row sets the estimate,
+    /// so that the budget errs on the safe side.
+    pub fn calibrate(limit: u64, baseline: u64, samples: &[(u64, u64)]) -> Self {
+        let bytes_per_row = samples
+            .iter()
+            .map(|(rows, peak)| peak.saturating_sub(baseline).div_ceil(*rows))
+            .max()
+            .unwrap_or(0);
+        Self {
+            limit,
+            baseline,
+            bytes_per_row,
+        }
+    }
+
+    /// Returns the estimated peak memory of proving a transaction of `cycles` cycles.
+    pub fn estimate(&self, cycles: u64) -> u64 {
+        self.baseline + trace_rows(cycles) * self.bytes_per_row
+    }
+
+    /// Returns whether a transaction of `cycles` cycles can be proven within the limit.
+    pub fn fits(&self, cycles: u64) -> bool {
+        self.estimate(cycles) <= self.limit
+    }
+
+    /// Returns the most cycles a transaction proven within the limit can run, or `None` if
+    /// not even the shortest trace fits.
+    pub fn max_cycles(&self) -> Option<u64> {
+        let mut rows = MIN_TRACE_ROWS;
+        if !self.fits(rows) {
+            return None;
+        }
+        while self.fits(rows * 2) {
+            rows *= 2;
+        }
+        Some(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    #[test]
+    fn traces_are_padded_to_a_power_of_two() {
+        assert_eq!(trace_rows(1), MIN_TRACE_ROWS);
+        assert_eq!(trace_rows(40_000), 1 << 16);
+        assert_eq!(trace_rows(1 << 16), 1 << 16);
+        assert_eq!(trace_rows((1 << 16) + 1), 1 << 17);
+    }
+
+    #[test]
+    fn peak_memory_is_read_from_the_status_file() {
+        let status = "Name:\tbench\nVmPeak:\t  900000 kB\nVmHWM:\t  524288 kB\nVmRSS:\t 1024 kB\n";
+        assert_eq!(status_field(status, "VmHWM"), Some(512 * MIB));
+        assert_eq!(status_field(status, "VmRSS"), Some(MIB));
+        assert_eq!(status_field(status, "VmSwap"), None);
+    }
+
+    #[test]
+    fn budget_follows_the_costliest_proof() {
+        let budget = MemoryBudget::calibrate(
+            1024 * MIB,
+            64 * MIB,
+            &[(1 << 16, 192 * MIB), (1 << 17, 320 * MIB)],
+        );
+        // 128 MiB over 2^16 rows is the costlier of the two proofs, 2 KiB per row
+        assert_eq!(budget.bytes_per_row, 2048);
+        assert!(budget.fits(1 << 18));
+        assert!(!budget.fits((1 << 18) + 1));
+        assert_eq!(budget.max_cycles(), Some(1 << 18));
+
+        let tiny = MemoryBudget {
+            limit: 32 * MIB,
+            ..budget
+        };
+        assert_eq!(tiny.max_cycles(), None);
+    }
+}
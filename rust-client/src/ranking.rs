@@ -0,0 +1,363 @@
+//! Off-chain side of the tournament ranking (`masm/accounts/ranking.masm`).
+//!
+//! A ranking keeps the Elo rating, wins and losses of every player of a tournament. It is fed
+//! by the games themselves rather than by the players: a ranked tic-tac-toe game creates a
+//! `report_result_note` in the transaction of its winning move, or of the timeout claim, and
+//! the ranking consumes it like any other note. Neither player can report a result, and the
+//! loser cannot hold it back.
+//!
+//! The ranking takes the game from the sender of the report, so a report is only as good as
+//! the game sending it. The organizer of the tournament therefore registers every game with a
+//! `register_game_note` before it is played, and the ranking accepts one report per registered
+//! game. Draws are not reported and leave the ratings unchanged.
+//!
+//! The contract has no floating point, so it approximates the Elo update linearly, see
+//! [`rating_change`].
+
+use std::{cmp::Reverse, collections::BTreeMap, error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    rpc::{domain::account::FetchedAccount, NodeRpcClient},
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    NoteError, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{cli::SubmitWithProver, helpers::create_library};
+
+/// Path under which the ranking is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::ranking";
+/// Location of the ranking contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/ranking.masm";
+/// Location of the note registering a game, relative to `rust-client`.
+pub const REGISTER_GAME_NOTE_PATH: &str = "../masm/notes/register_game_note.masm";
+/// Location of the note reporting the result of a game, relative to `rust-client`.
+pub const REPORT_NOTE_PATH: &str = "../masm/notes/report_result_note.masm";
+
+/// Storage slot holding `[organizer_prefix, organizer_suffix, 0, 0]`.
+pub const ORGANIZER_SLOT: u8 = 0;
+/// Storage slot mapping `[game_prefix, game_suffix, 0, 0]` to the status of the game.
+pub const GAMES_SLOT: u8 = 1;
+/// Storage slot mapping `[player_prefix, player_suffix, 0, 0]` to
+/// `[rating, wins, losses, 0]`.
+pub const PLAYERS_SLOT: u8 = 2;
+
+/// Rating of a player before the first game.
+pub const INITIAL_RATING: u64 = 1200;
+
+/// Rating change of a game between equally rated players, half of the K-factor of 32.
+const HALF_K: u64 = 16;
+/// Rating difference worth one point of rating change.
+const RATING_SCALE: u64 = 25;
+/// Most points a game moves a rating by.
+const MAX_CHANGE: u64 = 2 * HALF_K - 1;
+
+/// Returns the points the winner gains and the loser loses, computed like the contract does:
+/// `16 + (loser_rating - winner_rating) / 25`, between 1 and 31.
+///
+/// This is the Elo update with a K-factor of 32, linearized around equal ratings. It stays
+/// within a point of the exact update for rating gaps up to 300.
+pub fn rating_change(winner_rating: u64, loser_rating: u64) -> u64 {
+    if loser_rating >= winner_rating {
+        (HALF_K + (loser_rating - winner_rating) / RATING_SCALE).min(MAX_CHANGE)
+    } else {
+        HALF_K - ((winner_rating - loser_rating) / RATING_SCALE).min(HALF_K - 1)
+    }
+}
+
+/// Record of a player in a ranking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlayerStats {
+    pub rating: u64,
+    pub wins: u64,
+    pub losses: u64,
+}
+
+impl Default for PlayerStats {
+    /// Returns the record of a player who has not played yet.
+    fn default() -> Self {
+        Self {
+            rating: INITIAL_RATING,
+            wins: 0,
+            losses: 0,
+        }
+    }
+}
+
+/// State of a ranking, read from a copy of its account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ranking {
+    pub id: AccountId,
+    pub organizer: AccountId,
+    /// Record of every player who played a reported game.
+    pub players: BTreeMap<AccountId, PlayerStats>,
+}
+
+impl Ranking {
+    /// Decodes the storage of a ranking account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let organizer = storage.get_item(ORGANIZER_SLOT)?;
+
+        let StorageSlot::Map(players_map) = &storage.slots()[PLAYERS_SLOT as usize] else {
+            return Err("the players slot of the ranking is not a map".into());
+        };
+        let mut players = BTreeMap::new();
+        for (key, value) in players_map.entries() {
+            let stats = PlayerStats {
+                rating: value[3].as_int(),
+                wins: value[2].as_int(),
+                losses: value[1].as_int(),
+            };
+            if stats.wins + stats.losses > 0 {
+                players.insert(AccountId::try_from([key[3], key[2]])?, stats);
+            }
+        }
+
+        Ok(Self {
+            id: account.id(),
+            organizer: AccountId::try_from([organizer[3], organizer[2]])?,
+            players,
+        })
+    }
+
+    /// Reads the public ranking `ranking_id` from the node.
+    pub async fn fetch(
+        rpc: &dyn NodeRpcClient,
+        ranking_id: AccountId,
+    ) -> Result<Self, Box<dyn Error>> {
+        match rpc.get_account_details(ranking_id).await? {
+            FetchedAccount::Public(account, _) => Self::from_account(&account),
+            FetchedAccount::Private(..) => Err("the ranking must be public".into()),
+        }
+    }
+
+    /// Returns the record of `player`, the one of a new player if it has not played yet.
+    pub fn player(&self, player: AccountId) -> PlayerStats {
+        self.players.get(&player).copied().unwrap_or_default()
+    }
+
+    /// Returns the players by decreasing rating, the player with more wins first on a tie.
+    pub fn leaderboard(&self) -> Vec<(AccountId, PlayerStats)> {
+        let mut players: Vec<_> = self
+            .players
+            .iter()
+            .map(|(player, stats)| (*player, *stats))
+            .collect();
+        players.sort_by_key(|(_, stats)| Reverse((stats.rating, stats.wins)));
+        players
+    }
+}
+
+/// Deploys an empty public ranking whose games are registered by `organizer`.
+pub async fn create_ranking(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    organizer: AccountId,
+) -> Result<Account, Box<dyn Error>> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(
+                [ZERO, ZERO, organizer.suffix(), organizer.prefix().as_felt()].into(),
+            ),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the ranking.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Returns the tag of the notes a game sends to the ranking `ranking_id`.
+pub fn report_tag(ranking_id: AccountId) -> NoteTag {
+    NoteTag::from_account_id(ranking_id)
+}
+
+/// Builds the note by which `organizer` registers the game `game_id` with the ranking
+/// `ranking_id`.
+pub fn register_game_note(
+    organizer: AccountId,
+    ranking_id: AccountId,
+    game_id: AccountId,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let inputs = NoteInputs::new(vec![game_id.prefix().as_felt(), game_id.suffix()])?;
+    let metadata = NoteMetadata::new(
+        organizer,
+        NoteType::Public,
+        report_tag(ranking_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(
+        NoteAssets::default(),
+        metadata,
+        NoteRecipient::new(serial_num, script, inputs),
+    ))
+}
+
+/// Builds the note by which the game `game_id` reports that `winner` beat `loser`, tagged with
+/// `tag`. `script` is the compiled [`REPORT_NOTE_PATH`].
+///
+/// The game derives the serial number from its id, like the serial numbers of its payouts, so
+/// the note is known before the game creates it.
+pub fn report_note(
+    game_id: AccountId,
+    winner: AccountId,
+    loser: AccountId,
+    tag: NoteTag,
+    script: NoteScript,
+) -> Result<Note, NoteError> {
+    let serial_num = [ZERO, ZERO, game_id.suffix(), game_id.prefix().as_felt()].into();
+    let inputs = NoteInputs::new(vec![
+        winner.prefix().as_felt(),
+        winner.suffix(),
+        loser.prefix().as_felt(),
+        loser.suffix(),
+    ])?;
+    let metadata = NoteMetadata::new(
+        game_id,
+        NoteType::Public,
+        tag,
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(
+        NoteAssets::default(),
+        metadata,
+        NoteRecipient::new(serial_num, script, inputs),
+    ))
+}
+
+/// Has `organizer` register `game_id` with the ranking, and the ranking consume the note right
+/// away. Returns the id of the ranking transaction.
+///
+/// The ranking is a `NoAuth` account, so any client tracking it can execute its transactions.
+pub async fn register_game(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    organizer: AccountId,
+    ranking_id: AccountId,
+    game_id: AccountId,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let script = compile_note_script(client, REGISTER_GAME_NOTE_PATH)?;
+    let serial_num = client.rng().draw_word();
+    let note = register_game_note(organizer, ranking_id, game_id, script, serial_num)?;
+
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    client.submit_with_prover(organizer, request).await?;
+
+    consume_note(client, ranking_id, note).await
+}
+
+/// Has the ranking consume `note`, the registration of a game or the report of its result.
+pub async fn consume_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    ranking_id: AccountId,
+    note: Note,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .build()?;
+    Ok(client.submit_with_prover(ranking_id, request).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    #[test]
+    fn equal_ratings_move_by_half_the_k_factor() {
+        assert_eq!(rating_change(INITIAL_RATING, INITIAL_RATING), 16);
+        // Less than a full step of rating difference does not count
+        assert_eq!(rating_change(1200, 1224), 16);
+        assert_eq!(rating_change(1224, 1200), 16);
+    }
+
+    #[test]
+    fn upsets_move_ratings_more_than_expected_wins() {
+        assert_eq!(rating_change(1200, 1400), 24);
+        assert_eq!(rating_change(1400, 1200), 8);
+        // A win always counts, however large the gap
+        assert_eq!(rating_change(1000, 3000), MAX_CHANGE);
+        assert_eq!(rating_change(3000, 1000), 1);
+    }
+
+    #[test]
+    fn leaderboard_sorts_by_rating_then_wins() {
+        let player = |seed| {
+            AccountId::dummy(
+                [seed; 15],
+                AccountIdVersion::Version0,
+                AccountType::RegularAccountUpdatableCode,
+                AccountStorageMode::Public,
+            )
+        };
+        let stats = |rating, wins| PlayerStats {
+            rating,
+            wins,
+            losses: 1,
+        };
+        let ranking = Ranking {
+            id: player(1),
+            organizer: player(2),
+            players: BTreeMap::from([
+                (player(3), stats(1184, 0)),
+                (player(4), stats(1216, 1)),
+                (player(5), stats(1216, 2)),
+            ]),
+        };
+
+        let order: Vec<_> = ranking
+            .leaderboard()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(order, [player(5), player(4), player(3)]);
+        assert_eq!(ranking.player(player(6)), PlayerStats::default());
+    }
+}
@@ -0,0 +1,91 @@
+//! In-memory rate limiting for example services.
+//!
+//! Services handing out something on request, like `faucet_server`, limit how often each caller
+//! may ask. A [`RateLimiter`] remembers when every key (an IP address, an account id, ...) was
+//! last granted a request and refuses the next one until the interval has passed. Unlike the
+//! top-up log of [`FundingGuard`], it is not kept across restarts.
+//!
+//! [`FundingGuard`]: crate::funding::FundingGuard
+
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+/// Allows one request per key every `interval`.
+#[derive(Debug, Clone)]
+pub struct RateLimiter<K> {
+    interval: Duration,
+    granted: BTreeMap<K, Instant>,
+}
+
+impl<K: Ord> RateLimiter<K> {
+    /// Creates a limiter allowing one request per key every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            granted: BTreeMap::new(),
+        }
+    }
+
+    /// Grants a request of `key` at `now`, or returns how long it has to wait.
+    ///
+    /// Keys whose interval has passed are forgotten on the way, so the limiter only holds the
+    /// keys granted within the last interval.
+    pub fn try_acquire(&mut self, key: K, now: Instant) -> Result<(), Duration> {
+        let interval = self.interval;
+        self.granted
+            .retain(|_, granted_at| now.saturating_duration_since(*granted_at) < interval);
+
+        if let Some(granted_at) = self.granted.get(&key) {
+            return Err(interval - now.saturating_duration_since(*granted_at));
+        }
+        self.granted.insert(key, now);
+        Ok(())
+    }
+
+    /// Forgets the last request of `key`, e.g. because serving it failed.
+    pub fn release(&mut self, key: &K) {
+        self.granted.remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn second_request_waits_for_the_interval() {
+        let mut limiter = RateLimiter::new(INTERVAL);
+        let start = Instant::now();
+
+        assert_eq!(limiter.try_acquire("alice", start), Ok(()));
+        assert_eq!(
+            limiter.try_acquire("alice", start + Duration::from_secs(20)),
+            Err(Duration::from_secs(40))
+        );
+        assert_eq!(limiter.try_acquire("alice", start + INTERVAL), Ok(()));
+    }
+
+    #[test]
+    fn keys_are_limited_independently() {
+        let mut limiter = RateLimiter::new(INTERVAL);
+        let start = Instant::now();
+
+        assert_eq!(limiter.try_acquire("alice", start), Ok(()));
+        assert_eq!(limiter.try_acquire("bob", start), Ok(()));
+    }
+
+    #[test]
+    fn released_key_can_retry_right_away() {
+        let mut limiter = RateLimiter::new(INTERVAL);
+        let start = Instant::now();
+
+        limiter.try_acquire("alice", start).unwrap();
+        limiter.release(&"alice");
+
+        assert_eq!(limiter.try_acquire("alice", start), Ok(()));
+    }
+}
@@ -0,0 +1,266 @@
+//! Relaying transactions proven by someone else.
+//!
+//! A relayer submits transactions on behalf of users who do not talk to the node themselves,
+//! and takes a fee for it. The user executes and proves the transaction on its own machine, then
+//! hands the relayer a [`RelayRequest`]: the proven transaction, the inputs the node needs to
+//! accept it, and the full details of the output notes the user claims it creates.
+//!
+//! The relayer trusts none of it. [`RelayPolicy::check`] verifies the proof, checks that every
+//! claimed note is an output of the proven transaction, and that one of the claimed notes pays
+//! the relayer its fee. A note id commits to the recipient and the assets of the note, so a
+//! claimed note matching an output id is exactly the note the transaction creates, even when
+//! the note is private and the proven transaction only carries its id. Only a request passing
+//! every check reaches the node.
+
+use std::{error::Error, fmt};
+
+use miden_client::{
+    account::AccountId,
+    keystore::FilesystemKeyStore,
+    note::{Note, NoteId},
+    rpc::NodeRpcClient,
+    transaction::{TransactionRequest, TransactionResult},
+    utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
+    Client,
+};
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    asset::{Asset, FungibleAsset},
+    block::BlockNumber,
+    transaction::{OutputNote, ProvenTransaction, TransactionInputs},
+    MIN_PROOF_SECURITY_LEVEL,
+};
+use miden_tx::TransactionVerifier;
+use rand::rngs::StdRng;
+
+use crate::cli::ProverOption;
+
+/// A transaction proven by a user, as handed to a relayer.
+#[derive(Debug, Clone)]
+pub struct RelayRequest {
+    pub transaction: ProvenTransaction,
+    /// Inputs the transaction was executed against, submitted to the node with the proof.
+    pub inputs: TransactionInputs,
+    /// Output notes whose details the user discloses to the relayer, the fee among them.
+    pub claimed_notes: Vec<Note>,
+}
+
+impl Serializable for RelayRequest {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.transaction.write_into(target);
+        self.inputs.write_into(target);
+        self.claimed_notes.write_into(target);
+    }
+}
+
+impl Deserializable for RelayRequest {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(Self {
+            transaction: ProvenTransaction::read_from(source)?,
+            inputs: TransactionInputs::read_from(source)?,
+            claimed_notes: Vec::<Note>::read_from(source)?,
+        })
+    }
+}
+
+/// Reason for a relayer to refuse a request.
+#[derive(Debug)]
+pub enum RelayError {
+    /// The proof does not verify against the claimed transaction.
+    InvalidProof(String),
+    /// A claimed note is not created by the transaction.
+    UnknownNote(NoteId),
+    /// None of the claimed notes pays the relayer its fee.
+    MissingFee(FungibleAsset),
+}
+
+impl fmt::Display for RelayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidProof(reason) => write!(f, "invalid transaction proof: {reason}"),
+            Self::UnknownNote(note_id) => write!(
+                f,
+                "claimed note {} is not an output of the transaction",
+                note_id.to_hex()
+            ),
+            Self::MissingFee(fee) => write!(
+                f,
+                "no claimed note pays the relayer {} of faucet {}",
+                fee.amount(),
+                fee.faucet_id()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RelayError {}
+
+/// What a relayer requires before submitting a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelayPolicy {
+    /// Account of the relayer, the target of the fee.
+    pub relayer_id: AccountId,
+    /// Smallest fee the relayer accepts.
+    pub fee: FungibleAsset,
+}
+
+impl RelayPolicy {
+    /// Returns whether `note` is a P2ID note paying the relayer at least the fee.
+    pub fn is_fee(&self, note: &Note) -> bool {
+        let recipient = note.recipient();
+        let target = [self.relayer_id.suffix(), self.relayer_id.prefix().as_felt()];
+        recipient.script().root() == WellKnownNote::P2ID.script_root()
+            && recipient.inputs().values() == target.as_slice()
+            && note.assets().iter().any(|asset| match asset {
+                Asset::Fungible(asset) => {
+                    asset.faucet_id() == self.fee.faucet_id() && asset.amount() >= self.fee.amount()
+                }
+                Asset::NonFungible(_) => false,
+            })
+    }
+
+    /// Checks the proof of `request`, that the transaction creates every claimed note and
+    /// that one of them is the fee.
+    pub fn check(&self, request: &RelayRequest) -> Result<(), RelayError> {
+        TransactionVerifier::new(MIN_PROOF_SECURITY_LEVEL)
+            .verify(&request.transaction)
+            .map_err(|err| RelayError::InvalidProof(err.to_string()))?;
+
+        let outputs = request.transaction.output_notes();
+        for note in &request.claimed_notes {
+            let created = outputs
+                .iter()
+                .any(|output| output.id() == note.id() && output.metadata() == note.metadata());
+            if !created {
+                return Err(RelayError::UnknownNote(note.id()));
+            }
+        }
+
+        if !request.claimed_notes.iter().any(|note| self.is_fee(note)) {
+            return Err(RelayError::MissingFee(self.fee));
+        }
+        Ok(())
+    }
+}
+
+/// Checks `request` against `policy` and submits it to the node. Returns the block at which
+/// the node accepted the transaction, which the user needs to apply it to its own store.
+pub async fn relay(
+    rpc: &dyn NodeRpcClient,
+    policy: &RelayPolicy,
+    request: RelayRequest,
+) -> Result<BlockNumber, Box<dyn Error>> {
+    policy.check(&request)?;
+    Ok(rpc
+        .submit_proven_transaction(request.transaction, request.inputs)
+        .await?)
+}
+
+/// Executes and proves `request` against `account_id` without submitting it, with the prover
+/// chosen by `--prover`. Returns the result, to apply once the relayer reports the submission,
+/// and the request to hand to the relayer, disclosing every output note the client knows.
+pub async fn prepare(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+    request: TransactionRequest,
+) -> Result<(TransactionResult, RelayRequest), Box<dyn Error>> {
+    let prover = ProverOption::global()
+        .transaction_prover()
+        .ok_or("relayed transactions must be proven, run without `--prover none`")?;
+
+    let tx_result = client.execute_transaction(account_id, request).await?;
+    let transaction = client.prove_transaction_with(&tx_result, prover).await?;
+    let claimed_notes = tx_result
+        .created_notes()
+        .iter()
+        .filter_map(|note| match note {
+            OutputNote::Full(note) => Some(note.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let request = RelayRequest {
+        transaction,
+        inputs: tx_result.executed_transaction().tx_inputs().clone(),
+        claimed_notes,
+    };
+    Ok((tx_result, request))
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_client::note::NoteType;
+    use miden_objects::{
+        account::{AccountIdVersion, AccountStorageMode, AccountType},
+        Felt, Word, ZERO,
+    };
+
+    use super::*;
+    use crate::serial::p2id_note;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn faucet(seed: u8) -> AccountId {
+        account(seed, AccountType::FungibleFaucet)
+    }
+
+    fn policy() -> RelayPolicy {
+        RelayPolicy {
+            relayer_id: account(1, AccountType::RegularAccountUpdatableCode),
+            fee: FungibleAsset::new(faucet(10), 5).unwrap(),
+        }
+    }
+
+    /// Builds a P2ID note from a user to `target` carrying `amount` tokens of `faucet_id`.
+    fn payment(target: AccountId, faucet_id: AccountId, amount: u64) -> Note {
+        let user = account(2, AccountType::RegularAccountUpdatableCode);
+        let asset = FungibleAsset::new(faucet_id, amount).unwrap();
+        let serial_num: Word = [ZERO, ZERO, ZERO, Felt::new(amount)].into();
+        p2id_note(
+            user,
+            target,
+            vec![asset.into()],
+            NoteType::Private,
+            serial_num,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn fee_is_a_payment_of_at_least_the_fee_to_the_relayer() {
+        let policy = policy();
+        assert!(policy.is_fee(&payment(policy.relayer_id, faucet(10), 5)));
+        assert!(policy.is_fee(&payment(policy.relayer_id, faucet(10), 50)));
+        assert!(!policy.is_fee(&payment(policy.relayer_id, faucet(10), 4)));
+    }
+
+    #[test]
+    fn fee_must_be_paid_in_the_fee_token_to_the_relayer() {
+        let policy = policy();
+        let other = account(3, AccountType::RegularAccountUpdatableCode);
+        assert!(!policy.is_fee(&payment(policy.relayer_id, faucet(11), 5)));
+        assert!(!policy.is_fee(&payment(other, faucet(10), 5)));
+    }
+
+    #[test]
+    fn refusals_name_what_is_missing() {
+        let policy = policy();
+        let note = payment(policy.relayer_id, faucet(10), 5);
+        let unknown = RelayError::UnknownNote(note.id()).to_string();
+        assert!(unknown.contains(&note.id().to_hex()));
+        assert_eq!(
+            RelayError::MissingFee(policy.fee).to_string(),
+            format!(
+                "no claimed note pays the relayer 5 of faucet {}",
+                faucet(10)
+            )
+        );
+    }
+}
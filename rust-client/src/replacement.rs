@@ -0,0 +1,87 @@
+//! Cancelling and replacing notes one has sent but nobody consumed yet.
+//!
+//! An offer, a bid or any other note which waits for a counterparty goes stale when the terms
+//! it was written with no longer hold. If its script lets the sender consume it back, as
+//! `masm/notes/swap_offer_note.masm` does, [`replace_note`] takes the note back and issues the
+//! replacement in the same transaction: either both happen, or neither does, so there is no
+//! moment at which the sender has no offer out or two of them.
+//!
+//! The note may be consumed by somebody else first. Its nullifier is then on-chain, so the
+//! transaction of the sender cannot consume it anymore; [`replace_note`] reports this as
+//! [`Replacement::AlreadyConsumed`] rather than as an error, since it is a normal outcome for
+//! anybody trading against other people.
+
+use miden_client::{
+    account::AccountId,
+    keystore::FilesystemKeyStore,
+    note::Note,
+    store::{NoteFilter, OutputNoteState},
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, ClientError,
+};
+use rand::rngs::StdRng;
+
+use crate::cli::SubmitWithProver;
+
+/// Outcome of [`replace_note`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Replacement {
+    /// The note was taken back and its replacements issued by the transaction.
+    Replaced(TransactionId),
+    /// Somebody consumed the note first, nothing was submitted.
+    AlreadyConsumed,
+}
+
+/// Returns whether `note`, sent by the owner of `client`, was consumed, as of the last sync.
+///
+/// The client tracks the notes it sent as output notes and learns of their consumption from
+/// the nullifiers it receives while syncing.
+pub async fn is_consumed(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note: &Note,
+) -> Result<bool, ClientError> {
+    let records = client
+        .get_output_notes(NoteFilter::Nullifiers(vec![note.nullifier()]))
+        .await?;
+    Ok(records
+        .iter()
+        .any(|record| matches!(record.state(), OutputNoteState::Consumed { .. })))
+}
+
+/// Has `owner`, the sender of `note`, consume it and create `replacements` in one
+/// transaction. Pass no replacement to cancel the note.
+///
+/// Syncs first and skips notes which are already consumed. A counterparty may still consume
+/// the note between the sync and the submission, in which case the node rejects the
+/// transaction; the client then syncs once more and reports the note as consumed if it was.
+/// A transaction the node accepted can still be discarded if the counterparty's transaction
+/// makes it into a block first: waiting for it with
+/// [`wait_for_tx_with_timeout`](crate::helpers::wait_for_tx_with_timeout) tells both cases
+/// apart.
+pub async fn replace_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    owner: AccountId,
+    note: &Note,
+    replacements: Vec<Note>,
+) -> Result<Replacement, ClientError> {
+    client.sync_state().await?;
+    if is_consumed(client, note).await? {
+        return Ok(Replacement::AlreadyConsumed);
+    }
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note.clone(), None)])
+        .own_output_notes(replacements.into_iter().map(OutputNote::Full).collect())
+        .build()?;
+    match client.submit_with_prover(owner, request).await {
+        Ok(tx_id) => Ok(Replacement::Replaced(tx_id)),
+        Err(err) => {
+            client.sync_state().await?;
+            if is_consumed(client, note).await? {
+                Ok(Replacement::AlreadyConsumed)
+            } else {
+                Err(err)
+            }
+        }
+    }
+}
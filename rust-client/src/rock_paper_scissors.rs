@@ -0,0 +1,340 @@
+//! Off-chain side of the rock-paper-scissors game (`masm/accounts/rock_paper_scissors.masm`).
+//!
+//! Both players pick their hand at the same time, which a public contract cannot offer: the hand
+//! sent second would be chosen knowing the first. Each player therefore sends a
+//! `hand_commit_note` carrying only the hash of its [`HiddenHand`], then a `hand_reveal_note`
+//! carrying the hand and its salt. The game only accepts reveals once both players committed,
+//! and only a hand hashing to the commitment of its player, so seeing the first reveal is of no
+//! use to the second player. The game records the winner as soon as both hands are revealed.
+//!
+//! Nothing forces the losing player to reveal: a game with stakes would add a reveal deadline
+//! after which the player who revealed wins, as the lottery does for its draw.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    Hasher, NoteError, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{cli::SubmitWithProver, helpers::create_library};
+
+/// Path under which the game contract is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::rock_paper_scissors";
+/// Location of the game contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/rock_paper_scissors.masm";
+/// Location of the note committing to a hand, relative to `rust-client`.
+pub const COMMIT_NOTE_PATH: &str = "../masm/notes/hand_commit_note.masm";
+/// Location of the note revealing a hand, relative to `rust-client`.
+pub const REVEAL_NOTE_PATH: &str = "../masm/notes/hand_reveal_note.masm";
+
+const PLAYER1_SLOT: u8 = 0;
+const PLAYER2_SLOT: u8 = 1;
+const NUM_COMMITMENTS_SLOT: u8 = 3;
+const HAND1_SLOT: u8 = 4;
+const HAND2_SLOT: u8 = 5;
+const WINNER_SLOT: u8 = 6;
+
+/// Value of the winner slot for a game where both players showed the same hand.
+const DRAW: u64 = 3;
+
+/// A hand, numbered like the contract numbers it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    Rock = 0,
+    Paper = 1,
+    Scissors = 2,
+}
+
+impl Hand {
+    pub const ALL: [Self; 3] = [Self::Rock, Self::Paper, Self::Scissors];
+
+    /// Returns whether `self` beats `other`. Every hand beats the one numbered before it, and
+    /// rock beats scissors.
+    pub fn beats(self, other: Hand) -> bool {
+        (self as u8 + 3 - other as u8) % 3 == 1
+    }
+}
+
+/// A hand, hidden behind its commitment until revealed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HiddenHand {
+    pub hand: Hand,
+    pub salt: [Felt; 3],
+}
+
+impl HiddenHand {
+    /// Returns `hand` with a fresh random salt.
+    pub fn new(hand: Hand, rng: &mut impl FeltRng) -> Self {
+        let salt = rng.draw_word();
+        Self {
+            hand,
+            salt: [salt[0], salt[1], salt[2]],
+        }
+    }
+
+    /// Returns the hand as revealed to the game, `[salt_0, salt_1, salt_2, hand]`.
+    pub fn to_word(&self) -> Word {
+        [
+            self.salt[0],
+            self.salt[1],
+            self.salt[2],
+            Felt::from(self.hand as u8),
+        ]
+        .into()
+    }
+
+    /// Returns the commitment sent before the hand is revealed.
+    pub fn commitment(&self) -> Word {
+        Hasher::hash_elements(self.to_word().as_elements())
+    }
+}
+
+/// How a game stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// A hand is not revealed yet.
+    Pending,
+    Winner(AccountId),
+    Draw,
+}
+
+/// Players and state of a deployed game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub id: AccountId,
+    pub player1: AccountId,
+    pub player2: AccountId,
+    pub num_commitments: u64,
+    /// Hands revealed by the first and the second player.
+    pub hands: [Option<Hand>; 2],
+    pub outcome: Outcome,
+}
+
+impl Game {
+    /// Decodes the players and state of a deployed game account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let value =
+            |slot| -> Result<u64, Box<dyn Error>> { Ok(storage.get_item(slot)?[3].as_int()) };
+        let player = |slot| -> Result<AccountId, Box<dyn Error>> {
+            let word = storage.get_item(slot)?;
+            Ok(AccountId::try_from([word[3], word[2]])?)
+        };
+        let hand = |slot| -> Result<Option<Hand>, Box<dyn Error>> {
+            match value(slot)? {
+                0 => Ok(None),
+                revealed => Hand::ALL
+                    .get(revealed as usize - 1)
+                    .copied()
+                    .map(Some)
+                    .ok_or_else(|| format!("invalid hand {}", revealed - 1).into()),
+            }
+        };
+
+        let (player1, player2) = (player(PLAYER1_SLOT)?, player(PLAYER2_SLOT)?);
+        let outcome = match value(WINNER_SLOT)? {
+            0 => Outcome::Pending,
+            1 => Outcome::Winner(player1),
+            2 => Outcome::Winner(player2),
+            DRAW => Outcome::Draw,
+            winner => return Err(format!("invalid winner {winner}").into()),
+        };
+
+        Ok(Self {
+            id: account.id(),
+            player1,
+            player2,
+            num_commitments: value(NUM_COMMITMENTS_SLOT)?,
+            hands: [hand(HAND1_SLOT)?, hand(HAND2_SLOT)?],
+            outcome,
+        })
+    }
+}
+
+/// Deploys a public game between `player1` and `player2`.
+pub async fn create_game(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player1: AccountId,
+    player2: AccountId,
+) -> Result<Account, Box<dyn Error>> {
+    if player1 == player2 {
+        return Err("a player cannot play against itself".into());
+    }
+
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let player_word = |player: AccountId| -> Word {
+        [ZERO, ZERO, player.suffix(), player.prefix().as_felt()].into()
+    };
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(player_word(player1)),
+            StorageSlot::Value(player_word(player2)),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the game contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `player` to the game `game_id`, carrying `word` as its inputs.
+pub fn game_note(
+    player: AccountId,
+    game_id: AccountId,
+    word: Word,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let inputs = NoteInputs::new(word.as_elements().to_vec())?;
+    let recipient = NoteRecipient::new(serial_num, script, inputs);
+    let metadata = NoteMetadata::new(
+        player,
+        NoteType::Public,
+        NoteTag::from_account_id(game_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::default(), metadata, recipient))
+}
+
+/// Has `player` send `word` to `game` with the note script `script`, and the game consume it.
+///
+/// The game is a `NoAuth` account, so any client tracking it can execute its transactions.
+async fn send_to_game(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player: AccountId,
+    game: &Game,
+    word: Word,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = game_note(player, game.id, word, script, serial_num)?;
+
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    client.submit_with_prover(player, request).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .build()?;
+    Ok(client.submit_with_prover(game.id, request).await?)
+}
+
+/// Registers the commitment of `hand` as the hand of `player`. `script` is the compiled
+/// [`COMMIT_NOTE_PATH`].
+pub async fn commit(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player: AccountId,
+    game: &Game,
+    hand: &HiddenHand,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    send_to_game(client, player, game, hand.commitment(), script).await
+}
+
+/// Reveals `hand`, which `player` committed to, to the game. `script` is the compiled
+/// [`REVEAL_NOTE_PATH`].
+///
+/// Fails without sending anything unless both players committed in `game`. A hand not matching
+/// the commitment is rejected by the game.
+pub async fn reveal(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player: AccountId,
+    game: &Game,
+    hand: &HiddenHand,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    if game.num_commitments < 2 {
+        return Err("both players must commit before revealing".into());
+    }
+    send_to_game(client, player, game, hand.to_word(), script).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hidden(hand: Hand, salt: u64) -> HiddenHand {
+        HiddenHand {
+            hand,
+            salt: [Felt::new(salt), Felt::new(salt + 1), Felt::new(salt + 2)],
+        }
+    }
+
+    #[test]
+    fn every_hand_beats_exactly_one_other() {
+        assert!(Hand::Rock.beats(Hand::Scissors));
+        assert!(Hand::Paper.beats(Hand::Rock));
+        assert!(Hand::Scissors.beats(Hand::Paper));
+
+        for hand in Hand::ALL {
+            assert!(!hand.beats(hand));
+            let beaten = Hand::ALL.iter().filter(|other| hand.beats(**other)).count();
+            assert_eq!(beaten, 1, "{hand:?}");
+        }
+    }
+
+    #[test]
+    fn commitment_depends_on_hand_and_salt() {
+        let commitment = hidden(Hand::Rock, 7).commitment();
+
+        assert_eq!(commitment, hidden(Hand::Rock, 7).commitment());
+        assert_ne!(commitment, hidden(Hand::Paper, 7).commitment());
+        assert_ne!(commitment, hidden(Hand::Rock, 8).commitment());
+    }
+
+    #[test]
+    fn hand_is_the_last_element_of_the_revealed_word() {
+        let word = hidden(Hand::Scissors, 7).to_word();
+
+        assert_eq!(word[3], Felt::new(2));
+        assert_eq!(&word.as_elements()[..3], &hidden(Hand::Scissors, 7).salt);
+    }
+}
@@ -0,0 +1,154 @@
+//! Note serial numbers derived from the context of a note instead of drawn at random.
+//!
+//! The serial number is the only part of a note that is not determined by what the note does:
+//! recipient, script, inputs and assets follow from the payment, the serial number keeps two
+//! otherwise identical notes apart and, being secret, keeps the nullifier of a note from being
+//! linked to it. Drawing it at random means the note can only be known by whoever drew it. A
+//! [`SerialDeriver`] computes it instead from the sender, a nonce and the purpose of the note,
+//! so that anybody knowing that context, the receiver included, can rebuild the exact same note
+//! and compute its id ahead of time.
+//!
+//! What the serial number no longer gets from randomness has to come from the context:
+//!
+//! - uniqueness: the same context always yields the same serial number, and the same payment
+//!   with it the same note and nullifier. A sender must never use a nonce twice for a purpose,
+//!   or only one of the two notes can ever be consumed;
+//! - privacy: whoever can derive the serial number of a note can compute its nullifier and tell
+//!   when it is consumed. A deriver keyed with a secret shared by sender and receiver keeps that
+//!   to them, [`SerialDeriver::public`] makes it available to anybody guessing the context.
+
+use miden_client::{
+    account::AccountId,
+    asset::Asset,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteTag,
+        NoteType,
+    },
+    Felt, Word,
+};
+use miden_lib::note::WellKnownNote;
+use miden_objects::{Hasher, NoteError};
+
+/// Derives note serial numbers from `(sender, nonce, purpose)`, keyed with a secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerialDeriver {
+    secret: Word,
+}
+
+impl SerialDeriver {
+    /// Creates a deriver keyed with `secret`, which only the parties of the notes should know.
+    pub fn new(secret: Word) -> Self {
+        Self { secret }
+    }
+
+    /// Creates a deriver without a secret: the serial numbers it derives can be computed by
+    /// anybody knowing the context of the note.
+    pub fn public() -> Self {
+        Self::new(Word::default())
+    }
+
+    /// Returns the serial number of the note `sender` creates with `nonce` for `purpose`.
+    pub fn serial_num(&self, sender: AccountId, nonce: u64, purpose: &str) -> Word {
+        let purpose = Hasher::hash(purpose.as_bytes());
+        let elements: Vec<Felt> = self
+            .secret
+            .iter()
+            .copied()
+            .chain([sender.prefix().as_felt(), sender.suffix(), Felt::new(nonce)])
+            .chain(purpose.iter().copied())
+            .collect();
+        Hasher::hash_elements(&elements)
+    }
+}
+
+/// Builds the P2ID note by which `sender` sends `assets` to `target`, with the serial number
+/// `serial_num`. Unlike the notes of `create_p2id_note`, it is fully determined by its arguments.
+pub fn p2id_note(
+    sender: AccountId,
+    target: AccountId,
+    assets: Vec<Asset>,
+    note_type: NoteType,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let recipient = NoteRecipient::new(
+        serial_num,
+        WellKnownNote::P2ID.script(),
+        NoteInputs::new(vec![target.suffix(), target.prefix().as_felt()])?,
+    );
+    let metadata = NoteMetadata::new(
+        sender,
+        note_type,
+        NoteTag::from_account_id(target),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{
+        account::{AccountIdVersion, AccountStorageMode, AccountType},
+        asset::FungibleAsset,
+    };
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn secret(value: u64) -> Word {
+        [Felt::new(value), Felt::new(0), Felt::new(0), Felt::new(0)].into()
+    }
+
+    #[test]
+    fn serial_numbers_depend_on_every_part_of_the_context() {
+        let deriver = SerialDeriver::new(secret(7));
+        let alice = account(1, AccountType::RegularAccountUpdatableCode);
+        let bob = account(2, AccountType::RegularAccountUpdatableCode);
+
+        let serial_num = deriver.serial_num(alice, 1, "invoice");
+        assert_eq!(deriver.serial_num(alice, 1, "invoice"), serial_num);
+        assert_ne!(deriver.serial_num(bob, 1, "invoice"), serial_num);
+        assert_ne!(deriver.serial_num(alice, 2, "invoice"), serial_num);
+        assert_ne!(deriver.serial_num(alice, 1, "refund"), serial_num);
+    }
+
+    #[test]
+    fn serial_numbers_depend_on_the_secret() {
+        let alice = account(1, AccountType::RegularAccountUpdatableCode);
+
+        let shared = SerialDeriver::new(secret(7)).serial_num(alice, 1, "invoice");
+        assert_ne!(
+            SerialDeriver::new(secret(8)).serial_num(alice, 1, "invoice"),
+            shared
+        );
+        assert_ne!(
+            SerialDeriver::public().serial_num(alice, 1, "invoice"),
+            shared
+        );
+    }
+
+    #[test]
+    fn p2id_notes_are_determined_by_their_context() {
+        let deriver = SerialDeriver::new(secret(7));
+        let alice = account(1, AccountType::RegularAccountUpdatableCode);
+        let bob = account(2, AccountType::RegularAccountUpdatableCode);
+        let faucet = account(3, AccountType::FungibleFaucet);
+        let assets = vec![FungibleAsset::new(faucet, 30).unwrap().into()];
+        let note = |nonce| {
+            let serial_num = deriver.serial_num(alice, nonce, "invoice");
+            p2id_note(alice, bob, assets.clone(), NoteType::Private, serial_num).unwrap()
+        };
+
+        assert_eq!(note(1).id(), note(1).id());
+        assert_eq!(note(1).nullifier(), note(1).nullifier());
+        assert_ne!(note(1).id(), note(2).id());
+    }
+}
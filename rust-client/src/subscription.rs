@@ -0,0 +1,215 @@
+//! Subscriptions paid by pull.
+//!
+//! A merchant cannot take tokens out of an account it does not own, so the subscriber
+//! authorizes the renewals up front: [`subscribe`] sends the merchant one P2IDE note per period,
+//! each carrying the price of one period. The note of a period is timelocked until the period
+//! starts, so the merchant may [`pull`] a renewal at any time during its period but never
+//! earlier. Its reclaim height is the end of the period: a renewal the merchant leaves alone
+//! lapses, and the subscriber takes it back like any overdue recallable payment, see
+//! [`crate::unclaimed`].
+//!
+//! Renewals are prepaid: the subscriber chooses how many periods to authorize, but cannot stop
+//! the merchant from pulling a period once it started.
+
+use std::error::Error;
+
+use miden_client::{
+    account::AccountId,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{create_p2ide_note, Note, NoteType},
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt,
+};
+use miden_objects::{asset::FungibleAsset, block::BlockNumber, NoteError};
+use rand::rngs::StdRng;
+
+use crate::cli::SubmitWithProver;
+
+/// The payment of one period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Renewal {
+    pub note: Note,
+    /// First block at which the merchant may pull the renewal.
+    pub due: BlockNumber,
+    /// First block at which the subscriber may take the renewal back.
+    pub lapses: BlockNumber,
+}
+
+/// A subscription authorized for a fixed number of periods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subscription {
+    pub subscriber: AccountId,
+    pub merchant: AccountId,
+    /// Blocks between two renewals.
+    pub period: u32,
+    /// One renewal per period, from the first period on.
+    pub renewals: Vec<Renewal>,
+}
+
+impl Subscription {
+    /// Builds the renewals of a subscription to `merchant` costing `price` every `period`
+    /// blocks, for `periods` periods from block `start` on.
+    pub fn new(
+        subscriber: AccountId,
+        merchant: AccountId,
+        price: FungibleAsset,
+        period: u32,
+        start: BlockNumber,
+        periods: u32,
+        rng: &mut impl FeltRng,
+    ) -> Result<Self, NoteError> {
+        let renewals = (0..periods)
+            .map(|index| {
+                let due = BlockNumber::from(start.as_u32() + index * period);
+                let lapses = BlockNumber::from(due.as_u32() + period);
+                let note = create_p2ide_note(
+                    subscriber,
+                    merchant,
+                    vec![price.into()],
+                    Some(lapses),
+                    Some(due),
+                    NoteType::Public,
+                    Felt::new(0),
+                    rng,
+                )?;
+                Ok(Renewal { note, due, lapses })
+            })
+            .collect::<Result<_, NoteError>>()?;
+
+        Ok(Self {
+            subscriber,
+            merchant,
+            period,
+            renewals,
+        })
+    }
+
+    /// Returns the renewal of the period containing `block_num`, if the subscription covers it.
+    pub fn current(&self, block_num: BlockNumber) -> Option<&Renewal> {
+        self.renewals
+            .iter()
+            .find(|renewal| renewal.due <= block_num && block_num < renewal.lapses)
+    }
+}
+
+/// Has `subscriber` authorize `periods` renewals of `price` to `merchant`, one every `period`
+/// blocks. The first period starts at the last synced block, so its renewal is due at once.
+pub async fn subscribe(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    subscriber: AccountId,
+    merchant: AccountId,
+    price: FungibleAsset,
+    period: u32,
+    periods: u32,
+) -> Result<(TransactionId, Subscription), Box<dyn Error>> {
+    let start = client.get_sync_height().await?;
+    let subscription = Subscription::new(
+        subscriber,
+        merchant,
+        price,
+        period,
+        start,
+        periods,
+        client.rng(),
+    )?;
+
+    let notes = subscription
+        .renewals
+        .iter()
+        .map(|renewal| OutputNote::Full(renewal.note.clone()))
+        .collect();
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(notes)
+        .build()?;
+    let tx_id = client.submit_with_prover(subscriber, request).await?;
+    Ok((tx_id, subscription))
+}
+
+/// Has `merchant` pull `renewal` into its account.
+///
+/// Fails before the renewal is due, since the note script rejects everybody until its timelock.
+pub async fn pull(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    merchant: AccountId,
+    renewal: &Renewal,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(renewal.note.clone(), None)])
+        .build()?;
+    Ok(client.submit_with_prover(merchant, request).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{
+        account::{AccountIdVersion, AccountStorageMode, AccountType},
+        crypto::rand::RpoRandomCoin,
+        testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+        Word,
+    };
+
+    use super::*;
+
+    fn account(seed: u8) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            AccountType::RegularAccountUpdatableCode,
+            AccountStorageMode::Public,
+        )
+    }
+
+    /// Three periods of 10 blocks, from block 100 on.
+    fn subscription() -> Subscription {
+        let faucet = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap();
+        Subscription::new(
+            account(1),
+            account(2),
+            FungibleAsset::new(faucet, 25).unwrap(),
+            10,
+            BlockNumber::from(100),
+            3,
+            &mut RpoRandomCoin::new(Word::default()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn renewals_fall_due_one_period_apart() {
+        let subscription = subscription();
+
+        let windows: Vec<(u32, u32)> = subscription
+            .renewals
+            .iter()
+            .map(|renewal| (renewal.due.as_u32(), renewal.lapses.as_u32()))
+            .collect();
+        assert_eq!(windows, vec![(100, 110), (110, 120), (120, 130)]);
+    }
+
+    #[test]
+    fn the_current_renewal_is_the_one_of_the_period_containing_the_block() {
+        let subscription = subscription();
+
+        assert_eq!(
+            subscription.current(100.into()),
+            Some(&subscription.renewals[0])
+        );
+        assert_eq!(
+            subscription.current(119.into()),
+            Some(&subscription.renewals[1])
+        );
+        assert_eq!(
+            subscription.current(120.into()),
+            Some(&subscription.renewals[2])
+        );
+    }
+
+    #[test]
+    fn no_renewal_is_current_outside_the_subscription() {
+        let subscription = subscription();
+
+        assert_eq!(subscription.current(99.into()), None);
+        assert_eq!(subscription.current(130.into()), None);
+    }
+}
@@ -0,0 +1,98 @@
+//! Callbacks run on what a sync brings in.
+//!
+//! `Client::sync_state` returns a [`SyncSummary`] listing what changed since the previous sync:
+//! transactions of the client which got committed, notes which arrived or got committed, notes
+//! which got consumed. [`SyncHooks`] wraps the sync and hands each of these to the callbacks
+//! registered for it, so an application reacts to them as they arrive instead of diffing the
+//! store itself.
+//!
+//! A summary only covers the changes since the previous sync, whoever ran it. Anything syncing
+//! the client behind the back of the hooks, such as [`crate::helpers::wait_for_tx`], swallows
+//! the changes it brings in, so an application relying on hooks should sync through them only.
+
+use miden_client::{
+    keystore::FilesystemKeyStore, note::NoteId, store::InputNoteRecord, sync::SyncSummary,
+    transaction::TransactionId, Client, ClientError,
+};
+use rand::rngs::StdRng;
+
+use crate::helpers::{sync_with_retry, DEFAULT_SYNC_ATTEMPTS, DEFAULT_SYNC_BACKOFF};
+
+/// Syncs a client and runs the callbacks registered for the changes it brings in.
+#[derive(Default)]
+pub struct SyncHooks {
+    on_transaction_committed: Vec<Box<dyn FnMut(&SyncSummary, TransactionId)>>,
+    on_note_received: Vec<Box<dyn FnMut(&SyncSummary, &InputNoteRecord)>>,
+    on_note_consumed: Vec<Box<dyn FnMut(&SyncSummary, NoteId)>>,
+}
+
+impl SyncHooks {
+    /// Returns hooks without any callback.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `callback` for every transaction of the client which got committed.
+    pub fn on_transaction_committed(
+        &mut self,
+        callback: impl FnMut(&SyncSummary, TransactionId) + 'static,
+    ) -> &mut Self {
+        self.on_transaction_committed.push(Box::new(callback));
+        self
+    }
+
+    /// Runs `callback` for every note the client received or saw committed, with its record.
+    pub fn on_note_received(
+        &mut self,
+        callback: impl FnMut(&SyncSummary, &InputNoteRecord) + 'static,
+    ) -> &mut Self {
+        self.on_note_received.push(Box::new(callback));
+        self
+    }
+
+    /// Runs `callback` for every tracked note which got consumed.
+    pub fn on_note_consumed(
+        &mut self,
+        callback: impl FnMut(&SyncSummary, NoteId) + 'static,
+    ) -> &mut Self {
+        self.on_note_consumed.push(Box::new(callback));
+        self
+    }
+
+    /// Syncs the client and runs the callbacks, in the order they were registered: first for
+    /// committed transactions, then received notes, then consumed notes. Returns the summary of
+    /// the sync.
+    pub async fn sync(
+        &mut self,
+        client: &mut Client<FilesystemKeyStore<StdRng>>,
+    ) -> Result<SyncSummary, ClientError> {
+        let summary = sync_with_retry(client, DEFAULT_SYNC_ATTEMPTS, DEFAULT_SYNC_BACKOFF).await?;
+
+        for &tx_id in &summary.committed_transactions {
+            for callback in &mut self.on_transaction_committed {
+                callback(&summary, tx_id);
+            }
+        }
+
+        let received = summary
+            .new_public_notes
+            .iter()
+            .chain(&summary.committed_notes);
+        for &note_id in received {
+            let Some(record) = client.get_input_note(note_id).await? else {
+                continue;
+            };
+            for callback in &mut self.on_note_received {
+                callback(&summary, &record);
+            }
+        }
+
+        for &note_id in &summary.consumed_notes {
+            for callback in &mut self.on_note_consumed {
+                callback(&summary, note_id);
+            }
+        }
+
+        Ok(summary)
+    }
+}
@@ -0,0 +1,637 @@
+//! Off-chain side of the tic-tac-toe game (`masm/accounts/tic_tac_toe.masm`).
+//!
+//! Every game is its own contract account, deployed with both players as constructor
+//! arguments. A player moves by sending a `make_a_move_note` to the game: the contract takes
+//! the mover from the note sender, so nobody can play on behalf of someone else. A full board
+//! without a winner is finalized by either player with a `claim_draw_note`. The [`state`]
+//! module decodes the storage of a game account into a [`GameState`].
+//!
+//! A game can be wagered: before the first move, each player sends the same fungible stake with
+//! a `place_wager_note`. The contract keeps the stakes in its vault and pays them out with P2ID
+//! notes, both to the winner or one back to each player on a draw.
+//!
+//! Every turn runs against a clock of a fixed number of blocks, set at deployment. The clock of
+//! a turn starts with the previous move, or with the deployment and the stakes before the first
+//! move. Once it runs out, the player waiting for the move can win the game with a
+//! `claim_timeout_note`. The player to move can still move as long as nobody claimed the game.
+//!
+//! A game can be ranked: deployed with [`ranked_deployer`], it reports its winner to a
+//! tournament [`ranking`](crate::ranking) with a note created in the same transaction as the
+//! winning move or the timeout claim.
+//!
+//! Instead of holding the stakes itself, a game can leave them to a
+//! [`wager_escrow`](crate::wager_escrow) deployed next to it. Deployed with
+//! [`escrowed_deployer`], the game reports its winner to the escrow like a ranked game reports
+//! to its ranking, and the escrow reads the players and the result of the game through FPI.
+//!
+//! A game deployed with [`deposit_deployer`] makes every move carry a deposit, sent with a
+//! `deposit_move_note`. The game consumes the note even when the move breaks the rules: a valid
+//! move gets its deposit back with a P2ID note, while an invalid one leaves the board untouched
+//! and the game keeps the deposit. Flooding a public game with invalid moves costs the sender,
+//! as in the `refundable_deposit` tutorial.
+
+use std::{error::Error, fs, io};
+
+use miden_client::{
+    account::AccountId,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client,
+};
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{StorageMap, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    Felt, NoteError, Word, ZERO,
+};
+use rand::rngs::StdRng;
+
+use crate::{
+    cli::SubmitWithProver, deployer::Deployer, helpers::create_library, ranking, wager_escrow,
+};
+
+pub mod state;
+
+pub use state::{GameState, Mark};
+
+/// Path under which the game contract is linked into the move note.
+pub const LIBRARY_PATH: &str = "external_contract::tic_tac_toe";
+/// Location of the game contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/tic_tac_toe.masm";
+/// Location of the note playing a move, relative to `rust-client`.
+pub const MOVE_NOTE_PATH: &str = "../masm/notes/make_a_move_note.masm";
+/// Location of the note claiming a draw, relative to `rust-client`.
+pub const CLAIM_DRAW_NOTE_PATH: &str = "../masm/notes/claim_draw_note.masm";
+/// Location of the note staking an asset on a game, relative to `rust-client`.
+pub const WAGER_NOTE_PATH: &str = "../masm/notes/place_wager_note.masm";
+/// Location of the note claiming a game whose opponent ran out of time, relative to
+/// `rust-client`.
+pub const CLAIM_TIMEOUT_NOTE_PATH: &str = "../masm/notes/claim_timeout_note.masm";
+/// Location of the note playing a move with a deposit, relative to `rust-client`.
+pub const DEPOSIT_MOVE_NOTE_PATH: &str = "../masm/notes/deposit_move_note.masm";
+
+/// Storage slot holding `[player_prefix, player_suffix, 0, 0]` of the player placing X.
+pub const PLAYER1_SLOT: u8 = 0;
+/// Storage slot holding `[player_prefix, player_suffix, 0, 0]` of the player placing O.
+pub const PLAYER2_SLOT: u8 = 1;
+/// Storage map from a field index to the mark placed on it.
+pub const BOARD_SLOT: u8 = 2;
+/// Storage slot counting the moves played.
+pub const MOVES_SLOT: u8 = 3;
+/// Storage slot holding the mark of the winner, [`DRAW`] once a draw was claimed, 0 while the
+/// game is running.
+pub const WINNER_SLOT: u8 = 4;
+/// Storage slot holding the asset each player stakes, empty until the first stake.
+pub const WAGER_SLOT: u8 = 6;
+/// Storage slot counting the players who staked.
+pub const STAKES_SLOT: u8 = 7;
+/// Storage map from a mark to the tag of the notes paying out its player.
+pub const PAYOUT_TAGS_SLOT: u8 = 8;
+/// Storage slot holding the number of blocks a player has to move.
+pub const TURN_TIMEOUT_SLOT: u8 = 10;
+/// Storage slot holding the block at which the clock of the current turn started.
+pub const CLOCK_START_SLOT: u8 = 11;
+/// Storage slot holding `[report_tag, ranked, 0, 0]` of the notes reporting the winner to a
+/// ranking or a wager escrow.
+pub const REPORT_TAG_SLOT: u8 = 12;
+/// Storage slot holding the script root of the notes reporting the winner to a ranking or a
+/// wager escrow.
+pub const REPORT_SCRIPT_ROOT_SLOT: u8 = 13;
+/// Storage slot holding the deposit every move must carry, as a fungible asset of the minimum
+/// amount, empty for a game whose moves need no deposit.
+pub const MOVE_DEPOSIT_SLOT: u8 = 14;
+/// Storage slot holding the total amount of the deposits kept from moves breaking the rules.
+pub const KEPT_DEPOSITS_SLOT: u8 = 15;
+
+/// Value of [`WINNER_SLOT`] for a game finished without a winner.
+pub const DRAW: u64 = 3;
+
+/// Number of fields of the board.
+pub const NUM_FIELDS: usize = 9;
+
+/// Turn timeout of the games deployed by the tutorials, in blocks.
+pub const DEFAULT_TURN_TIMEOUT: u32 = 100;
+
+impl GameState {
+    /// Returns the notes the game creates when the player whose turn it is plays `field`: the
+    /// payout of both stakes if the move wins a wagered game, none otherwise.
+    pub fn payouts_for_move(&self, field: usize) -> Result<Vec<Note>, Box<dyn Error>> {
+        let (Some(stake), Some(mark)) = (self.stake, self.next_mark()) else {
+            return Ok(vec![]);
+        };
+        if !self.is_wagered() || !self.wins_with(field) {
+            return Ok(vec![]);
+        }
+        Ok(vec![payout_note(
+            self.id,
+            self.player(mark),
+            stake.add(stake)?,
+        )?])
+    }
+
+    /// Returns the notes the game creates when `player` claims it on timeout: the payout of
+    /// both stakes if the game is wagered, none otherwise.
+    pub fn payouts_for_timeout(&self, player: AccountId) -> Result<Vec<Note>, Box<dyn Error>> {
+        match self.stake {
+            Some(stake) if self.is_wagered() => {
+                Ok(vec![payout_note(self.id, player, stake.add(stake)?)?])
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Returns the note reporting the player placing `winner` as the winner to the ranking of a
+    /// ranked game, or to the escrow of an escrowed game, `None` for a game reporting to nobody.
+    /// `script` is the compiled [`ranking::REPORT_NOTE_PATH`] or
+    /// [`wager_escrow::SETTLE_NOTE_PATH`].
+    pub fn report_for_win(
+        &self,
+        winner: Mark,
+        script: NoteScript,
+    ) -> Result<Option<Note>, NoteError> {
+        let Some(tag) = self.report_tag else {
+            return Ok(None);
+        };
+        ranking::report_note(
+            self.id,
+            self.player(winner),
+            self.player(winner.opponent()),
+            tag,
+            script,
+        )
+        .map(Some)
+    }
+
+    /// Returns the notes the game creates when the draw is claimed: one stake back to each
+    /// player of a wagered game, none otherwise.
+    pub fn payouts_for_draw(&self) -> Result<Vec<Note>, Box<dyn Error>> {
+        match self.stake {
+            Some(stake) if self.is_wagered() => Ok(vec![
+                payout_note(self.id, self.player1, stake)?,
+                payout_note(self.id, self.player2, stake)?,
+            ]),
+            _ => Ok(vec![]),
+        }
+    }
+}
+
+/// Returns the initial storage of a game, reporting its winner with notes tagged `report_tag`
+/// and running `report_script_root` for a ranked game, and requiring `move_deposit` with every
+/// move if given.
+fn initial_storage(
+    report_tag: Option<NoteTag>,
+    report_script_root: Word,
+    move_deposit: Option<FungibleAsset>,
+) -> Vec<StorageSlot> {
+    let report = match report_tag {
+        Some(tag) => [ZERO, ZERO, Felt::new(1), tag.into()].into(),
+        None => Word::default(),
+    };
+    vec![
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Map(StorageMap::new()),
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Map(StorageMap::new()),
+        StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Value(report),
+        StorageSlot::Value(report_script_root),
+        StorageSlot::Value(move_deposit.map_or(Word::default(), |asset| Asset::from(asset).into())),
+        StorageSlot::Value(Word::default()),
+    ]
+}
+
+/// Returns the deployer of a game between `player1`, who places X and moves first, and
+/// `player2`, each having `turn_timeout` blocks to move.
+pub fn deployer(player1: AccountId, player2: AccountId, turn_timeout: u32) -> io::Result<Deployer> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    Ok(Deployer::new(code, LIBRARY_PATH)
+        .with_storage(initial_storage(None, Word::default(), None))
+        .with_arg(player1.prefix().as_felt())
+        .with_arg(player1.suffix())
+        .with_arg(player2.prefix().as_felt())
+        .with_arg(player2.suffix())
+        .with_arg(turn_timeout))
+}
+
+/// Returns the deployer of a game like [`deployer`] whose moves must carry a deposit of at least
+/// `move_deposit`, sent with a [`DEPOSIT_MOVE_NOTE_PATH`] note.
+pub fn deposit_deployer(
+    player1: AccountId,
+    player2: AccountId,
+    turn_timeout: u32,
+    move_deposit: FungibleAsset,
+) -> io::Result<Deployer> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    Ok(Deployer::new(code, LIBRARY_PATH)
+        .with_storage(initial_storage(None, Word::default(), Some(move_deposit)))
+        .with_arg(player1.prefix().as_felt())
+        .with_arg(player1.suffix())
+        .with_arg(player2.prefix().as_felt())
+        .with_arg(player2.suffix())
+        .with_arg(turn_timeout))
+}
+
+/// Returns the deployer of a game like [`deployer`] that reports its winner to the ranking
+/// `ranking_id`, with notes running `report_script`.
+///
+/// The ranking only accepts the report once its organizer registered the deployed game.
+pub fn ranked_deployer(
+    player1: AccountId,
+    player2: AccountId,
+    turn_timeout: u32,
+    ranking_id: AccountId,
+    report_script: &NoteScript,
+) -> io::Result<Deployer> {
+    reporting_deployer(
+        player1,
+        player2,
+        turn_timeout,
+        ranking::report_tag(ranking_id),
+        report_script,
+    )
+}
+
+/// Returns the deployer of a game like [`deployer`] whose stakes are held by the wager escrow
+/// `escrow_id`, to which it reports its winner with notes running `settle_script`, the compiled
+/// [`wager_escrow::SETTLE_NOTE_PATH`].
+///
+/// The escrow only accepts the report once it was deployed for the deployed game, see
+/// [`wager_escrow::escrow_id`].
+pub fn escrowed_deployer(
+    player1: AccountId,
+    player2: AccountId,
+    turn_timeout: u32,
+    escrow_id: AccountId,
+    settle_script: &NoteScript,
+) -> io::Result<Deployer> {
+    reporting_deployer(
+        player1,
+        player2,
+        turn_timeout,
+        NoteTag::from_account_id(escrow_id),
+        settle_script,
+    )
+}
+
+/// Returns the deployer of a game reporting its winner with notes tagged `report_tag` and
+/// running `report_script`.
+fn reporting_deployer(
+    player1: AccountId,
+    player2: AccountId,
+    turn_timeout: u32,
+    report_tag: NoteTag,
+    report_script: &NoteScript,
+) -> io::Result<Deployer> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let storage = initial_storage(Some(report_tag), report_script.root(), None);
+    Ok(Deployer::new(code, LIBRARY_PATH)
+        .with_storage(storage)
+        .with_arg(player1.prefix().as_felt())
+        .with_arg(player1.suffix())
+        .with_arg(player2.prefix().as_felt())
+        .with_arg(player2.suffix())
+        .with_arg(turn_timeout))
+}
+
+/// Compiles the note script at `note_path`, linked against the game contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `player` to the game `game_id`.
+fn game_note(
+    player: AccountId,
+    game_id: AccountId,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let metadata = NoteMetadata::new(
+        player,
+        NoteType::Public,
+        NoteTag::from_account_id(game_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(
+        NoteAssets::new(assets)?,
+        metadata,
+        NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?),
+    ))
+}
+
+/// Builds the note by which `player` places its mark on `field` of the game `game_id`.
+pub fn move_note(
+    player: AccountId,
+    game_id: AccountId,
+    field: usize,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    game_note(
+        player,
+        game_id,
+        vec![Felt::new(field as u64)],
+        vec![],
+        script,
+        serial_num,
+    )
+}
+
+/// Builds the note by which `player` places its mark on `field` of the game `game_id`, carrying
+/// `deposit`. The game refunds a valid move with notes tagged for the account of `player`.
+pub fn deposit_move_note(
+    player: AccountId,
+    game_id: AccountId,
+    field: usize,
+    deposit: FungibleAsset,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    game_note(
+        player,
+        game_id,
+        vec![
+            Felt::new(field as u64),
+            NoteTag::from_account_id(player).into(),
+        ],
+        vec![deposit.into()],
+        script,
+        serial_num,
+    )
+}
+
+/// Builds the P2ID note by which the game `game_id` refunds `deposit` to `player` for the valid
+/// move sent with the serial number `move_serial_num`.
+///
+/// The note script derives the serial number of the refund by adding one to the last element of
+/// its own.
+pub fn deposit_refund_note(
+    game_id: AccountId,
+    player: AccountId,
+    deposit: FungibleAsset,
+    move_serial_num: Word,
+) -> Result<Note, NoteError> {
+    let serial_num = [
+        move_serial_num[0],
+        move_serial_num[1],
+        move_serial_num[2],
+        move_serial_num[3] + Felt::new(1),
+    ]
+    .into();
+    let recipient = NoteRecipient::new(
+        serial_num,
+        WellKnownNote::P2ID.script(),
+        NoteInputs::new(vec![player.suffix(), player.prefix().as_felt()])?,
+    );
+    let metadata = NoteMetadata::new(
+        game_id,
+        NoteType::Public,
+        NoteTag::from_account_id(player),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(
+        NoteAssets::new(vec![deposit.into()])?,
+        metadata,
+        recipient,
+    ))
+}
+
+/// Builds the note by which `player` claims the draw of the game `game_id`.
+pub fn draw_claim_note(
+    player: AccountId,
+    game_id: AccountId,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    game_note(player, game_id, vec![], vec![], script, serial_num)
+}
+
+/// Builds the note by which `player` claims the game `game_id` after its opponent ran out of
+/// time.
+pub fn timeout_claim_note(
+    player: AccountId,
+    game_id: AccountId,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    game_note(player, game_id, vec![], vec![], script, serial_num)
+}
+
+/// Builds the note by which `player` stakes `stake` on the game `game_id`. The game pays
+/// `player` with notes tagged for its account.
+pub fn wager_note(
+    player: AccountId,
+    game_id: AccountId,
+    stake: FungibleAsset,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    game_note(
+        player,
+        game_id,
+        vec![NoteTag::from_account_id(player).into()],
+        vec![stake.into()],
+        script,
+        serial_num,
+    )
+}
+
+/// Builds the P2ID note by which the game `game_id` pays `asset` to `player`.
+///
+/// The contract derives the serial number from the id of the game, which makes the note
+/// predictable: the client needs its recipient to accept it as an output of the transaction.
+pub fn payout_note(
+    game_id: AccountId,
+    player: AccountId,
+    asset: FungibleAsset,
+) -> Result<Note, NoteError> {
+    let serial_num = [ZERO, ZERO, game_id.suffix(), game_id.prefix().as_felt()].into();
+    let recipient = NoteRecipient::new(
+        serial_num,
+        WellKnownNote::P2ID.script(),
+        NoteInputs::new(vec![player.suffix(), player.prefix().as_felt()])?,
+    );
+    let metadata = NoteMetadata::new(
+        game_id,
+        NoteType::Public,
+        NoteTag::from_account_id(player),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(
+        NoteAssets::new(vec![asset.into()])?,
+        metadata,
+        recipient,
+    ))
+}
+
+/// Has `player` create `note`, then has the game consume it right away, creating `outputs`.
+///
+/// The game is a `NoAuth` account, so the client of the player can execute its transaction as
+/// long as it tracks the game. Returns the id of the game transaction.
+async fn submit_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player: AccountId,
+    game_id: AccountId,
+    note: Note,
+    outputs: Vec<Note>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    client.submit_with_prover(player, request).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .expected_output_recipients(
+            outputs
+                .iter()
+                .map(|output| output.recipient().clone())
+                .collect(),
+        )
+        .build()?;
+    Ok(client.submit_with_prover(game_id, request).await?)
+}
+
+/// Compiles the script of the notes by which `game` reports its winner: the report of a ranked
+/// game, or the settlement of an escrowed game.
+fn report_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    game: &GameState,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let scripts = [
+        ranking::compile_note_script(client, ranking::REPORT_NOTE_PATH)?,
+        wager_escrow::compile_note_script(client, wager_escrow::SETTLE_NOTE_PATH)?,
+    ];
+    scripts
+        .into_iter()
+        .find(|script| script.root() == game.report_script_root)
+        .ok_or_else(|| "the game reports its winner with an unknown note script".into())
+}
+
+/// Returns the report a ranked or escrowed `game` sends when the player placing `winner` wins,
+/// none for a game reporting to nobody or without a winner.
+fn reports_for_win(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    game: &GameState,
+    winner: Option<Mark>,
+) -> Result<Vec<Note>, Box<dyn Error>> {
+    match winner {
+        Some(mark) if game.report_tag.is_some() => {
+            let script = report_script(client, game)?;
+            Ok(game.report_for_win(mark, script)?.into_iter().collect())
+        }
+        _ => Ok(vec![]),
+    }
+}
+
+/// Plays a move of `player` on `field` of `game`. `script` is the compiled
+/// [`MOVE_NOTE_PATH`].
+pub async fn play_move(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    game: &GameState,
+    player: AccountId,
+    field: usize,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = move_note(player, game.id, field, script, serial_num)?;
+    let mut outputs = game.payouts_for_move(field)?;
+    let winner = game.next_mark().filter(|_| game.wins_with(field));
+    outputs.extend(reports_for_win(client, game, winner)?);
+    submit_note(client, player, game.id, note, outputs).await
+}
+
+/// Plays a move of `player` on `field` of `game`, whose moves must carry a deposit, attaching
+/// the minimum deposit. `script` is the compiled [`DEPOSIT_MOVE_NOTE_PATH`].
+///
+/// The game consumes the note whether or not the move is valid, so the transaction succeeds
+/// either way: a valid move is played and refunded, an invalid one costs the deposit.
+pub async fn play_deposit_move(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    game: &GameState,
+    player: AccountId,
+    field: usize,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let deposit = game
+        .move_deposit
+        .ok_or("the moves of the game take no deposit")?;
+    let serial_num = client.rng().draw_word();
+    let note = deposit_move_note(player, game.id, field, deposit, script, serial_num)?;
+
+    let mut outputs = vec![];
+    if game.is_valid_move(player, field) {
+        outputs.extend(game.payouts_for_move(field)?);
+        let winner = game.next_mark().filter(|_| game.wins_with(field));
+        outputs.extend(reports_for_win(client, game, winner)?);
+        outputs.push(deposit_refund_note(game.id, player, deposit, serial_num)?);
+    }
+    submit_note(client, player, game.id, note, outputs).await
+}
+
+/// Stakes `stake` on `game_id` on behalf of `player`. `script` is the compiled
+/// [`WAGER_NOTE_PATH`].
+pub async fn place_wager(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    player: AccountId,
+    game_id: AccountId,
+    stake: FungibleAsset,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = wager_note(player, game_id, stake, script, serial_num)?;
+    submit_note(client, player, game_id, note, vec![]).await
+}
+
+/// Finishes `game`, whose board is full without a winner, on behalf of `player`. `script` is
+/// the compiled [`CLAIM_DRAW_NOTE_PATH`].
+pub async fn submit_draw_claim(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    game: &GameState,
+    player: AccountId,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = draw_claim_note(player, game.id, script, serial_num)?;
+    let payouts = game.payouts_for_draw()?;
+    submit_note(client, player, game.id, note, payouts).await
+}
+
+/// Claims `game`, whose opponent of `player` ran out of time. `script` is the compiled
+/// [`CLAIM_TIMEOUT_NOTE_PATH`].
+pub async fn submit_timeout_claim(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    game: &GameState,
+    player: AccountId,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = timeout_claim_note(player, game.id, script, serial_num)?;
+    let mut outputs = game.payouts_for_timeout(player)?;
+    outputs.extend(reports_for_win(client, game, game.mark_of(player))?);
+    submit_note(client, player, game.id, note, outputs).await
+}
@@ -0,0 +1,381 @@
+//! Typed view of the storage of a game account.
+//!
+//! The contract keeps the board in a storage map keyed by field index and everything else in
+//! value slots. [`GameState`] decodes all of it in one place, so the binaries and the tests
+//! never build storage keys by hand.
+
+use std::{error::Error, fmt};
+
+use miden_client::{
+    account::{Account, AccountId},
+    keystore::FilesystemKeyStore,
+    note::NoteTag,
+    Client,
+};
+use miden_objects::{
+    asset::{Asset, FungibleAsset},
+    Word,
+};
+use rand::rngs::StdRng;
+
+use super::{
+    BOARD_SLOT, CLOCK_START_SLOT, DRAW, KEPT_DEPOSITS_SLOT, MOVES_SLOT, MOVE_DEPOSIT_SLOT,
+    NUM_FIELDS, PLAYER1_SLOT, PLAYER2_SLOT, REPORT_SCRIPT_ROOT_SLOT, REPORT_TAG_SLOT, STAKES_SLOT,
+    TURN_TIMEOUT_SLOT, WAGER_SLOT, WINNER_SLOT,
+};
+use crate::storage::{felt_key, read_account_id_slot, read_map_u64, read_u64_slot};
+
+/// Fields forming a line, in the order the contract checks them.
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+/// Mark placed on the board by a player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    /// Placed by player 1, who moves first.
+    X,
+    /// Placed by player 2.
+    O,
+}
+
+impl Mark {
+    /// Decodes a mark as stored by the contract.
+    fn from_value(value: u64) -> Option<Self> {
+        match value {
+            1 => Some(Self::X),
+            2 => Some(Self::O),
+            _ => None,
+        }
+    }
+
+    /// Returns the mark of the other player.
+    pub fn opponent(self) -> Self {
+        match self {
+            Self::X => Self::O,
+            Self::O => Self::X,
+        }
+    }
+}
+
+impl fmt::Display for Mark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::X => write!(f, "X"),
+            Self::O => write!(f, "O"),
+        }
+    }
+}
+
+/// State of a game, read from a copy of its account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameState {
+    pub id: AccountId,
+    pub player1: AccountId,
+    pub player2: AccountId,
+    pub board: [Option<Mark>; NUM_FIELDS],
+    pub moves: u64,
+    pub winner: Option<Mark>,
+    /// Whether a player claimed the draw of a full board without a winner.
+    pub draw: bool,
+    /// Asset staked by each player, once the first stake was placed.
+    pub stake: Option<FungibleAsset>,
+    /// Number of players who staked.
+    pub stakes: u64,
+    /// Number of blocks a player has to move.
+    pub turn_timeout: u64,
+    /// Block at which the clock of the current turn started.
+    pub clock_start: u64,
+    /// Tag of the notes reporting the winner to the ranking of a ranked game, or to the wager
+    /// escrow of an escrowed game.
+    pub report_tag: Option<NoteTag>,
+    /// Script root of the notes reporting the winner.
+    pub report_script_root: Word,
+    /// Faucet and minimum amount of the deposit every move must carry, `None` for a game whose
+    /// moves need no deposit.
+    pub move_deposit: Option<FungibleAsset>,
+    /// Total amount of the deposits kept from moves breaking the rules.
+    pub kept_deposits: u64,
+}
+
+/// Decodes a slot holding a fungible asset, `None` while it is empty.
+fn fungible_asset(word: Word) -> Result<Option<FungibleAsset>, Box<dyn Error>> {
+    if word == Word::default() {
+        return Ok(None);
+    }
+    match Asset::try_from(word)? {
+        Asset::Fungible(asset) => Ok(Some(asset)),
+        Asset::NonFungible(_) => Err("the slot holds a non-fungible asset".into()),
+    }
+}
+
+impl GameState {
+    /// Decodes the storage of a deployed game account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+
+        let mut board = [None; NUM_FIELDS];
+        for (field, mark) in board.iter_mut().enumerate() {
+            let value = read_map_u64(account, BOARD_SLOT, felt_key(field as u64))?;
+            *mark = Mark::from_value(value);
+        }
+
+        let winner = read_u64_slot(account, WINNER_SLOT)?;
+        let report = storage.get_item(REPORT_TAG_SLOT)?;
+        let report_tag =
+            (report[2].as_int() == 1).then(|| NoteTag::from(report[3].as_int() as u32));
+        Ok(Self {
+            id: account.id(),
+            player1: read_account_id_slot(account, PLAYER1_SLOT)?,
+            player2: read_account_id_slot(account, PLAYER2_SLOT)?,
+            board,
+            moves: read_u64_slot(account, MOVES_SLOT)?,
+            winner: Mark::from_value(winner),
+            draw: winner == DRAW,
+            stake: fungible_asset(storage.get_item(WAGER_SLOT)?)?,
+            stakes: read_u64_slot(account, STAKES_SLOT)?,
+            turn_timeout: read_u64_slot(account, TURN_TIMEOUT_SLOT)?,
+            clock_start: read_u64_slot(account, CLOCK_START_SLOT)?,
+            report_tag,
+            report_script_root: storage.get_item(REPORT_SCRIPT_ROOT_SLOT)?,
+            move_deposit: fungible_asset(storage.get_item(MOVE_DEPOSIT_SLOT)?)?,
+            kept_deposits: read_u64_slot(account, KEPT_DEPOSITS_SLOT)?,
+        })
+    }
+
+    /// Reads the game `game_id` as last synced by `client`, which must track it.
+    pub async fn load(
+        client: &Client<FilesystemKeyStore<StdRng>>,
+        game_id: AccountId,
+    ) -> Result<Self, Box<dyn Error>> {
+        let record = client
+            .get_account(game_id)
+            .await?
+            .ok_or("the game is not tracked by the client")?;
+        Self::from_account(record.account())
+    }
+
+    /// Returns whether both players staked, so that the result of the game is paid out.
+    pub fn is_wagered(&self) -> bool {
+        self.stakes == 2
+    }
+
+    /// Returns whether the game has a winner or the board is full.
+    pub fn is_over(&self) -> bool {
+        self.winner.is_some() || self.moves as usize >= NUM_FIELDS
+    }
+
+    /// Returns whether the game is over and its result is recorded by the contract: a winner,
+    /// or a draw that was claimed.
+    pub fn is_finalized(&self) -> bool {
+        self.winner.is_some() || self.draw
+    }
+
+    /// Returns whether the board is full without a winner and the draw is yet to be claimed.
+    pub fn can_claim_draw(&self) -> bool {
+        self.is_over() && !self.is_finalized()
+    }
+
+    /// Returns the mark of the player whose turn it is, or `None` once the game is over.
+    pub fn next_mark(&self) -> Option<Mark> {
+        if self.is_over() {
+            None
+        } else if self.moves % 2 == 0 {
+            Some(Mark::X)
+        } else {
+            Some(Mark::O)
+        }
+    }
+
+    /// Returns the first block at which the player waiting for a move can claim the game.
+    pub fn timeout_block(&self) -> u64 {
+        self.clock_start + self.turn_timeout
+    }
+
+    /// Returns the player waiting for the opponent to move, or `None` once the game is over or
+    /// while a stake is missing.
+    pub fn waiting_player(&self) -> Option<AccountId> {
+        if self.stakes == 1 {
+            return None;
+        }
+        match self.next_mark()? {
+            Mark::X => Some(self.player2),
+            Mark::O => Some(self.player1),
+        }
+    }
+
+    /// Returns whether `player` can claim the game at `block_num`, its opponent having run out
+    /// of time.
+    pub fn can_claim_timeout(&self, player: AccountId, block_num: u64) -> bool {
+        self.waiting_player() == Some(player) && block_num >= self.timeout_block()
+    }
+
+    /// Returns the player placing `mark`.
+    pub fn player(&self, mark: Mark) -> AccountId {
+        match mark {
+            Mark::X => self.player1,
+            Mark::O => self.player2,
+        }
+    }
+
+    /// Returns the mark placed by `account_id`, if it plays this game.
+    pub fn mark_of(&self, account_id: AccountId) -> Option<Mark> {
+        if account_id == self.player1 {
+            Some(Mark::X)
+        } else if account_id == self.player2 {
+            Some(Mark::O)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether `field` is on the board and still empty.
+    pub fn is_free(&self, field: usize) -> bool {
+        field < NUM_FIELDS && self.board[field].is_none()
+    }
+
+    /// Returns whether `player` may play `field`: the game is running with both stakes or none,
+    /// it is the turn of `player` and `field` is free. A move carrying a deposit gets it back
+    /// exactly when this holds.
+    pub fn is_valid_move(&self, player: AccountId, field: usize) -> bool {
+        self.stakes != 1
+            && self.next_mark().map(|mark| self.player(mark)) == Some(player)
+            && self.is_free(field)
+    }
+
+    /// Returns whether the player whose turn it is wins by playing `field`.
+    pub fn wins_with(&self, field: usize) -> bool {
+        let Some(mark) = self.next_mark() else {
+            return false;
+        };
+        LINES
+            .iter()
+            .filter(|line| line.contains(&field))
+            .any(|line| {
+                line.iter()
+                    .all(|&other| other == field || self.board[other] == Some(mark))
+            })
+    }
+}
+
+/// Renders the board, showing the index of every empty field.
+impl fmt::Display for GameState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (row, fields) in self.board.chunks(3).enumerate() {
+            if row > 0 {
+                writeln!(f, "---+---+---")?;
+            }
+            let cells: Vec<String> = fields
+                .iter()
+                .enumerate()
+                .map(|(column, mark)| match mark {
+                    Some(mark) => mark.to_string(),
+                    None => (row * 3 + column).to_string(),
+                })
+                .collect();
+            writeln!(f, " {} ", cells.join(" | "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::{AccountIdVersion, AccountStorageMode, AccountType};
+
+    use super::*;
+
+    fn player(seed: u8) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            AccountType::RegularAccountUpdatableCode,
+            AccountStorageMode::Public,
+        )
+    }
+
+    /// Returns a fresh game in which `fields` were played in order, X first.
+    fn play(fields: &[usize]) -> GameState {
+        let mut game = GameState {
+            id: player(0),
+            player1: player(1),
+            player2: player(2),
+            board: [None; NUM_FIELDS],
+            moves: 0,
+            winner: None,
+            draw: false,
+            stake: None,
+            stakes: 0,
+            turn_timeout: 10,
+            clock_start: 0,
+            report_tag: None,
+            report_script_root: Word::default(),
+            move_deposit: None,
+            kept_deposits: 0,
+        };
+        for &field in fields {
+            let mark = game.next_mark().unwrap();
+            if game.wins_with(field) {
+                game.winner = Some(mark);
+            }
+            game.board[field] = Some(mark);
+            game.moves += 1;
+        }
+        game
+    }
+
+    #[test]
+    fn players_alternate_until_a_line_is_completed() {
+        let game = play(&[0, 3, 1]);
+        assert_eq!(game.next_mark(), Some(Mark::O));
+        assert_eq!(game.waiting_player(), Some(game.player1));
+        assert!(!game.wins_with(4));
+        assert!(game.is_free(4) && !game.is_free(3));
+
+        let game = play(&[0, 3, 1, 4, 2]);
+        assert_eq!(game.winner, Some(Mark::X));
+        assert_eq!(game.next_mark(), None);
+        assert!(game.is_over() && game.is_finalized());
+    }
+
+    #[test]
+    fn full_board_waits_for_the_draw_claim() {
+        let game = play(&[0, 1, 2, 4, 3, 5, 7, 6, 8]);
+        assert_eq!(game.winner, None);
+        assert!(game.can_claim_draw());
+        assert_eq!(game.waiting_player(), None);
+        assert_eq!(game.to_string().lines().next(), Some(" X | O | X "));
+    }
+
+    #[test]
+    fn only_the_waiting_player_claims_a_timeout() {
+        let game = play(&[4]);
+        assert_eq!(game.timeout_block(), 10);
+        assert!(game.can_claim_timeout(game.player1, 10));
+        assert!(!game.can_claim_timeout(game.player1, 9));
+        assert!(!game.can_claim_timeout(game.player2, 10));
+        assert_eq!(
+            game.mark_of(game.player2).map(Mark::opponent),
+            Some(Mark::X)
+        );
+    }
+
+    #[test]
+    fn only_the_moves_following_the_rules_are_valid() {
+        let game = play(&[0, 3]);
+        assert!(game.is_valid_move(game.player1, 1));
+        assert!(!game.is_valid_move(game.player2, 1));
+        assert!(!game.is_valid_move(game.player1, 3));
+        assert!(!game.is_valid_move(game.player1, NUM_FIELDS));
+
+        let game = play(&[0, 3, 1, 4, 2]);
+        assert!(!game.is_valid_move(game.player2, 5));
+    }
+}
@@ -0,0 +1,287 @@
+//! Off-chain side of the token registry (`masm/accounts/token_registry.masm`).
+//!
+//! [`AssetFormatter::load_token`](crate::format::AssetFormatter::load_token) reads the symbol
+//! and decimals of a token from its faucet, one request per faucet. A wallet showing a dozen
+//! tokens makes a dozen requests, and cannot show a token of a private faucet at all. The
+//! registry collects the metadata of every token in one public account instead: a single
+//! request for the registry gives the formatter all of them.
+//!
+//! A faucet registers its token with a `register_token_note`. The registry takes the faucet id
+//! from the sender of the note, so a faucet can only describe its own token. Next to the symbol
+//! and decimals it stores the hash of the token icon: the icon itself is served off-chain, and
+//! the hash lets a wallet check it got the icon the faucet registered.
+
+use std::{collections::BTreeMap, error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    asset::TokenSymbol,
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    rpc::{domain::account::FetchedAccount, NodeRpcClient},
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    Hasher,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{cli::SubmitWithProver, format::TokenInfo, helpers::create_library};
+
+/// Path under which the registry is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::token_registry";
+/// Location of the registry contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/token_registry.masm";
+/// Location of the note registering a token, relative to `rust-client`.
+pub const NOTE_PATH: &str = "../masm/notes/register_token_note.masm";
+
+/// Storage slot mapping `[faucet_prefix, faucet_suffix, 0, 0]` to `[symbol, decimals, 0, 0]`.
+pub const TOKENS_SLOT: u8 = 0;
+/// Storage slot mapping `[faucet_prefix, faucet_suffix, 0, 0]` to the hash of the token icon.
+pub const ICONS_SLOT: u8 = 1;
+
+/// Most decimals the registry accepts, the limit of fungible faucets.
+pub const MAX_DECIMALS: u8 = 12;
+
+/// Returns the hash a faucet registers for the icon `icon`.
+pub fn icon_hash(icon: &[u8]) -> Word {
+    Hasher::hash(icon)
+}
+
+/// Metadata a faucet registers for its token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub symbol: String,
+    pub decimals: u8,
+    /// [`icon_hash`] of the token icon, or the empty word for a token without one.
+    pub icon_hash: Word,
+}
+
+impl TokenMetadata {
+    /// Returns the inputs of the note registering the token, `[ICON_HASH, symbol, decimals]`.
+    ///
+    /// Fails on metadata the registry rejects, before anything is sent.
+    pub fn note_inputs(&self) -> Result<Vec<Felt>, Box<dyn Error>> {
+        if self.decimals > MAX_DECIMALS {
+            return Err(format!("tokens have at most {MAX_DECIMALS} decimals").into());
+        }
+        let symbol = TokenSymbol::new(&self.symbol)?;
+
+        let mut inputs = self.icon_hash.as_elements().to_vec();
+        inputs.extend([Felt::from(symbol), Felt::from(self.decimals)]);
+        Ok(inputs)
+    }
+
+    /// Returns what the asset formatter needs of the token.
+    pub fn info(&self) -> TokenInfo {
+        TokenInfo {
+            symbol: self.symbol.clone(),
+            decimals: self.decimals,
+        }
+    }
+}
+
+/// Tokens registered in a registry, read from a copy of its account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenRegistry {
+    pub id: AccountId,
+    pub tokens: BTreeMap<AccountId, TokenMetadata>,
+}
+
+impl TokenRegistry {
+    /// Decodes the storage of a registry account.
+    ///
+    /// Entries whose symbol does not decode are skipped rather than failing the whole registry:
+    /// a faucet can register any field element, and one bad entry must not hide the others.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let StorageSlot::Map(tokens_map) = &storage.slots()[TOKENS_SLOT as usize] else {
+            return Err("the tokens slot of the registry is not a map".into());
+        };
+
+        let mut tokens = BTreeMap::new();
+        for (key, value) in tokens_map.entries() {
+            let Ok(faucet_id) = AccountId::try_from([key[3], key[2]]) else {
+                continue;
+            };
+            let Some(symbol) = TokenSymbol::try_from(value[3])
+                .ok()
+                .and_then(|symbol| symbol.to_string().ok())
+            else {
+                continue;
+            };
+            let Ok(decimals) = u8::try_from(value[2].as_int()) else {
+                continue;
+            };
+
+            let icon_hash = storage.get_map_item(ICONS_SLOT, *key)?;
+            tokens.insert(
+                faucet_id,
+                TokenMetadata {
+                    symbol,
+                    decimals,
+                    icon_hash,
+                },
+            );
+        }
+
+        Ok(Self {
+            id: account.id(),
+            tokens,
+        })
+    }
+
+    /// Reads the public registry `registry_id` from the node, in a single request.
+    pub async fn fetch(
+        rpc: &dyn NodeRpcClient,
+        registry_id: AccountId,
+    ) -> Result<Self, Box<dyn Error>> {
+        match rpc.get_account_details(registry_id).await? {
+            FetchedAccount::Public(account, _) => Self::from_account(&account),
+            FetchedAccount::Private(..) => Err("the token registry must be public".into()),
+        }
+    }
+
+    /// Returns the metadata registered for the token of `faucet_id`, if any.
+    pub fn get(&self, faucet_id: AccountId) -> Option<&TokenMetadata> {
+        self.tokens.get(&faucet_id)
+    }
+}
+
+/// Deploys an empty public registry.
+pub async fn create_registry(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+) -> Result<Account, Box<dyn Error>> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles [`NOTE_PATH`], linked against the registry.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&fs::read_to_string(NOTE_PATH)?)?)
+}
+
+/// Builds the note of `faucet_id` registering `metadata` in the registry `registry_id`.
+pub fn register_note(
+    faucet_id: AccountId,
+    registry_id: AccountId,
+    metadata: &TokenMetadata,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, Box<dyn Error>> {
+    let inputs = NoteInputs::new(metadata.note_inputs()?)?;
+    let recipient = NoteRecipient::new(serial_num, script, inputs);
+    let note_metadata = NoteMetadata::new(
+        faucet_id,
+        NoteType::Public,
+        NoteTag::from_account_id(registry_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::default(), note_metadata, recipient))
+}
+
+/// Has the faucet `faucet_id` send `metadata` to the registry, and the registry consume it.
+/// Returns the transaction of the registry.
+///
+/// The faucet signs the note, so the client needs its key. The registry is a `NoAuth` account:
+/// any client tracking it can execute its transactions.
+pub async fn register(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    faucet_id: AccountId,
+    registry_id: AccountId,
+    metadata: &TokenMetadata,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let script = compile_note_script(client)?;
+    let serial_num = client.rng().draw_word();
+    let note = register_note(faucet_id, registry_id, metadata, script, serial_num)?;
+
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    client.submit_with_prover(faucet_id, request).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .build()?;
+    Ok(client.submit_with_prover(registry_id, request).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(symbol: &str, decimals: u8) -> TokenMetadata {
+        TokenMetadata {
+            symbol: symbol.to_string(),
+            decimals,
+            icon_hash: icon_hash(b"<svg/>"),
+        }
+    }
+
+    #[test]
+    fn note_inputs_put_the_icon_hash_first() {
+        let inputs = metadata("MID", 8).note_inputs().unwrap();
+
+        assert_eq!(inputs.len(), 6);
+        assert_eq!(&inputs[..4], icon_hash(b"<svg/>").as_elements());
+        assert_eq!(inputs[4], Felt::from(TokenSymbol::new("MID").unwrap()));
+        assert_eq!(inputs[5], Felt::new(8));
+    }
+
+    #[test]
+    fn metadata_the_registry_rejects_is_never_sent() {
+        assert!(metadata("MID", MAX_DECIMALS).note_inputs().is_ok());
+        assert!(metadata("MID", MAX_DECIMALS + 1).note_inputs().is_err());
+        assert!(metadata("", 8).note_inputs().is_err());
+        assert!(metadata("mid!", 8).note_inputs().is_err());
+    }
+
+    #[test]
+    fn icon_hash_depends_on_every_byte() {
+        assert_eq!(icon_hash(b"<svg/>"), icon_hash(b"<svg/>"));
+        assert_ne!(icon_hash(b"<svg/>"), icon_hash(b"<svg />"));
+        assert_eq!(
+            metadata("MID", 8).info(),
+            TokenInfo {
+                symbol: "MID".to_string(),
+                decimals: 8,
+            }
+        );
+    }
+}
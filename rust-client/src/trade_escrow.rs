@@ -0,0 +1,364 @@
+//! Off-chain side of the trade escrow (`masm/accounts/trade_escrow.masm`).
+//!
+//! An [`Escrow`] holds funds a buyer locked for a seller. The buyer releases them once it is
+//! satisfied with the trade, after which only the seller can withdraw them. If the buyer never
+//! releases them, it can take them back once the timeout block is reached. Every action is a
+//! note sent by one of the parties and consumed by the escrow, which checks the sender of the
+//! note against the parties it stores.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, note::WellKnownNote, transaction::TransactionKernel};
+use miden_objects::{
+    account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    block::BlockNumber,
+    NoteError, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::{create_library, reply_note},
+};
+
+/// Path under which the escrow contract is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::trade_escrow";
+/// Location of the escrow contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/trade_escrow.masm";
+/// Location of the note locking funds into the escrow, relative to `rust-client`.
+pub const LOCK_NOTE_PATH: &str = "../masm/notes/escrow_lock_note.masm";
+/// Location of the note releasing the funds to the seller, relative to `rust-client`.
+pub const RELEASE_NOTE_PATH: &str = "../masm/notes/escrow_release_note.masm";
+/// Location of the note withdrawing the funds, relative to `rust-client`.
+pub const WITHDRAW_NOTE_PATH: &str = "../masm/notes/escrow_withdraw_note.masm";
+
+const BUYER_SLOT: u8 = 0;
+const SELLER_SLOT: u8 = 1;
+const ASSET_SLOT: u8 = 2;
+const TIMEOUT_SLOT: u8 = 3;
+const STATE_SLOT: u8 = 4;
+
+/// Where an escrow stands, as stored by the contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscrowState {
+    /// The buyer can lock more funds, release them, or reclaim them after the timeout.
+    Locked,
+    /// The buyer released the funds, the seller can withdraw them.
+    Released,
+    /// The funds were paid out.
+    Closed,
+}
+
+impl TryFrom<u64> for EscrowState {
+    type Error = String;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Locked),
+            1 => Ok(Self::Released),
+            2 => Ok(Self::Closed),
+            _ => Err(format!("unknown escrow state {value}")),
+        }
+    }
+}
+
+/// Parties, terms and funds of a deployed escrow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Escrow {
+    pub id: AccountId,
+    pub buyer: AccountId,
+    pub seller: AccountId,
+    pub faucet_id: AccountId,
+    pub timeout: BlockNumber,
+    pub state: EscrowState,
+    pub funds: u64,
+}
+
+impl Escrow {
+    /// Decodes the terms of a deployed escrow account and reads the funds it holds.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let account_id = |slot| -> Result<AccountId, Box<dyn Error>> {
+            let value = storage.get_item(slot)?;
+            Ok(AccountId::try_from([value[3], value[2]])?)
+        };
+        let faucet_id = account_id(ASSET_SLOT)?;
+        let timeout = u32::try_from(storage.get_item(TIMEOUT_SLOT)?[3].as_int())?;
+
+        Ok(Self {
+            id: account.id(),
+            buyer: account_id(BUYER_SLOT)?,
+            seller: account_id(SELLER_SLOT)?,
+            faucet_id,
+            timeout: BlockNumber::from(timeout),
+            state: EscrowState::try_from(storage.get_item(STATE_SLOT)?[3].as_int())?,
+            funds: account.vault().get_balance(faucet_id)?,
+        })
+    }
+
+    /// Returns the party which can withdraw the funds in a transaction executed against
+    /// `block_num`, if any.
+    pub fn withdrawable_by(&self, block_num: BlockNumber) -> Option<AccountId> {
+        match self.state {
+            EscrowState::Released => Some(self.seller),
+            EscrowState::Locked if block_num >= self.timeout => Some(self.buyer),
+            _ => None,
+        }
+    }
+
+    /// Returns the P2ID note by which the escrow pays its funds to `party` in reply to the
+    /// withdrawal note with serial number `serial_num`.
+    pub fn payout(&self, party: AccountId, serial_num: Word) -> Result<Note, Box<dyn Error>> {
+        let funds = FungibleAsset::new(self.faucet_id, self.funds)?;
+        Ok(reply_note(self.id, party, funds, serial_num)?)
+    }
+}
+
+/// Deploys a public escrow in which `buyer` locks tokens of `faucet_id` for `seller`, and can
+/// reclaim them from block `timeout` on unless it released them.
+pub async fn create_escrow(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    buyer: AccountId,
+    seller: AccountId,
+    faucet_id: AccountId,
+    timeout: BlockNumber,
+) -> Result<Account, Box<dyn Error>> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let account_word = |account_id: AccountId| -> Word {
+        [
+            ZERO,
+            ZERO,
+            account_id.suffix(),
+            account_id.prefix().as_felt(),
+        ]
+        .into()
+    };
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(account_word(buyer)),
+            StorageSlot::Value(account_word(seller)),
+            StorageSlot::Value(account_word(faucet_id)),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::from(timeout.as_u32())].into()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the escrow contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `sender` to the escrow `escrow_id`.
+pub fn escrow_note(
+    sender: AccountId,
+    escrow_id: AccountId,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(escrow_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+/// Builds the note by which `party` withdraws the funds of `escrow`. `script` is the compiled
+/// [`WITHDRAW_NOTE_PATH`].
+pub fn withdraw_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    party: AccountId,
+    escrow: &Escrow,
+    script: NoteScript,
+) -> Result<Note, NoteError> {
+    let serial_num = client.rng().draw_word();
+    let inputs = vec![NoteTag::from_account_id(party).into()];
+    escrow_note(party, escrow.id, inputs, vec![], script, serial_num)
+}
+
+/// Has `sender` create `note` for the escrow.
+pub async fn send_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    note: &Note,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    Ok(client.submit_with_prover(sender, request).await?)
+}
+
+/// Has `escrow` consume `note`, creating `replies`.
+///
+/// The escrow is a `NoAuth` account, so any client tracking it can execute its transactions.
+/// A note the escrow rejects stays unconsumed and can be consumed again later, for instance a
+/// withdrawal of the buyer once the timeout is reached.
+pub async fn consume_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    escrow: &Escrow,
+    note: &Note,
+    replies: Vec<Note>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note.clone(), None)])
+        .expected_output_recipients(
+            replies
+                .iter()
+                .map(|reply| reply.recipient().clone())
+                .collect(),
+        )
+        .build()?;
+    Ok(client.submit_with_prover(escrow.id, request).await?)
+}
+
+/// Locks `amount` tokens from the vault of the buyer into `escrow`. `script` is the compiled
+/// [`LOCK_NOTE_PATH`].
+pub async fn lock(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    escrow: &Escrow,
+    amount: u64,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let asset = FungibleAsset::new(escrow.faucet_id, amount)?;
+    let note = escrow_note(
+        escrow.buyer,
+        escrow.id,
+        vec![],
+        vec![asset.into()],
+        script,
+        serial_num,
+    )?;
+    send_note(client, escrow.buyer, &note).await?;
+    consume_note(client, escrow, &note, vec![]).await
+}
+
+/// Has the buyer release the funds of `escrow` to the seller. `script` is the compiled
+/// [`RELEASE_NOTE_PATH`].
+pub async fn release(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    escrow: &Escrow,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = escrow_note(escrow.buyer, escrow.id, vec![], vec![], script, serial_num)?;
+    send_note(client, escrow.buyer, &note).await?;
+    consume_note(client, escrow, &note, vec![]).await
+}
+
+/// Has `party` withdraw the funds of `escrow`, which pays them with a P2ID note. `script` is
+/// the compiled [`WITHDRAW_NOTE_PATH`].
+///
+/// Fails without sending anything if `party` cannot withdraw at the current sync height.
+pub async fn withdraw(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    party: AccountId,
+    escrow: &Escrow,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    if escrow.withdrawable_by(block_num) != Some(party) {
+        return Err(format!("{party} cannot withdraw from the escrow at block {block_num}").into());
+    }
+
+    let note = withdraw_note(client, party, escrow, script)?;
+    let payout = escrow.payout(party, note.serial_num())?;
+    send_note(client, party, &note).await?;
+    consume_note(client, escrow, &note, vec![payout]).await
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn escrow(state: EscrowState) -> Escrow {
+        Escrow {
+            id: account(1, AccountType::RegularAccountImmutableCode),
+            buyer: account(2, AccountType::RegularAccountUpdatableCode),
+            seller: account(3, AccountType::RegularAccountUpdatableCode),
+            faucet_id: account(4, AccountType::FungibleFaucet),
+            timeout: BlockNumber::from(100),
+            state,
+            funds: 50,
+        }
+    }
+
+    #[test]
+    fn released_funds_go_to_the_seller() {
+        let escrow = escrow(EscrowState::Released);
+
+        assert_eq!(escrow.withdrawable_by(0.into()), Some(escrow.seller));
+        assert_eq!(escrow.withdrawable_by(1000.into()), Some(escrow.seller));
+    }
+
+    #[test]
+    fn locked_funds_go_back_to_the_buyer_from_the_timeout_on() {
+        let escrow = escrow(EscrowState::Locked);
+
+        assert_eq!(escrow.withdrawable_by(99.into()), None);
+        assert_eq!(escrow.withdrawable_by(100.into()), Some(escrow.buyer));
+    }
+
+    #[test]
+    fn closed_escrow_pays_nobody() {
+        assert_eq!(
+            escrow(EscrowState::Closed).withdrawable_by(1000.into()),
+            None
+        );
+        assert!(EscrowState::try_from(3).is_err());
+    }
+}
@@ -0,0 +1,473 @@
+//! Off-chain side of the DAO treasury (`masm/accounts/treasury.masm`).
+//!
+//! The treasury is a contract account holding funds on behalf of the holders of a governance
+//! token. Its storage carries a snapshot of their balances, written when it is deployed, which
+//! weighs their votes: the contract cannot read the vaults of other accounts, so the snapshot
+//! does not follow later transfers of the token.
+//!
+//! Any holder submits a proposal to pay an asset to a recipient with a `treasury_propose_note`,
+//! then holders vote for it with a `treasury_vote_note` until its voting period is over. Once the
+//! total weight of the holders voting for it exceeds the threshold, anyone executes the proposal
+//! with a `treasury_execute_note`, and the treasury pays the asset with a P2ID note whose serial
+//! number is the serial number of the execution note plus one. Funds are added with a
+//! `treasury_deposit_note`.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, note::WellKnownNote, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    asset::{Asset, FungibleAsset},
+    block::BlockNumber,
+    NoteError, ONE, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::{create_library, reply_note},
+};
+
+/// Path under which the treasury contract is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::treasury";
+/// Location of the treasury contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/treasury.masm";
+/// Location of the note adding funds to the treasury, relative to `rust-client`.
+pub const DEPOSIT_NOTE_PATH: &str = "../masm/notes/treasury_deposit_note.masm";
+/// Location of the note submitting a proposal, relative to `rust-client`.
+pub const PROPOSE_NOTE_PATH: &str = "../masm/notes/treasury_propose_note.masm";
+/// Location of the note voting for a proposal, relative to `rust-client`.
+pub const VOTE_NOTE_PATH: &str = "../masm/notes/treasury_vote_note.masm";
+/// Location of the note executing a proposal, relative to `rust-client`.
+pub const EXECUTE_NOTE_PATH: &str = "../masm/notes/treasury_execute_note.masm";
+
+const THRESHOLD_SLOT: u8 = 0;
+const VOTING_PERIOD_SLOT: u8 = 1;
+const WEIGHTS_SLOT: u8 = 2;
+const NUM_PROPOSALS_SLOT: u8 = 3;
+const PROPOSALS_SLOT: u8 = 4;
+const PAYOUTS_SLOT: u8 = 5;
+const YES_WEIGHTS_SLOT: u8 = 6;
+const BALLOTS_SLOT: u8 = 7;
+
+/// A proposal to pay an asset from the treasury.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Proposal {
+    /// Position of the proposal in the order they were submitted, from 0.
+    pub id: u32,
+    pub recipient: AccountId,
+    pub payout: FungibleAsset,
+    /// Block from which the proposal no longer accepts votes.
+    pub deadline: BlockNumber,
+    /// Total weight of the holders which voted for the proposal.
+    pub yes_weight: u64,
+    pub executed: bool,
+}
+
+impl Proposal {
+    /// Returns whether the proposal accepts votes in a transaction executed against
+    /// `block_num`.
+    pub fn is_open_at(&self, block_num: BlockNumber) -> bool {
+        block_num < self.deadline
+    }
+
+    /// Returns the P2ID note by which `treasury_id` pays the proposal in reply to the execution
+    /// note with serial number `serial_num`.
+    pub fn payment(&self, treasury_id: AccountId, serial_num: Word) -> Result<Note, NoteError> {
+        reply_note(treasury_id, self.recipient, self.payout, serial_num)
+    }
+}
+
+/// Parameters and proposals of a deployed treasury.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Treasury {
+    pub id: AccountId,
+    /// Yes-weight a proposal has to exceed to be executed.
+    pub threshold: u64,
+    /// Number of blocks during which a proposal accepts votes.
+    pub voting_period: u32,
+    /// Proposals, in the order they were submitted.
+    pub proposals: Vec<Proposal>,
+}
+
+impl Treasury {
+    /// Decodes the parameters and proposals of a deployed treasury account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let num_proposals = u32::try_from(storage.get_item(NUM_PROPOSALS_SLOT)?[3].as_int())?;
+
+        let mut proposals = Vec::new();
+        for id in 0..num_proposals {
+            let key: Word = [ZERO, ZERO, ZERO, Felt::from(id)].into();
+            let value = storage.get_map_item(PROPOSALS_SLOT, key)?;
+            let payout = match Asset::try_from(storage.get_map_item(PAYOUTS_SLOT, key)?)? {
+                Asset::Fungible(asset) => asset,
+                Asset::NonFungible(_) => return Err(format!("proposal {id} pays an NFT").into()),
+            };
+            proposals.push(Proposal {
+                id,
+                recipient: AccountId::try_from([value[3], value[2]])?,
+                payout,
+                deadline: BlockNumber::from(u32::try_from(value[1].as_int())?),
+                yes_weight: storage.get_map_item(YES_WEIGHTS_SLOT, key)?[3].as_int(),
+                executed: value[0] == ONE,
+            });
+        }
+
+        Ok(Self {
+            id: account.id(),
+            threshold: storage.get_item(THRESHOLD_SLOT)?[3].as_int(),
+            voting_period: u32::try_from(storage.get_item(VOTING_PERIOD_SLOT)?[3].as_int())?,
+            proposals,
+        })
+    }
+
+    /// Returns the proposal `id`, if it was submitted.
+    pub fn proposal(&self, id: u32) -> Option<&Proposal> {
+        self.proposals.get(id as usize)
+    }
+
+    /// Returns whether `proposal` can be executed: it was not yet and its yes-weight exceeds the
+    /// threshold.
+    pub fn can_execute(&self, proposal: &Proposal) -> bool {
+        !proposal.executed && proposal.yes_weight > self.threshold
+    }
+}
+
+fn holder_key(holder: AccountId) -> Word {
+    [ZERO, ZERO, holder.suffix(), holder.prefix().as_felt()].into()
+}
+
+/// Returns the weight of `holder` in the snapshot of the treasury `account`.
+pub fn weight_of(account: &Account, holder: AccountId) -> Result<u64, Box<dyn Error>> {
+    Ok(account
+        .storage()
+        .get_map_item(WEIGHTS_SLOT, holder_key(holder))?[3]
+        .as_int())
+}
+
+/// Returns whether `holder` voted for the proposal `proposal_id` of the treasury `account`.
+pub fn has_voted(
+    account: &Account,
+    holder: AccountId,
+    proposal_id: u32,
+) -> Result<bool, Box<dyn Error>> {
+    let key: Word = [
+        ZERO,
+        Felt::from(proposal_id),
+        holder.suffix(),
+        holder.prefix().as_felt(),
+    ]
+    .into();
+    Ok(account.storage().get_map_item(BALLOTS_SLOT, key)?[3] == ONE)
+}
+
+/// Reads the balance of `token` of every account in `holders` from the store of the client,
+/// skipping the accounts without any.
+///
+/// The client has to track the holders, and should be synced first: the snapshot is what the
+/// store holds.
+pub async fn snapshot(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    token: AccountId,
+    holders: &[AccountId],
+) -> Result<Vec<(AccountId, u64)>, Box<dyn Error>> {
+    let mut weights = Vec::new();
+    for &holder in holders {
+        let record = client
+            .get_account(holder)
+            .await?
+            .ok_or_else(|| format!("{holder} is not tracked by the client"))?;
+        let balance = record.account().vault().get_balance(token)?;
+        if balance > 0 {
+            weights.push((holder, balance));
+        }
+    }
+    Ok(weights)
+}
+
+/// Deploys a public treasury weighing votes by `weights`, usually a [`snapshot`], where a
+/// proposal accepts votes for `voting_period` blocks and needs more than `threshold` of weight.
+pub async fn create_treasury(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    weights: &[(AccountId, u64)],
+    threshold: u64,
+    voting_period: u32,
+) -> Result<Account, Box<dyn Error>> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let value_word = |value: u64| -> Word { [ZERO, ZERO, ZERO, Felt::new(value)].into() };
+    let weights = StorageMap::with_entries(
+        weights
+            .iter()
+            .map(|&(holder, weight)| (holder_key(holder), value_word(weight))),
+    )?;
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(value_word(threshold)),
+            StorageSlot::Value(value_word(voting_period.into())),
+            StorageSlot::Map(weights),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the treasury contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `sender` to the treasury `treasury_id`.
+pub fn treasury_note(
+    sender: AccountId,
+    treasury_id: AccountId,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(treasury_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+/// Returns the inputs of a `treasury_propose_note` paying `payout` to `recipient`.
+pub fn proposal_inputs(recipient: AccountId, payout: FungibleAsset) -> Vec<Felt> {
+    let mut inputs = Word::from(payout).to_vec();
+    inputs.extend(holder_key(recipient).as_elements());
+    inputs
+}
+
+/// Returns the inputs of a `treasury_execute_note` executing the proposal `proposal`.
+pub fn execution_inputs(proposal: &Proposal) -> Vec<Felt> {
+    vec![
+        Felt::from(proposal.id),
+        NoteTag::from_account_id(proposal.recipient).into(),
+    ]
+}
+
+/// Has `sender` create `note` for the treasury, then has the treasury consume it, creating
+/// `replies`.
+///
+/// The treasury is a `NoAuth` account, so any client tracking it can execute its transactions.
+async fn send_to_treasury(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    treasury_id: AccountId,
+    note: Note,
+    replies: Vec<Note>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    client.submit_with_prover(sender, request).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .expected_output_recipients(
+            replies
+                .iter()
+                .map(|reply| reply.recipient().clone())
+                .collect(),
+        )
+        .build()?;
+    Ok(client.submit_with_prover(treasury_id, request).await?)
+}
+
+/// Moves `asset` from the vault of `sender` to the treasury. `script` is the compiled
+/// [`DEPOSIT_NOTE_PATH`].
+pub async fn deposit(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    treasury: &Treasury,
+    asset: Asset,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = treasury_note(sender, treasury.id, vec![], vec![asset], script, serial_num)?;
+    send_to_treasury(client, sender, treasury.id, note, vec![]).await
+}
+
+/// Has `proposer` submit a proposal to pay `payout` to `recipient`. The proposal gets the id
+/// `treasury.proposals.len()`, unless another one is submitted first. `script` is the compiled
+/// [`PROPOSE_NOTE_PATH`].
+pub async fn propose(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    proposer: AccountId,
+    treasury: &Treasury,
+    recipient: AccountId,
+    payout: FungibleAsset,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let inputs = proposal_inputs(recipient, payout);
+    let note = treasury_note(proposer, treasury.id, inputs, vec![], script, serial_num)?;
+    send_to_treasury(client, proposer, treasury.id, note, vec![]).await
+}
+
+/// Has `holder` vote for `proposal` with its weight in the snapshot. `script` is the compiled
+/// [`VOTE_NOTE_PATH`].
+///
+/// Fails without sending anything if the proposal is closed at the current sync height.
+pub async fn vote(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    holder: AccountId,
+    treasury: &Treasury,
+    proposal: &Proposal,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    if !proposal.is_open_at(block_num) {
+        return Err(format!(
+            "proposal {} stopped accepting votes at block {}",
+            proposal.id, proposal.deadline
+        )
+        .into());
+    }
+
+    let serial_num = client.rng().draw_word();
+    let inputs = vec![Felt::from(proposal.id)];
+    let note = treasury_note(holder, treasury.id, inputs, vec![], script, serial_num)?;
+    send_to_treasury(client, holder, treasury.id, note, vec![]).await
+}
+
+/// Has `executor` execute `proposal`, which the treasury pays with a P2ID note. `script` is the
+/// compiled [`EXECUTE_NOTE_PATH`].
+///
+/// Fails without sending anything unless [`Treasury::can_execute`] holds.
+pub async fn execute(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    executor: AccountId,
+    treasury: &Treasury,
+    proposal: &Proposal,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    if !treasury.can_execute(proposal) {
+        return Err(format!(
+            "proposal {} cannot be executed with a yes-weight of {} for a threshold of {}",
+            proposal.id, proposal.yes_weight, treasury.threshold
+        )
+        .into());
+    }
+
+    let serial_num = client.rng().draw_word();
+    let inputs = execution_inputs(proposal);
+    let note = treasury_note(executor, treasury.id, inputs, vec![], script, serial_num)?;
+    let payment = proposal.payment(treasury.id, serial_num)?;
+    send_to_treasury(client, executor, treasury.id, note, vec![payment]).await
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn proposal(yes_weight: u64) -> Proposal {
+        let token = account(3, AccountType::FungibleFaucet);
+        Proposal {
+            id: 0,
+            recipient: account(2, AccountType::RegularAccountUpdatableCode),
+            payout: FungibleAsset::new(token, 25).unwrap(),
+            deadline: BlockNumber::from(50),
+            yes_weight,
+            executed: false,
+        }
+    }
+
+    fn treasury() -> Treasury {
+        Treasury {
+            id: account(1, AccountType::RegularAccountImmutableCode),
+            threshold: 50,
+            voting_period: 20,
+            proposals: vec![],
+        }
+    }
+
+    #[test]
+    fn yes_weight_has_to_exceed_the_threshold() {
+        let treasury = treasury();
+
+        assert!(!treasury.can_execute(&proposal(50)));
+        assert!(treasury.can_execute(&proposal(51)));
+        assert!(!treasury.can_execute(&Proposal {
+            executed: true,
+            ..proposal(51)
+        }));
+    }
+
+    #[test]
+    fn proposal_accepts_votes_until_its_deadline() {
+        let proposal = proposal(0);
+
+        assert!(proposal.is_open_at(49.into()));
+        assert!(!proposal.is_open_at(50.into()));
+    }
+
+    #[test]
+    fn proposal_inputs_hold_the_payout_and_the_recipient_key() {
+        let proposal = proposal(0);
+        let inputs = proposal_inputs(proposal.recipient, proposal.payout);
+
+        assert_eq!(inputs.len(), 8);
+        assert_eq!(&inputs[..4], Word::from(proposal.payout).as_elements());
+        assert_eq!(inputs[6], proposal.recipient.suffix());
+        assert_eq!(inputs[7], proposal.recipient.prefix().as_felt());
+    }
+}
@@ -0,0 +1,196 @@
+//! Payments whose receiver never consumes them.
+//!
+//! A note sits on-chain until somebody consumes it. If the receiver lost its keys, went away or
+//! simply ignores the payment, a plain P2ID note locks the assets forever. Sending a P2IDE note
+//! instead gives the payment a deadline: from its reclaim height on, the sender may consume the
+//! note back as long as the receiver has not.
+//!
+//! A [`PendingPayment`] keeps what the sender needs to follow such a payment: [`status`] tells
+//! whether it was claimed, is still within its deadline or is overdue, and [`reclaim`] takes an
+//! overdue payment back.
+
+use std::error::Error;
+
+use miden_client::{
+    account::AccountId,
+    asset::Asset,
+    keystore::FilesystemKeyStore,
+    note::{create_p2ide_note, Note, NoteType},
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt,
+};
+use miden_objects::block::BlockNumber;
+use rand::rngs::StdRng;
+
+use crate::{
+    cli::SubmitWithProver,
+    replacement::{self, Replacement},
+};
+
+/// Where a payment stands from the point of view of its sender.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaymentStatus {
+    /// The note was consumed, by the receiver or by a reclaim.
+    Settled,
+    /// Only the receiver can consume the note, for `blocks_left` more blocks.
+    Pending { blocks_left: u32 },
+    /// The deadline passed without the receiver consuming the note: the sender may reclaim it.
+    Overdue,
+}
+
+/// A recallable payment the sender is waiting on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingPayment {
+    pub note: Note,
+    pub receiver: AccountId,
+    /// First block at which the sender may reclaim the note.
+    pub reclaim_height: BlockNumber,
+}
+
+impl PendingPayment {
+    /// Returns the status of the payment at `block_num`, given whether its note was consumed.
+    pub fn status_at(&self, block_num: BlockNumber, consumed: bool) -> PaymentStatus {
+        if consumed {
+            PaymentStatus::Settled
+        } else if block_num >= self.reclaim_height {
+            PaymentStatus::Overdue
+        } else {
+            PaymentStatus::Pending {
+                blocks_left: self.reclaim_height.as_u32() - block_num.as_u32(),
+            }
+        }
+    }
+}
+
+/// Has `sender` pay `assets` to `receiver` in a public P2IDE note the sender may reclaim
+/// `timeout_blocks` blocks after the last synced block.
+pub async fn send_recallable(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    receiver: AccountId,
+    assets: Vec<Asset>,
+    timeout_blocks: u32,
+) -> Result<(TransactionId, PendingPayment), Box<dyn Error>> {
+    let tip = client.get_sync_height().await?;
+    let reclaim_height = BlockNumber::from(tip.as_u32() + timeout_blocks);
+    let note = create_p2ide_note(
+        sender,
+        receiver,
+        assets,
+        Some(reclaim_height),
+        None,
+        NoteType::Public,
+        Felt::new(0),
+        client.rng(),
+    )?;
+
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    let tx_id = client.submit_with_prover(sender, request).await?;
+    Ok((
+        tx_id,
+        PendingPayment {
+            note,
+            receiver,
+            reclaim_height,
+        },
+    ))
+}
+
+/// Syncs the client of the sender and returns the status of `payment` at the new tip.
+pub async fn status(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    payment: &PendingPayment,
+) -> Result<PaymentStatus, Box<dyn Error>> {
+    let block_num = client.sync_state().await?.block_num;
+    let consumed = replacement::is_consumed(client, &payment.note).await?;
+    Ok(payment.status_at(block_num, consumed))
+}
+
+/// Has `sender` consume an overdue `payment` back.
+///
+/// Fails if the payment is still pending, since the note script rejects the sender until the
+/// reclaim height. The receiver may still consume the note at the last moment, which is reported
+/// as [`Replacement::AlreadyConsumed`] like for any other note taken back.
+pub async fn reclaim(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    payment: &PendingPayment,
+) -> Result<Replacement, Box<dyn Error>> {
+    if let PaymentStatus::Pending { blocks_left } = status(client, payment).await? {
+        return Err(format!("the payment can be reclaimed in {blocks_left} blocks").into());
+    }
+    Ok(replacement::replace_note(client, sender, &payment.note, vec![]).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::{
+        account::{AccountIdVersion, AccountStorageMode, AccountType},
+        crypto::rand::RpoRandomCoin,
+        Word,
+    };
+
+    use super::*;
+
+    fn account(seed: u8) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            AccountType::RegularAccountUpdatableCode,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn payment(reclaim_height: u32) -> PendingPayment {
+        let receiver = account(2);
+        let reclaim_height = BlockNumber::from(reclaim_height);
+        let note = create_p2ide_note(
+            account(1),
+            receiver,
+            vec![],
+            Some(reclaim_height),
+            None,
+            NoteType::Public,
+            Felt::new(0),
+            &mut RpoRandomCoin::new(Word::default()),
+        )
+        .unwrap();
+        PendingPayment {
+            note,
+            receiver,
+            reclaim_height,
+        }
+    }
+
+    #[test]
+    fn a_payment_is_pending_until_the_reclaim_height() {
+        let payment = payment(110);
+
+        assert_eq!(
+            payment.status_at(100.into(), false),
+            PaymentStatus::Pending { blocks_left: 10 }
+        );
+        assert_eq!(
+            payment.status_at(109.into(), false),
+            PaymentStatus::Pending { blocks_left: 1 }
+        );
+    }
+
+    #[test]
+    fn a_payment_is_overdue_from_the_reclaim_height() {
+        let payment = payment(110);
+
+        assert_eq!(payment.status_at(110.into(), false), PaymentStatus::Overdue);
+        assert_eq!(payment.status_at(500.into(), false), PaymentStatus::Overdue);
+    }
+
+    #[test]
+    fn a_consumed_payment_is_settled_whatever_the_block() {
+        let payment = payment(110);
+
+        assert_eq!(payment.status_at(100.into(), true), PaymentStatus::Settled);
+        assert_eq!(payment.status_at(110.into(), true), PaymentStatus::Settled);
+    }
+}
@@ -0,0 +1,381 @@
+//! Off-chain side of the vesting contract (`masm/accounts/vesting.masm`).
+//!
+//! A vesting contract holds tokens of one faucet for a beneficiary and unlocks them linearly
+//! over block height: from the block it was deployed at, a fixed number of tokens vests with
+//! every block until the whole amount has vested after `duration` blocks. Anyone can fund the
+//! contract with a `vesting_deposit_note`. The beneficiary withdraws with a
+//! `vesting_withdraw_note` naming an amount, which the contract only pays, with a P2ID note
+//! whose serial number is the serial number of the request plus one, if it does not bring the
+//! total withdrawn above what vested so far.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, note::WellKnownNote, transaction::TransactionKernel};
+use miden_objects::{
+    account::{AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    block::BlockNumber,
+    NoteError, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{
+    cli::SubmitWithProver,
+    helpers::{create_library, reply_note},
+};
+
+/// Path under which the vesting contract is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::vesting";
+/// Location of the vesting contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/vesting.masm";
+/// Location of the note funding a vesting contract, relative to `rust-client`.
+pub const DEPOSIT_NOTE_PATH: &str = "../masm/notes/vesting_deposit_note.masm";
+/// Location of the note withdrawing vested tokens, relative to `rust-client`.
+pub const WITHDRAW_NOTE_PATH: &str = "../masm/notes/vesting_withdraw_note.masm";
+
+const BENEFICIARY_SLOT: u8 = 0;
+const CURRENCY_SLOT: u8 = 1;
+const START_SLOT: u8 = 2;
+const DURATION_SLOT: u8 = 3;
+const RATE_SLOT: u8 = 4;
+const WITHDRAWN_SLOT: u8 = 5;
+
+/// Terms and state of a deployed vesting contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vesting {
+    pub id: AccountId,
+    pub beneficiary: AccountId,
+    /// Faucet of the vesting tokens.
+    pub currency: AccountId,
+    /// Block the tokens start to unlock at.
+    pub start: BlockNumber,
+    /// Number of blocks after which all tokens are unlocked.
+    pub duration: u32,
+    /// Tokens unlocked per block.
+    pub rate: u64,
+    /// Total withdrawn by the beneficiary so far.
+    pub withdrawn: u64,
+}
+
+impl Vesting {
+    /// Decodes the terms and state of a deployed vesting account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let account_id = |slot| -> Result<AccountId, Box<dyn Error>> {
+            let value = storage.get_item(slot)?;
+            Ok(AccountId::try_from([value[3], value[2]])?)
+        };
+        let value =
+            |slot| -> Result<u64, Box<dyn Error>> { Ok(storage.get_item(slot)?[3].as_int()) };
+
+        Ok(Self {
+            id: account.id(),
+            beneficiary: account_id(BENEFICIARY_SLOT)?,
+            currency: account_id(CURRENCY_SLOT)?,
+            start: BlockNumber::from(u32::try_from(value(START_SLOT)?)?),
+            duration: u32::try_from(value(DURATION_SLOT)?)?,
+            rate: value(RATE_SLOT)?,
+            withdrawn: value(WITHDRAWN_SLOT)?,
+        })
+    }
+
+    /// Returns the number of tokens vesting in total.
+    pub fn total(&self) -> u64 {
+        self.rate * u64::from(self.duration)
+    }
+
+    /// Returns the tokens vested in a transaction executed against `block_num`, withdrawn ones
+    /// included.
+    pub fn vested_at(&self, block_num: BlockNumber) -> u64 {
+        let elapsed = block_num
+            .as_u32()
+            .saturating_sub(self.start.as_u32())
+            .min(self.duration);
+        self.rate * u64::from(elapsed)
+    }
+
+    /// Returns what the beneficiary can still withdraw in a transaction executed against
+    /// `block_num`.
+    pub fn claimable_at(&self, block_num: BlockNumber) -> u64 {
+        self.vested_at(block_num).saturating_sub(self.withdrawn)
+    }
+
+    /// Returns the P2ID note by which the contract pays `amount` tokens to the beneficiary in
+    /// reply to the withdrawal note with serial number `serial_num`.
+    pub fn payout(&self, amount: u64, serial_num: Word) -> Result<Note, Box<dyn Error>> {
+        let tokens = FungibleAsset::new(self.currency, amount)?;
+        Ok(reply_note(self.id, self.beneficiary, tokens, serial_num)?)
+    }
+}
+
+/// Deploys a public contract vesting `total` tokens of `currency` for `beneficiary` over
+/// `duration` blocks, starting at the current sync height.
+///
+/// `total` has to be a multiple of `duration`, so that the same number of tokens unlocks with
+/// every block. The tokens themselves are added afterwards with [`deposit`].
+pub async fn create_vesting(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    beneficiary: AccountId,
+    currency: AccountId,
+    total: u64,
+    duration: u32,
+) -> Result<Account, Box<dyn Error>> {
+    if duration == 0 || total % u64::from(duration) != 0 {
+        return Err(format!("{total} tokens cannot vest evenly over {duration} blocks").into());
+    }
+
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let start = client.get_sync_height().await?;
+    let account_word = |account_id: AccountId| -> Word {
+        [
+            ZERO,
+            ZERO,
+            account_id.suffix(),
+            account_id.prefix().as_felt(),
+        ]
+        .into()
+    };
+    let value_word = |value: u64| -> Word { [ZERO, ZERO, ZERO, Felt::new(value)].into() };
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(account_word(beneficiary)),
+            StorageSlot::Value(account_word(currency)),
+            StorageSlot::Value(value_word(start.as_u32().into())),
+            StorageSlot::Value(value_word(duration.into())),
+            StorageSlot::Value(value_word(total / u64::from(duration))),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the vesting contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `sender` to the vesting contract `vesting_id`.
+pub fn vesting_note(
+    sender: AccountId,
+    vesting_id: AccountId,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let recipient = NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(vesting_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+/// Builds a note by which the beneficiary asks `vesting` for `amount` tokens. `script` is the
+/// compiled [`WITHDRAW_NOTE_PATH`].
+pub fn withdrawal_request(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    vesting: &Vesting,
+    amount: u64,
+    script: NoteScript,
+) -> Result<Note, NoteError> {
+    let serial_num = client.rng().draw_word();
+    let inputs = vec![
+        Felt::new(amount),
+        NoteTag::from_account_id(vesting.beneficiary).into(),
+    ];
+    vesting_note(
+        vesting.beneficiary,
+        vesting.id,
+        inputs,
+        vec![],
+        script,
+        serial_num,
+    )
+}
+
+/// Has `sender` create `note` for the vesting contract.
+pub async fn send_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    note: &Note,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    Ok(client.submit_with_prover(sender, request).await?)
+}
+
+/// Has `vesting` consume `note`, creating `replies`.
+///
+/// The contract is a `NoAuth` account, so any client tracking it can execute its transactions.
+/// The transaction is executed before anything is submitted, so a note the contract rejects
+/// fails here whether or not it was sent.
+pub async fn consume_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    vesting: &Vesting,
+    note: &Note,
+    replies: Vec<Note>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note.clone(), None)])
+        .expected_output_recipients(
+            replies
+                .iter()
+                .map(|reply| reply.recipient().clone())
+                .collect(),
+        )
+        .build()?;
+    Ok(client.submit_with_prover(vesting.id, request).await?)
+}
+
+/// Funds `vesting` with `amount` tokens from the vault of `funder`. `script` is the compiled
+/// [`DEPOSIT_NOTE_PATH`].
+pub async fn deposit(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    funder: AccountId,
+    vesting: &Vesting,
+    amount: u64,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let asset = FungibleAsset::new(vesting.currency, amount)?;
+    let note = vesting_note(
+        funder,
+        vesting.id,
+        vec![],
+        vec![asset.into()],
+        script,
+        serial_num,
+    )?;
+    send_note(client, funder, &note).await?;
+    consume_note(client, vesting, &note, vec![]).await
+}
+
+/// Has the beneficiary withdraw `amount` vested tokens from `vesting`, which pays them with a
+/// P2ID note. `script` is the compiled [`WITHDRAW_NOTE_PATH`].
+///
+/// Fails without sending anything if `amount` is not claimable at the current sync height.
+pub async fn withdraw(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    vesting: &Vesting,
+    amount: u64,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    let claimable = vesting.claimable_at(block_num);
+    if amount == 0 || amount > claimable {
+        return Err(format!(
+            "{amount} tokens cannot be withdrawn at block {block_num}, {claimable} are claimable"
+        )
+        .into());
+    }
+
+    let note = withdrawal_request(client, vesting, amount, script)?;
+    let payout = vesting.payout(amount, note.serial_num())?;
+    send_note(client, vesting.beneficiary, &note).await?;
+    consume_note(client, vesting, &note, vec![payout]).await
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn vesting(withdrawn: u64) -> Vesting {
+        Vesting {
+            id: account(1, AccountType::RegularAccountImmutableCode),
+            beneficiary: account(2, AccountType::RegularAccountUpdatableCode),
+            currency: account(3, AccountType::FungibleFaucet),
+            start: BlockNumber::from(100),
+            duration: 50,
+            rate: 4,
+            withdrawn,
+        }
+    }
+
+    #[test]
+    fn tokens_unlock_linearly_until_the_end_of_the_duration() {
+        let vesting = vesting(0);
+
+        assert_eq!(vesting.total(), 200);
+        assert_eq!(vesting.vested_at(90.into()), 0);
+        assert_eq!(vesting.vested_at(100.into()), 0);
+        assert_eq!(vesting.vested_at(125.into()), 100);
+        assert_eq!(vesting.vested_at(150.into()), 200);
+        assert_eq!(vesting.vested_at(400.into()), 200);
+    }
+
+    #[test]
+    fn withdrawn_tokens_are_no_longer_claimable() {
+        let vesting = vesting(60);
+
+        assert_eq!(vesting.claimable_at(110.into()), 0);
+        assert_eq!(vesting.claimable_at(125.into()), 40);
+        assert_eq!(vesting.claimable_at(200.into()), 140);
+    }
+
+    #[test]
+    fn payouts_go_to_the_beneficiary() {
+        let vesting = vesting(0);
+        let serial_num: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)].into();
+
+        let payout = vesting.payout(30, serial_num).unwrap();
+        let expected = reply_note(
+            vesting.id,
+            vesting.beneficiary,
+            FungibleAsset::new(vesting.currency, 30).unwrap(),
+            serial_num,
+        )
+        .unwrap();
+        assert_eq!(payout.id(), expected.id());
+    }
+}
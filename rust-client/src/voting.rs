@@ -0,0 +1,344 @@
+//! Off-chain side of the commit-reveal poll (`masm/accounts/voting.masm`).
+//!
+//! A public poll cannot simply collect votes in the open: early voters would show everybody
+//! else where the vote stands. Voting therefore happens in two phases. Until the commit end
+//! block, every voter sends a `voting_commit_note` carrying only the hash of its [`Ballot`],
+//! which the poll stores in a storage map under the voter. From the commit end block until the
+//! reveal end block, every voter sends a `voting_reveal_note` carrying the ballot itself. The
+//! poll checks that the ballot hashes to the commitment of the voter before counting its
+//! choice, so nobody can change their vote after seeing the others.
+//!
+//! The salt of a ballot keeps the commitment from being guessed by hashing every choice. The
+//! voter has to keep the ballot until the reveal phase: a commitment which is never revealed
+//! is never counted.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    transaction::{OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::{account::auth::NoAuth, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap, StorageSlot,
+    },
+    block::BlockNumber,
+    Hasher, NoteError, ZERO,
+};
+use rand::{rngs::StdRng, RngCore};
+
+use crate::{cli::SubmitWithProver, helpers::create_library};
+
+/// Path under which the poll contract is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::voting";
+/// Location of the poll contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/voting.masm";
+/// Location of the note committing to a ballot, relative to `rust-client`.
+pub const COMMIT_NOTE_PATH: &str = "../masm/notes/voting_commit_note.masm";
+/// Location of the note revealing a ballot, relative to `rust-client`.
+pub const REVEAL_NOTE_PATH: &str = "../masm/notes/voting_reveal_note.masm";
+
+const COMMIT_END_SLOT: u8 = 0;
+const REVEAL_END_SLOT: u8 = 1;
+const NUM_CHOICES_SLOT: u8 = 2;
+const TALLY_SLOT: u8 = 4;
+const NUM_COMMITMENTS_SLOT: u8 = 5;
+const NUM_REVEALS_SLOT: u8 = 6;
+
+/// A vote, hidden behind its commitment until revealed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ballot {
+    pub choice: u32,
+    pub salt: [Felt; 3],
+}
+
+impl Ballot {
+    /// Returns a ballot for `choice` with a fresh random salt.
+    pub fn new(choice: u32, rng: &mut impl FeltRng) -> Self {
+        let salt = rng.draw_word();
+        Self {
+            choice,
+            salt: [salt[0], salt[1], salt[2]],
+        }
+    }
+
+    /// Returns the ballot as revealed to the poll, `[salt_0, salt_1, salt_2, choice]`.
+    pub fn to_word(&self) -> Word {
+        [
+            self.salt[0],
+            self.salt[1],
+            self.salt[2],
+            Felt::from(self.choice),
+        ]
+        .into()
+    }
+
+    /// Returns the commitment registered during the commit phase.
+    pub fn commitment(&self) -> Word {
+        Hasher::hash_elements(self.to_word().as_elements())
+    }
+}
+
+/// Which notes a poll accepts in a transaction executed against a given block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Commitments are accepted.
+    Commit,
+    /// Ballots matching their commitment are counted.
+    Reveal,
+    /// The tally is final.
+    Closed,
+}
+
+/// Schedule and tally of a deployed poll.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Poll {
+    pub id: AccountId,
+    /// Block from which commitments are rejected and reveals accepted.
+    pub commit_end: BlockNumber,
+    /// Block from which reveals are rejected.
+    pub reveal_end: BlockNumber,
+    pub num_commitments: u64,
+    pub num_reveals: u64,
+    /// Votes counted for every choice, indexed by choice.
+    pub tally: Vec<u64>,
+}
+
+impl Poll {
+    /// Decodes the schedule and tally of a deployed poll account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let value =
+            |slot| -> Result<u64, Box<dyn Error>> { Ok(storage.get_item(slot)?[3].as_int()) };
+        let block = |slot| -> Result<BlockNumber, Box<dyn Error>> {
+            Ok(BlockNumber::from(u32::try_from(value(slot)?)?))
+        };
+
+        let mut tally = Vec::new();
+        for choice in 0..value(NUM_CHOICES_SLOT)? {
+            let key: Word = [ZERO, ZERO, ZERO, Felt::new(choice)].into();
+            tally.push(storage.get_map_item(TALLY_SLOT, key)?[3].as_int());
+        }
+
+        Ok(Self {
+            id: account.id(),
+            commit_end: block(COMMIT_END_SLOT)?,
+            reveal_end: block(REVEAL_END_SLOT)?,
+            num_commitments: value(NUM_COMMITMENTS_SLOT)?,
+            num_reveals: value(NUM_REVEALS_SLOT)?,
+            tally,
+        })
+    }
+
+    /// Returns the phase of the poll in a transaction executed against `block_num`.
+    pub fn phase_at(&self, block_num: BlockNumber) -> Phase {
+        if block_num < self.commit_end {
+            Phase::Commit
+        } else if block_num < self.reveal_end {
+            Phase::Reveal
+        } else {
+            Phase::Closed
+        }
+    }
+}
+
+/// Deploys a public poll between `num_choices` choices, accepting commitments until block
+/// `commit_end` and reveals from then until block `reveal_end`.
+pub async fn create_poll(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    num_choices: u32,
+    commit_end: BlockNumber,
+    reveal_end: BlockNumber,
+) -> Result<Account, Box<dyn Error>> {
+    if commit_end >= reveal_end {
+        return Err("the reveal phase must end after the commit phase".into());
+    }
+
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    let value_word = |value: u32| -> Word { [ZERO, ZERO, ZERO, Felt::from(value)].into() };
+
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(value_word(commit_end.as_u32())),
+            StorageSlot::Value(value_word(reveal_end.as_u32())),
+            StorageSlot::Value(value_word(num_choices)),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build()?;
+
+    client.add_account(&account, false).await?;
+    Ok(account)
+}
+
+/// Compiles the note script at `note_path`, linked against the poll contract.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `voter` to the poll `poll_id`, carrying `word` as its inputs.
+pub fn poll_note(
+    voter: AccountId,
+    poll_id: AccountId,
+    word: Word,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let inputs = NoteInputs::new(word.as_elements().to_vec())?;
+    let recipient = NoteRecipient::new(serial_num, script, inputs);
+    let metadata = NoteMetadata::new(
+        voter,
+        NoteType::Public,
+        NoteTag::from_account_id(poll_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(NoteAssets::default(), metadata, recipient))
+}
+
+/// Has `voter` send `word` to `poll` with the note script `script`, and the poll consume it.
+///
+/// The poll is a `NoAuth` account, so any client tracking it can execute its transactions.
+async fn send_to_poll(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    voter: AccountId,
+    poll: &Poll,
+    word: Word,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = poll_note(voter, poll.id, word, script, serial_num)?;
+
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    client.submit_with_prover(voter, request).await?;
+
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .build()?;
+    Ok(client.submit_with_prover(poll.id, request).await?)
+}
+
+/// Registers the commitment of `ballot` as the vote of `voter`. `script` is the compiled
+/// [`COMMIT_NOTE_PATH`].
+///
+/// Fails without sending anything unless the poll is in its commit phase at the current sync
+/// height.
+pub async fn commit(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    voter: AccountId,
+    poll: &Poll,
+    ballot: &Ballot,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    if poll.phase_at(block_num) != Phase::Commit {
+        return Err(format!("the poll no longer accepts commitments at block {block_num}").into());
+    }
+    send_to_poll(client, voter, poll, ballot.commitment(), script).await
+}
+
+/// Reveals `ballot`, which `voter` committed to, to the poll. `script` is the compiled
+/// [`REVEAL_NOTE_PATH`].
+///
+/// Fails without sending anything unless the poll is in its reveal phase at the current sync
+/// height. A ballot not matching the commitment is rejected by the poll.
+pub async fn reveal(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    voter: AccountId,
+    poll: &Poll,
+    ballot: &Ballot,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let block_num = client.get_sync_height().await?;
+    if poll.phase_at(block_num) != Phase::Reveal {
+        return Err(format!("the poll does not accept reveals at block {block_num}").into());
+    }
+    send_to_poll(client, voter, poll, ballot.to_word(), script).await
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::AccountIdVersion;
+
+    use super::*;
+
+    fn poll() -> Poll {
+        Poll {
+            id: AccountId::dummy(
+                [1; 15],
+                AccountIdVersion::Version0,
+                AccountType::RegularAccountImmutableCode,
+                AccountStorageMode::Public,
+            ),
+            commit_end: BlockNumber::from(10),
+            reveal_end: BlockNumber::from(20),
+            num_commitments: 0,
+            num_reveals: 0,
+            tally: vec![0, 0],
+        }
+    }
+
+    fn ballot(choice: u32, salt: u64) -> Ballot {
+        Ballot {
+            choice,
+            salt: [Felt::new(salt), Felt::new(salt + 1), Felt::new(salt + 2)],
+        }
+    }
+
+    #[test]
+    fn phases_follow_the_schedule() {
+        let poll = poll();
+
+        assert_eq!(poll.phase_at(9.into()), Phase::Commit);
+        assert_eq!(poll.phase_at(10.into()), Phase::Reveal);
+        assert_eq!(poll.phase_at(19.into()), Phase::Reveal);
+        assert_eq!(poll.phase_at(20.into()), Phase::Closed);
+    }
+
+    #[test]
+    fn commitment_depends_on_choice_and_salt() {
+        assert_eq!(ballot(1, 7).commitment(), ballot(1, 7).commitment());
+        assert_ne!(ballot(1, 7).commitment(), ballot(0, 7).commitment());
+        assert_ne!(ballot(1, 7).commitment(), ballot(1, 8).commitment());
+    }
+
+    #[test]
+    fn choice_is_the_last_element_of_the_revealed_word() {
+        let word = ballot(3, 7).to_word();
+
+        assert_eq!(word[3], Felt::new(3));
+        assert_eq!(&word.as_elements()[..3], &ballot(3, 7).salt);
+    }
+}
@@ -0,0 +1,437 @@
+//! Off-chain side of the wager escrow (`masm/accounts/wager_escrow.masm`).
+//!
+//! A wager escrow holds the stakes of a tic-tac-toe game instead of the game itself. The game
+//! and the escrow are deployed independently and only know each other's id:
+//!
+//! - the escrow reads the players of the game, and whether the game started, through FPI by
+//!   calling the `get_players` and `get_progress` getters of the game. Each player deposits its
+//!   stake with a `wager_deposit_note` before the first move.
+//! - the game, deployed with [`tic_tac_toe::escrowed_deployer`], reports its winner to the
+//!   escrow with a `wager_settle_note`. It creates the note in the transaction of the winning
+//!   move or of the timeout claim, the same way a ranked game reports to its ranking. The escrow
+//!   only accepts the note from its game, and pays both stakes to the winner.
+//!
+//! The game does not report draws. Once a draw was claimed, anyone can have the escrow refund
+//! both stakes with a `wager_refund_note`, the escrow reading the result from the game through
+//! FPI. A wager only one player deposited into is called off when the game reports, and that
+//! stake goes back to its player.
+
+use std::{error::Error, fs};
+
+use miden_client::{
+    account::{Account, AccountId},
+    crypto::FeltRng,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    rpc::domain::account::AccountStorageRequirements,
+    transaction::{ForeignAccount, OutputNote, TransactionId, TransactionRequestBuilder},
+    Client, Felt, Word,
+};
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{AccountStorageMode, StorageMap, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    NoteError,
+};
+use rand::rngs::StdRng;
+
+use crate::{
+    cli::SubmitWithProver,
+    deployer::Deployer,
+    helpers::{create_library, procedure_root},
+    storage::{read_account_id_slot, read_u64_slot},
+    tic_tac_toe::{self, GameState},
+};
+
+/// Path under which the escrow is linked into its notes.
+pub const LIBRARY_PATH: &str = "external_contract::wager_escrow";
+/// Location of the escrow contract, relative to `rust-client`.
+pub const CONTRACT_PATH: &str = "../masm/accounts/wager_escrow.masm";
+/// Location of the note depositing a stake, relative to `rust-client`.
+pub const DEPOSIT_NOTE_PATH: &str = "../masm/notes/wager_deposit_note.masm";
+/// Location of the note by which the game reports its winner, relative to `rust-client`.
+pub const SETTLE_NOTE_PATH: &str = "../masm/notes/wager_settle_note.masm";
+/// Location of the note refunding the stakes of a draw, relative to `rust-client`.
+pub const REFUND_NOTE_PATH: &str = "../masm/notes/wager_refund_note.masm";
+
+/// Storage slot holding `[game_prefix, game_suffix, 0, 0]`.
+pub const GAME_SLOT: u8 = 0;
+/// Storage slot holding the asset each player stakes.
+pub const STAKE_SLOT: u8 = 1;
+/// Storage map from `[player_prefix, player_suffix, 0, 0]` to `[payout_tag, deposited, 0, 0]`.
+pub const DEPOSITS_SLOT: u8 = 2;
+/// Storage slot set to 1 once the stakes were paid out.
+pub const SETTLED_SLOT: u8 = 4;
+
+/// State of a wager escrow, read from a copy of its account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WagerEscrow {
+    pub id: AccountId,
+    /// Game whose result the escrow pays out.
+    pub game_id: AccountId,
+    /// Asset each player stakes.
+    pub stake: FungibleAsset,
+    /// Players who deposited their stake.
+    pub depositors: Vec<AccountId>,
+    /// Whether the stakes were paid out.
+    pub settled: bool,
+}
+
+impl WagerEscrow {
+    /// Decodes the storage of a deployed escrow account.
+    pub fn from_account(account: &Account) -> Result<Self, Box<dyn Error>> {
+        let storage = account.storage();
+        let stake = match Asset::try_from(storage.get_item(STAKE_SLOT)?)? {
+            Asset::Fungible(asset) => asset,
+            Asset::NonFungible(_) => return Err("the escrow holds a non-fungible stake".into()),
+        };
+
+        let StorageSlot::Map(deposits) = &storage.slots()[DEPOSITS_SLOT as usize] else {
+            return Err("the deposits slot of the escrow is not a map".into());
+        };
+        let mut depositors = Vec::new();
+        for (key, value) in deposits.entries() {
+            if value[2].as_int() == 1 {
+                depositors.push(AccountId::try_from([key[3], key[2]])?);
+            }
+        }
+        depositors.sort();
+
+        Ok(Self {
+            id: account.id(),
+            game_id: read_account_id_slot(account, GAME_SLOT)?,
+            stake,
+            depositors,
+            settled: read_u64_slot(account, SETTLED_SLOT)? == 1,
+        })
+    }
+
+    /// Reads the escrow `escrow_id` as last synced by `client`, which must track it.
+    pub async fn load(
+        client: &Client<FilesystemKeyStore<StdRng>>,
+        escrow_id: AccountId,
+    ) -> Result<Self, Box<dyn Error>> {
+        let record = client
+            .get_account(escrow_id)
+            .await?
+            .ok_or("the escrow is not tracked by the client")?;
+        Self::from_account(record.account())
+    }
+
+    /// Returns whether both players deposited, so that the winner takes both stakes.
+    pub fn is_funded(&self) -> bool {
+        self.depositors.len() == 2
+    }
+
+    /// Returns the notes the escrow creates when its game reports that `winner` beat `loser`:
+    /// both stakes to the winner of a funded wager, otherwise the stake of each depositor back.
+    pub fn payouts_for_win(
+        &self,
+        winner: AccountId,
+        loser: AccountId,
+    ) -> Result<Vec<Note>, Box<dyn Error>> {
+        if self.is_funded() {
+            return Ok(vec![tic_tac_toe::payout_note(
+                self.id,
+                winner,
+                self.stake.add(self.stake)?,
+            )?]);
+        }
+        self.refunds(&[winner, loser])
+    }
+
+    /// Returns the notes the escrow creates once a draw of its game was claimed: the stake of
+    /// each depositor back.
+    pub fn payouts_for_draw(&self) -> Result<Vec<Note>, Box<dyn Error>> {
+        self.refunds(&self.depositors)
+    }
+
+    /// Returns the notes sending their stake back to those of `players` who deposited.
+    ///
+    /// The escrow pays with the same P2ID notes as a wagered game, its id taking the place of
+    /// the id of the game in the serial number.
+    fn refunds(&self, players: &[AccountId]) -> Result<Vec<Note>, Box<dyn Error>> {
+        Ok(players
+            .iter()
+            .filter(|player| self.depositors.contains(*player))
+            .map(|player| tic_tac_toe::payout_note(self.id, *player, self.stake))
+            .collect::<Result<_, _>>()?)
+    }
+}
+
+/// Returns the initial storage of an escrow holding a stake of `stake` from each player.
+fn initial_storage(stake: FungibleAsset) -> Result<Vec<StorageSlot>, Box<dyn Error>> {
+    let game = create_library(
+        &fs::read_to_string(tic_tac_toe::CONTRACT_PATH)?,
+        tic_tac_toe::LIBRARY_PATH,
+    )?;
+    Ok(vec![
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Value(Asset::from(stake).into()),
+        StorageSlot::Map(StorageMap::new()),
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        StorageSlot::Value(procedure_root(&game, "get_players")?),
+        StorageSlot::Value(procedure_root(&game, "get_progress")?),
+        StorageSlot::Value(Word::default()),
+    ])
+}
+
+/// Returns the deployer of an escrow without the game its constructor records.
+fn unbound_deployer(stake: FungibleAsset) -> Result<Deployer, Box<dyn Error>> {
+    let code = fs::read_to_string(CONTRACT_PATH)?;
+    Ok(Deployer::new(code, LIBRARY_PATH).with_storage(initial_storage(stake)?))
+}
+
+/// Returns the id of the escrow [`deployer`] deploys from `seed`, whatever its game.
+///
+/// The game is a constructor argument rather than part of the initial storage, so the id of the
+/// escrow is known before the game is deployed, and the game can be deployed to report to it.
+pub fn escrow_id(stake: FungibleAsset, seed: [u8; 32]) -> Result<AccountId, Box<dyn Error>> {
+    Ok(unbound_deployer(stake)?
+        .build_account(seed, AccountStorageMode::Public)?
+        .id())
+}
+
+/// Returns the deployer of an escrow holding a stake of `stake` from each player of the game
+/// `game_id`. Deploy it as a public account from the seed given to [`escrow_id`].
+pub fn deployer(game_id: AccountId, stake: FungibleAsset) -> Result<Deployer, Box<dyn Error>> {
+    Ok(unbound_deployer(stake)?
+        .with_arg(game_id.prefix().as_felt())
+        .with_arg(game_id.suffix()))
+}
+
+/// Compiles the note script at `note_path`, linked against the escrow.
+pub fn compile_note_script(
+    client: &Client<FilesystemKeyStore<StdRng>>,
+    note_path: &str,
+) -> Result<NoteScript, Box<dyn Error>> {
+    let library = create_library(&fs::read_to_string(CONTRACT_PATH)?, LIBRARY_PATH)?;
+    let note_code = fs::read_to_string(note_path)?;
+    Ok(client
+        .script_builder()
+        .with_dynamically_linked_library(&library)?
+        .compile_note_script(&note_code)?)
+}
+
+/// Builds a note sent by `sender` to the escrow `escrow_id`.
+fn escrow_note(
+    sender: AccountId,
+    escrow_id: AccountId,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(escrow_id),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(
+        NoteAssets::new(assets)?,
+        metadata,
+        NoteRecipient::new(serial_num, script, NoteInputs::new(inputs)?),
+    ))
+}
+
+/// Builds the note by which `player` deposits `stake` into the escrow `escrow_id`. The escrow
+/// pays `player` with notes tagged for its account.
+pub fn deposit_note(
+    player: AccountId,
+    escrow_id: AccountId,
+    stake: FungibleAsset,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    escrow_note(
+        player,
+        escrow_id,
+        vec![NoteTag::from_account_id(player).into()],
+        vec![stake.into()],
+        script,
+        serial_num,
+    )
+}
+
+/// Builds the note by which `sender` asks the escrow `escrow_id` to refund the stakes of a draw.
+pub fn refund_note(
+    sender: AccountId,
+    escrow_id: AccountId,
+    script: NoteScript,
+    serial_num: Word,
+) -> Result<Note, NoteError> {
+    escrow_note(sender, escrow_id, vec![], vec![], script, serial_num)
+}
+
+/// Has `escrow` consume `note`, loading `foreign_accounts` and creating `outputs`.
+///
+/// The escrow is a `NoAuth` account, so any client tracking it can execute its transactions.
+async fn consume_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    escrow: &WagerEscrow,
+    note: Note,
+    foreign_accounts: Vec<ForeignAccount>,
+    outputs: Vec<Note>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .unauthenticated_input_notes([(note, None)])
+        .foreign_accounts(foreign_accounts)
+        .expected_output_recipients(
+            outputs
+                .iter()
+                .map(|output| output.recipient().clone())
+                .collect(),
+        )
+        .build()?;
+    Ok(client.submit_with_prover(escrow.id, request).await?)
+}
+
+/// Has `sender` create `note`, then has `escrow` consume it right away, reading the game of the
+/// escrow through FPI.
+async fn submit_note(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    sender: AccountId,
+    escrow: &WagerEscrow,
+    note: Note,
+    outputs: Vec<Note>,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let request = TransactionRequestBuilder::new()
+        .own_output_notes(vec![OutputNote::Full(note.clone())])
+        .build()?;
+    client.submit_with_prover(sender, request).await?;
+
+    let game = ForeignAccount::public(escrow.game_id, AccountStorageRequirements::default())?;
+    consume_note(client, escrow, note, vec![game], outputs).await
+}
+
+/// Deposits the stake of `player` into `escrow`. `script` is the compiled
+/// [`DEPOSIT_NOTE_PATH`].
+pub async fn deposit(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    escrow: &WagerEscrow,
+    player: AccountId,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = deposit_note(player, escrow.id, escrow.stake, script, serial_num)?;
+    submit_note(client, player, escrow, note, vec![]).await
+}
+
+/// Has `escrow` consume the note by which its `game` reported its winner, paying out the stakes.
+/// `script` is the compiled [`SETTLE_NOTE_PATH`].
+///
+/// The game created the note when it was won, so nobody has to send it: its serial number is
+/// derived from the id of the game, and the client rebuilds it from the state of the game.
+pub async fn settle(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    escrow: &WagerEscrow,
+    game: &GameState,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let winner = game.winner.ok_or("the game has no winner")?;
+    let report = game
+        .report_for_win(winner, script)?
+        .ok_or("the game does not report its winner")?;
+    let outputs = escrow.payouts_for_win(game.player(winner), game.player(winner.opponent()))?;
+    consume_note(client, escrow, report, vec![], outputs).await
+}
+
+/// Has `escrow` refund both stakes once a draw of its game was claimed, at the request of
+/// `sender`. `script` is the compiled [`REFUND_NOTE_PATH`].
+pub async fn refund_draw(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    escrow: &WagerEscrow,
+    sender: AccountId,
+    script: NoteScript,
+) -> Result<TransactionId, Box<dyn Error>> {
+    let serial_num = client.rng().draw_word();
+    let note = refund_note(sender, escrow.id, script, serial_num)?;
+    let outputs = escrow.payouts_for_draw()?;
+    submit_note(client, sender, escrow, note, outputs).await
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_objects::account::{AccountIdVersion, AccountType};
+
+    use super::*;
+
+    fn account(seed: u8, account_type: AccountType) -> AccountId {
+        AccountId::dummy(
+            [seed; 15],
+            AccountIdVersion::Version0,
+            account_type,
+            AccountStorageMode::Public,
+        )
+    }
+
+    fn escrow(depositors: &[u8]) -> WagerEscrow {
+        let faucet_id = account(9, AccountType::FungibleFaucet);
+        WagerEscrow {
+            id: account(1, AccountType::RegularAccountImmutableCode),
+            game_id: account(2, AccountType::RegularAccountImmutableCode),
+            stake: FungibleAsset::new(faucet_id, 40).unwrap(),
+            depositors: depositors
+                .iter()
+                .map(|seed| account(*seed, AccountType::RegularAccountUpdatableCode))
+                .collect(),
+            settled: false,
+        }
+    }
+
+    fn amount(note: &Note) -> u64 {
+        note.assets()
+            .iter()
+            .map(|asset| match asset {
+                Asset::Fungible(asset) => asset.amount(),
+                Asset::NonFungible(_) => 0,
+            })
+            .sum()
+    }
+
+    #[test]
+    fn winner_of_a_funded_wager_takes_both_stakes() {
+        let escrow = escrow(&[3, 4]);
+        let (winner, loser) = (escrow.depositors[1], escrow.depositors[0]);
+
+        let payouts = escrow.payouts_for_win(winner, loser).unwrap();
+        assert_eq!(payouts.len(), 1);
+        assert_eq!(payouts[0].metadata().sender(), escrow.id);
+        assert_eq!(
+            payouts[0].metadata().tag(),
+            NoteTag::from_account_id(winner)
+        );
+        assert_eq!(amount(&payouts[0]), 80);
+    }
+
+    #[test]
+    fn wager_funded_by_one_player_is_called_off() {
+        let escrow = escrow(&[3]);
+        let depositor = escrow.depositors[0];
+        let other = account(4, AccountType::RegularAccountUpdatableCode);
+
+        for (winner, loser) in [(depositor, other), (other, depositor)] {
+            let payouts = escrow.payouts_for_win(winner, loser).unwrap();
+            assert_eq!(payouts.len(), 1);
+            assert_eq!(
+                payouts[0].metadata().tag(),
+                NoteTag::from_account_id(depositor)
+            );
+            assert_eq!(amount(&payouts[0]), 40);
+        }
+    }
+
+    #[test]
+    fn draw_refunds_every_depositor() {
+        let payouts = escrow(&[3, 4]).payouts_for_draw().unwrap();
+        assert_eq!(payouts.iter().map(amount).collect::<Vec<_>>(), [40, 40]);
+        assert!(escrow(&[]).payouts_for_draw().unwrap().is_empty());
+    }
+}
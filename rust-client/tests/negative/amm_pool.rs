@@ -0,0 +1,325 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    note::{Note, NoteId, NoteTag},
+    testing::account_id::{
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1,
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2,
+    },
+    Felt, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::amm::{self, Pool};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note_script,
+    random_word, sender, wallet,
+};
+
+fn eth_faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn usd_faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1).unwrap()
+}
+
+fn unknown_faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2).unwrap()
+}
+
+fn account_word(account_id: AccountId) -> Word {
+    [
+        ZERO,
+        ZERO,
+        account_id.suffix(),
+        account_id.prefix().as_felt(),
+    ]
+    .into()
+}
+
+/// Builds an empty pool trading ETH against USD.
+fn amm_pool() -> Result<Account> {
+    contract(
+        &masm("accounts/amm_pool.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(account_word(eth_faucet())),
+            StorageSlot::Value(account_word(usd_faucet())),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )
+}
+
+/// Builds a note of `from` running the note script at `script_path` on the pool.
+fn pool_note(
+    pool: &Account,
+    from: AccountId,
+    script_path: &str,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+) -> Result<Note> {
+    let library = create_library(masm("accounts/amm_pool.masm"), amm::LIBRARY_PATH)?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    Ok(amm::pool_note(
+        from,
+        pool.id(),
+        inputs,
+        assets,
+        script,
+        random_word(),
+    )?)
+}
+
+fn deposit_note(pool: &Account, from: AccountId, assets: Vec<Asset>) -> Result<Note> {
+    pool_note(pool, from, "notes/amm_deposit_note.masm", vec![], assets)
+}
+
+/// Builds a note depositing `eth` ETH and `usd` USD for `from`.
+fn pair_deposit_note(pool: &Account, from: AccountId, eth: u64, usd: u64) -> Result<Note> {
+    deposit_note(
+        pool,
+        from,
+        vec![
+            FungibleAsset::new(eth_faucet(), eth)?.into(),
+            FungibleAsset::new(usd_faucet(), usd)?.into(),
+        ],
+    )
+}
+
+/// Builds a note swapping `asset_in` for `amount_out`, sent back to the test sender.
+fn swap_note(pool: &Account, asset_in: FungibleAsset, amount_out: u64) -> Result<Note> {
+    let inputs = vec![
+        Felt::new(amount_out),
+        NoteTag::from_account_id(sender()).into(),
+    ];
+    pool_note(
+        pool,
+        sender(),
+        "notes/amm_swap_note.masm",
+        inputs,
+        vec![asset_in.into()],
+    )
+}
+
+/// Builds a note redeeming `shares` of `from`.
+fn withdraw_note(pool: &Account, from: AccountId, shares: u64) -> Result<Note> {
+    let inputs = vec![Felt::new(shares), NoteTag::from_account_id(from).into()];
+    pool_note(pool, from, "notes/amm_withdraw_note.masm", inputs, vec![])
+}
+
+/// Builds the note of the test sender adding 1_000 ETH and 100_000 USD to the reserves of the
+/// pool, for 1_000 shares.
+fn liquidity(pool: &Account) -> Result<Note> {
+    pair_deposit_note(pool, sender(), 1_000, 100_000)
+}
+
+fn read_pool(chain: &MockChain, pool: &Account) -> Result<Pool> {
+    Pool::from_account(&chain.committed_account(pool.id())?).map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+fn read_shares(chain: &MockChain, pool: &Account, provider: AccountId) -> Result<u64> {
+    amm::shares(&chain.committed_account(pool.id())?, provider)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+/// Consumes `note` with the pool and returns the ids of the notes it created.
+async fn consume_by_pool(
+    chain: &mut MockChain,
+    pool: &Account,
+    note: &Note,
+) -> Result<Vec<NoteId>> {
+    let executed = chain
+        .build_tx_context(pool.id(), &[note.id()], &[])?
+        .build()?
+        .execute()
+        .await?;
+    let created = executed
+        .output_notes()
+        .iter()
+        .map(|note| note.id())
+        .collect();
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(created)
+}
+
+#[tokio::test]
+async fn liquidity_of_an_unknown_asset_is_rejected() -> Result<()> {
+    let pool = amm_pool()?;
+    let deposit = deposit_note(
+        &pool,
+        sender(),
+        vec![
+            FungibleAsset::new(unknown_faucet(), 10)?.into(),
+            FungibleAsset::new(usd_faucet(), 10)?.into(),
+        ],
+    )?;
+    let mut chain = mock_chain(&[&pool], &[&deposit])?;
+
+    assert_fails_with(
+        consume(&mut chain, pool.id(), &deposit).await,
+        "Pool only accepts the two assets of its pair",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn liquidity_note_without_asset_is_rejected() -> Result<()> {
+    let pool = amm_pool()?;
+    let deposit = deposit_note(&pool, sender(), vec![])?;
+    let mut chain = mock_chain(&[&pool], &[&deposit])?;
+
+    assert_fails_with(
+        consume(&mut chain, pool.id(), &deposit).await,
+        "Liquidity note must carry both assets of the pair",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_of_an_unknown_asset_is_rejected() -> Result<()> {
+    let pool = amm_pool()?;
+    let swap = swap_note(&pool, FungibleAsset::new(unknown_faucet(), 10)?, 1)?;
+    let mut chain = mock_chain(&[&pool], &[&swap])?;
+
+    assert_fails_with(
+        consume(&mut chain, pool.id(), &swap).await,
+        "Pool only accepts the two assets of its pair",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_cannot_drain_the_reserve() -> Result<()> {
+    let pool = amm_pool()?;
+    let deposit = liquidity(&pool)?;
+    let swap = swap_note(&pool, FungibleAsset::new(usd_faucet(), 1_000_000)?, 1_000)?;
+    let mut chain = mock_chain(&[&pool], &[&deposit, &swap])?;
+
+    consume(&mut chain, pool.id(), &deposit).await?;
+    assert_fails_with(
+        consume(&mut chain, pool.id(), &swap).await,
+        "Swap must leave part of the reserve in the pool",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_cannot_decrease_the_product_of_the_reserves() -> Result<()> {
+    let pool = amm_pool()?;
+    let deposit = liquidity(&pool)?;
+    // 10_000 USD buy 90.9 ETH at most
+    let swap = swap_note(&pool, FungibleAsset::new(usd_faucet(), 10_000)?, 91)?;
+    let mut chain = mock_chain(&[&pool], &[&deposit, &swap])?;
+
+    consume(&mut chain, pool.id(), &deposit).await?;
+    assert_fails_with(
+        consume(&mut chain, pool.id(), &swap).await,
+        "Swap would decrease the product of the reserves",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn providers_redeem_their_shares_with_the_fees() -> Result<()> {
+    let pool = amm_pool()?;
+    let bob = wallet(vec![])?;
+    let deposit = liquidity(&pool)?;
+    let bob_deposit = pair_deposit_note(&pool, bob.id(), 100, 10_000)?;
+    // 1_100 * 10_000 / 120_000 = 91.6, the pool keeps the rounding
+    let swap = swap_note(&pool, FungibleAsset::new(usd_faucet(), 10_000)?, 91)?;
+    let withdraw = withdraw_note(&pool, bob.id(), 100)?;
+    let mut chain = mock_chain(&[&pool], &[&deposit, &bob_deposit, &swap, &withdraw])?;
+
+    consume(&mut chain, pool.id(), &deposit).await?;
+    consume(&mut chain, pool.id(), &bob_deposit).await?;
+    assert_eq!(read_shares(&chain, &pool, sender())?, 1_000);
+    assert_eq!(read_shares(&chain, &pool, bob.id())?, 100);
+    assert_eq!(read_pool(&chain, &pool)?.total_shares, 1_100);
+
+    consume(&mut chain, pool.id(), &swap).await?;
+    let before = read_pool(&chain, &pool)?;
+    assert_eq!((before.reserve_a, before.reserve_b), (1_009, 120_000));
+
+    // Bob owns 1/11 of the pool: 91.7 ETH and 10_909 USD, rounded down
+    let (eth, usd) = before.withdrawal(100).unwrap();
+    assert_eq!((eth.amount(), usd.amount()), (91, 10_909));
+    let payouts = before
+        .withdrawal_notes(bob.id(), 100, withdraw.serial_num())
+        .map_err(|err| anyhow::anyhow!("{err}"))?;
+    assert_eq!(
+        consume_by_pool(&mut chain, &pool, &withdraw).await?,
+        payouts.iter().map(|note| note.id()).collect::<Vec<_>>()
+    );
+
+    let after = read_pool(&chain, &pool)?;
+    assert_eq!(read_shares(&chain, &pool, bob.id())?, 0);
+    assert_eq!(after.total_shares, 1_000);
+    assert_eq!((after.reserve_a, after.reserve_b), (918, 109_091));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_held_shares_can_be_redeemed() -> Result<()> {
+    let pool = amm_pool()?;
+    let mallory = wallet(vec![])?;
+    let deposit = liquidity(&pool)?;
+    let theft = withdraw_note(&pool, mallory.id(), 1)?;
+    let excessive = withdraw_note(&pool, sender(), 1_001)?;
+    let empty = withdraw_note(&pool, sender(), 0)?;
+    let mut chain = mock_chain(&[&pool], &[&deposit, &theft, &excessive, &empty])?;
+
+    consume(&mut chain, pool.id(), &deposit).await?;
+    assert_fails_with(
+        consume(&mut chain, pool.id(), &theft).await,
+        "Provider does not hold that many shares",
+    );
+    assert_fails_with(
+        consume(&mut chain, pool.id(), &excessive).await,
+        "Provider does not hold that many shares",
+    );
+    assert_fails_with(
+        consume(&mut chain, pool.id(), &empty).await,
+        "Withdrawal must redeem at least one share",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn deposits_mint_shares_of_their_smaller_side() -> Result<()> {
+    let pool = amm_pool()?;
+    let bob = wallet(vec![])?;
+    let deposit = liquidity(&pool)?;
+    // 10 USD are worth 0.1 ETH, not a single share
+    let dust = pair_deposit_note(&pool, bob.id(), 1, 10)?;
+    // Twice the USD needed for 100 ETH: the excess goes to the pool
+    let lopsided = pair_deposit_note(&pool, bob.id(), 100, 20_000)?;
+    let mut chain = mock_chain(&[&pool], &[&deposit, &dust, &lopsided])?;
+
+    consume(&mut chain, pool.id(), &deposit).await?;
+    assert_fails_with(
+        consume(&mut chain, pool.id(), &dust).await,
+        "Deposit is too small to mint a share",
+    );
+
+    consume(&mut chain, pool.id(), &lopsided).await?;
+    assert_eq!(read_shares(&chain, &pool, bob.id())?, 100);
+    let pool = read_pool(&chain, &pool)?;
+    assert_eq!(pool.total_shares, 1_100);
+    assert_eq!((pool.reserve_a, pool.reserve_b), (1_100, 120_000));
+
+    Ok(())
+}
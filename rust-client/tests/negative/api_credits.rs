@@ -0,0 +1,142 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    note::Note,
+    testing::account_id::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1},
+    Felt, Word, ZERO,
+};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note, note_script,
+};
+
+const CREDITS_PER_TOKEN: u64 = 2;
+
+fn payment_faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn gateway_contract() -> Result<Account> {
+    let faucet = payment_faucet();
+    let pricing: Word = [
+        Felt::new(CREDITS_PER_TOKEN),
+        ZERO,
+        faucet.suffix(),
+        faucet.prefix().as_felt(),
+    ]
+    .into();
+
+    contract(
+        &masm("accounts/api_credits.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(pricing),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )
+}
+
+/// Builds a note of the test sender running the note script at `script_path` on the gateway.
+fn gateway_note(gateway: &Account, script_path: &str, assets: Vec<Asset>) -> Result<Note> {
+    let library = create_library(
+        masm("accounts/api_credits.masm"),
+        "external_contract::api_credits",
+    )?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    note(gateway.id(), script, vec![], assets)
+}
+
+fn purchase_note(gateway: &Account, assets: Vec<Asset>) -> Result<Note> {
+    gateway_note(gateway, "notes/buy_credits_note.masm", assets)
+}
+
+fn request_note(gateway: &Account) -> Result<Note> {
+    gateway_note(gateway, "notes/api_request_note.masm", vec![])
+}
+
+#[tokio::test]
+async fn payment_in_another_asset_is_rejected() -> Result<()> {
+    let gateway = gateway_contract()?;
+    let other_faucet = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1)?;
+    let payment = FungibleAsset::new(other_faucet, 1)?;
+    let purchase = purchase_note(&gateway, vec![payment.into()])?;
+    let mut chain = mock_chain(&[&gateway], &[&purchase])?;
+
+    assert_fails_with(
+        consume(&mut chain, gateway.id(), &purchase).await,
+        "Credits must be paid with the configured faucet",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn purchase_without_payment_is_rejected() -> Result<()> {
+    let gateway = gateway_contract()?;
+    let purchase = purchase_note(&gateway, vec![])?;
+    let mut chain = mock_chain(&[&gateway], &[&purchase])?;
+
+    assert_fails_with(
+        consume(&mut chain, gateway.id(), &purchase).await,
+        "Credit purchase note must carry exactly one payment asset",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn request_without_credits_is_rejected() -> Result<()> {
+    let gateway = gateway_contract()?;
+    let request = request_note(&gateway)?;
+    let mut chain = mock_chain(&[&gateway], &[&request])?;
+
+    assert_fails_with(
+        consume(&mut chain, gateway.id(), &request).await,
+        "Caller has no credits left",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn requests_stop_once_the_credits_are_spent() -> Result<()> {
+    let gateway = gateway_contract()?;
+    let payment = FungibleAsset::new(payment_faucet(), 1)?;
+    let purchase = purchase_note(&gateway, vec![payment.into()])?;
+    let requests = (0..=CREDITS_PER_TOKEN)
+        .map(|_| request_note(&gateway))
+        .collect::<Result<Vec<_>>>()?;
+    let mut notes = vec![&purchase];
+    notes.extend(&requests);
+    let mut chain = mock_chain(&[&gateway], &notes)?;
+
+    consume(&mut chain, gateway.id(), &purchase).await?;
+    let (last, paid) = requests.split_last().unwrap();
+    for request in paid {
+        consume(&mut chain, gateway.id(), request).await?;
+    }
+    assert_fails_with(
+        consume(&mut chain, gateway.id(), last).await,
+        "Caller has no credits left",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn request_note_cannot_be_replayed() -> Result<()> {
+    let gateway = gateway_contract()?;
+    let payment = FungibleAsset::new(payment_faucet(), 1)?;
+    let purchase = purchase_note(&gateway, vec![payment.into()])?;
+    let request = request_note(&gateway)?;
+    let mut chain = mock_chain(&[&gateway], &[&purchase, &request])?;
+
+    consume(&mut chain, gateway.id(), &purchase).await?;
+    consume(&mut chain, gateway.id(), &request).await?;
+    assert!(consume(&mut chain, gateway.id(), &request).await.is_err());
+
+    Ok(())
+}
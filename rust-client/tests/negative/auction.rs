@@ -0,0 +1,408 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    note::{Note, NoteId, NoteTag},
+    testing::account_id::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1},
+    Felt, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::auction::{self, Auction, AuctionState};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note_script,
+    random_word, wallet,
+};
+
+/// Smallest amount the first bid can offer.
+const RESERVE: u64 = 50;
+/// Block from which bids are rejected and the auction can be closed.
+const END: u32 = 10;
+
+fn currency() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+/// The asset sold, one token of another faucet.
+fn lot() -> Result<Asset> {
+    let faucet = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1)?;
+    Ok(FungibleAsset::new(faucet, 1)?.into())
+}
+
+/// Builds an auction of `seller` waiting for its lot.
+fn auction_contract(seller: &Account) -> Result<Account> {
+    let seller_tag = NoteTag::from_account_id(seller.id());
+    contract(
+        &masm("accounts/auction.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(
+                [
+                    ZERO,
+                    seller_tag.into(),
+                    seller.id().suffix(),
+                    seller.id().prefix().as_felt(),
+                ]
+                .into(),
+            ),
+            StorageSlot::Value(
+                [
+                    ZERO,
+                    ZERO,
+                    currency().suffix(),
+                    currency().prefix().as_felt(),
+                ]
+                .into(),
+            ),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::new(RESERVE)].into()),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::from(END)].into()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )
+}
+
+/// Builds a note of `sender` running the note script at `script_path` on the auction.
+fn auction_note(
+    sender: &Account,
+    auction: &Account,
+    script_path: &str,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+) -> Result<Note> {
+    let library = create_library(masm("accounts/auction.masm"), auction::LIBRARY_PATH)?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    Ok(auction::auction_note(
+        sender.id(),
+        auction.id(),
+        inputs,
+        assets,
+        script,
+        random_word(),
+    )?)
+}
+
+fn list_note(sender: &Account, auction: &Account) -> Result<Note> {
+    auction_note(
+        sender,
+        auction,
+        "notes/auction_list_note.masm",
+        vec![],
+        vec![lot()?],
+    )
+}
+
+fn bid_note(bidder: &Account, auction: &Account, asset: FungibleAsset) -> Result<Note> {
+    let inputs = vec![NoteTag::from_account_id(bidder.id()).into()];
+    auction_note(
+        bidder,
+        auction,
+        "notes/auction_bid_note.masm",
+        inputs,
+        vec![asset.into()],
+    )
+}
+
+fn close_note(sender: &Account, auction: &Account) -> Result<Note> {
+    auction_note(
+        sender,
+        auction,
+        "notes/auction_close_note.masm",
+        vec![],
+        vec![],
+    )
+}
+
+fn bid(amount: u64) -> Result<FungibleAsset> {
+    Ok(FungibleAsset::new(currency(), amount)?)
+}
+
+fn read_auction(chain: &MockChain, auction: &Account) -> Result<Auction> {
+    Auction::from_account(&chain.committed_account(auction.id())?)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+/// Consumes `note` with the auction and returns the ids of the notes it created.
+async fn consume_by_auction(
+    chain: &mut MockChain,
+    auction: &Account,
+    note: &Note,
+) -> Result<Vec<NoteId>> {
+    let executed = chain
+        .build_tx_context(auction.id(), &[note.id()], &[])?
+        .build()?
+        .execute()
+        .await?;
+    let created = executed
+        .output_notes()
+        .iter()
+        .map(|note| note.id())
+        .collect();
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(created)
+}
+
+fn ids(notes: &[Note]) -> Vec<NoteId> {
+    notes.iter().map(Note::id).collect()
+}
+
+/// Returns the account a P2ID note pays.
+fn receiver_of(note: &Note) -> AccountId {
+    let inputs = note.recipient().inputs().values();
+    AccountId::try_from([inputs[1], inputs[0]]).unwrap()
+}
+
+#[tokio::test]
+async fn highest_of_several_bidders_wins_and_the_others_are_refunded() -> Result<()> {
+    let seller = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let carol = wallet(vec![])?;
+    let auction = auction_contract(&seller)?;
+
+    let list = list_note(&seller, &auction)?;
+    let alice_bid = bid_note(&alice, &auction, bid(60)?)?;
+    let bob_bid = bid_note(&bob, &auction, bid(75)?)?;
+    let carol_low_bid = bid_note(&carol, &auction, bid(70)?)?;
+    let carol_bid = bid_note(&carol, &auction, bid(90)?)?;
+    let close = close_note(&alice, &auction)?;
+    let mut chain = mock_chain(
+        &[&auction],
+        &[
+            &list,
+            &alice_bid,
+            &bob_bid,
+            &carol_low_bid,
+            &carol_bid,
+            &close,
+        ],
+    )?;
+
+    consume(&mut chain, auction.id(), &list).await?;
+    let mut state = read_auction(&chain, &auction)?;
+    assert_eq!(state.state, AuctionState::Open);
+    assert_eq!(state.lot, Some(lot()?));
+
+    // Every accepted bid refunds the one it outbids, with the note the client predicts
+    for note in [&alice_bid, &bob_bid] {
+        let expected = state
+            .payouts_for_bid()
+            .map_err(|err| anyhow::anyhow!("{err}"))?;
+        assert_eq!(
+            consume_by_auction(&mut chain, &auction, note).await?,
+            ids(&expected)
+        );
+        state = read_auction(&chain, &auction)?;
+    }
+    assert_fails_with(
+        consume(&mut chain, auction.id(), &carol_low_bid).await,
+        "Bid must be at least the reserve and higher than the highest bid",
+    );
+    let expected = state
+        .payouts_for_bid()
+        .map_err(|err| anyhow::anyhow!("{err}"))?;
+    assert_eq!(receiver_of(&expected[0]), bob.id());
+    assert_eq!(
+        consume_by_auction(&mut chain, &auction, &carol_bid).await?,
+        ids(&expected)
+    );
+
+    let state = read_auction(&chain, &auction)?;
+    let bidders: Vec<_> = state
+        .bids
+        .iter()
+        .map(|bid| (bid.bidder, bid.amount))
+        .collect();
+    assert_eq!(
+        bidders,
+        [(alice.id(), 60), (bob.id(), 75), (carol.id(), 90)]
+    );
+    assert_eq!(
+        chain
+            .committed_account(auction.id())?
+            .vault()
+            .get_balance(currency())?,
+        90
+    );
+
+    // Closing pays the lot to Carol and her bid to the seller
+    chain.prove_until_block(END)?;
+    let expected = state
+        .payouts_for_close()
+        .map_err(|err| anyhow::anyhow!("{err}"))?;
+    assert_eq!(receiver_of(&expected[0]), carol.id());
+    assert_eq!(receiver_of(&expected[1]), seller.id());
+    assert_eq!(
+        consume_by_auction(&mut chain, &auction, &close).await?,
+        ids(&expected)
+    );
+
+    let state = read_auction(&chain, &auction)?;
+    assert_eq!(state.state, AuctionState::Closed);
+    assert_eq!(
+        chain
+            .committed_account(auction.id())?
+            .vault()
+            .assets()
+            .count(),
+        0
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_the_seller_can_list_the_lot() -> Result<()> {
+    let seller = wallet(vec![])?;
+    let outsider = wallet(vec![])?;
+    let auction = auction_contract(&seller)?;
+    let list = list_note(&outsider, &auction)?;
+    let mut chain = mock_chain(&[&auction], &[&list])?;
+
+    assert_fails_with(
+        consume(&mut chain, auction.id(), &list).await,
+        "Only the seller can list the lot",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bids_are_rejected_before_the_lot_is_listed() -> Result<()> {
+    let seller = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let auction = auction_contract(&seller)?;
+    let early_bid = bid_note(&alice, &auction, bid(RESERVE)?)?;
+    let mut chain = mock_chain(&[&auction], &[&early_bid])?;
+
+    assert_fails_with(
+        consume(&mut chain, auction.id(), &early_bid).await,
+        "Auction is not open",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn first_bid_below_the_reserve_is_rejected() -> Result<()> {
+    let seller = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let auction = auction_contract(&seller)?;
+    let list = list_note(&seller, &auction)?;
+    let low_bid = bid_note(&alice, &auction, bid(RESERVE - 1)?)?;
+    let mut chain = mock_chain(&[&auction], &[&list, &low_bid])?;
+
+    consume(&mut chain, auction.id(), &list).await?;
+    assert_fails_with(
+        consume(&mut chain, auction.id(), &low_bid).await,
+        "Bid must be at least the reserve and higher than the highest bid",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bid_in_another_currency_is_rejected() -> Result<()> {
+    let seller = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let auction = auction_contract(&seller)?;
+    let list = list_note(&seller, &auction)?;
+    let Asset::Fungible(lot_token) = lot()? else {
+        unreachable!("the lot is fungible")
+    };
+    let wrong_bid = bid_note(
+        &alice,
+        &auction,
+        FungibleAsset::new(lot_token.faucet_id(), 100)?,
+    )?;
+    let mut chain = mock_chain(&[&auction], &[&list, &wrong_bid])?;
+
+    consume(&mut chain, auction.id(), &list).await?;
+    assert_fails_with(
+        consume(&mut chain, auction.id(), &wrong_bid).await,
+        "Bid is not paid in the currency of the auction",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn seller_cannot_bid_on_its_own_lot() -> Result<()> {
+    let seller = wallet(vec![])?;
+    let auction = auction_contract(&seller)?;
+    let list = list_note(&seller, &auction)?;
+    let shill_bid = bid_note(&seller, &auction, bid(RESERVE)?)?;
+    let mut chain = mock_chain(&[&auction], &[&list, &shill_bid])?;
+
+    consume(&mut chain, auction.id(), &list).await?;
+    assert_fails_with(
+        consume(&mut chain, auction.id(), &shill_bid).await,
+        "Seller cannot bid on its own lot",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bids_are_rejected_from_the_end_block_on() -> Result<()> {
+    let seller = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let auction = auction_contract(&seller)?;
+    let list = list_note(&seller, &auction)?;
+    let late_bid = bid_note(&alice, &auction, bid(RESERVE)?)?;
+    let mut chain = mock_chain(&[&auction], &[&list, &late_bid])?;
+
+    consume(&mut chain, auction.id(), &list).await?;
+    chain.prove_until_block(END)?;
+    assert_fails_with(
+        consume(&mut chain, auction.id(), &late_bid).await,
+        "Auction has ended",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn auction_cannot_be_closed_before_the_end_block() -> Result<()> {
+    let seller = wallet(vec![])?;
+    let auction = auction_contract(&seller)?;
+    let list = list_note(&seller, &auction)?;
+    let close = close_note(&seller, &auction)?;
+    let mut chain = mock_chain(&[&auction], &[&list, &close])?;
+
+    consume(&mut chain, auction.id(), &list).await?;
+    assert_fails_with(
+        consume(&mut chain, auction.id(), &close).await,
+        "Auction cannot be closed before its end block",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unsold_lot_goes_back_to_the_seller() -> Result<()> {
+    let seller = wallet(vec![])?;
+    let auction = auction_contract(&seller)?;
+    let list = list_note(&seller, &auction)?;
+    let close = close_note(&seller, &auction)?;
+    let mut chain = mock_chain(&[&auction], &[&list, &close])?;
+
+    consume(&mut chain, auction.id(), &list).await?;
+    chain.prove_until_block(END)?;
+    let expected = read_auction(&chain, &auction)?
+        .payouts_for_close()
+        .map_err(|err| anyhow::anyhow!("{err}"))?;
+    assert_eq!(receiver_of(&expected[0]), seller.id());
+    assert_eq!(
+        consume_by_auction(&mut chain, &auction, &close).await?,
+        ids(&expected)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,430 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    crypto::rand::RpoRandomCoin,
+    note::{Note, NoteTag},
+    testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+    Felt, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::battleship::{self, Board, CellOpening, Game};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note_script,
+    random_word, wallet,
+};
+
+/// Amount each player stakes.
+const STAKE: u64 = 50;
+/// Blocks a challenged player has to open the challenged cell.
+const CHALLENGE_PERIOD: u32 = 5;
+
+fn currency() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn tokens(amount: u64) -> Result<FungibleAsset> {
+    Ok(FungibleAsset::new(currency(), amount)?)
+}
+
+fn board(ships: &[usize]) -> Result<Board> {
+    Board::new(ships, &mut RpoRandomCoin::new(random_word()))
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+/// Builds a game between `player1` and `player2` which nobody joined yet.
+fn game_contract(player1: &Account, player2: &Account) -> Result<Account> {
+    let player_word = |player: &Account| -> Word {
+        [
+            ZERO,
+            ZERO,
+            player.id().suffix(),
+            player.id().prefix().as_felt(),
+        ]
+        .into()
+    };
+    let value_word = |value: u64| -> Word { [ZERO, ZERO, ZERO, Felt::new(value)].into() };
+
+    contract(
+        &masm("accounts/battleship.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(player_word(player1)),
+            StorageSlot::Value(player_word(player2)),
+            StorageSlot::Value(Asset::from(tokens(STAKE)?).into()),
+            StorageSlot::Value(value_word(CHALLENGE_PERIOD.into())),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(value_word(1)),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )
+}
+
+/// Builds a note of `sender` running the note script at `script_path` on the game.
+fn game_note(
+    sender: &Account,
+    game: &Account,
+    script_path: &str,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+) -> Result<Note> {
+    let library = create_library(masm("accounts/battleship.masm"), battleship::LIBRARY_PATH)?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    Ok(battleship::game_note(
+        sender.id(),
+        game.id(),
+        inputs,
+        assets,
+        script,
+        random_word(),
+    )?)
+}
+
+fn join_note(
+    player: &Account,
+    game: &Account,
+    board: &Board,
+    stake: FungibleAsset,
+) -> Result<Note> {
+    let mut inputs = board
+        .commitment()
+        .map_err(|err| anyhow::anyhow!("{err}"))?
+        .as_elements()
+        .to_vec();
+    inputs.push(NoteTag::from_account_id(player.id()).into());
+    game_note(
+        player,
+        game,
+        "notes/battleship_join_note.masm",
+        inputs,
+        vec![stake.into()],
+    )
+}
+
+fn shot_note(player: &Account, game: &Account, cell: u64) -> Result<Note> {
+    let inputs = vec![Felt::new(cell)];
+    game_note(
+        player,
+        game,
+        "notes/battleship_shot_note.masm",
+        inputs,
+        vec![],
+    )
+}
+
+fn answer_note(player: &Account, game: &Account, is_hit: bool) -> Result<Note> {
+    let inputs = vec![Felt::from(is_hit as u8)];
+    game_note(
+        player,
+        game,
+        "notes/battleship_answer_note.masm",
+        inputs,
+        vec![],
+    )
+}
+
+fn challenge_note(player: &Account, game: &Account, cell: u64) -> Result<Note> {
+    let inputs = vec![Felt::new(cell)];
+    game_note(
+        player,
+        game,
+        "notes/battleship_challenge_note.masm",
+        inputs,
+        vec![],
+    )
+}
+
+fn open_note(player: &Account, game: &Account, opening: &CellOpening) -> Result<Note> {
+    let inputs = opening.value.as_elements().to_vec();
+    game_note(
+        player,
+        game,
+        "notes/battleship_open_note.masm",
+        inputs,
+        vec![],
+    )
+}
+
+fn slash_note(sender: &Account, game: &Account) -> Result<Note> {
+    game_note(
+        sender,
+        game,
+        "notes/battleship_slash_note.masm",
+        vec![],
+        vec![],
+    )
+}
+
+fn opening(board: &Board, cell: usize) -> Result<CellOpening> {
+    board.open(cell).map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+fn read_game(chain: &MockChain, game: &Account) -> Result<Game> {
+    Game::from_account(&chain.committed_account(game.id())?).map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+fn balance(chain: &MockChain, game: &Account) -> Result<u64> {
+    Ok(chain
+        .committed_account(game.id())?
+        .vault()
+        .get_balance(currency())?)
+}
+
+/// Consumes the open note `note` with the game, providing the Merkle proof of `opening` in the
+/// advice inputs, and includes the transaction in a new block.
+async fn open(
+    chain: &mut MockChain,
+    game: &Account,
+    note: &Note,
+    opening: &CellOpening,
+) -> Result<()> {
+    let executed = chain
+        .build_tx_context(game.id(), &[note.id()], &[])?
+        .extend_merkle_store(opening.merkle_nodes.iter().cloned())
+        .extend_advice_map([opening.leaf.clone()])
+        .build()?
+        .execute()
+        .await?;
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn hitting_every_ship_cell_wins_both_stakes() -> Result<()> {
+    let (alice, bob) = (wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let (alice_board, bob_board) = (board(&[0, 1, 2])?, board(&[5, 9, 13])?);
+    let notes = [
+        join_note(&alice, &game, &alice_board, tokens(STAKE)?)?,
+        join_note(&bob, &game, &bob_board, tokens(STAKE)?)?,
+        shot_note(&alice, &game, 5)?,
+        answer_note(&bob, &game, true)?,
+        shot_note(&bob, &game, 15)?,
+        answer_note(&alice, &game, false)?,
+        shot_note(&alice, &game, 9)?,
+        answer_note(&bob, &game, true)?,
+        shot_note(&bob, &game, 0)?,
+        answer_note(&alice, &game, true)?,
+        shot_note(&alice, &game, 13)?,
+        answer_note(&bob, &game, true)?,
+    ];
+    let mut chain = mock_chain(&[&game], &notes.iter().collect::<Vec<_>>())?;
+
+    for note in &notes[..2] {
+        consume(&mut chain, game.id(), note).await?;
+    }
+    let state = read_game(&chain, &game)?;
+    assert!(state.boards.iter().all(Option::is_some));
+    assert_eq!(balance(&chain, &game)?, 2 * STAKE);
+
+    for note in &notes[2..11] {
+        consume(&mut chain, game.id(), note).await?;
+    }
+    let state = read_game(&chain, &game)?;
+    assert_eq!(state.hits, [2, 1]);
+    assert_eq!(state.claims[1][5], Some(true));
+    assert_eq!(state.claims[0][15], Some(false));
+    assert_eq!(state.pending_shot, Some(13));
+    assert_eq!(state.winner_after_answer(true), Some(alice.id()));
+
+    consume(&mut chain, game.id(), &notes[11]).await?;
+    let state = read_game(&chain, &game)?;
+    assert_eq!(state.winner, Some(alice.id()));
+    assert_eq!(balance(&chain, &game)?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn challenged_claim_is_backed_by_opening_the_cell() -> Result<()> {
+    let (alice, bob) = (wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let (alice_board, bob_board) = (board(&[0, 1, 2])?, board(&[5, 9, 13])?);
+    let opened = opening(&bob_board, 6)?;
+    let notes = [
+        join_note(&alice, &game, &alice_board, tokens(STAKE)?)?,
+        join_note(&bob, &game, &bob_board, tokens(STAKE)?)?,
+        shot_note(&alice, &game, 6)?,
+        answer_note(&bob, &game, false)?,
+        challenge_note(&alice, &game, 6)?,
+        open_note(&bob, &game, &opened)?,
+    ];
+    let mut chain = mock_chain(&[&game], &notes.iter().collect::<Vec<_>>())?;
+
+    for note in &notes[..5] {
+        consume(&mut chain, game.id(), note).await?;
+    }
+    let challenge = read_game(&chain, &game)?
+        .challenge
+        .expect("the challenge is recorded");
+    assert_eq!(challenge.challenger, alice.id());
+    assert_eq!(challenge.cell, 6);
+
+    open(&mut chain, &game, &notes[5], &opened).await?;
+    let state = read_game(&chain, &game)?;
+    assert_eq!(state.challenge, None);
+    assert_eq!(state.winner, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn false_claim_cannot_be_opened_and_is_slashed() -> Result<()> {
+    let (alice, bob) = (wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let (alice_board, bob_board) = (board(&[0, 1, 2])?, board(&[5, 9, 13])?);
+    // Bob answers the hit on cell 5 with a miss
+    let opened = opening(&bob_board, 5)?;
+    let notes = [
+        join_note(&alice, &game, &alice_board, tokens(STAKE)?)?,
+        join_note(&bob, &game, &bob_board, tokens(STAKE)?)?,
+        shot_note(&alice, &game, 5)?,
+        answer_note(&bob, &game, false)?,
+        challenge_note(&alice, &game, 5)?,
+        open_note(&bob, &game, &opened)?,
+        slash_note(&bob, &game)?,
+    ];
+    let mut chain = mock_chain(&[&game], &notes.iter().collect::<Vec<_>>())?;
+
+    for note in &notes[..5] {
+        consume(&mut chain, game.id(), note).await?;
+    }
+    assert_fails_with(
+        open(&mut chain, &game, &notes[5], &opened).await,
+        "Cell does not match the claim",
+    );
+
+    let deadline = read_game(&chain, &game)?
+        .challenge
+        .expect("the challenge is recorded")
+        .deadline;
+    chain.prove_until_block(deadline)?;
+    consume(&mut chain, game.id(), &notes[6]).await?;
+    let state = read_game(&chain, &game)?;
+    assert_eq!(state.winner, Some(alice.id()));
+    assert_eq!(balance(&chain, &game)?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cell_not_in_the_committed_board_is_rejected() -> Result<()> {
+    let (alice, bob) = (wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let (alice_board, bob_board) = (board(&[0, 1, 2])?, board(&[5, 9, 13])?);
+    // Bob backs the miss on cell 5 by opening it as water, with the genuine Merkle proof
+    let genuine = opening(&bob_board, 5)?;
+    let value = genuine.value;
+    let forged = CellOpening {
+        value: [value[0], value[1], value[2], ZERO].into(),
+        ..genuine
+    };
+    let notes = [
+        join_note(&alice, &game, &alice_board, tokens(STAKE)?)?,
+        join_note(&bob, &game, &bob_board, tokens(STAKE)?)?,
+        shot_note(&alice, &game, 5)?,
+        answer_note(&bob, &game, false)?,
+        challenge_note(&alice, &game, 5)?,
+        open_note(&bob, &game, &forged)?,
+    ];
+    let mut chain = mock_chain(&[&game], &notes.iter().collect::<Vec<_>>())?;
+
+    for note in &notes[..5] {
+        consume(&mut chain, game.id(), note).await?;
+    }
+    assert_fails_with(
+        open(&mut chain, &game, &notes[5], &forged).await,
+        "Cell does not match the board commitment",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn slash_waits_for_the_challenge_period() -> Result<()> {
+    let (alice, bob) = (wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let notes = [
+        join_note(&alice, &game, &board(&[0, 1, 2])?, tokens(STAKE)?)?,
+        join_note(&bob, &game, &board(&[5, 9, 13])?, tokens(STAKE)?)?,
+        shot_note(&alice, &game, 5)?,
+        answer_note(&bob, &game, false)?,
+        challenge_note(&alice, &game, 5)?,
+        slash_note(&alice, &game)?,
+    ];
+    let mut chain = mock_chain(&[&game], &notes.iter().collect::<Vec<_>>())?;
+
+    for note in &notes[..5] {
+        consume(&mut chain, game.id(), note).await?;
+    }
+    assert_fails_with(
+        consume(&mut chain, game.id(), &notes[5]).await,
+        "Challenge period is not over",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_the_player_whose_turn_it_is_can_shoot() -> Result<()> {
+    let (alice, bob) = (wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let notes = [
+        join_note(&alice, &game, &board(&[0, 1, 2])?, tokens(STAKE)?)?,
+        join_note(&bob, &game, &board(&[5, 9, 13])?, tokens(STAKE)?)?,
+        shot_note(&bob, &game, 0)?,
+    ];
+    let mut chain = mock_chain(&[&game], &notes.iter().collect::<Vec<_>>())?;
+
+    for note in &notes[..2] {
+        consume(&mut chain, game.id(), note).await?;
+    }
+    assert_fails_with(
+        consume(&mut chain, game.id(), &notes[2]).await,
+        "It is not the turn of the sender",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn join_below_the_stake_is_rejected() -> Result<()> {
+    let (alice, bob) = (wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let join = join_note(&alice, &game, &board(&[0, 1, 2])?, tokens(STAKE - 1)?)?;
+    let mut chain = mock_chain(&[&game], &[&join])?;
+
+    assert_fails_with(
+        consume(&mut chain, game.id(), &join).await,
+        "Asset is not the stake of the game",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_the_players_can_join() -> Result<()> {
+    let (alice, bob, carol) = (wallet(vec![])?, wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let join = join_note(&carol, &game, &board(&[0, 1, 2])?, tokens(STAKE)?)?;
+    let mut chain = mock_chain(&[&game], &[&join])?;
+
+    assert_fails_with(
+        consume(&mut chain, game.id(), &join).await,
+        "Sender does not play this game",
+    );
+
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use anyhow::Result;
+use miden_lib::{
+    account::{auth::NoAuth, wallets::BasicWallet},
+    note::WellKnownNote,
+    transaction::TransactionKernel,
+};
+use miden_objects::{
+    account::{
+        Account, AccountBuilder, AccountComponent, AccountId, AccountStorageMode, AccountType,
+        StorageMap, StorageSlot,
+    },
+    assembly::Library,
+    asset::FungibleAsset,
+    testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+    Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::budget::{bucket_id, Budget, Operation, LIBRARY_PATH};
+
+use crate::common::{
+    assert_fails_with, create_library, masm, mock_chain, note, run_script, sender, tx_script,
+};
+
+/// Tokens in the vault of every test budget.
+const BALANCE: u64 = 100;
+
+fn faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+/// Builds a wallet holding [`BALANCE`] tokens with an empty budget on top. The account uses
+/// `NoAuth`, so the tests need no signatures.
+fn budget_account() -> Result<(Account, Library)> {
+    let code = masm("accounts/budget.masm");
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value([ZERO, ZERO, faucet().suffix(), faucet().prefix().as_felt()].into()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    let account = AccountBuilder::new(rand::random())
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(BasicWallet)
+        .with_component(component)
+        .with_assets(vec![FungibleAsset::new(faucet(), BALANCE)?.into()])
+        .build_existing()?;
+    let library = create_library(code, LIBRARY_PATH)?;
+    Ok((account, library))
+}
+
+/// Applies `operation` to `budget` and includes the transaction in a new block.
+async fn apply(
+    chain: &mut MockChain,
+    budget: &Account,
+    library: &Library,
+    operation: Operation,
+) -> Result<()> {
+    let code = operation
+        .script_code()
+        .map_err(|err| anyhow::anyhow!("{err}"))?;
+    run_script(chain, budget.id(), tx_script(&code, Some(library))?).await
+}
+
+/// Builds a P2ID note paying `amount` tokens to the test sender.
+fn payment(amount: u64) -> Result<Operation> {
+    let payment = note(
+        sender(),
+        WellKnownNote::P2ID.script(),
+        vec![sender().suffix(), sender().prefix().as_felt()],
+        vec![FungibleAsset::new(faucet(), amount)?.into()],
+    )?;
+    Ok(Operation::Spend {
+        bucket: bucket_id("rent"),
+        payment,
+    })
+}
+
+fn read_budget(chain: &MockChain, budget: &Account) -> Result<Budget> {
+    Budget::from_account(chain.committed_account(budget.id())?)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+#[tokio::test]
+async fn spending_empties_the_bucket_and_the_vault_together() -> Result<()> {
+    let (budget, library) = budget_account()?;
+    let mut chain = mock_chain(&[&budget], &[])?;
+    let (rent, food) = (bucket_id("rent"), bucket_id("food"));
+
+    for operation in [
+        Operation::Allocate {
+            bucket: rent,
+            amount: 40,
+        },
+        Operation::Allocate {
+            bucket: food,
+            amount: 30,
+        },
+        Operation::Reallocate {
+            from: food,
+            to: rent,
+            amount: 10,
+        },
+        payment(45)?,
+    ] {
+        apply(&mut chain, &budget, &library, operation).await?;
+    }
+
+    let state = read_budget(&chain, &budget)?;
+    assert_eq!(state.balance, BALANCE - 45);
+    assert_eq!(state.allocated, 25);
+    assert_eq!(state.bucket(rent), 5);
+    assert_eq!(state.bucket(food), 20);
+    assert_eq!(state.unallocated(), 30);
+
+    assert_fails_with(
+        apply(&mut chain, &budget, &library, payment(6)?).await,
+        "Bucket does not hold enough tokens",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn allocation_is_limited_to_unallocated_tokens() -> Result<()> {
+    let (budget, library) = budget_account()?;
+    let mut chain = mock_chain(&[&budget], &[])?;
+
+    let allocate = |bucket, amount| Operation::Allocate {
+        bucket: bucket_id(bucket),
+        amount,
+    };
+    apply(&mut chain, &budget, &library, allocate("rent", 60)).await?;
+
+    assert_fails_with(
+        apply(&mut chain, &budget, &library, allocate("food", 41)).await,
+        "Not enough unallocated tokens",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn release_is_limited_to_the_bucket() -> Result<()> {
+    let (budget, library) = budget_account()?;
+    let mut chain = mock_chain(&[&budget], &[])?;
+    let rent = bucket_id("rent");
+
+    let allocate = Operation::Allocate {
+        bucket: rent,
+        amount: 10,
+    };
+    apply(&mut chain, &budget, &library, allocate).await?;
+
+    let release = Operation::Release {
+        bucket: rent,
+        amount: 11,
+    };
+    assert_fails_with(
+        apply(&mut chain, &budget, &library, release).await,
+        "Bucket does not hold enough tokens",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reallocation_is_limited_to_the_source_bucket() -> Result<()> {
+    let (budget, library) = budget_account()?;
+    let mut chain = mock_chain(&[&budget], &[])?;
+
+    // The target bucket holding enough does not help
+    let allocate = Operation::Allocate {
+        bucket: bucket_id("rent"),
+        amount: 50,
+    };
+    apply(&mut chain, &budget, &library, allocate).await?;
+
+    let reallocate = Operation::Reallocate {
+        from: bucket_id("food"),
+        to: bucket_id("rent"),
+        amount: 1,
+    };
+    assert_fails_with(
+        apply(&mut chain, &budget, &library, reallocate).await,
+        "Bucket does not hold enough tokens",
+    );
+
+    Ok(())
+}
@@ -0,0 +1,229 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use anyhow::Result;
+use miden_lib::{
+    account::auth::NoAuth, account::wallets::BasicWallet, transaction::TransactionKernel,
+    utils::ScriptBuilder,
+};
+use miden_objects::{
+    account::{
+        Account, AccountBuilder, AccountComponent, AccountId, AccountStorageMode, AccountType,
+        StorageSlot,
+    },
+    assembly::Library,
+    asset::Asset,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteScript,
+        NoteTag, NoteType,
+    },
+    testing::account_id::ACCOUNT_ID_SENDER,
+    transaction::TransactionScript,
+    Felt, Word,
+};
+use miden_testing::MockChain;
+
+/// Reads a MASM file relative to the `masm` directory at the root of the repository.
+pub fn masm(relative_path: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../masm")
+        .join(relative_path);
+    fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {err}", path.display()))
+}
+
+/// Creates a Miden library from the provided account code and library path.
+pub fn create_library(account_code: String, library_path: &str) -> Result<Library> {
+    rust_client::helpers::create_library(&account_code, library_path)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+/// Builds an existing public contract account with the given code, type and storage.
+pub fn contract(
+    code: &str,
+    account_type: AccountType,
+    storage: Vec<StorageSlot>,
+) -> Result<Account> {
+    let component = AccountComponent::compile(code, TransactionKernel::assembler(), storage)?
+        .with_supports_all_types();
+
+    Ok(AccountBuilder::new(rand::random())
+        .account_type(account_type)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(component)
+        .build_existing()?)
+}
+
+/// Builds an existing basic wallet holding `assets`, used as the wrong target of notes and
+/// scripts written for a contract.
+pub fn wallet(assets: Vec<Asset>) -> Result<Account> {
+    Ok(AccountBuilder::new(rand::random())
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(NoAuth)
+        .with_component(BasicWallet)
+        .with_assets(assets)
+        .build_existing()?)
+}
+
+/// Compiles a note script, linking the contract library if one is given.
+pub fn note_script(code: &str, library: Option<&Library>) -> Result<NoteScript> {
+    let mut builder = ScriptBuilder::new(true);
+    if let Some(library) = library {
+        builder = builder.with_dynamically_linked_library(library)?;
+    }
+    Ok(builder.compile_note_script(code)?)
+}
+
+/// Compiles a transaction script, linking the contract library if one is given.
+pub fn tx_script(code: &str, library: Option<&Library>) -> Result<TransactionScript> {
+    let mut builder = ScriptBuilder::new(true);
+    if let Some(library) = library {
+        builder = builder.with_dynamically_linked_library(library)?;
+    }
+    Ok(builder.compile_tx_script(code)?)
+}
+
+/// Returns a random word, used for serial numbers and recipients.
+pub fn random_word() -> Word {
+    Word::from(rand::random::<[u32; 4]>().map(Felt::from))
+}
+
+/// Returns the account used as the sender of every test note.
+pub fn sender() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_SENDER).unwrap()
+}
+
+/// Builds a public note for `target` sent by the test sender.
+pub fn note(
+    target: AccountId,
+    script: NoteScript,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+) -> Result<Note> {
+    let recipient = NoteRecipient::new(random_word(), script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender(),
+        NoteType::Public,
+        NoteTag::from_account_id(target),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+
+    Ok(Note::new(NoteAssets::new(assets)?, metadata, recipient))
+}
+
+/// Returns the `[RECIPIENT, tag, aux, note_type, execution_hint]` inputs contracts use to
+/// create an output note for the test sender.
+pub fn output_note_inputs() -> Vec<Felt> {
+    let mut inputs = random_word().to_vec();
+    inputs.extend([
+        NoteTag::from_account_id(sender()).into(),
+        Felt::new(0),
+        Felt::from(NoteType::Public),
+        Felt::new(u64::from(NoteExecutionHint::always())),
+    ]);
+    inputs
+}
+
+/// Builds a mock chain containing `accounts` and the committed `notes`.
+pub fn mock_chain(accounts: &[&Account], notes: &[&Note]) -> Result<MockChain> {
+    let mut builder = MockChain::builder();
+    for account in accounts {
+        builder.add_account((*account).clone())?;
+    }
+    for note in notes {
+        builder.add_output_note((*note).clone());
+    }
+    builder.build()
+}
+
+/// Consumes a committed note with `account_id` and includes the transaction in a new block.
+pub async fn consume(chain: &mut MockChain, account_id: AccountId, note: &Note) -> Result<()> {
+    let executed = chain
+        .build_tx_context(account_id, &[note.id()], &[])?
+        .build()?
+        .execute()
+        .await?;
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(())
+}
+
+/// Consumes a committed note with `account_id`, loading the `foreign` accounts its script reads
+/// through FPI, and includes the transaction in a new block.
+pub async fn consume_with_foreign_accounts(
+    chain: &mut MockChain,
+    account_id: AccountId,
+    note: &Note,
+    foreign: &[AccountId],
+) -> Result<()> {
+    let foreign_accounts = foreign
+        .iter()
+        .map(|id| chain.get_foreign_account_inputs(*id))
+        .collect::<Result<Vec<_>>>()?;
+    let executed = chain
+        .build_tx_context(account_id, &[note.id()], &[])?
+        .foreign_accounts(foreign_accounts)
+        .build()?
+        .execute()
+        .await?;
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(())
+}
+
+/// Executes the consumption of a committed note by `account_id`, passing `note_args` to its
+/// script, without including the transaction in a block.
+pub async fn consume_with_args(
+    chain: &MockChain,
+    account_id: AccountId,
+    note: &Note,
+    note_args: Word,
+) -> Result<()> {
+    chain
+        .build_tx_context(account_id, &[note.id()], &[])?
+        .extend_note_args(BTreeMap::from([(note.id(), note_args)]))
+        .build()?
+        .execute()
+        .await?;
+    Ok(())
+}
+
+/// Runs `script` against `account_id` and includes the transaction in a new block.
+pub async fn run_script(
+    chain: &mut MockChain,
+    account_id: AccountId,
+    script: TransactionScript,
+) -> Result<()> {
+    let executed = chain
+        .build_tx_context(account_id, &[], &[])?
+        .tx_script(script)
+        .build()?
+        .execute()
+        .await?;
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(())
+}
+
+/// Asserts that `result` failed with an error mentioning `message`.
+pub fn assert_fails_with(result: Result<()>, message: &str) {
+    let err = result.expect_err("transaction was expected to fail");
+    assert!(
+        format!("{err:?}").contains(message),
+        "expected failure `{message}`, got: {err:?}"
+    );
+}
+
+/// Transaction script writing storage slot 0 directly instead of going through the contract.
+pub const DIRECT_STORAGE_WRITE: &str = "
+use.miden::native_account
+
+begin
+    push.0.0.0.99
+    push.0
+    exec.native_account::set_item
+    dropw
+end
+";
@@ -0,0 +1,184 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageSlot},
+    assembly::Library,
+    note::Note,
+    Felt, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::connect_four::{self, Board, Disc, Game, LIBRARY_PATH, NUM_ROWS};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note_script,
+    random_word, sender, wallet,
+};
+
+/// Builds a game between the test sender (red) and `player2` (yellow) whose winner slot holds
+/// `winner`.
+fn game_contract(player2: AccountId, winner: Felt) -> Result<(Account, Library)> {
+    let player_word = |player: AccountId| -> Word {
+        [ZERO, ZERO, player.suffix(), player.prefix().as_felt()].into()
+    };
+
+    let code = masm("accounts/connect_four.masm");
+    let account = contract(
+        &code,
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(player_word(sender())),
+            StorageSlot::Value(player_word(player2)),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value([ZERO, ZERO, ZERO, winner].into()),
+        ],
+    )?;
+    let library = create_library(code, LIBRARY_PATH)?;
+    Ok((account, library))
+}
+
+/// Builds the note by which `player` drops a disc into `column`.
+fn move_note(player: AccountId, game: &Account, library: &Library, column: usize) -> Result<Note> {
+    let script = note_script(&masm("notes/connect_four_move_note.masm"), Some(library))?;
+    Ok(connect_four::move_note(
+        player,
+        game.id(),
+        column,
+        script,
+        random_word(),
+    )?)
+}
+
+/// Builds the notes of a game between the test sender and `player2` dropping discs into
+/// `columns`, alternating red and yellow.
+fn moves(
+    player2: AccountId,
+    game: &Account,
+    library: &Library,
+    columns: &[usize],
+) -> Result<Vec<Note>> {
+    columns
+        .iter()
+        .enumerate()
+        .map(|(turn, &column)| {
+            let player = if turn % 2 == 0 { sender() } else { player2 };
+            move_note(player, game, library, column)
+        })
+        .collect()
+}
+
+fn read_game(chain: &MockChain, game: &Account) -> Result<Game> {
+    Game::from_account(chain.committed_account(game.id())?).map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+#[tokio::test]
+async fn line_of_four_wins_in_every_direction() -> Result<()> {
+    let games: [&[usize]; 4] = [
+        // Horizontal, on the bottom row against the right edge
+        &[3, 3, 4, 4, 5, 5, 6],
+        // Vertical
+        &[4, 5, 4, 5, 4, 5, 4],
+        // Rising diagonal from column 0 to 3
+        &[0, 1, 1, 2, 2, 3, 2, 3, 3, 6, 3],
+        // Falling diagonal from column 6 to 3
+        &[6, 5, 5, 4, 4, 3, 4, 3, 3, 0, 3],
+    ];
+
+    for columns in games {
+        let opponent = wallet(vec![])?;
+        let (game, library) = game_contract(opponent.id(), ZERO)?;
+        let notes = moves(opponent.id(), &game, &library, columns)?;
+        let mut chain = mock_chain(&[&game], &notes.iter().collect::<Vec<_>>())?;
+
+        let mut expected = Board::default();
+        for (turn, (note, &column)) in notes.iter().zip(columns).enumerate() {
+            let state = read_game(&chain, &game)?;
+            assert_eq!(state.winner, None, "{columns:?}\n{}", state.board);
+
+            consume(&mut chain, game.id(), note).await?;
+            let disc = if turn % 2 == 0 {
+                Disc::Red
+            } else {
+                Disc::Yellow
+            };
+            expected
+                .drop_disc(column, disc)
+                .map_err(|err| anyhow::anyhow!("{err}"))?;
+        }
+
+        let state = read_game(&chain, &game)?;
+        assert_eq!(state.board, expected, "{columns:?}");
+        assert_eq!(
+            state.winner,
+            Some(Disc::Red),
+            "{columns:?}\n{}",
+            state.board
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn second_player_cannot_move_first() -> Result<()> {
+    let opponent = wallet(vec![])?;
+    let (game, library) = game_contract(opponent.id(), ZERO)?;
+    let early_move = move_note(opponent.id(), &game, &library, 3)?;
+    let mut chain = mock_chain(&[&game], &[&early_move])?;
+
+    assert_fails_with(
+        consume(&mut chain, game.id(), &early_move).await,
+        "It is not the turn of the sender",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn column_must_be_on_the_board() -> Result<()> {
+    let opponent = wallet(vec![])?;
+    let (game, library) = game_contract(opponent.id(), ZERO)?;
+    let off_board = move_note(sender(), &game, &library, 7)?;
+    let mut chain = mock_chain(&[&game], &[&off_board])?;
+
+    assert_fails_with(
+        consume(&mut chain, game.id(), &off_board).await,
+        "Column must be between 0 and 6",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn full_column_takes_no_more_discs() -> Result<()> {
+    let opponent = wallet(vec![])?;
+    let (game, library) = game_contract(opponent.id(), ZERO)?;
+    let notes = moves(opponent.id(), &game, &library, &[2; NUM_ROWS + 1])?;
+    let mut chain = mock_chain(&[&game], &notes.iter().collect::<Vec<_>>())?;
+
+    for note in &notes[..NUM_ROWS] {
+        consume(&mut chain, game.id(), note).await?;
+    }
+    assert_eq!(read_game(&chain, &game)?.board.height(2), NUM_ROWS);
+    assert_fails_with(
+        consume(&mut chain, game.id(), &notes[NUM_ROWS]).await,
+        "Column is full",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn no_move_after_the_game_is_won() -> Result<()> {
+    let opponent = wallet(vec![])?;
+    let (game, library) = game_contract(opponent.id(), Felt::new(1))?;
+    let late_move = move_note(sender(), &game, &library, 0)?;
+    let mut chain = mock_chain(&[&game], &[&late_move])?;
+
+    assert_fails_with(
+        consume(&mut chain, game.id(), &late_move).await,
+        "Game is already over",
+    );
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use anyhow::Result;
+use miden_lib::transaction::TransactionKernel;
+use miden_objects::{
+    account::{Account, AccountComponent, AccountId, AccountType, StorageSlot},
+    Felt, Word,
+};
+
+use crate::common::{
+    contract, create_library, masm, mock_chain, run_script, tx_script, DIRECT_STORAGE_WRITE,
+};
+
+fn count_reader_contract() -> Result<Account> {
+    contract(
+        &masm("accounts/count_reader.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![StorageSlot::Value(Word::default())],
+    )
+}
+
+fn counter_contract() -> Result<Account> {
+    contract(
+        &masm("accounts/counter.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![StorageSlot::Value(Word::default())],
+    )
+}
+
+/// Returns the hash of the `get_count` procedure of the counter, formatted for `push`.
+fn get_count_hash() -> Result<String> {
+    let component = AccountComponent::compile(
+        &masm("accounts/counter.masm"),
+        TransactionKernel::assembler(),
+        vec![],
+    )?;
+    let export = component
+        .library()
+        .exports()
+        .find(|export| export.name.name.as_str() == "get_count")
+        .unwrap();
+    let node_id = component.library().get_export_node_id(&export.name);
+    let digest = component
+        .library()
+        .mast_forest()
+        .get_node_by_id(node_id)
+        .unwrap()
+        .digest();
+
+    Ok(digest
+        .as_elements()
+        .iter()
+        .map(|f: &Felt| f.as_int().to_string())
+        .collect::<Vec<_>>()
+        .join("."))
+}
+
+/// Fills in the reader script so that it reads the count of `counter_id`.
+fn reader_script(counter_id: AccountId) -> Result<String> {
+    Ok(masm("scripts/reader_script.masm")
+        .replace("{get_count_proc_hash}", &get_count_hash()?)
+        .replace("{account_id_suffix}", &counter_id.suffix().to_string())
+        .replace("{account_id_prefix}", &counter_id.prefix().to_string()))
+}
+
+#[tokio::test]
+async fn count_cannot_be_read_from_the_native_account() -> Result<()> {
+    let reader = count_reader_contract()?;
+    let mut chain = mock_chain(&[&reader], &[])?;
+
+    // Foreign procedure invocation against the executing account itself is forbidden
+    let library = create_library(
+        masm("accounts/count_reader.masm"),
+        "external_contract::count_reader_contract",
+    )?;
+    let script = tx_script(&reader_script(reader.id())?, Some(&library))?;
+    assert!(run_script(&mut chain, reader.id(), script).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn count_cannot_be_read_without_foreign_account_inputs() -> Result<()> {
+    let reader = count_reader_contract()?;
+    let counter = counter_contract()?;
+    let mut chain = mock_chain(&[&reader, &counter], &[])?;
+
+    // The counter exists on chain, but the transaction does not provide it as a foreign account
+    let library = create_library(
+        masm("accounts/count_reader.masm"),
+        "external_contract::count_reader_contract",
+    )?;
+    let script = tx_script(&reader_script(counter.id())?, Some(&library))?;
+    assert!(run_script(&mut chain, reader.id(), script).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn copied_count_cannot_be_written_outside_the_contract() -> Result<()> {
+    let reader = count_reader_contract()?;
+    let mut chain = mock_chain(&[&reader], &[])?;
+
+    let script = tx_script(DIRECT_STORAGE_WRITE, None)?;
+    assert!(run_script(&mut chain, reader.id(), script).await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use anyhow::Result;
+use miden_lib::{
+    account::auth::{AuthRpoFalcon512Acl, AuthRpoFalcon512AclConfig},
+    transaction::TransactionKernel,
+};
+use miden_objects::{
+    account::{
+        Account, AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageSlot,
+    },
+    assembly::Library,
+    Word,
+};
+use rust_client::{helpers::procedure_root, storage::read_u64_slot};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note, note_script,
+    random_word, run_script, tx_script, wallet, DIRECT_STORAGE_WRITE,
+};
+
+fn counter_contract() -> Result<(Account, Library)> {
+    let code = masm("accounts/counter.masm");
+    let account = contract(
+        &code,
+        AccountType::RegularAccountImmutableCode,
+        vec![StorageSlot::Value(Word::default())],
+    )?;
+    let library = create_library(code, "external_contract::counter_contract")?;
+    Ok((account, library))
+}
+
+/// Builds a counter whose reset needs a signature of `owner_key`, while incrementing and
+/// decrementing need none.
+fn acl_counter(owner_key: Word) -> Result<(Account, Library)> {
+    let code = masm("accounts/counter.masm");
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![StorageSlot::Value(Word::default())],
+    )?
+    .with_supports_all_types();
+    let reset_root = procedure_root(component.library(), "reset_count")
+        .map_err(|err| anyhow::anyhow!("{err}"))?;
+    let auth = AuthRpoFalcon512Acl::new(
+        owner_key.into(),
+        AuthRpoFalcon512AclConfig::new().with_auth_trigger_procedures(vec![reset_root]),
+    )?;
+
+    let account = AccountBuilder::new(rand::random())
+        .account_type(AccountType::RegularAccountImmutableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(auth)
+        .with_component(component)
+        .build_existing()?;
+    let library = create_library(code, "external_contract::counter_contract")?;
+    Ok((account, library))
+}
+
+#[tokio::test]
+async fn counter_storage_cannot_be_written_outside_the_contract() -> Result<()> {
+    let (counter, _) = counter_contract()?;
+    let mut chain = mock_chain(&[&counter], &[])?;
+
+    let script = tx_script(DIRECT_STORAGE_WRITE, None)?;
+    assert!(run_script(&mut chain, counter.id(), script).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn increment_note_cannot_be_consumed_by_another_account() -> Result<()> {
+    let (counter, library) = counter_contract()?;
+    let other = wallet(vec![])?;
+
+    let script = note_script(&masm("notes/network_increment_note.masm"), Some(&library))?;
+    let increment_note = note(counter.id(), script, vec![], vec![])?;
+    let mut chain = mock_chain(&[&counter, &other], &[&increment_note])?;
+
+    assert!(consume(&mut chain, other.id(), &increment_note)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn increment_note_cannot_be_replayed() -> Result<()> {
+    let (counter, library) = counter_contract()?;
+
+    let script = note_script(&masm("notes/network_increment_note.masm"), Some(&library))?;
+    let increment_note = note(counter.id(), script, vec![], vec![])?;
+    let mut chain = mock_chain(&[&counter], &[&increment_note])?;
+
+    consume(&mut chain, counter.id(), &increment_note).await?;
+    assert!(consume(&mut chain, counter.id(), &increment_note)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn count_cannot_be_decremented_below_zero() -> Result<()> {
+    let (counter, library) = counter_contract()?;
+    let mut chain = mock_chain(&[&counter], &[])?;
+
+    let decrement = tx_script(
+        &masm("scripts/counter_decrement_script.masm"),
+        Some(&library),
+    )?;
+    assert_fails_with(
+        run_script(&mut chain, counter.id(), decrement).await,
+        "Count cannot go below zero",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reset_needs_the_signature_of_the_owner() -> Result<()> {
+    let (counter, library) = acl_counter(random_word())?;
+    let mut chain = mock_chain(&[&counter], &[])?;
+
+    // The mock chain holds no key for the counter, so only unprotected calls go through
+    let increment = tx_script(&masm("scripts/counter_script.masm"), Some(&library))?;
+    run_script(&mut chain, counter.id(), increment).await?;
+
+    let reset = tx_script(&masm("scripts/counter_reset_script.masm"), Some(&library))?;
+    assert!(run_script(&mut chain, counter.id(), reset).await.is_err());
+
+    // The count follows the public key, the configuration and the trigger procedures of the
+    // auth component
+    let count = read_u64_slot(chain.committed_account(counter.id())?, 3)?;
+    assert_eq!(count, 1);
+
+    Ok(())
+}
@@ -0,0 +1,332 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    note::{Note, NoteId, NoteTag},
+    testing::account_id::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1},
+    Felt, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::crowdfund::{self, Campaign};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note_script,
+    random_word, wallet,
+};
+
+/// Amount the campaign has to raise.
+const GOAL: u64 = 100;
+/// Block from which contributions are rejected and the campaign can be settled.
+const DEADLINE: u32 = 10;
+
+fn currency() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn tokens(amount: u64) -> Result<FungibleAsset> {
+    Ok(FungibleAsset::new(currency(), amount)?)
+}
+
+fn account_word(account_id: AccountId) -> Word {
+    [
+        ZERO,
+        ZERO,
+        account_id.suffix(),
+        account_id.prefix().as_felt(),
+    ]
+    .into()
+}
+
+/// Builds a campaign of `creator` which has not raised anything yet.
+fn campaign_contract(creator: &Account) -> Result<Account> {
+    contract(
+        &masm("accounts/crowdfund.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(account_word(creator.id())),
+            StorageSlot::Value(account_word(currency())),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::new(GOAL)].into()),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::from(DEADLINE)].into()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )
+}
+
+/// Builds a note of `sender` running the note script at `script_path` on the campaign.
+fn campaign_note(
+    sender: &Account,
+    campaign: &Account,
+    script_path: &str,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+) -> Result<Note> {
+    let library = create_library(masm("accounts/crowdfund.masm"), crowdfund::LIBRARY_PATH)?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    Ok(crowdfund::campaign_note(
+        sender.id(),
+        campaign.id(),
+        inputs,
+        assets,
+        script,
+        random_word(),
+    )?)
+}
+
+fn contribute_note(
+    contributor: &Account,
+    campaign: &Account,
+    asset: FungibleAsset,
+) -> Result<Note> {
+    campaign_note(
+        contributor,
+        campaign,
+        "notes/crowdfund_contribute_note.masm",
+        vec![],
+        vec![asset.into()],
+    )
+}
+
+fn withdraw_note(sender: &Account, campaign: &Account) -> Result<Note> {
+    let inputs = vec![NoteTag::from_account_id(sender.id()).into()];
+    campaign_note(
+        sender,
+        campaign,
+        "notes/crowdfund_withdraw_note.masm",
+        inputs,
+        vec![],
+    )
+}
+
+fn reclaim_note(sender: &Account, campaign: &Account) -> Result<Note> {
+    let inputs = vec![NoteTag::from_account_id(sender.id()).into()];
+    campaign_note(
+        sender,
+        campaign,
+        "notes/crowdfund_reclaim_note.masm",
+        inputs,
+        vec![],
+    )
+}
+
+fn read_campaign(chain: &MockChain, campaign: &Account) -> Result<Campaign> {
+    Campaign::from_account(&chain.committed_account(campaign.id())?)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+fn contribution_of(chain: &MockChain, campaign: &Account, contributor: &Account) -> Result<u64> {
+    crowdfund::contribution_of(&chain.committed_account(campaign.id())?, contributor.id())
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+fn balance(chain: &MockChain, campaign: &Account) -> Result<u64> {
+    Ok(chain
+        .committed_account(campaign.id())?
+        .vault()
+        .get_balance(currency())?)
+}
+
+/// Consumes `note` with the campaign and returns the ids of the notes it created.
+async fn consume_by_campaign(
+    chain: &mut MockChain,
+    campaign: &Account,
+    note: &Note,
+) -> Result<Vec<NoteId>> {
+    let executed = chain
+        .build_tx_context(campaign.id(), &[note.id()], &[])?
+        .build()?
+        .execute()
+        .await?;
+    let created = executed
+        .output_notes()
+        .iter()
+        .map(|note| note.id())
+        .collect();
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(created)
+}
+
+#[tokio::test]
+async fn creator_withdraws_the_funds_when_the_goal_is_met() -> Result<()> {
+    let creator = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let campaign = campaign_contract(&creator)?;
+
+    let alice_funds = contribute_note(&alice, &campaign, tokens(60)?)?;
+    let bob_funds = contribute_note(&bob, &campaign, tokens(50)?)?;
+    let alice_reclaim = reclaim_note(&alice, &campaign)?;
+    let withdraw = withdraw_note(&creator, &campaign)?;
+    let replay = withdraw_note(&creator, &campaign)?;
+    let mut chain = mock_chain(
+        &[&campaign],
+        &[&alice_funds, &bob_funds, &alice_reclaim, &withdraw, &replay],
+    )?;
+
+    consume(&mut chain, campaign.id(), &alice_funds).await?;
+    consume(&mut chain, campaign.id(), &bob_funds).await?;
+    assert_eq!(contribution_of(&chain, &campaign, &alice)?, 60);
+    assert_eq!(contribution_of(&chain, &campaign, &bob)?, 50);
+    assert_eq!(read_campaign(&chain, &campaign)?.raised, 110);
+
+    // Once the deadline is reached the goal is met, so only the creator gets paid
+    chain.prove_until_block(DEADLINE)?;
+    assert_fails_with(
+        consume(&mut chain, campaign.id(), &alice_reclaim).await,
+        "Contributions can only be reclaimed if the goal was missed",
+    );
+
+    let expected = read_campaign(&chain, &campaign)?
+        .withdrawal(withdraw.serial_num())
+        .map_err(|err| anyhow::anyhow!("{err}"))?;
+    assert_eq!(
+        consume_by_campaign(&mut chain, &campaign, &withdraw).await?,
+        vec![expected.id()]
+    );
+    assert!(read_campaign(&chain, &campaign)?.withdrawn);
+    assert_eq!(balance(&chain, &campaign)?, 0);
+
+    assert_fails_with(
+        consume(&mut chain, campaign.id(), &replay).await,
+        "Funds were already withdrawn",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn contributors_reclaim_their_contributions_when_the_goal_is_missed() -> Result<()> {
+    let creator = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let campaign = campaign_contract(&creator)?;
+
+    let alice_funds = contribute_note(&alice, &campaign, tokens(30)?)?;
+    let alice_more_funds = contribute_note(&alice, &campaign, tokens(10)?)?;
+    let bob_funds = contribute_note(&bob, &campaign, tokens(20)?)?;
+    let withdraw = withdraw_note(&creator, &campaign)?;
+    let alice_reclaim = reclaim_note(&alice, &campaign)?;
+    let alice_replay = reclaim_note(&alice, &campaign)?;
+    let bob_reclaim = reclaim_note(&bob, &campaign)?;
+    let mut chain = mock_chain(
+        &[&campaign],
+        &[
+            &alice_funds,
+            &alice_more_funds,
+            &bob_funds,
+            &withdraw,
+            &alice_reclaim,
+            &alice_replay,
+            &bob_reclaim,
+        ],
+    )?;
+
+    for note in [&alice_funds, &alice_more_funds, &bob_funds] {
+        consume(&mut chain, campaign.id(), note).await?;
+    }
+    assert_eq!(contribution_of(&chain, &campaign, &alice)?, 40);
+    assert_eq!(contribution_of(&chain, &campaign, &bob)?, 20);
+
+    // Once the deadline is reached the goal is missed, so every contributor gets its tokens back
+    chain.prove_until_block(DEADLINE)?;
+    assert_fails_with(
+        consume(&mut chain, campaign.id(), &withdraw).await,
+        "Funds can only be withdrawn if the goal was met",
+    );
+
+    let state = read_campaign(&chain, &campaign)?;
+    for (contributor, reclaim, amount) in [(&alice, &alice_reclaim, 40), (&bob, &bob_reclaim, 20)] {
+        let expected = state
+            .refund(contributor.id(), amount, reclaim.serial_num())
+            .map_err(|err| anyhow::anyhow!("{err}"))?;
+        assert_eq!(
+            consume_by_campaign(&mut chain, &campaign, reclaim).await?,
+            vec![expected.id()]
+        );
+        assert_eq!(contribution_of(&chain, &campaign, contributor)?, 0);
+    }
+    assert_eq!(balance(&chain, &campaign)?, 0);
+
+    assert_fails_with(
+        consume(&mut chain, campaign.id(), &alice_replay).await,
+        "Sender has no contribution to reclaim",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn contributions_are_rejected_from_the_deadline_on() -> Result<()> {
+    let creator = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let campaign = campaign_contract(&creator)?;
+    let funds = contribute_note(&alice, &campaign, tokens(60)?)?;
+    let mut chain = mock_chain(&[&campaign], &[&funds])?;
+
+    chain.prove_until_block(DEADLINE)?;
+    assert_fails_with(
+        consume(&mut chain, campaign.id(), &funds).await,
+        "Campaign no longer accepts contributions",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn contribution_in_another_currency_is_rejected() -> Result<()> {
+    let creator = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let campaign = campaign_contract(&creator)?;
+    let other = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1)?;
+    let funds = contribute_note(&alice, &campaign, FungibleAsset::new(other, 60)?)?;
+    let mut chain = mock_chain(&[&campaign], &[&funds])?;
+
+    assert_fails_with(
+        consume(&mut chain, campaign.id(), &funds).await,
+        "Contribution is not paid in the currency of the campaign",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn funds_cannot_be_withdrawn_before_the_deadline() -> Result<()> {
+    let creator = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let campaign = campaign_contract(&creator)?;
+    let funds = contribute_note(&alice, &campaign, tokens(GOAL)?)?;
+    let withdraw = withdraw_note(&creator, &campaign)?;
+    let mut chain = mock_chain(&[&campaign], &[&funds, &withdraw])?;
+
+    consume(&mut chain, campaign.id(), &funds).await?;
+    assert_fails_with(
+        consume(&mut chain, campaign.id(), &withdraw).await,
+        "Campaign is still running",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_the_creator_can_withdraw() -> Result<()> {
+    let creator = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let campaign = campaign_contract(&creator)?;
+    let funds = contribute_note(&alice, &campaign, tokens(GOAL)?)?;
+    let withdraw = withdraw_note(&alice, &campaign)?;
+    let mut chain = mock_chain(&[&campaign], &[&funds, &withdraw])?;
+
+    consume(&mut chain, campaign.id(), &funds).await?;
+    chain.prove_until_block(DEADLINE)?;
+    assert_fails_with(
+        consume(&mut chain, campaign.id(), &withdraw).await,
+        "Only the creator can withdraw the funds",
+    );
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountType, StorageSlot},
+    assembly::Library,
+    Word,
+};
+
+use crate::common::{
+    assert_fails_with, contract, create_library, masm, mock_chain, run_script, tx_script,
+    DIRECT_STORAGE_WRITE,
+};
+
+fn debug_counter_contract() -> Result<(Account, Library)> {
+    let code = masm("accounts/debug_counter.masm");
+    let account = contract(
+        &code,
+        AccountType::RegularAccountImmutableCode,
+        vec![StorageSlot::Value(Word::default())],
+    )?;
+    let library = create_library(code, "external_contract::debug_counter")?;
+    Ok((account, library))
+}
+
+/// Returns the source of a script adding `a^2 + b^2` to the counter.
+fn add_script(a: u64, b: u64) -> String {
+    format!(
+        "use.external_contract::debug_counter
+
+begin
+    push.{b} push.{a}
+    call.debug_counter::add_sum_of_squares
+end
+"
+    )
+}
+
+#[tokio::test]
+async fn counter_storage_cannot_be_written_outside_the_contract() -> Result<()> {
+    let (counter, _) = debug_counter_contract()?;
+    let mut chain = mock_chain(&[&counter], &[])?;
+
+    let script = tx_script(DIRECT_STORAGE_WRITE, None)?;
+    assert!(run_script(&mut chain, counter.id(), script).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn inputs_must_be_u32_values() -> Result<()> {
+    let (counter, library) = debug_counter_contract()?;
+    let mut chain = mock_chain(&[&counter], &[])?;
+
+    assert_fails_with(
+        run_script(
+            &mut chain,
+            counter.id(),
+            tx_script(&add_script(1 << 32, 1), Some(&library))?,
+        )
+        .await,
+        "Inputs must be u32 values",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn square_must_fit_in_a_u32() -> Result<()> {
+    let (counter, library) = debug_counter_contract()?;
+    let mut chain = mock_chain(&[&counter], &[])?;
+
+    assert_fails_with(
+        run_script(
+            &mut chain,
+            counter.id(),
+            tx_script(&add_script(70_000, 1), Some(&library))?,
+        )
+        .await,
+        "Sum of squares does not fit in a u32",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sum_must_fit_in_a_u32() -> Result<()> {
+    let (counter, library) = debug_counter_contract()?;
+    let mut chain = mock_chain(&[&counter], &[])?;
+
+    // 50000^2 fits in a u32 on its own, twice that does not
+    assert_fails_with(
+        run_script(
+            &mut chain,
+            counter.id(),
+            tx_script(&add_script(50_000, 50_000), Some(&library))?,
+        )
+        .await,
+        "Sum of squares does not fit in a u32",
+    );
+
+    Ok(())
+}
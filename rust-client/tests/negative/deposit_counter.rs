@@ -0,0 +1,117 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    note::{Note, NoteTag},
+    testing::account_id::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1},
+    Felt, Word, ZERO,
+};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note, note_script,
+    sender,
+};
+
+const MIN_DEPOSIT: u64 = 5;
+
+fn deposit_faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn deposit_counter_contract() -> Result<Account> {
+    let faucet = deposit_faucet();
+    let deposit_config: Word = [
+        Felt::new(MIN_DEPOSIT),
+        ZERO,
+        faucet.suffix(),
+        faucet.prefix().as_felt(),
+    ]
+    .into();
+
+    contract(
+        &masm("accounts/deposit_counter.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(deposit_config),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )
+}
+
+/// Builds an interaction note asking the contract to increment by `increment_by`.
+fn interaction_note(contract: &Account, increment_by: u64, assets: Vec<Asset>) -> Result<Note> {
+    let library = create_library(
+        masm("accounts/deposit_counter.masm"),
+        "external_contract::deposit_counter",
+    )?;
+    let script = note_script(&masm("notes/deposit_increment_note.masm"), Some(&library))?;
+    let inputs = vec![
+        Felt::new(increment_by),
+        NoteTag::from_account_id(sender()).into(),
+    ];
+
+    note(contract.id(), script, inputs, assets)
+}
+
+#[tokio::test]
+async fn deposit_in_another_asset_is_rejected() -> Result<()> {
+    let counter = deposit_counter_contract()?;
+    let other_faucet = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1)?;
+    let deposit = FungibleAsset::new(other_faucet, MIN_DEPOSIT)?;
+    let interaction = interaction_note(&counter, 1, vec![deposit.into()])?;
+    let mut chain = mock_chain(&[&counter], &[&interaction])?;
+
+    assert_fails_with(
+        consume(&mut chain, counter.id(), &interaction).await,
+        "Deposit must be paid with the configured faucet",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn deposit_below_minimum_is_rejected() -> Result<()> {
+    let counter = deposit_counter_contract()?;
+    let deposit = FungibleAsset::new(deposit_faucet(), MIN_DEPOSIT - 1)?;
+    let interaction = interaction_note(&counter, 1, vec![deposit.into()])?;
+    let mut chain = mock_chain(&[&counter], &[&interaction])?;
+
+    assert_fails_with(
+        consume(&mut chain, counter.id(), &interaction).await,
+        "Deposit is below the required minimum",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn interaction_without_deposit_is_rejected() -> Result<()> {
+    let counter = deposit_counter_contract()?;
+    let interaction = interaction_note(&counter, 1, vec![])?;
+    let mut chain = mock_chain(&[&counter], &[&interaction])?;
+
+    assert_fails_with(
+        consume(&mut chain, counter.id(), &interaction).await,
+        "Interaction note must carry exactly one deposit asset",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn interaction_note_cannot_be_replayed() -> Result<()> {
+    let counter = deposit_counter_contract()?;
+    let deposit = FungibleAsset::new(deposit_faucet(), MIN_DEPOSIT)?;
+    let interaction = interaction_note(&counter, 1, vec![deposit.into()])?;
+    let mut chain = mock_chain(&[&counter], &[&interaction])?;
+
+    consume(&mut chain, counter.id(), &interaction).await?;
+    assert!(consume(&mut chain, counter.id(), &interaction)
+        .await
+        .is_err());
+
+    Ok(())
+}
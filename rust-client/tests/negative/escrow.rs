@@ -0,0 +1,84 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountType, StorageSlot},
+    assembly::Library,
+    Felt, Word,
+};
+use rust_client::deployer::Deployer;
+
+use crate::common::{
+    assert_fails_with, contract, create_library, masm, mock_chain, run_script, sender, tx_script,
+};
+
+fn escrow_contract() -> Result<(Account, Library)> {
+    let code = masm("accounts/escrow.masm");
+    let account = contract(
+        &code,
+        AccountType::RegularAccountImmutableCode,
+        vec![StorageSlot::Value(Word::default()); 4],
+    )?;
+    let library = create_library(code, "external_contract::escrow")?;
+    Ok((account, library))
+}
+
+/// Returns the source of the deployment script calling the constructor with `fee_rate` and
+/// `deadline`, owned by the test sender.
+fn constructor_script(fee_rate: u64, deadline: u32) -> String {
+    Deployer::new(masm("accounts/escrow.masm"), "external_contract::escrow")
+        .with_arg(sender().prefix().as_felt())
+        .with_arg(sender().suffix())
+        .with_arg(Felt::new(fee_rate))
+        .with_arg(Felt::from(deadline))
+        .script_source()
+}
+
+#[tokio::test]
+async fn constructor_rejects_fee_rate_above_100_percent() -> Result<()> {
+    let (escrow, library) = escrow_contract()?;
+    let mut chain = mock_chain(&[&escrow], &[])?;
+
+    assert_fails_with(
+        run_script(
+            &mut chain,
+            escrow.id(),
+            tx_script(&constructor_script(10_001, 1_000), Some(&library))?,
+        )
+        .await,
+        "Fee rate cannot exceed 10000 basis points",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn constructor_rejects_past_deadline() -> Result<()> {
+    let (escrow, library) = escrow_contract()?;
+    let mut chain = mock_chain(&[&escrow], &[])?;
+
+    assert_fails_with(
+        run_script(
+            &mut chain,
+            escrow.id(),
+            tx_script(&constructor_script(250, 0), Some(&library))?,
+        )
+        .await,
+        "Escrow deadline has passed",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn constructor_cannot_run_twice() -> Result<()> {
+    let (escrow, library) = escrow_contract()?;
+    let mut chain = mock_chain(&[&escrow], &[])?;
+    let script = constructor_script(250, 1_000);
+
+    run_script(&mut chain, escrow.id(), tx_script(&script, Some(&library))?).await?;
+    assert_fails_with(
+        run_script(&mut chain, escrow.id(), tx_script(&script, Some(&library))?).await,
+        "Escrow is already initialized",
+    );
+
+    Ok(())
+}
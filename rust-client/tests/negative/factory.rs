@@ -0,0 +1,80 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    assembly::Library,
+    Word,
+};
+use miden_testing::MockChain;
+use rust_client::factory::Factory;
+
+use crate::common::{
+    assert_fails_with, contract, create_library, masm, mock_chain, run_script, sender, tx_script,
+    wallet, DIRECT_STORAGE_WRITE,
+};
+
+fn factory_contract() -> Result<(Account, Library)> {
+    let code = masm("accounts/factory.masm");
+    let account = contract(
+        &code,
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?;
+    let library = create_library(code, "external_contract::factory")?;
+    Ok((account, library))
+}
+
+async fn register(
+    chain: &mut MockChain,
+    factory: &Account,
+    library: &Library,
+    owner: AccountId,
+    instance: AccountId,
+) -> Result<()> {
+    let script = Factory::registration_script(owner, instance);
+    run_script(chain, factory.id(), tx_script(&script, Some(library))?).await
+}
+
+#[tokio::test]
+async fn owner_cannot_register_a_second_instance() -> Result<()> {
+    let (factory, library) = factory_contract()?;
+    let first = wallet(vec![])?;
+    let second = wallet(vec![])?;
+    let mut chain = mock_chain(&[&factory], &[])?;
+
+    register(&mut chain, &factory, &library, sender(), first.id()).await?;
+    assert_fails_with(
+        register(&mut chain, &factory, &library, sender(), second.id()).await,
+        "Owner already has an instance",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn registration_cannot_be_replayed() -> Result<()> {
+    let (factory, library) = factory_contract()?;
+    let instance = wallet(vec![])?;
+    let mut chain = mock_chain(&[&factory], &[])?;
+
+    register(&mut chain, &factory, &library, sender(), instance.id()).await?;
+    assert_fails_with(
+        register(&mut chain, &factory, &library, sender(), instance.id()).await,
+        "Owner already has an instance",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn registry_cannot_be_written_outside_the_contract() -> Result<()> {
+    let (factory, _) = factory_contract()?;
+    let mut chain = mock_chain(&[&factory], &[])?;
+
+    let script = tx_script(DIRECT_STORAGE_WRITE, None)?;
+    assert!(run_script(&mut chain, factory.id(), script).await.is_err());
+
+    Ok(())
+}
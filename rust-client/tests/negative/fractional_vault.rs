@@ -0,0 +1,100 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageSlot},
+    assembly::Library,
+    asset::{Asset, FungibleAsset, NonFungibleAsset, NonFungibleAssetDetails},
+    note::Note,
+    testing::account_id::ACCOUNT_ID_PUBLIC_NON_FUNGIBLE_FAUCET,
+    Felt, Word,
+};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note, note_script,
+    output_note_inputs,
+};
+
+const TOTAL_SHARES: u64 = 100;
+
+fn vault_contract() -> Result<(Account, Library)> {
+    let code = masm("accounts/fractional_vault.masm");
+    let account = contract(
+        &code,
+        AccountType::FungibleFaucet,
+        vec![
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?;
+    let library = create_library(code, "external_contract::fractional_vault")?;
+    Ok((account, library))
+}
+
+fn nft(data: &[u8]) -> Result<Asset> {
+    let collection = AccountId::try_from(ACCOUNT_ID_PUBLIC_NON_FUNGIBLE_FAUCET)?;
+    let details = NonFungibleAssetDetails::new(collection.prefix(), data.to_vec())?;
+    Ok(NonFungibleAsset::new(&details)?.into())
+}
+
+fn fractionalize_note(vault: &Account, library: &Library, nft: Asset) -> Result<Note> {
+    let script = note_script(&masm("notes/fractionalize_note.masm"), Some(library))?;
+    let mut inputs = output_note_inputs();
+    inputs.insert(4, Felt::new(TOTAL_SHARES));
+
+    note(vault.id(), script, inputs, vec![nft])
+}
+
+fn redeem_note(vault: &Account, library: &Library, shares: u64) -> Result<Note> {
+    let script = note_script(&masm("notes/redeem_shares_note.masm"), Some(library))?;
+    let shares = FungibleAsset::new(vault.id(), shares)?;
+
+    note(
+        vault.id(),
+        script,
+        output_note_inputs(),
+        vec![shares.into()],
+    )
+}
+
+#[tokio::test]
+async fn partial_redemption_is_rejected() -> Result<()> {
+    let (vault, library) = vault_contract()?;
+    let lock = fractionalize_note(&vault, &library, nft(b"negative #1")?)?;
+    let partial_redeem = redeem_note(&vault, &library, 70)?;
+    let mut chain = mock_chain(&[&vault], &[&lock, &partial_redeem])?;
+
+    consume(&mut chain, vault.id(), &lock).await?;
+    assert_fails_with(
+        consume(&mut chain, vault.id(), &partial_redeem).await,
+        "Redemption requires 100% of the outstanding shares",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn second_nft_cannot_be_locked() -> Result<()> {
+    let (vault, library) = vault_contract()?;
+    let first = fractionalize_note(&vault, &library, nft(b"negative #1")?)?;
+    let second = fractionalize_note(&vault, &library, nft(b"negative #2")?)?;
+    let mut chain = mock_chain(&[&vault], &[&first, &second])?;
+
+    consume(&mut chain, vault.id(), &first).await?;
+    assert_fails_with(
+        consume(&mut chain, vault.id(), &second).await,
+        "Vault already holds an NFT",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fractionalize_note_cannot_be_replayed() -> Result<()> {
+    let (vault, library) = vault_contract()?;
+    let lock = fractionalize_note(&vault, &library, nft(b"negative #1")?)?;
+    let mut chain = mock_chain(&[&vault], &[&lock])?;
+
+    consume(&mut chain, vault.id(), &lock).await?;
+    assert!(consume(&mut chain, vault.id(), &lock).await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountId},
+    asset::FungibleAsset,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteTag,
+        NoteType,
+    },
+    testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+    Felt, Hasher, Word,
+};
+use miden_testing::MockChain;
+
+use crate::common::{
+    assert_fails_with, consume, consume_with_args, masm, mock_chain, note_script, random_word,
+    wallet,
+};
+
+/// Tokens locked in every note.
+const AMOUNT: u64 = 30;
+/// Block from which the sender can take the note back.
+const REFUND_AFTER: u32 = 5;
+
+fn faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn preimage() -> Word {
+    [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)].into()
+}
+
+/// Builds a note from `sender` which `target` claims with [`preimage`].
+fn htlc_note(sender: &Account, target: &Account) -> Result<Note> {
+    let mut inputs = Hasher::hash_elements(preimage().as_elements()).to_vec();
+    inputs.extend([
+        target.id().prefix().as_felt(),
+        target.id().suffix(),
+        Felt::from(REFUND_AFTER),
+    ]);
+    let script = note_script(&masm("notes/htlc_note.masm"), None)?;
+    let recipient = NoteRecipient::new(random_word(), script, NoteInputs::new(inputs)?);
+    let metadata = NoteMetadata::new(
+        sender.id(),
+        NoteType::Public,
+        NoteTag::from_account_id(target.id()),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    let assets = NoteAssets::new(vec![FungibleAsset::new(faucet(), AMOUNT)?.into()])?;
+
+    Ok(Note::new(assets, metadata, recipient))
+}
+
+fn balance(chain: &MockChain, account: &Account) -> Result<u64> {
+    Ok(chain
+        .committed_account(account.id())?
+        .vault()
+        .get_balance(faucet())?)
+}
+
+#[tokio::test]
+async fn target_claims_with_the_preimage() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let htlc = htlc_note(&alice, &bob)?;
+    let chain = mock_chain(&[&alice, &bob], &[&htlc])?;
+
+    consume_with_args(&chain, bob.id(), &htlc, preimage()).await
+}
+
+#[tokio::test]
+async fn wrong_preimage_is_rejected() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let htlc = htlc_note(&alice, &bob)?;
+    let chain = mock_chain(&[&alice, &bob], &[&htlc])?;
+
+    assert_fails_with(
+        consume_with_args(&chain, bob.id(), &htlc, random_word()).await,
+        "Note args are not the preimage of the hashlock",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn third_party_cannot_claim_with_the_preimage() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let carol = wallet(vec![])?;
+    let htlc = htlc_note(&alice, &bob)?;
+    let chain = mock_chain(&[&alice, &bob, &carol], &[&htlc])?;
+
+    // Once the preimage is public anybody knows it, so the note has to check the consumer too
+    assert_fails_with(
+        consume_with_args(&chain, carol.id(), &htlc, preimage()).await,
+        "Only the target of the note can claim it",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sender_cannot_take_the_note_back_early() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let htlc = htlc_note(&alice, &bob)?;
+    let mut chain = mock_chain(&[&alice, &bob], &[&htlc])?;
+
+    assert_fails_with(
+        consume(&mut chain, alice.id(), &htlc).await,
+        "Sender cannot take the note back before the refund block",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sender_takes_the_note_back_after_the_refund_block() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let htlc = htlc_note(&alice, &bob)?;
+    let mut chain = mock_chain(&[&alice, &bob], &[&htlc])?;
+
+    chain.prove_until_block(REFUND_AFTER)?;
+    consume(&mut chain, alice.id(), &htlc).await?;
+
+    assert_eq!(balance(&chain, &alice)?, AMOUNT);
+
+    Ok(())
+}
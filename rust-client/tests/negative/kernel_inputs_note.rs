@@ -0,0 +1,85 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountId},
+    note::Note,
+    Felt,
+};
+
+use crate::common::{
+    assert_fails_with, consume_with_args, masm, mock_chain, note, note_script, random_word, sender,
+    wallet,
+};
+
+/// Builds a kernel inputs note for `target` expecting `expected_sender` and `min_nonce`.
+fn kernel_inputs_note(
+    target: &Account,
+    not_before: u32,
+    expected_sender: AccountId,
+    min_nonce: u64,
+) -> Result<Note> {
+    let script = note_script(&masm("notes/kernel_inputs_note.masm"), None)?;
+    let inputs = vec![
+        Felt::from(not_before),
+        expected_sender.prefix().as_felt(),
+        expected_sender.suffix(),
+        Felt::new(min_nonce),
+    ];
+
+    note(target.id(), script, inputs, vec![])
+}
+
+#[tokio::test]
+async fn note_rejects_wrong_serial_number() -> Result<()> {
+    let target = wallet(vec![])?;
+    let note = kernel_inputs_note(&target, 0, sender(), 0)?;
+    let chain = mock_chain(&[&target], &[&note])?;
+
+    assert_fails_with(
+        consume_with_args(&chain, target.id(), &note, random_word()).await,
+        "Note args do not match the note serial number",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn note_is_rejected_before_its_block() -> Result<()> {
+    let target = wallet(vec![])?;
+    let note = kernel_inputs_note(&target, 1_000, sender(), 0)?;
+    let chain = mock_chain(&[&target], &[&note])?;
+
+    assert_fails_with(
+        consume_with_args(&chain, target.id(), &note, note.serial_num()).await,
+        "Note cannot be consumed before its block",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn note_rejects_other_sender() -> Result<()> {
+    let target = wallet(vec![])?;
+    let note = kernel_inputs_note(&target, 0, target.id(), 0)?;
+    let chain = mock_chain(&[&target], &[&note])?;
+
+    assert_fails_with(
+        consume_with_args(&chain, target.id(), &note, note.serial_num()).await,
+        "Note was not created by the expected sender",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn note_rejects_account_with_low_nonce() -> Result<()> {
+    let target = wallet(vec![])?;
+    let note = kernel_inputs_note(&target, 0, sender(), target.nonce().as_int() + 1)?;
+    let chain = mock_chain(&[&target], &[&note])?;
+
+    assert_fails_with(
+        consume_with_args(&chain, target.id(), &note, note.serial_num()).await,
+        "Consuming account has not executed enough transactions",
+    );
+
+    Ok(())
+}
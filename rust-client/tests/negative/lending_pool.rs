@@ -0,0 +1,160 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    note::{Note, NoteTag},
+    testing::account_id::{
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1,
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2,
+    },
+    Felt, Word, ZERO,
+};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note, note_script,
+    random_word, sender,
+};
+
+fn collateral_faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn debt_faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1).unwrap()
+}
+
+fn account_word(account_id: AccountId) -> Word {
+    [
+        ZERO,
+        ZERO,
+        account_id.suffix(),
+        account_id.prefix().as_felt(),
+    ]
+    .into()
+}
+
+/// Builds a pool with a max LTV of 50% and a liquidation threshold of 80%. None of the tests
+/// reaches the price check, so the pool points to a random price feed.
+fn lending_pool() -> Result<Account> {
+    contract(
+        &masm("accounts/lending_pool.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(account_word(collateral_faucet())),
+            StorageSlot::Value(account_word(debt_faucet())),
+            StorageSlot::Value(random_word()),
+            StorageSlot::Value(random_word()),
+            StorageSlot::Value([ZERO, ZERO, Felt::new(80), Felt::new(50)].into()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::new(1)].into()),
+        ],
+    )
+}
+
+/// Builds a note of the test sender running the note script at `script_path` on the pool.
+fn pool_note(
+    pool: &Account,
+    script_path: &str,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+) -> Result<Note> {
+    let library = create_library(
+        masm("accounts/lending_pool.masm"),
+        "external_contract::lending_pool",
+    )?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    note(pool.id(), script, inputs, assets)
+}
+
+fn deposit_note(pool: &Account, assets: Vec<Asset>) -> Result<Note> {
+    pool_note(pool, "notes/pool_deposit_note.masm", vec![], assets)
+}
+
+/// Builds a note liquidating the position of the test sender.
+fn liquidation_note(pool: &Account, assets: Vec<Asset>) -> Result<Note> {
+    let inputs = vec![
+        sender().prefix().as_felt(),
+        sender().suffix(),
+        NoteTag::from_account_id(sender()).into(),
+    ];
+    pool_note(pool, "notes/pool_liquidate_note.masm", inputs, assets)
+}
+
+#[tokio::test]
+async fn deposit_of_an_unknown_asset_is_rejected() -> Result<()> {
+    let pool = lending_pool()?;
+    let unknown_faucet = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_2)?;
+    let deposit = deposit_note(&pool, vec![FungibleAsset::new(unknown_faucet, 10)?.into()])?;
+    let mut chain = mock_chain(&[&pool], &[&deposit])?;
+
+    assert_fails_with(
+        consume(&mut chain, pool.id(), &deposit).await,
+        "Pool only accepts the collateral and the borrowed asset",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn deposit_without_asset_is_rejected() -> Result<()> {
+    let pool = lending_pool()?;
+    let deposit = deposit_note(&pool, vec![])?;
+    let mut chain = mock_chain(&[&pool], &[&deposit])?;
+
+    assert_fails_with(
+        consume(&mut chain, pool.id(), &deposit).await,
+        "Deposit note must carry exactly one asset",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn liquidation_must_repay_the_borrowed_asset() -> Result<()> {
+    let pool = lending_pool()?;
+    let repayment = FungibleAsset::new(collateral_faucet(), 10)?;
+    let liquidation = liquidation_note(&pool, vec![repayment.into()])?;
+    let mut chain = mock_chain(&[&pool], &[&liquidation])?;
+
+    assert_fails_with(
+        consume(&mut chain, pool.id(), &liquidation).await,
+        "Liquidation must repay the borrowed asset",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn position_without_debt_cannot_be_liquidated() -> Result<()> {
+    let pool = lending_pool()?;
+    let collateral = FungibleAsset::new(collateral_faucet(), 10)?;
+    let deposit = deposit_note(&pool, vec![collateral.into()])?;
+    let repayment = FungibleAsset::new(debt_faucet(), 10)?;
+    let liquidation = liquidation_note(&pool, vec![repayment.into()])?;
+    let mut chain = mock_chain(&[&pool], &[&deposit, &liquidation])?;
+
+    consume(&mut chain, pool.id(), &deposit).await?;
+    assert_fails_with(
+        consume(&mut chain, pool.id(), &liquidation).await,
+        "Position has no debt to liquidate",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn deposit_note_cannot_be_replayed() -> Result<()> {
+    let pool = lending_pool()?;
+    let collateral = FungibleAsset::new(collateral_faucet(), 10)?;
+    let deposit = deposit_note(&pool, vec![collateral.into()])?;
+    let mut chain = mock_chain(&[&pool], &[&deposit])?;
+
+    consume(&mut chain, pool.id(), &deposit).await?;
+    assert!(consume(&mut chain, pool.id(), &deposit).await.is_err());
+
+    Ok(())
+}
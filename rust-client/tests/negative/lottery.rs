@@ -0,0 +1,294 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    note::{Note, NoteId, NoteTag},
+    testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+    Felt, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::lottery::{self, Lottery, Secret};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note_script,
+    random_word, wallet,
+};
+
+/// Price of a ticket.
+const PRICE: u64 = 10;
+/// Block from which tickets are rejected and reveals accepted.
+const SALES_END: u32 = 5;
+/// Block from which reveals are rejected and the winner can be drawn.
+const REVEAL_END: u32 = 10;
+
+fn currency() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn tokens(amount: u64) -> Result<FungibleAsset> {
+    Ok(FungibleAsset::new(currency(), amount)?)
+}
+
+fn value_word(value: u64) -> Word {
+    [ZERO, ZERO, ZERO, Felt::new(value)].into()
+}
+
+/// Builds a lottery which has not sold any ticket yet.
+fn lottery_contract() -> Result<Account> {
+    contract(
+        &masm("accounts/lottery.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(
+                [
+                    ZERO,
+                    ZERO,
+                    currency().suffix(),
+                    currency().prefix().as_felt(),
+                ]
+                .into(),
+            ),
+            StorageSlot::Value(value_word(PRICE)),
+            StorageSlot::Value(value_word(SALES_END.into())),
+            StorageSlot::Value(value_word(REVEAL_END.into())),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )
+}
+
+/// Builds a note of `sender` running the note script at `script_path` on the lottery.
+fn lottery_note(
+    sender: &Account,
+    lottery: &Account,
+    script_path: &str,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+) -> Result<Note> {
+    let library = create_library(masm("accounts/lottery.masm"), lottery::LIBRARY_PATH)?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    Ok(lottery::lottery_note(
+        sender.id(),
+        lottery.id(),
+        inputs,
+        assets,
+        script,
+        random_word(),
+    )?)
+}
+
+fn ticket_note(
+    player: &Account,
+    lottery: &Account,
+    secret: &Secret,
+    paid: FungibleAsset,
+) -> Result<Note> {
+    lottery_note(
+        player,
+        lottery,
+        "notes/lottery_ticket_note.masm",
+        secret.commitment().as_elements().to_vec(),
+        vec![paid.into()],
+    )
+}
+
+fn reveal_note(player: &Account, lottery: &Account, secret: &Secret) -> Result<Note> {
+    lottery_note(
+        player,
+        lottery,
+        "notes/lottery_reveal_note.masm",
+        secret.0.as_elements().to_vec(),
+        vec![],
+    )
+}
+
+fn draw_note(sender: &Account, lottery: &Account, winner: AccountId) -> Result<Note> {
+    let inputs = vec![NoteTag::from_account_id(winner).into()];
+    lottery_note(
+        sender,
+        lottery,
+        "notes/lottery_draw_note.masm",
+        inputs,
+        vec![],
+    )
+}
+
+fn read_lottery(chain: &MockChain, lottery: &Account) -> Result<Lottery> {
+    Lottery::from_account(&chain.committed_account(lottery.id())?)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+fn balance(chain: &MockChain, lottery: &Account) -> Result<u64> {
+    Ok(chain
+        .committed_account(lottery.id())?
+        .vault()
+        .get_balance(currency())?)
+}
+
+/// Consumes the unauthenticated `note` with the lottery and returns the ids of the notes it
+/// created. The draw note is only built once the winner is known, so it is not on the chain.
+async fn draw(chain: &mut MockChain, lottery: &Account, note: &Note) -> Result<Vec<NoteId>> {
+    let executed = chain
+        .build_tx_context(lottery.id(), &[], &[note.clone()])?
+        .build()?
+        .execute()
+        .await?;
+    let created = executed
+        .output_notes()
+        .iter()
+        .map(|note| note.id())
+        .collect();
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(created)
+}
+
+#[tokio::test]
+async fn a_player_which_revealed_wins_every_ticket() -> Result<()> {
+    let players = [wallet(vec![])?, wallet(vec![])?, wallet(vec![])?];
+    let secrets = [
+        Secret(random_word()),
+        Secret(random_word()),
+        Secret(random_word()),
+    ];
+    let lottery = lottery_contract()?;
+
+    let mut tickets = Vec::new();
+    let mut reveals = Vec::new();
+    for (player, secret) in players.iter().zip(&secrets) {
+        tickets.push(ticket_note(player, &lottery, secret, tokens(PRICE)?)?);
+        reveals.push(reveal_note(player, &lottery, secret)?);
+    }
+    let notes: Vec<&Note> = tickets.iter().chain(&reveals).collect();
+    let mut chain = mock_chain(&[&lottery], &notes)?;
+
+    for ticket in &tickets {
+        consume(&mut chain, lottery.id(), ticket).await?;
+    }
+    assert_eq!(read_lottery(&chain, &lottery)?.num_tickets, 3);
+    assert_eq!(balance(&chain, &lottery)?, 3 * PRICE);
+
+    // The last player never reveals, so it cannot win
+    chain.prove_until_block(SALES_END)?;
+    for reveal in &reveals[..2] {
+        consume(&mut chain, lottery.id(), reveal).await?;
+    }
+    let state = read_lottery(&chain, &lottery)?;
+    assert_eq!(state.revealed, vec![players[0].id(), players[1].id()]);
+    assert_ne!(state.seed, Word::default());
+
+    chain.prove_until_block(REVEAL_END)?;
+    let state = read_lottery(&chain, &lottery)?;
+    let winner = state.winner().expect("two players revealed");
+    assert_ne!(winner, players[2].id());
+
+    let request = draw_note(&players[2], &lottery, winner)?;
+    let expected = state
+        .payout(request.serial_num())
+        .map_err(|err| anyhow::anyhow!("{err}"))?;
+    assert_eq!(
+        draw(&mut chain, &lottery, &request).await?,
+        vec![expected.id()]
+    );
+    assert!(read_lottery(&chain, &lottery)?.drawn);
+    assert_eq!(balance(&chain, &lottery)?, 0);
+
+    let replay = draw_note(&players[2], &lottery, winner)?;
+    let result = draw(&mut chain, &lottery, &replay).await.map(|_| ());
+    assert_fails_with(result, "Jackpot was already paid out");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn tickets_are_rejected_from_the_end_of_sales() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let lottery = lottery_contract()?;
+    let ticket = ticket_note(&alice, &lottery, &Secret(random_word()), tokens(PRICE)?)?;
+    let mut chain = mock_chain(&[&lottery], &[&ticket])?;
+
+    chain.prove_until_block(SALES_END)?;
+    assert_fails_with(
+        consume(&mut chain, lottery.id(), &ticket).await,
+        "Ticket sales are over",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ticket_below_the_price_is_rejected() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let lottery = lottery_contract()?;
+    let ticket = ticket_note(&alice, &lottery, &Secret(random_word()), tokens(PRICE - 1)?)?;
+    let mut chain = mock_chain(&[&lottery], &[&ticket])?;
+
+    assert_fails_with(
+        consume(&mut chain, lottery.id(), &ticket).await,
+        "Ticket is not paid at the ticket price",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn a_player_cannot_hold_two_tickets() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let lottery = lottery_contract()?;
+    let first = ticket_note(&alice, &lottery, &Secret(random_word()), tokens(PRICE)?)?;
+    let second = ticket_note(&alice, &lottery, &Secret(random_word()), tokens(PRICE)?)?;
+    let mut chain = mock_chain(&[&lottery], &[&first, &second])?;
+
+    consume(&mut chain, lottery.id(), &first).await?;
+    assert_fails_with(
+        consume(&mut chain, lottery.id(), &second).await,
+        "Player already holds a ticket",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn secret_not_matching_the_commitment_is_rejected() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let lottery = lottery_contract()?;
+    let ticket = ticket_note(&alice, &lottery, &Secret(random_word()), tokens(PRICE)?)?;
+    let reveal = reveal_note(&alice, &lottery, &Secret(random_word()))?;
+    let mut chain = mock_chain(&[&lottery], &[&ticket, &reveal])?;
+
+    consume(&mut chain, lottery.id(), &ticket).await?;
+    chain.prove_until_block(SALES_END)?;
+    assert_fails_with(
+        consume(&mut chain, lottery.id(), &reveal).await,
+        "Secret does not match the commitment",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn winner_cannot_be_drawn_before_the_reveal_phase_ends() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let secret = Secret(random_word());
+    let lottery = lottery_contract()?;
+    let ticket = ticket_note(&alice, &lottery, &secret, tokens(PRICE)?)?;
+    let reveal = reveal_note(&alice, &lottery, &secret)?;
+    let mut chain = mock_chain(&[&lottery], &[&ticket, &reveal])?;
+
+    consume(&mut chain, lottery.id(), &ticket).await?;
+    chain.prove_until_block(SALES_END)?;
+    consume(&mut chain, lottery.id(), &reveal).await?;
+
+    let request = draw_note(&alice, &lottery, alice.id())?;
+    let result = draw(&mut chain, &lottery, &request).await.map(|_| ());
+    assert_fails_with(result, "Reveal phase is not over");
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! Negative-path tests for the MASM contracts of the tutorials.
+//!
+//! Every contract gets at least three failure paths (bad inputs, unauthorized callers and
+//! replayed notes where the contract is driven by notes), all executed against the mock chain
+//! so that they run without a node.
+//!
+//! The tests of the contracts whose examples sit behind a cargo feature only run with that
+//! feature, so run them all with `cargo test --features full`.
+
+mod common;
+
+#[cfg(feature = "defi")]
+mod amm_pool;
+mod api_credits;
+#[cfg(feature = "defi")]
+mod auction;
+#[cfg(feature = "games")]
+mod battleship;
+#[cfg(feature = "governance")]
+mod budget;
+#[cfg(feature = "games")]
+mod connect_four;
+mod count_reader;
+mod counter;
+#[cfg(feature = "defi")]
+mod crowdfund;
+mod debug_counter;
+mod deposit_counter;
+#[cfg(feature = "defi")]
+mod escrow;
+#[cfg(feature = "defi")]
+mod factory;
+mod fractional_vault;
+mod htlc_note;
+mod kernel_inputs_note;
+mod lending_pool;
+#[cfg(feature = "games")]
+mod lottery;
+mod mapping_example;
+#[cfg(feature = "governance")]
+mod multisig;
+mod nft_collection;
+#[cfg(feature = "defi")]
+mod nft_marketplace;
+mod note_routing;
+mod oracle_reader;
+#[cfg(feature = "defi")]
+mod payment_stream;
+#[cfg(feature = "games")]
+mod ranking;
+mod recallable_p2id;
+#[cfg(feature = "games")]
+mod rock_paper_scissors;
+mod royalty_sale_note;
+mod scheduler;
+mod smart_wallet;
+#[cfg(feature = "guides")]
+mod storage_migration;
+#[cfg(feature = "defi")]
+mod subscription;
+mod swap_offer;
+mod swapp_note;
+#[cfg(feature = "games")]
+mod tic_tac_toe;
+#[cfg(feature = "defi")]
+mod token_registry;
+mod trade_escrow;
+#[cfg(feature = "governance")]
+mod treasury;
+#[cfg(feature = "defi")]
+mod vesting;
+#[cfg(feature = "governance")]
+mod voting;
+#[cfg(feature = "games")]
+mod wager_escrow;
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{AccountType, StorageMap, StorageSlot},
+    Word,
+};
+
+use crate::common::{
+    contract, create_library, masm, mock_chain, run_script, tx_script, DIRECT_STORAGE_WRITE,
+};
+
+const LIBRARY_PATH: &str = "miden_by_example::mapping_example_contract";
+
+fn mapping_storage() -> Vec<StorageSlot> {
+    vec![
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Map(StorageMap::new()),
+    ]
+}
+
+#[tokio::test]
+async fn map_cannot_be_written_outside_the_contract() -> Result<()> {
+    let code = masm("accounts/mapping_example_contract.masm");
+    let mapping = contract(
+        &code,
+        AccountType::RegularAccountImmutableCode,
+        mapping_storage(),
+    )?;
+    let mut chain = mock_chain(&[&mapping], &[])?;
+
+    let script = tx_script(
+        "
+        use.miden::native_account
+
+        begin
+            push.1.2.3.4
+            push.0.0.0.0
+            push.1
+            exec.native_account::set_map_item
+            dropw dropw
+        end
+        ",
+        None,
+    )?;
+    assert!(run_script(&mut chain, mapping.id(), script).await.is_err());
+
+    // Plain value slots are protected the same way
+    let script = tx_script(DIRECT_STORAGE_WRITE, None)?;
+    assert!(run_script(&mut chain, mapping.id(), script).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn write_to_map_fails_if_slot_is_not_a_map() -> Result<()> {
+    let code = masm("accounts/mapping_example_contract.masm");
+    let misconfigured = contract(
+        &code,
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?;
+    let mut chain = mock_chain(&[&misconfigured], &[])?;
+
+    let library = create_library(code, LIBRARY_PATH)?;
+    let script = tx_script(&masm("scripts/mapping_example_script.masm"), Some(&library))?;
+    assert!(run_script(&mut chain, misconfigured.id(), script)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mapping_script_fails_against_another_contract() -> Result<()> {
+    let counter = contract(
+        &masm("accounts/counter.masm"),
+        AccountType::RegularAccountImmutableCode,
+        mapping_storage(),
+    )?;
+    let mut chain = mock_chain(&[&counter], &[])?;
+
+    let library = create_library(masm("accounts/mapping_example_contract.masm"), LIBRARY_PATH)?;
+    let script = tx_script(&masm("scripts/mapping_example_script.masm"), Some(&library))?;
+    assert!(run_script(&mut chain, counter.id(), script).await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{auth::AuthSecretKey, Account, AccountId, AccountType, StorageMap, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+    transaction::TransactionScript,
+    Felt, Word, ONE, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::{
+    helpers::word_to_masm,
+    multisig::{Approval, Proposal},
+};
+
+use crate::common::{
+    assert_fails_with, contract, create_library, masm, mock_chain, note, note_script, random_word,
+    run_script, sender, tx_script,
+};
+
+/// Builds a 2-of-3 multisig of `signer_keys`.
+fn multisig_account(signer_keys: &[AuthSecretKey]) -> Result<Account> {
+    let signers = StorageMap::with_entries(signer_keys.iter().map(|key| {
+        (
+            key.public_key().to_commitment().into(),
+            Word::from([ZERO, ZERO, ZERO, ONE]),
+        )
+    }))?;
+    contract(
+        &masm("accounts/multisig.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Map(signers),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::new(2)].into()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+        ],
+    )
+}
+
+fn signer_keys() -> [AuthSecretKey; 3] {
+    [
+        AuthSecretKey::new_rpo_falcon512(),
+        AuthSecretKey::new_rpo_falcon512(),
+        AuthSecretKey::new_rpo_falcon512(),
+    ]
+}
+
+/// Proposes to pay 10 tokens to the test sender.
+fn proposal() -> Result<Proposal> {
+    let faucet_id = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET)?;
+    let payout = note(
+        sender(),
+        WellKnownNote::P2ID.script(),
+        vec![sender().suffix(), sender().prefix().as_felt()],
+        vec![FungibleAsset::new(faucet_id, 10)?.into()],
+    )?;
+    Proposal::new(payout).map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+/// Signs `proposal` with `key` for the multisig `signed_for`.
+fn approval(signed_for: &Account, proposal: &Proposal, key: &AuthSecretKey) -> Result<Approval> {
+    let library = create_library(
+        masm("accounts/multisig.masm"),
+        "external_contract::multisig",
+    )?;
+    let script = note_script(&masm("notes/multisig_approval_note.masm"), Some(&library))?;
+    Ok(Approval::new(
+        sender(),
+        signed_for.id(),
+        proposal,
+        key,
+        script,
+        random_word(),
+    )?)
+}
+
+/// Consumes `approvals` with the multisig, providing their signatures in the advice map, and
+/// includes the transaction in a new block.
+async fn approve(chain: &mut MockChain, multisig: &Account, approvals: &[&Approval]) -> Result<()> {
+    let note_ids: Vec<_> = approvals
+        .iter()
+        .map(|approval| approval.note.id())
+        .collect();
+    let executed = chain
+        .build_tx_context(multisig.id(), &note_ids, &[])?
+        .extend_advice_map(
+            approvals
+                .iter()
+                .map(|approval| (approval.signature_key, approval.signature.clone())),
+        )
+        .build()?
+        .execute()
+        .await?;
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(())
+}
+
+/// Transaction script executing `proposal`.
+fn execute_script(proposal: &Proposal) -> Result<TransactionScript> {
+    let library = create_library(
+        masm("accounts/multisig.masm"),
+        "external_contract::multisig",
+    )?;
+    let code = format!(
+        "use.external_contract::multisig\n\
+         begin\n    push.{}\n    push.{}\n    push.{}\n    call.multisig::execute\nend\n",
+        proposal.payout.metadata().tag().as_u32(),
+        word_to_masm(Asset::from(proposal.asset).into()),
+        word_to_masm(proposal.payout.recipient().digest())
+    );
+    tx_script(&code, Some(&library))
+}
+
+#[tokio::test]
+async fn approval_of_an_unknown_key_is_rejected() -> Result<()> {
+    let keys = signer_keys();
+    let multisig = multisig_account(&keys)?;
+    let proposal = proposal()?;
+    let outsider = approval(&multisig, &proposal, &AuthSecretKey::new_rpo_falcon512())?;
+    let mut chain = mock_chain(&[&multisig], &[&outsider.note])?;
+
+    assert_fails_with(
+        approve(&mut chain, &multisig, &[&outsider]).await,
+        "Approver is not a signer of this multisig",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn approval_signed_for_another_multisig_is_rejected() -> Result<()> {
+    let keys = signer_keys();
+    let multisig = multisig_account(&keys)?;
+    let other = multisig_account(&keys)?;
+    let proposal = proposal()?;
+    let replayed = approval(&other, &proposal, &keys[0])?;
+    let mut chain = mock_chain(&[&multisig], &[&replayed.note])?;
+
+    // The signature is looked up under a message including the id of the multisig, so the
+    // one made for the other multisig is not found
+    assert!(approve(&mut chain, &multisig, &[&replayed]).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn signer_cannot_approve_twice() -> Result<()> {
+    let keys = signer_keys();
+    let multisig = multisig_account(&keys)?;
+    let proposal = proposal()?;
+    let first = approval(&multisig, &proposal, &keys[0])?;
+    let second = approval(&multisig, &proposal, &keys[0])?;
+    let mut chain = mock_chain(&[&multisig], &[&first.note, &second.note])?;
+
+    approve(&mut chain, &multisig, &[&first]).await?;
+    assert_fails_with(
+        approve(&mut chain, &multisig, &[&second]).await,
+        "Signer already approved this payout",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn payout_needs_enough_approvals() -> Result<()> {
+    let keys = signer_keys();
+    let multisig = multisig_account(&keys)?;
+    let proposal = proposal()?;
+    let first = approval(&multisig, &proposal, &keys[0])?;
+    let mut chain = mock_chain(&[&multisig], &[&first.note])?;
+
+    approve(&mut chain, &multisig, &[&first]).await?;
+    assert_fails_with(
+        run_script(&mut chain, multisig.id(), execute_script(&proposal)?).await,
+        "Payout does not have enough approvals",
+    );
+
+    Ok(())
+}
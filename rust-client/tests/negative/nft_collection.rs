@@ -0,0 +1,116 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageSlot},
+    asset::{NonFungibleAsset, NonFungibleAssetDetails},
+    note::{NoteExecutionHint, NoteTag, NoteType},
+    testing::account_id::ACCOUNT_ID_PUBLIC_NON_FUNGIBLE_FAUCET,
+    Word,
+};
+use rust_client::helpers::word_to_masm;
+
+use crate::common::{
+    contract, create_library, masm, mock_chain, random_word, run_script, sender, tx_script,
+};
+
+fn collection_contract() -> Result<Account> {
+    contract(
+        &masm("accounts/nft_collection.masm"),
+        AccountType::NonFungibleFaucet,
+        vec![StorageSlot::Value(Word::default())],
+    )
+}
+
+/// Fills in the mint script so that it mints `nft` into a note for the test sender.
+fn mint_script(nft: NonFungibleAsset) -> String {
+    masm("scripts/mint_nft_script.masm")
+        .replace("{recipient}", &word_to_masm(random_word()))
+        .replace(
+            "{execution_hint}",
+            &u64::from(NoteExecutionHint::always()).to_string(),
+        )
+        .replace("{note_type}", &(NoteType::Public as u8).to_string())
+        .replace("{aux}", "0")
+        .replace(
+            "{tag}",
+            &u32::from(NoteTag::from_account_id(sender())).to_string(),
+        )
+        .replace("{nft_asset}", &word_to_masm(Word::from(nft)))
+}
+
+fn nft(collection: AccountId, data: &[u8]) -> Result<NonFungibleAsset> {
+    let details = NonFungibleAssetDetails::new(collection.prefix(), data.to_vec())?;
+    Ok(NonFungibleAsset::new(&details)?)
+}
+
+#[tokio::test]
+async fn nft_of_another_collection_cannot_be_minted() -> Result<()> {
+    let code = masm("accounts/nft_collection.masm");
+    let collection = collection_contract()?;
+    let mut chain = mock_chain(&[&collection], &[])?;
+
+    let other_collection = AccountId::try_from(ACCOUNT_ID_PUBLIC_NON_FUNGIBLE_FAUCET)?;
+    let library = create_library(code, "external_contract::nft_collection")?;
+    let script = tx_script(
+        &mint_script(nft(other_collection, b"foreign")?),
+        Some(&library),
+    )?;
+    assert!(run_script(&mut chain, collection.id(), script)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn nft_cannot_be_minted_outside_the_contract() -> Result<()> {
+    let collection = collection_contract()?;
+    let mut chain = mock_chain(&[&collection], &[])?;
+
+    let nft = nft(collection.id(), b"bypass")?;
+    let script = tx_script(
+        &format!(
+            "
+            use.miden::faucet
+
+            begin
+                push.{}
+                exec.faucet::mint
+                dropw
+            end
+            ",
+            word_to_masm(Word::from(nft))
+        ),
+        None,
+    )?;
+    assert!(run_script(&mut chain, collection.id(), script)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn same_nft_cannot_be_minted_twice() -> Result<()> {
+    let code = masm("accounts/nft_collection.masm");
+    let collection = collection_contract()?;
+    let mut chain = mock_chain(&[&collection], &[])?;
+
+    let library = create_library(code, "external_contract::nft_collection")?;
+    let nft = nft(collection.id(), b"one of one")?;
+
+    run_script(
+        &mut chain,
+        collection.id(),
+        tx_script(&mint_script(nft), Some(&library))?,
+    )
+    .await?;
+    assert!(run_script(
+        &mut chain,
+        collection.id(),
+        tx_script(&mint_script(nft), Some(&library))?,
+    )
+    .await
+    .is_err());
+
+    Ok(())
+}
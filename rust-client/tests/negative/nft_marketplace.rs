@@ -0,0 +1,298 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    asset::{Asset, FungibleAsset, NonFungibleAsset, NonFungibleAssetDetails},
+    note::{Note, NoteId, NoteTag},
+    testing::account_id::{
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1,
+        ACCOUNT_ID_PUBLIC_NON_FUNGIBLE_FAUCET,
+    },
+    Felt, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::nft_marketplace::{
+    self, Listing, Marketplace, SELLER_PAYOUT_OFFSET, SENDER_PAYOUT_OFFSET,
+};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note_script,
+    random_word, wallet,
+};
+
+/// Price every test lists the NFT for.
+const PRICE: u64 = 200;
+
+fn currency() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn tokens(amount: u64) -> Result<FungibleAsset> {
+    Ok(FungibleAsset::new(currency(), amount)?)
+}
+
+fn nft() -> Result<NonFungibleAsset> {
+    let collection = AccountId::try_from(ACCOUNT_ID_PUBLIC_NON_FUNGIBLE_FAUCET)?;
+    let details = NonFungibleAssetDetails::new(collection.prefix(), b"Marketplace NFT".to_vec())?;
+    Ok(NonFungibleAsset::new(&details)?)
+}
+
+/// Builds an empty marketplace in which prices are paid in `currency()`.
+fn marketplace_contract() -> Result<Account> {
+    contract(
+        &masm("accounts/nft_marketplace.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(
+                [
+                    ZERO,
+                    ZERO,
+                    currency().suffix(),
+                    currency().prefix().as_felt(),
+                ]
+                .into(),
+            ),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )
+}
+
+/// Builds a note of `sender` running the note script at `script_path` on the marketplace.
+fn marketplace_note(
+    sender: &Account,
+    marketplace: &Account,
+    script_path: &str,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+) -> Result<Note> {
+    let library = create_library(
+        masm("accounts/nft_marketplace.masm"),
+        nft_marketplace::LIBRARY_PATH,
+    )?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    Ok(nft_marketplace::marketplace_note(
+        sender.id(),
+        marketplace.id(),
+        inputs,
+        assets,
+        script,
+        random_word(),
+    )?)
+}
+
+fn list_note(seller: &Account, marketplace: &Account, price: u64) -> Result<Note> {
+    let inputs = vec![
+        Felt::new(price),
+        NoteTag::from_account_id(seller.id()).into(),
+    ];
+    marketplace_note(
+        seller,
+        marketplace,
+        "notes/nft_list_note.masm",
+        inputs,
+        vec![nft()?.into()],
+    )
+}
+
+fn nft_inputs(sender: &Account) -> Result<Vec<Felt>> {
+    let mut inputs = Word::from(nft()?).to_vec();
+    inputs.push(NoteTag::from_account_id(sender.id()).into());
+    Ok(inputs)
+}
+
+fn buy_note(buyer: &Account, marketplace: &Account, payment: FungibleAsset) -> Result<Note> {
+    marketplace_note(
+        buyer,
+        marketplace,
+        "notes/nft_buy_note.masm",
+        nft_inputs(buyer)?,
+        vec![payment.into()],
+    )
+}
+
+fn cancel_note(seller: &Account, marketplace: &Account) -> Result<Note> {
+    marketplace_note(
+        seller,
+        marketplace,
+        "notes/nft_cancel_note.masm",
+        nft_inputs(seller)?,
+        vec![],
+    )
+}
+
+fn read_marketplace(chain: &MockChain, marketplace: &Account) -> Result<Marketplace> {
+    Marketplace::from_account(&chain.committed_account(marketplace.id())?)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+fn read_listing(chain: &MockChain, marketplace: &Account) -> Result<Option<Listing>> {
+    nft_marketplace::listing(&chain.committed_account(marketplace.id())?, nft()?)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+/// Consumes `note` with the marketplace and returns the ids of the notes it created.
+async fn consume_by_marketplace(
+    chain: &mut MockChain,
+    marketplace: &Account,
+    note: &Note,
+) -> Result<Vec<NoteId>> {
+    let executed = chain
+        .build_tx_context(marketplace.id(), &[note.id()], &[])?
+        .build()?
+        .execute()
+        .await?;
+    let created = executed
+        .output_notes()
+        .iter()
+        .map(|note| note.id())
+        .collect();
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(created)
+}
+
+#[tokio::test]
+async fn buyer_gets_the_nft_and_seller_the_payment_in_one_transaction() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let carol = wallet(vec![])?;
+    let mallory = wallet(vec![])?;
+    let marketplace = marketplace_contract()?;
+
+    let listing = list_note(&alice, &marketplace, PRICE)?;
+    let underpaid = buy_note(&mallory, &marketplace, tokens(PRICE - 1)?)?;
+    let other = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1)?;
+    let wrong_currency = buy_note(&mallory, &marketplace, FungibleAsset::new(other, PRICE)?)?;
+    let purchase = buy_note(&bob, &marketplace, tokens(PRICE)?)?;
+    let late = buy_note(&carol, &marketplace, tokens(PRICE)?)?;
+    let mut chain = mock_chain(
+        &[&marketplace],
+        &[&listing, &underpaid, &wrong_currency, &purchase, &late],
+    )?;
+
+    consume(&mut chain, marketplace.id(), &listing).await?;
+    assert_eq!(
+        read_listing(&chain, &marketplace)?,
+        Some(Listing {
+            seller: alice.id(),
+            price: PRICE,
+            seller_tag: NoteTag::from_account_id(alice.id()),
+        })
+    );
+
+    assert_fails_with(
+        consume(&mut chain, marketplace.id(), &underpaid).await,
+        "Payment does not match the listing price",
+    );
+    assert_fails_with(
+        consume(&mut chain, marketplace.id(), &wrong_currency).await,
+        "Payment is not made in the marketplace currency",
+    );
+
+    let market = read_marketplace(&chain, &marketplace)?;
+    let to_bob = market.payout_note(
+        bob.id(),
+        nft()?.into(),
+        purchase.serial_num(),
+        SENDER_PAYOUT_OFFSET,
+    )?;
+    let to_alice = market.payout_note(
+        alice.id(),
+        tokens(PRICE)?.into(),
+        purchase.serial_num(),
+        SELLER_PAYOUT_OFFSET,
+    )?;
+    assert_eq!(
+        consume_by_marketplace(&mut chain, &marketplace, &purchase).await?,
+        vec![to_bob.id(), to_alice.id()]
+    );
+    assert_eq!(read_listing(&chain, &marketplace)?, None);
+    assert_eq!(
+        chain
+            .committed_account(marketplace.id())?
+            .vault()
+            .assets()
+            .count(),
+        0
+    );
+
+    assert_fails_with(
+        consume(&mut chain, marketplace.id(), &late).await,
+        "NFT is not listed",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_the_seller_can_cancel_a_listing() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let mallory = wallet(vec![])?;
+    let marketplace = marketplace_contract()?;
+
+    let listing = list_note(&alice, &marketplace, PRICE)?;
+    let theft = cancel_note(&mallory, &marketplace)?;
+    let cancel = cancel_note(&alice, &marketplace)?;
+    let replay = cancel_note(&alice, &marketplace)?;
+    let purchase = buy_note(&bob, &marketplace, tokens(PRICE)?)?;
+    let mut chain = mock_chain(
+        &[&marketplace],
+        &[&listing, &theft, &cancel, &replay, &purchase],
+    )?;
+
+    consume(&mut chain, marketplace.id(), &listing).await?;
+    assert_fails_with(
+        consume(&mut chain, marketplace.id(), &theft).await,
+        "Only the seller can cancel the listing",
+    );
+
+    let returned = read_marketplace(&chain, &marketplace)?.payout_note(
+        alice.id(),
+        nft()?.into(),
+        cancel.serial_num(),
+        SENDER_PAYOUT_OFFSET,
+    )?;
+    assert_eq!(
+        consume_by_marketplace(&mut chain, &marketplace, &cancel).await?,
+        vec![returned.id()]
+    );
+
+    for note in [&replay, &purchase] {
+        assert_fails_with(
+            consume(&mut chain, marketplace.id(), note).await,
+            "NFT is not listed",
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn listing_twice_or_for_free_is_rejected() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let marketplace = marketplace_contract()?;
+
+    let free = list_note(&alice, &marketplace, 0)?;
+    let listing = list_note(&alice, &marketplace, PRICE)?;
+    let relisting = list_note(&alice, &marketplace, PRICE + 1)?;
+    let mut chain = mock_chain(&[&marketplace], &[&free, &listing, &relisting])?;
+
+    assert_fails_with(
+        consume(&mut chain, marketplace.id(), &free).await,
+        "Listing price must not be zero",
+    );
+    consume(&mut chain, marketplace.id(), &listing).await?;
+    assert_fails_with(
+        consume(&mut chain, marketplace.id(), &relisting).await,
+        "NFT is already listed",
+    );
+    assert_eq!(
+        read_listing(&chain, &marketplace)?.map(|listing| listing.price),
+        Some(PRICE)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//! Note tags only route notes to clients, they don't restrict who can consume them.
+//!
+//! A client syncs every public note whose tag it tracks, so colliding tags make it receive
+//! notes meant for other accounts. Which account can consume a note is decided by its script
+//! alone.
+
+use anyhow::Result;
+use miden_lib::note::create_p2id_note;
+use miden_objects::{
+    account::{AccountId, AccountIdVersion, AccountStorageMode, AccountType},
+    crypto::rand::RpoRandomCoin,
+    note::{Note, NoteExecutionMode, NoteMetadata, NoteTag, NoteType},
+    Felt,
+};
+
+use crate::common::{consume, mock_chain, random_word, sender, wallet};
+
+/// Builds an account id from `seed`. Seeds differing only in the last bytes give ids with the
+/// same prefix.
+fn account_id(seed: [u8; 15], storage_mode: AccountStorageMode) -> AccountId {
+    AccountId::dummy(
+        seed,
+        AccountIdVersion::Version0,
+        AccountType::RegularAccountImmutableCode,
+        storage_mode,
+    )
+}
+
+/// Builds a P2ID note for `target` carrying `tag` instead of the tag derived from `target`.
+fn p2id_note_with_tag(target: AccountId, tag: NoteTag) -> Result<Note> {
+    let mut rng = RpoRandomCoin::new(random_word());
+    let note = create_p2id_note(
+        sender(),
+        target,
+        vec![],
+        NoteType::Public,
+        Felt::new(0),
+        &mut rng,
+    )?;
+    let metadata = NoteMetadata::new(
+        sender(),
+        NoteType::Public,
+        tag,
+        note.metadata().execution_hint(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(
+        note.assets().clone(),
+        metadata,
+        note.recipient().clone(),
+    ))
+}
+
+#[test]
+fn local_accounts_sharing_the_high_prefix_bits_share_a_tag() {
+    // Only the 14 high bits of the prefix make it into the tag of a local account
+    let mut seed = [7u8; 15];
+    let first = account_id(seed, AccountStorageMode::Public);
+    seed[2] ^= 0xff;
+    seed[14] ^= 0xff;
+    let second = account_id(seed, AccountStorageMode::Public);
+
+    assert_ne!(first, second);
+    assert_eq!(
+        NoteTag::from_account_id(first),
+        NoteTag::from_account_id(second),
+        "a client tracking either account syncs the notes of both"
+    );
+}
+
+#[test]
+fn network_account_tags_keep_more_prefix_bits() {
+    // The same ids in network mode differ in the 30 high bits, so their tags differ too
+    let mut seed = [7u8; 15];
+    let first = account_id(seed, AccountStorageMode::Network);
+    seed[2] ^= 0xff;
+    let second = account_id(seed, AccountStorageMode::Network);
+
+    let first_tag = NoteTag::from_account_id(first);
+    assert_eq!(first_tag.execution_mode(), NoteExecutionMode::Network);
+    assert_ne!(first_tag, NoteTag::from_account_id(second));
+}
+
+#[test]
+fn tutorials_using_the_default_public_tag_collide() {
+    // `hash_preimage_note` and `note_creation_in_masm` both tag their notes with use case 0, so
+    // a client running both examples syncs the notes of each
+    let preimage_tag = NoteTag::for_public_use_case(0, 0, NoteExecutionMode::Local).unwrap();
+    let note_creation_tag = NoteTag::for_public_use_case(0, 0, NoteExecutionMode::Local).unwrap();
+    assert_eq!(preimage_tag, note_creation_tag);
+
+    // A distinct use case gives a distinct tag
+    let own_tag = NoteTag::for_public_use_case(1, 0, NoteExecutionMode::Local).unwrap();
+    assert_ne!(preimage_tag, own_tag);
+}
+
+#[tokio::test]
+async fn mis_tagged_note_cannot_be_consumed_by_the_tagged_account() -> Result<()> {
+    let target = wallet(vec![])?;
+    let tagged = wallet(vec![])?;
+
+    // Addressed to `target`, but routed to clients tracking `tagged`
+    let note = p2id_note_with_tag(target.id(), NoteTag::from_account_id(tagged.id()))?;
+    let mut chain = mock_chain(&[&target, &tagged], &[&note])?;
+
+    assert!(consume(&mut chain, tagged.id(), &note).await.is_err());
+    consume(&mut chain, target.id(), &note).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn tracking_the_tag_does_not_make_a_note_consumable() -> Result<()> {
+    let target = wallet(vec![])?;
+    let other = wallet(vec![])?;
+
+    // Correctly tagged for `target`. A client syncing it because its own account's tag
+    // collides still cannot consume the note with that account.
+    let note = p2id_note_with_tag(target.id(), NoteTag::from_account_id(target.id()))?;
+    let mut chain = mock_chain(&[&target, &other], &[&note])?;
+
+    assert!(consume(&mut chain, other.id(), &note).await.is_err());
+    consume(&mut chain, target.id(), &note).await?;
+
+    Ok(())
+}
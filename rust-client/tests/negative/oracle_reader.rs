@@ -0,0 +1,65 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{AccountType, StorageSlot},
+    Word,
+};
+
+use crate::common::{
+    contract, create_library, masm, mock_chain, run_script, tx_script, DIRECT_STORAGE_WRITE,
+};
+
+const LIBRARY_PATH: &str = "external_contract::oracle_reader";
+
+#[tokio::test]
+async fn price_cannot_be_read_without_the_oracle_account() -> Result<()> {
+    let code = masm("accounts/oracle_reader.masm");
+    let reader = contract(&code, AccountType::RegularAccountImmutableCode, vec![])?;
+    let mut chain = mock_chain(&[&reader], &[])?;
+
+    // The Pragma oracle only exists on testnet, so it cannot be provided as a foreign account
+    let library = create_library(code, LIBRARY_PATH)?;
+    let script = tx_script(&masm("scripts/oracle_reader_script.masm"), Some(&library))?;
+    assert!(run_script(&mut chain, reader.id(), script).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn get_price_cannot_be_called_on_another_contract() -> Result<()> {
+    let counter = contract(
+        &masm("accounts/counter.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![StorageSlot::Value(Word::default())],
+    )?;
+    let mut chain = mock_chain(&[&counter], &[])?;
+
+    let library = create_library(masm("accounts/oracle_reader.masm"), LIBRARY_PATH)?;
+    let script = tx_script(
+        "
+        use.external_contract::oracle_reader
+
+        begin
+            call.oracle_reader::get_price
+        end
+        ",
+        Some(&library),
+    )?;
+    assert!(run_script(&mut chain, counter.id(), script).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn oracle_reader_has_no_writable_storage() -> Result<()> {
+    let reader = contract(
+        &masm("accounts/oracle_reader.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![],
+    )?;
+    let mut chain = mock_chain(&[&reader], &[])?;
+
+    let script = tx_script(DIRECT_STORAGE_WRITE, None)?;
+    assert!(run_script(&mut chain, reader.id(), script).await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,299 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    note::{Note, NoteId, NoteTag},
+    testing::account_id::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1},
+    Felt, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::payment_stream::{self, PaymentStream};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note_script,
+    random_word, wallet,
+};
+
+/// Block the stream starts at.
+const START: u32 = 5;
+/// Number of blocks the stream runs for.
+const DURATION: u32 = 10;
+/// Tokens accruing per block.
+const RATE: u64 = 10;
+
+fn currency() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn tokens(amount: u64) -> Result<FungibleAsset> {
+    Ok(FungibleAsset::new(currency(), amount)?)
+}
+
+fn account_word(account_id: AccountId) -> Word {
+    [
+        ZERO,
+        ZERO,
+        account_id.suffix(),
+        account_id.prefix().as_felt(),
+    ]
+    .into()
+}
+
+fn value_word(value: u64) -> Word {
+    [ZERO, ZERO, ZERO, Felt::new(value)].into()
+}
+
+/// Builds an unfunded stream by which `employer` pays `employee` `RATE` tokens per block.
+fn stream_contract(employer: &Account, employee: &Account) -> Result<Account> {
+    contract(
+        &masm("accounts/payment_stream.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(account_word(employer.id())),
+            StorageSlot::Value(account_word(employee.id())),
+            StorageSlot::Value(account_word(currency())),
+            StorageSlot::Value(value_word(START.into())),
+            StorageSlot::Value(value_word((START + DURATION).into())),
+            StorageSlot::Value(value_word(RATE)),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )
+}
+
+/// Builds a note of `sender` running the note script at `script_path` on the stream.
+fn stream_note(
+    sender: &Account,
+    stream: &Account,
+    script_path: &str,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+) -> Result<Note> {
+    let library = create_library(
+        masm("accounts/payment_stream.masm"),
+        payment_stream::LIBRARY_PATH,
+    )?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    Ok(payment_stream::stream_note(
+        sender.id(),
+        stream.id(),
+        inputs,
+        assets,
+        script,
+        random_word(),
+    )?)
+}
+
+fn deposit_note(funder: &Account, stream: &Account, asset: FungibleAsset) -> Result<Note> {
+    stream_note(
+        funder,
+        stream,
+        "notes/payment_stream_deposit_note.masm",
+        vec![],
+        vec![asset.into()],
+    )
+}
+
+fn withdraw_note(sender: &Account, stream: &Account) -> Result<Note> {
+    let inputs = vec![NoteTag::from_account_id(sender.id()).into()];
+    stream_note(
+        sender,
+        stream,
+        "notes/payment_stream_withdraw_note.masm",
+        inputs,
+        vec![],
+    )
+}
+
+fn cancel_note(sender: &Account, stream: &Account) -> Result<Note> {
+    let inputs = vec![NoteTag::from_account_id(sender.id()).into()];
+    stream_note(
+        sender,
+        stream,
+        "notes/payment_stream_cancel_note.masm",
+        inputs,
+        vec![],
+    )
+}
+
+fn read_stream(chain: &MockChain, stream: &Account) -> Result<PaymentStream> {
+    PaymentStream::from_account(&chain.committed_account(stream.id())?)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+fn balance(chain: &MockChain, stream: &Account) -> Result<u64> {
+    Ok(chain
+        .committed_account(stream.id())?
+        .vault()
+        .get_balance(currency())?)
+}
+
+/// Consumes `note` with the stream and returns the ids of the notes it created.
+async fn consume_by_stream(
+    chain: &mut MockChain,
+    stream: &Account,
+    note: &Note,
+) -> Result<Vec<NoteId>> {
+    let executed = chain
+        .build_tx_context(stream.id(), &[note.id()], &[])?
+        .build()?
+        .execute()
+        .await?;
+    let created = executed
+        .output_notes()
+        .iter()
+        .map(|note| note.id())
+        .collect();
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(created)
+}
+
+#[tokio::test]
+async fn employee_withdraws_what_accrued_since_the_last_withdrawal() -> Result<()> {
+    let employer = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let stream = stream_contract(&employer, &alice)?;
+
+    let deposit = deposit_note(&employer, &stream, tokens(100)?)?;
+    let early = withdraw_note(&alice, &stream)?;
+    let first = withdraw_note(&alice, &stream)?;
+    let last = withdraw_note(&alice, &stream)?;
+    let replay = withdraw_note(&alice, &stream)?;
+    let mut chain = mock_chain(&[&stream], &[&deposit, &early, &first, &last, &replay])?;
+
+    consume(&mut chain, stream.id(), &deposit).await?;
+    assert_fails_with(
+        consume(&mut chain, stream.id(), &early).await,
+        "Nothing accrued since the last withdrawal",
+    );
+
+    // Three blocks after the start, 30 tokens accrued
+    chain.prove_until_block(START + 3)?;
+    let expected = read_stream(&chain, &stream)?.payment(alice.id(), 30, first.serial_num())?;
+    assert_eq!(
+        consume_by_stream(&mut chain, &stream, &first).await?,
+        vec![expected.id()]
+    );
+    assert_eq!(read_stream(&chain, &stream)?.withdrawn, 30);
+    assert_eq!(balance(&chain, &stream)?, 70);
+
+    // Past the end, the remaining 70 tokens accrued and nothing more does
+    chain.prove_until_block(START + DURATION + 5)?;
+    let expected = read_stream(&chain, &stream)?.payment(alice.id(), 70, last.serial_num())?;
+    assert_eq!(
+        consume_by_stream(&mut chain, &stream, &last).await?,
+        vec![expected.id()]
+    );
+    assert_eq!(balance(&chain, &stream)?, 0);
+
+    assert_fails_with(
+        consume(&mut chain, stream.id(), &replay).await,
+        "Nothing accrued since the last withdrawal",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancelling_refunds_what_has_yet_to_accrue_only_once() -> Result<()> {
+    let employer = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let stream = stream_contract(&employer, &alice)?;
+
+    let deposit = deposit_note(&employer, &stream, tokens(100)?)?;
+    let cancel = cancel_note(&employer, &stream)?;
+    let withdrawal = withdraw_note(&alice, &stream)?;
+    let replay = cancel_note(&employer, &stream)?;
+    let mut chain = mock_chain(&[&stream], &[&deposit, &cancel, &withdrawal, &replay])?;
+
+    consume(&mut chain, stream.id(), &deposit).await?;
+
+    // Cancelled four blocks after the start, the 60 tokens of the last six blocks go back
+    chain.prove_until_block(START + 4)?;
+    let expected = read_stream(&chain, &stream)?.payment(employer.id(), 60, cancel.serial_num())?;
+    assert_eq!(
+        consume_by_stream(&mut chain, &stream, &cancel).await?,
+        vec![expected.id()]
+    );
+    assert_eq!(read_stream(&chain, &stream)?.end.as_u32(), START + 4);
+    assert_eq!(balance(&chain, &stream)?, 40);
+
+    // The 40 tokens accrued before the cancellation are still the employee's
+    let expected =
+        read_stream(&chain, &stream)?.payment(alice.id(), 40, withdrawal.serial_num())?;
+    assert_eq!(
+        consume_by_stream(&mut chain, &stream, &withdrawal).await?,
+        vec![expected.id()]
+    );
+    assert_eq!(balance(&chain, &stream)?, 0);
+
+    assert_fails_with(
+        consume(&mut chain, stream.id(), &replay).await,
+        "Stream already ended",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_the_parties_can_withdraw_and_cancel() -> Result<()> {
+    let employer = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let mallory = wallet(vec![])?;
+    let stream = stream_contract(&employer, &alice)?;
+
+    let deposit = deposit_note(&employer, &stream, tokens(100)?)?;
+    let theft = withdraw_note(&mallory, &stream)?;
+    let employer_withdrawal = withdraw_note(&employer, &stream)?;
+    let sabotage = cancel_note(&mallory, &stream)?;
+    let employee_cancel = cancel_note(&alice, &stream)?;
+    let mut chain = mock_chain(
+        &[&stream],
+        &[
+            &deposit,
+            &theft,
+            &employer_withdrawal,
+            &sabotage,
+            &employee_cancel,
+        ],
+    )?;
+
+    consume(&mut chain, stream.id(), &deposit).await?;
+    chain.prove_until_block(START + 4)?;
+    for note in [&theft, &employer_withdrawal] {
+        assert_fails_with(
+            consume(&mut chain, stream.id(), note).await,
+            "Only the employee can withdraw",
+        );
+    }
+    for note in [&sabotage, &employee_cancel] {
+        assert_fails_with(
+            consume(&mut chain, stream.id(), note).await,
+            "Only the employer can cancel the stream",
+        );
+    }
+    assert_eq!(balance(&chain, &stream)?, 100);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn deposit_in_another_currency_is_rejected() -> Result<()> {
+    let employer = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let stream = stream_contract(&employer, &alice)?;
+
+    let other = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1)?;
+    let deposit = deposit_note(&employer, &stream, FungibleAsset::new(other, 100)?)?;
+    let mut chain = mock_chain(&[&stream], &[&deposit])?;
+
+    assert_fails_with(
+        consume(&mut chain, stream.id(), &deposit).await,
+        "Deposit is not paid in the stream currency",
+    );
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    assembly::Library,
+    note::Note,
+    Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::ranking::{self, PlayerStats, Ranking, LIBRARY_PATH};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note, note_script,
+    sender, wallet,
+};
+
+/// Builds a ranking whose games are registered by `organizer`.
+fn ranking_contract(organizer: AccountId) -> Result<(Account, Library)> {
+    let code = masm("accounts/ranking.masm");
+    let organizer: Word = [ZERO, ZERO, organizer.suffix(), organizer.prefix().as_felt()].into();
+    let account = contract(
+        &code,
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(organizer),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+        ],
+    )?;
+    Ok((account, create_library(code, LIBRARY_PATH)?))
+}
+
+/// Builds the note by which the test sender registers `game_id`.
+fn register(game_id: AccountId, ranking: &Account, library: &Library) -> Result<Note> {
+    let script = note_script(&masm("notes/register_game_note.masm"), Some(library))?;
+    note(
+        ranking.id(),
+        script,
+        vec![game_id.prefix().as_felt(), game_id.suffix()],
+        vec![],
+    )
+}
+
+/// Builds the note by which the game `game_id` reports that `winner` beat `loser`.
+fn report(
+    game_id: AccountId,
+    winner: AccountId,
+    loser: AccountId,
+    ranking: &Account,
+    library: &Library,
+) -> Result<Note> {
+    let script = note_script(&masm("notes/report_result_note.masm"), Some(library))?;
+    Ok(ranking::report_note(
+        game_id,
+        winner,
+        loser,
+        ranking::report_tag(ranking.id()),
+        script,
+    )?)
+}
+
+fn read_ranking(chain: &MockChain, ranking: &Account) -> Result<Ranking> {
+    Ranking::from_account(chain.committed_account(ranking.id())?)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+#[tokio::test]
+async fn only_the_organizer_registers_games() -> Result<()> {
+    let organizer = wallet(vec![])?;
+    let game = wallet(vec![])?;
+    let (ranking, library) = ranking_contract(organizer.id())?;
+    let registration = register(game.id(), &ranking, &library)?;
+    let mut chain = mock_chain(&[&ranking], &[&registration])?;
+
+    assert_fails_with(
+        consume(&mut chain, ranking.id(), &registration).await,
+        "Only the organizer can register games",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn game_cannot_be_registered_twice() -> Result<()> {
+    let game = wallet(vec![])?;
+    let (ranking, library) = ranking_contract(sender())?;
+    let first = register(game.id(), &ranking, &library)?;
+    let second = register(game.id(), &ranking, &library)?;
+    let mut chain = mock_chain(&[&ranking], &[&first, &second])?;
+
+    consume(&mut chain, ranking.id(), &first).await?;
+    assert_fails_with(
+        consume(&mut chain, ranking.id(), &second).await,
+        "Game is already registered",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unregistered_game_cannot_report() -> Result<()> {
+    let (game, winner, loser) = (wallet(vec![])?, wallet(vec![])?, wallet(vec![])?);
+    let (ranking, library) = ranking_contract(sender())?;
+    let result = report(game.id(), winner.id(), loser.id(), &ranking, &library)?;
+    let mut chain = mock_chain(&[&ranking], &[&result])?;
+
+    assert_fails_with(
+        consume(&mut chain, ranking.id(), &result).await,
+        "Sender is not a registered game",
+    );
+    assert!(read_ranking(&chain, &ranking)?.players.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn game_reports_its_result_once() -> Result<()> {
+    let (game, winner, loser) = (wallet(vec![])?, wallet(vec![])?, wallet(vec![])?);
+    let (ranking, library) = ranking_contract(sender())?;
+    let registration = register(game.id(), &ranking, &library)?;
+    let result = report(game.id(), winner.id(), loser.id(), &ranking, &library)?;
+    // The same game claiming the opposite result afterwards
+    let reversed = report(game.id(), loser.id(), winner.id(), &ranking, &library)?;
+    let mut chain = mock_chain(&[&ranking], &[&registration, &result, &reversed])?;
+
+    consume(&mut chain, ranking.id(), &registration).await?;
+    consume(&mut chain, ranking.id(), &result).await?;
+    let state = read_ranking(&chain, &ranking)?;
+    assert_eq!(
+        state.player(winner.id()),
+        PlayerStats {
+            rating: 1216,
+            wins: 1,
+            losses: 0,
+        }
+    );
+    assert_eq!(
+        state.player(loser.id()),
+        PlayerStats {
+            rating: 1184,
+            wins: 0,
+            losses: 1,
+        }
+    );
+
+    assert_fails_with(
+        consume(&mut chain, ranking.id(), &reversed).await,
+        "Game already reported its result",
+    );
+
+    Ok(())
+}
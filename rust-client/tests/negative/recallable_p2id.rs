@@ -0,0 +1,106 @@
+//! Recallable pay-to-ID (P2IDE) notes: the target can consume them at any time, the sender can
+//! reclaim them once the reclaim height is reached.
+
+use anyhow::Result;
+use miden_lib::note::create_p2ide_note;
+use miden_objects::{
+    account::{Account, AccountId},
+    asset::FungibleAsset,
+    block::BlockNumber,
+    crypto::rand::RpoRandomCoin,
+    note::{Note, NoteType},
+    testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+    Felt,
+};
+use miden_testing::MockChain;
+
+use crate::common::{consume, mock_chain, random_word, wallet};
+
+/// Tokens sent in every note.
+const AMOUNT: u64 = 30;
+/// Block from which the sender can reclaim the note.
+const RECLAIM_HEIGHT: u32 = 5;
+
+fn faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+/// Builds a recallable note from `sender` to `target` carrying [`AMOUNT`] tokens.
+fn recallable_note(sender: &Account, target: &Account) -> Result<Note> {
+    let mut rng = RpoRandomCoin::new(random_word());
+    Ok(create_p2ide_note(
+        sender.id(),
+        target.id(),
+        vec![FungibleAsset::new(faucet(), AMOUNT)?.into()],
+        Some(BlockNumber::from(RECLAIM_HEIGHT)),
+        None,
+        NoteType::Public,
+        Felt::new(0),
+        &mut rng,
+    )?)
+}
+
+fn balance(chain: &MockChain, account: &Account) -> Result<u64> {
+    Ok(chain
+        .committed_account(account.id())?
+        .vault()
+        .get_balance(faucet())?)
+}
+
+#[tokio::test]
+async fn target_consumes_before_the_reclaim_height() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let payment = recallable_note(&alice, &bob)?;
+    let mut chain = mock_chain(&[&alice, &bob], &[&payment])?;
+
+    consume(&mut chain, bob.id(), &payment).await?;
+
+    assert_eq!(balance(&chain, &bob)?, AMOUNT);
+    assert_eq!(balance(&chain, &alice)?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sender_reclaims_after_the_reclaim_height() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let payment = recallable_note(&alice, &bob)?;
+    let mut chain = mock_chain(&[&alice, &bob], &[&payment])?;
+
+    chain.prove_until_block(RECLAIM_HEIGHT)?;
+    consume(&mut chain, alice.id(), &payment).await?;
+
+    assert_eq!(balance(&chain, &alice)?, AMOUNT);
+    assert_eq!(balance(&chain, &bob)?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sender_cannot_reclaim_before_the_reclaim_height() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let payment = recallable_note(&alice, &bob)?;
+    let mut chain = mock_chain(&[&alice, &bob], &[&payment])?;
+
+    assert!(consume(&mut chain, alice.id(), &payment).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn third_party_cannot_consume_after_the_reclaim_height() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let carol = wallet(vec![])?;
+    let payment = recallable_note(&alice, &bob)?;
+    let mut chain = mock_chain(&[&alice, &bob, &carol], &[&payment])?;
+
+    // Reaching the reclaim height only opens the note to the sender, not to anybody
+    chain.prove_until_block(RECLAIM_HEIGHT)?;
+    assert!(consume(&mut chain, carol.id(), &payment).await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,242 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountType, StorageMap, StorageSlot},
+    note::Note,
+    Felt, Hasher, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::rock_paper_scissors::{self, Game, Hand, HiddenHand, Outcome};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note_script,
+    random_word, wallet,
+};
+
+fn hidden(hand: Hand) -> HiddenHand {
+    let salt = random_word();
+    HiddenHand {
+        hand,
+        salt: [salt[0], salt[1], salt[2]],
+    }
+}
+
+/// Builds a game between `player1` and `player2` in which nobody committed yet.
+fn game_contract(player1: &Account, player2: &Account) -> Result<Account> {
+    let player_word = |player: &Account| -> Word {
+        [
+            ZERO,
+            ZERO,
+            player.id().suffix(),
+            player.id().prefix().as_felt(),
+        ]
+        .into()
+    };
+
+    contract(
+        &masm("accounts/rock_paper_scissors.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(player_word(player1)),
+            StorageSlot::Value(player_word(player2)),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )
+}
+
+/// Builds a note of `player` running the note script at `script_path` on the game.
+fn game_note(player: &Account, game: &Account, script_path: &str, word: Word) -> Result<Note> {
+    let library = create_library(
+        masm("accounts/rock_paper_scissors.masm"),
+        rock_paper_scissors::LIBRARY_PATH,
+    )?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    Ok(rock_paper_scissors::game_note(
+        player.id(),
+        game.id(),
+        word,
+        script,
+        random_word(),
+    )?)
+}
+
+fn commit_note(player: &Account, game: &Account, hand: &HiddenHand) -> Result<Note> {
+    game_note(
+        player,
+        game,
+        "notes/hand_commit_note.masm",
+        hand.commitment(),
+    )
+}
+
+fn reveal_note(player: &Account, game: &Account, hand: &HiddenHand) -> Result<Note> {
+    game_note(player, game, "notes/hand_reveal_note.masm", hand.to_word())
+}
+
+fn read_game(chain: &MockChain, game: &Account) -> Result<Game> {
+    Game::from_account(&chain.committed_account(game.id())?).map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+#[tokio::test]
+async fn winner_is_recorded_once_both_hands_are_revealed() -> Result<()> {
+    let (alice, bob) = (wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let (rock, scissors) = (hidden(Hand::Rock), hidden(Hand::Scissors));
+    let notes = [
+        commit_note(&alice, &game, &rock)?,
+        commit_note(&bob, &game, &scissors)?,
+        reveal_note(&alice, &game, &rock)?,
+        reveal_note(&bob, &game, &scissors)?,
+    ];
+    let mut chain = mock_chain(&[&game], &notes.iter().collect::<Vec<_>>())?;
+
+    for note in &notes[..3] {
+        consume(&mut chain, game.id(), note).await?;
+    }
+    let state = read_game(&chain, &game)?;
+    assert_eq!(state.num_commitments, 2);
+    assert_eq!(state.hands, [Some(Hand::Rock), None]);
+    assert_eq!(state.outcome, Outcome::Pending);
+
+    consume(&mut chain, game.id(), &notes[3]).await?;
+    let state = read_game(&chain, &game)?;
+    assert_eq!(state.hands, [Some(Hand::Rock), Some(Hand::Scissors)]);
+    assert_eq!(state.outcome, Outcome::Winner(alice.id()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn same_hands_are_a_draw() -> Result<()> {
+    let (alice, bob) = (wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let (alice_hand, bob_hand) = (hidden(Hand::Paper), hidden(Hand::Paper));
+    let notes = [
+        commit_note(&alice, &game, &alice_hand)?,
+        commit_note(&bob, &game, &bob_hand)?,
+        reveal_note(&bob, &game, &bob_hand)?,
+        reveal_note(&alice, &game, &alice_hand)?,
+    ];
+    let mut chain = mock_chain(&[&game], &notes.iter().collect::<Vec<_>>())?;
+
+    for note in &notes {
+        consume(&mut chain, game.id(), note).await?;
+    }
+    assert_eq!(read_game(&chain, &game)?.outcome, Outcome::Draw);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn hands_cannot_be_revealed_before_both_players_committed() -> Result<()> {
+    let (alice, bob) = (wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let rock = hidden(Hand::Rock);
+    let commit = commit_note(&alice, &game, &rock)?;
+    let reveal = reveal_note(&alice, &game, &rock)?;
+    let mut chain = mock_chain(&[&game], &[&commit, &reveal])?;
+
+    consume(&mut chain, game.id(), &commit).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &reveal).await,
+        "Both players must commit before revealing",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn hand_not_matching_the_commitment_is_rejected() -> Result<()> {
+    let (alice, bob) = (wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let (rock, scissors) = (hidden(Hand::Rock), hidden(Hand::Scissors));
+    // Bob committed to scissors, sees Alice reveal rock and tries to switch to paper
+    let switched = HiddenHand {
+        hand: Hand::Paper,
+        ..scissors
+    };
+    let notes = [
+        commit_note(&alice, &game, &rock)?,
+        commit_note(&bob, &game, &scissors)?,
+        reveal_note(&alice, &game, &rock)?,
+        reveal_note(&bob, &game, &switched)?,
+    ];
+    let mut chain = mock_chain(&[&game], &notes.iter().collect::<Vec<_>>())?;
+
+    for note in &notes[..3] {
+        consume(&mut chain, game.id(), note).await?;
+    }
+    assert_fails_with(
+        consume(&mut chain, game.id(), &notes[3]).await,
+        "Hand does not match the commitment",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn a_player_cannot_commit_twice() -> Result<()> {
+    let (alice, bob) = (wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let first = commit_note(&alice, &game, &hidden(Hand::Rock))?;
+    let second = commit_note(&alice, &game, &hidden(Hand::Paper))?;
+    let mut chain = mock_chain(&[&game], &[&first, &second])?;
+
+    consume(&mut chain, game.id(), &first).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &second).await,
+        "Player already committed a hand",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_the_players_can_commit() -> Result<()> {
+    let (alice, bob, carol) = (wallet(vec![])?, wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let commit = commit_note(&carol, &game, &hidden(Hand::Rock))?;
+    let mut chain = mock_chain(&[&game], &[&commit])?;
+
+    assert_fails_with(
+        consume(&mut chain, game.id(), &commit).await,
+        "Sender does not play this game",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn hand_outside_rock_paper_scissors_is_rejected() -> Result<()> {
+    let (alice, bob) = (wallet(vec![])?, wallet(vec![])?);
+    let game = game_contract(&alice, &bob)?;
+    let rock = hidden(Hand::Rock);
+    // Bob commits to a hand numbered 3, which the game only sees once revealed
+    let salt = random_word();
+    let invalid: Word = [salt[0], salt[1], salt[2], Felt::new(3)].into();
+    let notes = [
+        commit_note(&alice, &game, &rock)?,
+        game_note(
+            &bob,
+            &game,
+            "notes/hand_commit_note.masm",
+            Hasher::hash_elements(invalid.as_elements()),
+        )?,
+        game_note(&bob, &game, "notes/hand_reveal_note.masm", invalid)?,
+    ];
+    let mut chain = mock_chain(&[&game], &notes.iter().collect::<Vec<_>>())?;
+
+    for note in &notes[..2] {
+        consume(&mut chain, game.id(), note).await?;
+    }
+    assert_fails_with(
+        consume(&mut chain, game.id(), &notes[2]).await,
+        "Hand must be rock (0), paper (1) or scissors (2)",
+    );
+
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageSlot},
+    asset::{FungibleAsset, NonFungibleAsset, NonFungibleAssetDetails},
+    note::{Note, NoteTag},
+    testing::account_id::{
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_NON_FUNGIBLE_FAUCET,
+    },
+    Felt, Word, ZERO,
+};
+
+use crate::common::{
+    assert_fails_with, consume_with_foreign_accounts, contract, create_library, masm, mock_chain,
+    note, note_script, sender, wallet,
+};
+
+/// Royalty the collection under test charges on every sale, in percent.
+const ROYALTY_PERCENT: u64 = 10;
+/// Price of the NFT in the sale notes, unless a test is about the price.
+const PRICE: u64 = 200;
+
+fn payment_faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+/// Builds a collection paying [`ROYALTY_PERCENT`] of every sale to `creator`.
+fn collection_contract(creator: AccountId) -> Result<Account> {
+    let royalty_config: Word = [
+        Felt::new(ROYALTY_PERCENT),
+        ZERO,
+        creator.suffix(),
+        creator.prefix().as_felt(),
+    ]
+    .into();
+    contract(
+        &masm("accounts/nft_collection.masm"),
+        AccountType::NonFungibleFaucet,
+        vec![StorageSlot::Value(royalty_config)],
+    )
+}
+
+fn nft(collection: AccountId) -> Result<NonFungibleAsset> {
+    let details = NonFungibleAssetDetails::new(collection.prefix(), b"Royalty NFT".to_vec())?;
+    Ok(NonFungibleAsset::new(&details)?)
+}
+
+/// Builds the note by which the test sender sells `nft` of `collection` for `price`, with the
+/// royalty terms the seller claims.
+fn sale_note(
+    collection: AccountId,
+    nft: NonFungibleAsset,
+    price: u64,
+    royalty_percent: u64,
+    creator: AccountId,
+) -> Result<Note> {
+    let library = create_library(
+        masm("accounts/nft_collection.masm"),
+        "external_contract::nft_collection",
+    )?;
+    let script = note_script(&masm("notes/royalty_sale_note.masm"), Some(&library))?;
+
+    let mut inputs = WellKnownNote::P2ID.script_root().to_vec();
+    inputs.extend([
+        Felt::new(price),
+        Felt::new(royalty_percent),
+        payment_faucet().suffix(),
+        payment_faucet().prefix().as_felt(),
+        sender().suffix(),
+        sender().prefix().as_felt(),
+        NoteTag::from_account_id(sender()).into(),
+        creator.suffix(),
+        creator.prefix().as_felt(),
+        NoteTag::from_account_id(creator).into(),
+        collection.suffix(),
+        collection.prefix().as_felt(),
+    ]);
+    note(collection, script, inputs, vec![nft.into()])
+}
+
+/// Builds a buyer holding enough payment tokens for any sale.
+fn buyer() -> Result<Account> {
+    wallet(vec![FungibleAsset::new(payment_faucet(), PRICE)?.into()])
+}
+
+#[tokio::test]
+async fn seller_cannot_lower_the_royalty() -> Result<()> {
+    let creator = wallet(vec![])?;
+    let collection = collection_contract(creator.id())?;
+    let buyer = buyer()?;
+    let sale = sale_note(
+        collection.id(),
+        nft(collection.id())?,
+        PRICE,
+        0,
+        creator.id(),
+    )?;
+    let mut chain = mock_chain(&[&collection, &buyer], &[&sale])?;
+
+    assert_fails_with(
+        consume_with_foreign_accounts(&mut chain, buyer.id(), &sale, &[collection.id()]).await,
+        "Royalty percent of the sale note does not match its collection",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn seller_cannot_name_themselves_the_creator() -> Result<()> {
+    let creator = wallet(vec![])?;
+    let collection = collection_contract(creator.id())?;
+    let buyer = buyer()?;
+    let sale = sale_note(
+        collection.id(),
+        nft(collection.id())?,
+        PRICE,
+        ROYALTY_PERCENT,
+        sender(),
+    )?;
+    let mut chain = mock_chain(&[&collection, &buyer], &[&sale])?;
+
+    assert_fails_with(
+        consume_with_foreign_accounts(&mut chain, buyer.id(), &sale, &[collection.id()]).await,
+        "Creator of the sale note does not match its collection",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn nft_must_come_from_the_collection_of_the_note() -> Result<()> {
+    let creator = wallet(vec![])?;
+    let collection = collection_contract(creator.id())?;
+    let buyer = buyer()?;
+    // An NFT of another collection, sold under the royalty terms of `collection`
+    let other_collection = AccountId::try_from(ACCOUNT_ID_PUBLIC_NON_FUNGIBLE_FAUCET)?;
+    let sale = sale_note(
+        collection.id(),
+        nft(other_collection)?,
+        PRICE,
+        ROYALTY_PERCENT,
+        creator.id(),
+    )?;
+    let mut chain = mock_chain(&[&collection, &buyer], &[&sale])?;
+
+    assert_fails_with(
+        consume_with_foreign_accounts(&mut chain, buyer.id(), &sale, &[collection.id()]).await,
+        "Royalty sale note must sell an NFT of its collection",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn royalty_of_an_oversized_price_is_rejected() -> Result<()> {
+    let creator = wallet(vec![])?;
+    let collection = collection_contract(creator.id())?;
+    let buyer = buyer()?;
+
+    // A price beyond a u32, and one whose royalty product overflows a u32 without wrapping
+    // around the field
+    for price in [
+        u64::from(u32::MAX) + 1,
+        u64::from(u32::MAX) / ROYALTY_PERCENT + 1,
+    ] {
+        let sale = sale_note(
+            collection.id(),
+            nft(collection.id())?,
+            price,
+            ROYALTY_PERCENT,
+            creator.id(),
+        )?;
+        let mut chain = mock_chain(&[&collection, &buyer], &[&sale])?;
+
+        assert_fails_with(
+            consume_with_foreign_accounts(&mut chain, buyer.id(), &sale, &[collection.id()]).await,
+            "price * royalty_percent must fit into a u32",
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountType, StorageMap, StorageSlot},
+    assembly::Library,
+    note::Note,
+    Felt, Word,
+};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note, note_script,
+    run_script, tx_script,
+};
+
+fn scheduler_contract() -> Result<(Account, Library)> {
+    let code = masm("accounts/scheduler.masm");
+    let account = contract(
+        &code,
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?;
+    let library = create_library(code, "external_contract::scheduler")?;
+    Ok((account, library))
+}
+
+fn scheduled_note(
+    scheduler: &Account,
+    library: &Library,
+    execute_after: u32,
+    schedule_id: u64,
+) -> Result<Note> {
+    let script = note_script(&masm("notes/scheduled_note.masm"), Some(library))?;
+    let inputs = vec![
+        Felt::from(execute_after),
+        Felt::new(schedule_id),
+        Felt::new(100),
+    ];
+
+    note(scheduler.id(), script, inputs, vec![])
+}
+
+#[tokio::test]
+async fn scheduled_note_is_rejected_before_its_block() -> Result<()> {
+    let (scheduler, library) = scheduler_contract()?;
+    let early = scheduled_note(&scheduler, &library, 1_000, 1)?;
+    let mut chain = mock_chain(&[&scheduler], &[&early])?;
+
+    assert_fails_with(
+        consume(&mut chain, scheduler.id(), &early).await,
+        "Scheduled note cannot be executed before its block",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn schedules_are_rejected_out_of_order() -> Result<()> {
+    let (scheduler, library) = scheduler_contract()?;
+    let second = scheduled_note(&scheduler, &library, 0, 2)?;
+    let mut chain = mock_chain(&[&scheduler], &[&second])?;
+
+    assert_fails_with(
+        consume(&mut chain, scheduler.id(), &second).await,
+        "Schedules must be executed in order",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn scheduled_note_cannot_be_replayed() -> Result<()> {
+    let (scheduler, library) = scheduler_contract()?;
+    let first = scheduled_note(&scheduler, &library, 0, 1)?;
+    let mut chain = mock_chain(&[&scheduler], &[&first])?;
+
+    consume(&mut chain, scheduler.id(), &first).await?;
+    assert!(consume(&mut chain, scheduler.id(), &first).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn scheduler_cannot_be_initialized_twice() -> Result<()> {
+    let (scheduler, library) = scheduler_contract()?;
+    let mut chain = mock_chain(&[&scheduler], &[])?;
+    let init_code = masm("scripts/scheduler_init_script.masm");
+
+    run_script(
+        &mut chain,
+        scheduler.id(),
+        tx_script(&init_code, Some(&library))?,
+    )
+    .await?;
+    assert_fails_with(
+        run_script(
+            &mut chain,
+            scheduler.id(),
+            tx_script(&init_code, Some(&library))?,
+        )
+        .await,
+        "Scheduler is already initialized",
+    );
+
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use anyhow::Result;
+use miden_lib::{account::wallets::BasicWallet, transaction::TransactionKernel};
+use miden_objects::{
+    account::{
+        Account, AccountBuilder, AccountComponent, AccountStorageMode, AccountType, StorageMap,
+        StorageSlot,
+    },
+    assembly::Library,
+    transaction::TransactionScript,
+    Felt, Word, ONE, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::helpers::word_to_masm;
+
+use crate::common::{assert_fails_with, create_library, masm, mock_chain, random_word, tx_script};
+
+/// Number of guardian approvals needed to replace the owner key. Above the number of
+/// approvals the tests give, so the owner key is never replaced.
+const RECOVERY_THRESHOLD: u64 = 3;
+
+/// Keys of a test smart wallet. They are only public key commitments: every failure under
+/// test is raised before the signature is checked, so the tests need no key pairs.
+struct Keys {
+    owner: Word,
+    guardians: [Word; 2],
+    session: Word,
+}
+
+impl Keys {
+    fn new() -> Self {
+        Self {
+            owner: random_word(),
+            guardians: [random_word(), random_word()],
+            session: random_word(),
+        }
+    }
+}
+
+/// Builds a smart wallet of `keys` whose session key is valid until block `expires_at`.
+fn smart_wallet(keys: &Keys, expires_at: u64) -> Result<(Account, Library)> {
+    let code = masm("accounts/smart_wallet.masm");
+    let guardians = StorageMap::with_entries(
+        keys.guardians
+            .iter()
+            .map(|key| (*key, Word::from([ZERO, ZERO, ZERO, ONE]))),
+    )?;
+    let session_keys = StorageMap::with_entries([(
+        keys.session,
+        Word::from([ZERO, ZERO, ZERO, Felt::new(expires_at)]),
+    )])?;
+    let component = AccountComponent::compile(
+        &code,
+        TransactionKernel::assembler(),
+        vec![
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(keys.owner),
+            StorageSlot::Map(guardians),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::new(RECOVERY_THRESHOLD)].into()),
+            StorageSlot::Map(session_keys),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?
+    .with_supports_all_types();
+
+    // The smart wallet component is the auth component of the account
+    let account = AccountBuilder::new(rand::random())
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(component)
+        .with_component(BasicWallet)
+        .build_existing()?;
+    let library = create_library(code, "external_contract::smart_wallet")?;
+    Ok((account, library))
+}
+
+/// Transaction script approving the recovery to `new_owner` once per key of `guardians`.
+fn approve_script(
+    library: &Library,
+    guardians: &[Word],
+    new_owner: Word,
+) -> Result<TransactionScript> {
+    let calls: String = guardians
+        .iter()
+        .map(|guardian| {
+            format!(
+                "    push.{}\n    push.{}\n    call.smart_wallet::approve_recovery\n",
+                word_to_masm(new_owner),
+                word_to_masm(*guardian)
+            )
+        })
+        .collect();
+    tx_script(
+        &format!("use.external_contract::smart_wallet\nbegin\n{calls}end\n"),
+        Some(library),
+    )
+}
+
+/// Transaction script adding `session_key` until block `expires_at`.
+fn add_session_key_script(
+    library: &Library,
+    session_key: Word,
+    expires_at: u64,
+) -> Result<TransactionScript> {
+    tx_script(
+        &format!(
+            "use.external_contract::smart_wallet\n\
+             begin\n    push.{expires_at}\n    push.{}\n    call.smart_wallet::add_session_key\nend\n",
+            word_to_masm(session_key)
+        ),
+        Some(library),
+    )
+}
+
+/// Executes `script` against `wallet`, naming `signer` as the signing key in the auth
+/// arguments, without including the transaction in a block.
+async fn execute(
+    chain: &MockChain,
+    wallet: &Account,
+    script: TransactionScript,
+    signer: Word,
+) -> Result<()> {
+    chain
+        .build_tx_context(wallet.id(), &[], &[])?
+        .tx_script(script)
+        .auth_args(signer)
+        .build()?
+        .execute()
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn recovery_cannot_be_approved_by_an_outsider() -> Result<()> {
+    let keys = Keys::new();
+    let (wallet, library) = smart_wallet(&keys, 1_000)?;
+    let chain = mock_chain(&[&wallet], &[])?;
+
+    let outsider = random_word();
+    let script = approve_script(&library, &[outsider], random_word())?;
+    assert_fails_with(
+        execute(&chain, &wallet, script, outsider).await,
+        "Approver is not a guardian of this wallet",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn guardian_cannot_approve_a_recovery_twice() -> Result<()> {
+    let keys = Keys::new();
+    let (wallet, library) = smart_wallet(&keys, 1_000)?;
+    let chain = mock_chain(&[&wallet], &[])?;
+
+    // Both approvals run in the same transaction, so the second one sees the first
+    let guardian = keys.guardians[0];
+    let script = approve_script(&library, &[guardian, guardian], random_word())?;
+    assert_fails_with(
+        execute(&chain, &wallet, script, guardian).await,
+        "Guardian already approved this recovery",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn recovery_approval_must_be_signed_by_the_guardian() -> Result<()> {
+    let keys = Keys::new();
+    let (wallet, library) = smart_wallet(&keys, 1_000)?;
+    let chain = mock_chain(&[&wallet], &[])?;
+
+    // Neither the other guardian nor the owner can sign the approval of a guardian
+    for signer in [keys.guardians[1], keys.owner] {
+        let script = approve_script(&library, &[keys.guardians[0]], random_word())?;
+        assert_fails_with(
+            execute(&chain, &wallet, script, signer).await,
+            "Recovery approvals must be signed by the approving guardian",
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn expired_session_key_is_rejected() -> Result<()> {
+    let keys = Keys::new();
+    let (wallet, library) = smart_wallet(&keys, 1)?;
+    let mut chain = mock_chain(&[&wallet], &[])?;
+    chain.prove_until_block(5)?;
+
+    let script = add_session_key_script(&library, random_word(), 100)?;
+    assert_fails_with(
+        execute(&chain, &wallet, script, keys.session).await,
+        "Session key has expired",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn session_key_cannot_add_session_keys() -> Result<()> {
+    let keys = Keys::new();
+    let (wallet, library) = smart_wallet(&keys, 1_000)?;
+    let chain = mock_chain(&[&wallet], &[])?;
+
+    let script = add_session_key_script(&library, random_word(), 100)?;
+    assert_fails_with(
+        execute(&chain, &wallet, script, keys.session).await,
+        "Session keys cannot manage the wallet",
+    );
+
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountType, StorageMap, StorageSlot},
+    transaction::TransactionScript,
+    Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::migration;
+
+use crate::common::{
+    assert_fails_with, contract, create_library, masm, mock_chain, run_script, tx_script, wallet,
+};
+
+const V1: &str = "accounts/loyalty_v1.masm";
+const V2: &str = "accounts/loyalty_v2.masm";
+
+/// Returns the initial storage of the first version, with the slot of the second version
+/// reserved if `reserve` is set.
+fn v1_storage(reserve: bool) -> Vec<StorageSlot> {
+    let mut slots = vec![
+        StorageSlot::Map(StorageMap::new()),
+        StorageSlot::Value(Word::default()),
+    ];
+    if reserve {
+        slots.push(StorageSlot::Value(Word::default()));
+    }
+    slots
+}
+
+fn v2_storage() -> Vec<StorageSlot> {
+    vec![
+        StorageSlot::Map(StorageMap::new()),
+        StorageSlot::Value(Word::default()),
+        StorageSlot::Value(Word::default()),
+    ]
+}
+
+/// Builds a script calling `procedure` of the contract version at `version` for `points` of
+/// `member`.
+fn loyalty_script(
+    version: &str,
+    procedure: &str,
+    member: &Account,
+    points: u64,
+) -> Result<TransactionScript> {
+    let library = create_library(masm(version), "external_contract::loyalty")?;
+    let code = format!(
+        "use.external_contract::loyalty\n\
+         begin\n    push.{}\n    push.{}\n    push.{points}\n    call.loyalty::{procedure}\nend\n",
+        member.id().suffix(),
+        member.id().prefix().as_felt(),
+    );
+    tx_script(&code, Some(&library))
+}
+
+fn points_of(chain: &MockChain, contract: &Account, member: &Account) -> Result<u64> {
+    let key: Word = [
+        ZERO,
+        ZERO,
+        member.id().suffix(),
+        member.id().prefix().as_felt(),
+    ]
+    .into();
+    Ok(chain
+        .committed_account(contract.id())?
+        .storage()
+        .get_map_item(0, key)?[3]
+        .as_int())
+}
+
+/// Deploys the first version with `storage` and awards 120 points to `member`, returning the
+/// storage of the contract afterwards.
+async fn award_on_v1(storage: Vec<StorageSlot>, member: &Account) -> Result<Vec<StorageSlot>> {
+    let legacy = contract(&masm(V1), AccountType::RegularAccountImmutableCode, storage)?;
+    let mut chain = mock_chain(&[&legacy], &[])?;
+
+    run_script(
+        &mut chain,
+        legacy.id(),
+        loyalty_script(V1, "award", member, 120)?,
+    )
+    .await?;
+    Ok(chain
+        .committed_account(legacy.id())?
+        .storage()
+        .slots()
+        .to_vec())
+}
+
+#[tokio::test]
+async fn version_2_cannot_run_on_the_layout_of_version_1() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let slots = award_on_v1(v1_storage(false), &alice).await?;
+
+    let mismatched = contract(&masm(V2), AccountType::RegularAccountImmutableCode, slots)?;
+    let mut chain = mock_chain(&[&mismatched], &[])?;
+
+    assert_fails_with(
+        run_script(
+            &mut chain,
+            mismatched.id(),
+            loyalty_script(V2, "redeem", &alice, 50)?,
+        )
+        .await,
+        "out of bounds",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrated_storage_keeps_the_points_and_runs_version_2() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let slots = award_on_v1(v1_storage(false), &alice).await?;
+
+    let migrated =
+        migration::migrate_storage(&slots, v2_storage()).map_err(|err| anyhow::anyhow!("{err}"))?;
+    let upgraded = contract(
+        &masm(V2),
+        AccountType::RegularAccountImmutableCode,
+        migrated,
+    )?;
+    let mut chain = mock_chain(&[&upgraded], &[])?;
+    assert_eq!(points_of(&chain, &upgraded, &alice)?, 120);
+
+    run_script(
+        &mut chain,
+        upgraded.id(),
+        loyalty_script(V2, "redeem", &alice, 50)?,
+    )
+    .await?;
+    assert_eq!(points_of(&chain, &upgraded, &alice)?, 70);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reserved_slot_lets_version_2_run_on_the_old_storage() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let slots = award_on_v1(v1_storage(true), &alice).await?;
+
+    // The storage is used as it is, without going through the migration
+    let upgraded = contract(&masm(V2), AccountType::RegularAccountImmutableCode, slots)?;
+    let mut chain = mock_chain(&[&upgraded], &[])?;
+
+    run_script(
+        &mut chain,
+        upgraded.id(),
+        loyalty_script(V2, "redeem", &alice, 120)?,
+    )
+    .await?;
+    assert_eq!(points_of(&chain, &upgraded, &alice)?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn redeeming_more_points_than_held_fails() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let slots = award_on_v1(v1_storage(true), &alice).await?;
+    let upgraded = contract(&masm(V2), AccountType::RegularAccountImmutableCode, slots)?;
+    let mut chain = mock_chain(&[&upgraded], &[])?;
+
+    assert_fails_with(
+        run_script(
+            &mut chain,
+            upgraded.id(),
+            loyalty_script(V2, "redeem", &alice, 121)?,
+        )
+        .await,
+        "Member does not have enough points",
+    );
+    assert_fails_with(
+        run_script(
+            &mut chain,
+            upgraded.id(),
+            loyalty_script(V2, "redeem", &bob, 1)?,
+        )
+        .await,
+        "Member does not have enough points",
+    );
+
+    Ok(())
+}
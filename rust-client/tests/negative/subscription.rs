@@ -0,0 +1,118 @@
+//! Pull-based subscriptions: the merchant pulls each renewal from the start of its period, the
+//! subscriber takes back the renewals the merchant let lapse.
+
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountId},
+    asset::FungibleAsset,
+    block::BlockNumber,
+    crypto::rand::RpoRandomCoin,
+    testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+};
+use miden_testing::MockChain;
+use rust_client::subscription::Subscription;
+
+use crate::common::{consume, mock_chain, random_word, wallet};
+
+/// Tokens paid every period.
+const PRICE: u64 = 25;
+/// Blocks between two renewals.
+const PERIOD: u32 = 5;
+/// Block at which the first period starts.
+const START: u32 = 2;
+
+fn faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+/// Builds a subscription of `merchant` to `subscriber` for three periods, and a mock chain
+/// holding its renewals.
+fn subscribed(subscriber: &Account, merchant: &Account) -> Result<(Subscription, MockChain)> {
+    let subscription = Subscription::new(
+        subscriber.id(),
+        merchant.id(),
+        FungibleAsset::new(faucet(), PRICE)?,
+        PERIOD,
+        BlockNumber::from(START),
+        3,
+        &mut RpoRandomCoin::new(random_word()),
+    )?;
+    let notes: Vec<_> = subscription
+        .renewals
+        .iter()
+        .map(|renewal| &renewal.note)
+        .collect();
+    let chain = mock_chain(&[subscriber, merchant], &notes)?;
+    Ok((subscription, chain))
+}
+
+fn balance(chain: &MockChain, account: &Account) -> Result<u64> {
+    Ok(chain
+        .committed_account(account.id())?
+        .vault()
+        .get_balance(faucet())?)
+}
+
+#[tokio::test]
+async fn merchant_pulls_each_renewal_once_due() -> Result<()> {
+    let subscriber = wallet(vec![])?;
+    let merchant = wallet(vec![])?;
+    let (subscription, mut chain) = subscribed(&subscriber, &merchant)?;
+
+    for renewal in &subscription.renewals[..2] {
+        chain.prove_until_block(renewal.due.as_u32())?;
+        consume(&mut chain, merchant.id(), &renewal.note).await?;
+    }
+
+    assert_eq!(balance(&chain, &merchant)?, 2 * PRICE);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn merchant_cannot_pull_a_renewal_before_its_period() -> Result<()> {
+    let subscriber = wallet(vec![])?;
+    let merchant = wallet(vec![])?;
+    let (subscription, mut chain) = subscribed(&subscriber, &merchant)?;
+
+    chain.prove_until_block(subscription.renewals[0].due.as_u32())?;
+    consume(&mut chain, merchant.id(), &subscription.renewals[0].note).await?;
+    assert!(
+        consume(&mut chain, merchant.id(), &subscription.renewals[1].note)
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn subscriber_cannot_take_back_a_renewal_during_its_period() -> Result<()> {
+    let subscriber = wallet(vec![])?;
+    let merchant = wallet(vec![])?;
+    let (subscription, mut chain) = subscribed(&subscriber, &merchant)?;
+
+    chain.prove_until_block(subscription.renewals[0].due.as_u32())?;
+    assert!(
+        consume(&mut chain, subscriber.id(), &subscription.renewals[0].note)
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn subscriber_takes_back_a_lapsed_renewal() -> Result<()> {
+    let subscriber = wallet(vec![])?;
+    let merchant = wallet(vec![])?;
+    let (subscription, mut chain) = subscribed(&subscriber, &merchant)?;
+
+    chain.prove_until_block(subscription.renewals[0].lapses.as_u32())?;
+    consume(&mut chain, subscriber.id(), &subscription.renewals[0].note).await?;
+
+    assert_eq!(balance(&chain, &subscriber)?, PRICE);
+    assert_eq!(balance(&chain, &merchant)?, 0);
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::AccountId,
+    asset::{Asset, FungibleAsset},
+    note::{Note, NoteTag},
+    testing::account_id::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1},
+    Felt,
+};
+
+use crate::common::{
+    assert_fails_with, consume, masm, mock_chain, note, note_script, sender, wallet,
+};
+
+fn eth_faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn usd_faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1).unwrap()
+}
+
+/// Returns the inputs of an offer of the test sender requesting `requested`.
+fn offer_inputs(requested: FungibleAsset) -> Vec<Felt> {
+    let mut inputs = WellKnownNote::P2ID.script_root().to_vec();
+    inputs.extend([
+        Felt::new(requested.amount()),
+        requested.faucet_id().suffix(),
+        requested.faucet_id().prefix().as_felt(),
+        NoteTag::from_account_id(sender()).into(),
+    ]);
+    inputs
+}
+
+/// Builds an offer of the test sender for `taker`.
+fn offer_note(taker: AccountId, inputs: Vec<Felt>, assets: Vec<Asset>) -> Result<Note> {
+    let script = note_script(&masm("notes/swap_offer_note.masm"), None)?;
+    note(taker, script, inputs, assets)
+}
+
+#[tokio::test]
+async fn offer_without_asset_is_rejected() -> Result<()> {
+    let taker = wallet(vec![FungibleAsset::new(usd_faucet(), 1_010)?.into()])?;
+    let offer = offer_note(
+        taker.id(),
+        offer_inputs(FungibleAsset::new(usd_faucet(), 1_010)?),
+        vec![],
+    )?;
+    let mut chain = mock_chain(&[&taker], &[&offer])?;
+
+    assert_fails_with(
+        consume(&mut chain, taker.id(), &offer).await,
+        "Swap offer must carry exactly one asset",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn offer_with_missing_inputs_is_rejected() -> Result<()> {
+    let taker = wallet(vec![FungibleAsset::new(usd_faucet(), 1_010)?.into()])?;
+    let mut inputs = offer_inputs(FungibleAsset::new(usd_faucet(), 1_010)?);
+    inputs.pop();
+    let offer = offer_note(
+        taker.id(),
+        inputs,
+        vec![FungibleAsset::new(eth_faucet(), 10)?.into()],
+    )?;
+    let mut chain = mock_chain(&[&taker], &[&offer])?;
+
+    assert_fails_with(
+        consume(&mut chain, taker.id(), &offer).await,
+        "Swap offer expects exactly 8 inputs",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn offer_cannot_be_taken_without_paying_in_full() -> Result<()> {
+    // The taker holds less than the requested 1_010 USD, so the payment cannot be funded
+    let taker = wallet(vec![FungibleAsset::new(usd_faucet(), 1_000)?.into()])?;
+    let offer = offer_note(
+        taker.id(),
+        offer_inputs(FungibleAsset::new(usd_faucet(), 1_010)?),
+        vec![FungibleAsset::new(eth_faucet(), 10)?.into()],
+    )?;
+    let mut chain = mock_chain(&[&taker], &[&offer])?;
+
+    consume(&mut chain, taker.id(), &offer)
+        .await
+        .expect_err("an offer must not be filled without the requested asset");
+
+    Ok(())
+}
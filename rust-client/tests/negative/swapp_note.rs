@@ -0,0 +1,109 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::AccountId,
+    asset::{Asset, FungibleAsset},
+    note::{Note, NoteTag},
+    testing::account_id::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1},
+    Felt, Word, ZERO,
+};
+
+use crate::common::{
+    assert_fails_with, consume_with_args, masm, mock_chain, note, note_script, sender, wallet,
+};
+
+fn faucet_a() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn faucet_b() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1).unwrap()
+}
+
+/// Returns the inputs of a swap of the test sender requesting 200 B.
+fn swapp_inputs() -> Vec<Felt> {
+    let maker_tag = NoteTag::from_account_id(sender());
+    let mut inputs = WellKnownNote::P2ID.script_root().to_vec();
+    inputs.extend([
+        Felt::new(200),
+        faucet_b().suffix(),
+        faucet_b().prefix().as_felt(),
+        maker_tag.into(),
+        sender().suffix(),
+        sender().prefix().as_felt(),
+        maker_tag.into(),
+    ]);
+    inputs
+}
+
+/// Builds a swap of the test sender for `taker`.
+fn swapp_note(taker: AccountId, inputs: Vec<Felt>, assets: Vec<Asset>) -> Result<Note> {
+    let script = note_script(&masm("notes/swapp_note.masm"), None)?;
+    note(taker, script, inputs, assets)
+}
+
+fn offered() -> Result<Vec<Asset>> {
+    Ok(vec![FungibleAsset::new(faucet_a(), 100)?.into()])
+}
+
+fn fill(amount: u64) -> Word {
+    [ZERO, ZERO, ZERO, Felt::new(amount)].into()
+}
+
+#[tokio::test]
+async fn swap_with_missing_inputs_is_rejected() -> Result<()> {
+    let taker = wallet(vec![FungibleAsset::new(faucet_b(), 200)?.into()])?;
+    let mut inputs = swapp_inputs();
+    inputs.pop();
+    let swap = swapp_note(taker.id(), inputs, offered()?)?;
+    let chain = mock_chain(&[&taker], &[&swap])?;
+
+    assert_fails_with(
+        consume_with_args(&chain, taker.id(), &swap, fill(50)).await,
+        "Partially fillable swap expects exactly 11 inputs",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn swap_without_asset_is_rejected() -> Result<()> {
+    let taker = wallet(vec![FungibleAsset::new(faucet_b(), 200)?.into()])?;
+    let swap = swapp_note(taker.id(), swapp_inputs(), vec![])?;
+    let chain = mock_chain(&[&taker], &[&swap])?;
+
+    assert_fails_with(
+        consume_with_args(&chain, taker.id(), &swap, fill(50)).await,
+        "Partially fillable swap must carry exactly one asset",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn empty_fill_is_rejected() -> Result<()> {
+    let taker = wallet(vec![FungibleAsset::new(faucet_b(), 200)?.into()])?;
+    let swap = swapp_note(taker.id(), swapp_inputs(), offered()?)?;
+    let chain = mock_chain(&[&taker], &[&swap])?;
+
+    assert_fails_with(
+        consume_with_args(&chain, taker.id(), &swap, fill(0)).await,
+        "Fill must be between 1 and the requested amount",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn fill_above_the_requested_amount_is_rejected() -> Result<()> {
+    let taker = wallet(vec![FungibleAsset::new(faucet_b(), 300)?.into()])?;
+    let swap = swapp_note(taker.id(), swapp_inputs(), offered()?)?;
+    let chain = mock_chain(&[&taker], &[&swap])?;
+
+    assert_fails_with(
+        consume_with_args(&chain, taker.id(), &swap, fill(201)).await,
+        "Fill must be between 1 and the requested amount",
+    );
+
+    Ok(())
+}
@@ -0,0 +1,569 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    assembly::Library,
+    asset::{Asset, FungibleAsset},
+    note::Note,
+    testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+    Felt, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::tic_tac_toe::{self, GameState, Mark, LIBRARY_PATH};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note_script,
+    random_word, run_script, sender, tx_script, wallet,
+};
+
+/// Number of blocks each player has to move.
+const TURN_TIMEOUT: u32 = 5;
+
+/// Tokens every move of a game taking deposits must carry.
+const MOVE_DEPOSIT: u64 = 3;
+
+/// Builds a game account whose winner slot holds `winner`, before the constructor ran.
+fn game_contract(winner: Felt) -> Result<(Account, Library)> {
+    game_contract_with_deposit(winner, None)
+}
+
+/// Builds a game account like [`game_contract`] whose moves must carry `move_deposit`, if any.
+fn game_contract_with_deposit(
+    winner: Felt,
+    move_deposit: Option<FungibleAsset>,
+) -> Result<(Account, Library)> {
+    let code = masm("accounts/tic_tac_toe.masm");
+    let account = contract(
+        &code,
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value([ZERO, ZERO, ZERO, winner].into()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(
+                move_deposit.map_or(Word::default(), |asset| Asset::from(asset).into()),
+            ),
+            StorageSlot::Value(Word::default()),
+        ],
+    )?;
+    let library = create_library(code, LIBRARY_PATH)?;
+    Ok((account, library))
+}
+
+/// Runs the constructor of `game` with `player1` and `player2`.
+async fn start(
+    chain: &mut MockChain,
+    game: &Account,
+    library: &Library,
+    player1: AccountId,
+    player2: AccountId,
+) -> Result<()> {
+    let script = tic_tac_toe::deployer(player1, player2, TURN_TIMEOUT)?.script_source();
+    run_script(chain, game.id(), tx_script(&script, Some(library))?).await
+}
+
+/// Builds the note by which `player` plays `field`.
+fn move_note(player: AccountId, game: &Account, library: &Library, field: usize) -> Result<Note> {
+    let script = note_script(&masm("notes/make_a_move_note.masm"), Some(library))?;
+    Ok(tic_tac_toe::move_note(
+        player,
+        game.id(),
+        field,
+        script,
+        random_word(),
+    )?)
+}
+
+/// Builds the note by which `player` claims the draw.
+fn draw_claim(player: AccountId, game: &Account, library: &Library) -> Result<Note> {
+    let script = note_script(&masm("notes/claim_draw_note.masm"), Some(library))?;
+    Ok(tic_tac_toe::draw_claim_note(
+        player,
+        game.id(),
+        script,
+        random_word(),
+    )?)
+}
+
+/// Builds the note by which `player` claims the game on timeout.
+fn timeout_claim(player: AccountId, game: &Account, library: &Library) -> Result<Note> {
+    let script = note_script(&masm("notes/claim_timeout_note.masm"), Some(library))?;
+    Ok(tic_tac_toe::timeout_claim_note(
+        player,
+        game.id(),
+        script,
+        random_word(),
+    )?)
+}
+
+/// Returns `amount` tokens of the test faucet.
+fn stake(amount: u64) -> Result<FungibleAsset> {
+    let faucet = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET)?;
+    Ok(FungibleAsset::new(faucet, amount)?)
+}
+
+/// Builds the note by which `player` stakes `amount` tokens on the game.
+fn wager(player: AccountId, game: &Account, library: &Library, amount: u64) -> Result<Note> {
+    let script = note_script(&masm("notes/place_wager_note.masm"), Some(library))?;
+    Ok(tic_tac_toe::wager_note(
+        player,
+        game.id(),
+        stake(amount)?,
+        script,
+        random_word(),
+    )?)
+}
+
+/// Builds the note by which `player` plays `field` with a deposit of `amount` tokens.
+fn deposit_move(
+    player: AccountId,
+    game: &Account,
+    library: &Library,
+    field: usize,
+    amount: u64,
+) -> Result<Note> {
+    let script = note_script(&masm("notes/deposit_move_note.masm"), Some(library))?;
+    Ok(tic_tac_toe::deposit_move_note(
+        player,
+        game.id(),
+        field,
+        stake(amount)?,
+        script,
+        random_word(),
+    )?)
+}
+
+fn read_game(chain: &MockChain, game: &Account) -> Result<GameState> {
+    GameState::from_account(chain.committed_account(game.id())?)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+/// Builds the nine moves of a game between the test sender (X) and `player2` (O) filling the
+/// board without a winner:
+///
+/// ```text
+///  X | O | X
+/// ---+---+---
+///  X | O | O
+/// ---+---+---
+///  O | X | X
+/// ```
+fn drawn_game_moves(player2: AccountId, game: &Account, library: &Library) -> Result<Vec<Note>> {
+    [0, 1, 2, 4, 3, 5, 7, 6, 8]
+        .into_iter()
+        .enumerate()
+        .map(|(turn, field)| {
+            let player = if turn % 2 == 0 { sender() } else { player2 };
+            move_note(player, game, library, field)
+        })
+        .collect()
+}
+
+/// Starts a game between the test sender and `player2`, then plays `moves`.
+async fn play(
+    chain: &mut MockChain,
+    game: &Account,
+    library: &Library,
+    player2: AccountId,
+    moves: &[Note],
+) -> Result<()> {
+    start(chain, game, library, sender(), player2).await?;
+    for note in moves {
+        consume(chain, game.id(), note).await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn constructor_cannot_run_twice() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let mut chain = mock_chain(&[&game], &[])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    assert_fails_with(
+        start(&mut chain, &game, &library, opponent.id(), sender()).await,
+        "Game is already initialized",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn player_cannot_play_against_itself() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let mut chain = mock_chain(&[&game], &[])?;
+
+    assert_fails_with(
+        start(&mut chain, &game, &library, sender(), sender()).await,
+        "A player cannot play against itself",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn second_player_cannot_move_first() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let early_move = move_note(opponent.id(), &game, &library, 4)?;
+    let mut chain = mock_chain(&[&game], &[&early_move])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &early_move).await,
+        "It is not the turn of the sender",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn outsider_cannot_move() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let outsider = wallet(vec![])?;
+    let outsider_move = move_note(outsider.id(), &game, &library, 4)?;
+    let mut chain = mock_chain(&[&game], &[&outsider_move])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &outsider_move).await,
+        "It is not the turn of the sender",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn field_cannot_be_taken_twice() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let first = move_note(sender(), &game, &library, 4)?;
+    let second = move_note(opponent.id(), &game, &library, 4)?;
+    let mut chain = mock_chain(&[&game], &[&first, &second])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    consume(&mut chain, game.id(), &first).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &second).await,
+        "Field is already taken",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn field_must_be_on_the_board() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let off_board = move_note(sender(), &game, &library, 9)?;
+    let mut chain = mock_chain(&[&game], &[&off_board])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &off_board).await,
+        "Field index must be between 0 and 8",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn no_move_after_the_game_is_won() -> Result<()> {
+    let (game, library) = game_contract(Felt::new(1))?;
+    let opponent = wallet(vec![])?;
+    let late_move = move_note(sender(), &game, &library, 0)?;
+    let mut chain = mock_chain(&[&game], &[&late_move])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &late_move).await,
+        "Game is already over",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn draw_cannot_be_claimed_before_the_board_is_full() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let early_claim = draw_claim(sender(), &game, &library)?;
+    let mut chain = mock_chain(&[&game], &[&early_claim])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &early_claim).await,
+        "A draw can only be claimed on a full board",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn outsider_cannot_claim_a_draw() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let outsider = wallet(vec![])?;
+    let moves = drawn_game_moves(opponent.id(), &game, &library)?;
+    let outsider_claim = draw_claim(outsider.id(), &game, &library)?;
+    let notes: Vec<&Note> = moves.iter().chain([&outsider_claim]).collect();
+    let mut chain = mock_chain(&[&game], &notes)?;
+
+    play(&mut chain, &game, &library, opponent.id(), &moves).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &outsider_claim).await,
+        "Sender does not play this game",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn full_board_is_recorded_as_a_draw_once() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let moves = drawn_game_moves(opponent.id(), &game, &library)?;
+    let claim = draw_claim(opponent.id(), &game, &library)?;
+    let second_claim = draw_claim(sender(), &game, &library)?;
+    let notes: Vec<&Note> = moves.iter().chain([&claim, &second_claim]).collect();
+    let mut chain = mock_chain(&[&game], &notes)?;
+
+    play(&mut chain, &game, &library, opponent.id(), &moves).await?;
+    let state = read_game(&chain, &game)?;
+    assert!(state.can_claim_draw());
+
+    consume(&mut chain, game.id(), &claim).await?;
+    let state = read_game(&chain, &game)?;
+    assert!(state.draw);
+    assert_eq!(state.winner, None);
+    assert!(state.is_finalized());
+
+    assert_fails_with(
+        consume(&mut chain, game.id(), &second_claim).await,
+        "Game is already over",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn moves_wait_for_both_stakes() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let first_stake = wager(sender(), &game, &library, 10)?;
+    let early_move = move_note(sender(), &game, &library, 4)?;
+    let mut chain = mock_chain(&[&game], &[&first_stake, &early_move])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    consume(&mut chain, game.id(), &first_stake).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &early_move).await,
+        "Both players must stake before the first move",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn stake_must_match_the_first_stake() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let first_stake = wager(sender(), &game, &library, 10)?;
+    let smaller_stake = wager(opponent.id(), &game, &library, 5)?;
+    let mut chain = mock_chain(&[&game], &[&first_stake, &smaller_stake])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    consume(&mut chain, game.id(), &first_stake).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &smaller_stake).await,
+        "Stake must match the stake of the other player",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn player_cannot_stake_twice() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let first_stake = wager(sender(), &game, &library, 10)?;
+    let second_stake = wager(sender(), &game, &library, 10)?;
+    let mut chain = mock_chain(&[&game], &[&first_stake, &second_stake])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    consume(&mut chain, game.id(), &first_stake).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &second_stake).await,
+        "Player already staked on this game",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn outsider_cannot_stake() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let outsider = wallet(vec![])?;
+    let outsider_stake = wager(outsider.id(), &game, &library, 10)?;
+    let mut chain = mock_chain(&[&game], &[&outsider_stake])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &outsider_stake).await,
+        "Sender does not play this game",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn no_stake_after_the_first_move() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let first_move = move_note(sender(), &game, &library, 4)?;
+    let late_stake = wager(opponent.id(), &game, &library, 10)?;
+    let mut chain = mock_chain(&[&game], &[&first_move, &late_stake])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    consume(&mut chain, game.id(), &first_move).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &late_stake).await,
+        "Stakes can only be placed before the first move",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn waiting_player_wins_once_the_opponent_times_out() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let first_move = move_note(sender(), &game, &library, 4)?;
+    let early_claim = timeout_claim(sender(), &game, &library)?;
+    let mover_claim = timeout_claim(opponent.id(), &game, &library)?;
+    let claim = timeout_claim(sender(), &game, &library)?;
+    let late_move = move_note(opponent.id(), &game, &library, 0)?;
+    let notes = [&first_move, &early_claim, &mover_claim, &claim, &late_move];
+    let mut chain = mock_chain(&[&game], &notes)?;
+
+    play(&mut chain, &game, &library, opponent.id(), &[first_move]).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &early_claim).await,
+        "The player to move still has time",
+    );
+
+    for _ in 0..TURN_TIMEOUT {
+        chain.prove_next_block()?;
+    }
+    // The opponent is to move, only the test sender waits for a move
+    assert_fails_with(
+        consume(&mut chain, game.id(), &mover_claim).await,
+        "Only the player waiting for a move can claim a timeout",
+    );
+
+    let state = read_game(&chain, &game)?;
+    let block_num = chain.latest_block_header().block_num().as_u32();
+    assert!(state.can_claim_timeout(sender(), block_num.into()));
+
+    consume(&mut chain, game.id(), &claim).await?;
+    let state = read_game(&chain, &game)?;
+    assert_eq!(state.winner, Some(Mark::X));
+
+    assert_fails_with(
+        consume(&mut chain, game.id(), &late_move).await,
+        "Game is already over",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn moves_of_a_deposit_game_need_a_deposit() -> Result<()> {
+    let (game, library) = game_contract_with_deposit(ZERO, Some(stake(MOVE_DEPOSIT)?))?;
+    let opponent = wallet(vec![])?;
+    let free_move = move_note(sender(), &game, &library, 4)?;
+    let mut chain = mock_chain(&[&game], &[&free_move])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &free_move).await,
+        "Moves of this game must carry a deposit",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn move_deposit_must_reach_the_minimum() -> Result<()> {
+    let (game, library) = game_contract_with_deposit(ZERO, Some(stake(MOVE_DEPOSIT)?))?;
+    let opponent = wallet(vec![])?;
+    let cheap_move = deposit_move(sender(), &game, &library, 4, MOVE_DEPOSIT - 1)?;
+    let mut chain = mock_chain(&[&game], &[&cheap_move])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &cheap_move).await,
+        "Deposit is below the required minimum",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn game_without_deposits_refuses_deposit_moves() -> Result<()> {
+    let (game, library) = game_contract(ZERO)?;
+    let opponent = wallet(vec![])?;
+    let paid_move = deposit_move(sender(), &game, &library, 4, MOVE_DEPOSIT)?;
+    let mut chain = mock_chain(&[&game], &[&paid_move])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    assert_fails_with(
+        consume(&mut chain, game.id(), &paid_move).await,
+        "Moves of this game take no deposit",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_move_costs_its_deposit() -> Result<()> {
+    let (game, library) = game_contract_with_deposit(ZERO, Some(stake(MOVE_DEPOSIT)?))?;
+    let opponent = wallet(vec![])?;
+    // Player 2 moving first breaks the rules: the note is consumed, but the move is not played
+    let early_move = deposit_move(opponent.id(), &game, &library, 4, MOVE_DEPOSIT)?;
+    let valid_move = deposit_move(sender(), &game, &library, 4, MOVE_DEPOSIT)?;
+    let mut chain = mock_chain(&[&game], &[&early_move, &valid_move])?;
+
+    start(&mut chain, &game, &library, sender(), opponent.id()).await?;
+    consume(&mut chain, game.id(), &early_move).await?;
+    let state = read_game(&chain, &game)?;
+    assert_eq!((state.moves, state.kept_deposits), (0, MOVE_DEPOSIT));
+
+    // A valid move is played and its deposit leaves the game again with the refund
+    consume(&mut chain, game.id(), &valid_move).await?;
+    let state = read_game(&chain, &game)?;
+    assert_eq!((state.moves, state.kept_deposits), (1, MOVE_DEPOSIT));
+    assert_eq!(
+        chain
+            .committed_account(game.id())?
+            .vault()
+            .get_balance(stake(0)?.faucet_id())?,
+        MOVE_DEPOSIT
+    );
+
+    Ok(())
+}
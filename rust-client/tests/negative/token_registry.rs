@@ -0,0 +1,152 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    assembly::Library,
+    asset::TokenSymbol,
+    note::Note,
+    testing::account_id::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1},
+    Felt, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::token_registry::{self, icon_hash, TokenMetadata, TokenRegistry, LIBRARY_PATH};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note, note_script,
+    random_word,
+};
+
+fn faucet(id: u128) -> AccountId {
+    AccountId::try_from(id).unwrap()
+}
+
+fn metadata(symbol: &str, decimals: u8, icon: &[u8]) -> TokenMetadata {
+    TokenMetadata {
+        symbol: symbol.to_string(),
+        decimals,
+        icon_hash: icon_hash(icon),
+    }
+}
+
+fn registry_contract() -> Result<(Account, Library)> {
+    let code = masm("accounts/token_registry.masm");
+    let registry = contract(
+        &code,
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+        ],
+    )?;
+    Ok((registry, create_library(code, LIBRARY_PATH)?))
+}
+
+/// Builds the note of `faucet_id` registering `metadata`.
+fn register_note(
+    faucet_id: AccountId,
+    registry: &Account,
+    library: &Library,
+    metadata: &TokenMetadata,
+) -> Result<Note> {
+    let script = note_script(&masm("notes/register_token_note.masm"), Some(library))?;
+    token_registry::register_note(faucet_id, registry.id(), metadata, script, random_word())
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+/// Builds a registration note with raw `symbol` and `decimals`, which
+/// [`TokenMetadata::note_inputs`] would refuse to encode.
+fn raw_register_note(
+    registry: &Account,
+    library: &Library,
+    symbol: Felt,
+    decimals: u64,
+) -> Result<Note> {
+    let script = note_script(&masm("notes/register_token_note.masm"), Some(library))?;
+    let mut inputs = icon_hash(b"icon").as_elements().to_vec();
+    inputs.extend([symbol, Felt::new(decimals)]);
+    note(registry.id(), script, inputs, vec![])
+}
+
+fn read_registry(chain: &MockChain, registry: &Account) -> Result<TokenRegistry> {
+    TokenRegistry::from_account(&chain.committed_account(registry.id())?)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+#[tokio::test]
+async fn each_faucet_registers_its_own_token() -> Result<()> {
+    let (registry, library) = registry_contract()?;
+    let (mid, gem) = (
+        faucet(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET),
+        faucet(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1),
+    );
+    let mid_metadata = metadata("MID", 8, b"mid");
+    let gem_metadata = metadata("GEM", 6, b"gem");
+    // MID later replaces its symbol and icon, keeping its decimals
+    let renamed = metadata("MIDEN", 8, b"miden");
+    let notes = [
+        register_note(mid, &registry, &library, &mid_metadata)?,
+        register_note(gem, &registry, &library, &gem_metadata)?,
+        register_note(mid, &registry, &library, &renamed)?,
+    ];
+    let mut chain = mock_chain(&[&registry], &notes.iter().collect::<Vec<_>>())?;
+
+    for note in &notes[..2] {
+        consume(&mut chain, registry.id(), note).await?;
+    }
+    let state = read_registry(&chain, &registry)?;
+    assert_eq!(state.tokens.len(), 2);
+    assert_eq!(state.get(mid), Some(&mid_metadata));
+    assert_eq!(state.get(gem), Some(&gem_metadata));
+
+    consume(&mut chain, registry.id(), &notes[2]).await?;
+    let state = read_registry(&chain, &registry)?;
+    assert_eq!(state.get(mid), Some(&renamed));
+    assert_eq!(state.get(gem), Some(&gem_metadata));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn decimals_of_a_registered_token_cannot_change() -> Result<()> {
+    let (registry, library) = registry_contract()?;
+    let mid = faucet(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET);
+    let first = register_note(mid, &registry, &library, &metadata("MID", 8, b"mid"))?;
+    let second = register_note(mid, &registry, &library, &metadata("MID", 6, b"mid"))?;
+    let mut chain = mock_chain(&[&registry], &[&first, &second])?;
+
+    consume(&mut chain, registry.id(), &first).await?;
+    assert_fails_with(
+        consume(&mut chain, registry.id(), &second).await,
+        "Decimals of a registered token cannot change",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn empty_symbol_is_rejected() -> Result<()> {
+    let (registry, library) = registry_contract()?;
+    let register = raw_register_note(&registry, &library, ZERO, 8)?;
+    let mut chain = mock_chain(&[&registry], &[&register])?;
+
+    assert_fails_with(
+        consume(&mut chain, registry.id(), &register).await,
+        "Token symbol must not be empty",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn decimals_above_the_faucet_limit_are_rejected() -> Result<()> {
+    let (registry, library) = registry_contract()?;
+    let symbol = Felt::from(TokenSymbol::new("MID")?);
+    let register = raw_register_note(&registry, &library, symbol, 13)?;
+    let mut chain = mock_chain(&[&registry], &[&register])?;
+
+    assert_fails_with(
+        consume(&mut chain, registry.id(), &register).await,
+        "Tokens have at most 12 decimals",
+    );
+
+    Ok(())
+}
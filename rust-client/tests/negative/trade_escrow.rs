@@ -0,0 +1,185 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    note::{Note, NoteTag},
+    testing::account_id::{
+        ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1,
+        ACCOUNT_ID_REGULAR_PUBLIC_ACCOUNT_UPDATABLE_CODE,
+    },
+    Felt, Word, ZERO,
+};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note, note_script,
+    sender,
+};
+
+/// Block from which the buyer can reclaim its funds, far ahead of the mock chain.
+const TIMEOUT: u64 = 1_000;
+
+fn usd_faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn eth_faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1).unwrap()
+}
+
+/// The party of the escrow which is not the test sender.
+fn counterparty() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_REGULAR_PUBLIC_ACCOUNT_UPDATABLE_CODE).unwrap()
+}
+
+fn account_word(account_id: AccountId) -> Word {
+    [
+        ZERO,
+        ZERO,
+        account_id.suffix(),
+        account_id.prefix().as_felt(),
+    ]
+    .into()
+}
+
+/// Builds a locked escrow of USD between `buyer` and `seller`.
+fn trade_escrow(buyer: AccountId, seller: AccountId) -> Result<Account> {
+    contract(
+        &masm("accounts/trade_escrow.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(account_word(buyer)),
+            StorageSlot::Value(account_word(seller)),
+            StorageSlot::Value(account_word(usd_faucet())),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::new(TIMEOUT)].into()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )
+}
+
+/// Builds a note of the test sender running the note script at `script_path` on the escrow.
+fn escrow_note(
+    escrow: &Account,
+    script_path: &str,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+) -> Result<Note> {
+    let library = create_library(
+        masm("accounts/trade_escrow.masm"),
+        "external_contract::trade_escrow",
+    )?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    note(escrow.id(), script, inputs, assets)
+}
+
+fn lock_note(escrow: &Account, asset: FungibleAsset) -> Result<Note> {
+    escrow_note(
+        escrow,
+        "notes/escrow_lock_note.masm",
+        vec![],
+        vec![asset.into()],
+    )
+}
+
+fn release_note(escrow: &Account) -> Result<Note> {
+    escrow_note(escrow, "notes/escrow_release_note.masm", vec![], vec![])
+}
+
+fn withdraw_note(escrow: &Account) -> Result<Note> {
+    let inputs = vec![NoteTag::from_account_id(sender()).into()];
+    escrow_note(escrow, "notes/escrow_withdraw_note.masm", inputs, vec![])
+}
+
+#[tokio::test]
+async fn only_the_buyer_can_lock_funds() -> Result<()> {
+    let escrow = trade_escrow(counterparty(), sender())?;
+    let lock = lock_note(&escrow, FungibleAsset::new(usd_faucet(), 100)?)?;
+    let mut chain = mock_chain(&[&escrow], &[&lock])?;
+
+    assert_fails_with(
+        consume(&mut chain, escrow.id(), &lock).await,
+        "Only the buyer can lock, release or reclaim the funds",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn lock_of_another_asset_is_rejected() -> Result<()> {
+    let escrow = trade_escrow(sender(), counterparty())?;
+    let lock = lock_note(&escrow, FungibleAsset::new(eth_faucet(), 100)?)?;
+    let mut chain = mock_chain(&[&escrow], &[&lock])?;
+
+    assert_fails_with(
+        consume(&mut chain, escrow.id(), &lock).await,
+        "Escrow only holds the asset it was created for",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn seller_cannot_withdraw_before_release() -> Result<()> {
+    let escrow = trade_escrow(counterparty(), sender())?;
+    let withdraw = withdraw_note(&escrow)?;
+    let mut chain = mock_chain(&[&escrow], &[&withdraw])?;
+
+    assert_fails_with(
+        consume(&mut chain, escrow.id(), &withdraw).await,
+        "Only the buyer can lock, release or reclaim the funds",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn buyer_cannot_reclaim_before_the_timeout() -> Result<()> {
+    let escrow = trade_escrow(sender(), counterparty())?;
+    let lock = lock_note(&escrow, FungibleAsset::new(usd_faucet(), 100)?)?;
+    let withdraw = withdraw_note(&escrow)?;
+    let mut chain = mock_chain(&[&escrow], &[&lock, &withdraw])?;
+
+    consume(&mut chain, escrow.id(), &lock).await?;
+    assert_fails_with(
+        consume(&mut chain, escrow.id(), &withdraw).await,
+        "Buyer cannot reclaim the funds before the timeout",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn buyer_cannot_reclaim_released_funds() -> Result<()> {
+    let escrow = trade_escrow(sender(), counterparty())?;
+    let lock = lock_note(&escrow, FungibleAsset::new(usd_faucet(), 100)?)?;
+    let release = release_note(&escrow)?;
+    let withdraw = withdraw_note(&escrow)?;
+    let mut chain = mock_chain(&[&escrow], &[&lock, &release, &withdraw])?;
+
+    consume(&mut chain, escrow.id(), &lock).await?;
+    consume(&mut chain, escrow.id(), &release).await?;
+    assert_fails_with(
+        consume(&mut chain, escrow.id(), &withdraw).await,
+        "Only the seller can claim released funds",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn funds_cannot_be_released_twice() -> Result<()> {
+    let escrow = trade_escrow(sender(), counterparty())?;
+    let release = release_note(&escrow)?;
+    let replay = release_note(&escrow)?;
+    let mut chain = mock_chain(&[&escrow], &[&release, &replay])?;
+
+    consume(&mut chain, escrow.id(), &release).await?;
+    assert_fails_with(
+        consume(&mut chain, escrow.id(), &replay).await,
+        "Escrow is no longer locked",
+    );
+
+    Ok(())
+}
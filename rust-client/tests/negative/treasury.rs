@@ -0,0 +1,329 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    asset::FungibleAsset,
+    note::{Note, NoteId, NoteTag},
+    testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+    Felt, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::treasury::{self, Treasury};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note_script,
+    random_word, wallet,
+};
+
+/// Yes-weight a proposal has to exceed.
+const THRESHOLD: u64 = 50;
+/// Number of blocks during which a proposal accepts votes.
+const VOTING_PERIOD: u32 = 10;
+
+fn faucet() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn funds(amount: u64) -> Result<FungibleAsset> {
+    Ok(FungibleAsset::new(faucet(), amount)?)
+}
+
+fn value_word(value: u64) -> Word {
+    [ZERO, ZERO, ZERO, Felt::new(value)].into()
+}
+
+/// Builds an empty treasury whose snapshot gives every holder its weight.
+fn treasury_contract(weights: &[(&Account, u64)]) -> Result<Account> {
+    let snapshot = StorageMap::with_entries(weights.iter().map(|(holder, weight)| {
+        let key: Word = [
+            ZERO,
+            ZERO,
+            holder.id().suffix(),
+            holder.id().prefix().as_felt(),
+        ]
+        .into();
+        (key, value_word(*weight))
+    }))?;
+
+    contract(
+        &masm("accounts/treasury.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(value_word(THRESHOLD)),
+            StorageSlot::Value(value_word(VOTING_PERIOD.into())),
+            StorageSlot::Map(snapshot),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )
+}
+
+/// Builds a note of `sender` running the note script at `script_path` on the treasury.
+fn treasury_note(
+    sender: &Account,
+    treasury: &Account,
+    script_path: &str,
+    inputs: Vec<Felt>,
+    funds: Option<FungibleAsset>,
+) -> Result<Note> {
+    let library = create_library(masm("accounts/treasury.masm"), treasury::LIBRARY_PATH)?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    Ok(treasury::treasury_note(
+        sender.id(),
+        treasury.id(),
+        inputs,
+        funds.into_iter().map(Into::into).collect(),
+        script,
+        random_word(),
+    )?)
+}
+
+fn deposit_note(sender: &Account, treasury: &Account, amount: u64) -> Result<Note> {
+    treasury_note(
+        sender,
+        treasury,
+        "notes/treasury_deposit_note.masm",
+        vec![],
+        Some(funds(amount)?),
+    )
+}
+
+fn propose_note(
+    proposer: &Account,
+    treasury: &Account,
+    recipient: &Account,
+    amount: u64,
+) -> Result<Note> {
+    let inputs = treasury::proposal_inputs(recipient.id(), funds(amount)?);
+    treasury_note(
+        proposer,
+        treasury,
+        "notes/treasury_propose_note.masm",
+        inputs,
+        None,
+    )
+}
+
+fn vote_note(holder: &Account, treasury: &Account, proposal_id: u32) -> Result<Note> {
+    treasury_note(
+        holder,
+        treasury,
+        "notes/treasury_vote_note.masm",
+        vec![Felt::from(proposal_id)],
+        None,
+    )
+}
+
+/// Builds a note executing the proposal `proposal_id`, which pays `recipient`.
+fn execute_note(
+    executor: &Account,
+    treasury: &Account,
+    proposal_id: u32,
+    recipient: &Account,
+) -> Result<Note> {
+    let inputs = vec![
+        Felt::from(proposal_id),
+        NoteTag::from_account_id(recipient.id()).into(),
+    ];
+    treasury_note(
+        executor,
+        treasury,
+        "notes/treasury_execute_note.masm",
+        inputs,
+        None,
+    )
+}
+
+fn read_treasury(chain: &MockChain, treasury: &Account) -> Result<Treasury> {
+    Treasury::from_account(&chain.committed_account(treasury.id())?)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+fn balance(chain: &MockChain, treasury: &Account) -> Result<u64> {
+    Ok(chain
+        .committed_account(treasury.id())?
+        .vault()
+        .get_balance(faucet())?)
+}
+
+/// Consumes `note` with the treasury and returns the ids of the notes it created.
+async fn consume_by_treasury(
+    chain: &mut MockChain,
+    treasury: &Account,
+    note: &Note,
+) -> Result<Vec<NoteId>> {
+    let executed = chain
+        .build_tx_context(treasury.id(), &[note.id()], &[])?
+        .build()?
+        .execute()
+        .await?;
+    let created = executed
+        .output_notes()
+        .iter()
+        .map(|note| note.id())
+        .collect();
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(created)
+}
+
+#[tokio::test]
+async fn proposal_is_paid_once_its_yes_weight_exceeds_the_threshold() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let carol = wallet(vec![])?;
+    let treasury = treasury_contract(&[(&alice, 50), (&bob, 30), (&carol, 20)])?;
+
+    let deposit = deposit_note(&alice, &treasury, 100)?;
+    let proposal = propose_note(&carol, &treasury, &carol, 40)?;
+    let alice_vote = vote_note(&alice, &treasury, 0)?;
+    let carol_vote = vote_note(&carol, &treasury, 0)?;
+    let early_execution = execute_note(&bob, &treasury, 0, &carol)?;
+    let execution = execute_note(&bob, &treasury, 0, &carol)?;
+    let replay = execute_note(&bob, &treasury, 0, &carol)?;
+    let mut chain = mock_chain(
+        &[&treasury],
+        &[
+            &deposit,
+            &proposal,
+            &alice_vote,
+            &carol_vote,
+            &early_execution,
+            &execution,
+            &replay,
+        ],
+    )?;
+
+    consume(&mut chain, treasury.id(), &deposit).await?;
+    consume(&mut chain, treasury.id(), &proposal).await?;
+    let state = read_treasury(&chain, &treasury)?;
+    assert_eq!(state.proposals.len(), 1);
+    assert_eq!(state.proposals[0].recipient, carol.id());
+    assert_eq!(state.proposals[0].payout, funds(40)?);
+
+    // Alice alone only reaches the threshold, which is not enough
+    consume(&mut chain, treasury.id(), &alice_vote).await?;
+    assert_eq!(
+        read_treasury(&chain, &treasury)?.proposals[0].yes_weight,
+        50
+    );
+    assert_fails_with(
+        consume(&mut chain, treasury.id(), &early_execution).await,
+        "Yes-weight of the proposal does not exceed the threshold",
+    );
+
+    consume(&mut chain, treasury.id(), &carol_vote).await?;
+    let state = read_treasury(&chain, &treasury)?;
+    assert_eq!(state.proposals[0].yes_weight, 70);
+    assert!(
+        treasury::has_voted(&chain.committed_account(treasury.id())?, carol.id(), 0)
+            .map_err(|err| anyhow::anyhow!("{err}"))?
+    );
+
+    let expected = state.proposals[0].payment(treasury.id(), execution.serial_num())?;
+    assert_eq!(
+        consume_by_treasury(&mut chain, &treasury, &execution).await?,
+        vec![expected.id()]
+    );
+    assert!(read_treasury(&chain, &treasury)?.proposals[0].executed);
+    assert_eq!(balance(&chain, &treasury)?, 60);
+
+    assert_fails_with(
+        consume(&mut chain, treasury.id(), &replay).await,
+        "Proposal was already executed",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn holder_cannot_vote_twice_for_a_proposal() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let treasury = treasury_contract(&[(&alice, 40), (&bob, 20)])?;
+    let proposal = propose_note(&alice, &treasury, &bob, 10)?;
+    let vote = vote_note(&alice, &treasury, 0)?;
+    let replay = vote_note(&alice, &treasury, 0)?;
+    let mut chain = mock_chain(&[&treasury], &[&proposal, &vote, &replay])?;
+
+    consume(&mut chain, treasury.id(), &proposal).await?;
+    consume(&mut chain, treasury.id(), &vote).await?;
+    assert_fails_with(
+        consume(&mut chain, treasury.id(), &replay).await,
+        "Holder already voted for the proposal",
+    );
+    assert_eq!(
+        read_treasury(&chain, &treasury)?.proposals[0].yes_weight,
+        40
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn accounts_outside_the_snapshot_cannot_propose_or_vote() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let mallory = wallet(vec![])?;
+    let treasury = treasury_contract(&[(&alice, 60)])?;
+    let proposal = propose_note(&alice, &treasury, &alice, 10)?;
+    let mallory_proposal = propose_note(&mallory, &treasury, &mallory, 10)?;
+    let mallory_vote = vote_note(&mallory, &treasury, 0)?;
+    let mut chain = mock_chain(&[&treasury], &[&proposal, &mallory_proposal, &mallory_vote])?;
+
+    assert_fails_with(
+        consume(&mut chain, treasury.id(), &mallory_proposal).await,
+        "Sender holds no governance tokens in the snapshot",
+    );
+    consume(&mut chain, treasury.id(), &proposal).await?;
+    assert_fails_with(
+        consume(&mut chain, treasury.id(), &mallory_vote).await,
+        "Sender holds no governance tokens in the snapshot",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn votes_are_rejected_after_the_voting_period() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let treasury = treasury_contract(&[(&alice, 60), (&bob, 20)])?;
+    let proposal = propose_note(&bob, &treasury, &bob, 10)?;
+    let vote = vote_note(&alice, &treasury, 0)?;
+    let mut chain = mock_chain(&[&treasury], &[&proposal, &vote])?;
+
+    consume(&mut chain, treasury.id(), &proposal).await?;
+    let deadline = read_treasury(&chain, &treasury)?.proposals[0].deadline;
+    chain.prove_until_block(deadline)?;
+    assert_fails_with(
+        consume(&mut chain, treasury.id(), &vote).await,
+        "Voting on the proposal is closed",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unknown_proposal_cannot_be_voted_for_or_executed() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let treasury = treasury_contract(&[(&alice, 60)])?;
+    let vote = vote_note(&alice, &treasury, 3)?;
+    let execution = execute_note(&alice, &treasury, 3, &alice)?;
+    let mut chain = mock_chain(&[&treasury], &[&vote, &execution])?;
+
+    assert_fails_with(
+        consume(&mut chain, treasury.id(), &vote).await,
+        "Unknown proposal",
+    );
+    assert_fails_with(
+        consume(&mut chain, treasury.id(), &execution).await,
+        "Unknown proposal",
+    );
+
+    Ok(())
+}
@@ -0,0 +1,246 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageSlot},
+    asset::{Asset, FungibleAsset},
+    note::{Note, NoteId, NoteTag},
+    testing::account_id::{ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET, ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1},
+    Felt, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::vesting::{self, Vesting};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note_script,
+    random_word, wallet,
+};
+
+/// Block the tokens start to unlock at.
+const START: u32 = 5;
+/// Number of blocks over which the tokens unlock.
+const DURATION: u32 = 10;
+/// Tokens unlocked per block.
+const RATE: u64 = 10;
+
+fn currency() -> AccountId {
+    AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET).unwrap()
+}
+
+fn tokens(amount: u64) -> Result<FungibleAsset> {
+    Ok(FungibleAsset::new(currency(), amount)?)
+}
+
+fn account_word(account_id: AccountId) -> Word {
+    [
+        ZERO,
+        ZERO,
+        account_id.suffix(),
+        account_id.prefix().as_felt(),
+    ]
+    .into()
+}
+
+fn value_word(value: u64) -> Word {
+    [ZERO, ZERO, ZERO, Felt::new(value)].into()
+}
+
+/// Builds an unfunded contract vesting `RATE * DURATION` tokens for `beneficiary`.
+fn vesting_contract(beneficiary: &Account) -> Result<Account> {
+    contract(
+        &masm("accounts/vesting.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(account_word(beneficiary.id())),
+            StorageSlot::Value(account_word(currency())),
+            StorageSlot::Value(value_word(START.into())),
+            StorageSlot::Value(value_word(DURATION.into())),
+            StorageSlot::Value(value_word(RATE)),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+        ],
+    )
+}
+
+/// Builds a note of `sender` running the note script at `script_path` on the contract.
+fn vesting_note(
+    sender: &Account,
+    vesting: &Account,
+    script_path: &str,
+    inputs: Vec<Felt>,
+    assets: Vec<Asset>,
+) -> Result<Note> {
+    let library = create_library(masm("accounts/vesting.masm"), vesting::LIBRARY_PATH)?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    Ok(vesting::vesting_note(
+        sender.id(),
+        vesting.id(),
+        inputs,
+        assets,
+        script,
+        random_word(),
+    )?)
+}
+
+fn deposit_note(funder: &Account, vesting: &Account, asset: FungibleAsset) -> Result<Note> {
+    vesting_note(
+        funder,
+        vesting,
+        "notes/vesting_deposit_note.masm",
+        vec![],
+        vec![asset.into()],
+    )
+}
+
+fn withdraw_note(sender: &Account, vesting: &Account, amount: u64) -> Result<Note> {
+    let inputs = vec![
+        Felt::new(amount),
+        NoteTag::from_account_id(sender.id()).into(),
+    ];
+    vesting_note(
+        sender,
+        vesting,
+        "notes/vesting_withdraw_note.masm",
+        inputs,
+        vec![],
+    )
+}
+
+fn read_vesting(chain: &MockChain, vesting: &Account) -> Result<Vesting> {
+    Vesting::from_account(&chain.committed_account(vesting.id())?)
+        .map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+fn balance(chain: &MockChain, vesting: &Account) -> Result<u64> {
+    Ok(chain
+        .committed_account(vesting.id())?
+        .vault()
+        .get_balance(currency())?)
+}
+
+/// Consumes `note` with the contract and returns the ids of the notes it created.
+async fn consume_by_vesting(
+    chain: &mut MockChain,
+    vesting: &Account,
+    note: &Note,
+) -> Result<Vec<NoteId>> {
+    let executed = chain
+        .build_tx_context(vesting.id(), &[note.id()], &[])?
+        .build()?
+        .execute()
+        .await?;
+    let created = executed
+        .output_notes()
+        .iter()
+        .map(|note| note.id())
+        .collect();
+    chain.add_pending_executed_transaction(&executed)?;
+    chain.prove_next_block()?;
+    Ok(created)
+}
+
+#[tokio::test]
+async fn beneficiary_claims_what_vested_and_no_more() -> Result<()> {
+    let employer = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let vesting = vesting_contract(&alice)?;
+
+    let deposit = deposit_note(&employer, &vesting, tokens(100)?)?;
+    let early = withdraw_note(&alice, &vesting, 10)?;
+    let claim = withdraw_note(&alice, &vesting, 40)?;
+    let excess = withdraw_note(&alice, &vesting, 11)?;
+    let mut chain = mock_chain(&[&vesting], &[&deposit, &early, &claim, &excess])?;
+
+    consume(&mut chain, vesting.id(), &deposit).await?;
+    assert_fails_with(
+        consume(&mut chain, vesting.id(), &early).await,
+        "Amount exceeds the tokens vested so far",
+    );
+
+    // Four blocks after the start, 40 tokens vested
+    chain.prove_until_block(START + 4)?;
+    let expected = read_vesting(&chain, &vesting)?.payout(40, claim.serial_num())?;
+    assert_eq!(
+        consume_by_vesting(&mut chain, &vesting, &claim).await?,
+        vec![expected.id()]
+    );
+    assert_eq!(read_vesting(&chain, &vesting)?.withdrawn, 40);
+    assert_eq!(balance(&chain, &vesting)?, 60);
+
+    // One block later, 50 tokens vested, of which 40 were withdrawn
+    assert_fails_with(
+        consume(&mut chain, vesting.id(), &excess).await,
+        "Amount exceeds the tokens vested so far",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn everything_is_claimable_after_the_duration_but_only_once() -> Result<()> {
+    let employer = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let vesting = vesting_contract(&alice)?;
+
+    let deposit = deposit_note(&employer, &vesting, tokens(100)?)?;
+    let claim = withdraw_note(&alice, &vesting, 100)?;
+    let replay = withdraw_note(&alice, &vesting, 1)?;
+    let nothing = withdraw_note(&alice, &vesting, 0)?;
+    let mut chain = mock_chain(&[&vesting], &[&deposit, &claim, &replay, &nothing])?;
+
+    consume(&mut chain, vesting.id(), &deposit).await?;
+    chain.prove_until_block(START + DURATION + 5)?;
+    consume(&mut chain, vesting.id(), &claim).await?;
+    assert_eq!(balance(&chain, &vesting)?, 0);
+
+    assert_fails_with(
+        consume(&mut chain, vesting.id(), &replay).await,
+        "Amount exceeds the tokens vested so far",
+    );
+    assert_fails_with(
+        consume(&mut chain, vesting.id(), &nothing).await,
+        "Withdrawal amount must not be zero",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_the_beneficiary_can_withdraw() -> Result<()> {
+    let employer = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let mallory = wallet(vec![])?;
+    let vesting = vesting_contract(&alice)?;
+
+    let deposit = deposit_note(&employer, &vesting, tokens(100)?)?;
+    let theft = withdraw_note(&mallory, &vesting, 10)?;
+    let mut chain = mock_chain(&[&vesting], &[&deposit, &theft])?;
+
+    consume(&mut chain, vesting.id(), &deposit).await?;
+    chain.prove_until_block(START + DURATION)?;
+    assert_fails_with(
+        consume(&mut chain, vesting.id(), &theft).await,
+        "Only the beneficiary can withdraw",
+    );
+    assert_eq!(balance(&chain, &vesting)?, 100);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn deposit_in_another_currency_is_rejected() -> Result<()> {
+    let employer = wallet(vec![])?;
+    let alice = wallet(vec![])?;
+    let vesting = vesting_contract(&alice)?;
+
+    let other = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET_1)?;
+    let deposit = deposit_note(&employer, &vesting, FungibleAsset::new(other, 100)?)?;
+    let mut chain = mock_chain(&[&vesting], &[&deposit])?;
+
+    assert_fails_with(
+        consume(&mut chain, vesting.id(), &deposit).await,
+        "Deposit is not paid in the vesting currency",
+    );
+
+    Ok(())
+}
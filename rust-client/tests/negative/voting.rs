@@ -0,0 +1,237 @@
+use anyhow::Result;
+use miden_objects::{
+    account::{Account, AccountType, StorageMap, StorageSlot},
+    note::Note,
+    Felt, Word, ZERO,
+};
+use miden_testing::MockChain;
+use rust_client::voting::{self, Ballot, Poll};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note_script,
+    random_word, wallet,
+};
+
+/// Number of choices on the ballot.
+const NUM_CHOICES: u32 = 2;
+/// Block from which commitments are rejected and reveals accepted.
+const COMMIT_END: u32 = 10;
+/// Block from which reveals are rejected.
+const REVEAL_END: u32 = 20;
+
+fn value_word(value: u32) -> Word {
+    [ZERO, ZERO, ZERO, Felt::from(value)].into()
+}
+
+/// Builds a poll without any commitment.
+fn poll_contract() -> Result<Account> {
+    contract(
+        &masm("accounts/voting.masm"),
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(value_word(COMMIT_END)),
+            StorageSlot::Value(value_word(REVEAL_END)),
+            StorageSlot::Value(value_word(NUM_CHOICES)),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+        ],
+    )
+}
+
+fn ballot(choice: u32, salt: u64) -> Ballot {
+    Ballot {
+        choice,
+        salt: [Felt::new(salt), Felt::new(salt * 3), Felt::new(salt * 7)],
+    }
+}
+
+/// Builds a note of `voter` carrying `word` to the poll with the note script at `script_path`.
+fn poll_note(voter: &Account, poll: &Account, script_path: &str, word: Word) -> Result<Note> {
+    let library = create_library(masm("accounts/voting.masm"), voting::LIBRARY_PATH)?;
+    let script = note_script(&masm(script_path), Some(&library))?;
+
+    Ok(voting::poll_note(
+        voter.id(),
+        poll.id(),
+        word,
+        script,
+        random_word(),
+    )?)
+}
+
+fn commit_note(voter: &Account, poll: &Account, ballot: &Ballot) -> Result<Note> {
+    poll_note(
+        voter,
+        poll,
+        "notes/voting_commit_note.masm",
+        ballot.commitment(),
+    )
+}
+
+fn reveal_note(voter: &Account, poll: &Account, ballot: &Ballot) -> Result<Note> {
+    poll_note(
+        voter,
+        poll,
+        "notes/voting_reveal_note.masm",
+        ballot.to_word(),
+    )
+}
+
+fn read_poll(chain: &MockChain, poll: &Account) -> Result<Poll> {
+    Poll::from_account(&chain.committed_account(poll.id())?).map_err(|err| anyhow::anyhow!("{err}"))
+}
+
+#[tokio::test]
+async fn only_valid_reveals_are_tallied() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let bob = wallet(vec![])?;
+    let carol = wallet(vec![])?;
+    let dave = wallet(vec![])?;
+    let poll = poll_contract()?;
+
+    let alice_ballot = ballot(1, 11);
+    let bob_ballot = ballot(1, 12);
+    let carol_ballot = ballot(0, 13);
+    let dave_ballot = ballot(0, 14);
+    let commits = [
+        commit_note(&alice, &poll, &alice_ballot)?,
+        commit_note(&bob, &poll, &bob_ballot)?,
+        commit_note(&carol, &poll, &carol_ballot)?,
+        commit_note(&dave, &poll, &dave_ballot)?,
+    ];
+    // Carol tries to switch her vote after seeing the others, Dave never reveals
+    let carol_switch = reveal_note(&carol, &poll, &ballot(1, 13))?;
+    let reveals = [
+        reveal_note(&alice, &poll, &alice_ballot)?,
+        reveal_note(&bob, &poll, &bob_ballot)?,
+        reveal_note(&carol, &poll, &carol_ballot)?,
+    ];
+    let notes: Vec<&Note> = commits
+        .iter()
+        .chain(&reveals)
+        .chain([&carol_switch])
+        .collect();
+    let mut chain = mock_chain(&[&poll], &notes)?;
+
+    for note in &commits {
+        consume(&mut chain, poll.id(), note).await?;
+    }
+    let state = read_poll(&chain, &poll)?;
+    assert_eq!(state.num_commitments, 4);
+    assert_eq!(state.tally, [0, 0]);
+
+    chain.prove_until_block(COMMIT_END)?;
+    assert_fails_with(
+        consume(&mut chain, poll.id(), &carol_switch).await,
+        "Ballot does not match the commitment",
+    );
+    for note in &reveals {
+        consume(&mut chain, poll.id(), note).await?;
+    }
+
+    let state = read_poll(&chain, &poll)?;
+    assert_eq!(state.num_reveals, 3);
+    assert_eq!(state.tally, [1, 2]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ballot_cannot_be_revealed_twice() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let poll = poll_contract()?;
+    let alice_ballot = ballot(1, 11);
+    let commit = commit_note(&alice, &poll, &alice_ballot)?;
+    let reveal = reveal_note(&alice, &poll, &alice_ballot)?;
+    let replay = reveal_note(&alice, &poll, &alice_ballot)?;
+    let mut chain = mock_chain(&[&poll], &[&commit, &reveal, &replay])?;
+
+    consume(&mut chain, poll.id(), &commit).await?;
+    chain.prove_until_block(COMMIT_END)?;
+    consume(&mut chain, poll.id(), &reveal).await?;
+    assert_fails_with(
+        consume(&mut chain, poll.id(), &replay).await,
+        "Voter has no commitment to reveal",
+    );
+    assert_eq!(read_poll(&chain, &poll)?.tally, [0, 1]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn voter_cannot_commit_twice() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let poll = poll_contract()?;
+    let commit = commit_note(&alice, &poll, &ballot(1, 11))?;
+    let second = commit_note(&alice, &poll, &ballot(0, 12))?;
+    let mut chain = mock_chain(&[&poll], &[&commit, &second])?;
+
+    consume(&mut chain, poll.id(), &commit).await?;
+    assert_fails_with(
+        consume(&mut chain, poll.id(), &second).await,
+        "Voter already committed",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn commitments_are_rejected_after_the_commit_phase() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let poll = poll_contract()?;
+    let commit = commit_note(&alice, &poll, &ballot(1, 11))?;
+    let mut chain = mock_chain(&[&poll], &[&commit])?;
+
+    chain.prove_until_block(COMMIT_END)?;
+    assert_fails_with(
+        consume(&mut chain, poll.id(), &commit).await,
+        "Commit phase is over",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reveals_are_rejected_outside_the_reveal_phase() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let poll = poll_contract()?;
+    let alice_ballot = ballot(1, 11);
+    let commit = commit_note(&alice, &poll, &alice_ballot)?;
+    let reveal = reveal_note(&alice, &poll, &alice_ballot)?;
+    let mut chain = mock_chain(&[&poll], &[&commit, &reveal])?;
+
+    consume(&mut chain, poll.id(), &commit).await?;
+    assert_fails_with(
+        consume(&mut chain, poll.id(), &reveal).await,
+        "Reveals are only accepted during the reveal phase",
+    );
+
+    chain.prove_until_block(REVEAL_END)?;
+    assert_fails_with(
+        consume(&mut chain, poll.id(), &reveal).await,
+        "Reveals are only accepted during the reveal phase",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn choice_off_the_ballot_is_not_counted() -> Result<()> {
+    let alice = wallet(vec![])?;
+    let poll = poll_contract()?;
+    let alice_ballot = ballot(NUM_CHOICES, 11);
+    let commit = commit_note(&alice, &poll, &alice_ballot)?;
+    let reveal = reveal_note(&alice, &poll, &alice_ballot)?;
+    let mut chain = mock_chain(&[&poll], &[&commit, &reveal])?;
+
+    consume(&mut chain, poll.id(), &commit).await?;
+    chain.prove_until_block(COMMIT_END)?;
+    assert_fails_with(
+        consume(&mut chain, poll.id(), &reveal).await,
+        "Choice is not on the ballot",
+    );
+
+    Ok(())
+}
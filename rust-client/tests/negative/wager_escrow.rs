@@ -0,0 +1,148 @@
+use anyhow::Result;
+use miden_lib::note::WellKnownNote;
+use miden_objects::{
+    account::{Account, AccountId, AccountType, StorageMap, StorageSlot},
+    assembly::Library,
+    asset::{Asset, FungibleAsset},
+    note::Note,
+    testing::account_id::ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET,
+    Felt, Word, ZERO,
+};
+use rust_client::wager_escrow::{self, WagerEscrow, LIBRARY_PATH};
+
+use crate::common::{
+    assert_fails_with, consume, contract, create_library, masm, mock_chain, note, note_script,
+    random_word, sender, wallet,
+};
+
+/// Stake of the escrows under test.
+const STAKE: u64 = 10;
+
+fn stake(amount: u64) -> Result<FungibleAsset> {
+    let faucet = AccountId::try_from(ACCOUNT_ID_PUBLIC_FUNGIBLE_FAUCET)?;
+    Ok(FungibleAsset::new(faucet, amount)?)
+}
+
+/// Builds an initialized escrow for the game `game_id`, already paid out if `settled`.
+fn escrow_contract(game_id: AccountId, settled: bool) -> Result<(Account, Library)> {
+    let code = masm("accounts/wager_escrow.masm");
+    let game: Word = [ZERO, ZERO, game_id.suffix(), game_id.prefix().as_felt()].into();
+    let account = contract(
+        &code,
+        AccountType::RegularAccountImmutableCode,
+        vec![
+            StorageSlot::Value(game),
+            StorageSlot::Value(Asset::from(stake(STAKE)?).into()),
+            StorageSlot::Map(StorageMap::new()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::new(settled as u64)].into()),
+            StorageSlot::Value(WellKnownNote::P2ID.script_root()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value(Word::default()),
+            StorageSlot::Value([ZERO, ZERO, ZERO, Felt::new(1)].into()),
+        ],
+    )?;
+    Ok((account, create_library(code, LIBRARY_PATH)?))
+}
+
+/// Builds the note by which the test sender deposits `amount` tokens into `escrow`.
+fn deposit(escrow: &Account, library: &Library, amount: u64) -> Result<Note> {
+    let script = note_script(&masm("notes/wager_deposit_note.masm"), Some(library))?;
+    Ok(wager_escrow::deposit_note(
+        sender(),
+        escrow.id(),
+        stake(amount)?,
+        script,
+        random_word(),
+    )?)
+}
+
+/// Builds the note by which the test sender reports that `winner` beat `loser`.
+fn settlement(
+    winner: AccountId,
+    loser: AccountId,
+    escrow: &Account,
+    library: &Library,
+) -> Result<Note> {
+    let script = note_script(&masm("notes/wager_settle_note.masm"), Some(library))?;
+    note(
+        escrow.id(),
+        script,
+        vec![
+            winner.prefix().as_felt(),
+            winner.suffix(),
+            loser.prefix().as_felt(),
+            loser.suffix(),
+        ],
+        vec![],
+    )
+}
+
+#[tokio::test]
+async fn deposit_must_be_the_stake() -> Result<()> {
+    let game = wallet(vec![])?;
+    let (escrow, library) = escrow_contract(game.id(), false)?;
+    let smaller_stake = deposit(&escrow, &library, STAKE - 1)?;
+    let mut chain = mock_chain(&[&escrow], &[&smaller_stake])?;
+
+    assert_fails_with(
+        consume(&mut chain, escrow.id(), &smaller_stake).await,
+        "Deposit must be the stake of the escrow",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn no_deposit_after_the_payout() -> Result<()> {
+    let game = wallet(vec![])?;
+    let (escrow, library) = escrow_contract(game.id(), true)?;
+    let late_stake = deposit(&escrow, &library, STAKE)?;
+    let mut chain = mock_chain(&[&escrow], &[&late_stake])?;
+
+    assert_fails_with(
+        consume(&mut chain, escrow.id(), &late_stake).await,
+        "Wager escrow already paid out its stakes",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn only_the_game_settles_the_escrow() -> Result<()> {
+    let (game, winner, loser) = (wallet(vec![])?, wallet(vec![])?, wallet(vec![])?);
+    let (escrow, library) = escrow_contract(game.id(), false)?;
+    // The test sender claiming a result on behalf of the game
+    let forged = settlement(winner.id(), loser.id(), &escrow, &library)?;
+    let mut chain = mock_chain(&[&escrow], &[&forged])?;
+
+    assert_fails_with(
+        consume(&mut chain, escrow.id(), &forged).await,
+        "Only the game of the escrow can settle it",
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn escrow_settles_once() -> Result<()> {
+    let (winner, loser) = (wallet(vec![])?, wallet(vec![])?);
+    let (escrow, library) = escrow_contract(sender(), false)?;
+    let result = settlement(winner.id(), loser.id(), &escrow, &library)?;
+    // The same game claiming the opposite result afterwards
+    let reversed = settlement(loser.id(), winner.id(), &escrow, &library)?;
+    let mut chain = mock_chain(&[&escrow], &[&result, &reversed])?;
+
+    // Nobody deposited, so the settlement pays nothing out
+    consume(&mut chain, escrow.id(), &result).await?;
+    let state = WagerEscrow::from_account(chain.committed_account(escrow.id())?)
+        .map_err(|err| anyhow::anyhow!("{err}"))?;
+    assert!(state.settled && state.depositors.is_empty());
+
+    assert_fails_with(
+        consume(&mut chain, escrow.id(), &reversed).await,
+        "Wager escrow already paid out its stakes",
+    );
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+//! Command line options shared by the tutorial binaries.
+//!
+//! Every binary submitting transactions accepts `--prover <local|none|URL>`:
+//!
+//! - `local` (the default) proves transactions on this machine,
+//! - a URL delegates proving to a remote prover, e.g. `https://tx-prover.testnet.miden.io`,
+//! - `none` only executes transactions, without proving or submitting them. Useful to check
+//!   that scripts run, but later steps waiting for the transaction will not make progress.
+//!
+//! ```text
+//! cargo run --release --bin counter_contract_increment -- --prover https://tx-prover.testnet.miden.io
+//! ```
+//!
+//! They talk to the testnet unless given `--local`, or run with `MIDEN_LOCAL_NODE=1`: a fresh
+//! node is then started on this machine for the duration of the run, see [`network`].
+
+use std::{str::FromStr, sync::Arc, sync::OnceLock};
+
+use miden_client::{
+    account::AccountId,
+    address::NetworkId,
+    keystore::FilesystemKeyStore,
+    rpc::Endpoint,
+    transaction::{LocalTransactionProver, TransactionId, TransactionProver, TransactionRequest},
+    Client, ClientError, RemoteTransactionProver,
+};
+use rand::rngs::StdRng;
+
+use crate::local_node::LocalNodeHarness;
+
+const PROVER_FLAG: &str = "--prover";
+const LOCAL_FLAG: &str = "--local";
+
+/// Environment variable running the binaries against a local node, like `--local`.
+pub const LOCAL_NODE_ENV: &str = "MIDEN_LOCAL_NODE";
+
+/// How transactions are proven.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProverOption {
+    /// Prove on this machine.
+    Local,
+    /// Delegate proving to the remote prover at the given URL.
+    Remote(String),
+    /// Execute transactions only, without proving or submitting them.
+    ExecuteOnly,
+}
+
+impl FromStr for ProverOption {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "local" => Ok(Self::Local),
+            "none" => Ok(Self::ExecuteOnly),
+            url if url.starts_with("http://") || url.starts_with("https://") => {
+                Ok(Self::Remote(url.to_string()))
+            }
+            other => Err(format!(
+                "invalid value `{other}` for {PROVER_FLAG}, expected `local`, `none` or a URL"
+            )),
+        }
+    }
+}
+
+impl ProverOption {
+    /// Parses `--prover` from the command line arguments, defaulting to [`ProverOption::Local`].
+    pub fn from_args() -> Result<Self, String> {
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == PROVER_FLAG {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("{PROVER_FLAG} requires a value"))?;
+                return value.parse();
+            }
+            if let Some(value) = arg.strip_prefix("--prover=") {
+                return value.parse();
+            }
+        }
+        Ok(Self::Local)
+    }
+
+    /// Returns the option chosen on the command line, exiting with an error message if it is
+    /// invalid. Parsed once per process.
+    pub fn global() -> &'static Self {
+        static PROVER: OnceLock<ProverOption> = OnceLock::new();
+        PROVER.get_or_init(|| {
+            Self::from_args().unwrap_or_else(|err| {
+                eprintln!("error: {err}");
+                std::process::exit(2);
+            })
+        })
+    }
+
+    /// Returns the prover to use, or `None` if transactions should only be executed.
+    pub fn transaction_prover(&self) -> Option<Arc<dyn TransactionProver>> {
+        match self {
+            Self::Local => Some(Arc::new(LocalTransactionProver::default())),
+            Self::Remote(url) => Some(Arc::new(RemoteTransactionProver::new(url.clone()))),
+            Self::ExecuteOnly => None,
+        }
+    }
+}
+
+/// Returns the command line arguments other than the shared options, without the binary name.
+pub fn positional_args() -> Vec<String> {
+    let mut positional = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == PROVER_FLAG {
+            args.next();
+        } else if !arg.starts_with("--prover=") && arg != LOCAL_FLAG {
+            positional.push(arg);
+        }
+    }
+    positional
+}
+
+/// Returns whether `--local` was given or `MIDEN_LOCAL_NODE` is set to anything but `0`.
+pub fn use_local_node() -> bool {
+    std::env::args().skip(1).any(|arg| arg == LOCAL_FLAG)
+        || std::env::var(LOCAL_NODE_ENV).is_ok_and(|value| !value.is_empty() && value != "0")
+}
+
+/// The node a binary talks to.
+///
+/// Holds the local node, if one was started, and stops it on drop: keep it alive until the
+/// binary is done.
+pub struct Network {
+    endpoint: Endpoint,
+    local_node: Option<LocalNodeHarness>,
+}
+
+impl Network {
+    /// Returns the endpoint of the node.
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    /// Returns whether the node was started for this run.
+    pub fn is_local(&self) -> bool {
+        self.local_node.is_some()
+    }
+
+    /// Returns the id of the network, which addresses on it are encoded with.
+    pub fn network_id(&self) -> NetworkId {
+        self.endpoint.to_network_id()
+    }
+}
+
+/// Returns the network chosen on the command line: the testnet, or a node started on this
+/// machine with `--local`.
+pub async fn network() -> Result<Network, ClientError> {
+    if !use_local_node() {
+        return Ok(Network {
+            endpoint: Endpoint::testnet(),
+            local_node: None,
+        });
+    }
+
+    println!("Starting a local node...");
+    let local_node = LocalNodeHarness::start()
+        .await
+        .map_err(|err| ClientError::ClientInitializationError(err.to_string()))?;
+    println!("Local node listening on {}", local_node.endpoint());
+
+    Ok(Network {
+        endpoint: local_node.endpoint().clone(),
+        local_node: Some(local_node),
+    })
+}
+
+/// Submits transactions with the prover chosen by `--prover`.
+#[allow(async_fn_in_trait)]
+pub trait SubmitWithProver {
+    /// Executes `request` against `account_id`, then proves, submits and applies it with the
+    /// prover chosen on the command line. With `--prover none` the transaction is only executed.
+    async fn submit_with_prover(
+        &mut self,
+        account_id: AccountId,
+        request: TransactionRequest,
+    ) -> Result<TransactionId, ClientError>;
+}
+
+impl SubmitWithProver for Client<FilesystemKeyStore<StdRng>> {
+    async fn submit_with_prover(
+        &mut self,
+        account_id: AccountId,
+        request: TransactionRequest,
+    ) -> Result<TransactionId, ClientError> {
+        let tx_result = self.execute_transaction(account_id, request).await?;
+        let tx_id = tx_result.executed_transaction().id();
+
+        match ProverOption::global().transaction_prover() {
+            Some(prover) => {
+                let proven_transaction = self.prove_transaction_with(&tx_result, prover).await?;
+                let submission_height = self
+                    .submit_proven_transaction(proven_transaction, &tx_result)
+                    .await?;
+                self.apply_transaction(&tx_result, submission_height)
+                    .await?;
+            }
+            None => println!("Executed {tx_id:?} without proving or submitting it (--prover none)"),
+        }
+
+        Ok(tx_id)
+    }
+}
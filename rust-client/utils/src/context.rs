@@ -0,0 +1,158 @@
+//! Where a tutorial binary keeps its keystore and store.
+//!
+//! The binaries used to share `./keystore` and `./store.sqlite3`, so two of them running at the
+//! same time would write to the same sqlite file and clobber each other's keys. A
+//! [`TutorialContext`] gives each run a directory of its own instead:
+//!
+//! - by default a fresh temporary directory, deleted when the binary exits,
+//! - with `MIDEN_TUTORIAL_STATE_DIR` set, `$MIDEN_TUTORIAL_STATE_DIR/<name>`, kept across runs so
+//!   that the accounts of a run can be inspected or reused afterwards.
+//!
+//! Binaries built around state that outlives a run, such as `devnet_setup` and the tools
+//! working on its accounts, open a [`TutorialContext::persistent`] context, kept in
+//! `./state/<name>` unless `MIDEN_TUTORIAL_STATE_DIR` says otherwise. Against a local node
+//! every context is temporary: the chain it was synced with is gone after the run.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use miden_client::{keystore::FilesystemKeyStore, ClientError};
+use rand::rngs::StdRng;
+
+use crate::{cli::use_local_node, ephemeral::ClientDirs};
+
+/// Environment variable naming the directory under which contexts are kept across runs.
+pub const STATE_DIR_ENV: &str = "MIDEN_TUTORIAL_STATE_DIR";
+
+/// Directory under which persistent contexts are kept when `MIDEN_TUTORIAL_STATE_DIR` is unset.
+pub const DEFAULT_STATE_DIR: &str = "./state";
+
+enum ContextDir {
+    /// Deleted on drop.
+    Temporary(ClientDirs),
+    /// Kept across runs.
+    Persistent(PathBuf),
+}
+
+/// The keystore and store directory of one run of a tutorial binary.
+pub struct TutorialContext {
+    name: String,
+    dir: ContextDir,
+}
+
+impl TutorialContext {
+    /// Opens the context of the binary `name`: a new temporary directory, or
+    /// `$MIDEN_TUTORIAL_STATE_DIR/<name>` if the variable is set.
+    pub fn new(name: &str) -> Result<Self, ClientError> {
+        let root = std::env::var_os(STATE_DIR_ENV).map(PathBuf::from);
+        Self::open(name, root.as_deref()).map_err(init_error)
+    }
+
+    /// Opens the context `name` kept across runs, in `$MIDEN_TUTORIAL_STATE_DIR/<name>` or
+    /// `./state/<name>`. Binaries sharing state across runs open the same `name`.
+    pub fn persistent(name: &str) -> Result<Self, ClientError> {
+        let root = std::env::var_os(STATE_DIR_ENV)
+            .map_or_else(|| PathBuf::from(DEFAULT_STATE_DIR), PathBuf::from);
+        Self::open(name, Some(&root)).map_err(init_error)
+    }
+
+    /// Opens the context `name` under `root`, or in a temporary directory if `root` is `None`
+    /// or the run uses a local node.
+    fn open(name: &str, root: Option<&Path>) -> io::Result<Self> {
+        let dir = match root {
+            Some(root) if !use_local_node() => {
+                let path = root.join(name);
+                fs::create_dir_all(&path)?;
+                ContextDir::Persistent(path)
+            }
+            _ => ContextDir::Temporary(ClientDirs::new()?),
+        };
+        Ok(Self {
+            name: name.to_string(),
+            dir,
+        })
+    }
+
+    /// Returns the name the context was opened with.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the directory holding the keystore and store.
+    pub fn dir(&self) -> &Path {
+        match &self.dir {
+            ContextDir::Temporary(dirs) => dirs.path(),
+            ContextDir::Persistent(path) => path,
+        }
+    }
+
+    /// Returns whether the directory is kept after the run.
+    pub fn is_persistent(&self) -> bool {
+        matches!(self.dir, ContextDir::Persistent(_))
+    }
+
+    /// Returns the path to use for the keystore.
+    pub fn keystore_path(&self) -> PathBuf {
+        self.dir().join("keystore")
+    }
+
+    /// Returns the path to use for the sqlite store.
+    pub fn store_path(&self) -> PathBuf {
+        self.dir().join("store.sqlite3")
+    }
+
+    /// Opens the keystore of the context.
+    pub fn keystore(&self) -> Result<Arc<FilesystemKeyStore<StdRng>>, ClientError> {
+        let keystore = FilesystemKeyStore::<StdRng>::new(self.keystore_path())
+            .map_err(|err| ClientError::ClientInitializationError(err.to_string()))?;
+        Ok(Arc::new(keystore))
+    }
+}
+
+fn init_error(err: io::Error) -> ClientError {
+    ClientError::ClientInitializationError(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temporary_contexts_do_not_share_a_directory() {
+        let first = TutorialContext::open("counter", None).unwrap();
+        let second = TutorialContext::open("counter", None).unwrap();
+
+        assert!(!first.is_persistent());
+        assert_ne!(first.store_path(), second.store_path());
+        assert_ne!(first.keystore_path(), second.keystore_path());
+    }
+
+    #[test]
+    fn temporary_context_is_deleted_on_drop() {
+        let context = TutorialContext::open("counter", None).unwrap();
+        let dir = context.dir().to_path_buf();
+        assert!(dir.exists());
+
+        drop(context);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn persistent_context_is_kept_under_its_name() {
+        let root = tempfile::tempdir().unwrap();
+        let context = TutorialContext::open("devnet", Some(root.path())).unwrap();
+        let dir = context.dir().to_path_buf();
+
+        assert!(context.is_persistent());
+        assert_eq!(
+            context.store_path(),
+            root.path().join("devnet/store.sqlite3")
+        );
+
+        drop(context);
+        assert!(dir.exists());
+    }
+}
@@ -0,0 +1,140 @@
+//! Clients whose store and keystore disappear when the client is dropped.
+//!
+//! Most tutorials get their store and keystore from a [`TutorialContext`], which can keep them
+//! across runs so that later binaries pick up the accounts created by earlier ones. Quick demos
+//! and tests don't need that: an [`EphemeralClient`] always keeps both in a fresh temporary
+//! directory which is deleted on drop, so runs leave no files behind and several clients never
+//! contend for the same sqlite file.
+//!
+//! [`TutorialContext`]: crate::context::TutorialContext
+
+use std::{
+    io,
+    ops::{Deref, DerefMut},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use miden_client::{
+    builder::ClientBuilder,
+    keystore::FilesystemKeyStore,
+    rpc::{Endpoint, GrpcClient},
+    Client, ClientError,
+};
+use miden_client_sqlite_store::ClientBuilderSqliteExt;
+use rand::rngs::StdRng;
+use tempfile::TempDir;
+
+/// A fresh temporary directory for the store and keystore of one client.
+///
+/// Tests that build their own client use this fixture, so that they can run in parallel. The
+/// directory is removed on drop.
+pub struct ClientDirs {
+    dir: TempDir,
+}
+
+impl ClientDirs {
+    /// Creates a new, empty temporary directory.
+    pub fn new() -> io::Result<Self> {
+        Ok(Self {
+            dir: tempfile::tempdir()?,
+        })
+    }
+
+    /// Returns the root of the temporary directory.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Returns the path to use for the keystore.
+    pub fn keystore_path(&self) -> PathBuf {
+        self.dir.path().join("keystore")
+    }
+
+    /// Returns the path to use for the sqlite store.
+    pub fn store_path(&self) -> PathBuf {
+        self.dir.path().join("store.sqlite3")
+    }
+
+    /// Deletes the directory, reporting any error.
+    pub fn close(self) -> io::Result<()> {
+        self.dir.close()
+    }
+}
+
+/// A client backed by a temporary store and keystore.
+///
+/// Derefs to the underlying [`Client`].
+pub struct EphemeralClient {
+    client: Client<FilesystemKeyStore<StdRng>>,
+    keystore: Arc<FilesystemKeyStore<StdRng>>,
+    // Declared last so that the directory is removed only after the client has been dropped
+    dirs: ClientDirs,
+}
+
+impl EphemeralClient {
+    /// Builds a client connected to `endpoint` with its store and keystore in a new temporary
+    /// directory.
+    pub async fn new(endpoint: Endpoint) -> Result<Self, ClientError> {
+        let dirs = ClientDirs::new()
+            .map_err(|err| ClientError::ClientInitializationError(err.to_string()))?;
+
+        let timeout_ms = 10_000;
+        let rpc_client = Arc::new(GrpcClient::new(&endpoint, timeout_ms));
+
+        let keystore = Arc::new(
+            FilesystemKeyStore::<StdRng>::new(dirs.keystore_path())
+                .map_err(|err| ClientError::ClientInitializationError(err.to_string()))?,
+        );
+
+        let client = ClientBuilder::new()
+            .rpc(rpc_client)
+            .sqlite_store(dirs.store_path())
+            .authenticator(keystore.clone())
+            .in_debug_mode(true.into())
+            .build()
+            .await?;
+
+        Ok(Self {
+            client,
+            keystore,
+            dirs,
+        })
+    }
+
+    /// Returns the keystore of the client, used to register keys of new accounts.
+    pub fn keystore(&self) -> &Arc<FilesystemKeyStore<StdRng>> {
+        &self.keystore
+    }
+
+    /// Returns the temporary directory holding the store and keystore.
+    pub fn dir(&self) -> &Path {
+        self.dirs.path()
+    }
+
+    /// Drops the client and deletes its temporary directory, reporting any error.
+    pub fn close(self) -> io::Result<()> {
+        let Self {
+            client,
+            keystore,
+            dirs,
+        } = self;
+        drop(client);
+        drop(keystore);
+        dirs.close()
+    }
+}
+
+impl Deref for EphemeralClient {
+    type Target = Client<FilesystemKeyStore<StdRng>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.client
+    }
+}
+
+impl DerefMut for EphemeralClient {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.client
+    }
+}
@@ -0,0 +1,558 @@
+//! Account creation, library assembly and polling helpers used by most examples.
+
+use std::{error::Error, fmt, sync::Arc};
+
+use miden_client::{
+    account::{
+        component::{BasicFungibleFaucet, BasicWallet},
+        Account, AccountId,
+    },
+    asset::{FungibleAsset, TokenSymbol},
+    auth::AuthSecretKey,
+    keystore::FilesystemKeyStore,
+    note::{
+        Note, NoteAssets, NoteExecutionHint, NoteInputs, NoteMetadata, NoteRecipient, NoteTag,
+        NoteType,
+    },
+    store::TransactionFilter,
+    sync::SyncSummary,
+    transaction::{TransactionId, TransactionRequestBuilder, TransactionStatus},
+    Client, ClientError, Felt, Word,
+};
+use miden_lib::{
+    account::auth::AuthRpoFalcon512, note::WellKnownNote, transaction::TransactionKernel,
+};
+use miden_objects::{
+    account::{AccountBuilder, AccountStorageMode, AccountType},
+    assembly::{Assembler, DefaultSourceManager, Library, LibraryPath, Module, ModuleKind},
+    transaction::TransactionMeasurements,
+    NoteError,
+};
+use miden_processor::{execute, AdviceInputs, DefaultHost, ExecutionOptions, StackInputs};
+use rand::{rngs::StdRng, RngCore};
+use tokio::time::{sleep, Duration, Instant};
+
+use crate::cli::{Network, SubmitWithProver};
+
+/// Creates a Miden library from the provided account code and library path.
+pub fn create_library(account_code: &str, library_path: &str) -> Result<Library, Box<dyn Error>> {
+    let assembler: Assembler = TransactionKernel::assembler().with_debug_mode(true);
+    let source_manager = Arc::new(DefaultSourceManager::default());
+    let module = Module::parser(ModuleKind::Library).parse_str(
+        LibraryPath::new(library_path)?,
+        account_code,
+        &source_manager,
+    )?;
+    let library = assembler.assemble_library([module])?;
+    Ok(library)
+}
+
+/// Returns the MAST root of the procedure `name` exported by `library`, the digest by which
+/// foreign procedure invocation and ACL auth components refer to it.
+pub fn procedure_root(library: &Library, name: &str) -> Result<Word, Box<dyn Error>> {
+    let export = library
+        .exports()
+        .find(|export| export.name.name.as_str() == name)
+        .ok_or_else(|| format!("the library does not export {name}"))?;
+    let node_id = library.get_export_node_id(&export.name);
+    Ok(library
+        .mast_forest()
+        .get_node_by_id(node_id)
+        .ok_or_else(|| format!("{name} is not in the library"))?
+        .digest())
+}
+
+/// Executes the program `code` in a local VM, linking `library` if one is given, and returns the
+/// value it leaves on top of the stack.
+///
+/// Used to run pure procedures, which touch no account, without a transaction.
+pub fn execute_program(code: &str, library: Option<&Library>) -> Result<u64, Box<dyn Error>> {
+    let mut assembler = TransactionKernel::assembler();
+    if let Some(library) = library {
+        assembler = assembler.with_static_library(library)?;
+    }
+    let program = assembler.assemble_program(code)?;
+
+    let trace = execute(
+        &program,
+        StackInputs::default(),
+        AdviceInputs::default(),
+        &mut DefaultHost::default(),
+        ExecutionOptions::default(),
+    )?;
+    let top = trace
+        .stack_outputs()
+        .get_stack_item(0)
+        .ok_or("the program left no output on the stack")?;
+    Ok(top.as_int())
+}
+
+/// Creates a public basic wallet with Falcon auth and adds its key to `keystore`.
+pub async fn create_basic_account(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    keystore: &FilesystemKeyStore<StdRng>,
+) -> Result<Account, ClientError> {
+    create_basic_account_with_storage_mode(client, keystore, AccountStorageMode::Public).await
+}
+
+/// Creates a basic wallet with Falcon auth and the given storage mode, and adds its key to
+/// `keystore`.
+pub async fn create_basic_account_with_storage_mode(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    keystore: &FilesystemKeyStore<StdRng>,
+    storage_mode: AccountStorageMode,
+) -> Result<Account, ClientError> {
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::RegularAccountUpdatableCode)
+        .storage_mode(storage_mode)
+        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+        .with_component(BasicWallet)
+        .build()
+        .unwrap();
+
+    client.add_account(&account, false).await?;
+    keystore.add_key(&key_pair).unwrap();
+
+    Ok(account)
+}
+
+/// Creates a public fungible faucet issuing `symbol`, with 8 decimals and a max supply of
+/// 1 000 000, and adds its key to `keystore`.
+pub async fn create_basic_faucet(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    keystore: &FilesystemKeyStore<StdRng>,
+    symbol: TokenSymbol,
+) -> Result<Account, ClientError> {
+    let mut init_seed = [0_u8; 32];
+    client.rng().fill_bytes(&mut init_seed);
+
+    let key_pair = AuthSecretKey::new_rpo_falcon512();
+    let decimals = 8;
+    let max_supply = Felt::new(1_000_000);
+
+    let account = AccountBuilder::new(init_seed)
+        .account_type(AccountType::FungibleFaucet)
+        .storage_mode(AccountStorageMode::Public)
+        .with_auth_component(AuthRpoFalcon512::new(key_pair.public_key().to_commitment()))
+        .with_component(BasicFungibleFaucet::new(symbol, decimals, max_supply).unwrap())
+        .build()
+        .unwrap();
+
+    client.add_account(&account, false).await?;
+    keystore.add_key(&key_pair).unwrap();
+
+    Ok(account)
+}
+
+/// Attempts [`sync_with_retry`] makes in the polling helpers below before giving up.
+pub const DEFAULT_SYNC_ATTEMPTS: u32 = 5;
+/// Wait after the first failed sync of the polling helpers below, doubled after each failure.
+pub const DEFAULT_SYNC_BACKOFF: Duration = Duration::from_secs(1);
+/// How long [`wait_for_tx`], [`wait_for_commit`] and [`wait_for_notes`] wait before giving up.
+pub const DEFAULT_TX_TIMEOUT: Duration = Duration::from_secs(300);
+/// Longest wait between two polls of the `*_with_timeout` helpers.
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(16);
+
+/// Why [`wait_for_tx_with_timeout`] and the other waiting helpers gave up.
+#[derive(Debug)]
+pub enum WaitError {
+    /// The transaction was not committed before the deadline.
+    Timeout {
+        tx_id: TransactionId,
+        waited: Duration,
+    },
+    /// The account did not receive the expected notes before the deadline.
+    NotesTimeout {
+        account_id: AccountId,
+        found: usize,
+        expected: usize,
+        waited: Duration,
+    },
+    /// The node discarded the transaction, it will never be committed.
+    Discarded(TransactionId),
+    /// Syncing with the node kept failing.
+    Client(ClientError),
+}
+
+impl fmt::Display for WaitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout { tx_id, waited } => write!(
+                f,
+                "transaction {} not committed after {}s",
+                tx_id.to_hex(),
+                waited.as_secs()
+            ),
+            Self::NotesTimeout {
+                account_id,
+                found,
+                expected,
+                waited,
+            } => write!(
+                f,
+                "account {} has {found} of {expected} consumable notes after {}s",
+                account_id.to_hex(),
+                waited.as_secs()
+            ),
+            Self::Discarded(tx_id) => {
+                write!(f, "transaction {} was discarded", tx_id.to_hex())
+            }
+            Self::Client(err) => write!(f, "client error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for WaitError {}
+
+impl From<ClientError> for WaitError {
+    fn from(err: ClientError) -> Self {
+        Self::Client(err)
+    }
+}
+
+/// Returns the wait before retry number `retry` (starting at 0) when the first wait is
+/// `backoff`: `backoff`, then twice as long after every further failure, at most `max`.
+fn backoff_delay(backoff: Duration, retry: u32, max: Duration) -> Duration {
+    backoff
+        .checked_mul(2_u32.saturating_pow(retry))
+        .map_or(max, |delay| delay.min(max))
+}
+
+/// Syncs `client` with the node, retrying failed syncs with exponential backoff.
+///
+/// A sync fails on transient errors too, such as a dropped connection or a node lagging
+/// behind, which used to abort the examples halfway through a run. Waits `backoff` after the
+/// first failure and twice as long after every further one. Returns the error of the last
+/// attempt once `max_attempts` attempts failed.
+pub async fn sync_with_retry(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    max_attempts: u32,
+    backoff: Duration,
+) -> Result<SyncSummary, ClientError> {
+    let mut retry = 0;
+    loop {
+        match client.sync_state().await {
+            Ok(summary) => return Ok(summary),
+            Err(err) if retry + 1 >= max_attempts => return Err(err),
+            Err(err) => {
+                let delay = backoff_delay(backoff, retry, Duration::MAX);
+                println!(
+                    "Sync failed ({err}), retrying in {}ms ({}/{max_attempts})",
+                    delay.as_millis(),
+                    retry + 1
+                );
+                sleep(delay).await;
+                retry += 1;
+            }
+        }
+    }
+}
+
+/// Waits until the transaction `tx_id` is committed, for at most [`DEFAULT_TX_TIMEOUT`].
+///
+/// Fails like [`wait_for_tx_with_timeout`] when the transaction is not committed in time or
+/// the node discards it.
+pub async fn wait_for_tx(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    tx_id: TransactionId,
+) -> Result<(), WaitError> {
+    wait_for_tx_with_timeout(client, tx_id, DEFAULT_TX_TIMEOUT).await
+}
+
+/// Waits until the transaction `tx_id` is committed, for at most `timeout`.
+///
+/// Polls with exponential backoff and fails with [`WaitError::Timeout`] once `timeout` has
+/// passed, or with [`WaitError::Discarded`] as soon as the node drops the transaction.
+pub async fn wait_for_tx_with_timeout(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    tx_id: TransactionId,
+    timeout: Duration,
+) -> Result<(), WaitError> {
+    let deadline = Instant::now() + timeout;
+    let mut poll = 0;
+    loop {
+        sync_with_retry(client, DEFAULT_SYNC_ATTEMPTS, DEFAULT_SYNC_BACKOFF).await?;
+
+        let txs = client
+            .get_transactions(TransactionFilter::Ids(vec![tx_id]))
+            .await?;
+        match txs.first().map(|tx| &tx.status) {
+            Some(TransactionStatus::Committed { .. }) => {
+                println!("✅ transaction {} committed", tx_id.to_hex());
+                return Ok(());
+            }
+            Some(TransactionStatus::Discarded(_)) => return Err(WaitError::Discarded(tx_id)),
+            _ => {}
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout {
+                tx_id,
+                waited: timeout,
+            });
+        }
+        let delay = backoff_delay(DEFAULT_SYNC_BACKOFF, poll, MAX_POLL_INTERVAL);
+        println!(
+            "Transaction {} not yet committed. Waiting {}s...",
+            tx_id.to_hex(),
+            delay.as_secs()
+        );
+        sleep(delay.min(deadline - now)).await;
+        poll += 1;
+    }
+}
+
+/// Waits until every transaction submitted by the client is committed, for at most
+/// [`DEFAULT_TX_TIMEOUT`].
+pub async fn wait_for_commit(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+) -> Result<(), WaitError> {
+    wait_for_commit_with_timeout(client, DEFAULT_TX_TIMEOUT).await
+}
+
+/// Waits until every transaction submitted by the client is committed, for at most `timeout`.
+///
+/// Polls like [`wait_for_tx_with_timeout`] and fails with [`WaitError::Timeout`], naming one
+/// of the transactions still uncommitted, once `timeout` has passed.
+pub async fn wait_for_commit_with_timeout(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    timeout: Duration,
+) -> Result<(), WaitError> {
+    let deadline = Instant::now() + timeout;
+    let mut poll = 0;
+    loop {
+        sync_with_retry(client, DEFAULT_SYNC_ATTEMPTS, DEFAULT_SYNC_BACKOFF).await?;
+        let uncommitted = client
+            .get_transactions(TransactionFilter::Uncommitted)
+            .await?;
+        let Some(pending) = uncommitted.first() else {
+            return Ok(());
+        };
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::Timeout {
+                tx_id: pending.id,
+                waited: timeout,
+            });
+        }
+        let delay = backoff_delay(DEFAULT_SYNC_BACKOFF, poll, MAX_POLL_INTERVAL);
+        println!(
+            "{} transactions not yet committed. Waiting {}s...",
+            uncommitted.len(),
+            delay.as_secs()
+        );
+        sleep(delay.min(deadline - now)).await;
+        poll += 1;
+    }
+}
+
+/// Waits until `account_id` has at least `expected` consumable notes, for at most
+/// [`DEFAULT_TX_TIMEOUT`]. `network` is the one `client` talks to, which the address printed
+/// while waiting is encoded for.
+pub async fn wait_for_notes(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    account_id: AccountId,
+    expected: usize,
+) -> Result<(), WaitError> {
+    wait_for_notes_with_timeout(client, network, account_id, expected, DEFAULT_TX_TIMEOUT).await
+}
+
+/// Waits until `account_id` has at least `expected` consumable notes, for at most `timeout`.
+///
+/// Polls like [`wait_for_tx_with_timeout`] and fails with [`WaitError::NotesTimeout`] once
+/// `timeout` has passed.
+pub async fn wait_for_notes_with_timeout(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    network: &Network,
+    account_id: AccountId,
+    expected: usize,
+    timeout: Duration,
+) -> Result<(), WaitError> {
+    let deadline = Instant::now() + timeout;
+    let mut poll = 0;
+    loop {
+        sync_with_retry(client, DEFAULT_SYNC_ATTEMPTS, DEFAULT_SYNC_BACKOFF).await?;
+        let notes = client.get_consumable_notes(Some(account_id)).await?;
+        if notes.len() >= expected {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(WaitError::NotesTimeout {
+                account_id,
+                found: notes.len(),
+                expected,
+                waited: timeout,
+            });
+        }
+        let delay = backoff_delay(DEFAULT_SYNC_BACKOFF, poll, MAX_POLL_INTERVAL);
+        println!(
+            "{} consumable notes found for account {}. Waiting {}s...",
+            notes.len(),
+            account_id.to_bech32(network.network_id()),
+            delay.as_secs()
+        );
+        sleep(delay.min(deadline - now)).await;
+        poll += 1;
+    }
+}
+
+/// Syncs `client` and returns the latest state of `account_id` it knows of.
+///
+/// Transactions are executed against the state the client holds. A transaction built from a
+/// state which another transaction has since moved past is rejected by the node, as its
+/// initial account commitment no longer matches the chain. Rebuild such a transaction from
+/// the state returned here rather than resubmitting it.
+pub async fn fresh_account_state(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+) -> Result<Account, ClientError> {
+    sync_with_retry(client, DEFAULT_SYNC_ATTEMPTS, DEFAULT_SYNC_BACKOFF).await?;
+    let record = client
+        .get_account(account_id)
+        .await?
+        .ok_or(ClientError::AccountDataNotFound(account_id))?;
+    Ok(record.account().clone())
+}
+
+/// Consumes every consumable note of `account_id` in a single transaction.
+pub async fn consume_all_notes(
+    client: &mut Client<FilesystemKeyStore<StdRng>>,
+    account_id: AccountId,
+) -> Result<TransactionId, ClientError> {
+    let consumable_notes = client.get_consumable_notes(Some(account_id)).await?;
+    let note_ids: Vec<_> = consumable_notes.iter().map(|(note, _)| note.id()).collect();
+
+    let consume_request = TransactionRequestBuilder::new().build_consume_notes(note_ids)?;
+
+    let tx_id = client
+        .submit_with_prover(account_id, consume_request)
+        .await?;
+    println!("Consumed notes. TX: {:?}", tx_id);
+
+    Ok(tx_id)
+}
+
+/// Builds the P2ID note by which `sender` sends `asset` to `receiver` in reply to the note
+/// with serial number `request_serial_num`.
+///
+/// The note scripts of the pools and swap offers derive the serial number of the reply by
+/// incrementing the last element of their own, which makes the note predictable: the client
+/// needs its recipient to accept it as an output of the transaction.
+pub fn reply_note(
+    sender: AccountId,
+    receiver: AccountId,
+    asset: FungibleAsset,
+    request_serial_num: Word,
+) -> Result<Note, NoteError> {
+    let serial_num = [
+        request_serial_num[0],
+        request_serial_num[1],
+        request_serial_num[2],
+        Felt::new(request_serial_num[3].as_int() + 1),
+    ]
+    .into();
+    let recipient = NoteRecipient::new(
+        serial_num,
+        WellKnownNote::P2ID.script(),
+        NoteInputs::new(vec![receiver.suffix(), receiver.prefix().as_felt()])?,
+    );
+    let metadata = NoteMetadata::new(
+        sender,
+        NoteType::Public,
+        NoteTag::from_account_id(receiver),
+        NoteExecutionHint::always(),
+        Felt::new(0),
+    )?;
+    Ok(Note::new(
+        NoteAssets::new(vec![asset.into()])?,
+        metadata,
+        recipient,
+    ))
+}
+
+/// Formats a word so that it can be pushed onto the stack with `push.{word}`.
+pub fn word_to_masm(word: Word) -> String {
+    word.iter()
+        .map(|felt| felt.as_int().to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Returns the number of cycles spent in the main phases of an executed transaction.
+pub fn total_cycles(measurements: &TransactionMeasurements) -> usize {
+    measurements.prologue
+        + measurements.notes_processing
+        + measurements.tx_script_processing
+        + measurements.epilogue
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRACT: &str = "
+        use.miden::active_account
+
+        export.get_value
+            push.0
+            exec.active_account::get_item
+        end
+    ";
+
+    #[test]
+    fn assembles_library_exporting_procedures() {
+        let library = create_library(CONTRACT, "external_contract::test_contract").unwrap();
+        assert_eq!(library.exports().count(), 1);
+    }
+
+    #[test]
+    fn finds_roots_of_exported_procedures_only() {
+        let library = create_library(CONTRACT, "external_contract::test_contract").unwrap();
+        assert_ne!(
+            procedure_root(&library, "get_value").unwrap(),
+            Word::default()
+        );
+        assert!(procedure_root(&library, "set_value").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_code() {
+        assert!(create_library("export.broken push.1", "external_contract::broken").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_library_path() {
+        assert!(create_library(CONTRACT, "not a path").is_err());
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let max = Duration::from_secs(16);
+        let delays: Vec<_> = (0..7)
+            .map(|retry| backoff_delay(Duration::from_secs(1), retry, max).as_secs())
+            .collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 16, 16]);
+        assert_eq!(backoff_delay(Duration::from_secs(1), 200, max), max);
+    }
+
+    #[test]
+    fn formats_word_for_push() {
+        let word: Word = [
+            Felt::new(1),
+            Felt::new(2),
+            Felt::new(3),
+            Felt::new(u64::from(u32::MAX)),
+        ]
+        .into();
+        assert_eq!(word_to_masm(word), "1.2.3.4294967295");
+    }
+}
@@ -0,0 +1,9 @@
+//! Helpers shared by the tutorial chapters and the other examples: command line options,
+//! per-run client directories, account creation, polling, storage decoding and a local node.
+
+pub mod cli;
+pub mod context;
+pub mod ephemeral;
+pub mod helpers;
+pub mod local_node;
+pub mod storage;
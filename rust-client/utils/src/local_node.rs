@@ -0,0 +1,158 @@
+//! A `miden-node` started for the duration of a run.
+//!
+//! The tutorials talk to the public testnet by default, so they are slow and flaky whenever it
+//! is congested. A [`LocalNodeHarness`] bootstraps a fresh node in a temporary directory, waits
+//! until its RPC answers and stops it again on drop, so that a run depends on nothing but this
+//! machine. Binaries start one when given `--local`, see [`crate::cli::network`].
+//!
+//! The `miden-node` binary must be installed, e.g. with `cargo install miden-node --locked`,
+//! or its location given in `MIDEN_NODE_BIN`. Accounts deployed on the testnet by others, such
+//! as the Pragma oracle, do not exist on a local node.
+
+use std::{
+    fs::{self, File},
+    io,
+    net::TcpListener,
+    path::Path,
+    process::{Child, Command},
+    time::{Duration, Instant},
+};
+
+use miden_client::rpc::{Endpoint, GrpcClient, NodeRpcClient};
+use tempfile::TempDir;
+use tokio::time::sleep;
+
+/// Environment variable overriding the `miden-node` binary to run.
+pub const NODE_BIN_ENV: &str = "MIDEN_NODE_BIN";
+
+/// How long the node gets to answer its first RPC request.
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+/// How long to wait between two readiness checks.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A local node running as a child process, with its data in a temporary directory.
+///
+/// The node is killed and its data deleted on drop.
+pub struct LocalNodeHarness {
+    child: Child,
+    endpoint: Endpoint,
+    // Declared last so that the directory is removed only after the node has been stopped
+    dir: TempDir,
+}
+
+impl LocalNodeHarness {
+    /// Bootstraps a new chain, starts a node serving it on a free local port and waits until
+    /// its RPC answers.
+    pub async fn start() -> io::Result<Self> {
+        let dir = tempfile::tempdir()?;
+        let data_dir = dir.path().join("data");
+        let accounts_dir = dir.path().join("accounts");
+        fs::create_dir_all(&data_dir)?;
+        fs::create_dir_all(&accounts_dir)?;
+
+        let node_bin = std::env::var(NODE_BIN_ENV).unwrap_or_else(|_| "miden-node".to_string());
+
+        let bootstrap_log_path = dir.path().join("bootstrap.log");
+        let bootstrap_log = File::create(&bootstrap_log_path)?;
+        let status = Command::new(&node_bin)
+            .args(["bundled", "bootstrap", "--data-directory"])
+            .arg(&data_dir)
+            .arg("--accounts-directory")
+            .arg(&accounts_dir)
+            .stdout(bootstrap_log.try_clone()?)
+            .stderr(bootstrap_log)
+            .status()
+            .map_err(|err| not_installed(&node_bin, err))?;
+        if !status.success() {
+            return Err(io::Error::other(format!(
+                "`{node_bin} bundled bootstrap` failed with {status}: {}",
+                tail(&bootstrap_log_path)
+            )));
+        }
+
+        // Let the OS pick a free port, so that several runs do not collide
+        let port = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+        let endpoint = Endpoint::new("http".to_string(), "127.0.0.1".to_string(), Some(port));
+
+        let log = File::create(dir.path().join("node.log"))?;
+        let child = Command::new(&node_bin)
+            .args(["bundled", "start", "--data-directory"])
+            .arg(&data_dir)
+            .arg("--rpc.url")
+            .arg(endpoint.to_string())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .spawn()
+            .map_err(|err| not_installed(&node_bin, err))?;
+
+        let mut harness = Self {
+            child,
+            endpoint,
+            dir,
+        };
+        harness.wait_until_ready().await?;
+        Ok(harness)
+    }
+
+    /// Returns the endpoint of the node's RPC.
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    /// Returns the temporary directory holding the node data.
+    pub fn dir(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// Polls the RPC until it returns the latest block header.
+    async fn wait_until_ready(&mut self) -> io::Result<()> {
+        let rpc = GrpcClient::new(&self.endpoint, POLL_INTERVAL.as_millis() as u64);
+        let started = Instant::now();
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                return Err(io::Error::other(format!(
+                    "miden-node exited with {status}: {}",
+                    tail(&self.dir.path().join("node.log"))
+                )));
+            }
+            if rpc.get_block_header_by_number(None, false).await.is_ok() {
+                return Ok(());
+            }
+            if started.elapsed() > READY_TIMEOUT {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!(
+                        "miden-node did not answer on {} within {READY_TIMEOUT:?}",
+                        self.endpoint
+                    ),
+                ));
+            }
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl Drop for LocalNodeHarness {
+    fn drop(&mut self) {
+        // The node may already have exited, in which case there is nothing to stop
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn not_installed(node_bin: &str, err: io::Error) -> io::Error {
+    io::Error::new(
+        err.kind(),
+        format!(
+            "cannot run `{node_bin}` ({err}). Install it with `cargo install miden-node --locked` \
+             or set {NODE_BIN_ENV}"
+        ),
+    )
+}
+
+/// Returns the last lines of the log at `path`, to explain why the node failed.
+fn tail(path: &Path) -> String {
+    let log = fs::read_to_string(path).unwrap_or_default();
+    let lines: Vec<&str> = log.lines().collect();
+    lines[lines.len().saturating_sub(10)..].join("\n")
+}
@@ -0,0 +1,110 @@
+//! Reading typed values out of account storage.
+//!
+//! The tutorial contracts keep a number in the last element of a word, `[0, 0, 0, n]`, which
+//! is what `push.n` followed by padding leaves for `set_item`, and key their maps with words of
+//! the same shape. An account id is stored as `[0, 0, suffix, prefix]`. These helpers decode
+//! those layouts so that examples do not build the words by hand.
+
+use miden_objects::{
+    account::{Account, AccountId},
+    AccountError, Felt, Word, ZERO,
+};
+
+/// Returns the word holding `value` in the layout of the tutorial contracts, used both as a
+/// storage value and as a map key.
+pub fn felt_key(value: u64) -> Word {
+    [ZERO, ZERO, ZERO, Felt::new(value)].into()
+}
+
+/// Returns the number stored in slot `index` of `account`.
+pub fn read_u64_slot(account: &Account, index: u8) -> Result<u64, AccountError> {
+    Ok(account.storage().get_item(index)?[3].as_int())
+}
+
+/// Returns the number stored under `key` in the map at slot `index` of `account`, zero if
+/// the key is not set.
+pub fn read_map_u64(account: &Account, index: u8, key: Word) -> Result<u64, AccountError> {
+    Ok(account.storage().get_map_item(index, key)?[3].as_int())
+}
+
+/// Returns the account id stored in slot `index` of `account`.
+pub fn read_account_id_slot(
+    account: &Account,
+    index: u8,
+) -> Result<AccountId, Box<dyn std::error::Error>> {
+    let word = account.storage().get_item(index)?;
+    Ok(AccountId::try_from([word[3], word[2]])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use miden_lib::{
+        account::{auth::NoAuth, wallets::BasicWallet},
+        transaction::TransactionKernel,
+    };
+    use miden_objects::account::{
+        AccountBuilder, AccountComponent, AccountIdVersion, AccountStorageMode, AccountType,
+        StorageMap, StorageSlot,
+    };
+
+    use super::*;
+
+    /// Builds an account with a number in slot 0, an account id in slot 1 and a map in slot 2.
+    fn account(number: u64, id: AccountId, entries: &[(u64, u64)]) -> Account {
+        let map = StorageMap::with_entries(
+            entries
+                .iter()
+                .map(|(key, value)| (felt_key(*key), felt_key(*value))),
+        )
+        .unwrap();
+        let component = AccountComponent::compile(
+            "export.noop push.0 drop end",
+            TransactionKernel::assembler(),
+            vec![
+                StorageSlot::Value(felt_key(number)),
+                StorageSlot::Value([ZERO, ZERO, id.suffix(), id.prefix().as_felt()].into()),
+                StorageSlot::Map(map),
+            ],
+        )
+        .unwrap()
+        .with_supports_all_types();
+        AccountBuilder::new([7; 32])
+            .account_type(AccountType::RegularAccountUpdatableCode)
+            .storage_mode(AccountStorageMode::Public)
+            .with_auth_component(NoAuth)
+            .with_component(component)
+            .with_component(BasicWallet)
+            .build_existing()
+            .unwrap()
+    }
+
+    fn other_id() -> AccountId {
+        AccountId::dummy(
+            [3; 15],
+            AccountIdVersion::Version0,
+            AccountType::RegularAccountUpdatableCode,
+            AccountStorageMode::Public,
+        )
+    }
+
+    #[test]
+    fn keys_hold_the_number_in_the_last_element() {
+        assert_eq!(felt_key(0), Word::default());
+        assert_eq!(felt_key(42)[3], Felt::new(42));
+        assert_eq!(felt_key(42)[..3], [ZERO; 3]);
+    }
+
+    #[test]
+    fn slots_decode_numbers_and_account_ids() {
+        let account = account(5, other_id(), &[]);
+        assert_eq!(read_u64_slot(&account, 0).unwrap(), 5);
+        assert_eq!(read_account_id_slot(&account, 1).unwrap(), other_id());
+    }
+
+    #[test]
+    fn map_entries_default_to_zero() {
+        let account = account(0, other_id(), &[(1, 10), (2, 20)]);
+        assert_eq!(read_map_u64(&account, 2, felt_key(2)).unwrap(), 20);
+        assert_eq!(read_map_u64(&account, 2, felt_key(3)).unwrap(), 0);
+    }
+}